@@ -0,0 +1,37 @@
+//! Criterion benchmarks for fuzzy matching and database loading
+//!
+//! Both run against the synthetic database of `goto --bench-selftest`
+//! (10k aliases), so results are comparable between the two.
+//!
+//! Run with `cargo bench --bench fuzzy`.
+
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+
+use goto::commands::bench::{synthetic_name, write_synthetic_database, SYNTHETIC_ALIASES};
+use goto::database::Database;
+use goto::fuzzy;
+
+fn find_matches(c: &mut Criterion) {
+    let names: Vec<String> = (0..SYNTHETIC_ALIASES).map(synthetic_name).collect();
+    let exact = synthetic_name(SYNTHETIC_ALIASES / 2);
+    let mut group = c.benchmark_group("find_matches");
+    for (label, query) in [("exact", exact.as_str()), ("typo", "bakend-docs"), ("no match", "zzqx")] {
+        group.bench_function(label, |b| {
+            b.iter(|| fuzzy::find_matches(black_box(query), names.iter().map(String::as_str)))
+        });
+    }
+    group.finish();
+}
+
+fn database_load(c: &mut Criterion) {
+    let dir = tempfile::tempdir().unwrap();
+    let base = write_synthetic_database(dir.path(), SYNTHETIC_ALIASES).unwrap();
+    c.bench_function("Database::load", |b| b.iter(|| Database::load_from_path(black_box(&base)).unwrap()));
+}
+
+criterion_group! {
+    name = benches;
+    config = Criterion::default().sample_size(20);
+    targets = find_matches, database_load
+}
+criterion_main!(benches);
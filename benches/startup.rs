@@ -0,0 +1,58 @@
+//! Startup-time benchmark for goto-bin
+//!
+//! Runs a few commands against a database of several thousand aliases and
+//! reports the mean wall-clock time per invocation. Commands that don't need
+//! the database (`-v`, `--help`, `--stack`) should not pay for parsing it.
+//!
+//! Run with `cargo bench --bench startup`.
+
+use std::fs;
+use std::path::Path;
+use std::process::{Command, Stdio};
+use std::time::{Duration, Instant};
+
+const ALIASES: usize = 5000;
+const RUNS: u32 = 50;
+
+fn write_database(dir: &Path) {
+    let mut content = String::new();
+    for i in 0..ALIASES {
+        content.push_str(&format!(
+            "[[aliases]]\nname = \"alias{i}\"\npath = \"/tmp/goto-bench/alias{i}\"\ntags = [\"bench\"]\nuse_count = {i}\ncreated_at = \"2024-01-01T00:00:00Z\"\n\n"
+        ));
+    }
+    fs::write(dir.join("aliases.toml"), content).unwrap();
+}
+
+fn mean_time(db_dir: &Path, args: &[&str]) -> Duration {
+    let start = Instant::now();
+    for _ in 0..RUNS {
+        Command::new(env!("CARGO_BIN_EXE_goto-bin"))
+            .env("GOTO_DB", db_dir)
+            .args(args)
+            .stdin(Stdio::null())
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .status()
+            .unwrap();
+    }
+    start.elapsed() / RUNS
+}
+
+fn main() {
+    let dir = tempfile::tempdir().unwrap();
+    write_database(dir.path());
+
+    println!("goto-bin startup, {} aliases, mean of {} runs", ALIASES, RUNS);
+    for args in [
+        &["-v"][..],
+        &["--help"][..],
+        &["--stack"][..],
+        &["-x", "alias42"][..],
+        &["--names-only"][..],
+        &["--duplicates"][..],
+    ] {
+        let time = mean_time(dir.path(), args);
+        println!("  {:<16} {:>8.2} ms", args.join(" "), time.as_secs_f64() * 1000.0);
+    }
+}
@@ -0,0 +1,20 @@
+
+# cd fallback (install.cd_fallback = true): `cd <alias>` enters the alias
+# when <alias> is not a directory cd can enter. Appended to goto.bash by
+# `goto --install`; needs _goto_enter from it.
+cd() {
+    # Options, several operands or none: plain cd
+    if [[ $# -ne 1 || "$1" == -* ]]; then
+        builtin cd "$@"
+        return
+    fi
+    builtin cd "$1" 2>/dev/null && return 0
+
+    local output
+    if output=$(GOTO_SHELL=bash GOTO_OLDPWD="$OLDPWD" goto-bin --escape=shell --resolve-quiet "$1"); then
+        _goto_enter "$output" 0
+        return
+    fi
+    # Nothing matched: let cd report its own error
+    builtin cd "$1"
+}
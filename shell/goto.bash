@@ -2,176 +2,140 @@
 # goto shell wrapper for bash
 # Source this file in your .bashrc: source /path/to/goto.bash
 
+# Identify this shell so per-session directory stacks stay separate
+export GOTO_SESSION=$$
+
+# Enter the directory on the first line of output, then apply the alias's
+# environment exports that follow it. The path arrives shell-quoted
+# (--escape=shell) so spaces, quotes and newlines survive.
+_goto_enter() {
+    local output="$1"
+    local exit_code="$2"
+    local line="${output%%$'\n'*}"
+    local target=""
+    [[ $exit_code -eq 0 && "$line" == \'* ]] && eval "target=$line"
+    if [[ $exit_code -eq 0 && -n "$target" && -d "$target" ]]; then
+        cd "$target" || return 1
+        [[ "$output" == *$'\n'* ]] && eval "${output#*$'\n'}"
+        return 0
+    fi
+    [[ -n "$output" ]] && echo "$output"
+    return $exit_code
+}
+
 goto() {
     local output
     local exit_code
 
-    # No arguments: interactive mode with fzf (if available)
+    # A leading --profile=<name> applies to the rest of the command
+    if [[ "$1" == --profile=* ]]; then
+        GOTO_PROFILE="${1#--profile=}" goto "${@:2}"
+        return $?
+    fi
+
+    # No arguments: pick an alias (fzf or built-in, see general.picker), or
+    # show the list when not interactive
     if [[ $# -eq 0 ]]; then
-        if [[ -t 0 ]] && command -v fzf &>/dev/null; then
-            local selected
-            selected=$(goto-bin --names-only | fzf \
-                --preview 'goto-bin -x {}' \
-                --preview-window 'right:50%' \
-                --height 40% \
-                --layout reverse \
-                --border \
-                ${GOTO_FZF_OPTS:-})
-            [[ -z "$selected" ]] && return 0
-            output=$(goto-bin "$selected")
+        if [[ -t 0 ]]; then
+            output=$(GOTO_SHELL=bash GOTO_OLDPWD="$OLDPWD" goto-bin --escape=shell --pick)
             exit_code=$?
-            if [[ $exit_code -eq 0 && -n "$output" && -d "$output" ]]; then
-                cd "$output" || return 1
-            else
-                [[ -n "$output" ]] && echo "$output"
-                return $exit_code
-            fi
-        else
-            # No fzf available or not interactive: show list
-            goto-bin -l
+            _goto_enter "$output" "$exit_code"
+            return $?
         fi
+        goto-bin -l
         return $?
     fi
 
-    output=$(goto-bin "$@")
+    # Editors, ssh sessions and prompts under a table need the terminal, so
+    # don't capture their output
+    if [[ "$1" == "--note" || "$1" == "--code" || "$1" == "--edit-in" || "$1" == "--ssh" || "$1" == "--learn-from-history" ]]; then
+        goto-bin "$@"
+        return $?
+    fi
+
+    # Paths to cd to come back quoted; -x prints the raw path for scripts
+    local escape="--escape=shell"
+    [[ "$1" == "-x" || "$1" == "--expand" ]] && escape=""
+
+    # The output is captured, so say whether it ends up on a terminal (colors)
+    local tty=""
+    [[ -t 1 ]] && tty=1
+
+    # Report the previous directory so `goto -` matches `cd -`
+    output=$(GOTO_TTY="$tty" GOTO_SHELL=bash GOTO_OLDPWD="$OLDPWD" goto-bin ${escape:+"$escape"} "$@")
     exit_code=$?
 
     case "$1" in
-        -h|--help|-v|--version|-l|--list|-c|--cleanup|-x|--expand|--list-aliases|--names-only)
+        -h|--help|-v|--version|-l|--list|-c|--cleanup|--check|--duplicates|--backups|--restore|--fsck|-x|--expand|--where|--preview|--explain|--search|--list-aliases|--names-only)
             echo "$output"
             ;;
         -r|--register|-u|--unregister)
             echo "$output"
             ;;
-        --export|--stats|--tags|--tags-raw|--config)
+        --export|--export-shell-aliases|--report|--diff|--ssh-import|--stats|--tags|--tags-raw|--complete-tag|--config|--profiles|--stack)
+            echo "$output"
+            ;;
+        --open|--rename|--tag|--untag|--rename-tag|--delete-tag|--tag-all|--tag-info|--rewrite-prefix|--env|--reset-stats|--set-count)
             echo "$output"
             ;;
-        --rename|--tag|--untag)
+        --recent-clear|--watch|--unwatch|--watch-scan|--suggestions|--review|--dry-run|--counter)
             echo "$output"
             ;;
-        --recent|--recent-clear)
-            # --recent can either display or navigate
-            if [[ "$1" == "--recent" && -n "$2" && "$2" =~ ^[0-9]+$ && "$2" -le 20 && $# -eq 2 ]]; then
-                # Navigation to Nth recent
-                if [[ $exit_code -eq 0 && -n "$output" && -d "$output" ]]; then
-                    cd "$output" || return 1
-                else
-                    [[ -n "$output" ]] && echo "$output"
-                    return $exit_code
-                fi
-            else
-                echo "$output"
-            fi
+        -R|--recent)
+            # Navigates to a picked or numbered entry, otherwise shows the list
+            _goto_enter "$output" "$exit_code"
+            return $?
             ;;
         --import)
             echo "$output"
             ;;
         -p|--push|-o|--pop|*)
-            if [[ $exit_code -eq 0 && -n "$output" && -d "$output" ]]; then
-                cd "$output" || return 1
-            else
-                [[ -n "$output" ]] && echo "$output"
-                return $exit_code
-            fi
+            _goto_enter "$output" "$exit_code"
+            return $?
             ;;
     esac
     return $exit_code
 }
 
-# Bash completion
+# Time tracking (privacy.track_time): goto sets GOTO_TRACK_DIR when it enters
+# an alias. Report leaving it, in the background so the prompt isn't held up.
+_goto_track_leave() {
+    local status=$?
+    if [[ -n "$GOTO_TRACK_DIR" && "$PWD" != "$GOTO_TRACK_DIR" && "$PWD" != "$GOTO_TRACK_DIR"/* ]]; then
+        unset GOTO_TRACK_DIR
+        (goto-bin --event leave >/dev/null 2>&1 &)
+    fi
+    return $status
+}
+[[ "${PROMPT_COMMAND[*]}" == *_goto_track_leave* ]] || PROMPT_COMMAND="_goto_track_leave${PROMPT_COMMAND:+;$PROMPT_COMMAND}"
+
+# Bash completion, driven by `goto-bin --complete`. The line is split into
+# words here because bash also breaks words at '=', '@' and ':'.
 _goto_completions() {
-    local cur="${COMP_WORDS[COMP_CWORD]}"
-    local prev="${COMP_WORDS[COMP_CWORD-1]}"
+    local line="${COMP_LINE:0:COMP_POINT}"
+    local -a words
+    read -ra words <<< "$line"
+    # A trailing space starts a new, empty word
+    [[ "$line" == *[[:space:]] ]] && words+=("")
+    local cur="${words[${#words[@]}-1]}"
 
-    # Complete flags
-    if [[ "$cur" == -* ]]; then
-        COMPREPLY=($(compgen -W "--export --import --rename --stats --recent --recent-clear --tag --untag --tags --filter= --sort= --config -l -r -u -p -c -h -v -x -o" -- "$cur"))
-        return
-    fi
+    local IFS=$'\n'
+    COMPREPLY=($(goto-bin --complete -- "${words[@]:1}" 2>/dev/null))
 
-    # Handle --filter= and --sort= completion (value after =)
-    if [[ "$cur" == --filter=* ]]; then
-        local prefix="${cur%%=*}="
-        local val="${cur#*=}"
-        COMPREPLY=($(compgen -W "$(goto-bin --tags-raw 2>/dev/null)" -- "$val"))
-        COMPREPLY=("${COMPREPLY[@]/#/$prefix}")
-        return
-    fi
-    if [[ "$cur" == --sort=* ]]; then
-        local prefix="${cur%%=*}="
-        local val="${cur#*=}"
-        COMPREPLY=($(compgen -W "alpha usage recent" -- "$val"))
-        COMPREPLY=("${COMPREPLY[@]/#/$prefix}")
-        return
+    # display.completion_hints sends "alias<TAB>missing" for a directory that's
+    # gone: a lone candidate is inserted bare, in a list it shows a trailing "!"
+    if (( ${#COMPREPLY[@]} == 1 )); then
+        COMPREPLY=("${COMPREPLY[0]%%$'\t'*}")
+    else
+        COMPREPLY=("${COMPREPLY[@]/$'\t'*/!}")
     fi
 
-    case "$prev" in
-        --import)
-            # Complete with files
-            COMPREPLY=($(compgen -f -- "$cur"))
-            return
-            ;;
-        --tag|--untag)
-            # After --tag/--untag, first arg is alias, second is tag
-            # Count how many args after the flag
-            local flag_pos=-1
-            for ((i=0; i<${#COMP_WORDS[@]}; i++)); do
-                if [[ "${COMP_WORDS[i]}" == "--tag" || "${COMP_WORDS[i]}" == "--untag" ]]; then
-                    flag_pos=$i
-                    break
-                fi
-            done
-            local args_after_flag=$((COMP_CWORD - flag_pos))
-            if [[ $args_after_flag -eq 1 ]]; then
-                # First arg: alias names
-                COMPREPLY=($(compgen -W "$(goto-bin --names-only 2>/dev/null)" -- "$cur"))
-            elif [[ $args_after_flag -eq 2 ]]; then
-                # Second arg: tag names
-                COMPREPLY=($(compgen -W "$(goto-bin --tags-raw 2>/dev/null)" -- "$cur"))
-            fi
-            return
-            ;;
-        --rename)
-            # After --rename, first arg is old alias, second is new name
-            local flag_pos=-1
-            for ((i=0; i<${#COMP_WORDS[@]}; i++)); do
-                if [[ "${COMP_WORDS[i]}" == "--rename" ]]; then
-                    flag_pos=$i
-                    break
-                fi
-            done
-            local args_after_flag=$((COMP_CWORD - flag_pos))
-            if [[ $args_after_flag -eq 1 ]]; then
-                # First arg: existing alias names
-                COMPREPLY=($(compgen -W "$(goto-bin --names-only 2>/dev/null)" -- "$cur"))
-            fi
-            # Second arg: new name (no completion)
-            return
-            ;;
-        -r|--register)
-            # First arg is alias name (no completion), second is directory
-            if [[ ${COMP_CWORD} -eq 3 ]]; then
-                COMPREPLY=($(compgen -d -- "$cur"))
-            fi
-            return
-            ;;
-        -u|--unregister|-x|--expand|-p|--push)
-            COMPREPLY=($(compgen -W "$(goto-bin --names-only 2>/dev/null)" -- "$cur"))
-            return
-            ;;
-        goto)
-            if [[ "$cur" == -* ]]; then
-                COMPREPLY=($(compgen -W "--export --import --rename --stats --recent --recent-clear --tag --untag --tags --filter= --sort= --config -l -r -u -p -x -c -o -v -h" -- "$cur"))
-            else
-                COMPREPLY=($(compgen -W "$(goto-bin --names-only 2>/dev/null)" -- "$cur"))
-            fi
-            return
-            ;;
-        *)
-            COMPREPLY=($(compgen -W "$(goto-bin --names-only 2>/dev/null)" -- "$cur"))
-            return
-            ;;
-    esac
+    # Bash replaces only the part after its last word break, so drop the rest
+    local prefix="${cur%"${COMP_WORDS[COMP_CWORD]}"}"
+    [[ -n "$prefix" ]] && COMPREPLY=("${COMPREPLY[@]#"$prefix"}")
+
+    # Directories and --flag= values continue without a space
+    [[ "${COMPREPLY[*]}" == */* || "${COMPREPLY[*]}" == *= ]] && compopt -o nospace
 }
 
 complete -F _goto_completions goto
@@ -1,76 +1,106 @@
 # goto shell wrapper for fish
 # Save to ~/.config/fish/functions/goto.fish or source in config.fish
 
+# Identify this shell so per-session directory stacks stay separate
+set -gx GOTO_SESSION $fish_pid
+
+# Enter the directory on the first line of output, then apply the alias's
+# environment exports that follow it. The path arrives fish-quoted
+# (--escape=fish) so spaces, quotes and newlines survive.
+function __goto_enter
+    set -l exit_code $argv[1]
+    set -l output $argv[2..-1]
+    set -l target ""
+    if test $exit_code -eq 0; and string match -q -- "'*" "$output[1]"
+        set target (string unescape -- $output[1] | string collect)
+    end
+    if test $exit_code -eq 0 -a -n "$target" -a -d "$target"
+        cd $target
+        for line in $output[2..-1]
+            eval $line
+        end
+        return 0
+    end
+    test -n "$output"; and printf '%s\n' $output
+    return $exit_code
+end
+
 function goto
-    # No arguments: interactive mode with fzf (if available)
+    # A leading --profile=<name> applies to the rest of the command
+    if string match -q -- '--profile=*' "$argv[1]"
+        set -lx GOTO_PROFILE (string replace -- '--profile=' '' $argv[1])
+        set argv $argv[2..-1]
+    end
+
+    # No arguments: pick an alias (fzf or built-in, see general.picker), or
+    # show the list when not interactive
     if test (count $argv) -eq 0
-        if isatty stdin; and type -q fzf
-            set -l selected (goto-bin --names-only | fzf \
-                --preview 'goto-bin -x {}' \
-                --preview-window 'right:50%' \
-                --height '40%' \
-                --layout reverse \
-                --border \
-                $GOTO_FZF_OPTS)
-            test -z "$selected"; and return 0
-            set -l output (goto-bin $selected)
-            set -l exit_code $status
-            if test $exit_code -eq 0 -a -n "$output" -a -d "$output"
-                cd $output
-            else
-                test -n "$output"; and echo $output
-                return $exit_code
-            end
-        else
-            # No fzf available or not interactive: show list
+        if not isatty stdin
             goto-bin -l
+            return $status
         end
+        set argv --pick
+    end
+
+    # Editors, ssh sessions and prompts under a table need the terminal, so
+    # don't capture their output
+    if contains -- "$argv[1]" --note --code --edit-in --ssh --learn-from-history
+        goto-bin $argv
         return $status
     end
 
-    set -l output (goto-bin $argv)
+    # Report the previous directory so `goto -` matches `cd -`
+    set -lx GOTO_OLDPWD $dirprev[-1]
+    set -lx GOTO_SHELL fish
+    # The output is captured, so say whether it ends up on a terminal (colors)
+    set -lx GOTO_TTY
+    isatty stdout; and set GOTO_TTY 1
+    # Paths to cd to come back quoted; -x prints the raw path for scripts
+    set -l escape --escape=fish
+    contains -- "$argv[1]" -x --expand; and set escape
+    set -l output (goto-bin $escape $argv)
     set -l exit_code $status
 
     switch "$argv[1]"
-        case -h --help -v --version -l --list -c --cleanup -x --expand --list-aliases --names-only -r --register -u --unregister --export --stats --tags --tags-raw --config --rename --tag --untag --import
+        case -h --help -v --version -l --list -c --cleanup --check --duplicates --backups --restore --fsck -x --expand --where --preview --explain --search --list-aliases --names-only -r --register -u --unregister --export --export-shell-aliases --report --diff --ssh-import --stats --tags --tags-raw --complete-tag --config --profiles --stack --open --rename --tag --untag --rename-tag --delete-tag --tag-all --tag-info --rewrite-prefix --env --reset-stats --set-count --import
             echo $output
-        case --recent --recent-clear
-            # --recent can either display or navigate
-            if test "$argv[1]" = "--recent" -a (count $argv) -eq 2 -a "$argv[2]" -le 20 2>/dev/null
-                # Navigation to Nth recent
-                if test $exit_code -eq 0 -a -n "$output" -a -d "$output"
-                    cd $output
-                else
-                    test -n "$output" && echo $output
-                    return $exit_code
-                end
-            else
-                echo $output
-            end
+        case --recent-clear --watch --unwatch --watch-scan --suggestions --review --dry-run --counter
+            echo $output
+        case -R --recent
+            # Navigates to a picked or numbered entry, otherwise shows the list
+            __goto_enter $exit_code $output
+            return $status
         case '*'
-            if test $exit_code -eq 0 -a -n "$output" -a -d "$output"
-                cd $output
-            else
-                test -n "$output" && echo $output
-                return $exit_code
-            end
+            __goto_enter $exit_code $output
+            return $status
     end
     return $exit_code
 end
 
+# Time tracking (privacy.track_time): goto sets GOTO_TRACK_DIR when it enters
+# an alias. Report leaving it, in the background so cd isn't held up.
+function __goto_track_leave --on-variable PWD
+    set -q GOTO_TRACK_DIR; or return
+    test "$PWD" = "$GOTO_TRACK_DIR"; and return
+    string match -q -- "$GOTO_TRACK_DIR/*" "$PWD"; and return
+    set -e GOTO_TRACK_DIR
+    command goto-bin --event leave >/dev/null 2>&1 &
+    disown
+end
+
 # Fish completions
 complete -c goto -f
 
 # Default: complete with alias names when no flag
-complete -c goto -n "not __fish_seen_subcommand_from -r --register -u --unregister -l --list -x --expand -c --cleanup -p --push -o --pop -v --version -h --help --export --import --rename --stats --recent --recent-clear --tag --untag --tags --filter --sort --config" -a "(goto-bin --names-only 2>/dev/null)"
+complete -c goto -n "not __fish_seen_subcommand_from -r --register -u --unregister -l --list -x --expand -c --cleanup -p --push -o --pop -v --version -h --help --export --import --rename --stats --recent --recent-clear --tag --untag --tags --filter --sort --config" -a "(goto-bin --complete -- '' 2>/dev/null)"
 
 # Basic options
 complete -c goto -s r -l register -d "Register alias" -r -F
-complete -c goto -s u -l unregister -d "Unregister alias" -ra "(goto-bin --names-only 2>/dev/null)"
+complete -c goto -s u -l unregister -d "Unregister alias" -ra "(goto-bin --complete -- '' 2>/dev/null)"
 complete -c goto -s l -l list -d "List aliases"
-complete -c goto -s x -l expand -d "Expand alias" -ra "(goto-bin --names-only 2>/dev/null)"
+complete -c goto -s x -l expand -d "Expand alias" -ra "(goto-bin --complete -- '' 2>/dev/null)"
 complete -c goto -s c -l cleanup -d "Cleanup invalid aliases"
-complete -c goto -s p -l push -d "Push and goto" -ra "(goto-bin --names-only 2>/dev/null)"
+complete -c goto -s p -l push -d "Push and goto" -ra "(goto-bin --complete -- '' 2>/dev/null)"
 complete -c goto -s o -l pop -d "Pop directory"
 complete -c goto -s v -l version -d "Show version"
 complete -c goto -s h -l help -d "Show help"
@@ -78,24 +108,28 @@ complete -c goto -s h -l help -d "Show help"
 # Export/Import
 complete -c goto -l export -d "Export aliases to TOML"
 complete -c goto -l import -d "Import aliases from file" -r
+complete -c goto -l report -d "Write an HTML report of the aliases"
+complete -c goto -l diff -d "Compare alias export files" -r
 
 # Rename
-complete -c goto -l rename -d "Rename an alias" -ra "(goto-bin --names-only 2>/dev/null)"
+complete -c goto -l rename -d "Rename an alias" -ra "(goto-bin --complete -- '' 2>/dev/null)"
 
 # Statistics and recent
 complete -c goto -l stats -d "Show usage statistics"
 complete -c goto -l recent -d "Show recently visited"
 complete -c goto -l recent-clear -d "Clear recent history"
+complete -c goto -l random -d "Go to a random alias"
+complete -c goto -l fsck -d "Check and salvage the database"
 
 # Tags
-complete -c goto -l tag -d "Add tag to alias" -ra "(goto-bin --names-only 2>/dev/null)"
-complete -c goto -l untag -d "Remove tag from alias" -ra "(goto-bin --names-only 2>/dev/null)"
+complete -c goto -l tag -d "Add tag to alias" -ra "(goto-bin --complete -- '' 2>/dev/null)"
+complete -c goto -l untag -d "Remove tag from alias" -ra "(goto-bin --complete -- '' 2>/dev/null)"
 complete -c goto -l tags -d "List all tags"
 
 # Filtering and sorting (used with --list)
 # Note: These use --filter=<tag> and --sort=<order> format
-complete -c goto -l filter= -d "Filter by tag" -xa "(goto-bin --tags-raw 2>/dev/null)"
-complete -c goto -l sort= -d "Sort list" -xa "alpha usage recent"
+complete -c goto -l filter= -d "Filter by tag" -xa "(goto-bin --tags-raw --with-counts 2>/dev/null)"
+complete -c goto -l sort= -d "Sort list" -xa "alpha usage recent status"
 
 # Config
 complete -c goto -l config -d "Show configuration"
@@ -0,0 +1,65 @@
+# goto shell wrapper for PowerShell
+# Dot-source this file in your $PROFILE: . /path/to/goto.ps1
+
+# Identify this shell so per-session directory stacks stay separate
+$env:GOTO_SESSION = "$PID"
+
+function goto {
+    # A leading --profile=<name> applies to the rest of the command
+    if ($args.Count -gt 0 -and $args[0] -like '--profile=*') {
+        $previous = $env:GOTO_PROFILE
+        $env:GOTO_PROFILE = $args[0].Substring('--profile='.Length)
+        $rest = @($args | Select-Object -Skip 1)
+        try {
+            goto @rest
+        } finally {
+            $env:GOTO_PROFILE = $previous
+        }
+        return
+    }
+
+    # No arguments: pick an alias (fzf or built-in, see general.picker), or
+    # show the list when not interactive
+    if ($args.Count -eq 0) {
+        if ([Console]::IsInputRedirected) {
+            goto-bin -l
+        } else {
+            goto --pick
+        }
+        return
+    }
+
+    # goto-bin writes the directory to enter to a file, so its output can go
+    # straight to the terminal: the path, a NUL, then the environment exports
+    $cdFile = [System.IO.Path]::GetTempFileName()
+    $env:GOTO_SHELL = 'powershell'
+    $env:GOTO_CD_FILE = $cdFile
+    try {
+        goto-bin @args
+        $output = [System.IO.File]::ReadAllText($cdFile)
+    } finally {
+        Remove-Item Env:GOTO_SHELL -ErrorAction SilentlyContinue
+        Remove-Item Env:GOTO_CD_FILE -ErrorAction SilentlyContinue
+        Remove-Item -LiteralPath $cdFile -ErrorAction SilentlyContinue
+    }
+
+    $parts = $output -split "`0", 2
+    $target = if ($parts.Count -gt 1) { $parts[0] } else { '' }
+    if ($target -and (Test-Path -LiteralPath $target -PathType Container)) {
+        Set-Location -LiteralPath $target
+        $exports = $parts[1].Trim()
+        if ($exports) {
+            Invoke-Expression $exports
+        }
+    }
+}
+
+# PowerShell completion: alias names for the first argument
+Register-ArgumentCompleter -Native -CommandName goto -ScriptBlock {
+    param($wordToComplete, $commandAst, $cursorPosition)
+    goto-bin --names-only 2>$null |
+        Where-Object { $_ -like "$wordToComplete*" } |
+        ForEach-Object {
+            [System.Management.Automation.CompletionResult]::new($_, $_, 'ParameterValue', $_)
+        }
+}
@@ -1,16 +1,27 @@
 //! Alias type and validation
 
-use chrono::{DateTime, Utc};
+use chrono::{DateTime, Duration, Utc};
 use regex::Regex;
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::Path;
 use std::sync::LazyLock;
 use thiserror::Error;
 
+use crate::volume::Volume;
+
+/// Alias names: a letter or digit, then letters, digits, `-`, `_`, `.` and `@`
+///
+/// Letters and digits of any script count, with their combining accents.
+/// Never a leading dash (flags), `/` (paths), `:` (`alias:subdir`) or whitespace.
 static VALID_ALIAS_PATTERN: LazyLock<Regex> =
-    LazyLock::new(|| Regex::new(r"^[a-zA-Z0-9][a-zA-Z0-9_.-]*$").unwrap());
+    LazyLock::new(|| Regex::new(r"^[\p{L}\p{N}][\p{L}\p{M}\p{N}_.@-]*$").unwrap());
 
 static VALID_TAG_PATTERN: LazyLock<Regex> =
-    LazyLock::new(|| Regex::new(r"^[a-zA-Z0-9][a-zA-Z0-9_-]*$").unwrap());
+    LazyLock::new(|| Regex::new(r"^[\p{L}\p{N}][\p{L}\p{M}\p{N}_-]*$").unwrap());
+
+static VALID_ENV_NAME_PATTERN: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"^[a-zA-Z_][a-zA-Z0-9_]*$").unwrap());
 
 /// Errors that can occur during alias operations
 #[derive(Error, Debug)]
@@ -27,8 +38,40 @@ pub enum AliasError {
     #[error("directory does not exist: {0}")]
     DirectoryNotFound(String),
 
+    #[error("permission denied: {0}")]
+    PermissionDenied(String),
+
+    #[error("alias '{0}' is shared and read-only")]
+    ReadOnly(String),
+
     #[error("invalid tag '{tag}': {reason}")]
     InvalidTag { tag: String, reason: String },
+
+    #[error("invalid env variable '{name}': {reason}")]
+    InvalidEnv { name: String, reason: String },
+
+    #[error("alias '{target}' not found (linked from '{alias}')")]
+    BrokenLink { alias: String, target: String },
+
+    #[error("alias links form a cycle: {0}")]
+    LinkCycle(String),
+}
+
+/// Why goto keeps `name` for itself, if it does
+///
+/// These names would be read as something else on the command line, so an
+/// alias with one couldn't be reached. User reservations come on top (see
+/// [`crate::Database::check_name`]).
+pub fn builtin_reservation(name: &str) -> Option<String> {
+    match name {
+        "-" => Some("'goto -' goes back to the previous directory".to_string()),
+        "." | ".." => Some(format!("'{}' is a relative path, not a name", name)),
+        _ if name.starts_with('-') => Some(format!("'{}' would be read as a goto option", name)),
+        _ if name.strip_prefix('%').is_some_and(|n| n.parse::<usize>().is_ok()) => {
+            Some(format!("'goto {}' goes to that entry of the last listing", name))
+        }
+        _ => None,
+    }
 }
 
 /// Validate that an alias name is acceptable
@@ -40,10 +83,17 @@ pub fn validate_alias(name: &str) -> Result<(), AliasError> {
         });
     }
 
+    if let Some(reason) = builtin_reservation(name) {
+        return Err(AliasError::InvalidAlias {
+            alias: name.to_string(),
+            reason: format!("reserved: {}", reason),
+        });
+    }
+
     if !VALID_ALIAS_PATTERN.is_match(name) {
         return Err(AliasError::InvalidAlias {
             alias: name.to_string(),
-            reason: "must start with letter/digit and contain only letters, digits, hyphens, underscores, dots".to_string(),
+            reason: "must start with letter/digit and contain only letters, digits, hyphens, underscores, dots, at-signs".to_string(),
         });
     }
 
@@ -69,8 +119,23 @@ pub fn validate_tag(tag: &str) -> Result<(), AliasError> {
     Ok(())
 }
 
+/// Validate that an environment variable name is acceptable
+pub fn validate_env_name(name: &str) -> Result<(), AliasError> {
+    if !VALID_ENV_NAME_PATTERN.is_match(name) {
+        return Err(AliasError::InvalidEnv {
+            name: name.to_string(),
+            reason: "must start with a letter or underscore and contain only letters, digits, underscores".to_string(),
+        });
+    }
+
+    Ok(())
+}
+
 /// Represents a directory alias with metadata
+///
+/// New fields may be added in minor releases; build one with [`Alias::new`].
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[non_exhaustive]
 pub struct Alias {
     /// The alias name
     pub name: String,
@@ -88,21 +153,49 @@ pub struct Alias {
     /// Timestamp when the alias was created
     #[serde(default = "Utc::now")]
     pub created_at: DateTime<Utc>,
+    /// Environment variables exported when navigating to this alias
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub env: BTreeMap<String, String>,
+    /// Registered with `--allow-missing` before the directory existed;
+    /// cleared once the directory has been seen
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub pending: bool,
+    /// Unused for `hygiene.dormant_after_days`; refreshed when the database is saved
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub dormant: bool,
+    /// When `--review` last chose to keep this alias
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reviewed_at: Option<DateTime<Utc>>,
+    /// The volume the directory was on when registered, unless the root filesystem
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub volume: Option<Volume>,
+    /// Another alias this one stands for (`-r <name> --link <alias>`); its
+    /// path follows that alias and uses are recorded there
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub link: Option<String>,
 }
 
 impl Alias {
     /// Create a new alias with the given name and path
     pub fn new(name: &str, path: &str) -> Result<Self, AliasError> {
-        validate_alias(name)?;
+        // One spelling per name, however the accents were typed
+        let name = crate::collate::nfc(name);
+        validate_alias(&name)?;
         Self::validate_path(path)?;
 
         Ok(Self {
-            name: name.to_string(),
+            name: name.into_owned(),
             path: path.to_string(),
             tags: Vec::new(),
             use_count: 0,
             last_used: None,
             created_at: Utc::now(),
+            env: BTreeMap::new(),
+            pending: false,
+            dormant: false,
+            reviewed_at: None,
+            volume: None,
+            link: None,
         })
     }
 
@@ -122,6 +215,25 @@ impl Alias {
     pub fn record_use(&mut self) {
         self.use_count += 1;
         self.last_used = Some(Utc::now());
+        // Only directories that exist can be navigated to
+        self.pending = false;
+        self.dormant = false;
+    }
+
+    /// Whether the alias has gone `after` without being used, created or
+    /// kept by `--review` (pending aliases never go dormant, nor do links,
+    /// whose uses count for the alias they stand for)
+    pub fn is_dormant(&self, after: Duration, now: DateTime<Utc>) -> bool {
+        let last_active = [self.last_used, self.reviewed_at]
+            .into_iter()
+            .flatten()
+            .fold(self.created_at, DateTime::max);
+        !self.pending && self.link.is_none() && now - last_active >= after
+    }
+
+    /// Whether the alias is still waiting for its directory to appear
+    pub fn is_pending(&self) -> bool {
+        self.pending && !Path::new(&self.path).exists()
     }
 
     /// Add a tag to this alias
@@ -153,6 +265,15 @@ impl Alias {
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_validate_env_name() {
+        assert!(validate_env_name("RUST_LOG").is_ok());
+        assert!(validate_env_name("_private1").is_ok());
+        assert!(validate_env_name("").is_err());
+        assert!(validate_env_name("1ABC").is_err());
+        assert!(validate_env_name("MY-VAR").is_err());
+    }
+
     #[test]
     fn test_new_alias() {
         let alias = Alias::new("projects", "/home/user/projects").unwrap();
@@ -198,6 +319,55 @@ mod tests {
         assert!(alias.last_used.is_some());
     }
 
+    #[test]
+    fn test_pending_until_directory_exists_or_used() {
+        let dir = tempfile::tempdir().unwrap();
+        let later = dir.path().join("later");
+        let mut alias = Alias::new("later", later.to_str().unwrap()).unwrap();
+        assert!(!alias.is_pending());
+
+        alias.pending = true;
+        assert!(alias.is_pending());
+
+        std::fs::create_dir(&later).unwrap();
+        assert!(!alias.is_pending());
+
+        alias.record_use();
+        assert!(!alias.pending);
+    }
+
+    #[test]
+    fn test_is_dormant() {
+        let now = Utc::now();
+        let after = Duration::days(90);
+        let mut alias = Alias::new("old", "/tmp").unwrap();
+        alias.created_at = now - Duration::days(200);
+        assert!(alias.is_dormant(after, now));
+
+        alias.last_used = Some(now - Duration::days(10));
+        assert!(!alias.is_dormant(after, now));
+
+        alias.last_used = Some(now - Duration::days(100));
+        alias.reviewed_at = Some(now - Duration::days(1));
+        assert!(!alias.is_dormant(after, now));
+
+        alias.reviewed_at = None;
+        alias.pending = true;
+        assert!(!alias.is_dormant(after, now));
+    }
+
+    #[test]
+    fn test_pending_is_omitted_from_toml_unless_set() {
+        let mut alias = Alias::new("test", "/tmp").unwrap();
+        assert!(!toml::to_string(&alias).unwrap().contains("pending"));
+
+        alias.pending = true;
+        let text = toml::to_string(&alias).unwrap();
+        assert!(text.contains("pending = true"));
+        let back: Alias = toml::from_str(&text).unwrap();
+        assert!(back.pending);
+    }
+
     #[test]
     fn test_tags() {
         let mut alias = Alias::new("test", "/tmp").unwrap();
@@ -232,6 +402,17 @@ mod tests {
         assert!(matches!(result, Err(AliasError::InvalidAlias { .. })));
     }
 
+    #[test]
+    fn test_validate_alias_reserved() {
+        for name in ["-", "..", ".", "--list", "-l", "%3"] {
+            let err = validate_alias(name).unwrap_err().to_string();
+            assert!(err.contains("reserved:"), "{}", err);
+        }
+        assert!(validate_alias("--list").unwrap_err().to_string().contains("read as a goto option"));
+        assert_eq!(builtin_reservation("list"), None);
+        assert_eq!(builtin_reservation("%x"), None);
+    }
+
     #[test]
     fn test_validate_alias_starts_with_underscore() {
         let result = validate_alias("_invalid");
@@ -251,14 +432,37 @@ mod tests {
         assert!(validate_alias("my_project").is_ok());
         assert!(validate_alias("my.project").is_ok());
         assert!(validate_alias("my-project_v1.0").is_ok());
+        assert!(validate_alias("api.v2").is_ok());
+        assert!(validate_alias("work@client").is_ok());
+        assert!(validate_alias("me@host.example").is_ok());
+    }
+
+    #[test]
+    fn test_validate_alias_unicode_letters() {
+        assert!(validate_alias("проект").is_ok());
+        assert!(validate_alias("東京").is_ok());
+        assert!(validate_alias("café-2").is_ok());
+        assert!(validate_alias("cafe\u{301}").is_ok());
+        assert!(validate_alias("\u{301}cafe").is_err());
+        assert!(validate_alias("проект мой").is_err());
+        assert!(validate_tag("работа").is_ok());
+        assert!(validate_tag("日本").is_ok());
+    }
+
+    #[test]
+    fn test_alias_new_composes_name() {
+        let alias = Alias::new("cafe\u{301}", "/tmp").unwrap();
+        assert_eq!(alias.name, "café");
     }
 
     #[test]
     fn test_validate_alias_invalid_special_chars() {
         assert!(validate_alias("hello world").is_err());
-        assert!(validate_alias("hello@world").is_err());
         assert!(validate_alias("hello/world").is_err());
         assert!(validate_alias("hello:world").is_err());
+        assert!(validate_alias("hello\tworld").is_err());
+        assert!(validate_alias("@client").is_err());
+        assert!(validate_alias(".hidden").is_err());
     }
 
     // Tests for validate_tag function
@@ -314,6 +518,9 @@ mod tests {
         let err = AliasError::DirectoryNotFound("/nonexistent".to_string());
         assert_eq!(format!("{}", err), "directory does not exist: /nonexistent");
 
+        let err = AliasError::PermissionDenied("/root/secret".to_string());
+        assert_eq!(format!("{}", err), "permission denied: /root/secret");
+
         let err = AliasError::InvalidAlias {
             alias: "bad".to_string(),
             reason: "test reason".to_string(),
@@ -0,0 +1,202 @@
+//! Rotating timestamped backups of the alias database
+
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use chrono::{DateTime, NaiveDateTime, Utc};
+
+use crate::exit::{Code, Failure};
+
+/// Timestamp format used in backup file names (sorts chronologically)
+const TIMESTAMP_FORMAT: &str = "%Y%m%dT%H%M%S";
+
+const FILE_PREFIX: &str = "aliases-";
+const FILE_SUFFIX: &str = ".toml";
+
+/// A single backup file
+#[derive(Debug, Clone, PartialEq)]
+pub struct Backup {
+    /// Timestamp identifying the backup, e.g. `20240115T093000`
+    pub timestamp: String,
+    pub path: PathBuf,
+}
+
+impl Backup {
+    /// Time the backup was taken, if the timestamp is well-formed
+    pub fn created_at(&self) -> Option<DateTime<Utc>> {
+        NaiveDateTime::parse_from_str(&self.timestamp, TIMESTAMP_FORMAT)
+            .ok()
+            .map(|t| t.and_utc())
+    }
+}
+
+/// Directory of rotating database backups
+#[derive(Debug, Clone)]
+pub struct BackupStore {
+    dir: PathBuf,
+    keep_count: usize,
+}
+
+impl BackupStore {
+    pub fn new(dir: PathBuf, keep_count: usize) -> Self {
+        Self { dir, keep_count }
+    }
+
+    /// Copy `source` into the store and drop backups beyond `keep_count`
+    ///
+    /// Returns `None` if `source` does not exist yet.
+    pub fn create(&self, source: &Path) -> io::Result<Option<Backup>> {
+        if !source.exists() {
+            return Ok(None);
+        }
+
+        fs::create_dir_all(&self.dir)?;
+
+        let timestamp = Utc::now().format(TIMESTAMP_FORMAT).to_string();
+        let path = self.path_for(&timestamp);
+        // Several saves within one second keep the state before the last one
+        fs::copy(source, &path)?;
+
+        self.rotate()?;
+        Ok(Some(Backup { timestamp, path }))
+    }
+
+    /// Remove the oldest backups so at most `keep_count` remain
+    pub fn rotate(&self) -> io::Result<()> {
+        let backups = self.list()?;
+        let excess = backups.len().saturating_sub(self.keep_count);
+        for backup in &backups[..excess] {
+            fs::remove_file(&backup.path)?;
+            // Earlier versions locked backups when reading them
+            match fs::remove_file(backup.path.with_extension("lock")) {
+                Err(e) if e.kind() != io::ErrorKind::NotFound => return Err(e),
+                _ => {}
+            }
+        }
+        Ok(())
+    }
+
+    /// List backups, oldest first
+    pub fn list(&self) -> io::Result<Vec<Backup>> {
+        let entries = match fs::read_dir(&self.dir) {
+            Ok(entries) => entries,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(e) => return Err(e),
+        };
+
+        let mut backups: Vec<Backup> = entries
+            .filter_map(|e| e.ok())
+            .filter_map(|e| {
+                let name = e.file_name().to_string_lossy().into_owned();
+                let timestamp = name.strip_prefix(FILE_PREFIX)?.strip_suffix(FILE_SUFFIX)?;
+                Some(Backup {
+                    timestamp: timestamp.to_string(),
+                    path: e.path(),
+                })
+            })
+            .collect();
+
+        backups.sort_by(|a, b| a.timestamp.cmp(&b.timestamp));
+        Ok(backups)
+    }
+
+    /// Find a backup by timestamp, unique timestamp prefix, or `latest`
+    pub fn find(&self, timestamp: &str) -> Result<Backup, Failure> {
+        let backups = self.list().map_err(|e| Failure::new(Code::Error, e.to_string()))?;
+
+        if timestamp == "latest" {
+            return backups
+                .last()
+                .cloned()
+                .ok_or_else(|| Failure::not_found("backup 'latest' not found"));
+        }
+
+        let matches: Vec<&Backup> = backups
+            .iter()
+            .filter(|b| b.timestamp.starts_with(timestamp))
+            .collect();
+
+        match matches.as_slice() {
+            [] => Err(Failure::not_found(format!("backup '{}' not found", timestamp))),
+            [backup] => Ok((*backup).clone()),
+            _ => Err(Failure::invalid(format!(
+                "backup '{}' is ambiguous ({} matches)",
+                timestamp,
+                matches.len()
+            ))),
+        }
+    }
+
+    fn path_for(&self, timestamp: &str) -> PathBuf {
+        self.dir.join(format!("{}{}{}", FILE_PREFIX, timestamp, FILE_SUFFIX))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    fn write_backup(dir: &Path, timestamp: &str) {
+        fs::create_dir_all(dir).unwrap();
+        fs::write(dir.join(format!("aliases-{}.toml", timestamp)), timestamp).unwrap();
+    }
+
+    #[test]
+    fn test_create_copies_source() {
+        let temp = tempdir().unwrap();
+        let source = temp.path().join("aliases.toml");
+        fs::write(&source, "content").unwrap();
+        let store = BackupStore::new(temp.path().join("backups"), 5);
+
+        let backup = store.create(&source).unwrap().unwrap();
+
+        assert_eq!(fs::read_to_string(&backup.path).unwrap(), "content");
+        assert!(backup.created_at().is_some());
+        assert_eq!(store.list().unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_create_missing_source() {
+        let temp = tempdir().unwrap();
+        let store = BackupStore::new(temp.path().join("backups"), 5);
+
+        assert!(store.create(&temp.path().join("missing.toml")).unwrap().is_none());
+        assert!(!temp.path().join("backups").exists());
+    }
+
+    #[test]
+    fn test_rotate_keeps_newest() {
+        let temp = tempdir().unwrap();
+        let dir = temp.path().join("backups");
+        for ts in ["20240101T000000", "20240102T000000", "20240103T000000"] {
+            write_backup(&dir, ts);
+        }
+        fs::write(dir.join("unrelated.txt"), "").unwrap();
+        fs::write(dir.join("aliases-20240101T000000.lock"), "").unwrap();
+        let store = BackupStore::new(dir.clone(), 2);
+
+        store.rotate().unwrap();
+
+        let timestamps: Vec<String> = store.list().unwrap().into_iter().map(|b| b.timestamp).collect();
+        assert_eq!(timestamps, vec!["20240102T000000", "20240103T000000"]);
+        assert!(dir.join("unrelated.txt").exists());
+        assert!(!dir.join("aliases-20240101T000000.lock").exists());
+    }
+
+    #[test]
+    fn test_find() {
+        let temp = tempdir().unwrap();
+        let dir = temp.path().join("backups");
+        for ts in ["20240101T000000", "20240102T000000", "20240102T120000"] {
+            write_backup(&dir, ts);
+        }
+        let store = BackupStore::new(dir, 10);
+
+        assert_eq!(store.find("20240101").unwrap().timestamp, "20240101T000000");
+        assert_eq!(store.find("latest").unwrap().timestamp, "20240102T120000");
+        assert!(store.find("20240102").unwrap_err().message.contains("ambiguous"));
+        assert!(store.find("2023").unwrap_err().message.contains("not found"));
+    }
+}
@@ -1,6 +1,12 @@
 //! Command-line argument parsing for goto
 
+use std::path::PathBuf;
+use std::str::FromStr;
+
 use crate::commands::import_export::ImportStrategy;
+use crate::commands::open::Opener;
+use crate::commands::review::Criterion;
+use crate::output::PathFormat;
 
 const VERSION: &str = env!("CARGO_PKG_VERSION");
 
@@ -8,6 +14,22 @@ const VERSION: &str = env!("CARGO_PKG_VERSION");
 #[derive(Debug)]
 pub struct Args {
     pub command: Command,
+    /// Profile selected with `--profile=<name>`
+    pub profile: Option<String>,
+    /// How printed directory paths are written (`--print0`, `--escape=`)
+    pub path_format: PathFormat,
+    /// File to write the directory to enter to, instead of stdout (`--cd-file <path>`)
+    pub cd_file: Option<PathBuf>,
+    /// Debug logging to stderr (`-V/--verbose`)
+    pub verbose: bool,
+    /// Report how long each phase took on stderr (`--timings`)
+    pub timings: bool,
+    /// Show dates instead of "3 days ago" (`--absolute-dates`)
+    pub absolute_dates: bool,
+    /// Suppress informational messages and hints (`--quiet`)
+    pub quiet: bool,
+    /// Never prompt (`--no-input`)
+    pub no_input: bool,
 }
 
 /// All supported commands
@@ -15,10 +37,18 @@ pub struct Args {
 pub enum Command {
     Help,
     Version,
-    Config,
+    Config {
+        /// `--validate`: check config.toml instead of showing the settings
+        validate: bool,
+    },
+    Profiles,
     List {
         sort: Option<String>,
         filter: Option<String>,
+        columns: Option<String>,
+        group_by: Option<String>,
+        porcelain: bool,
+        wide: bool,
     },
     ListNames,
     Register {
@@ -26,16 +56,92 @@ pub enum Command {
         path: String,
         tags: Vec<String>,
         force: bool,
+        no_resolve: bool,
+        /// Keep the alias only for the current shell session
+        temporary: bool,
+        /// Accept a directory that doesn't exist yet
+        allow_missing: bool,
+        /// Stand for this other alias instead of a directory (`--link`)
+        link: Option<String>,
+        dry_run: bool,
     },
     Unregister {
         name: String,
+        dry_run: bool,
+    },
+    /// Create a directory from a template and register it (`--new`)
+    New {
+        name: String,
+        path: String,
+        template: Option<String>,
+        tags: Vec<String>,
+    },
+    Clone {
+        url: String,
+        alias: Option<String>,
+        tags: Vec<String>,
+    },
+    Previous,
+    /// Pick an alias interactively with the configured picker
+    Pick,
+    /// Alias containing the current directory; `cache` answers from the
+    /// where cache (the global `--quiet` fails silently outside any alias)
+    Where {
+        cache: bool,
+    },
+    /// Navigations in this shell session, for status bars
+    Counter,
+    /// Print a prompt snippet for a shell
+    PromptSnippet {
+        shell: String,
+    },
+    Search {
+        query: String,
+        limit: Option<usize>,
+        json: bool,
+        wide: bool,
     },
     Navigate {
         alias: String,
     },
+    /// `goto --random`: a random alias
+    Random {
+        filter: Option<String>,
+        weighted: bool,
+        dry_run: bool,
+    },
+    /// `goto %N`: row N of the last `-l`
+    NavigateListed {
+        index: usize,
+    },
     Expand {
         alias: String,
     },
+    /// Navigate without suggestions or messages, for the shells' `cd` fallback
+    ResolveQuiet {
+        query: String,
+    },
+    Preview {
+        alias: String,
+    },
+    Explain {
+        query: String,
+    },
+    Note {
+        alias: String,
+    },
+    /// Open an alias's directory in the file manager or editor
+    Open {
+        alias: String,
+        opener: Opener,
+    },
+    Env {
+        alias: String,
+        assignments: Vec<String>,
+        unset: Vec<String>,
+    },
+    Check,
+    Duplicates,
     Cleanup {
         dry_run: bool,
     },
@@ -43,9 +149,13 @@ pub enum Command {
         alias: String,
     },
     Pop,
+    ShowStack {
+        all_sessions: bool,
+    },
     Rename {
         old_name: String,
-        new_name: String,
+        /// `None` fuzzy-matches `old_name` and prompts for the new name
+        new_name: Option<String>,
     },
     Tag {
         alias: String,
@@ -62,33 +172,298 @@ pub enum Command {
         dry_run: bool,
         force: bool,
     },
-    ListTags,
-    ListTagsRaw,
+    DeleteTag {
+        tag: String,
+        dry_run: bool,
+        force: bool,
+    },
+    /// Replace the leading `old` of alias paths with `new`
+    RewritePrefix {
+        old: String,
+        new: String,
+        dry_run: bool,
+        allow_missing: bool,
+    },
+    TagAll {
+        tag: String,
+        filter: String,
+        remove: bool,
+        dry_run: bool,
+        force: bool,
+    },
+    ListTags {
+        sort: Option<String>,
+        json: bool,
+    },
+    TagInfo {
+        tag: String,
+        description: Option<String>,
+        color: Option<String>,
+    },
+    ListTagsRaw {
+        with_counts: bool,
+    },
+    CompleteTag {
+        prefix: String,
+        with_counts: bool,
+    },
     Stats,
     Recent {
         count: Option<usize>,
         navigate_to: Option<usize>,
     },
     RecentClear,
-    Export,
+    ResetStats {
+        /// `None` resets every alias (`--all`)
+        alias: Option<String>,
+    },
+    SetCount {
+        alias: String,
+        count: u64,
+    },
+    Export {
+        output: Option<String>,
+        /// Only export aliases matching this filter expression
+        filter: Option<String>,
+    },
+    ExportShellAliases {
+        /// `--shell=`; detected from `$SHELL` when missing
+        shell: Option<String>,
+        output: Option<String>,
+        filter: Option<String>,
+    },
+    /// Compare two export files, or the database with one
+    Diff {
+        old: String,
+        /// The file `old` is compared with; the database when missing
+        new: Option<String>,
+    },
+    /// Self-contained HTML page of the aliases, for sharing or auditing
+    Report {
+        output: Option<String>,
+        filter: Option<String>,
+    },
+    /// Open a shell in an alias's directory on a remote host
+    Ssh {
+        host: String,
+        alias: String,
+        /// Print the ssh command instead of running it
+        print: bool,
+    },
+    /// Report entering (`Some(alias)`) or leaving an alias, for `privacy.track_time`
+    Event {
+        enter: Option<String>,
+    },
+    SshImport {
+        host: String,
+        /// `--export` file, `-` for stdin
+        file: String,
+    },
+    Backups,
+    Fsck {
+        dry_run: bool,
+    },
+    Restore {
+        timestamp: String,
+        force: bool,
+    },
     Import {
         file: String,
         strategy: ImportStrategy,
+        force: bool,
+        dry_run: bool,
     },
     Install {
         shell: Option<String>,
         skip_rc: bool,
         dry_run: bool,
+        watch_timer: bool,
+    },
+    Uninstall {
+        shell: Option<String>,
+        purge: bool,
+        force: bool,
+        dry_run: bool,
+    },
+    Update {
+        version: Option<String>,
     },
-    Update,
     CheckUpdate,
+    /// Internal: run the automatic update check spawned after navigation
+    UpdateCheckDaemon,
+    /// Internal: time alias resolution on a synthetic database, failing
+    /// when a step's mean exceeds `max_ms`
+    BenchSelftest {
+        max_ms: Option<u64>,
+    },
     PruneSnooze {
         days: u32,
     },
+    /// `None` lists the watched directories
+    Watch {
+        dir: Option<String>,
+    },
+    /// Completion candidates for a partial command line (`--complete -- ...`)
+    Complete {
+        words: Vec<String>,
+    },
+    Unwatch {
+        dir: String,
+    },
+    WatchScan,
+    Suggestions,
+    /// Rank directories `cd`ed to in shell history for registering
+    /// (`None` reads the usual bash, zsh and fish history files)
+    LearnFromHistory {
+        file: Option<String>,
+        limit: Option<usize>,
+    },
+    /// Review queue over aliases matching the criteria (`--review [list]`)
+    Review {
+        criteria: Vec<Criterion>,
+    },
+}
+
+impl Command {
+    /// Whether the command reads or writes the alias database
+    ///
+    /// Commands that don't are dispatched before the database is loaded, so
+    /// they don't pay for parsing it.
+    pub fn needs_database(&self) -> bool {
+        !matches!(
+            self,
+            Command::Help
+                | Command::Version
+                | Command::Config { .. }
+                | Command::Where { cache: true }
+                | Command::Counter
+                | Command::PromptSnippet { .. }
+                | Command::Profiles
+                | Command::Install { .. }
+                | Command::Uninstall { .. }
+                | Command::Ssh { .. }
+                | Command::SshImport { .. }
+                | Command::Event { .. }
+                | Command::Backups
+                | Command::Fsck { .. }
+                | Command::Restore { .. }
+                | Command::Update { .. }
+                | Command::CheckUpdate
+                | Command::UpdateCheckDaemon
+                | Command::BenchSelftest { .. }
+                | Command::PruneSnooze { .. }
+                | Command::Watch { .. }
+                | Command::Unwatch { .. }
+                | Command::Pop
+                | Command::Previous
+                | Command::ShowStack { .. }
+        )
+    }
+
+    /// Whether the command was given `--force`
+    pub fn force(&self) -> bool {
+        match self {
+            Command::Register { force, .. }
+            | Command::Tag { force, .. }
+            | Command::RenameTag { force, .. }
+            | Command::DeleteTag { force, .. }
+            | Command::TagAll { force, .. }
+            | Command::Restore { force, .. }
+            | Command::Import { force, .. }
+            | Command::Uninstall { force, .. } => *force,
+            _ => false,
+        }
+    }
+
+    /// Whether `--dry-run` was given, for commands that can preview their changes
+    ///
+    /// `None` for the others, which refuse the flag rather than ignore it.
+    pub fn dry_run(&self) -> Option<bool> {
+        match self {
+            Command::Register { dry_run, .. }
+            | Command::Unregister { dry_run, .. }
+            | Command::Random { dry_run, .. }
+            | Command::Cleanup { dry_run }
+            | Command::RenameTag { dry_run, .. }
+            | Command::DeleteTag { dry_run, .. }
+            | Command::RewritePrefix { dry_run, .. }
+            | Command::TagAll { dry_run, .. }
+            | Command::Fsck { dry_run }
+            | Command::Import { dry_run, .. }
+            | Command::Install { dry_run, .. }
+            | Command::Uninstall { dry_run, .. } => Some(*dry_run),
+            _ => None,
+        }
+    }
 }
 
 /// Parse command-line arguments into a structured Args object
 pub fn parse_args(args: &[String]) -> Result<Args, String> {
+    // --profile=<name> applies to every command, wherever it appears
+    let profile = find_flag_value(args, "--profile=");
+    // So are the path output modes used by the shell wrappers
+    let path_format = match find_flag_value(args, "--escape=") {
+        Some(value) => PathFormat::from_escape(&value)?,
+        None if has_flag(args, "--print0") => PathFormat::Nul,
+        None => PathFormat::Line,
+    };
+    let cd_file = match find_flag_value(args, "--cd-file=") {
+        Some(file) => Some(file),
+        None if has_flag(args, "--cd-file") => {
+            Some(find_space_separated_flag(args, "--cd-file").ok_or("--cd-file requires a path")?)
+        }
+        None => None,
+    };
+    let verbose = has_flag(args, "-V") || has_flag(args, "--verbose");
+    let timings = has_flag(args, "--timings");
+    let absolute_dates = has_flag(args, "--absolute-dates");
+    let quiet = has_flag(args, "--quiet");
+    let no_input = has_flag(args, "--no-input");
+    let cd_file_value = args.iter().position(|a| a == "--cd-file").map(|i| i + 1);
+    let mut args: Vec<String> = args
+        .iter()
+        .enumerate()
+        .filter(|(i, _)| Some(*i) != cd_file_value)
+        .map(|(_, a)| a)
+        .filter(|a| {
+            !a.starts_with("--profile=")
+                && !a.starts_with("--escape=")
+                && !a.starts_with("--cd-file=")
+                && !matches!(
+                    a.as_str(),
+                    "--print0" | "--cd-file" | "-V" | "--verbose" | "--timings" | "--absolute-dates" | "--quiet"
+                        | "--no-input"
+                )
+        })
+        .cloned()
+        .collect();
+    // `goto --dry-run -r ...`: the command's own parsing finds it at the end
+    if args.len() > 2 && args[1] == "--dry-run" {
+        let flag = args.remove(1);
+        args.push(flag);
+    }
+
+    let command = parse_command(&args)?;
+    // Words after `--` (such as those `--complete` is given) aren't goto's
+    let dry_run = args.iter().take_while(|a| *a != "--").any(|a| a == "--dry-run");
+    if dry_run && command.dry_run().is_none() {
+        return Err(format!("--dry-run can't be used with {}", args[1]));
+    }
+    Ok(Args {
+        command,
+        profile,
+        path_format,
+        cd_file: cd_file.map(PathBuf::from),
+        verbose,
+        timings,
+        absolute_dates,
+        quiet,
+        no_input,
+    })
+}
+
+/// Parse the command and its options
+fn parse_command(args: &[String]) -> Result<Command, String> {
     if args.len() < 2 {
         return Err("No arguments provided".to_string());
     }
@@ -99,33 +474,137 @@ pub fn parse_args(args: &[String]) -> Result<Args, String> {
 
         "-v" | "--version" => Command::Version,
 
-        "--config" => Command::Config,
+        "--config" => Command::Config {
+            validate: has_flag(args, "--validate"),
+        },
+
+        "--profiles" => Command::Profiles,
 
         "-l" | "--list" => Command::List {
             sort: find_flag_value(args, "--sort="),
             filter: find_flag_value(args, "--filter="),
+            columns: find_flag_value(args, "--columns="),
+            group_by: find_flag_value(args, "--group-by="),
+            porcelain: has_flag(args, "--porcelain"),
+            wide: has_flag(args, "--wide"),
         },
 
         "-s" | "--stats" => Command::Stats,
 
+        "--random" => Command::Random {
+            filter: find_flag_value(args, "--filter="),
+            weighted: has_flag(args, "--weighted"),
+            dry_run: has_flag(args, "--dry-run"),
+        },
+
         "--list-aliases" | "--names-only" => Command::ListNames,
 
-        "--tags-raw" => Command::ListTagsRaw,
+        "--tags-raw" => Command::ListTagsRaw {
+            with_counts: has_flag(args, "--with-counts"),
+        },
+
+        "--complete-tag" => Command::CompleteTag {
+            prefix: args.get(2).filter(|a| !a.starts_with("--")).cloned().unwrap_or_default(),
+            with_counts: has_flag(args, "--with-counts"),
+        },
+
+        "--temp" => {
+            // `goto --temp -r <alias> ...`: a session-only registration
+            let rest: Vec<String> = args.iter().filter(|a| *a != "--temp").cloned().collect();
+            match parse_command(&rest)? {
+                Command::Register { name, path, tags, force, no_resolve, allow_missing, link: None, dry_run, .. } => {
+                    Command::Register {
+                        name,
+                        path,
+                        tags,
+                        force,
+                        no_resolve,
+                        temporary: true,
+                        allow_missing,
+                        link: None,
+                        dry_run,
+                    }
+                }
+                _ => return Err("Usage: goto --temp -r <alias> [directory]".to_string()),
+            }
+        }
 
         "-r" | "--register" => {
-            if args.len() < 4 {
-                return Err("Usage: goto -r <alias> <directory> [-t tags] [--force]".to_string());
+            if args.len() < 3 {
+                return Err(
+                    "Usage: goto -r <alias> [directory] [-t tags] [--force] [--no-resolve] [--temp] [--allow-missing]"
+                        .to_string(),
+                );
+            }
+            let link = find_flag_value(args, "--link=").or_else(|| find_space_separated_flag(args, "--link"));
+            let bad_link = link.as_deref().is_none_or(|l| l.is_empty() || l.starts_with('-'));
+            if (has_flag(args, "--link") || link.is_some()) && bad_link {
+                return Err("Usage: goto -r <alias> --link <alias>".to_string());
             }
             let tags = find_flag_value(args, "--tags=")
                 .or_else(|| find_space_separated_flag(args, "-t"))
                 .map(|t| t.split(',').map(String::from).collect::<Vec<_>>())
                 .unwrap_or_default();
-            let force = args.iter().any(|a| a == "--force" || a == "-f");
+            let force = has_force(args);
+            // Directory is optional and defaults to the current directory; it
+            // may come before or after the flags, but only once
+            let mut positional = Vec::new();
+            let mut rest = args[3..].iter();
+            while let Some(arg) = rest.next() {
+                match arg.as_str() {
+                    "-t" | "--link" => {
+                        rest.next();
+                    }
+                    "--force" | "-f" | "--no-resolve" | "--temp" | "--allow-missing" | "--dry-run" => {}
+                    a if a.starts_with("--tags=") || a.starts_with("--link=") => {}
+                    a if a.starts_with('-') => return Err(format!("Unknown option for -r: {}", a)),
+                    _ => positional.push(arg.clone()),
+                }
+            }
+            if let Some(extra) = positional.get(1) {
+                return Err(format!("Unexpected argument for -r: {} (one directory only)", extra));
+            }
+            let path = positional.pop().unwrap_or_else(|| ".".to_string());
             Command::Register {
                 name: args[2].clone(),
-                path: args[3].clone(),
+                path,
                 tags,
                 force,
+                no_resolve: has_flag(args, "--no-resolve"),
+                temporary: has_flag(args, "--temp"),
+                allow_missing: has_flag(args, "--allow-missing"),
+                link,
+                dry_run: has_flag(args, "--dry-run"),
+            }
+        }
+
+        "--clone" => {
+            if args.len() < 3 || args[2].starts_with('-') {
+                return Err("Usage: goto --clone <url> [alias] [-t tags]".to_string());
+            }
+            Command::Clone {
+                url: args[2].clone(),
+                alias: args.get(3).filter(|a| !a.starts_with('-')).cloned(),
+                tags: find_flag_value(args, "--tags=")
+                    .or_else(|| find_space_separated_flag(args, "-t"))
+                    .map(|t| t.split(',').map(String::from).collect())
+                    .unwrap_or_default(),
+            }
+        }
+
+        "--new" => {
+            let positional: Vec<&String> = args[2..].iter().filter(|a| !a.starts_with('-')).collect();
+            if positional.len() < 2 || args[2].starts_with('-') {
+                return Err("Usage: goto --new <alias> <path> [--template=<name>] [-t tags]".to_string());
+            }
+            Command::New {
+                name: positional[0].clone(),
+                path: positional[1].clone(),
+                template: find_flag_value(args, "--template="),
+                tags: find_flag_value(args, "--tags=")
+                    .or_else(|| find_space_separated_flag(args, "-t"))
+                    .map(|t| t.split(',').map(String::from).collect())
+                    .unwrap_or_default(),
             }
         }
 
@@ -135,6 +614,16 @@ pub fn parse_args(args: &[String]) -> Result<Args, String> {
             }
             Command::Unregister {
                 name: args[2].clone(),
+                dry_run: has_flag(args, "--dry-run"),
+            }
+        }
+
+        "--resolve-quiet" => {
+            if args.len() < 3 {
+                return Err("Usage: goto --resolve-quiet <alias>".to_string());
+            }
+            Command::ResolveQuiet {
+                query: args[2].clone(),
             }
         }
 
@@ -147,8 +636,95 @@ pub fn parse_args(args: &[String]) -> Result<Args, String> {
             }
         }
 
+        "--preview" => {
+            if args.len() < 3 {
+                return Err("Usage: goto --preview <alias>".to_string());
+            }
+            Command::Preview {
+                alias: args[2].clone(),
+            }
+        }
+
+        "--explain" => {
+            if args.len() < 3 {
+                return Err("Usage: goto --explain <query>".to_string());
+            }
+            Command::Explain {
+                query: args[2].clone(),
+            }
+        }
+
+        "--note" => {
+            if args.len() < 3 || args[2].starts_with('-') {
+                return Err("Usage: goto --note <alias>".to_string());
+            }
+            Command::Note {
+                alias: args[2].clone(),
+            }
+        }
+
+        "--open" | "--code" | "--edit-in" => {
+            if args.len() < 3 || args[2].starts_with('-') {
+                return Err(format!("Usage: goto {} <alias>", args[1]));
+            }
+            Command::Open {
+                alias: args[2].clone(),
+                opener: if args[1] == "--open" { Opener::FileManager } else { Opener::Editor },
+            }
+        }
+
+        "--env" => {
+            if args.len() < 3 || args[2].starts_with('-') {
+                return Err("Usage: goto --env <alias> [KEY=VALUE...] [--unset=KEY...]".to_string());
+            }
+            Command::Env {
+                alias: args[2].clone(),
+                assignments: args[3..].iter().filter(|a| !a.starts_with("--")).cloned().collect(),
+                unset: args[3..]
+                    .iter()
+                    .filter_map(|a| a.strip_prefix("--unset="))
+                    .map(String::from)
+                    .collect(),
+            }
+        }
+
+        "--where" => Command::Where {
+            cache: has_flag(args, "--cache"),
+        },
+
+        "--counter" => Command::Counter,
+
+        "--prompt-snippet" => match args.get(2) {
+            Some(shell) if !shell.starts_with('-') => Command::PromptSnippet { shell: shell.clone() },
+            _ => return Err("Usage: goto --prompt-snippet <bash|zsh|fish|starship>".to_string()),
+        },
+
+        "--search" => {
+            if args.len() < 3 || args[2].starts_with('-') {
+                return Err("Usage: goto --search <query> [--limit=N] [--json]".to_string());
+            }
+            let limit = match find_flag_value(args, "--limit=") {
+                Some(value) => Some(
+                    value
+                        .parse::<usize>()
+                        .map_err(|_| format!("Invalid limit: {}. Please provide a positive integer.", value))?,
+                ),
+                None => None,
+            };
+            Command::Search {
+                query: args[2].clone(),
+                limit,
+                json: has_flag(args, "--json"),
+                wide: has_flag(args, "--wide"),
+            }
+        }
+
+        "--check" => Command::Check,
+
+        "--duplicates" => Command::Duplicates,
+
         "-c" | "--cleanup" => Command::Cleanup {
-            dry_run: args.iter().any(|a| a == "--dry-run"),
+            dry_run: has_flag(args, "--dry-run"),
         },
 
         "-p" | "--push" => {
@@ -160,17 +736,67 @@ pub fn parse_args(args: &[String]) -> Result<Args, String> {
             }
         }
 
+        "--pick" => Command::Pick,
+
         "-o" | "--pop" => Command::Pop,
 
-        "-e" | "--export" => Command::Export,
+        "--stack" => Command::ShowStack {
+            all_sessions: has_flag(args, "--all-sessions"),
+        },
 
-        "--rename" => {
+        "-e" | "--export" => Command::Export {
+            output: find_flag_value(args, "--output="),
+            filter: find_flag_value(args, "--filter="),
+        },
+
+        "--export-shell-aliases" => Command::ExportShellAliases {
+            shell: find_flag_value(args, "--shell="),
+            output: find_flag_value(args, "--output="),
+            filter: find_flag_value(args, "--filter="),
+        },
+
+        "--report" => Command::Report {
+            output: find_flag_value(args, "--output="),
+            filter: find_flag_value(args, "--filter="),
+        },
+
+        "--ssh" => {
+            let positional: Vec<&String> = args[2..].iter().filter(|a| !a.starts_with('-')).collect();
+            let [host, alias] = positional[..] else {
+                return Err("Usage: goto --ssh <host> <alias> [--print]".to_string());
+            };
+            Command::Ssh {
+                host: host.clone(),
+                alias: alias.clone(),
+                print: has_flag(args, "--print"),
+            }
+        }
+
+        "--event" => match (args.get(2).map(String::as_str), args.get(3)) {
+            (Some("enter"), Some(alias)) => Command::Event {
+                enter: Some(alias.clone()),
+            },
+            (Some("leave"), None) => Command::Event { enter: None },
+            _ => return Err("Usage: goto --event enter <alias> | goto --event leave".to_string()),
+        },
+
+        "--ssh-import" => {
             if args.len() < 4 {
-                return Err("Usage: goto --rename <old-alias> <new-alias>".to_string());
+                return Err("Usage: goto --ssh-import <host> <file|->".to_string());
+            }
+            Command::SshImport {
+                host: args[2].clone(),
+                file: args[3].clone(),
+            }
+        }
+
+        "--rename" => {
+            if args.len() < 3 {
+                return Err("Usage: goto --rename <old-alias> [new-alias]".to_string());
             }
             Command::Rename {
                 old_name: args[2].clone(),
-                new_name: args[3].clone(),
+                new_name: args.get(3).cloned(),
             }
         }
 
@@ -178,7 +804,7 @@ pub fn parse_args(args: &[String]) -> Result<Args, String> {
             if args.len() < 4 {
                 return Err("Usage: goto --tag <alias> <tag> [--force]".to_string());
             }
-            let force = args.iter().any(|a| a == "--force" || a == "-f");
+            let force = has_force(args);
             Command::Tag {
                 alias: args[2].clone(),
                 tag: args[3].clone(),
@@ -200,8 +826,8 @@ pub fn parse_args(args: &[String]) -> Result<Args, String> {
             if args.len() < 4 {
                 return Err("Usage: goto --rename-tag <old-tag> <new-tag> [--dry-run] [--force]".to_string());
             }
-            let dry_run = args.iter().any(|a| a == "--dry-run");
-            let force = args.iter().any(|a| a == "--force" || a == "-f");
+            let dry_run = has_flag(args, "--dry-run");
+            let force = has_force(args);
             Command::RenameTag {
                 old_tag: args[2].clone(),
                 new_tag: args[3].clone(),
@@ -210,61 +836,240 @@ pub fn parse_args(args: &[String]) -> Result<Args, String> {
             }
         }
 
-        "-T" | "--tags" => Command::ListTags,
+        "--delete-tag" => {
+            if args.len() < 3 {
+                return Err("Usage: goto --delete-tag <tag> [--dry-run] [--force]".to_string());
+            }
+            Command::DeleteTag {
+                tag: args[2].clone(),
+                dry_run: has_flag(args, "--dry-run"),
+                force: has_force(args),
+            }
+        }
+
+        "--tag-all" => {
+            let usage = "Usage: goto --tag-all <tag> --filter=<expr> [--remove] [--dry-run] [--force]";
+            let tag = match args.get(2) {
+                Some(t) if !t.starts_with("--") => t.clone(),
+                _ => return Err(usage.to_string()),
+            };
+            let filter = find_flag_value(args, "--filter=").ok_or_else(|| usage.to_string())?;
+            Command::TagAll {
+                tag,
+                filter,
+                remove: has_flag(args, "--remove"),
+                dry_run: has_flag(args, "--dry-run"),
+                force: has_force(args),
+            }
+        }
+
+        "--rewrite-prefix" => match (args.get(2), args.get(3)) {
+            (Some(old), Some(new)) if !old.starts_with("--") && !new.starts_with("--") => Command::RewritePrefix {
+                old: old.clone(),
+                new: new.clone(),
+                dry_run: has_flag(args, "--dry-run"),
+                allow_missing: has_flag(args, "--allow-missing"),
+            },
+            _ => {
+                return Err(
+                    "Usage: goto --rewrite-prefix <old> <new> [--dry-run] [--allow-missing]".to_string(),
+                )
+            }
+        },
+
+        "-T" | "--tags" => Command::ListTags {
+            sort: find_flag_value(args, "--sort="),
+            json: has_flag(args, "--json"),
+        },
+
+        "--tag-info" => match args.get(2).filter(|a| !a.starts_with("--")) {
+            Some(tag) => Command::TagInfo {
+                tag: tag.clone(),
+                description: find_flag_value(args, "--description="),
+                color: find_flag_value(args, "--color="),
+            },
+            None => {
+                return Err("Usage: goto --tag-info <tag> [--description=<text>] [--color=<color>]".to_string())
+            }
+        },
 
         "-R" | "--recent" => {
             if args.len() >= 3 {
                 if let Ok(n) = args[2].parse::<usize>() {
-                    if n >= 1 && n <= 20 && args.len() == 3 {
-                        return Ok(Args {
-                            command: Command::Recent {
-                                count: None,
-                                navigate_to: Some(n),
-                            },
+                    if (1..=20).contains(&n) && args.len() == 3 {
+                        return Ok(Command::Recent {
+                            count: None,
+                            navigate_to: Some(n),
                         });
                     } else {
-                        return Ok(Args {
-                            command: Command::Recent {
-                                count: Some(n),
-                                navigate_to: None,
-                            },
+                        return Ok(Command::Recent {
+                            count: Some(n),
+                            navigate_to: None,
                         });
                     }
                 }
             }
             Command::Recent {
-                count: Some(10),
+                count: None,
                 navigate_to: None,
             }
         }
 
         "--recent-clear" => Command::RecentClear,
 
+        "--reset-stats" => {
+            let alias = args.get(2).filter(|a| !a.starts_with('-')).cloned();
+            if alias.is_none() && !has_flag(args, "--all") {
+                return Err("Usage: goto --reset-stats <alias> | --all".to_string());
+            }
+            Command::ResetStats { alias }
+        }
+
+        "--set-count" => {
+            if args.len() < 4 {
+                return Err("Usage: goto --set-count <alias> <n>".to_string());
+            }
+            let count = args[3]
+                .parse::<u64>()
+                .map_err(|_| format!("Invalid count: {}. Please provide a non-negative integer.", args[3]))?;
+            Command::SetCount {
+                alias: args[2].clone(),
+                count,
+            }
+        }
+
+        "--backups" => Command::Backups,
+
+        "--fsck" => Command::Fsck {
+            dry_run: has_flag(args, "--dry-run"),
+        },
+
+        "--restore" => {
+            if args.len() < 3 {
+                return Err("Usage: goto --restore <timestamp|latest> [--force]".to_string());
+            }
+            Command::Restore {
+                timestamp: args[2].clone(),
+                force: has_force(args),
+            }
+        }
+
         "-i" | "--import" => {
             if args.len() < 3 {
                 return Err(
-                    "Usage: goto --import <file> [--strategy=skip|overwrite|rename]".to_string(),
+                    "Usage: goto --import <file|-> [--strategy=skip|overwrite|rename]".to_string(),
                 );
             }
             let strategy_str = find_flag_value(args, "--strategy=").unwrap_or_else(|| "skip".to_string());
-            let strategy = ImportStrategy::from_str(&strategy_str)
-                .map_err(|e| e.to_string())?;
+            let strategy = ImportStrategy::from_str(&strategy_str)?;
             Command::Import {
                 file: args[2].clone(),
                 strategy,
+                force: has_force(args),
+                dry_run: has_flag(args, "--dry-run"),
+            }
+        }
+
+        "--diff" => {
+            // `-` (stdin) is a file, not an option
+            let files: Vec<&String> = args[2..].iter().filter(|a| *a == "-" || !a.starts_with('-')).collect();
+            match files[..] {
+                [old] => Command::Diff { old: old.clone(), new: None },
+                [old, new] => Command::Diff {
+                    old: old.clone(),
+                    new: Some(new.clone()),
+                },
+                _ => return Err("Usage: goto --diff <file> [<file>]".to_string()),
             }
         }
 
         "--install" => Command::Install {
             shell: find_flag_value(args, "--shell="),
-            skip_rc: args.iter().any(|a| a == "--skip-rc"),
-            dry_run: args.iter().any(|a| a == "--dry-run"),
+            skip_rc: has_flag(args, "--skip-rc"),
+            dry_run: has_flag(args, "--dry-run"),
+            watch_timer: has_flag(args, "--watch-timer"),
         },
 
-        "-U" | "--update" => Command::Update,
+        "--watch" => Command::Watch {
+            dir: args.get(2).filter(|a| !a.starts_with('-')).cloned(),
+        },
+
+        "--unwatch" => {
+            if args.len() < 3 {
+                return Err("Usage: goto --unwatch <directory>".to_string());
+            }
+            Command::Unwatch {
+                dir: args[2].clone(),
+            }
+        }
+
+        "--watch-scan" => Command::WatchScan,
+
+        "--suggestions" => Command::Suggestions,
+
+        "--learn-from-history" => {
+            let limit = match find_flag_value(args, "--limit=") {
+                Some(value) => Some(
+                    value
+                        .parse::<usize>()
+                        .map_err(|_| format!("Invalid limit: {}. Please provide a positive integer.", value))?,
+                ),
+                None => None,
+            };
+            Command::LearnFromHistory {
+                file: args.get(2).filter(|a| !a.starts_with('-')).cloned(),
+                limit,
+            }
+        }
+
+        "--review" => {
+            let criteria = match args.get(2).filter(|a| !a.starts_with('-')) {
+                Some(list) => Criterion::parse_list(list)?,
+                None => Criterion::DEFAULT.to_vec(),
+            };
+            Command::Review { criteria }
+        }
+
+        "--complete" => {
+            // Everything after `--` belongs to the line being completed
+            let rest = args.get(2..).unwrap_or_default();
+            let words = match rest.first() {
+                Some(first) if first == "--" => &rest[1..],
+                _ => rest,
+            };
+            Command::Complete {
+                words: words.to_vec(),
+            }
+        }
+
+        "--uninstall" => Command::Uninstall {
+            shell: find_flag_value(args, "--shell="),
+            purge: has_flag(args, "--purge"),
+            force: has_force(args),
+            dry_run: has_flag(args, "--dry-run"),
+        },
+
+        "-U" | "--update" => Command::Update {
+            version: find_flag_value(args, "--version="),
+        },
 
         "--check-update" => Command::CheckUpdate,
 
+        "--update-check-daemon" => Command::UpdateCheckDaemon,
+
+        "--bench-selftest" => Command::BenchSelftest {
+            max_ms: match find_flag_value(args, "--max-ms=") {
+                Some(value) => Some(
+                    value
+                        .parse()
+                        .map_err(|_| format!("Invalid --max-ms: {}. Please provide a number of milliseconds.", value))?,
+                ),
+                None => None,
+            },
+        },
+
+        "-" => Command::Previous,
+
         "--prune-snooze" => {
             if args.len() < 3 {
                 return Err("Usage: goto --prune-snooze <days>".to_string());
@@ -282,6 +1087,9 @@ pub fn parse_args(args: &[String]) -> Result<Args, String> {
             if arg.starts_with('-') {
                 return Err(format!("Unknown option: {}", arg));
             }
+            if let Some(index) = arg.strip_prefix('%').and_then(|n| n.parse().ok()) {
+                return Ok(Command::NavigateListed { index });
+            }
             // Default action: navigate to alias
             Command::Navigate {
                 alias: arg.clone(),
@@ -289,7 +1097,17 @@ pub fn parse_args(args: &[String]) -> Result<Args, String> {
         }
     };
 
-    Ok(Args { command })
+    Ok(command)
+}
+
+/// Whether a bare flag (e.g. "--dry-run") is present
+fn has_flag(args: &[String], flag: &str) -> bool {
+    args.iter().any(|a| a == flag)
+}
+
+/// Whether `--force` or its short form `-f` is present
+fn has_force(args: &[String]) -> bool {
+    has_flag(args, "--force") || has_flag(args, "-f")
 }
 
 /// Find a flag value with the given prefix (e.g., "--sort=alpha")
@@ -320,37 +1138,114 @@ pub fn print_help() {
 
 Usage:
   goto <alias>                    Navigate to the directory
-  goto -r <alias> <directory>     Register a new alias
+  goto -                          Return to the previous directory
+  goto / goto --pick              Pick an alias (fzf or built-in, see general.picker)
+  goto -r <alias> [directory]     Register a new alias (default: current dir)
   goto -r <alias> <dir> -t tags   Register with tags (comma-separated)
   goto -r <alias> <dir> --force   Skip confirmation for new tags
+  goto -r <alias> <dir> --no-resolve  Keep symlinks in the stored path
+  goto --temp -r <alias> [dir]    Register for this shell session only
+  goto -r <alias> <dir> --allow-missing  Register a directory that will exist later
+  goto -r <alias> --link <alias>  Register (or repoint) an alias standing for another
   goto -u <alias>                 Unregister an alias
+  goto --clone <url> [alias]      Clone under projects.root, register and cd
+  goto --new <alias> <path> --template=<name>  Create from a template, register and cd
   goto -l                         List all aliases
   goto -l --sort=<order>          List aliases with sorting
-  goto -l --filter=<tag>          List aliases with tag
+  goto -l --filter=<expr>         List aliases matching a filter (work, tag:a,b, 'proj-*')
+  goto -l --columns=<cols>        Choose columns (index,name,path,tags,count,last_used,created)
+  goto -l --group-by=<key>        One table per tag or dir-prefix
+  goto -l --porcelain             Stable tab-separated output for scripts
+  goto -l --wide                  Show long paths whole instead of fitting the terminal
+  goto %<N>                       Navigate to row N of this shell's last -l
+  goto --random                   Navigate to a random alias (--filter=<expr>, --weighted, --dry-run)
   goto -x <alias>                 Expand alias to path
+  goto --resolve-quiet <alias>    Print an alias's path, or exit 1 silently (cd fallback)
+  goto --where                    Show the alias containing the current dir
+  goto --where --quiet --cache    Same, silent outside aliases and cached (for prompts)
+  goto --counter                  Count navigations in this shell session (for status bars)
+  goto --prompt-snippet <shell>   Print a prompt snippet (bash, zsh, fish, starship)
+  goto --search <query>           Search names, paths, tags and notes (--limit=N, --json, --wide)
+  goto --preview <alias>          Show an alias's git branch and contents
+  goto --explain <query>          Show how a query would resolve (nothing recorded)
+  goto --note <alias>             Edit an alias's note in $EDITOR
+  goto --open <alias>             Open an alias's directory in the file manager
+  goto --code <alias>             Open an alias's directory in an editor (or --edit-in)
+  goto --env <alias> KEY=VALUE    Export KEY=VALUE when entering alias
+  goto --env <alias> --unset=KEY  Remove an exported variable
+  goto --check                    Audit aliases (non-zero exit on problems)
+  goto --duplicates               List aliases with the same or nested paths
   goto -c                         Cleanup invalid aliases
   goto -c --dry-run               List invalid aliases (don't remove)
   goto -p <alias>                 Push current dir, goto alias
   goto -o                         Pop and return to directory
+  goto --stack [--all-sessions]   Show the directory stack
   goto --rename <old> <new>       Rename an alias
+  goto --rename <fuzzy>           Pick an alias to rename and prompt for the new name
+  goto --rewrite-prefix <old> <new>  Move alias paths to a new root (--dry-run, --allow-missing)
   goto --tag <alias> <tag>        Add tag to alias
   goto --tag <alias> <tag> -f     Add tag without confirmation
   goto --untag <alias> <tag>      Remove tag from alias
   goto --rename-tag <old> <new>   Rename tag across all aliases
   goto --rename-tag old new -f    Rename without confirmation
   goto --rename-tag old new --dry-run  Preview changes only
-  goto -T / --tags                List all tags with counts
+  goto --delete-tag <tag>         Delete tag from all aliases
+  goto --tag-all <tag> --filter=<expr>  Tag every alias matching a filter
+  goto --tag-all old --filter=tag:x --remove  Remove a tag in bulk
+  goto -T / --tags                List all tags with counts and usage (--json)
+  goto --tags --sort=<order>      Sort tags by name, count, usage or recent
+  goto --tag-info <tag>           Show a tag's description and its aliases by usage
+  goto --tag-info <tag> --description=<text>  Describe a tag (empty clears)
+       [--color=<color>]
   goto -s / --stats               Show usage statistics
-  goto -R / --recent              List recently visited directories
+  goto --event enter <alias>|leave  Report entering or leaving an alias (privacy.track_time)
+  goto -R / --recent              Pick a recent directory (list when piped)
   goto -R <N> / --recent <N>      Navigate to Nth most recent
   goto --recent-clear             Clear recent history
+  goto --reset-stats <alias>|--all  Zero use count and last used
+  goto --set-count <alias> <n>    Set an alias's use count
   goto -e / --export              Export aliases to TOML (stdout)
-  goto -i / --import <file>       Import aliases from TOML file
+  goto --export --output=<file>   Export aliases to a file
+  goto --export --filter=<expr>   Export only aliases matching a filter
+  goto --export-shell-aliases     Export plain shell aliases (alias dev='cd ...')
+       [--shell=bash|zsh|fish] [--filter=<expr>] [--output=<file>]
+  goto --report --output=<file>   Write an HTML report of the aliases [--filter=<expr>]
+  goto -i / --import <file>       Import aliases from TOML file (- for stdin)
+  goto --diff <file> [<file>]     Compare two export files (one: the database with the file)
+  goto --import <file> -f         Import without the preview and confirmation
+  goto --ssh <host> <alias>       Open a shell in a remote alias's directory (--print shows the command)
+  goto --ssh-import <host> <file> Store a host's --export output for --ssh (- for stdin)
+  goto --backups                  List automatic database backups
+  goto --restore <timestamp>      Restore a backup (or 'latest')
+  goto --fsck [--dry-run]         Check the database and salvage it if damaged
   goto --config                   Show current configuration
+  goto --config --validate        Check config.toml for unknown keys and invalid values
+  goto --profiles                 List profiles (* marks the active one)
+  goto --profile=<name> ...       Use a profile's config and aliases (or GOTO_PROFILE)
+  goto --print0 ...               End printed paths with NUL instead of newline
+  goto --escape=shell|fish ...    Print paths quoted for eval (used by the shell functions)
+  goto --cd-file <path> ...       Write the directory to enter to a file (or GOTO_CD_FILE)
+  goto -V / --verbose ...         Timestamped debug log on stderr (or GOTO_LOG=debug)
+  goto --timings ...              Report how long config, database, matching and save took
+  goto --absolute-dates ...       Show dates instead of "3 days ago" in -l, --recent, --stats
+  goto --quiet ...                Only print results and errors (no confirmations or hints)
+  goto --no-input ...             Never prompt; confirmations take their default answer
+  goto ... --dry-run              Preview what -r, -u, --import, -c, --rewrite-prefix and the
+       tag commands would change, without saving
   goto --install                  Install shell integration
+  goto --uninstall [--purge]      Remove shell integration (--purge: also delete data)
   goto -U / --update              Update goto to latest version
+  goto -U --version=<ver>         Install a specific release (pin/downgrade)
   goto --check-update             Check for available updates
   goto --prune-snooze <days>      Snooze stale alias notification for N days
+  goto --watch [directory]        Suggest aliases for new subdirectories (no arg: list)
+  goto --unwatch <directory>      Stop watching a directory
+  goto --watch-scan               Look for new directories now (for cron/timers)
+  goto --complete -- <words...>   Completion candidates for a command line (for shells)
+  goto --suggestions              Review suggested aliases for new directories
+  goto --learn-from-history       Offer directories often cd'ed to in shell history as aliases
+       [file] [--limit=N]
+  goto --review [criteria]        Keep, edit, tag, archive or delete aliases one at a time
   goto -v                         Show version
   goto -h                         Show this help
 
@@ -358,9 +1253,10 @@ Sort options (use with -l/--list):
   --sort=alpha                    Sort alphabetically (default)
   --sort=usage                    Sort by use count (most used first)
   --sort=recent                   Sort by last used (most recent first)
+  --sort=status                   Sort by path status (missing, broken, unreadable first)
 
 Filter options (use with -l/--list):
-  --filter=<tag>                  Show only aliases with tag
+  --filter=<expr>                 Show only matching aliases (same syntax as --export)
 
 Import strategies (use with -i/--import):
   --strategy=skip                 Skip existing aliases (default)
@@ -368,9 +1264,10 @@ Import strategies (use with -i/--import):
   --strategy=rename               Rename conflicting aliases (add suffix)
 
 Install options (use with --install):
-  --shell=bash|zsh|fish           Shell to configure (auto-detects from $SHELL)
+  --shell=<shell>                 bash, zsh, fish, nushell, powershell (default: $SHELL)
   --skip-rc                       Don't modify shell rc file
   --dry-run                       Show what would be done without making changes
+  --watch-timer                   Also schedule --watch-scan hourly (systemd or cron)
 
 Configuration (edit ~/.config/goto/config.toml):
   table_style = "unicode"         Table border style (unicode/ascii/minimal)
@@ -386,6 +1283,7 @@ Examples:
   goto dev                        Navigate to ~/Development
   goto -l --sort=usage            List aliases by usage
   goto -l --filter=work           List aliases tagged 'work'
+  goto %3                         Navigate to the 3rd alias of that list
   goto --tag dev golang           Add 'golang' tag to 'dev'
   goto --untag dev golang         Remove 'golang' tag from 'dev'
   goto -T                         List all tags with counts
@@ -438,11 +1336,42 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_parse_navigate_listed() {
+        let result = parse_args(&args(&["goto", "%3"])).unwrap();
+        assert!(matches!(result.command, Command::NavigateListed { index: 3 }));
+
+        // Not a number: an ordinary (probably unknown) alias
+        let result = parse_args(&args(&["goto", "%x"])).unwrap();
+        assert!(matches!(result.command, Command::Navigate { .. }));
+    }
+
+    #[test]
+    fn test_parse_random() {
+        let result = parse_args(&args(&["goto", "--random", "--filter=side", "--weighted"])).unwrap();
+        if let Command::Random { filter, weighted, dry_run } = result.command {
+            assert_eq!(filter.as_deref(), Some("side"));
+            assert!(weighted);
+            assert!(!dry_run);
+        } else {
+            panic!("Expected Random command");
+        }
+        let result = parse_args(&args(&["goto", "--random", "--dry-run"])).unwrap();
+        assert!(matches!(result.command, Command::Random { filter: None, weighted: false, dry_run: true }));
+    }
+
+    #[test]
+    fn test_parse_resolve_quiet() {
+        let result = parse_args(&args(&["goto", "--resolve-quiet", "proj"])).unwrap();
+        assert!(matches!(result.command, Command::ResolveQuiet { query } if query == "proj"));
+        assert!(parse_args(&args(&["goto", "--resolve-quiet"])).unwrap_err().contains("Usage:"));
+    }
+
     #[test]
     fn test_parse_register() {
         let result = parse_args(&args(&["goto", "-r", "dev", "/path/to/dev"]));
         assert!(result.is_ok());
-        if let Command::Register { name, path, tags, force } = result.unwrap().command {
+        if let Command::Register { name, path, tags, force, .. } = result.unwrap().command {
             assert_eq!(name, "dev");
             assert_eq!(path, "/path/to/dev");
             assert!(tags.is_empty());
@@ -452,11 +1381,86 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_parse_register_flag_before_directory() {
+        for argv in [
+            &["goto", "-r", "foo", "-t", "work", "/tmp/zz"][..],
+            &["goto", "-r", "foo", "--force", "/tmp/zz", "--tags=work"][..],
+        ] {
+            if let Command::Register { name, path, tags, .. } = parse_args(&args(argv)).unwrap().command {
+                assert_eq!(name, "foo");
+                assert_eq!(path, "/tmp/zz");
+                assert_eq!(tags, vec!["work"]);
+            } else {
+                panic!("Expected Register command");
+            }
+        }
+
+        let parsed = parse_args(&args(&["goto", "-r", "foo", "-t", "work"])).unwrap();
+        assert!(matches!(parsed.command, Command::Register { ref path, .. } if path == "."));
+
+        let err = parse_args(&args(&["goto", "-r", "foo", "/tmp/a", "/tmp/b"])).unwrap_err();
+        assert!(err.contains("/tmp/b"));
+        let err = parse_args(&args(&["goto", "-r", "foo", "--bogus", "/tmp/a"])).unwrap_err();
+        assert!(err.contains("--bogus"));
+    }
+
+    #[test]
+    fn test_parse_register_temporary() {
+        for argv in [
+            &["goto", "--temp", "-r", "scratch", "/tmp/x"][..],
+            &["goto", "-r", "scratch", "/tmp/x", "--temp"][..],
+        ] {
+            if let Command::Register { name, path, temporary, .. } = parse_args(&args(argv)).unwrap().command {
+                assert_eq!(name, "scratch");
+                assert_eq!(path, "/tmp/x");
+                assert!(temporary);
+            } else {
+                panic!("Expected Register command");
+            }
+        }
+
+        assert!(parse_args(&args(&["goto", "--temp", "-l"])).is_err());
+    }
+
+    #[test]
+    fn test_parse_register_link() {
+        let parsed = parse_args(&args(&["goto", "-r", "current", "--link", "work-projectA"])).unwrap();
+        assert!(matches!(parsed.command, Command::Register { link: Some(ref l), .. } if l == "work-projectA"));
+
+        let parsed = parse_args(&args(&["goto", "-r", "current", "--link=work-projectA", "-t", "now"])).unwrap();
+        if let Command::Register { link, tags, .. } = parsed.command {
+            assert_eq!(link.as_deref(), Some("work-projectA"));
+            assert_eq!(tags, vec!["now"]);
+        } else {
+            panic!("Expected Register command");
+        }
+
+        assert!(parse_args(&args(&["goto", "-r", "current", "--link"])).unwrap_err().contains("--link"));
+        assert!(parse_args(&args(&["goto", "--temp", "-r", "current", "--link", "x"])).is_err());
+        let parsed = parse_args(&args(&["goto", "-r", "mnt", "/mnt/data"])).unwrap();
+        assert!(matches!(parsed.command, Command::Register { link: None, .. }));
+    }
+
+    #[test]
+    fn test_parse_register_allow_missing() {
+        let parsed = parse_args(&args(&["goto", "-r", "mnt", "/mnt/data", "--allow-missing"])).unwrap();
+        if let Command::Register { path, allow_missing, .. } = parsed.command {
+            assert_eq!(path, "/mnt/data");
+            assert!(allow_missing);
+        } else {
+            panic!("Expected Register command");
+        }
+
+        let parsed = parse_args(&args(&["goto", "-r", "mnt", "/mnt/data"])).unwrap();
+        assert!(matches!(parsed.command, Command::Register { allow_missing: false, .. }));
+    }
+
     #[test]
     fn test_parse_register_with_tags() {
         let result = parse_args(&args(&["goto", "-r", "dev", "/path", "--tags=work,rust"]));
         assert!(result.is_ok());
-        if let Command::Register { name, path, tags, force } = result.unwrap().command {
+        if let Command::Register { name, path, tags, force, .. } = result.unwrap().command {
             assert_eq!(name, "dev");
             assert_eq!(path, "/path");
             assert_eq!(tags, vec!["work", "rust"]);
@@ -470,7 +1474,7 @@ mod tests {
     fn test_parse_register_with_force() {
         let result = parse_args(&args(&["goto", "-r", "dev", "/path", "--force"]));
         assert!(result.is_ok());
-        if let Command::Register { name, path, tags, force } = result.unwrap().command {
+        if let Command::Register { name, path, tags, force, .. } = result.unwrap().command {
             assert_eq!(name, "dev");
             assert_eq!(path, "/path");
             assert!(tags.is_empty());
@@ -480,11 +1484,18 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_command_force() {
+        assert!(parse_args(&args(&["goto", "--delete-tag", "old", "-f"])).unwrap().command.force());
+        assert!(!parse_args(&args(&["goto", "--delete-tag", "old"])).unwrap().command.force());
+        assert!(!parse_args(&args(&["goto", "-l"])).unwrap().command.force());
+    }
+
     #[test]
     fn test_parse_register_with_short_force() {
         let result = parse_args(&args(&["goto", "-r", "dev", "/path", "-f"]));
         assert!(result.is_ok());
-        if let Command::Register { name, path, tags, force } = result.unwrap().command {
+        if let Command::Register { name, path, tags, force, .. } = result.unwrap().command {
             assert_eq!(name, "dev");
             assert_eq!(path, "/path");
             assert!(tags.is_empty());
@@ -498,7 +1509,7 @@ mod tests {
     fn test_parse_register_with_tags_and_force() {
         let result = parse_args(&args(&["goto", "-r", "dev", "/path", "--tags=work", "--force"]));
         assert!(result.is_ok());
-        if let Command::Register { name, path, tags, force } = result.unwrap().command {
+        if let Command::Register { name, path, tags, force, .. } = result.unwrap().command {
             assert_eq!(name, "dev");
             assert_eq!(path, "/path");
             assert_eq!(tags, vec!["work"]);
@@ -534,14 +1545,64 @@ mod tests {
     fn test_parse_list_with_options() {
         let result = parse_args(&args(&["goto", "-l", "--sort=usage", "--filter=work"]));
         assert!(result.is_ok());
-        if let Command::List { sort, filter } = result.unwrap().command {
+        if let Command::List { sort, filter, columns, group_by, porcelain, wide } = result.unwrap().command {
             assert_eq!(sort, Some("usage".to_string()));
             assert_eq!(filter, Some("work".to_string()));
+            assert_eq!(columns, None);
+            assert_eq!(group_by, None);
+            assert!(!porcelain);
+            assert!(!wide);
+        } else {
+            panic!("Expected List command");
+        }
+    }
+
+    #[test]
+    fn test_parse_list_porcelain() {
+        let result = parse_args(&args(&["goto", "--list", "--porcelain"]));
+        assert!(result.is_ok());
+        assert!(matches!(
+            result.unwrap().command,
+            Command::List { porcelain: true, .. }
+        ));
+    }
+
+    #[test]
+    fn test_parse_list_wide() {
+        let result = parse_args(&args(&["goto", "-l", "--wide"])).unwrap();
+        assert!(matches!(result.command, Command::List { wide: true, .. }));
+        let result = parse_args(&args(&["goto", "--search", "api", "--wide"])).unwrap();
+        assert!(matches!(result.command, Command::Search { wide: true, .. }));
+    }
+
+    #[test]
+    fn test_parse_list_with_columns() {
+        let result = parse_args(&args(&["goto", "-l", "--columns=name,count"]));
+        assert!(result.is_ok());
+        if let Command::List { columns, .. } = result.unwrap().command {
+            assert_eq!(columns, Some("name,count".to_string()));
         } else {
             panic!("Expected List command");
         }
     }
 
+    #[test]
+    fn test_parse_list_group_by() {
+        let result = parse_args(&args(&["goto", "-l", "--group-by=dir-prefix"]));
+        if let Command::List { group_by, .. } = result.unwrap().command {
+            assert_eq!(group_by, Some("dir-prefix".to_string()));
+        } else {
+            panic!("Expected List command");
+        }
+    }
+
+    #[test]
+    fn test_parse_previous() {
+        let result = parse_args(&args(&["goto", "-"]));
+        assert!(result.is_ok());
+        assert!(matches!(result.unwrap().command, Command::Previous));
+    }
+
     #[test]
     fn test_parse_unknown_option() {
         let result = parse_args(&args(&["goto", "--unknown"]));
@@ -557,11 +1618,37 @@ mod tests {
 
     #[test]
     fn test_parse_register_missing_args() {
-        let result = parse_args(&args(&["goto", "-r", "dev"]));
+        let result = parse_args(&args(&["goto", "-r"]));
         assert!(result.is_err());
         assert!(result.unwrap_err().contains("Usage:"));
     }
 
+    #[test]
+    fn test_parse_register_defaults_to_current_dir() {
+        let result = parse_args(&args(&["goto", "-r", "dev"]));
+        assert!(result.is_ok());
+        if let Command::Register { name, path, .. } = result.unwrap().command {
+            assert_eq!(name, "dev");
+            assert_eq!(path, ".");
+        } else {
+            panic!("Expected Register command");
+        }
+    }
+
+    #[test]
+    fn test_parse_register_defaults_to_current_dir_with_tags() {
+        let result = parse_args(&args(&["goto", "-r", "dev", "-t", "work", "-f"]));
+        assert!(result.is_ok());
+        if let Command::Register { name, path, tags, force, .. } = result.unwrap().command {
+            assert_eq!(name, "dev");
+            assert_eq!(path, ".");
+            assert_eq!(tags, vec!["work"]);
+            assert!(force);
+        } else {
+            panic!("Expected Register command");
+        }
+    }
+
     // Stack commands tests
     #[test]
     fn test_parse_push_short() {
@@ -606,6 +1693,21 @@ mod tests {
         assert!(matches!(result.unwrap().command, Command::Pop));
     }
 
+    #[test]
+    fn test_parse_show_stack() {
+        let result = parse_args(&args(&["goto", "--stack"]));
+        assert!(matches!(
+            result.unwrap().command,
+            Command::ShowStack { all_sessions: false }
+        ));
+
+        let result = parse_args(&args(&["goto", "--stack", "--all-sessions"]));
+        assert!(matches!(
+            result.unwrap().command,
+            Command::ShowStack { all_sessions: true }
+        ));
+    }
+
     // Tag commands tests
     #[test]
     fn test_parse_tag() {
@@ -676,14 +1778,73 @@ mod tests {
     fn test_parse_tags() {
         let result = parse_args(&args(&["goto", "--tags"]));
         assert!(result.is_ok());
-        assert!(matches!(result.unwrap().command, Command::ListTags));
+        assert!(matches!(result.unwrap().command, Command::ListTags { sort: None, json: false }));
+
+        let result = parse_args(&args(&["goto", "--tags", "--sort=count", "--json"])).unwrap();
+        if let Command::ListTags { sort, json } = result.command {
+            assert_eq!(sort.as_deref(), Some("count"));
+            assert!(json);
+        } else {
+            panic!("Expected ListTags command");
+        }
+    }
+
+    #[test]
+    fn test_parse_dry_run() {
+        for argv in [
+            &["goto", "-r", "proj", "/tmp", "--dry-run"][..],
+            &["goto", "--dry-run", "-u", "proj"],
+            &["goto", "--import", "file.toml", "--dry-run"],
+        ] {
+            assert_eq!(parse_args(&args(argv)).unwrap().command.dry_run(), Some(true));
+        }
+        assert_eq!(parse_args(&args(&["goto", "-u", "proj"])).unwrap().command.dry_run(), Some(false));
+
+        // Commands without a preview refuse it instead of making the change
+        let err = parse_args(&args(&["goto", "--rename", "a", "b", "--dry-run"])).unwrap_err();
+        assert!(err.contains("--dry-run can't be used with --rename"));
+        // Unless it's part of a line being completed
+        assert!(parse_args(&args(&["goto", "--complete", "--", "-r", "--dry-run"])).is_ok());
+    }
+
+    #[test]
+    fn test_parse_tag_info() {
+        let result = parse_args(&args(&["goto", "--tag-info", "work"])).unwrap();
+        assert!(matches!(
+            result.command,
+            Command::TagInfo { ref tag, description: None, color: None } if tag == "work"
+        ));
+
+        let result = parse_args(&args(&["goto", "--tag-info", "work", "--description=Day job", "--color="])).unwrap();
+        if let Command::TagInfo { description, color, .. } = result.command {
+            assert_eq!(description.as_deref(), Some("Day job"));
+            assert_eq!(color.as_deref(), Some(""));
+        } else {
+            panic!("Expected TagInfo command");
+        }
+
+        assert!(parse_args(&args(&["goto", "--tag-info"])).is_err());
+        assert!(parse_args(&args(&["goto", "--tag-info", "--color=red"])).is_err());
     }
 
     #[test]
     fn test_parse_tags_raw() {
         let result = parse_args(&args(&["goto", "--tags-raw"]));
         assert!(result.is_ok());
-        assert!(matches!(result.unwrap().command, Command::ListTagsRaw));
+        assert!(matches!(result.unwrap().command, Command::ListTagsRaw { with_counts: false }));
+
+        let result = parse_args(&args(&["goto", "--tags-raw", "--with-counts"])).unwrap();
+        assert!(matches!(result.command, Command::ListTagsRaw { with_counts: true }));
+    }
+
+    #[test]
+    fn test_parse_complete_tag() {
+        let result = parse_args(&args(&["goto", "--complete-tag", "wo"])).unwrap();
+        assert!(matches!(result.command, Command::CompleteTag { ref prefix, with_counts: false } if prefix == "wo"));
+
+        // An empty prefix (nothing typed yet) completes every tag
+        let result = parse_args(&args(&["goto", "--complete-tag", "--with-counts"])).unwrap();
+        assert!(matches!(result.command, Command::CompleteTag { ref prefix, with_counts: true } if prefix.is_empty()));
     }
 
     // Stats and recent commands tests
@@ -699,7 +1860,7 @@ mod tests {
         let result = parse_args(&args(&["goto", "--recent"]));
         assert!(result.is_ok());
         if let Command::Recent { count, navigate_to } = result.unwrap().command {
-            assert_eq!(count, Some(10));
+            assert_eq!(count, None);
             assert_eq!(navigate_to, None);
         } else {
             panic!("Expected Recent command");
@@ -738,32 +1899,184 @@ mod tests {
         assert!(matches!(result.unwrap().command, Command::RecentClear));
     }
 
-    // Rename command tests
     #[test]
-    fn test_parse_rename() {
-        let result = parse_args(&args(&["goto", "--rename", "old", "new"]));
-        assert!(result.is_ok());
-        if let Command::Rename { old_name, new_name } = result.unwrap().command {
-            assert_eq!(old_name, "old");
-            assert_eq!(new_name, "new");
+    fn test_parse_reset_stats() {
+        let result = parse_args(&args(&["goto", "--reset-stats", "proj"])).unwrap();
+        assert!(matches!(result.command, Command::ResetStats { alias: Some(ref a) } if a == "proj"));
+
+        let result = parse_args(&args(&["goto", "--reset-stats", "--all"])).unwrap();
+        assert!(matches!(result.command, Command::ResetStats { alias: None }));
+
+        assert!(parse_args(&args(&["goto", "--reset-stats"])).is_err());
+    }
+
+    #[test]
+    fn test_parse_set_count() {
+        let result = parse_args(&args(&["goto", "--set-count", "proj", "42"])).unwrap();
+        assert!(matches!(result.command, Command::SetCount { ref alias, count: 42 } if alias == "proj"));
+
+        assert!(parse_args(&args(&["goto", "--set-count", "proj"])).is_err());
+        let err = parse_args(&args(&["goto", "--set-count", "proj", "-1"])).unwrap_err();
+        assert!(err.contains("Invalid count"));
+    }
+
+    // Rename command tests
+    #[test]
+    fn test_parse_rename() {
+        let result = parse_args(&args(&["goto", "--rename", "old", "new"]));
+        assert!(result.is_ok());
+        if let Command::Rename { old_name, new_name } = result.unwrap().command {
+            assert_eq!(old_name, "old");
+            assert_eq!(new_name.as_deref(), Some("new"));
+        } else {
+            panic!("Expected Rename command");
+        }
+    }
+
+    #[test]
+    fn test_parse_rename_missing_args() {
+        let result = parse_args(&args(&["goto", "--rename"]));
+        assert!(result.is_err());
+        assert!(result.unwrap_err().contains("Usage:"));
+    }
+
+    #[test]
+    fn test_parse_rename_single_argument_is_interactive() {
+        let result = parse_args(&args(&["goto", "--rename", "proj"])).unwrap();
+        assert!(matches!(result.command, Command::Rename { ref old_name, new_name: None } if old_name == "proj"));
+    }
+
+    // Config command tests
+    #[test]
+    fn test_parse_config() {
+        let result = parse_args(&args(&["goto", "--config"]));
+        assert!(result.is_ok());
+        assert!(matches!(result.unwrap().command, Command::Config { validate: false }));
+
+        let result = parse_args(&args(&["goto", "--config", "--validate"])).unwrap();
+        assert!(matches!(result.command, Command::Config { validate: true }));
+    }
+
+    #[test]
+    fn test_parse_profile_anywhere() {
+        let result = parse_args(&args(&["goto", "--profile=work", "-l"])).unwrap();
+        assert_eq!(result.profile.as_deref(), Some("work"));
+        assert!(matches!(result.command, Command::List { .. }));
+
+        let result = parse_args(&args(&["goto", "proj", "--profile=home"])).unwrap();
+        assert_eq!(result.profile.as_deref(), Some("home"));
+        if let Command::Navigate { alias } = result.command {
+            assert_eq!(alias, "proj");
+        } else {
+            panic!("Expected Navigate command");
+        }
+
+        let result = parse_args(&args(&["goto", "--profiles"])).unwrap();
+        assert!(result.profile.is_none());
+        assert!(matches!(result.command, Command::Profiles));
+    }
+
+    #[test]
+    fn test_parse_verbose_anywhere() {
+        let result = parse_args(&args(&["goto", "-V", "proj"])).unwrap();
+        assert!(result.verbose);
+        assert!(matches!(result.command, Command::Navigate { .. }));
+
+        let result = parse_args(&args(&["goto", "-l", "--verbose"])).unwrap();
+        assert!(result.verbose);
+        assert!(matches!(result.command, Command::List { .. }));
+
+        // -v stays the version flag
+        let result = parse_args(&args(&["goto", "-v"])).unwrap();
+        assert!(!result.verbose);
+        assert!(matches!(result.command, Command::Version));
+    }
+
+    #[test]
+    fn test_parse_timings() {
+        let result = parse_args(&args(&["goto", "proj", "--timings"])).unwrap();
+        assert!(result.timings);
+        assert!(matches!(result.command, Command::Navigate { ref alias } if alias == "proj"));
+        assert!(!parse_args(&args(&["goto", "proj"])).unwrap().timings);
+    }
+
+    #[test]
+    fn test_needs_database() {
+        let needs = |argv: &[&str]| parse_args(&args(argv)).unwrap().command.needs_database();
+        assert!(!needs(&["goto", "-v"]));
+        assert!(!needs(&["goto", "--help"]));
+        assert!(!needs(&["goto", "--pop"]));
+        assert!(!needs(&["goto", "-"]));
+        assert!(needs(&["goto", "-l"]));
+        assert!(needs(&["goto", "proj"]));
+        assert!(needs(&["goto", "-p", "proj"]));
+    }
+
+    #[test]
+    fn test_parse_path_format() {
+        let result = parse_args(&args(&["goto", "proj"])).unwrap();
+        assert_eq!(result.path_format, PathFormat::Line);
+
+        let result = parse_args(&args(&["goto", "--print0", "-x", "proj"])).unwrap();
+        assert_eq!(result.path_format, PathFormat::Nul);
+        assert!(matches!(result.command, Command::Expand { .. }));
+
+        let result = parse_args(&args(&["goto", "proj", "--escape=shell"])).unwrap();
+        assert_eq!(result.path_format, PathFormat::Shell);
+        if let Command::Navigate { alias } = result.command {
+            assert_eq!(alias, "proj");
+        } else {
+            panic!("Expected Navigate command");
+        }
+
+        assert!(parse_args(&args(&["goto", "--escape=cmd", "proj"])).is_err());
+    }
+
+    #[test]
+    fn test_parse_cd_file() {
+        let result = parse_args(&args(&["goto", "proj"])).unwrap();
+        assert!(result.cd_file.is_none());
+
+        let result = parse_args(&args(&["goto", "--cd-file", "/tmp/cd", "proj"])).unwrap();
+        assert_eq!(result.cd_file, Some(PathBuf::from("/tmp/cd")));
+        if let Command::Navigate { alias } = result.command {
+            assert_eq!(alias, "proj");
         } else {
-            panic!("Expected Rename command");
+            panic!("Expected Navigate command");
         }
+
+        let result = parse_args(&args(&["goto", "-", "--cd-file=/tmp/cd"])).unwrap();
+        assert_eq!(result.cd_file, Some(PathBuf::from("/tmp/cd")));
+        assert!(matches!(result.command, Command::Previous));
+
+        assert!(parse_args(&args(&["goto", "proj", "--cd-file"])).is_err());
     }
 
     #[test]
-    fn test_parse_rename_missing_args() {
-        let result = parse_args(&args(&["goto", "--rename", "old"]));
-        assert!(result.is_err());
-        assert!(result.unwrap_err().contains("Usage:"));
+    fn test_parse_absolute_dates() {
+        let result = parse_args(&args(&["goto", "--recent", "--absolute-dates"])).unwrap();
+        assert!(result.absolute_dates);
+        assert!(matches!(result.command, Command::Recent { .. }));
+
+        assert!(!parse_args(&args(&["goto", "--recent"])).unwrap().absolute_dates);
     }
 
-    // Config command tests
     #[test]
-    fn test_parse_config() {
-        let result = parse_args(&args(&["goto", "--config"]));
-        assert!(result.is_ok());
-        assert!(matches!(result.unwrap().command, Command::Config));
+    fn test_parse_quiet_and_no_input_anywhere() {
+        let result = parse_args(&args(&["goto", "--quiet", "-r", "proj", "/tmp", "--no-input"])).unwrap();
+        assert!(result.quiet);
+        assert!(result.no_input);
+        match result.command {
+            Command::Register { name, path, .. } => {
+                assert_eq!(name, "proj");
+                assert_eq!(path, "/tmp");
+            }
+            _ => panic!("Expected Register command"),
+        }
+
+        let result = parse_args(&args(&["goto", "-l"])).unwrap();
+        assert!(!result.quiet);
+        assert!(!result.no_input);
     }
 
     // Install command tests
@@ -771,7 +2084,7 @@ mod tests {
     fn test_parse_install_default() {
         let result = parse_args(&args(&["goto", "--install"]));
         assert!(result.is_ok());
-        if let Command::Install { shell, skip_rc, dry_run } = result.unwrap().command {
+        if let Command::Install { shell, skip_rc, dry_run, .. } = result.unwrap().command {
             assert_eq!(shell, None);
             assert!(!skip_rc);
             assert!(!dry_run);
@@ -784,7 +2097,7 @@ mod tests {
     fn test_parse_install_with_shell() {
         let result = parse_args(&args(&["goto", "--install", "--shell=zsh"]));
         assert!(result.is_ok());
-        if let Command::Install { shell, skip_rc, dry_run } = result.unwrap().command {
+        if let Command::Install { shell, skip_rc, dry_run, .. } = result.unwrap().command {
             assert_eq!(shell, Some("zsh".to_string()));
             assert!(!skip_rc);
             assert!(!dry_run);
@@ -797,7 +2110,7 @@ mod tests {
     fn test_parse_install_with_skip_rc() {
         let result = parse_args(&args(&["goto", "--install", "--skip-rc"]));
         assert!(result.is_ok());
-        if let Command::Install { shell, skip_rc, dry_run } = result.unwrap().command {
+        if let Command::Install { shell, skip_rc, dry_run, .. } = result.unwrap().command {
             assert_eq!(shell, None);
             assert!(skip_rc);
             assert!(!dry_run);
@@ -810,7 +2123,7 @@ mod tests {
     fn test_parse_install_with_dry_run() {
         let result = parse_args(&args(&["goto", "--install", "--dry-run"]));
         assert!(result.is_ok());
-        if let Command::Install { shell, skip_rc, dry_run } = result.unwrap().command {
+        if let Command::Install { shell, skip_rc, dry_run, .. } = result.unwrap().command {
             assert_eq!(shell, None);
             assert!(!skip_rc);
             assert!(dry_run);
@@ -819,11 +2132,41 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_parse_uninstall() {
+        let result = parse_args(&args(&["goto", "--uninstall"]));
+        assert!(result.is_ok());
+        if let Command::Uninstall { shell, purge, force, dry_run } = result.unwrap().command {
+            assert_eq!(shell, None);
+            assert!(!purge);
+            assert!(!force);
+            assert!(!dry_run);
+        } else {
+            panic!("Expected Uninstall command");
+        }
+    }
+
+    #[test]
+    fn test_parse_uninstall_all_options() {
+        let result = parse_args(&args(&[
+            "goto", "--uninstall", "--shell=fish", "--purge", "--force", "--dry-run",
+        ]));
+        assert!(result.is_ok());
+        if let Command::Uninstall { shell, purge, force, dry_run } = result.unwrap().command {
+            assert_eq!(shell, Some("fish".to_string()));
+            assert!(purge);
+            assert!(force);
+            assert!(dry_run);
+        } else {
+            panic!("Expected Uninstall command");
+        }
+    }
+
     #[test]
     fn test_parse_install_all_options() {
         let result = parse_args(&args(&["goto", "--install", "--shell=bash", "--skip-rc", "--dry-run"]));
         assert!(result.is_ok());
-        if let Command::Install { shell, skip_rc, dry_run } = result.unwrap().command {
+        if let Command::Install { shell, skip_rc, dry_run, .. } = result.unwrap().command {
             assert_eq!(shell, Some("bash".to_string()));
             assert!(skip_rc);
             assert!(dry_run);
@@ -837,7 +2180,7 @@ mod tests {
     fn test_parse_import() {
         let result = parse_args(&args(&["goto", "--import", "backup.toml"]));
         assert!(result.is_ok());
-        if let Command::Import { file, strategy } = result.unwrap().command {
+        if let Command::Import { file, strategy, .. } = result.unwrap().command {
             assert_eq!(file, "backup.toml");
             assert!(matches!(strategy, ImportStrategy::Skip));
         } else {
@@ -845,11 +2188,19 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_parse_import_force() {
+        let result = parse_args(&args(&["goto", "--import", "backup.toml", "--force"])).unwrap();
+        assert!(result.command.force());
+        assert!(matches!(result.command, Command::Import { ref file, .. } if file == "backup.toml"));
+        assert!(!parse_args(&args(&["goto", "--import", "backup.toml"])).unwrap().command.force());
+    }
+
     #[test]
     fn test_parse_import_with_strategy_overwrite() {
         let result = parse_args(&args(&["goto", "--import", "backup.toml", "--strategy=overwrite"]));
         assert!(result.is_ok());
-        if let Command::Import { file, strategy } = result.unwrap().command {
+        if let Command::Import { file, strategy, .. } = result.unwrap().command {
             assert_eq!(file, "backup.toml");
             assert!(matches!(strategy, ImportStrategy::Overwrite));
         } else {
@@ -861,7 +2212,7 @@ mod tests {
     fn test_parse_import_with_strategy_rename() {
         let result = parse_args(&args(&["goto", "--import", "backup.toml", "--strategy=rename"]));
         assert!(result.is_ok());
-        if let Command::Import { file, strategy } = result.unwrap().command {
+        if let Command::Import { file, strategy, .. } = result.unwrap().command {
             assert_eq!(file, "backup.toml");
             assert!(matches!(strategy, ImportStrategy::Rename));
         } else {
@@ -881,7 +2232,7 @@ mod tests {
     fn test_parse_unregister_short() {
         let result = parse_args(&args(&["goto", "-u", "proj"]));
         assert!(result.is_ok());
-        if let Command::Unregister { name } = result.unwrap().command {
+        if let Command::Unregister { name, .. } = result.unwrap().command {
             assert_eq!(name, "proj");
         } else {
             panic!("Expected Unregister command");
@@ -892,7 +2243,7 @@ mod tests {
     fn test_parse_unregister_long() {
         let result = parse_args(&args(&["goto", "--unregister", "proj"]));
         assert!(result.is_ok());
-        if let Command::Unregister { name } = result.unwrap().command {
+        if let Command::Unregister { name, .. } = result.unwrap().command {
             assert_eq!(name, "proj");
         } else {
             panic!("Expected Unregister command");
@@ -906,6 +2257,48 @@ mod tests {
         assert!(result.unwrap_err().contains("Usage:"));
     }
 
+    #[test]
+    fn test_parse_clone() {
+        let result = parse_args(&args(&["goto", "--clone", "git@github.com:o/r.git", "r2", "-t", "a,b"]));
+        if let Command::Clone { url, alias, tags } = result.unwrap().command {
+            assert_eq!(url, "git@github.com:o/r.git");
+            assert_eq!(alias.as_deref(), Some("r2"));
+            assert_eq!(tags, vec!["a", "b"]);
+        } else {
+            panic!("Expected Clone command");
+        }
+
+        let result = parse_args(&args(&["goto", "--clone", "https://x.org/o/r", "--tags=a"]));
+        if let Command::Clone { alias, .. } = result.unwrap().command {
+            assert!(alias.is_none());
+        } else {
+            panic!("Expected Clone command");
+        }
+
+        assert!(parse_args(&args(&["goto", "--clone"])).unwrap_err().contains("Usage:"));
+    }
+
+    #[test]
+    fn test_parse_new() {
+        let result = parse_args(&args(&["goto", "--new", "app", "~/src/app", "--template=rust", "-t", "work"]));
+        if let Command::New { name, path, template, tags } = result.unwrap().command {
+            assert_eq!(name, "app");
+            assert_eq!(path, "~/src/app");
+            assert_eq!(template.as_deref(), Some("rust"));
+            assert_eq!(tags, vec!["work"]);
+        } else {
+            panic!("Expected New command");
+        }
+
+        let result = parse_args(&args(&["goto", "--new", "app", "/tmp/app"]));
+        assert!(matches!(result.unwrap().command, Command::New { template: None, .. }));
+
+        assert!(parse_args(&args(&["goto", "--new", "app"])).unwrap_err().contains("Usage:"));
+        assert!(parse_args(&args(&["goto", "--new", "--template=rust", "a", "b"]))
+            .unwrap_err()
+            .contains("Usage:"));
+    }
+
     #[test]
     fn test_parse_expand_short() {
         let result = parse_args(&args(&["goto", "-x", "proj"]));
@@ -935,12 +2328,197 @@ mod tests {
         assert!(result.unwrap_err().contains("Usage:"));
     }
 
+    #[test]
+    fn test_parse_env() {
+        let result = parse_args(&args(&["goto", "--env", "proj", "RUST_LOG=debug", "--unset=OLD"]));
+        if let Command::Env { alias, assignments, unset } = result.unwrap().command {
+            assert_eq!(alias, "proj");
+            assert_eq!(assignments, vec!["RUST_LOG=debug"]);
+            assert_eq!(unset, vec!["OLD"]);
+        } else {
+            panic!("Expected Env command");
+        }
+
+        let result = parse_args(&args(&["goto", "--env"]));
+        assert!(result.unwrap_err().contains("Usage:"));
+    }
+
+    #[test]
+    fn test_parse_preview() {
+        let result = parse_args(&args(&["goto", "--preview", "proj"]));
+        if let Command::Preview { alias } = result.unwrap().command {
+            assert_eq!(alias, "proj");
+        } else {
+            panic!("Expected Preview command");
+        }
+
+        let result = parse_args(&args(&["goto", "--preview"]));
+        assert!(result.unwrap_err().contains("Usage:"));
+    }
+
+    #[test]
+    fn test_parse_explain() {
+        let result = parse_args(&args(&["goto", "--explain", "proj:src"]));
+        if let Command::Explain { query } = result.unwrap().command {
+            assert_eq!(query, "proj:src");
+        } else {
+            panic!("Expected Explain command");
+        }
+
+        let result = parse_args(&args(&["goto", "--explain"]));
+        assert!(result.unwrap_err().contains("Usage:"));
+    }
+
+    #[test]
+    fn test_parse_note() {
+        let result = parse_args(&args(&["goto", "--note", "proj"]));
+        if let Command::Note { alias } = result.unwrap().command {
+            assert_eq!(alias, "proj");
+        } else {
+            panic!("Expected Note command");
+        }
+
+        let result = parse_args(&args(&["goto", "--note"]));
+        assert!(result.unwrap_err().contains("Usage:"));
+    }
+
+    #[test]
+    fn test_parse_open() {
+        let result = parse_args(&args(&["goto", "--open", "proj"]));
+        assert!(matches!(result.unwrap().command, Command::Open { ref alias, opener: Opener::FileManager } if alias == "proj"));
+        for flag in ["--code", "--edit-in"] {
+            let result = parse_args(&args(&["goto", flag, "proj"]));
+            assert!(matches!(result.unwrap().command, Command::Open { opener: Opener::Editor, .. }));
+        }
+        assert!(parse_args(&args(&["goto", "--code"])).unwrap_err().contains("Usage: goto --code <alias>"));
+    }
+
+    #[test]
+    fn test_parse_search() {
+        let result = parse_args(&args(&["goto", "--search", "api", "--limit=5", "--json"]));
+        if let Command::Search { query, limit, json, wide } = result.unwrap().command {
+            assert_eq!(query, "api");
+            assert_eq!(limit, Some(5));
+            assert!(json);
+            assert!(!wide);
+        } else {
+            panic!("Expected Search command");
+        }
+
+        assert!(parse_args(&args(&["goto", "--search"])).unwrap_err().contains("Usage:"));
+        assert!(parse_args(&args(&["goto", "--search", "x", "--limit=many"])).is_err());
+    }
+
+    #[test]
+    fn test_parse_pick() {
+        let result = parse_args(&args(&["goto", "--pick"]));
+        assert!(matches!(result.unwrap().command, Command::Pick));
+    }
+
+    #[test]
+    fn test_parse_where() {
+        let result = parse_args(&args(&["goto", "--where"]));
+        assert!(matches!(result.unwrap().command, Command::Where { cache: false }));
+
+        let result = parse_args(&args(&["goto", "--where", "--quiet", "--cache"])).unwrap();
+        assert!(matches!(result.command, Command::Where { cache: true }));
+        assert!(result.quiet);
+        assert!(!result.command.needs_database());
+    }
+
+    #[test]
+    fn test_parse_counter() {
+        let result = parse_args(&args(&["goto", "--counter"])).unwrap();
+        assert!(matches!(result.command, Command::Counter));
+        assert!(!result.command.needs_database());
+    }
+
+    #[test]
+    fn test_parse_prompt_snippet() {
+        let result = parse_args(&args(&["goto", "--prompt-snippet", "zsh"])).unwrap();
+        if let Command::PromptSnippet { shell } = result.command {
+            assert_eq!(shell, "zsh");
+        } else {
+            panic!("Expected PromptSnippet command");
+        }
+        assert!(parse_args(&args(&["goto", "--prompt-snippet"])).unwrap_err().contains("Usage:"));
+    }
+
+    #[test]
+    fn test_parse_check() {
+        let result = parse_args(&args(&["goto", "--check"]));
+        assert!(matches!(result.unwrap().command, Command::Check));
+    }
+
+    #[test]
+    fn test_parse_duplicates() {
+        let result = parse_args(&args(&["goto", "--duplicates"]));
+        assert!(matches!(result.unwrap().command, Command::Duplicates));
+    }
+
     // Export command test
     #[test]
     fn test_parse_export() {
         let result = parse_args(&args(&["goto", "--export"]));
         assert!(result.is_ok());
-        assert!(matches!(result.unwrap().command, Command::Export));
+        assert!(matches!(result.unwrap().command, Command::Export { output: None, filter: None }));
+    }
+
+    #[test]
+    fn test_parse_export_output() {
+        let result = parse_args(&args(&["goto", "--export", "--output=backup.toml"]));
+        if let Command::Export { output, .. } = result.unwrap().command {
+            assert_eq!(output.as_deref(), Some("backup.toml"));
+        } else {
+            panic!("Expected Export command");
+        }
+    }
+
+    #[test]
+    fn test_parse_export_filter() {
+        let result = parse_args(&args(&["goto", "--export", "--filter=tag:work"]));
+        if let Command::Export { output, filter } = result.unwrap().command {
+            assert_eq!(output, None);
+            assert_eq!(filter.as_deref(), Some("tag:work"));
+        } else {
+            panic!("Expected Export command");
+        }
+    }
+
+    #[test]
+    fn test_parse_fsck() {
+        let result = parse_args(&args(&["goto", "--fsck"])).unwrap();
+        assert!(matches!(result.command, Command::Fsck { dry_run: false }));
+        assert!(!result.command.needs_database());
+        let result = parse_args(&args(&["goto", "--fsck", "--dry-run"])).unwrap();
+        assert!(matches!(result.command, Command::Fsck { dry_run: true }));
+    }
+
+    #[test]
+    fn test_parse_backups_and_restore() {
+        let result = parse_args(&args(&["goto", "--backups"]));
+        assert!(matches!(result.unwrap().command, Command::Backups));
+
+        let result = parse_args(&args(&["goto", "--restore", "20240101T000000", "-f"]));
+        if let Command::Restore { timestamp, force } = result.unwrap().command {
+            assert_eq!(timestamp, "20240101T000000");
+            assert!(force);
+        } else {
+            panic!("Expected Restore command");
+        }
+
+        let result = parse_args(&args(&["goto", "--restore"]));
+        assert!(result.unwrap_err().contains("Usage:"));
+    }
+
+    #[test]
+    fn test_parse_import_stdin() {
+        let result = parse_args(&args(&["goto", "--import", "-"]));
+        if let Command::Import { file, .. } = result.unwrap().command {
+            assert_eq!(file, "-");
+        } else {
+            panic!("Expected Import command");
+        }
     }
 
     // List names test
@@ -963,14 +2541,25 @@ mod tests {
     fn test_parse_update_short() {
         let result = parse_args(&args(&["goto", "-U"]));
         assert!(result.is_ok());
-        assert!(matches!(result.unwrap().command, Command::Update));
+        assert!(matches!(result.unwrap().command, Command::Update { version: None }));
     }
 
     #[test]
     fn test_parse_update_long() {
         let result = parse_args(&args(&["goto", "--update"]));
         assert!(result.is_ok());
-        assert!(matches!(result.unwrap().command, Command::Update));
+        assert!(matches!(result.unwrap().command, Command::Update { version: None }));
+    }
+
+    #[test]
+    fn test_parse_update_with_version() {
+        let result = parse_args(&args(&["goto", "--update", "--version=1.3.2"]));
+        assert!(result.is_ok());
+        if let Command::Update { version } = result.unwrap().command {
+            assert_eq!(version, Some("1.3.2".to_string()));
+        } else {
+            panic!("Expected Update command");
+        }
     }
 
     #[test]
@@ -980,6 +2569,16 @@ mod tests {
         assert!(matches!(result.unwrap().command, Command::CheckUpdate));
     }
 
+    #[test]
+    fn test_parse_update_check_daemon() {
+        let result = parse_args(&args(&["goto", "--update-check-daemon"]));
+        assert!(matches!(result.unwrap().command, Command::UpdateCheckDaemon));
+
+        let result = parse_args(&args(&["goto", "--bench-selftest", "--max-ms=50"]));
+        assert!(matches!(result.unwrap().command, Command::BenchSelftest { max_ms: Some(50) }));
+        assert!(parse_args(&args(&["goto", "--bench-selftest", "--max-ms=fast"])).is_err());
+    }
+
     // Short flag tests
     #[test]
     fn test_parse_stats_short() {
@@ -992,7 +2591,7 @@ mod tests {
     fn test_parse_tags_short() {
         let result = parse_args(&args(&["goto", "-T"]));
         assert!(result.is_ok());
-        assert!(matches!(result.unwrap().command, Command::ListTags));
+        assert!(matches!(result.unwrap().command, Command::ListTags { .. }));
     }
 
     #[test]
@@ -1000,7 +2599,7 @@ mod tests {
         let result = parse_args(&args(&["goto", "-R"]));
         assert!(result.is_ok());
         if let Command::Recent { count, navigate_to } = result.unwrap().command {
-            assert_eq!(count, Some(10));
+            assert_eq!(count, None);
             assert_eq!(navigate_to, None);
         } else {
             panic!("Expected Recent command");
@@ -1019,18 +2618,81 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_parse_diff() {
+        let result = parse_args(&args(&["goto", "--diff", "ours.toml", "theirs.toml"]));
+        if let Command::Diff { old, new } = result.unwrap().command {
+            assert_eq!(old, "ours.toml");
+            assert_eq!(new.as_deref(), Some("theirs.toml"));
+        } else {
+            panic!("Expected Diff command");
+        }
+
+        let result = parse_args(&args(&["goto", "--diff", "-"]));
+        assert!(matches!(result.unwrap().command, Command::Diff { new: None, .. }));
+        assert!(parse_args(&args(&["goto", "--diff"])).is_err());
+        assert!(parse_args(&args(&["goto", "--diff", "a", "b", "c"])).is_err());
+    }
+
+    #[test]
+    fn test_parse_report() {
+        let result = parse_args(&args(&["goto", "--report", "--output=goto.html", "--filter=tag:work"]));
+        if let Command::Report { output, filter } = result.unwrap().command {
+            assert_eq!(output.as_deref(), Some("goto.html"));
+            assert_eq!(filter.as_deref(), Some("tag:work"));
+        } else {
+            panic!("Expected Report command");
+        }
+    }
+
+    #[test]
+    fn test_parse_export_shell_aliases() {
+        let result = parse_args(&args(&["goto", "--export-shell-aliases", "--shell=fish", "--filter=tag:work"]));
+        if let Command::ExportShellAliases { shell, output, filter } = result.unwrap().command {
+            assert_eq!(shell.as_deref(), Some("fish"));
+            assert_eq!(output, None);
+            assert_eq!(filter.as_deref(), Some("tag:work"));
+        } else {
+            panic!("Expected ExportShellAliases command");
+        }
+    }
+
+    #[test]
+    fn test_parse_ssh() {
+        let result = parse_args(&args(&["goto", "--ssh", "box", "api", "--print"]));
+        if let Command::Ssh { host, alias, print } = result.unwrap().command {
+            assert_eq!((host.as_str(), alias.as_str(), print), ("box", "api", true));
+        } else {
+            panic!("Expected Ssh command");
+        }
+        assert!(parse_args(&args(&["goto", "--ssh", "box"])).unwrap_err().contains("Usage:"));
+
+        let result = parse_args(&args(&["goto", "--ssh-import", "box", "-"]));
+        assert!(matches!(result.unwrap().command, Command::SshImport { ref host, ref file } if host == "box" && file == "-"));
+    }
+
+    #[test]
+    fn test_parse_event() {
+        let result = parse_args(&args(&["goto", "--event", "enter", "work"]));
+        assert!(matches!(result.unwrap().command, Command::Event { enter: Some(ref alias) } if alias == "work"));
+        let result = parse_args(&args(&["goto", "--event", "leave"]));
+        assert!(matches!(result.unwrap().command, Command::Event { enter: None }));
+        assert!(parse_args(&args(&["goto", "--event", "enter"])).unwrap_err().contains("Usage:"));
+        assert!(parse_args(&args(&["goto", "--event"])).unwrap_err().contains("Usage:"));
+    }
+
     #[test]
     fn test_parse_export_short() {
         let result = parse_args(&args(&["goto", "-e"]));
         assert!(result.is_ok());
-        assert!(matches!(result.unwrap().command, Command::Export));
+        assert!(matches!(result.unwrap().command, Command::Export { output: None, filter: None }));
     }
 
     #[test]
     fn test_parse_import_short() {
         let result = parse_args(&args(&["goto", "-i", "backup.toml"]));
         assert!(result.is_ok());
-        if let Command::Import { file, strategy } = result.unwrap().command {
+        if let Command::Import { file, strategy, .. } = result.unwrap().command {
             assert_eq!(file, "backup.toml");
             assert!(matches!(strategy, ImportStrategy::Skip));
         } else {
@@ -1042,7 +2704,7 @@ mod tests {
     fn test_parse_import_short_with_strategy() {
         let result = parse_args(&args(&["goto", "-i", "backup.toml", "--strategy=overwrite"]));
         assert!(result.is_ok());
-        if let Command::Import { file, strategy } = result.unwrap().command {
+        if let Command::Import { file, strategy, .. } = result.unwrap().command {
             assert_eq!(file, "backup.toml");
             assert!(matches!(strategy, ImportStrategy::Overwrite));
         } else {
@@ -1061,7 +2723,7 @@ mod tests {
     fn test_parse_register_with_short_tags() {
         let result = parse_args(&args(&["goto", "-r", "dev", "/path", "-t", "work,rust"]));
         assert!(result.is_ok());
-        if let Command::Register { name, path, tags, force } = result.unwrap().command {
+        if let Command::Register { name, path, tags, force, .. } = result.unwrap().command {
             assert_eq!(name, "dev");
             assert_eq!(path, "/path");
             assert_eq!(tags, vec!["work", "rust"]);
@@ -1075,7 +2737,7 @@ mod tests {
     fn test_parse_register_with_short_tags_and_force() {
         let result = parse_args(&args(&["goto", "-r", "dev", "/path", "-t", "work", "-f"]));
         assert!(result.is_ok());
-        if let Command::Register { name, path, tags, force } = result.unwrap().command {
+        if let Command::Register { name, path, tags, force, .. } = result.unwrap().command {
             assert_eq!(name, "dev");
             assert_eq!(path, "/path");
             assert_eq!(tags, vec!["work"]);
@@ -1085,6 +2747,17 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_parse_register_no_resolve() {
+        let result = parse_args(&args(&["goto", "-r", "cur", "~/current", "--no-resolve"]));
+        if let Command::Register { path, no_resolve, .. } = result.unwrap().command {
+            assert_eq!(path, "~/current");
+            assert!(no_resolve);
+        } else {
+            panic!("Expected Register command");
+        }
+    }
+
     // RenameTag command tests
     #[test]
     fn test_parse_rename_tag() {
@@ -1107,6 +2780,59 @@ mod tests {
         assert!(result.unwrap_err().contains("Usage:"));
     }
 
+    // DeleteTag command tests
+    #[test]
+    fn test_parse_delete_tag() {
+        let result = parse_args(&args(&["goto", "--delete-tag", "old", "--dry-run"]));
+        if let Command::DeleteTag { tag, dry_run, force } = result.unwrap().command {
+            assert_eq!(tag, "old");
+            assert!(dry_run);
+            assert!(!force);
+        } else {
+            panic!("Expected DeleteTag command");
+        }
+    }
+
+    #[test]
+    fn test_parse_delete_tag_missing_arg() {
+        let result = parse_args(&args(&["goto", "--delete-tag"]));
+        assert!(result.unwrap_err().contains("Usage:"));
+    }
+
+    // TagAll command tests
+    #[test]
+    fn test_parse_tag_all() {
+        let result = parse_args(&args(&["goto", "--tag-all", "work", "--filter=proj-*", "--dry-run"]));
+        if let Command::TagAll { tag, filter, remove, dry_run, force } = result.unwrap().command {
+            assert_eq!(tag, "work");
+            assert_eq!(filter, "proj-*");
+            assert!(!remove);
+            assert!(dry_run);
+            assert!(!force);
+        } else {
+            panic!("Expected TagAll command");
+        }
+    }
+
+    #[test]
+    fn test_parse_tag_all_remove() {
+        let result = parse_args(&args(&["goto", "--tag-all", "old", "--filter=tag:x", "--remove", "-f"]));
+        if let Command::TagAll { remove, force, .. } = result.unwrap().command {
+            assert!(remove);
+            assert!(force);
+        } else {
+            panic!("Expected TagAll command");
+        }
+    }
+
+    #[test]
+    fn test_parse_tag_all_requires_filter() {
+        let result = parse_args(&args(&["goto", "--tag-all", "work"]));
+        assert!(result.unwrap_err().contains("Usage:"));
+        let result = parse_args(&args(&["goto", "--tag-all", "--filter=*"]));
+        assert!(result.is_err());
+    }
+
     #[test]
     fn test_parse_rename_tag_with_force() {
         let result = parse_args(&args(&["goto", "--rename-tag", "old", "new", "--force"]));
@@ -1163,6 +2889,22 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_parse_rewrite_prefix() {
+        let result = parse_args(&args(&["goto", "--rewrite-prefix", "~/code", "/data/code", "--dry-run"]));
+        if let Command::RewritePrefix { old, new, dry_run, allow_missing } = result.unwrap().command {
+            assert_eq!(old, "~/code");
+            assert_eq!(new, "/data/code");
+            assert!(dry_run);
+            assert!(!allow_missing);
+        } else {
+            panic!("Expected RewritePrefix command");
+        }
+
+        let result = parse_args(&args(&["goto", "--rewrite-prefix", "~/code", "--dry-run"]));
+        assert!(result.unwrap_err().contains("Usage:"));
+    }
+
     // PruneSnooze command tests
     #[test]
     fn test_parse_prune_snooze() {
@@ -1175,6 +2917,72 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_parse_complete() {
+        let result = parse_args(&args(&["goto", "--complete", "--", "-r", "proj", ""]));
+        match result.unwrap().command {
+            Command::Complete { words } => assert_eq!(words, vec!["-r", "proj", ""]),
+            _ => panic!("Expected Complete command"),
+        }
+
+        let result = parse_args(&args(&["goto", "--complete"]));
+        assert!(matches!(result.unwrap().command, Command::Complete { words } if words.is_empty()));
+    }
+
+    #[test]
+    fn test_parse_watch() {
+        let result = parse_args(&args(&["goto", "--watch", "~/src"]));
+        match result.unwrap().command {
+            Command::Watch { dir } => assert_eq!(dir.as_deref(), Some("~/src")),
+            _ => panic!("Expected Watch command"),
+        }
+
+        let result = parse_args(&args(&["goto", "--watch"]));
+        assert!(matches!(result.unwrap().command, Command::Watch { dir: None }));
+
+        let result = parse_args(&args(&["goto", "--unwatch"]));
+        assert!(result.unwrap_err().contains("Usage:"));
+
+        let result = parse_args(&args(&["goto", "--watch-scan"]));
+        assert!(matches!(result.unwrap().command, Command::WatchScan));
+
+        let result = parse_args(&args(&["goto", "--review"]));
+        match result.unwrap().command {
+            Command::Review { criteria } => assert_eq!(criteria, Criterion::DEFAULT.to_vec()),
+            other => panic!("unexpected command: {:?}", other),
+        }
+
+        let result = parse_args(&args(&["goto", "--review", "untagged,missing"]));
+        match result.unwrap().command {
+            Command::Review { criteria } => assert_eq!(criteria, vec![Criterion::Missing, Criterion::Untagged]),
+            other => panic!("unexpected command: {:?}", other),
+        }
+
+        let result = parse_args(&args(&["goto", "--review", "stale"]));
+        assert!(result.unwrap_err().contains("invalid review criterion"));
+
+        let result = parse_args(&args(&["goto", "--install", "--watch-timer"]));
+        assert!(matches!(result.unwrap().command, Command::Install { watch_timer: true, .. }));
+    }
+
+    #[test]
+    fn test_parse_learn_from_history() {
+        let result = parse_args(&args(&["goto", "--learn-from-history"]));
+        assert!(matches!(result.unwrap().command, Command::LearnFromHistory { file: None, limit: None }));
+
+        let result = parse_args(&args(&["goto", "--learn-from-history", "~/.zsh_history", "--limit=5"]));
+        match result.unwrap().command {
+            Command::LearnFromHistory { file, limit } => {
+                assert_eq!(file.as_deref(), Some("~/.zsh_history"));
+                assert_eq!(limit, Some(5));
+            }
+            other => panic!("unexpected command: {:?}", other),
+        }
+
+        let result = parse_args(&args(&["goto", "--learn-from-history", "--limit=many"]));
+        assert!(result.unwrap_err().contains("Invalid limit"));
+    }
+
     #[test]
     fn test_parse_prune_snooze_missing_days() {
         let result = parse_args(&args(&["goto", "--prune-snooze"]));
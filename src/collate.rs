@@ -0,0 +1,123 @@
+//! Unicode-aware comparison of alias names, tags and paths
+//!
+//! Byte order puts "Zebra" before "apple" and "école" after "zoo", and a
+//! plain lowercase comparison misses that "é" typed on a keyboard and "e"
+//! followed by a combining accent (as macOS hands out file names) are the
+//! same text. Names are compared by their [`fold`] instead: decomposed,
+//! accents dropped and case folded, so "Café", "cafe" and "CAFÉ" sort and
+//! match together. Ties are broken by accents and then case, so the order is
+//! still total and stable.
+
+use std::borrow::Cow;
+use std::cmp::Ordering;
+
+use icu_normalizer::{ComposingNormalizerBorrowed, DecomposingNormalizerBorrowed};
+
+/// `s` in canonically composed form (NFC), borrowed when it already is
+pub fn nfc(s: &str) -> Cow<'_, str> {
+    if s.is_ascii() {
+        return Cow::Borrowed(s);
+    }
+    ComposingNormalizerBorrowed::new_nfc().normalize(s)
+}
+
+/// `s` composed and case folded, for comparisons that respect accents
+pub fn fold_case(s: &str) -> String {
+    if s.is_ascii() {
+        return s.to_ascii_lowercase();
+    }
+    let mut folded = String::with_capacity(s.len());
+    for c in nfc(s).chars() {
+        match c {
+            // Full case folding beyond what lowercasing does
+            'ß' | 'ẞ' => folded.push_str("ss"),
+            'ς' => folded.push('σ'),
+            _ => folded.extend(c.to_lowercase()),
+        }
+    }
+    folded
+}
+
+/// `s` case folded with accents dropped, for matching and sorting
+pub fn fold(s: &str) -> String {
+    if s.is_ascii() {
+        return s.to_ascii_lowercase();
+    }
+    let bare: String = DecomposingNormalizerBorrowed::new_nfd()
+        .normalize(s)
+        .chars()
+        .filter(|c| !is_accent(*c))
+        .collect();
+    fold_case(&bare)
+}
+
+/// Combining diacritical marks, the accents [`fold`] drops
+///
+/// Marks that change a letter rather than accent it, such as the Japanese
+/// voicing marks, are kept.
+fn is_accent(c: char) -> bool {
+    matches!(
+        c,
+        '\u{0300}'..='\u{036F}' | '\u{1AB0}'..='\u{1AFF}' | '\u{1DC0}'..='\u{1DFF}' | '\u{20D0}'..='\u{20FF}' | '\u{FE20}'..='\u{FE2F}'
+    )
+}
+
+/// Whether `a` and `b` are the same text ignoring case
+pub fn eq_ignore_case(a: &str, b: &str) -> bool {
+    a == b || fold_case(a) == fold_case(b)
+}
+
+/// Reading order: by [`fold`], then accents, then case and bytes
+pub fn compare(a: &str, b: &str) -> Ordering {
+    fold(a)
+        .cmp(&fold(b))
+        .then_with(|| fold_case(a).cmp(&fold_case(b)))
+        .then_with(|| a.cmp(b))
+}
+
+/// Key sorting like [`compare`], for `sort_by_cached_key`
+pub fn sort_key(s: &str) -> (String, String, String) {
+    (fold(s), fold_case(s), s.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fold() {
+        assert_eq!(fold("Café"), "cafe");
+        assert_eq!(fold("Cafe\u{301}"), "cafe");
+        assert_eq!(fold("Straße"), "strasse");
+        assert_eq!(fold("Проект"), "проект");
+        assert_eq!(fold("ЁЛКА"), "елка");
+        assert_eq!(fold("東京"), "東京");
+        // Voicing marks make a different kana and stay
+        assert_eq!(fold("が"), "が");
+        assert_eq!(fold_case("École"), "école");
+    }
+
+    #[test]
+    fn test_eq_ignore_case() {
+        assert!(eq_ignore_case("ÉCOLE", "école"));
+        assert!(eq_ignore_case("e\u{301}cole", "École"));
+        assert!(eq_ignore_case("МОСКВА", "москва"));
+        assert!(!eq_ignore_case("ecole", "école"));
+    }
+
+    #[test]
+    fn test_compare_sorts_by_reading_order() {
+        let mut names = vec!["zoo", "École", "apple", "Zebra", "ecole", "Ångström", "b"];
+        names.sort_by(|a, b| compare(a, b));
+        assert_eq!(names, vec!["Ångström", "apple", "b", "ecole", "École", "Zebra", "zoo"]);
+
+        let mut cyrillic = vec!["яблоко", "Арбуз", "ёж", "банан", "Ель"];
+        cyrillic.sort_by(|a, b| compare(a, b));
+        assert_eq!(cyrillic, vec!["Арбуз", "банан", "ёж", "Ель", "яблоко"]);
+
+        let mut keyed = names.clone();
+        keyed.reverse();
+        keyed.sort_by_cached_key(|n| sort_key(n));
+        assert_eq!(keyed, names);
+    }
+}
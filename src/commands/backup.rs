@@ -0,0 +1,212 @@
+//! Backup commands: list backups, restore a backup
+
+use std::fs;
+
+use chrono::{DateTime, Utc};
+
+use crate::backup::{Backup, BackupStore};
+use crate::config::Config;
+use crate::context::Ctx;
+use crate::database::Database;
+use crate::exit::Failure;
+use crate::table::{create_table, TableStyle};
+
+fn store(config: &Config) -> Result<BackupStore, Box<dyn std::error::Error>> {
+    Ok(BackupStore::new(config.backup_dir()?, config.user.backup.keep_count))
+}
+
+/// A database backup as listed by [`list_backups`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BackupSummary {
+    pub timestamp: String,
+    pub created_at: Option<DateTime<Utc>>,
+    /// Number of aliases in the backup, `None` if it can't be read
+    pub aliases: Option<usize>,
+}
+
+/// List available database backups, newest first
+pub fn list_backups(config: &Config) -> Result<Vec<BackupSummary>, Box<dyn std::error::Error>> {
+    let backups = store(config)?.list()?;
+
+    Ok(backups
+        .iter()
+        .rev()
+        .map(|backup| BackupSummary {
+            timestamp: backup.timestamp.clone(),
+            created_at: backup.created_at(),
+            aliases: Database::read_aliases(&backup.path).ok().map(|aliases| aliases.len()),
+        })
+        .collect())
+}
+
+/// Render backups as the `--backups` table
+pub fn format_backups(backups: &[BackupSummary], style: TableStyle) -> String {
+    let mut table = create_table(style);
+    table.set_header(vec!["Timestamp", "Created", "Aliases"]);
+
+    for backup in backups {
+        let created = backup
+            .created_at
+            .map(|t| t.format("%Y-%m-%d %H:%M:%S UTC").to_string())
+            .unwrap_or_default();
+        let count = backup
+            .aliases
+            .map_or_else(|| "unreadable".to_string(), |n| n.to_string());
+        table.add_row(vec![backup.timestamp.clone(), created, count]);
+    }
+
+    table.to_string()
+}
+
+/// Replace the alias database with a backup
+///
+/// The current database is backed up first, so a restore can itself be undone.
+/// Returns the restored backup and its number of aliases.
+pub fn restore(ctx: &mut Ctx, config: &Config, timestamp: &str) -> Result<(Backup, usize), Box<dyn std::error::Error>> {
+    let store = store(config)?;
+    let backup = store.find(timestamp)?;
+
+    // Make sure the backup is a readable database before touching anything
+    let content = fs::read_to_string(&backup.path)?;
+    let restored = Database::read_aliases(&backup.path)
+        .map_err(|e| format!("backup '{}' is not a valid database: {}", backup.timestamp, e))?;
+
+    let aliases_path = config.aliases_path.with_extension("toml");
+    // A damaged database is exactly when a backup is wanted
+    let current = match Database::load_from_path(&config.aliases_path) {
+        Ok(db) => format!("{} aliases", db.len()),
+        Err(_) => "unreadable database".to_string(),
+    };
+
+    let message = format!(
+        "Restore backup {} ({} aliases), replacing the current {}?",
+        backup.timestamp,
+        restored.len(),
+        current
+    );
+    if !ctx.confirm(&message, false)? {
+        return Err(Failure::cancelled("Restore cancelled").into());
+    }
+
+    config.ensure_dirs()?;
+    store.create(&aliases_path)?;
+    // Uses logged since the backup was taken belong to the database it replaces
+    Database::overwrite_file(&config.aliases_path, &content)?;
+
+    Ok((backup, restored.len()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::alias::Alias;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_save_creates_backup_and_restore_rolls_back() {
+        let dir = tempdir().unwrap();
+        let config = Config::in_dir(dir.path());
+
+        let mut db = Database::load(&config).unwrap();
+        db.insert(Alias::new("keep", "/tmp").unwrap());
+        db.insert(Alias::new("lost", "/tmp").unwrap());
+        db.save().unwrap();
+
+        // Removing an alias backs up the previous file
+        db.remove("lost");
+        db.save().unwrap();
+
+        let backups = list_backups(&config).unwrap();
+        assert_eq!(backups.len(), 1);
+        assert_eq!(backups[0].aliases, Some(2));
+        assert!(format_backups(&backups, TableStyle::Minimal).contains(&backups[0].timestamp));
+
+        let mut ctx = Ctx::silent();
+        assert!(restore(&mut ctx, &config, &backups[0].timestamp).is_err());
+        ctx.force = true;
+        let (backup, count) = restore(&mut ctx, &config, &backups[0].timestamp).unwrap();
+        assert_eq!(backup.timestamp, backups[0].timestamp);
+        assert_eq!(count, 2);
+
+        let db = Database::load(&config).unwrap();
+        assert!(db.get("lost").is_some());
+        assert!(db.get("keep").is_some());
+
+        // Reading backups leaves nothing but backups behind
+        let stray: Vec<_> = fs::read_dir(config.backup_dir().unwrap())
+            .unwrap()
+            .map(|e| e.unwrap().file_name().to_string_lossy().into_owned())
+            .filter(|name| !name.ends_with(".toml"))
+            .collect();
+        assert!(stray.is_empty(), "{:?}", stray);
+    }
+
+    #[test]
+    fn test_usage_only_changes_do_not_back_up() {
+        let dir = tempdir().unwrap();
+        let config = Config::in_dir(dir.path());
+
+        let mut db = Database::load(&config).unwrap();
+        db.insert(Alias::new("proj", "/tmp").unwrap());
+        db.save().unwrap();
+
+        db.get_mut("proj").unwrap().record_use();
+        db.save().unwrap();
+
+        assert!(store(&config).unwrap().list().unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_restore_drops_usage_logged_since() {
+        let dir = tempdir().unwrap();
+        let config = Config::in_dir(dir.path());
+
+        let mut db = Database::load(&config).unwrap();
+        db.insert(Alias::new("proj", "/tmp").unwrap());
+        db.save().unwrap();
+        db.insert(Alias::new("other", "/tmp").unwrap());
+        db.save().unwrap();
+        // Logged, not written into the database file
+        for _ in 0..3 {
+            db.record_usage("proj").unwrap();
+        }
+        db.save().unwrap();
+        drop(db);
+
+        let mut ctx = Ctx::silent();
+        ctx.force = true;
+        restore(&mut ctx, &config, "latest").unwrap();
+
+        let mut db = Database::load(&config).unwrap();
+        assert_eq!(db.get("proj").unwrap().use_count, 0);
+        assert!(!db.contains("other"));
+        db.insert(Alias::new("new", "/tmp").unwrap());
+        db.save().unwrap();
+        let db = Database::load(&config).unwrap();
+        assert_eq!(db.get("proj").unwrap().use_count, 0);
+    }
+
+    #[test]
+    fn test_backups_disabled() {
+        let dir = tempdir().unwrap();
+        let mut config = Config::in_dir(dir.path());
+        config.user.backup.enabled = false;
+
+        let mut db = Database::load(&config).unwrap();
+        db.insert(Alias::new("proj", "/tmp").unwrap());
+        db.save().unwrap();
+        db.remove("proj");
+        db.save().unwrap();
+
+        assert!(store(&config).unwrap().list().unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_restore_unknown_backup() {
+        let dir = tempdir().unwrap();
+        let config = Config::in_dir(dir.path());
+
+        let err = restore(&mut Ctx::silent(), &config, "20200101").unwrap_err();
+        assert!(err.to_string().contains("not found"));
+    }
+}
@@ -0,0 +1,177 @@
+//! `goto --bench-selftest`: alias resolution latency on a synthetic database
+//!
+//! Gives performance work (ranking, path matching) a baseline without
+//! touching the user's aliases. The criterion benchmarks in `benches/fuzzy.rs`
+//! use the same synthetic database.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process;
+use std::time::{Duration, Instant};
+
+use crate::alias::Alias;
+use crate::commands::navigate::{self, NavigateOptions, Ranking};
+use crate::context::Ctx;
+use crate::database::Database;
+use crate::table::{create_table, TableStyle};
+
+/// Aliases in the synthetic database `--bench-selftest` measures
+pub const SYNTHETIC_ALIASES: usize = 10_000;
+
+/// Times each step is run
+pub const SELFTEST_RUNS: u32 = 20;
+
+const WORDS: &[&str] = &[
+    "api", "app", "backend", "blog", "client", "config", "core", "data", "docs", "frontend", "infra",
+    "lib", "mobile", "notes", "ops", "platform", "scripts", "server", "site", "tools", "ui", "web",
+];
+
+/// Name of the `i`th synthetic alias, e.g. `backend-docs-178`
+pub fn synthetic_name(i: usize) -> String {
+    format!("{}-{}-{}", WORDS[i % WORDS.len()], WORDS[(i / WORDS.len()) % WORDS.len()], i)
+}
+
+/// Write a database of `count` synthetic aliases below `dir`
+///
+/// Alias `name` points at `dir/name`; the directories aren't created.
+/// Returns the base path to pass to [`Database::load_from_path`].
+pub fn write_synthetic_database(dir: &Path, count: usize) -> Result<PathBuf, Box<dyn std::error::Error>> {
+    let base = dir.join("aliases");
+    let mut db = Database::load_from_path(&base)?;
+    for i in 0..count {
+        let name = synthetic_name(i);
+        let mut alias = Alias::new(&name, dir.join(&name).to_str().ok_or("non-UTF-8 temp directory")?)?;
+        alias.add_tag(WORDS[i % WORDS.len()]);
+        alias.use_count = (i % 50) as u64;
+        db.insert(alias);
+    }
+    db.save()?;
+    Ok(base)
+}
+
+/// Time taken by one selftest step
+#[derive(Debug, Clone, PartialEq)]
+pub struct Timing {
+    pub step: &'static str,
+    pub mean: Duration,
+    pub max: Duration,
+}
+
+fn time(
+    step: &'static str,
+    runs: u32,
+    mut f: impl FnMut() -> Result<(), Box<dyn std::error::Error>>,
+) -> Result<Timing, Box<dyn std::error::Error>> {
+    let mut total = Duration::ZERO;
+    let mut max = Duration::ZERO;
+    for _ in 0..runs.max(1) {
+        let start = Instant::now();
+        f()?;
+        let elapsed = start.elapsed();
+        total += elapsed;
+        max = max.max(elapsed);
+    }
+    Ok(Timing {
+        step,
+        mean: total / runs.max(1),
+        max,
+    })
+}
+
+/// Removes the selftest directory when dropped
+struct TempDir(PathBuf);
+
+impl Drop for TempDir {
+    fn drop(&mut self) {
+        let _ = fs::remove_dir_all(&self.0);
+    }
+}
+
+/// Measure alias resolution on a synthetic database of `count` aliases
+///
+/// Times loading the database, navigating to an exact name (which records
+/// the use and saves), ranking fuzzy candidates for a typo and for a name
+/// matching nothing, and completing a prefix. Each step runs `runs` times.
+pub fn selftest(count: usize, runs: u32) -> Result<Vec<Timing>, Box<dyn std::error::Error>> {
+    let dir = TempDir(std::env::temp_dir().join(format!("goto-bench-{}", process::id())));
+    fs::create_dir_all(&dir.0)?;
+    let base = write_synthetic_database(&dir.0, count)?;
+
+    let target = synthetic_name(count / 2);
+    fs::create_dir_all(dir.0.join(&target))?;
+    // A dropped letter, as when mistyping
+    let typo: String = target.chars().enumerate().filter(|&(i, _)| i != 2).map(|(_, c)| c).collect();
+    let options = NavigateOptions::default();
+
+    let mut db = Database::load_from_path(&base)?;
+    let timings = vec![
+        time("load", runs, || {
+            Database::load_from_path(&base)?;
+            Ok(())
+        })?,
+        time("navigate (exact)", runs, || {
+            navigate::navigate_with_options(&mut Ctx::silent(), &mut db, &target, &options)?;
+            Ok(())
+        })?,
+        time("fuzzy (typo)", runs, || {
+            let mut matches = navigate::fuzzy_candidates(&db, &typo);
+            Ranking::new(&db, &[], 0.1).rank(&mut matches);
+            Ok(())
+        })?,
+        time("fuzzy (no match)", runs, || {
+            navigate::fuzzy_candidates(&db, "zzqx");
+            Ok(())
+        })?,
+        time("complete (prefix)", runs, || {
+            navigate::completions(&db, "backend-d");
+            Ok(())
+        })?,
+    ];
+    Ok(timings)
+}
+
+/// Steps whose mean time exceeds `budget`
+pub fn over_budget(timings: &[Timing], budget: Duration) -> Vec<&Timing> {
+    timings.iter().filter(|t| t.mean > budget).collect()
+}
+
+fn millis(duration: Duration) -> String {
+    format!("{:.2} ms", duration.as_secs_f64() * 1000.0)
+}
+
+/// Format selftest timings as a table
+pub fn format_timings(timings: &[Timing], style: TableStyle) -> String {
+    let mut table = create_table(style);
+    table.set_header(vec!["Step", "Mean", "Max"]);
+    for timing in timings {
+        table.add_row(vec![timing.step.to_string(), millis(timing.mean), millis(timing.max)]);
+    }
+    table.to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_synthetic_database() {
+        let dir = tempdir().unwrap();
+        let base = write_synthetic_database(dir.path(), 100).unwrap();
+        let db = Database::load_from_path(&base).unwrap();
+        assert_eq!(db.len(), 100);
+        assert!(db.get(&synthetic_name(42)).is_some());
+        assert_eq!(synthetic_name(178), "backend-docs-178");
+    }
+
+    #[test]
+    fn test_selftest_and_budget() {
+        let timings = selftest(200, 2).unwrap();
+        let steps: Vec<_> = timings.iter().map(|t| t.step).collect();
+        assert_eq!(steps, ["load", "navigate (exact)", "fuzzy (typo)", "fuzzy (no match)", "complete (prefix)"]);
+        assert!(timings.iter().all(|t| t.max >= t.mean));
+
+        assert!(over_budget(&timings, Duration::from_secs(60)).is_empty());
+        assert_eq!(over_budget(&timings, Duration::ZERO).len(), timings.len());
+    }
+}
@@ -0,0 +1,484 @@
+//! Alias audit command
+
+use std::collections::HashMap;
+use std::fs;
+use std::io::ErrorKind;
+use std::path::Path;
+use std::sync::mpsc;
+use std::thread;
+use std::time::{Duration, Instant};
+
+use crate::alias::Alias;
+use crate::database::Database;
+use crate::table::{create_table, TableStyle};
+use crate::volume::{MountTable, Volume};
+
+/// How serious a problem found by `--check` is
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Severity {
+    /// Navigating to the alias will fail
+    Error,
+    /// The alias works but probably needs attention
+    Warning,
+}
+
+impl Severity {
+    fn label(self) -> &'static str {
+        match self {
+            Severity::Error => "error",
+            Severity::Warning => "warning",
+        }
+    }
+}
+
+/// A problem found with a single alias
+#[derive(Debug, Clone, PartialEq)]
+pub struct Issue {
+    pub severity: Severity,
+    pub alias: String,
+    pub problem: String,
+    pub fix: String,
+}
+
+/// Audit every alias and return the problems found, errors first
+pub fn audit(db: &Database) -> Vec<Issue> {
+    let mut issues = Vec::new();
+
+    let mut aliases: Vec<_> = db.all().collect();
+    aliases.sort_by(|a, b| a.name.cmp(&b.name));
+
+    let mounts = MountTable::read();
+    let mut unmounted: Vec<(&Volume, Vec<&str>)> = Vec::new();
+    for alias in &aliases {
+        // Registered with --allow-missing; not a problem until it appears
+        if alias.is_pending() {
+            continue;
+        }
+        // A link's directory is checked with the alias it stands for
+        if alias.link.is_some() {
+            if let Err(e) = db.resolve(&alias.name) {
+                issues.push(Issue {
+                    severity: Severity::Error,
+                    alias: alias.name.clone(),
+                    problem: e.to_string(),
+                    fix: format!("goto -r {} --link <alias> or goto -u {}", alias.name, alias.name),
+                });
+            }
+            continue;
+        }
+        // Reported once per volume below
+        if let Some(volume) = unmounted_volume(alias, &mounts) {
+            match unmounted.iter_mut().find(|(v, _)| *v == volume) {
+                Some((_, names)) => names.push(&alias.name),
+                None => unmounted.push((volume, vec![&alias.name])),
+            }
+            continue;
+        }
+        if let Some((severity, problem, fix)) = check_path(&alias.name, &alias.path) {
+            issues.push(Issue {
+                severity,
+                alias: alias.name.clone(),
+                problem,
+                fix,
+            });
+        }
+    }
+
+    for (volume, names) in unmounted {
+        issues.push(Issue {
+            severity: Severity::Warning,
+            alias: names.join(", "),
+            problem: format!(
+                "{} alias{} on unmounted volume {}",
+                names.len(),
+                if names.len() == 1 { " lives" } else { "es live" },
+                volume
+            ),
+            fix: format!("mount {} at {}", volume, volume.mount_point),
+        });
+    }
+
+    // Duplicate paths registered under several names
+    let mut by_path: HashMap<&str, Vec<&str>> = HashMap::new();
+    aliases.retain(|a| a.link.is_none());
+    for alias in &aliases {
+        by_path
+            .entry(alias.path.trim_end_matches('/'))
+            .or_default()
+            .push(&alias.name);
+    }
+    for alias in &aliases {
+        let names = &by_path[alias.path.trim_end_matches('/')];
+        if names.len() > 1 {
+            let others: Vec<&str> = names.iter().copied().filter(|n| *n != alias.name).collect();
+            issues.push(Issue {
+                severity: Severity::Warning,
+                alias: alias.name.clone(),
+                problem: format!("same path as {}", others.join(", ")),
+                fix: format!("goto -u {} (if redundant)", alias.name),
+            });
+        }
+    }
+
+    issues.sort_by(|a, b| a.severity.cmp(&b.severity).then_with(|| a.alias.cmp(&b.alias)));
+    issues
+}
+
+/// The volume `alias` was registered on, if its directory is gone because
+/// that volume isn't mounted
+fn unmounted_volume<'a>(alias: &'a Alias, mounts: &MountTable) -> Option<&'a Volume> {
+    let volume = alias.volume.as_ref()?;
+    (!mounts.is_mounted(volume) && !Path::new(&alias.path).exists()).then_some(volume)
+}
+
+/// Check a single alias path, returning (severity, problem, suggested fix)
+fn check_path(name: &str, path: &str) -> Option<(Severity, String, String)> {
+    let p = Path::new(path);
+
+    let link_meta = match fs::symlink_metadata(p) {
+        Ok(meta) => meta,
+        Err(e) if e.kind() == ErrorKind::PermissionDenied => {
+            return Some((
+                Severity::Error,
+                "permission denied".to_string(),
+                "check permissions of the parent directories".to_string(),
+            ));
+        }
+        Err(_) => {
+            return Some((
+                Severity::Error,
+                "directory does not exist".to_string(),
+                format!("goto -u {} or goto -r {} <new-path>", name, name),
+            ));
+        }
+    };
+
+    if link_meta.file_type().is_symlink() && fs::metadata(p).is_err() {
+        let target = fs::read_link(p)
+            .map(|t| t.display().to_string())
+            .unwrap_or_default();
+        return Some((
+            Severity::Error,
+            format!("symlink target moved ({})", target),
+            format!("update the symlink or goto -r {} <new-path>", name),
+        ));
+    }
+
+    if !p.is_dir() {
+        return Some((
+            Severity::Error,
+            "path is a file, not a directory".to_string(),
+            format!("goto -r {} <directory>", name),
+        ));
+    }
+
+    if let Err(e) = fs::read_dir(p) {
+        if e.kind() == ErrorKind::PermissionDenied {
+            return Some((
+                Severity::Warning,
+                "directory is not readable".to_string(),
+                format!("chmod u+rx {}", path),
+            ));
+        }
+    }
+
+    None
+}
+
+/// State of an alias directory, for the `-l` status column and `--cleanup`
+///
+/// Ordered worst first, so sorting by status puts problems on top.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum PathStatus {
+    /// Nothing at the path, or not a directory
+    Missing,
+    /// A symlink whose target is gone
+    SymlinkBroken,
+    /// Missing, but on a volume that isn't mounted; the directory may be
+    /// back once it is
+    Unmounted,
+    /// The directory exists but can't be listed
+    Unreadable,
+    /// No answer in time, or an I/O error (e.g. a hung or stale network
+    /// mount); the directory may well still exist
+    Unreachable,
+    Ok,
+}
+
+impl PathStatus {
+    pub fn label(self) -> &'static str {
+        match self {
+            PathStatus::Missing => "missing",
+            PathStatus::SymlinkBroken => "symlink-broken",
+            PathStatus::Unmounted => "unmounted",
+            PathStatus::Unreadable => "unreadable",
+            PathStatus::Unreachable => "unreachable",
+            PathStatus::Ok => "ok",
+        }
+    }
+}
+
+/// Most paths statted at once
+const PATH_CHECK_WORKERS: usize = 8;
+
+/// Stat a single alias directory
+pub fn path_status(path: &str) -> PathStatus {
+    let p = Path::new(path);
+    match fs::symlink_metadata(p) {
+        Ok(meta) if meta.file_type().is_symlink() && fs::metadata(p).is_err() => PathStatus::SymlinkBroken,
+        Ok(_) if !p.is_dir() => PathStatus::Missing,
+        Ok(_) => match fs::read_dir(p) {
+            Err(e) if e.kind() == ErrorKind::PermissionDenied => PathStatus::Unreadable,
+            _ => PathStatus::Ok,
+        },
+        Err(e) => match e.kind() {
+            ErrorKind::NotFound | ErrorKind::NotADirectory => PathStatus::Missing,
+            ErrorKind::PermissionDenied => PathStatus::Unreadable,
+            _ => PathStatus::Unreachable,
+        },
+    }
+}
+
+/// Stat many paths in parallel, giving each at most `timeout`
+///
+/// Returns one status per path, in order. At most [`PATH_CHECK_WORKERS`]
+/// checks run at once; a path that doesn't answer in time is
+/// [`PathStatus::Unreachable`] and its thread is left behind rather than
+/// joined, so a hung mount can't block the caller or hold up other paths.
+pub fn path_statuses(paths: &[String], timeout: Duration) -> Vec<PathStatus> {
+    let mut statuses = vec![PathStatus::Unreachable; paths.len()];
+    let (tx, rx) = mpsc::channel();
+    // Checks started but not answered yet: index -> start time
+    let mut running: HashMap<usize, Instant> = HashMap::new();
+    let mut next = 0;
+
+    while next < paths.len() || !running.is_empty() {
+        while next < paths.len() && running.len() < PATH_CHECK_WORKERS {
+            let (tx, i, path) = (tx.clone(), next, paths[next].clone());
+            thread::spawn(move || {
+                let _ = tx.send((i, path_status(&path)));
+            });
+            running.insert(next, Instant::now());
+            next += 1;
+        }
+
+        let oldest = running.values().min().copied().unwrap_or_else(Instant::now);
+        match rx.recv_timeout((oldest + timeout).saturating_duration_since(Instant::now())) {
+            Ok((i, status)) => {
+                if running.remove(&i).is_some() {
+                    statuses[i] = status;
+                }
+            }
+            Err(_) => {
+                let now = Instant::now();
+                running.retain(|i, started| {
+                    let waiting = now < *started + timeout;
+                    if !waiting {
+                        crate::debug!("no answer from {} in {:?}", paths[*i], timeout);
+                    }
+                    waiting
+                });
+            }
+        }
+    }
+    statuses
+}
+
+/// [`path_statuses`] for aliases, telling missing directories on volumes
+/// that aren't mounted ([`PathStatus::Unmounted`]) from deleted ones
+pub fn alias_statuses(aliases: &[Alias], timeout: Duration) -> Vec<PathStatus> {
+    let paths: Vec<String> = aliases.iter().map(|a| a.path.clone()).collect();
+    let statuses = path_statuses(&paths, timeout);
+    if !aliases.iter().any(|a| a.volume.is_some()) {
+        return statuses;
+    }
+    let mounts = MountTable::read();
+    aliases
+        .iter()
+        .zip(statuses)
+        .map(|(alias, status)| match (status, &alias.volume) {
+            (PathStatus::Missing, Some(volume)) if !mounts.is_mounted(volume) => PathStatus::Unmounted,
+            (status, _) => status,
+        })
+        .collect()
+}
+
+/// Render problems found by [`audit`] as a table
+pub fn format_issues(issues: &[Issue], style: TableStyle) -> String {
+    let mut table = create_table(style);
+    table.set_header(vec!["Severity", "Name", "Problem", "Suggested Fix"]);
+
+    for issue in issues {
+        table.add_row(vec![
+            issue.severity.label().to_string(),
+            issue.alias.clone(),
+            issue.problem.clone(),
+            issue.fix.clone(),
+        ]);
+    }
+
+    table.to_string()
+}
+
+/// The `--check` failure message, e.g. "check failed: 1 error, 2 warnings"
+///
+/// `--check` exits non-zero with it when problems were found, so it can be
+/// used in CI or dotfiles checks.
+pub fn failure_message(issues: &[Issue]) -> String {
+    let errors = issues.iter().filter(|i| i.severity == Severity::Error).count();
+    format!(
+        "check failed: {} error{}, {} warning{}",
+        errors,
+        if errors == 1 { "" } else { "s" },
+        issues.len() - errors,
+        if issues.len() - errors == 1 { "" } else { "s" }
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::alias::Alias;
+    use tempfile::{tempdir, NamedTempFile};
+
+    fn create_test_db() -> (Database, NamedTempFile) {
+        let file = NamedTempFile::new().unwrap();
+        let db = Database::load_from_path(file.path()).unwrap();
+        (db, file)
+    }
+
+    #[test]
+    fn test_audit_clean() {
+        let (mut db, _file) = create_test_db();
+        let dir = tempdir().unwrap();
+        db.insert(Alias::new("ok", dir.path().to_str().unwrap()).unwrap());
+
+        assert!(audit(&db).is_empty());
+    }
+
+    #[test]
+    fn test_audit_missing_and_file() {
+        let (mut db, _file) = create_test_db();
+        let dir = tempdir().unwrap();
+        let file_path = dir.path().join("file.txt");
+        fs::write(&file_path, "").unwrap();
+        db.insert(Alias::new("gone", dir.path().join("missing").to_str().unwrap()).unwrap());
+        db.insert(Alias::new("file", file_path.to_str().unwrap()).unwrap());
+
+        let issues = audit(&db);
+        assert_eq!(issues.len(), 2);
+        assert!(issues.iter().all(|i| i.severity == Severity::Error));
+        assert!(issues[0].problem.contains("file"));
+        assert!(issues[1].problem.contains("does not exist"));
+    }
+
+    #[test]
+    fn test_audit_duplicate_paths() {
+        let (mut db, _file) = create_test_db();
+        let dir = tempdir().unwrap();
+        let path = dir.path().to_str().unwrap();
+        db.insert(Alias::new("one", path).unwrap());
+        db.insert(Alias::new("two", &format!("{}/", path)).unwrap());
+
+        let issues = audit(&db);
+        assert_eq!(issues.len(), 2);
+        assert_eq!(issues[0].severity, Severity::Warning);
+        assert_eq!(issues[0].problem, "same path as two");
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_audit_dangling_symlink() {
+        let (mut db, _file) = create_test_db();
+        let dir = tempdir().unwrap();
+        let link = dir.path().join("link");
+        std::os::unix::fs::symlink(dir.path().join("moved"), &link).unwrap();
+        db.insert(Alias::new("link", link.to_str().unwrap()).unwrap());
+
+        let issues = audit(&db);
+        assert_eq!(issues.len(), 1);
+        assert!(issues[0].problem.starts_with("symlink target moved"));
+    }
+
+    #[test]
+    fn test_path_statuses() {
+        let dir = tempdir().unwrap();
+        let file_path = dir.path().join("file.txt");
+        fs::write(&file_path, "").unwrap();
+        let paths = vec![
+            dir.path().to_string_lossy().into_owned(),
+            dir.path().join("missing").to_string_lossy().into_owned(),
+            file_path.to_string_lossy().into_owned(),
+        ];
+
+        let statuses = path_statuses(&paths, Duration::from_secs(5));
+        assert_eq!(statuses, vec![PathStatus::Ok, PathStatus::Missing, PathStatus::Missing]);
+        assert!(path_statuses(&[], Duration::from_secs(5)).is_empty());
+        assert!(PathStatus::Missing < PathStatus::Ok);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_path_status_broken_symlink() {
+        let dir = tempdir().unwrap();
+        let link = dir.path().join("link");
+        std::os::unix::fs::symlink(dir.path().join("moved"), &link).unwrap();
+        assert_eq!(path_status(link.to_str().unwrap()), PathStatus::SymlinkBroken);
+    }
+
+    #[test]
+    fn test_audit_links() {
+        let (mut db, _file) = create_test_db();
+        let dir = tempdir().unwrap();
+        let path = dir.path().to_str().unwrap();
+        db.insert(Alias::new("project", path).unwrap());
+        for (name, link) in [("current", "project"), ("old", "gone")] {
+            let mut alias = Alias::new(name, path).unwrap();
+            alias.link = Some(link.to_string());
+            db.insert(alias);
+        }
+
+        // A link isn't a duplicate of the alias it stands for
+        let issues = audit(&db);
+        assert_eq!(issues.len(), 1);
+        assert_eq!(issues[0].alias, "old");
+        assert_eq!(issues[0].problem, "alias 'gone' not found (linked from 'old')");
+    }
+
+    #[test]
+    fn test_audit_groups_unmounted_volumes() {
+        let (mut db, _file) = create_test_db();
+        let volume = Volume {
+            mount_point: "/nonexistent/goto/usb".to_string(),
+            uuid: Some("1234-ABCD".to_string()),
+            label: Some("backup".to_string()),
+            source: None,
+        };
+        for name in ["music", "photos", "video"] {
+            let mut alias = Alias::new(name, &format!("{}/{}", volume.mount_point, name)).unwrap();
+            alias.volume = Some(volume.clone());
+            db.insert(alias);
+        }
+
+        let issues = audit(&db);
+        assert_eq!(issues.len(), 1);
+        assert_eq!(issues[0].severity, Severity::Warning);
+        assert_eq!(issues[0].alias, "music, photos, video");
+        assert_eq!(issues[0].problem, "3 aliases live on unmounted volume LABEL=backup");
+        assert_eq!(issues[0].fix, "mount LABEL=backup at /nonexistent/goto/usb");
+
+        let aliases: Vec<Alias> = db.all().cloned().collect();
+        let statuses = alias_statuses(&aliases, Duration::from_secs(5));
+        assert!(statuses.iter().all(|s| *s == PathStatus::Unmounted));
+    }
+
+    #[test]
+    fn test_check_fails_with_problems() {
+        let (mut db, _file) = create_test_db();
+        db.insert(Alias::new("gone", "/nonexistent/goto/check").unwrap());
+
+        let issues = audit(&db);
+        assert_eq!(failure_message(&issues), "check failed: 1 error, 0 warnings");
+        assert!(format_issues(&issues, TableStyle::Minimal).contains("gone"));
+    }
+}
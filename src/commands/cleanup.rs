@@ -1,65 +1,124 @@
 //! Cleanup commands
 
-use std::path::Path;
+use std::time::Duration;
 
+use crate::alias::Alias;
+use crate::commands::check::{alias_statuses, PathStatus};
 use crate::config::Config;
 use crate::database::Database;
 use crate::table::{create_table, TableStyle};
 
+/// What [`cleanup`] found
+#[derive(Debug, Clone, Default)]
+pub struct CleanupResult {
+    /// Aliases whose directory is definitely gone (removed unless dry-run)
+    pub removed: Vec<Alias>,
+    /// Aliases whose path didn't answer in time or gave an I/O error; kept,
+    /// since the directory may just be on an unreachable mount
+    pub unreachable: Vec<Alias>,
+    /// Aliases whose directory is missing because its volume isn't mounted; kept
+    pub unmounted: Vec<Alias>,
+}
+
 /// Remove aliases with invalid (non-existent) paths
-/// If dry_run is true, only lists invalid aliases without removing them
-pub fn cleanup(db: &mut Database, config: &Config, dry_run: bool) -> Result<(), Box<dyn std::error::Error>> {
-    let invalid: Vec<String> = db
+///
+/// Paths are checked in parallel, each for at most
+/// `general.path_timeout_ms`. If dry_run is true, invalid aliases are only
+/// found, not removed.
+pub fn cleanup(db: &mut Database, config: &Config, dry_run: bool) -> Result<CleanupResult, Box<dyn std::error::Error>> {
+    // Pending aliases whose directory has appeared become ordinary ones;
+    // those still waiting for it are kept
+    if !dry_run {
+        let appeared: Vec<String> = db
+            .all()
+            .filter(|a| a.pending && !a.is_pending())
+            .map(|a| a.name.clone())
+            .collect();
+        for name in &appeared {
+            if let Some(alias) = db.get_mut(name) {
+                alias.pending = false;
+            }
+        }
+        if !appeared.is_empty() {
+            db.save()?;
+        }
+    }
+
+    // Shared aliases are read-only; their owners clean them up
+    let mut candidates: Vec<Alias> = db
         .all()
-        .filter(|a| !Path::new(&a.path).exists())
-        .map(|a| a.name.clone())
+        .filter(|a| !a.pending && !db.is_shared(&a.name))
+        .cloned()
         .collect();
-
-    if invalid.is_empty() {
-        println!("All aliases point to valid paths.");
-        return Ok(());
+    candidates.sort_by(|a, b| a.name.cmp(&b.name));
+
+    // Links go with the alias they stand for, unless that is gone
+    let mut result = CleanupResult::default();
+    let (links, candidates): (Vec<Alias>, Vec<Alias>) = candidates.into_iter().partition(|a| a.link.is_some());
+    result.removed.extend(links.into_iter().filter(|a| db.resolve(&a.name).is_err()));
+
+    let timeout = Duration::from_millis(config.user.general.path_timeout_ms);
+    let statuses = alias_statuses(&candidates, timeout);
+    for (alias, status) in candidates.into_iter().zip(statuses) {
+        match status {
+            PathStatus::Missing | PathStatus::SymlinkBroken => result.removed.push(alias),
+            PathStatus::Unreachable => result.unreachable.push(alias),
+            PathStatus::Unmounted => result.unmounted.push(alias),
+            PathStatus::Unreadable | PathStatus::Ok => {}
+        }
     }
+    result.removed.sort_by(|a, b| a.name.cmp(&b.name));
 
-    if dry_run {
-        println!("Would remove {} aliases with invalid paths (dry-run):", invalid.len());
-    } else {
-        println!("Removing {} aliases with invalid paths:", invalid.len());
+    if !dry_run && !result.removed.is_empty() {
+        for alias in &result.removed {
+            db.remove(&alias.name);
+        }
+        db.save()?;
+        // Reset prune cache since stale aliases are now cleaned
+        let _ = crate::commands::prune::reset_cache(config);
     }
 
-    let style = TableStyle::from(config.user.display.table_style.as_str());
+    Ok(result)
+}
+
+/// Render the aliases removed by [`cleanup`] as a table
+pub fn format_removed(removed: &[Alias], style: TableStyle) -> String {
+    format_aliases(removed, "Path does not exist", style)
+}
+
+/// Render the aliases [`cleanup`] couldn't reach as a table
+pub fn format_unreachable(unreachable: &[Alias], style: TableStyle) -> String {
+    format_aliases(unreachable, "Path did not respond", style)
+}
+
+/// Render the aliases [`cleanup`] kept for unmounted volumes as a table
+pub fn format_unmounted(unmounted: &[Alias], style: TableStyle) -> String {
     let mut table = create_table(style);
     table.set_header(vec!["Name", "Path", "Status"]);
 
-    for name in &invalid {
-        if let Some(alias) = db.get(name) {
-            table.add_row(vec![
-                name.clone(),
-                alias.path.clone(),
-                "Path does not exist".to_string(),
-            ]);
-        }
-        if !dry_run {
-            db.remove(name);
-        }
+    for alias in unmounted {
+        let volume = alias.volume.as_ref().map(|v| v.to_string()).unwrap_or_default();
+        table.add_row(vec![alias.name.clone(), alias.path.clone(), format!("Volume {} not mounted", volume)]);
     }
 
-    println!("{}", table);
+    table.to_string()
+}
 
-    if !dry_run {
-        db.save()?;
-        // Reset prune cache since stale aliases are now cleaned
-        let _ = crate::commands::prune::reset_cache(config);
-        println!("Cleanup complete.");
+fn format_aliases(aliases: &[Alias], status: &str, style: TableStyle) -> String {
+    let mut table = create_table(style);
+    table.set_header(vec!["Name", "Path", "Status"]);
+
+    for alias in aliases {
+        table.add_row(vec![alias.name.clone(), alias.path.clone(), status.to_string()]);
     }
 
-    Ok(())
+    table.to_string()
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::alias::Alias;
-    use crate::config::Config;
+    use crate::volume::Volume;
     use tempfile::{NamedTempFile, TempDir};
 
     fn create_test_db() -> (Database, NamedTempFile) {
@@ -90,8 +149,10 @@ mod tests {
         db.insert(Alias::new("valid", temp_dir.path().to_str().unwrap()).unwrap());
         db.insert(Alias::new("invalid", "/nonexistent/path/12345").unwrap());
 
-        let result = cleanup(&mut db, &config, false);
-        assert!(result.is_ok());
+        let removed = cleanup(&mut db, &config, false).unwrap().removed;
+        assert_eq!(removed.len(), 1);
+        assert_eq!(removed[0].name, "invalid");
+        assert!(format_removed(&removed, TableStyle::Minimal).contains("/nonexistent/path/12345"));
         assert!(db.contains("valid"));
         assert!(!db.contains("invalid"));
     }
@@ -105,18 +166,87 @@ mod tests {
         db.insert(Alias::new("valid", temp_dir.path().to_str().unwrap()).unwrap());
         db.insert(Alias::new("invalid", "/nonexistent/path/12345").unwrap());
 
-        let result = cleanup(&mut db, &config, true);
-        assert!(result.is_ok());
+        let removed = cleanup(&mut db, &config, true).unwrap().removed;
+        assert_eq!(removed.len(), 1);
         // Both should still exist after dry-run
         assert!(db.contains("valid"));
         assert!(db.contains("invalid"));
     }
 
+    #[test]
+    fn test_cleanup_keeps_pending_until_directory_appears() {
+        let (mut db, _file) = create_test_db();
+        let config = Config::load().unwrap();
+        let temp_dir = TempDir::new().unwrap();
+        let later = temp_dir.path().join("later");
+
+        let mut alias = Alias::new("later", later.to_str().unwrap()).unwrap();
+        alias.pending = true;
+        db.insert(alias);
+
+        cleanup(&mut db, &config, false).unwrap();
+        assert!(db.get("later").unwrap().pending);
+
+        // Once the directory has appeared the alias is an ordinary one
+        std::fs::create_dir(&later).unwrap();
+        cleanup(&mut db, &config, false).unwrap();
+        assert!(!db.get("later").unwrap().pending);
+
+        std::fs::remove_dir(&later).unwrap();
+        cleanup(&mut db, &config, false).unwrap();
+        assert!(!db.contains("later"));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_cleanup_removes_broken_symlink() {
+        let (mut db, _file) = create_test_db();
+        let config = Config::load().unwrap();
+        let temp_dir = TempDir::new().unwrap();
+        let link = temp_dir.path().join("link");
+        std::os::unix::fs::symlink(temp_dir.path().join("moved"), &link).unwrap();
+        db.insert(Alias::new("link", link.to_str().unwrap()).unwrap());
+
+        let result = cleanup(&mut db, &config, false).unwrap();
+        assert_eq!(result.removed.len(), 1);
+        assert!(result.unreachable.is_empty());
+        assert!(!db.contains("link"));
+    }
+
+    #[test]
+    fn test_cleanup_keeps_aliases_on_unmounted_volumes() {
+        let (mut db, _file) = create_test_db();
+        let config = Config::load().unwrap();
+
+        let mut alias = Alias::new("photos", "/nonexistent/goto/usb/photos").unwrap();
+        alias.volume = Some(Volume {
+            mount_point: "/nonexistent/goto/usb".to_string(),
+            uuid: None,
+            label: Some("backup".to_string()),
+            source: Some("/dev/sdb1".to_string()),
+        });
+        db.insert(alias);
+
+        let result = cleanup(&mut db, &config, false).unwrap();
+        assert!(result.removed.is_empty());
+        assert_eq!(result.unmounted.len(), 1);
+        assert!(db.contains("photos"));
+        assert!(format_unmounted(&result.unmounted, TableStyle::Minimal).contains("LABEL=backup not mounted"));
+    }
+
+    #[test]
+    fn test_format_unreachable() {
+        let alias = Alias::new("nfs", "/mnt/nfs/project").unwrap();
+        let out = format_unreachable(&[alias], TableStyle::Minimal);
+        assert!(out.contains("/mnt/nfs/project"));
+        assert!(out.contains("did not respond"));
+    }
+
     #[test]
     fn test_cleanup_empty() {
         let (mut db, _file) = create_test_db();
         let config = Config::load().unwrap();
-        let result = cleanup(&mut db, &config, false);
-        assert!(result.is_ok());
+        let result = cleanup(&mut db, &config, false).unwrap();
+        assert!(result.removed.is_empty() && result.unreachable.is_empty());
     }
 }
@@ -0,0 +1,193 @@
+//! Clone a repository into the projects root and register it as an alias
+
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::process;
+
+use crate::alias::{validate_tag, Alias, AliasError};
+use crate::commands::register::validate_and_normalize_tags;
+use crate::config::{expand_path_with, Config};
+use crate::context::Ctx;
+use crate::database::Database;
+use crate::exit::Failure;
+
+/// Where a repository URL lives: its host and path segments (owner(s), then repo)
+#[derive(Debug, Clone, PartialEq)]
+pub struct RepoLocation {
+    pub host: String,
+    pub segments: Vec<String>,
+}
+
+impl RepoLocation {
+    /// Parse an `https://`, `ssh://`, `file://` or scp-style (`git@host:org/repo`) URL
+    pub fn parse(url: &str) -> Result<Self, Failure> {
+        let invalid = || Failure::invalid(format!("invalid repository URL '{}'", url));
+        let trimmed = url.trim().trim_end_matches('/');
+        let trimmed = trimmed.strip_suffix(".git").unwrap_or(trimmed);
+
+        let (host, path) = if let Some((scheme, rest)) = trimmed.split_once("://") {
+            if scheme == "file" {
+                ("local", rest)
+            } else {
+                let (authority, path) = rest.split_once('/').ok_or_else(invalid)?;
+                let host = authority.rsplit('@').next().unwrap_or(authority);
+                (host.split(':').next().unwrap_or(host), path)
+            }
+        } else {
+            // scp-like syntax: [user@]host:path
+            let (authority, path) = trimmed.split_once(':').ok_or_else(invalid)?;
+            if authority.contains('/') {
+                return Err(invalid());
+            }
+            (authority.rsplit('@').next().unwrap_or(authority), path)
+        };
+
+        let segments: Vec<String> = path
+            .split('/')
+            .filter(|s| !s.is_empty())
+            .map(String::from)
+            .collect();
+        if host.is_empty() || segments.is_empty() || segments.iter().any(|s| s == "." || s == "..") {
+            return Err(invalid());
+        }
+
+        Ok(Self {
+            host: host.to_string(),
+            segments,
+        })
+    }
+
+    /// Repository name (last path segment)
+    pub fn name(&self) -> &str {
+        self.segments.last().map(String::as_str).unwrap_or_default()
+    }
+
+    /// Namespace directly containing the repository (user, org or subgroup)
+    pub fn owner(&self) -> Option<&str> {
+        let len = self.segments.len();
+        (len > 1).then(|| self.segments[len - 2].as_str())
+    }
+
+    /// `<host>/<owner>/<repo>` path below the projects root
+    pub fn relative_path(&self) -> PathBuf {
+        let mut path = PathBuf::from(&self.host);
+        path.extend(&self.segments);
+        path
+    }
+}
+
+/// Clone `url` into `dest` with git, creating parent directories
+///
+/// git's own output goes to stderr so stdout stays free for the path the
+/// shell wrapper changes into.
+pub fn git_clone(ctx: &mut Ctx, url: &str, dest: &Path) -> Result<(), Box<dyn std::error::Error>> {
+    if let Some(parent) = dest.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    crate::enote!(ctx, "Cloning {} into {}", url, dest.display());
+
+    let status = process::Command::new("git")
+        .arg("clone")
+        .arg(url)
+        .arg(dest)
+        .stdout(io::stderr())
+        .status()
+        .map_err(|e| format!("failed to run git: {}", e))?;
+    if !status.success() {
+        return Err(format!("git clone of {} failed", url).into());
+    }
+    Ok(())
+}
+
+/// Clone a repository under the projects root and register it
+///
+/// Returns the new alias; the CLI changes into its directory.
+/// The alias defaults to the repository name. With `projects.auto_tags` the
+/// alias is tagged with the repository owner. An existing checkout at the
+/// target is registered without cloning again.
+pub fn clone(
+    ctx: &mut Ctx,
+    db: &mut Database,
+    config: &Config,
+    url: &str,
+    alias: Option<&str>,
+    tags: &[String],
+) -> Result<Alias, Box<dyn std::error::Error>> {
+    let repo = RepoLocation::parse(url)?;
+
+    let name = alias.unwrap_or(repo.name());
+    db.check_name(name).map_err(|e| match alias {
+        Some(_) => e.to_string(),
+        None => format!("{} (pass one: goto --clone <url> <alias>)", e),
+    })?;
+    if db.get(name).is_some() {
+        return Err(AliasError::AlreadyExists(name.to_string()).into());
+    }
+
+    let mut all_tags = tags.to_vec();
+    if config.user.projects.auto_tags {
+        if let Some(owner) = repo.owner().map(str::to_lowercase) {
+            // Owners that don't make valid tags (e.g. containing dots) are skipped
+            if validate_tag(&owner).is_ok() {
+                all_tags.push(owner);
+            }
+        }
+    }
+    let tags = validate_and_normalize_tags(&all_tags)?;
+
+    let target = config.projects_root()?.join(repo.relative_path());
+    if target.exists() {
+        crate::enote!(ctx, "{} already exists, skipping clone", target.display());
+    } else {
+        git_clone(ctx, url, &target)?;
+    }
+
+    let path = expand_path_with(&target.to_string_lossy(), config.user.general.resolve_symlinks)?;
+    let path_str = path.to_string_lossy().into_owned();
+    db.add_with_tags(Alias::new(name, &path_str)?, tags)?;
+    db.save()?;
+
+    db.get(name).cloned().ok_or_else(|| AliasError::NotFound(name.to_string()).into())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn location(host: &str, segments: &[&str]) -> RepoLocation {
+        RepoLocation {
+            host: host.to_string(),
+            segments: segments.iter().map(|s| s.to_string()).collect(),
+        }
+    }
+
+    #[test]
+    fn test_parse_https_and_ssh_urls() {
+        let expected = location("github.com", &["rust-lang", "cargo"]);
+        assert_eq!(RepoLocation::parse("https://github.com/rust-lang/cargo").unwrap(), expected);
+        assert_eq!(RepoLocation::parse("https://github.com/rust-lang/cargo.git/").unwrap(), expected);
+        assert_eq!(RepoLocation::parse("git@github.com:rust-lang/cargo.git").unwrap(), expected);
+        assert_eq!(RepoLocation::parse("ssh://git@github.com:22/rust-lang/cargo.git").unwrap(), expected);
+    }
+
+    #[test]
+    fn test_parse_nested_groups_and_file_urls() {
+        let repo = RepoLocation::parse("https://gitlab.com/group/sub/project").unwrap();
+        assert_eq!(repo.name(), "project");
+        assert_eq!(repo.owner(), Some("sub"));
+        assert_eq!(repo.relative_path(), PathBuf::from("gitlab.com/group/sub/project"));
+
+        let repo = RepoLocation::parse("file:///srv/git/tool.git").unwrap();
+        assert_eq!(repo, location("local", &["srv", "git", "tool"]));
+        assert_eq!(repo.owner(), Some("git"));
+    }
+
+    #[test]
+    fn test_parse_invalid_urls() {
+        assert!(RepoLocation::parse("not a url").is_err());
+        assert!(RepoLocation::parse("https://github.com").is_err());
+        assert!(RepoLocation::parse("https://github.com/org/../etc").is_err());
+        assert!(RepoLocation::parse("./relative/path").is_err());
+    }
+}
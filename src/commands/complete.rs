@@ -0,0 +1,285 @@
+//! Shell completion protocol: `goto --complete -- <words...>`
+//!
+//! The words are the command line after `goto`, up to the cursor; the last
+//! one is the word being completed (empty after a space). Candidates are
+//! whole words, so shells that split on `=` or `@` must trim what they
+//! consider already typed. With `display.completion_hints`, an alias whose
+//! directory is gone is followed by a tab and its status.
+
+use std::fs;
+use std::path::PathBuf;
+use std::time::Duration;
+
+use crate::alias::Alias;
+use crate::collate;
+use crate::commands::check::{alias_statuses, PathStatus};
+use crate::database::Database;
+
+/// Flags offered when completing a word starting with `-`
+const FLAGS: &[&str] = &[
+    "-r", "--register", "-u", "--unregister", "-l", "--list", "-x", "--expand", "-p", "--push",
+    "-o", "--pop", "-c", "--cleanup", "-R", "--recent", "-s", "--stats", "-T", "--tags",
+    "-e", "--export", "-i", "--import", "-h", "--help", "-v", "--version",
+    "--clone", "--new", "--pick", "--random", "--where", "--counter", "--prompt-snippet", "--search", "--preview", "--explain", "--note", "--env", "--check",
+    "--diff", "--duplicates", "--report", "--stack", "--rename", "--tag", "--untag", "--rename-tag", "--delete-tag", "--rewrite-prefix",
+    "--tag-all", "--tag-info", "--recent-clear", "--reset-stats", "--set-count", "--backups", "--restore", "--fsck",
+    "--config", "--profiles", "--watch", "--unwatch", "--suggestions", "--learn-from-history", "--review", "--absolute-dates",
+    "--quiet", "--no-input",
+    "--template=", "--filter=", "--sort=", "--columns=", "--group-by=", "--dry-run", "--force", "--weighted", "--validate",
+];
+
+/// Values of `--sort=`
+const SORT_ORDERS: &[&str] = &["alpha", "usage", "recent", "status"];
+
+/// Criteria of `--review`
+const REVIEW_CRITERIA: &[&str] = &["all", "dormant", "duplicate", "missing", "untagged"];
+
+/// How long a directory may take to answer [`complete_with_hints`]; pressing
+/// tab mustn't hang on a slow mount
+pub const HINT_TIMEOUT: Duration = Duration::from_millis(100);
+
+/// Completion candidates for the last of `words`
+pub fn complete(db: &Database, words: &[String]) -> Vec<String> {
+    match candidates(db, words) {
+        Candidates::Aliases(names) | Candidates::Other(names) => names,
+    }
+}
+
+/// [`complete`], with a hint after aliases whose directory is gone
+///
+/// Such aliases are printed as `<name>\t<status>` (`missing` or
+/// `symlink-broken`), the tab-separated description zsh and fish show next to
+/// a candidate. Directories that don't answer within `timeout` are left
+/// unmarked, as are those on unmounted volumes.
+pub fn complete_with_hints(db: &Database, words: &[String], timeout: Duration) -> Vec<String> {
+    let names = match candidates(db, words) {
+        Candidates::Aliases(names) => names,
+        Candidates::Other(other) => return other,
+    };
+    let aliases: Vec<Alias> = names.iter().filter_map(|n| db.resolve(n).ok().or_else(|| db.get(n))).cloned().collect();
+    if aliases.len() != names.len() {
+        return names;
+    }
+    names
+        .into_iter()
+        .zip(alias_statuses(&aliases, timeout))
+        .map(|(name, status)| match status {
+            PathStatus::Missing | PathStatus::SymlinkBroken => format!("{}\t{}", name, status.label()),
+            _ => name,
+        })
+        .collect()
+}
+
+/// Candidates for a word; alias names are kept apart for [`complete_with_hints`]
+enum Candidates {
+    Aliases(Vec<String>),
+    Other(Vec<String>),
+}
+
+fn candidates(db: &Database, words: &[String]) -> Candidates {
+    use Candidates::{Aliases, Other};
+
+    let Some((current, before)) = words.split_last() else {
+        return Aliases(alias_names(db, ""));
+    };
+    let current = current.as_str();
+
+    if let Some(prefix) = current.strip_prefix("--filter=") {
+        return Other(with_prefix("--filter=", tags(db, prefix)));
+    }
+    if let Some(prefix) = current.strip_prefix("--sort=") {
+        let orders = SORT_ORDERS.iter().filter(|o| o.starts_with(prefix)).map(|o| o.to_string());
+        return Other(with_prefix("--sort=", orders.collect()));
+    }
+    if current.starts_with('-') {
+        return Other(FLAGS.iter().filter(|f| f.starts_with(current)).map(|f| f.to_string()).collect());
+    }
+
+    // Tags after -t and review criteria are comma-separated lists; complete
+    // the last item
+    let (done, prefix) = match current.rfind(',') {
+        Some(i) => current.split_at(i + 1),
+        None => ("", current),
+    };
+    if before.last().is_some_and(|w| w == "-t") {
+        return Other(with_prefix(done, tags(db, prefix)));
+    }
+    if before.len() == 1 && before[0] == "--review" {
+        let criteria = REVIEW_CRITERIA.iter().filter(|c| c.starts_with(prefix)).map(|c| c.to_string());
+        return Other(with_prefix(done, criteria.collect()));
+    }
+
+    // Position among the positional arguments following the command flag
+    let Some(command) = before.first() else {
+        return Aliases(alias_names(db, current));
+    };
+    let position = before[1..].iter().filter(|w| !w.starts_with('-')).count() + 1;
+
+    match (command.as_str(), position) {
+        ("-r" | "--register" | "--new", 2) | ("--watch" | "--unwatch", 1) => Other(paths(current, true)),
+        ("-r" | "--register" | "--clone" | "--new", _) => Other(Vec::new()),
+        ("-i" | "--import", 1) | ("--diff", 1 | 2) => Other(paths(current, false)),
+        ("--tag", 2) => Other(tags(db, current)),
+        ("--untag", 2) => Other(
+            db.get(&before[1])
+                .map(|alias| alias.tags.iter().filter(|t| t.starts_with(current)).cloned().collect())
+                .unwrap_or_default(),
+        ),
+        ("--rename-tag" | "--delete-tag" | "--tag-all" | "--tag-info", 1) => Other(tags(db, current)),
+        (
+            "-u" | "--unregister" | "-x" | "--expand" | "-p" | "--push" | "--preview" | "--note"
+            | "--env" | "--rename" | "--tag" | "--untag" | "--reset-stats" | "--set-count",
+            1,
+        ) => Aliases(alias_names(db, current)),
+        _ => Other(Vec::new()),
+    }
+}
+
+/// Sorted alias names starting with `prefix`
+fn alias_names(db: &Database, prefix: &str) -> Vec<String> {
+    let mut names: Vec<String> = db.names().filter(|n| n.starts_with(prefix)).map(String::from).collect();
+    names.sort_by(|a, b| collate::compare(a, b));
+    names
+}
+
+/// Sorted tags starting with `prefix`
+fn tags(db: &Database, prefix: &str) -> Vec<String> {
+    db.all_tags().into_iter().filter(|t| t.starts_with(prefix)).collect()
+}
+
+fn with_prefix(prefix: &str, values: Vec<String>) -> Vec<String> {
+    values.into_iter().map(|v| format!("{}{}", prefix, v)).collect()
+}
+
+/// Entries of the directory named by `partial` that start with its last
+/// component; directories end in `/`
+///
+/// A leading `~` is expanded for reading but kept in the candidates. Hidden
+/// entries are only offered once the component starts with a dot.
+fn paths(partial: &str, dirs_only: bool) -> Vec<String> {
+    let (dir, prefix) = match partial.rfind('/') {
+        Some(i) => partial.split_at(i + 1),
+        None => ("", partial),
+    };
+    let base = if dir.is_empty() {
+        PathBuf::from(".")
+    } else if let Some(rest) = dir.strip_prefix('~') {
+        match dirs::home_dir() {
+            Some(home) => home.join(rest.trim_start_matches('/')),
+            None => return Vec::new(),
+        }
+    } else {
+        PathBuf::from(dir)
+    };
+
+    let Ok(entries) = fs::read_dir(base) else {
+        return Vec::new();
+    };
+    let mut candidates: Vec<String> = entries
+        .filter_map(|e| e.ok())
+        .filter_map(|e| {
+            let name = e.file_name().to_string_lossy().into_owned();
+            if !name.starts_with(prefix) || (name.starts_with('.') && !prefix.starts_with('.')) {
+                return None;
+            }
+            if e.path().is_dir() {
+                Some(format!("{}{}/", dir, name))
+            } else {
+                (!dirs_only).then(|| format!("{}{}", dir, name))
+            }
+        })
+        .collect();
+    candidates.sort_by(|a, b| collate::compare(a, b));
+    candidates
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::{tempdir, NamedTempFile};
+
+    fn create_test_db() -> (Database, NamedTempFile) {
+        let file = NamedTempFile::new().unwrap();
+        let mut db = Database::load_from_path(file.path()).unwrap();
+        let mut api = Alias::new("api", "/srv/api").unwrap();
+        api.add_tag("work");
+        api.add_tag("rust");
+        db.insert(api);
+        db.insert(Alias::new("apps", "/srv/apps").unwrap());
+        db.insert(Alias::new("docs", "/srv/docs").unwrap());
+        (db, file)
+    }
+
+    fn words(words: &[&str]) -> Vec<String> {
+        words.iter().map(|w| w.to_string()).collect()
+    }
+
+    #[test]
+    fn test_complete_aliases() {
+        let (db, _file) = create_test_db();
+        assert_eq!(complete(&db, &words(&["ap"])), vec!["api", "apps"]);
+        assert_eq!(complete(&db, &[]), vec!["api", "apps", "docs"]);
+        assert_eq!(complete(&db, &words(&["-u", "d"])), vec!["docs"]);
+        assert_eq!(complete(&db, &words(&["--tag", ""])).len(), 3);
+        assert!(complete(&db, &words(&["-u", "docs", ""])).is_empty());
+    }
+
+    #[test]
+    fn test_complete_tags() {
+        let (db, _file) = create_test_db();
+        assert_eq!(complete(&db, &words(&["-l", "--filter=w"])), vec!["--filter=work"]);
+        assert_eq!(complete(&db, &words(&["--tag", "docs", "r"])), vec!["rust"]);
+        assert_eq!(complete(&db, &words(&["--untag", "api", ""])), vec!["rust", "work"]);
+        assert_eq!(complete(&db, &words(&["-r", "x", ".", "-t", "rust,w"])), vec!["rust,work"]);
+        assert_eq!(complete(&db, &words(&["-l", "--sort=u"])), vec!["--sort=usage"]);
+        assert_eq!(complete(&db, &words(&["--review", "missing,d"])), vec!["missing,dormant", "missing,duplicate"]);
+    }
+
+    #[test]
+    fn test_complete_flags() {
+        let (db, _file) = create_test_db();
+        let flags = complete(&db, &words(&["--un"]));
+        assert_eq!(flags, vec!["--unregister", "--untag", "--unwatch"]);
+    }
+
+    #[test]
+    fn test_complete_register_directory() {
+        let (db, _file) = create_test_db();
+        let dir = tempdir().unwrap();
+        fs::create_dir(dir.path().join("project")).unwrap();
+        fs::create_dir(dir.path().join(".hidden")).unwrap();
+        fs::write(dir.path().join("promo.txt"), "").unwrap();
+        let base = format!("{}/", dir.path().display());
+
+        // The new name gets nothing, the directory gets directories only
+        assert!(complete(&db, &words(&["-r", "pro"])).is_empty());
+        let partial = format!("{}pro", base);
+        assert_eq!(complete(&db, &words(&["-r", "new", &partial])), vec![format!("{}project/", base)]);
+        assert_eq!(complete(&db, &words(&["-r", "new", &base])), vec![format!("{}project/", base)]);
+
+        let partial = format!("{}.h", base);
+        assert_eq!(complete(&db, &words(&["-r", "new", &partial])), vec![format!("{}.hidden/", base)]);
+
+        // --import also offers files
+        let partial = format!("{}pro", base);
+        assert_eq!(complete(&db, &words(&["--import", &partial])).len(), 2);
+        assert_eq!(complete(&db, &words(&["--diff", "a.toml", &partial])).len(), 2);
+    }
+
+    #[test]
+    fn test_complete_with_hints() {
+        let file = NamedTempFile::new().unwrap();
+        let mut db = Database::load_from_path(file.path()).unwrap();
+        let dir = tempdir().unwrap();
+        db.insert(Alias::new("here", &dir.path().to_string_lossy()).unwrap());
+        db.insert(Alias::new("gone", &dir.path().join("gone").to_string_lossy()).unwrap());
+        db.add_tag("gone", "here").unwrap();
+        let timeout = Duration::from_secs(5);
+
+        assert_eq!(complete_with_hints(&db, &[], timeout), vec!["gone\tmissing", "here"]);
+        assert_eq!(complete_with_hints(&db, &words(&["-x", "g"]), timeout), vec!["gone\tmissing"]);
+        // Tags named like an alias aren't aliases
+        assert_eq!(complete_with_hints(&db, &words(&["-l", "--filter=h"]), timeout), vec!["--filter=here"]);
+        assert_eq!(complete(&db, &[]), vec!["gone", "here"]);
+    }
+}
@@ -1,10 +1,386 @@
-//! Config command: show_config
+//! Config commands: list_profiles, check_config
 
-use crate::config::Config;
+use std::fmt;
+use std::fs;
+use std::io;
+use std::ops::Range;
+use std::path::Path;
 
-/// Show the current configuration
-pub fn show_config(config: &Config) {
-    print!("{}", config.format_config());
+use toml_edit::{ImDocument, Item, Key, TableLike, Value};
+
+use crate::config::{profile_names, Config, ProjectTemplate, UserConfig};
+use crate::database::Database;
+use crate::fuzzy;
+use crate::rules::dynamic::DynamicRule;
+use crate::table;
+
+/// A profile as listed by [`list_profiles`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ProfileSummary {
+    /// Profile name, `None` for the default profile
+    pub name: Option<String>,
+    /// Number of aliases in the profile's database
+    pub aliases: usize,
+    /// Whether this is the profile in use
+    pub active: bool,
+}
+
+impl fmt::Display for ProfileSummary {
+    /// `* work (3 aliases)`, the active profile marked with `*`
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} {} ({} alias{})",
+            if self.active { "*" } else { " " },
+            self.name.as_deref().unwrap_or("default"),
+            self.aliases,
+            if self.aliases == 1 { "" } else { "es" }
+        )
+    }
+}
+
+/// The default profile and every named profile with alias counts
+pub fn list_profiles(config: &Config) -> Result<Vec<ProfileSummary>, Box<dyn std::error::Error>> {
+    let mut profiles = vec![None];
+    profiles.extend(profile_names()?.into_iter().map(Some));
+
+    profiles
+        .into_iter()
+        .map(|name| {
+            let profile = Config::load_profile(name.as_deref())?;
+            Ok(ProfileSummary {
+                aliases: Database::load_from_path(&profile.aliases_path)?.len(),
+                active: config.profile == name,
+                name,
+            })
+        })
+        .collect()
+}
+
+/// How much a problem in config.toml matters
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Severity {
+    /// The setting is read, but doesn't do what it says (conflicting options)
+    Warning,
+    /// The setting is ignored, or the file doesn't load at all
+    Error,
+}
+
+impl Severity {
+    pub fn label(&self) -> &'static str {
+        match self {
+            Severity::Warning => "warning",
+            Severity::Error => "error",
+        }
+    }
+}
+
+/// A problem [`check_config`] found in config.toml
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConfigProblem {
+    /// Line of the offending key or value (1-based), when known
+    pub line: Option<usize>,
+    pub severity: Severity,
+    pub message: String,
+}
+
+/// Values string settings accept (in any case), as `(section, key, values)`
+const CHOICES: &[(&str, &str, &[&str])] = &[
+    ("general", "default_sort", &["alpha", "usage", "recent", "status"]),
+    ("general", "picker", &["fzf", "builtin"]),
+    ("display", "table_style", &["unicode", "ascii", "minimal", "none"]),
+    ("display", "path_style", &["full", "home", "short"]),
+    ("display", "truncate_paths", &["middle", "end", "off", "none"]),
+    ("display", "color", &["auto", "always", "never"]),
+    ("update", "channel", &["stable", "prerelease", "pre", "beta"]),
+];
+
+/// Numeric settings limited to a range, as `(section, key, min, max)`
+const RANGES: &[(&str, &str, f64, f64)] = &[
+    ("general", "fuzzy_threshold", 0.0, 1.0),
+    ("general", "usage_weight", 0.0, 1.0),
+];
+
+/// Settings that do nothing with `privacy.track_usage = false`
+const NEEDS_USAGE: &[(&str, &str)] = &[
+    ("general", "default_sort"),
+    ("general", "usage_weight"),
+    ("display", "show_stats"),
+    ("hygiene", "dormant_after_days"),
+];
+
+/// Check the text of a config.toml, problems sorted by line
+///
+/// Finds syntax errors, unknown sections and keys, values outside what a
+/// setting accepts (which goto would quietly replace by a default) and
+/// options that cancel each other out. Checking is cheap enough to do on
+/// every load; `thorough` also deserializes the file to catch wrong value
+/// types and compiles the `[[rules]]` patterns.
+pub fn check_config(content: &str, thorough: bool) -> Vec<ConfigProblem> {
+    let mut checker = Checker {
+        content,
+        problems: Vec::new(),
+    };
+    let document = match ImDocument::parse(content) {
+        Ok(document) => document,
+        Err(e) => {
+            checker.report(e.span(), Severity::Error, e.message().trim().to_string());
+            return checker.problems;
+        }
+    };
+    let root = document.as_table();
+    let known = toml::Value::try_from(UserConfig::default()).unwrap_or(toml::Value::Boolean(false));
+    let sections = known.as_table().cloned().unwrap_or_default();
+
+    for (name, item) in root.iter() {
+        let key = root.key(name);
+        match name {
+            "rules" => {
+                let rule = DynamicRule {
+                    pattern: String::new(),
+                    path: String::new(),
+                    clone: Some(String::new()),
+                };
+                for table in tables(item) {
+                    checker.keys(table, "[[rules]]", &field_names(&rule));
+                }
+            }
+            "templates" => {
+                let template = ProjectTemplate {
+                    skeleton: Some(String::new()),
+                    init: Some(String::new()),
+                    tags: Some(Vec::new()),
+                };
+                for (template_name, table) in item.as_table_like().into_iter().flat_map(|t| t.iter()) {
+                    if let Some(table) = table.as_table_like() {
+                        let section = format!("[templates.{}]", template_name);
+                        checker.keys(table, &section, &field_names(&template));
+                    }
+                }
+            }
+            "tag_colors" => {
+                for (tag, color) in item.as_table_like().into_iter().flat_map(|t| t.iter()) {
+                    match color.as_str() {
+                        Some(color) if !table::is_color_name(color) => checker.report(
+                            color_span(item, tag),
+                            Severity::Error,
+                            format!("unknown color '{}' for tag '{}' in [tag_colors]", color, tag),
+                        ),
+                        _ => {}
+                    }
+                }
+            }
+            _ => match (sections.get(name).and_then(|v| v.as_table()), item.as_table_like()) {
+                (Some(fields), Some(table)) => {
+                    let fields: Vec<String> = fields.keys().cloned().collect();
+                    checker.keys(table, &format!("[{}]", name), &fields);
+                    checker.values(name, table);
+                }
+                (Some(_), None) => {}
+                (None, _) => {
+                    let what = if item.is_table_like() {
+                        format!("unknown section [{}]", name)
+                    } else {
+                        format!("unknown key '{}'", name)
+                    };
+                    let names: Vec<String> = sections.keys().cloned().collect();
+                    checker.report(span(key, item), Severity::Error, suggest(what, name, &names));
+                }
+            },
+        }
+    }
+    checker.conflicts(root);
+
+    if thorough {
+        match toml::from_str::<UserConfig>(content) {
+            Ok(user) => {
+                let rules = tables(root.get("rules").unwrap_or(&Item::None));
+                for (i, rule) in user.rules.iter().enumerate() {
+                    if let Err(e) = rule.check() {
+                        let span = rules.get(i).and_then(|t| t.get("pattern")).and_then(Item::span);
+                        checker.report(span, Severity::Error, e.message);
+                    }
+                }
+            }
+            Err(e) => checker.report(e.span(), Severity::Error, e.message().trim().to_string()),
+        }
+    }
+
+    checker.problems.sort_by_key(|p| (p.line.is_none(), p.line));
+    checker.problems
+}
+
+/// Check the config file at `path`; a missing file has no problems
+pub fn validate_config(path: &Path) -> io::Result<Vec<ConfigProblem>> {
+    match fs::read_to_string(path) {
+        Ok(content) => Ok(check_config(&content, true)),
+        Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(Vec::new()),
+        Err(e) => Err(e),
+    }
+}
+
+/// The quick check run on every load, every problem demoted to a warning
+///
+/// A config.toml that loaded is usable, so nothing here stops a command.
+pub fn load_warnings(config: &Config) -> Vec<ConfigProblem> {
+    let Ok(content) = fs::read_to_string(&config.config_path) else {
+        return Vec::new();
+    };
+    check_config(&content, false)
+        .into_iter()
+        .map(|p| ConfigProblem {
+            severity: Severity::Warning,
+            ..p
+        })
+        .collect()
+}
+
+/// One `<path>:<line>: <severity>: <message>` line per problem
+pub fn format_problems(path: &Path, problems: &[ConfigProblem]) -> String {
+    problems
+        .iter()
+        .map(|p| match p.line {
+            Some(line) => format!("{}:{}: {}: {}\n", path.display(), line, p.severity.label(), p.message),
+            None => format!("{}: {}: {}\n", path.display(), p.severity.label(), p.message),
+        })
+        .collect()
+}
+
+/// Collects problems, turning byte spans into line numbers
+struct Checker<'a> {
+    content: &'a str,
+    problems: Vec<ConfigProblem>,
+}
+
+impl Checker<'_> {
+    fn report(&mut self, span: Option<Range<usize>>, severity: Severity, message: String) {
+        let line = span.map(|s| self.content[..s.start.min(self.content.len())].matches('\n').count() + 1);
+        self.problems.push(ConfigProblem { line, severity, message });
+    }
+
+    /// Report keys of `table` that aren't among `fields`
+    fn keys(&mut self, table: &dyn TableLike, section: &str, fields: &[String]) {
+        for (name, item) in table.iter() {
+            if !fields.iter().any(|f| f == name) {
+                let what = format!("unknown key '{}' in {}", name, section);
+                self.report(span(table.key(name), item), Severity::Error, suggest(what, name, fields));
+            }
+        }
+    }
+
+    /// Report values of a section's settings that goto doesn't accept
+    fn values(&mut self, section: &str, table: &dyn TableLike) {
+        for (_, key, accepted) in CHOICES.iter().filter(|(s, _, _)| *s == section) {
+            let Some(item) = table.get(key) else { continue };
+            match item.as_str() {
+                Some(value) if !accepted.contains(&value.to_lowercase().as_str()) => self.report(
+                    item.span(),
+                    Severity::Error,
+                    format!(
+                        "invalid {}.{} '{}' (expected {})",
+                        section,
+                        key,
+                        value,
+                        accepted.join(", ")
+                    ),
+                ),
+                _ => {}
+            }
+        }
+        for (_, key, min, max) in RANGES.iter().filter(|(s, _, _, _)| *s == section) {
+            let Some(item) = table.get(key) else { continue };
+            match number(item) {
+                Some(value) if !(*min..=*max).contains(&value) => self.report(
+                    item.span(),
+                    Severity::Error,
+                    format!("{}.{} = {} is out of range ({} to {})", section, key, value, min, max),
+                ),
+                _ => {}
+            }
+        }
+    }
+
+    /// Report options that undo each other, when both are set in the file
+    fn conflicts(&mut self, root: &toml_edit::Table) {
+        let setting = |section: &str, key: &str| -> Option<&Item> {
+            root.get(section).and_then(Item::as_table_like).and_then(|t| t.get(key))
+        };
+
+        if let (Some(enabled), Some(auto_check)) = (setting("update", "enabled"), setting("update", "auto_check")) {
+            if enabled.as_bool() == Some(false) && auto_check.as_bool() == Some(true) {
+                self.report(
+                    auto_check.span(),
+                    Severity::Warning,
+                    "update.auto_check has no effect with update.enabled = false".to_string(),
+                );
+            }
+        }
+
+        if setting("privacy", "track_usage").and_then(Item::as_bool) == Some(false) {
+            for (section, key) in NEEDS_USAGE {
+                if let Some(item) = setting(section, key).filter(|item| needs_usage(item)) {
+                    self.report(
+                        item.span(),
+                        Severity::Warning,
+                        format!("{}.{} has no effect with privacy.track_usage = false", section, key),
+                    );
+                }
+            }
+        }
+    }
+}
+
+/// Span of a key, or of its value when the key has none
+fn span(key: Option<&Key>, item: &Item) -> Option<Range<usize>> {
+    key.and_then(Key::span).or_else(|| item.span())
+}
+
+/// Span of a tag's color in `[tag_colors]`
+fn color_span(item: &Item, tag: &str) -> Option<Range<usize>> {
+    item.as_table_like()
+        .and_then(|t| t.get_key_value(tag))
+        .and_then(|(key, value)| span(Some(key), value))
+}
+
+/// Append "(did you mean 'x'?)" when a known name is close to `name`
+fn suggest(message: String, name: &str, known: &[String]) -> String {
+    match fuzzy::find_similar_names(name, known, 0.6).first() {
+        Some(close) => format!("{} (did you mean '{}'?)", message, close),
+        None => message,
+    }
+}
+
+/// Field names of a serializable struct
+fn field_names(value: &impl serde::Serialize) -> Vec<String> {
+    toml::Value::try_from(value)
+        .ok()
+        .and_then(|v| v.as_table().map(|t| t.keys().cloned().collect()))
+        .unwrap_or_default()
+}
+
+/// The tables of `[[x]]` or `x = [{ ... }]`
+fn tables(item: &Item) -> Vec<&dyn TableLike> {
+    if let Some(array) = item.as_array_of_tables() {
+        array.iter().map(|t| t as &dyn TableLike).collect()
+    } else if let Some(array) = item.as_array() {
+        array.iter().filter_map(Value::as_inline_table).map(|t| t as &dyn TableLike).collect()
+    } else {
+        Vec::new()
+    }
+}
+
+/// An integer or float setting as a float
+fn number(item: &Item) -> Option<f64> {
+    item.as_float().or_else(|| item.as_integer().map(|i| i as f64))
+}
+
+/// Whether a [`NEEDS_USAGE`] setting is set to something usage tracking drives
+fn needs_usage(item: &Item) -> bool {
+    if let Some(sort) = item.as_str() {
+        matches!(sort.to_lowercase().as_str(), "usage" | "recent")
+    } else {
+        item.as_bool().unwrap_or(false) || number(item).is_some_and(|n| n > 0.0)
+    }
 }
 
 #[cfg(test)]
@@ -12,9 +388,88 @@ mod tests {
     use super::*;
 
     #[test]
-    fn test_show_config_does_not_panic() {
-        let config = Config::load().unwrap();
-        // Just verify it doesn't panic
-        show_config(&config);
+    fn test_profile_summary_display() {
+        let mut profile = ProfileSummary {
+            name: None,
+            aliases: 1,
+            active: true,
+        };
+        assert_eq!(profile.to_string(), "* default (1 alias)");
+
+        profile.name = Some("work".to_string());
+        profile.aliases = 3;
+        profile.active = false;
+        assert_eq!(profile.to_string(), "  work (3 aliases)");
+    }
+
+    #[test]
+    fn test_check_config_reports_lines() {
+        let content = r#"[general]
+fuzy_threshold = 0.5
+fuzzy_threshold = 1.5
+default_sort = "newest"
+
+[display]
+table_style = "ASCII"
+
+[colours]
+work = "blue"
+
+[tag_colors]
+work = "purple"
+
+[templates.rust]
+init = "cargo init"
+skel = "~/t"
+"#;
+        let problems = check_config(content, false);
+        let lines: Vec<_> = problems.iter().map(|p| (p.line, p.message.as_str())).collect();
+        assert_eq!(
+            lines,
+            vec![
+                (Some(2), "unknown key 'fuzy_threshold' in [general] (did you mean 'fuzzy_threshold'?)"),
+                (Some(3), "general.fuzzy_threshold = 1.5 is out of range (0 to 1)"),
+                (Some(4), "invalid general.default_sort 'newest' (expected alpha, usage, recent, status)"),
+                (Some(9), "unknown section [colours]"),
+                (Some(13), "unknown color 'purple' for tag 'work' in [tag_colors]"),
+                (Some(17), "unknown key 'skel' in [templates.rust] (did you mean 'skeleton'?)"),
+            ]
+        );
+        assert!(problems.iter().all(|p| p.severity == Severity::Error));
+    }
+
+    #[test]
+    fn test_check_config_conflicts_and_types() {
+        let content = "[update]\nenabled = false\nauto_check = true\n\n[privacy]\ntrack_usage = false\n\n[general]\ndefault_sort = \"usage\"\nsubdir_depth = \"two\"\n\n[[rules]]\npattern = \"gh:(\"\npath = \"~/src\"\n";
+        let quick = check_config(content, false);
+        assert_eq!(quick.len(), 2);
+        assert!(quick.iter().all(|p| p.severity == Severity::Warning));
+        assert_eq!(quick[0].line, Some(3));
+        assert_eq!(quick[1].line, Some(9));
+        assert!(quick[1].message.contains("privacy.track_usage = false"));
+
+        // Only the thorough check notices the wrong type; the pattern is
+        // checked once the file deserializes
+        let thorough = check_config(content, true);
+        assert_eq!(thorough.len(), 3);
+        assert_eq!(thorough[2].line, Some(10));
+        assert_eq!(thorough[2].severity, Severity::Error);
+
+        let fixed = content.replace("\"two\"", "2");
+        let thorough = check_config(&fixed, true);
+        assert_eq!(thorough.last().unwrap().line, Some(13));
+        assert!(thorough.last().unwrap().message.contains("invalid rule pattern"));
+    }
+
+    #[test]
+    fn test_check_config_syntax_error_and_defaults() {
+        let problems = check_config("[general\nfuzzy_threshold = 0.5\n", false);
+        assert_eq!(problems.len(), 1);
+        assert_eq!(problems[0].line, Some(1));
+
+        assert!(check_config("", true).is_empty());
+        let path = Path::new("/tmp/config.toml");
+        let out = format_problems(path, &problems[..1]);
+        assert!(out.starts_with("/tmp/config.toml:1: error: "));
     }
 }
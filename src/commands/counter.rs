@@ -0,0 +1,77 @@
+//! Navigations in the current shell session (`--counter`), for status bars
+//!
+//! Every navigation bumps a counter file named after the session
+//! (`$GOTO_SESSION`, see [`stack::session_id`]) in `counters/` of the data
+//! directory. The file holds just the number, so `--counter` reads a few
+//! bytes and never loads the database.
+
+use std::fs;
+use std::path::PathBuf;
+use std::time::Duration;
+
+use crate::config::Config;
+use crate::stack;
+
+/// Directory of the per-session counter files
+fn counters_dir(config: &Config) -> PathBuf {
+    config.database_path.join("counters")
+}
+
+/// Navigations counted so far in this session; 0 before the first
+pub fn count(config: &Config) -> u64 {
+    fs::read_to_string(counters_dir(config).join(stack::session_id()))
+        .ok()
+        .and_then(|s| s.trim().parse().ok())
+        .unwrap_or(0)
+}
+
+/// Count a navigation in this session (best-effort)
+///
+/// Nothing is counted with `privacy.track_usage = false`. A session's
+/// first navigation also clears away the counters of shells that are gone.
+pub fn increment(config: &Config) {
+    if !config.user.privacy.track_usage {
+        return;
+    }
+    let dir = counters_dir(config);
+    let session = stack::session_id();
+    let path = dir.join(&session);
+    if !path.exists() {
+        if fs::create_dir_all(&dir).is_err() {
+            return;
+        }
+        let ttl = Duration::from_secs(config.user.stack.session_ttl_hours * 3600);
+        let _ = stack::gc_stale_sessions(&dir, &session, ttl);
+    }
+    let _ = fs::write(&path, format!("{}\n", count(config) + 1));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::UserConfig;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_count_and_increment() {
+        let dir = tempdir().unwrap();
+        let mut config = Config {
+            database_path: dir.path().join("data"),
+            stack_path: dir.path().join("data").join("goto_stack"),
+            config_path: dir.path().join("data").join("config.toml"),
+            aliases_path: dir.path().join("data").join("aliases.toml"),
+            profile: None,
+            user: UserConfig::default(),
+            notices: Vec::new(),
+        };
+
+        assert_eq!(count(&config), 0);
+        increment(&config);
+        increment(&config);
+        assert_eq!(count(&config), 2);
+
+        config.user.privacy.track_usage = false;
+        increment(&config);
+        assert_eq!(count(&config), 2);
+    }
+}
@@ -0,0 +1,102 @@
+//! Compare alias export files (`--diff`)
+//!
+//! Shows what importing a file would change before anyone runs `--import`:
+//! the aliases added, removed or changed (path, link or tags) between two
+//! export files, or between the database and a file. Usage statistics are
+//! not compared.
+
+use crate::alias::Alias;
+use crate::commands::import_export::{parse_export, read_source};
+use crate::database::Database;
+use crate::plan::{self, Change, PlannedChange};
+
+/// The changes from `old` to `new`, sorted by alias name
+///
+/// Both are export files (`-` reads stdin). Without `new`, the database is
+/// compared against `old`: the changes importing it with `--strategy=overwrite`
+/// would make, plus the aliases the file doesn't have. Temporary aliases are
+/// left out of the database side.
+pub fn diff(db: &Database, old: &str, new: Option<&str>) -> Result<Vec<PlannedChange>, Box<dyn std::error::Error>> {
+    let read = |path: &str| -> Result<Vec<Alias>, Box<dyn std::error::Error>> {
+        let content = read_source(path).map_err(|e| format!("{}: {}", path, e))?;
+        parse_export(&content).map_err(|e| format!("{}: {}", path, e).into())
+    };
+
+    let changes = match new {
+        Some(new) => plan::diff(&read(old)?, read(new)?.iter()),
+        None => {
+            let current: Vec<Alias> = db.all().filter(|a| !db.is_temporary(&a.name)).cloned().collect();
+            plan::diff(&current, read(old)?.iter())
+        }
+    };
+    Ok(changes)
+}
+
+/// "2 added, 1 changed, 1 removed", or "No differences"
+pub fn summary(changes: &[PlannedChange]) -> String {
+    let count = |change| changes.iter().filter(|c| c.change == change).count();
+    let parts: Vec<String> = [(Change::Add, "added"), (Change::Update, "changed"), (Change::Remove, "removed")]
+        .into_iter()
+        .map(|(change, what)| (count(change), what))
+        .filter(|(n, _)| *n > 0)
+        .map(|(n, what)| format!("{} {}", n, what))
+        .collect();
+    if parts.is_empty() {
+        "No differences".to_string()
+    } else {
+        parts.join(", ")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_diff_files() {
+        let dir = tempdir().unwrap();
+        let db = Database::load_from_path(&dir.path().join("aliases")).unwrap();
+        let old = dir.path().join("old.toml");
+        let new = dir.path().join("new.toml");
+        fs::write(
+            &old,
+            "[[aliases]]\nname = \"api\"\npath = \"/srv/api\"\n\n[[aliases]]\nname = \"web\"\npath = \"/srv/web\"\n",
+        )
+        .unwrap();
+        fs::write(
+            &new,
+            "[[aliases]]\nname = \"api\"\npath = \"/srv/api\"\ntags = [\"work\"]\nuse_count = 7\n\n\
+             [[aliases]]\nname = \"docs\"\npath = \"/srv/docs\"\nonly_os = [\"plan9\"]\n",
+        )
+        .unwrap();
+
+        let changes = diff(&db, old.to_str().unwrap(), Some(new.to_str().unwrap())).unwrap();
+        assert_eq!(
+            changes,
+            vec![
+                PlannedChange::update("api", "/srv/api", "/srv/api [work]"),
+                PlannedChange::add("docs", "/srv/docs"),
+                PlannedChange::remove("web", "/srv/web"),
+            ]
+        );
+        assert_eq!(summary(&changes), "1 added, 1 changed, 1 removed");
+        assert_eq!(summary(&[]), "No differences");
+    }
+
+    #[test]
+    fn test_diff_against_database() {
+        let dir = tempdir().unwrap();
+        let mut db = Database::load_from_path(&dir.path().join("aliases")).unwrap();
+        db.insert(Alias::new("api", "/srv/api").unwrap());
+        let file = dir.path().join("team.toml");
+        fs::write(&file, "[[aliases]]\nname = \"api\"\npath = \"/srv/api-v2\"\n").unwrap();
+
+        let changes = diff(&db, file.to_str().unwrap(), None).unwrap();
+        assert_eq!(changes, vec![PlannedChange::update("api", "/srv/api", "/srv/api-v2")]);
+
+        let err = diff(&db, dir.path().join("missing.toml").to_str().unwrap(), None).unwrap_err();
+        assert!(err.to_string().contains("missing.toml"));
+    }
+}
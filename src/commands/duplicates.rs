@@ -0,0 +1,182 @@
+//! Overlapping alias paths: duplicates and aliases nested inside each other
+
+use std::path::Path;
+
+use crate::alias::Alias;
+use crate::config::Config;
+use crate::database::Database;
+use crate::table::{create_table, format_path, PathStyle, TableStyle};
+
+/// How a path relates to an existing alias's path
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Overlap {
+    /// Same directory
+    Duplicate,
+    /// Inside the alias's directory
+    Inside,
+    /// Contains the alias's directory
+    Contains,
+}
+
+impl Overlap {
+    /// How `path` relates to `other`, if at all
+    pub fn between(path: &Path, other: &Path) -> Option<Self> {
+        if path == other {
+            Some(Overlap::Duplicate)
+        } else if path.starts_with(other) {
+            Some(Overlap::Inside)
+        } else if other.starts_with(path) {
+            Some(Overlap::Contains)
+        } else {
+            None
+        }
+    }
+
+    /// Warning shown when registering `path` over `alias`
+    pub fn describe(self, path: &str, alias: &Alias) -> String {
+        match self {
+            Overlap::Duplicate => format!("{} is already registered as '{}'", path, alias.name),
+            Overlap::Inside => format!("{} is inside '{}' ({})", path, alias.name, alias.path),
+            Overlap::Contains => format!("{} contains '{}' ({})", path, alias.name, alias.path),
+        }
+    }
+}
+
+/// Aliases whose paths overlap `path`, duplicates first, then by name
+///
+/// `exclude` skips an alias by name (e.g. the one being re-registered).
+/// Looked up in the database's path index rather than comparing every alias.
+pub fn overlapping<'a>(db: &'a Database, path: &Path, exclude: Option<&str>) -> Vec<(&'a Alias, Overlap)> {
+    let mut found: Vec<(&Alias, Overlap)> = db
+        .aliases_containing(path)
+        .into_iter()
+        .chain(db.aliases_inside(path))
+        .filter(|a| Some(a.name.as_str()) != exclude)
+        .filter_map(|a| Overlap::between(path, Path::new(&a.path)).map(|o| (a, o)))
+        .collect();
+    found.sort_by(|x, y| x.1.cmp(&y.1).then_with(|| x.0.name.cmp(&y.0.name)));
+    found
+}
+
+/// Every overlapping pair as (kind, outer alias, inner alias)
+///
+/// Duplicates are listed once with the alphabetically first name first;
+/// nested pairs list the containing alias first.
+pub fn find_all(db: &Database) -> Vec<(Overlap, &Alias, &Alias)> {
+    // Each pair is found from its inner alias, looking up the directories above it
+    let mut pairs = Vec::new();
+    for inner in db.all() {
+        for outer in db.aliases_containing(Path::new(&inner.path)) {
+            match Overlap::between(Path::new(&inner.path), Path::new(&outer.path)) {
+                Some(Overlap::Duplicate) if outer.name < inner.name => {
+                    pairs.push((Overlap::Duplicate, outer, inner));
+                }
+                Some(Overlap::Inside) => pairs.push((Overlap::Contains, outer, inner)),
+                _ => {}
+            }
+        }
+    }
+    pairs.sort_by(|x, y| {
+        x.0.cmp(&y.0)
+            .then_with(|| x.1.name.cmp(&y.1.name))
+            .then_with(|| x.2.name.cmp(&y.2.name))
+    });
+    pairs
+}
+
+/// Render the pairs found by [`find_all`] as a table
+pub fn format_overlaps(pairs: &[(Overlap, &Alias, &Alias)], config: &Config) -> String {
+    let style = TableStyle::from(config.user.display.table_style.as_str());
+    let path_style = PathStyle::from(config.user.display.path_style.as_str());
+    let mut table = create_table(style);
+    table.set_header(vec!["Kind", "Alias", "Path", "Other", "Other Path"]);
+
+    for (kind, outer, inner) in pairs {
+        table.add_row(vec![
+            match kind {
+                Overlap::Duplicate => "duplicate",
+                _ => "nested",
+            }
+            .to_string(),
+            outer.name.clone(),
+            format_path(&outer.path, path_style),
+            inner.name.clone(),
+            format_path(&inner.path, path_style),
+        ]);
+    }
+
+    table.to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::NamedTempFile;
+
+    fn create_test_db() -> (Database, NamedTempFile) {
+        let file = NamedTempFile::new().unwrap();
+        let mut db = Database::load_from_path(file.path()).unwrap();
+        db.insert(Alias::new("src", "/home/user/src").unwrap());
+        db.insert(Alias::new("api", "/home/user/src/api").unwrap());
+        db.insert(Alias::new("api2", "/home/user/src/api").unwrap());
+        db.insert(Alias::new("srcs", "/home/user/srcs").unwrap());
+        (db, file)
+    }
+
+    #[test]
+    fn test_between() {
+        let p = Path::new;
+        assert_eq!(Overlap::between(p("/a/b"), p("/a/b")), Some(Overlap::Duplicate));
+        assert_eq!(Overlap::between(p("/a/b/c"), p("/a/b")), Some(Overlap::Inside));
+        assert_eq!(Overlap::between(p("/a"), p("/a/b")), Some(Overlap::Contains));
+        // Component-wise, not string prefixes
+        assert_eq!(Overlap::between(p("/a/bc"), p("/a/b")), None);
+    }
+
+    #[test]
+    fn test_overlapping() {
+        let (db, _file) = create_test_db();
+
+        let found = overlapping(&db, Path::new("/home/user/src/api"), None);
+        let names: Vec<(&str, Overlap)> = found.iter().map(|(a, o)| (a.name.as_str(), *o)).collect();
+        assert_eq!(
+            names,
+            vec![
+                ("api", Overlap::Duplicate),
+                ("api2", Overlap::Duplicate),
+                ("src", Overlap::Inside)
+            ]
+        );
+
+        let found = overlapping(&db, Path::new("/home/user/src/api"), Some("api"));
+        assert_eq!(found.len(), 2);
+        assert!(overlapping(&db, Path::new("/home/user/other"), None).is_empty());
+    }
+
+    #[test]
+    fn test_find_all() {
+        let (db, _file) = create_test_db();
+
+        let pairs: Vec<(Overlap, &str, &str)> = find_all(&db)
+            .into_iter()
+            .map(|(o, a, b)| (o, a.name.as_str(), b.name.as_str()))
+            .collect();
+        assert_eq!(
+            pairs,
+            vec![
+                (Overlap::Duplicate, "api", "api2"),
+                (Overlap::Contains, "src", "api"),
+                (Overlap::Contains, "src", "api2"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_describe() {
+        let alias = Alias::new("src", "/home/user/src").unwrap();
+        assert_eq!(
+            Overlap::Inside.describe("/home/user/src/x", &alias),
+            "/home/user/src/x is inside 'src' (/home/user/src)"
+        );
+    }
+}
@@ -0,0 +1,186 @@
+//! Per-alias environment variables exported on navigation
+
+use std::collections::BTreeMap;
+
+use crate::alias::{validate_env_name, AliasError};
+use crate::commands::install::ShellType;
+use crate::database::Database;
+use crate::exit::Failure;
+
+/// Outcome of [`env()`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum EnvChange {
+    /// Nothing was requested; the alias's current environment
+    Show(BTreeMap<String, String>),
+    /// Keys removed, then `KEY=VALUE` pairs set, in argument order
+    Updated {
+        unset: Vec<String>,
+        set: Vec<(String, String)>,
+    },
+}
+
+/// Set and unset environment variables on an alias
+///
+/// `assignments` are `KEY=VALUE` pairs; `unset` lists keys to remove. With
+/// neither, the alias's current environment is returned.
+pub fn env(
+    db: &mut Database,
+    alias: &str,
+    assignments: &[String],
+    unset: &[String],
+) -> Result<EnvChange, Box<dyn std::error::Error>> {
+    // Validate everything before touching the database
+    let mut parsed = Vec::new();
+    for assignment in assignments {
+        let (key, value) = assignment
+            .split_once('=')
+            .ok_or_else(|| Failure::invalid(format!("invalid env assignment '{}': expected KEY=VALUE", assignment)))?;
+        validate_env_name(key)?;
+        parsed.push((key.to_string(), value.to_string()));
+    }
+
+    let entry = db
+        .get(alias)
+        .ok_or_else(|| AliasError::NotFound(alias.to_string()))?;
+
+    if parsed.is_empty() && unset.is_empty() {
+        return Ok(EnvChange::Show(entry.env.clone()));
+    }
+
+    for key in unset {
+        if !entry.env.contains_key(key) {
+            return Err(Failure::not_found(format!("env variable '{}' not found on '{}'", key, alias)).into());
+        }
+    }
+
+    db.check_writable(alias)?;
+    if let Some(entry) = db.get_mut(alias) {
+        for key in unset {
+            entry.env.remove(key);
+        }
+        for (key, value) in &parsed {
+            entry.env.insert(key.clone(), value.clone());
+        }
+    }
+
+    db.save()?;
+    Ok(EnvChange::Updated {
+        unset: unset.to_vec(),
+        set: parsed,
+    })
+}
+
+/// Format environment variables as statements for the given shell
+pub fn format_exports(env: &BTreeMap<String, String>, shell: ShellType) -> String {
+    match shell {
+        ShellType::Bash | ShellType::Zsh => env
+            .iter()
+            .map(|(k, v)| format!("export {}='{}'\n", k, v.replace('\'', r"'\''")))
+            .collect(),
+        ShellType::Fish => env
+            .iter()
+            .map(|(k, v)| {
+                format!("set -gx {} '{}'\n", k, v.replace('\\', r"\\").replace('\'', r"\'"))
+            })
+            .collect(),
+        ShellType::PowerShell => env
+            .iter()
+            .map(|(k, v)| format!("$env:{} = '{}'\n", k, v.replace('\'', "''")))
+            .collect(),
+        // Nushell can't eval strings; its wrapper feeds this to `load-env`
+        ShellType::Nushell => format!("{}\n", serde_json::to_string(env).unwrap_or_default()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::alias::Alias;
+    use tempfile::NamedTempFile;
+
+    fn create_test_db() -> (Database, NamedTempFile) {
+        let file = NamedTempFile::new().unwrap();
+        let mut db = Database::load_from_path(file.path()).unwrap();
+        db.insert(Alias::new("proj", "/tmp").unwrap());
+        (db, file)
+    }
+
+    fn sample_env() -> BTreeMap<String, String> {
+        let mut env = BTreeMap::new();
+        env.insert("RUST_LOG".to_string(), "debug".to_string());
+        env.insert("GREETING".to_string(), "it's here".to_string());
+        env
+    }
+
+    #[test]
+    fn test_env_set_and_unset() {
+        let (mut db, _file) = create_test_db();
+
+        let change = env(&mut db, "proj", &["RUST_LOG=debug".to_string(), "EMPTY=".to_string()], &[]).unwrap();
+        assert_eq!(
+            change,
+            EnvChange::Updated {
+                unset: vec![],
+                set: vec![
+                    ("RUST_LOG".to_string(), "debug".to_string()),
+                    ("EMPTY".to_string(), String::new())
+                ],
+            }
+        );
+        let entry = db.get("proj").unwrap();
+        assert_eq!(entry.env.get("RUST_LOG").map(String::as_str), Some("debug"));
+        assert_eq!(entry.env.get("EMPTY").map(String::as_str), Some(""));
+
+        env(&mut db, "proj", &[], &["EMPTY".to_string()]).unwrap();
+        assert!(!db.get("proj").unwrap().env.contains_key("EMPTY"));
+
+        let EnvChange::Show(current) = env(&mut db, "proj", &[], &[]).unwrap() else {
+            panic!("expected the current environment");
+        };
+        assert_eq!(current.keys().collect::<Vec<_>>(), vec!["RUST_LOG"]);
+    }
+
+    #[test]
+    fn test_env_value_may_contain_equals() {
+        let (mut db, _file) = create_test_db();
+
+        env(&mut db, "proj", &["OPTS=a=b".to_string()], &[]).unwrap();
+        assert_eq!(db.get("proj").unwrap().env["OPTS"], "a=b");
+    }
+
+    #[test]
+    fn test_env_errors() {
+        let (mut db, _file) = create_test_db();
+
+        assert!(env(&mut db, "missing", &["A=1".to_string()], &[]).is_err());
+        let err = env(&mut db, "proj", &["1BAD=x".to_string()], &[]).unwrap_err();
+        assert!(err.to_string().contains("invalid env"));
+        let err = env(&mut db, "proj", &["NOEQUALS".to_string()], &[]).unwrap_err();
+        assert!(err.to_string().contains("invalid env"));
+        assert!(env(&mut db, "proj", &[], &["UNKNOWN".to_string()]).is_err());
+    }
+
+    #[test]
+    fn test_format_exports_posix() {
+        let out = format_exports(&sample_env(), ShellType::Bash);
+        assert_eq!(out, "export GREETING='it'\\''s here'\nexport RUST_LOG='debug'\n");
+    }
+
+    #[test]
+    fn test_format_exports_fish() {
+        let out = format_exports(&sample_env(), ShellType::Fish);
+        assert_eq!(out, "set -gx GREETING 'it\\'s here'\nset -gx RUST_LOG 'debug'\n");
+    }
+
+    #[test]
+    fn test_format_exports_powershell() {
+        let out = format_exports(&sample_env(), ShellType::PowerShell);
+        assert_eq!(out, "$env:GREETING = 'it''s here'\n$env:RUST_LOG = 'debug'\n");
+    }
+
+    #[test]
+    fn test_format_exports_nushell() {
+        let out = format_exports(&sample_env(), ShellType::Nushell);
+        assert_eq!(out, "{\"GREETING\":\"it's here\",\"RUST_LOG\":\"debug\"}\n");
+    }
+}
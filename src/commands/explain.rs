@@ -0,0 +1,320 @@
+//! Trace how a navigation query would be resolved
+
+use std::fmt::Write;
+use std::path::{Path, PathBuf};
+
+use crate::commands::navigate::{
+    check_enterable, context_tags, find_in_roots, find_subdir, fuzzy_candidates, NavigateOptions, Ranking,
+    FUZZY_CONFIDENT_SCORE, FUZZY_MIN_SCORE, MAX_SUGGESTIONS,
+};
+use crate::config::Config;
+use crate::database::Database;
+
+/// Describe what `goto <query>` would do, step by step
+///
+/// Follows the same order as navigation: dynamic rules (for unregistered
+/// names), `alias:name`, the registered alias, fuzzy suggestions, then the
+/// search roots.
+/// Nothing is recorded and nothing is cloned.
+pub fn trace(db: &Database, options: &NavigateOptions, query: &str, cwd: Option<&Path>) -> String {
+    let mut out = String::new();
+    let registered = db.get(query);
+
+    // 1. Dynamic rules
+    if registered.is_some() {
+        let _ = writeln!(out, "1. Dynamic rules: skipped, '{}' is a registered alias", query);
+    } else if options.rules.is_empty() {
+        let _ = writeln!(out, "1. Dynamic rules: none configured");
+    } else {
+        let matched = options
+            .rules
+            .iter()
+            .find_map(|rule| rule.resolve(query).transpose().map(|r| (rule, r)));
+        match matched {
+            None => {
+                let _ = writeln!(out, "1. Dynamic rules: none of {} match", options.rules.len());
+            }
+            Some((rule, Err(e))) => {
+                let _ = writeln!(out, "1. Dynamic rules: '{}' matches", rule.pattern);
+                let _ = writeln!(out, "\nResult: fails: {}", e);
+                return out;
+            }
+            Some((rule, Ok(resolved))) => {
+                let _ = writeln!(out, "1. Dynamic rules: '{}' matches", rule.pattern);
+                let target = resolved.path.to_string_lossy().into_owned();
+                let result = match (&resolved.clone_url, resolved.path.exists()) {
+                    (Some(url), false) => format!("clones {} and prints {}", url, target),
+                    _ => outcome(&target),
+                };
+                let _ = writeln!(out, "\nResult: {}", result);
+                return out;
+            }
+        }
+    }
+
+    // 2. alias:name
+    match (options.subdir_depth, query.split_once(':')) {
+        (None, Some(_)) => {
+            let _ = writeln!(out, "2. alias:name: disabled (general.subdir_navigation = false)");
+        }
+        (_, None) => {
+            let _ = writeln!(out, "2. alias:name: not used (no ':')");
+        }
+        (Some(depth), Some((parent, name))) => {
+            let _ = writeln!(out, "2. alias:name: '{}' below alias '{}' (depth {})", name, parent, depth);
+            let result = match db.get(parent) {
+                None => format!("fails: alias '{}' not found", parent),
+                Some(entry) => match check_enterable(&entry.path) {
+                    Err(e) => format!("fails: {}", e),
+                    Ok(()) => match find_subdir(&PathBuf::from(&entry.path), name, depth, &options.scan) {
+                        Some(target) => format!("prints {}", target.display()),
+                        None => format!("fails: directory '{}' not found under alias '{}'", name, parent),
+                    },
+                },
+            };
+            let _ = writeln!(out, "\nResult: {}", result);
+            return out;
+        }
+    }
+
+    // 3. Registered alias
+    if let Some(entry) = registered {
+        let _ = writeln!(out, "3. Registered alias: '{}' -> {}", query, entry.path);
+        let _ = writeln!(out, "\nResult: {}", outcome(&entry.path));
+        return out;
+    }
+    let _ = writeln!(out, "3. Registered alias: no alias named '{}'", query);
+
+    // 4. Fuzzy suggestions
+    let _ = writeln!(
+        out,
+        "4. Fuzzy matching: candidates need {}%, the best one {}%",
+        FUZZY_MIN_SCORE / 10,
+        FUZZY_CONFIDENT_SCORE / 10
+    );
+    let mut candidates = fuzzy_candidates(db, query);
+    let tags = match (options.context_ranking, cwd) {
+        (true, Some(cwd)) => context_tags(db, cwd),
+        _ => Vec::new(),
+    };
+    if !tags.is_empty() {
+        let _ = writeln!(out, "   context tags from the current directory: {}", tags.join(", "));
+    }
+    let confident = candidates
+        .first()
+        .is_some_and(|(_, score)| *score >= FUZZY_CONFIDENT_SCORE);
+    let ranking = Ranking::new(db, &tags, options.usage_weight);
+    if confident {
+        ranking.rank(&mut candidates);
+    }
+
+    let width = candidates.iter().map(|(n, _)| n.chars().count()).max().unwrap_or(0);
+    for (name, score) in &candidates {
+        let mut bonuses = Vec::new();
+        let context = ranking.context_bonus(name);
+        if context > 0 {
+            bonuses.push(format!("+{}% context", context / 10));
+        }
+        let usage = ranking.usage_bonus(name);
+        if usage > 0 {
+            bonuses.push(format!("+{:.1}% usage", usage as f64 / 10.0));
+        }
+        let _ = writeln!(
+            out,
+            "   {:<width$}  {:>3}%  {}{}",
+            name,
+            score / 10,
+            db.get(name).map(|a| a.path.as_str()).unwrap_or_default(),
+            if bonuses.is_empty() {
+                String::new()
+            } else {
+                format!("  ({})", bonuses.join(", "))
+            },
+            width = width
+        );
+    }
+
+    if !confident {
+        // 5. Search roots
+        if options.search_roots.is_empty() {
+            let _ = writeln!(out, "5. Search roots: none configured");
+        } else {
+            let found = find_in_roots(&options.search_roots, query, &options.scan);
+            let _ = writeln!(
+                out,
+                "5. Search roots: {} director{} named '{}'",
+                found.len(),
+                if found.len() == 1 { "y" } else { "ies" },
+                query
+            );
+            for path in &found {
+                let _ = writeln!(out, "   {}", path.display());
+            }
+            if !found.is_empty() {
+                let paths: Vec<String> = found.iter().map(|p| p.display().to_string()).collect();
+                let _ = writeln!(out, "\nResult: offers {} (and to register it)", paths.join(", "));
+                return out;
+            }
+        }
+    }
+
+    let result = if candidates.is_empty() {
+        format!("fails: alias '{}' not found (no candidates)", query)
+    } else if !confident {
+        format!(
+            "fails: alias '{}' not found (best match {}% is below {}%)",
+            query,
+            candidates[0].1 / 10,
+            FUZZY_CONFIDENT_SCORE / 10
+        )
+    } else {
+        let names: Vec<&str> = candidates
+            .iter()
+            .take(MAX_SUGGESTIONS)
+            .map(|(n, _)| n.as_str())
+            .collect();
+        format!("asks \"Did you mean\": {}", names.join(", "))
+    };
+    let _ = writeln!(out, "\nResult: {}", result);
+    out
+}
+
+/// What navigating to `path` would do
+fn outcome(path: &str) -> String {
+    match check_enterable(path) {
+        Ok(()) => format!("prints {}", path),
+        Err(e) => format!("fails: {}", e),
+    }
+}
+
+/// Describe how `goto <query>` would be resolved with the configured settings
+///
+/// Like [`trace`], preceded by the query, profile and relevant settings.
+pub fn explain(db: &Database, config: &Config, query: &str) -> String {
+    let options = NavigateOptions::from_config(config);
+    let general = &config.user.general;
+
+    let mut out = String::new();
+    let _ = writeln!(out, "Query: {}", query);
+    if let Some(profile) = &config.profile {
+        let _ = writeln!(out, "Profile: {}", profile);
+    }
+    let _ = writeln!(
+        out,
+        "Settings: context_ranking = {}, usage_weight = {}, subdir_navigation = {}, subdir_depth = {}, rules = {}, \
+         search_roots = {}",
+        general.context_ranking,
+        general.usage_weight,
+        general.subdir_navigation,
+        general.subdir_depth,
+        config.user.rules.len(),
+        general.search_roots.len()
+    );
+    out.push('\n');
+
+    let cwd = std::env::current_dir().ok();
+    out.push_str(&trace(db, &options, query, cwd.as_deref()));
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::alias::Alias;
+    use crate::rules::dynamic::DynamicRule;
+    use tempfile::{tempdir, NamedTempFile};
+
+    fn create_test_db(path: &str) -> (Database, NamedTempFile) {
+        let file = NamedTempFile::new().unwrap();
+        let mut db = Database::load_from_path(file.path()).unwrap();
+        let mut project = Alias::new("project", path).unwrap();
+        project.add_tag("work");
+        db.insert(project);
+        db.insert(Alias::new("projects", "/nonexistent/goto/projects").unwrap());
+        (db, file)
+    }
+
+    fn options() -> NavigateOptions {
+        NavigateOptions {
+            context_ranking: true,
+            subdir_depth: Some(2),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_trace_exact_match() {
+        let dir = tempdir().unwrap();
+        let (db, _file) = create_test_db(dir.path().to_str().unwrap());
+
+        let out = trace(&db, &options(), "project", None);
+        assert!(out.contains("1. Dynamic rules: skipped"));
+        assert!(out.contains(&format!("Result: prints {}", dir.path().display())));
+
+        let out = trace(&db, &options(), "projects", None);
+        assert!(out.contains("Result: fails: directory does not exist"));
+    }
+
+    #[test]
+    fn test_trace_fuzzy_candidates() {
+        let dir = tempdir().unwrap();
+        let (db, _file) = create_test_db(dir.path().to_str().unwrap());
+
+        let out = trace(&db, &options(), "projet", None);
+        assert!(out.contains("3. Registered alias: no alias named 'projet'"));
+        assert!(out.contains("candidates need 30%, the best one 70%"));
+        assert!(out.contains("Result: asks \"Did you mean\": project, projects"));
+
+        let out = trace(&db, &options(), "zzzz", None);
+        assert!(out.contains("5. Search roots: none configured"));
+        assert!(out.contains("Result: fails: alias 'zzzz' not found (no candidates)"));
+    }
+
+    #[test]
+    fn test_trace_search_roots() {
+        let dir = tempdir().unwrap();
+        std::fs::create_dir(dir.path().join("zzzz")).unwrap();
+        let (db, _file) = create_test_db("/nonexistent/goto/project");
+        let mut opts = options();
+        opts.search_roots = vec![dir.path().to_path_buf()];
+
+        let out = trace(&db, &opts, "zzzz", None);
+        assert!(out.contains("5. Search roots: 1 directory named 'zzzz'"));
+        assert!(out.contains(&format!("Result: offers {} (and to register it)", dir.path().join("zzzz").display())));
+
+        let out = trace(&db, &opts, "yyyy", None);
+        assert!(out.contains("5. Search roots: 0 directories named 'yyyy'"));
+        assert!(out.contains("Result: fails: alias 'yyyy' not found (no candidates)"));
+    }
+
+    #[test]
+    fn test_trace_context_bonus() {
+        let dir = tempdir().unwrap();
+        let (db, _file) = create_test_db(dir.path().to_str().unwrap());
+
+        let out = trace(&db, &options(), "projet", Some(dir.path()));
+        assert!(out.contains("context tags from the current directory: work"));
+        assert!(out.contains("(+15% context)"));
+    }
+
+    #[test]
+    fn test_trace_rules_and_subdirs() {
+        let dir = tempdir().unwrap();
+        std::fs::create_dir(dir.path().join("src")).unwrap();
+        let (db, _file) = create_test_db(dir.path().to_str().unwrap());
+
+        let out = trace(&db, &options(), "project:src", None);
+        assert!(out.contains("2. alias:name: 'src' below alias 'project'"));
+        assert!(out.contains(&format!("Result: prints {}", dir.path().join("src").display())));
+
+        let mut opts = options();
+        opts.rules = vec![DynamicRule {
+            pattern: "gh:(.+)".to_string(),
+            path: format!("{}/$1", dir.path().display()),
+            clone: None,
+        }];
+        let out = trace(&db, &opts, "gh:src", None);
+        assert!(out.contains("1. Dynamic rules: 'gh:(.+)' matches"));
+        assert!(out.contains(&format!("Result: prints {}", dir.path().join("src").display())));
+    }
+}
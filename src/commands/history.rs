@@ -0,0 +1,304 @@
+//! Alias candidates from shell history (`--learn-from-history`)
+//!
+//! Reads bash, zsh and fish history for `cd` to absolute or `~` paths,
+//! counts the visits to each directory that exists and has no alias yet, and
+//! ranks them for registering in one go. Unlike `--watch`, nothing is kept
+//! between runs. Relative `cd`s are skipped: history doesn't say where they
+//! started from.
+
+use std::collections::HashMap;
+use std::error::Error;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::alias::Alias;
+use crate::commands::register;
+use crate::commands::watch::suggest_name;
+use crate::config::{expand_path_with, Config};
+use crate::database::Database;
+use crate::exit::Failure;
+use crate::table::{create_table, format_path, PathStyle, TableStyle};
+
+/// Most candidates offered when no `--limit` is given
+pub const DEFAULT_LIMIT: usize = 20;
+
+/// A directory often visited from the shell, with a proposed alias name
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Candidate {
+    pub name: String,
+    pub path: String,
+    /// Number of `cd`s to it in history
+    pub visits: usize,
+}
+
+/// History files of the shells goto knows, those that exist
+///
+/// `$HISTFILE` when set (exported by some setups), then the bash, zsh and
+/// fish defaults.
+pub fn history_files() -> Vec<PathBuf> {
+    let mut files: Vec<PathBuf> = std::env::var_os("HISTFILE").map(PathBuf::from).into_iter().collect();
+    if let Some(home) = dirs::home_dir() {
+        files.push(home.join(".bash_history"));
+        files.push(home.join(".zsh_history"));
+    }
+    if let Some(data) = dirs::data_dir() {
+        files.push(data.join("fish").join("fish_history"));
+    }
+    let mut existing: Vec<PathBuf> = Vec::new();
+    for file in files {
+        if file.is_file() && !existing.contains(&file) {
+            existing.push(file);
+        }
+    }
+    existing
+}
+
+/// Read history files; zsh's metafied bytes are read lossily
+pub fn read_histories(files: &[PathBuf]) -> Vec<String> {
+    files
+        .iter()
+        .filter_map(|file| fs::read(file).ok())
+        .map(|bytes| String::from_utf8_lossy(&bytes).into_owned())
+        .collect()
+}
+
+/// The command of a history line: zsh's `: <time>:<duration>;` and fish's
+/// `- cmd: ` prefixes removed
+fn command_of(line: &str) -> &str {
+    if let Some(rest) = line.strip_prefix("- cmd: ") {
+        return rest;
+    }
+    if line.starts_with(": ") {
+        if let Some((_, command)) = line.split_once(';') {
+            return command;
+        }
+    }
+    line
+}
+
+/// The directory a `cd` or `pushd` command goes to, unquoted
+fn cd_target(command: &str) -> Option<String> {
+    let command = command.trim();
+    let command = command.strip_prefix("builtin ").unwrap_or(command);
+    let (verb, rest) = command.split_once(char::is_whitespace)?;
+    if !matches!(verb, "cd" | "pushd") {
+        return None;
+    }
+    let mut rest = rest.trim();
+    for option in ["-- ", "-P ", "-L "] {
+        rest = rest.strip_prefix(option).unwrap_or(rest).trim_start();
+    }
+
+    let target = match rest.chars().next()? {
+        quote @ ('\'' | '"') => rest.strip_prefix(quote)?.strip_suffix(quote)?.to_string(),
+        _ => rest.replace("\\ ", " "),
+    };
+    // Variables, globs and substitutions can't be resolved after the fact
+    let unresolvable = target.contains(['$', '`', '*', '?']);
+    ((target.starts_with('/') || target.starts_with('~')) && !unresolvable).then_some(target)
+}
+
+/// Every absolute or `~` directory `cd`ed to in `history`, in order
+///
+/// Commands chained with `&&`, `||`, `;` or `|` are looked at one by one.
+pub fn cd_targets(history: &str) -> Vec<String> {
+    history
+        .lines()
+        .map(command_of)
+        .flat_map(|line| line.split("&&").flat_map(|c| c.split("||")).flat_map(|c| c.split([';', '|'])))
+        .filter_map(cd_target)
+        .collect()
+}
+
+/// Rank the directories visited in `histories` that could use an alias
+///
+/// Only existing directories count, and none already registered, the home
+/// and root directories, or any below a directory `[scan] ignore` skips.
+/// Most visited first; at most `limit`.
+pub fn candidates(db: &Database, config: &Config, histories: &[String], limit: usize) -> Vec<Candidate> {
+    let resolve_symlinks = config.user.general.resolve_symlinks;
+    let mut visits: HashMap<String, usize> = HashMap::new();
+    for target in histories.iter().flat_map(|h| cd_targets(h)) {
+        *visits.entry(target).or_default() += 1;
+    }
+
+    // Several spellings (`~/src`, `/home/me/src/`) are one directory
+    let mut by_dir: HashMap<PathBuf, usize> = HashMap::new();
+    for (target, count) in visits {
+        if let Ok(path) = expand_path_with(&target, resolve_symlinks) {
+            *by_dir.entry(path).or_default() += count;
+        }
+    }
+
+    let home = dirs::home_dir();
+    let mut ranked: Vec<(PathBuf, usize)> = by_dir
+        .into_iter()
+        .filter(|(path, _)| {
+            path.parent().is_some()
+                && Some(path) != home.as_ref()
+                && db.names_at(path).is_empty()
+                && !ignored(path, config)
+                && path.is_dir()
+        })
+        .collect();
+    ranked.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+    ranked.truncate(limit);
+
+    let mut found: Vec<Candidate> = Vec::new();
+    for (path, visits) in ranked {
+        let dir_name = path.file_name().unwrap_or_default().to_string_lossy().into_owned();
+        let taken = |name: &str| db.contains(name) || found.iter().any(|c| c.name == name);
+        if let Some(name) = suggest_name(&dir_name, taken) {
+            found.push(Candidate {
+                name,
+                path: path.to_string_lossy().into_owned(),
+                visits,
+            });
+        }
+    }
+    found
+}
+
+/// [`candidates`] from `file`, or from [`history_files`] when `None`
+pub fn learn(
+    db: &Database,
+    config: &Config,
+    file: Option<&str>,
+    limit: usize,
+) -> Result<Vec<Candidate>, Box<dyn Error>> {
+    let files = match file {
+        Some(file) => {
+            let path = expand_path_with(file, false)?;
+            if !path.is_file() {
+                return Err(Failure::not_found(format!("history file not found: {}", path.display())).into());
+            }
+            vec![path]
+        }
+        None => history_files(),
+    };
+    if files.is_empty() {
+        return Err(Failure::not_found("shell history not found; give the history file to read").into());
+    }
+    Ok(candidates(db, config, &read_histories(&files), limit))
+}
+
+/// Whether any directory on `path` is one `[scan] ignore` skips
+fn ignored(path: &Path, config: &Config) -> bool {
+    path.iter().any(|part| config.user.scan.ignores(&part.to_string_lossy()))
+}
+
+/// Parse a selection such as `1-3,5` or `all` against `count` numbered rows
+///
+/// Returns 0-based indices, in order and without repeats.
+pub fn parse_selection(input: &str, count: usize) -> Result<Vec<usize>, String> {
+    if input.trim().eq_ignore_ascii_case("all") {
+        return Ok((0..count).collect());
+    }
+    let number = |s: &str| match s.trim().parse::<usize>() {
+        Ok(n) if (1..=count).contains(&n) => Ok(n - 1),
+        _ => Err(format!("invalid selection: {} (pick from 1-{})", s.trim(), count)),
+    };
+    let mut picked = Vec::new();
+    for part in input.split(',').filter(|p| !p.trim().is_empty()) {
+        let (first, last) = match part.split_once('-') {
+            Some((first, last)) => (number(first)?, number(last)?),
+            None => (number(part)?, number(part)?),
+        };
+        picked.extend(first..=last);
+    }
+    picked.sort();
+    picked.dedup();
+    Ok(picked)
+}
+
+/// Register the candidates at `picked` (0-based), returning the new aliases
+pub fn register_picked(
+    db: &mut Database,
+    candidates: &[Candidate],
+    picked: &[usize],
+) -> Result<Vec<Alias>, Box<dyn Error>> {
+    picked
+        .iter()
+        .filter_map(|&i| candidates.get(i))
+        .map(|c| register::register(db, &c.name, &c.path))
+        .collect()
+}
+
+/// Render candidates as a numbered table
+pub fn format_candidates(candidates: &[Candidate], config: &Config) -> String {
+    let style = TableStyle::from(config.user.display.table_style.as_str());
+    let path_style = PathStyle::from(config.user.display.path_style.as_str());
+    let mut table = create_table(style);
+    table.set_header(vec!["#", "Name", "Path", "Visits"]);
+    for (i, candidate) in candidates.iter().enumerate() {
+        table.add_row(vec![
+            (i + 1).to_string(),
+            candidate.name.clone(),
+            format_path(&candidate.path, path_style),
+            candidate.visits.to_string(),
+        ]);
+    }
+    table.to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::UserConfig;
+    use tempfile::{tempdir, NamedTempFile};
+
+    #[test]
+    fn test_cd_targets() {
+        let bash = "ls\ncd /srv/api && make\ncd 'My Projects/x'\ncd ~/src/goto\ncd \"/opt/my app\"\ncd ../up\n";
+        assert_eq!(cd_targets(bash), vec!["/srv/api", "~/src/goto", "/opt/my app"]);
+
+        let zsh = ": 1700000000:0;cd /srv/web\n: 1700000001:0;git pull; cd -- /srv/api\n";
+        assert_eq!(cd_targets(zsh), vec!["/srv/web", "/srv/api"]);
+
+        let fish = "- cmd: cd /srv/db\n  when: 1700000000\n- cmd: cd $HOME/x\n- cmd: pushd /tmp/a\\ b\n";
+        assert_eq!(cd_targets(fish), vec!["/srv/db", "/tmp/a b"]);
+    }
+
+    #[test]
+    fn test_candidates() {
+        let dir = tempdir().unwrap();
+        let root = fs::canonicalize(dir.path()).unwrap();
+        for sub in ["api", "web", "registered", "node_modules/pkg"] {
+            fs::create_dir_all(root.join(sub)).unwrap();
+        }
+        let file = NamedTempFile::new().unwrap();
+        let mut db = Database::load_from_path(file.path()).unwrap();
+        db.insert(Alias::new("reg", root.join("registered").to_str().unwrap()).unwrap());
+        db.insert(Alias::new("web", "/elsewhere").unwrap());
+        let config = Config {
+            database_path: root.join("data"),
+            stack_path: root.join("data").join("goto_stack"),
+            config_path: root.join("data").join("config.toml"),
+            aliases_path: root.join("data").join("aliases.toml"),
+            profile: None,
+            user: UserConfig::default(),
+            notices: Vec::new(),
+        };
+
+        let r = root.display();
+        let history = format!(
+            "cd {r}/web\ncd {r}/api\ncd {r}/api/\ncd {r}/registered\ncd {r}/registered\ncd {r}/registered\n\
+             cd {r}/node_modules/pkg\ncd {r}/gone\ncd /\n"
+        );
+        let histories = vec![history];
+        let found = candidates(&db, &config, &histories, DEFAULT_LIMIT);
+        let names: Vec<(&str, usize)> = found.iter().map(|c| (c.name.as_str(), c.visits)).collect();
+        // `web` is taken by another alias's name
+        assert_eq!(names, vec![("api", 2), ("web_2", 1)]);
+        assert_eq!(candidates(&db, &config, &histories, 1).len(), 1);
+    }
+
+    #[test]
+    fn test_parse_selection() {
+        assert_eq!(parse_selection("1-3,5", 6).unwrap(), vec![0, 1, 2, 4]);
+        assert_eq!(parse_selection("2, 2,1", 3).unwrap(), vec![0, 1]);
+        assert_eq!(parse_selection("all", 3).unwrap(), vec![0, 1, 2]);
+        assert!(parse_selection("4", 3).is_err());
+        assert!(parse_selection("x", 3).is_err());
+    }
+}
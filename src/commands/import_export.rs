@@ -2,20 +2,123 @@
 
 use std::collections::HashMap;
 use std::fs;
+use std::io::{self, Read};
 use std::path::Path;
+use std::str::FromStr;
 
-use crate::alias::{validate_alias, Alias};
+use crate::alias::Alias;
+use crate::collate;
+use crate::commands::install::ShellType;
+use crate::config::Config;
 use crate::database::Database;
+use crate::filter::AliasFilter;
+use crate::output::{quote_fish, quote_posix};
+use crate::table::{create_table, format_path, PathStyle, TableStyle};
+
+/// Export aliases as TOML, returning the content and how many aliases it holds
+///
+/// With a filter expression (see [`AliasFilter`]) only matching aliases are
+/// exported, so the result can be shared without the rest of the database.
+pub fn export(db: &Database, filter: Option<&str>) -> Result<(String, usize), Box<dyn std::error::Error>> {
+    let filter = filter.map(AliasFilter::parse).transpose()?;
+    let keep = |a: &Alias| filter.as_ref().is_none_or(|f| f.matches(a));
+
+    let count = db
+        .all()
+        .filter(|a| keep(a) && !db.is_temporary(&a.name) && !db.is_shared(&a.name))
+        .count();
+    Ok((db.export_toml_where(keep)?, count))
+}
+
+/// Export aliases as TOML to a file, returning how many were written
+///
+/// The file is written atomically: content goes to a temporary file in the
+/// same directory which is then renamed over the destination. Nothing is
+/// written when no aliases are selected.
+pub fn export_to_file(
+    db: &Database,
+    output: &str,
+    filter: Option<&str>,
+) -> Result<usize, Box<dyn std::error::Error>> {
+    let (toml, count) = export(db, filter)?;
+    if count == 0 {
+        return Ok(0);
+    }
+
+    write_atomic(Path::new(output), &toml)?;
+    Ok(count)
+}
 
-/// Export aliases as TOML to stdout
-pub fn export(db: &Database) -> Result<(), Box<dyn std::error::Error>> {
-    if db.is_empty() {
-        eprintln!("No aliases to export");
-        return Ok(());
+/// Export aliases as plain shell aliases (`alias dev='cd /home/me/dev'`)
+///
+/// For machines where goto can't be installed: the result is sourced from
+/// an rc file and needs nothing but the shell. Only bash, zsh and fish are
+/// supported. Temporary aliases are left out; shared ones are included,
+/// since the other machine has no shared file. Returns the script and how
+/// many aliases it holds.
+pub fn export_shell_aliases(
+    db: &Database,
+    shell: ShellType,
+    filter: Option<&str>,
+) -> Result<(String, usize), Box<dyn std::error::Error>> {
+    let filter = filter.map(AliasFilter::parse).transpose()?;
+    let shell_name = match shell {
+        ShellType::Bash => "bash",
+        ShellType::Zsh => "zsh",
+        ShellType::Fish => "fish",
+        ShellType::Nushell | ShellType::PowerShell => {
+            return Err("shell aliases can only be exported for bash, zsh or fish".into());
+        }
+    };
+
+    let mut aliases: Vec<&Alias> = db
+        .all()
+        .filter(|a| filter.as_ref().is_none_or(|f| f.matches(a)) && !db.is_temporary(&a.name))
+        .collect();
+    aliases.sort_by(|a, b| a.name.cmp(&b.name));
+
+    let mut script = format!("# goto aliases for {}, generated by goto --export-shell-aliases\n", shell_name);
+    for alias in &aliases {
+        let line = match shell {
+            ShellType::Fish => format!("alias {} {}\n", alias.name, quote_fish(&format!("cd {}", quote_fish(&alias.path)))),
+            _ => format!("alias {}={}\n", alias.name, quote_posix(&format!("cd {}", quote_posix(&alias.path)))),
+        };
+        script.push_str(&line);
     }
+    Ok((script, aliases.len()))
+}
 
-    let toml = db.export_toml()?;
-    print!("{}", toml);
+/// Export shell aliases to a file, returning how many were written
+///
+/// Written atomically like [`export_to_file`]; nothing is written when no
+/// aliases are selected.
+pub fn export_shell_aliases_to_file(
+    db: &Database,
+    shell: ShellType,
+    output: &str,
+    filter: Option<&str>,
+) -> Result<usize, Box<dyn std::error::Error>> {
+    let (script, count) = export_shell_aliases(db, shell, filter)?;
+    if count == 0 {
+        return Ok(0);
+    }
+
+    write_atomic(Path::new(output), &script)?;
+    Ok(count)
+}
+
+/// Write content to a path via a temporary sibling file and rename
+pub(crate) fn write_atomic(path: &Path, content: &str) -> io::Result<()> {
+    let file_name = path
+        .file_name()
+        .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, "output path has no file name"))?;
+    let tmp_path = path.with_file_name(format!(".{}.tmp", file_name.to_string_lossy()));
+
+    fs::write(&tmp_path, content)?;
+    if let Err(e) = fs::rename(&tmp_path, path) {
+        let _ = fs::remove_file(&tmp_path);
+        return Err(e);
+    }
     Ok(())
 }
 
@@ -25,9 +128,74 @@ pub struct ImportResult {
     pub imported: usize,
     pub skipped: usize,
     pub renamed: usize,
+    /// Entries whose `only_hosts` / `only_os` exclude this machine
+    pub not_for_host: usize,
     pub warnings: Vec<String>,
 }
 
+/// Machine conditions of an import entry (`only_hosts`, `only_os`)
+///
+/// An empty list doesn't restrict anything.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct EntryConditions {
+    pub only_hosts: Vec<String>,
+    pub only_os: Vec<String>,
+}
+
+impl EntryConditions {
+    /// Take the condition keys out of an `[[aliases]]` table
+    fn take(table: &mut toml::Table) -> Result<Self, String> {
+        Ok(Self {
+            only_hosts: take_list(table, "only_hosts")?,
+            only_os: take_list(table, "only_os")?,
+        })
+    }
+
+    /// Whether the entry applies on `host` (`None` when unknown) running `os`
+    ///
+    /// Hosts match case-insensitively on the name before the first dot;
+    /// `os` is a [`std::env::consts::OS`] value, with `darwin` accepted for
+    /// `macos`.
+    pub fn applies(&self, host: Option<&str>, os: &str) -> bool {
+        let short = |name: &str| name.split('.').next().unwrap_or(name).to_lowercase();
+        let host_ok = self.only_hosts.is_empty()
+            || host.is_some_and(|host| self.only_hosts.iter().any(|h| short(h) == short(host)));
+        let os_ok = self.only_os.is_empty()
+            || self.only_os.iter().any(|o| {
+                let o = o.to_lowercase();
+                o == os || (o == "darwin" && os == "macos")
+            });
+        host_ok && os_ok
+    }
+}
+
+fn take_list(table: &mut toml::Table, key: &str) -> Result<Vec<String>, String> {
+    match table.remove(key) {
+        None => Ok(Vec::new()),
+        Some(toml::Value::Array(values)) => values
+            .into_iter()
+            .map(|v| match v {
+                toml::Value::String(s) => Ok(s),
+                _ => Err(format!("{} must be a list of strings", key)),
+            })
+            .collect(),
+        Some(_) => Err(format!("{} must be a list of strings", key)),
+    }
+}
+
+/// This machine's host name, `None` when it can't be determined
+pub fn hostname() -> Option<String> {
+    let name = std::env::var("COMPUTERNAME")
+        .ok()
+        .or_else(|| fs::read_to_string("/proc/sys/kernel/hostname").ok())
+        .or_else(|| {
+            let output = std::process::Command::new("hostname").output().ok()?;
+            output.status.success().then(|| String::from_utf8_lossy(&output.stdout).into_owned())
+        })?;
+    let name = name.trim();
+    (!name.is_empty()).then(|| name.to_string())
+}
+
 /// Import strategy for handling conflicts
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
 pub enum ImportStrategy {
@@ -37,8 +205,10 @@ pub enum ImportStrategy {
     Rename,    // Rename conflicting aliases with suffix
 }
 
-impl ImportStrategy {
-    pub fn from_str(s: &str) -> Result<Self, String> {
+impl FromStr for ImportStrategy {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
         match s.to_lowercase().as_str() {
             "skip" => Ok(ImportStrategy::Skip),
             "overwrite" => Ok(ImportStrategy::Overwrite),
@@ -51,45 +221,115 @@ impl ImportStrategy {
     }
 }
 
+/// Read an import file, `-` meaning stdin
+pub fn read_source(file_path: &str) -> io::Result<String> {
+    if file_path == "-" {
+        let mut content = String::new();
+        io::stdin().read_to_string(&mut content)?;
+        Ok(content)
+    } else {
+        fs::read_to_string(file_path)
+    }
+}
+
 /// Import aliases from a TOML file with the specified strategy
+///
+/// A `file_path` of `-` reads the TOML from stdin.
 pub fn import(
     db: &mut Database,
     file_path: &str,
     strategy: ImportStrategy,
 ) -> Result<ImportResult, Box<dyn std::error::Error>> {
-    let content = fs::read_to_string(file_path)?;
+    let content = read_source(file_path)?;
     let result = import_from_content(db, &content, strategy)?;
     db.save()?;
     Ok(result)
 }
 
 /// Import aliases from TOML content string with the specified strategy
+///
+/// Entries with `only_hosts` or `only_os` lists that exclude this machine
+/// are left out and counted in [`ImportResult::not_for_host`].
 pub fn import_from_content(
     db: &mut Database,
     content: &str,
     strategy: ImportStrategy,
 ) -> Result<ImportResult, Box<dyn std::error::Error>> {
-    // Parse TOML content to get aliases
+    import_for_machine(db, content, strategy, hostname().as_deref(), std::env::consts::OS)
+}
+
+/// Every entry of an export file with its machine conditions
+fn entries(content: &str) -> Result<Vec<(Alias, EntryConditions)>, Box<dyn std::error::Error>> {
+    // Entries stay tables until their conditions are taken out
     #[derive(serde::Deserialize)]
     struct ImportFile {
         #[serde(default)]
-        aliases: Vec<Alias>,
+        aliases: Vec<toml::Table>,
     }
 
     let import_data: ImportFile = toml::from_str(content)?;
+    let mut entries = Vec::new();
+    for mut entry in import_data.aliases {
+        let conditions = EntryConditions::take(&mut entry)?;
+        let mut alias: Alias = toml::Value::Table(entry).try_into()?;
+        // Compared with existing names, which are stored composed
+        alias.name = collate::nfc(&alias.name).into_owned();
+        entries.push((alias, conditions));
+    }
+    Ok(entries)
+}
 
-    if import_data.aliases.is_empty() {
+/// The aliases of an export file, whichever machines they are meant for
+pub fn parse_export(content: &str) -> Result<Vec<Alias>, Box<dyn std::error::Error>> {
+    Ok(entries(content)?.into_iter().map(|(alias, _)| alias).collect())
+}
+
+/// The aliases of an import file that apply on `host` with `os`, and how
+/// many entries were left out for other machines
+fn entries_for_machine(
+    content: &str,
+    host: Option<&str>,
+    os: &str,
+) -> Result<(Vec<Alias>, usize), Box<dyn std::error::Error>> {
+    let entries = entries(content)?;
+    if entries.is_empty() {
         return Err("no aliases found in import file".into());
     }
 
+    let mut aliases = Vec::new();
+    let mut not_for_host = 0;
+    for (import_alias, conditions) in entries {
+        if conditions.applies(host, os) {
+            aliases.push(import_alias);
+        } else {
+            crate::debug!("'{}' is not for this machine ({:?})", import_alias.name, conditions);
+            not_for_host += 1;
+        }
+    }
+    Ok((aliases, not_for_host))
+}
+
+/// [`import_from_content`] as if running on `host` with `os`
+fn import_for_machine(
+    db: &mut Database,
+    content: &str,
+    strategy: ImportStrategy,
+    host: Option<&str>,
+    os: &str,
+) -> Result<ImportResult, Box<dyn std::error::Error>> {
+    let (aliases, not_for_host) = entries_for_machine(content, host, os)?;
+
     // Build map of existing alias names for quick lookup
     let mut existing_names: HashMap<String, bool> = db.names().map(|n| (n.to_string(), true)).collect();
 
-    let mut result = ImportResult::default();
+    let mut result = ImportResult {
+        not_for_host,
+        ..Default::default()
+    };
 
-    for import_alias in import_data.aliases {
+    for import_alias in aliases {
         // Validate alias name
-        if let Err(e) = validate_alias(&import_alias.name) {
+        if let Err(e) = db.check_name(&import_alias.name) {
             result.warnings.push(format!(
                 "skipping invalid alias name '{}': {}",
                 import_alias.name, e
@@ -136,6 +376,153 @@ pub fn import_from_content(
     Ok(result)
 }
 
+/// How an incoming alias compares with the alias of the same name
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ImportStatus {
+    /// No alias has the name yet
+    New,
+    /// An alias with the name points to the same path
+    Identical,
+    /// An alias with the name points elsewhere
+    Conflicting,
+}
+
+impl ImportStatus {
+    fn label(self) -> &'static str {
+        match self {
+            ImportStatus::New => "new",
+            ImportStatus::Identical => "identical",
+            ImportStatus::Conflicting => "conflicting",
+        }
+    }
+}
+
+/// One incoming alias in an [`ImportPreview`]
+#[derive(Debug, Clone)]
+pub struct PreviewEntry {
+    pub name: String,
+    pub path: String,
+    pub status: ImportStatus,
+    /// Path of the alias it conflicts with
+    pub current_path: Option<String>,
+    /// What the strategy does with it: "add", "skip", "overwrite" or "add as <name>"
+    pub action: String,
+}
+
+/// What an import would change, worked out without touching the database
+#[derive(Debug, Default)]
+pub struct ImportPreview {
+    pub entries: Vec<PreviewEntry>,
+    /// Entries skipped for an invalid alias name
+    pub invalid: usize,
+    /// Entries whose `only_hosts` / `only_os` exclude this machine
+    pub not_for_host: usize,
+}
+
+impl ImportPreview {
+    /// How many incoming aliases have `status`
+    pub fn count(&self, status: ImportStatus) -> usize {
+        self.entries.iter().filter(|e| e.status == status).count()
+    }
+
+    /// Counts per status, e.g. "2 new, 1 identical, 1 conflicting"
+    pub fn summary(&self) -> String {
+        let mut summary = format!(
+            "{} new, {} identical, {} conflicting",
+            self.count(ImportStatus::New),
+            self.count(ImportStatus::Identical),
+            self.count(ImportStatus::Conflicting)
+        );
+        if self.invalid > 0 {
+            summary.push_str(&format!(", {} invalid", self.invalid));
+        }
+        if self.not_for_host > 0 {
+            summary.push_str(&format!(", {} not for this machine", self.not_for_host));
+        }
+        summary
+    }
+}
+
+/// Work out what importing `content` with `strategy` would do
+pub fn preview(db: &Database, content: &str, strategy: ImportStrategy) -> Result<ImportPreview, Box<dyn std::error::Error>> {
+    preview_for_machine(db, content, strategy, hostname().as_deref(), std::env::consts::OS)
+}
+
+/// [`preview`] as if running on `host` with `os`
+fn preview_for_machine(
+    db: &Database,
+    content: &str,
+    strategy: ImportStrategy,
+    host: Option<&str>,
+    os: &str,
+) -> Result<ImportPreview, Box<dyn std::error::Error>> {
+    let (aliases, not_for_host) = entries_for_machine(content, host, os)?;
+
+    // Paths by name as the import goes, so repeated names in the file
+    // compare with the entry before them like the import itself does
+    let mut current: HashMap<String, String> = db.all().map(|a| (a.name.clone(), a.path.clone())).collect();
+    let mut taken: HashMap<String, bool> = current.keys().map(|n| (n.clone(), true)).collect();
+
+    let mut preview = ImportPreview {
+        not_for_host,
+        ..Default::default()
+    };
+    for alias in aliases {
+        if db.check_name(&alias.name).is_err() {
+            preview.invalid += 1;
+            continue;
+        }
+        let existing = current.get(&alias.name).cloned();
+        let status = match &existing {
+            None => ImportStatus::New,
+            Some(path) if *path == alias.path => ImportStatus::Identical,
+            Some(_) => ImportStatus::Conflicting,
+        };
+        let action = match (status, strategy) {
+            (ImportStatus::New, _) | (_, ImportStrategy::Overwrite) => {
+                current.insert(alias.name.clone(), alias.path.clone());
+                taken.insert(alias.name.clone(), true);
+                if status == ImportStatus::New { "add".to_string() } else { "overwrite".to_string() }
+            }
+            (_, ImportStrategy::Skip) => "skip".to_string(),
+            (_, ImportStrategy::Rename) => {
+                let new_name = find_unique_name(&alias.name, &taken);
+                taken.insert(new_name.clone(), true);
+                current.insert(new_name.clone(), alias.path.clone());
+                format!("add as {}", new_name)
+            }
+        };
+        preview.entries.push(PreviewEntry {
+            name: alias.name,
+            path: alias.path,
+            status,
+            current_path: existing.filter(|_| status == ImportStatus::Conflicting),
+            action,
+        });
+    }
+    Ok(preview)
+}
+
+/// Render an [`ImportPreview`] as a table
+pub fn format_preview(preview: &ImportPreview, config: &Config) -> String {
+    let style = TableStyle::from(config.user.display.table_style.as_str());
+    let path_style = PathStyle::from(config.user.display.path_style.as_str());
+    let mut table = create_table(style);
+    table.set_header(vec!["Alias", "Path", "Status", "Current Path", "Action"]);
+
+    for entry in &preview.entries {
+        table.add_row(vec![
+            entry.name.clone(),
+            format_path(&entry.path, path_style),
+            entry.status.label().to_string(),
+            entry.current_path.as_deref().map(|p| format_path(p, path_style)).unwrap_or_default(),
+            entry.action.clone(),
+        ]);
+    }
+
+    table.to_string()
+}
+
 /// Generate a unique alias name by appending a numeric suffix
 fn find_unique_name(base_name: &str, existing_names: &HashMap<String, bool>) -> String {
     let mut suffix = 2;
@@ -170,10 +557,10 @@ mod tests {
 
     #[test]
     fn test_export_empty_database() {
-        let (db, _dir) = create_test_db();
-        // Export should succeed but print message to stderr
-        let result = export(&db);
-        assert!(result.is_ok());
+        let (db, dir) = create_test_db();
+        let output = dir.path().join("backup.toml");
+        assert_eq!(export_to_file(&db, output.to_str().unwrap(), None).unwrap(), 0);
+        assert!(!output.exists());
     }
 
     #[test]
@@ -184,8 +571,100 @@ mod tests {
         alias.use_count = 5;
         db.insert(alias);
 
-        let result = export(&db);
-        assert!(result.is_ok());
+        let (toml, count) = export(&db, None).unwrap();
+        assert_eq!(count, 1);
+        assert!(toml.contains("name = \"test\""));
+        assert!(toml.contains("work"));
+    }
+
+    #[test]
+    fn test_export_shell_aliases() {
+        let (mut db, _dir) = create_test_db();
+        let mut api = Alias::new("api", "/srv/it's api").unwrap();
+        api.add_tag("work");
+        db.insert(api);
+        db.insert(Alias::new("blog", "/tmp/blog").unwrap());
+
+        let (script, count) = export_shell_aliases(&db, ShellType::Bash, None).unwrap();
+        assert_eq!(count, 2);
+        let lines: Vec<_> = script.lines().skip(1).collect();
+        assert_eq!(lines, [r"alias api='cd '\''/srv/it'\''\'\'''\''s api'\'''", r"alias blog='cd '\''/tmp/blog'\'''"]);
+
+        let (script, count) = export_shell_aliases(&db, ShellType::Fish, Some("tag:work")).unwrap();
+        assert_eq!(count, 1);
+        assert_eq!(script.lines().nth(1), Some(r"alias api 'cd \'/srv/it\\\'s api\''"));
+
+        assert!(export_shell_aliases(&db, ShellType::Nushell, None).is_err());
+    }
+
+    #[test]
+    fn test_export_filtered() {
+        let (mut db, dir) = create_test_db();
+        let mut api = Alias::new("proj-api", "/tmp/api").unwrap();
+        api.add_tag("work");
+        db.insert(api);
+        let mut blog = Alias::new("blog", "/tmp/blog").unwrap();
+        blog.add_tag("personal");
+        db.insert(blog);
+
+        let (toml, count) = export(&db, Some("tag:work")).unwrap();
+        assert_eq!(count, 1);
+        assert!(toml.contains("proj-api"));
+        assert!(!toml.contains("blog"));
+
+        let (toml, count) = export(&db, Some("!proj-*")).unwrap();
+        assert_eq!(count, 1);
+        assert!(toml.contains("blog"));
+
+        assert!(export(&db, Some("tag:")).is_err());
+
+        // Nothing matching means nothing written
+        let output = dir.path().join("partial.toml");
+        assert_eq!(export_to_file(&db, output.to_str().unwrap(), Some("tag:none")).unwrap(), 0);
+        assert!(!output.exists());
+
+        // A partial file imports like any other, leaving existing aliases alone
+        assert_eq!(export_to_file(&db, output.to_str().unwrap(), Some("tag:work")).unwrap(), 1);
+        let (mut other, _other_dir) = create_test_db();
+        other.insert(Alias::new("mine", "/tmp").unwrap());
+        let content = fs::read_to_string(&output).unwrap();
+        let result = import_from_content(&mut other, &content, ImportStrategy::Skip).unwrap();
+        assert_eq!(result.imported, 1);
+        assert!(other.contains("mine"));
+        assert!(other.contains("proj-api"));
+        assert!(!other.contains("blog"));
+    }
+
+    #[test]
+    fn test_export_to_file() {
+        let (mut db, dir) = create_test_db();
+        db.insert(Alias::new("test", "/tmp/test").unwrap());
+        let output = dir.path().join("backup.toml");
+
+        assert_eq!(export_to_file(&db, output.to_str().unwrap(), None).unwrap(), 1);
+
+        let content = fs::read_to_string(&output).unwrap();
+        assert!(content.contains("name = \"test\""));
+        assert!(!dir.path().join(".backup.toml.tmp").exists());
+
+        // Round-trips through import
+        let (mut other, _other_dir) = create_test_db();
+        let result = import_from_content(&mut other, &content, ImportStrategy::Skip).unwrap();
+        assert_eq!(result.imported, 1);
+    }
+
+    #[test]
+    fn test_export_to_file_replaces_existing() {
+        let (mut db, dir) = create_test_db();
+        db.insert(Alias::new("fresh", "/tmp").unwrap());
+        let output = dir.path().join("backup.toml");
+        fs::write(&output, "stale").unwrap();
+
+        export_to_file(&db, output.to_str().unwrap(), None).unwrap();
+
+        let content = fs::read_to_string(&output).unwrap();
+        assert!(content.contains("fresh"));
+        assert!(!content.contains("stale"));
     }
 
     #[test]
@@ -289,6 +768,33 @@ created_at = "2024-01-01T00:00:00Z"
         assert!(alias.has_tag("imported"));
     }
 
+    #[test]
+    fn test_import_overwrite_temporary_alias_persists() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("aliases");
+        let overlay = dir.path().join("session");
+        {
+            let mut db = Database::load_from_path(&path).unwrap();
+            db.load_overlay(&overlay).unwrap();
+            db.add_temporary(Alias::new("scratch", "/tmp/scratch").unwrap(), Vec::new()).unwrap();
+            db.save().unwrap();
+        }
+
+        let mut db = Database::load_from_path(&path).unwrap();
+        db.load_overlay(&overlay).unwrap();
+        let content = "[[aliases]]\nname = \"scratch\"\npath = \"/srv/scratch\"\n";
+        let result = import_from_content(&mut db, content, ImportStrategy::Overwrite).unwrap();
+        assert_eq!(result.imported, 1);
+        assert!(!db.is_temporary("scratch"));
+        db.save().unwrap();
+        drop(db);
+
+        // In the database file, and gone from the session overlay
+        let db = Database::load_from_path(&path).unwrap();
+        assert_eq!(db.get("scratch").unwrap().path, "/srv/scratch");
+        assert!(!overlay.exists());
+    }
+
     #[test]
     fn test_import_rename_existing() {
         let (mut db, _dir) = create_test_db_with_alias();
@@ -408,7 +914,7 @@ created_at = "2024-01-01T00:00:00Z"
         let (mut db, _dir) = create_test_db();
 
         let mut import_file = NamedTempFile::new().unwrap();
-        writeln!(import_file, "").unwrap();
+        writeln!(import_file).unwrap();
 
         let result = import(&mut db, import_file.path().to_str().unwrap(), ImportStrategy::Skip);
         assert!(result.is_err());
@@ -437,6 +943,114 @@ created_at = "2024-01-01T00:00:00Z"
         assert_eq!(find_unique_name("test", &existing), "test_5");
     }
 
+    #[test]
+    fn test_entry_conditions_applies() {
+        let any = EntryConditions::default();
+        assert!(any.applies(None, "linux"));
+
+        let hosts = EntryConditions {
+            only_hosts: vec!["Laptop".to_string(), "build.example.com".to_string()],
+            ..Default::default()
+        };
+        assert!(hosts.applies(Some("laptop"), "linux"));
+        assert!(hosts.applies(Some("build"), "linux"));
+        assert!(hosts.applies(Some("laptop.local"), "linux"));
+        assert!(!hosts.applies(Some("desktop"), "linux"));
+        assert!(!hosts.applies(None, "linux"));
+
+        let os = EntryConditions {
+            only_os: vec!["Darwin".to_string()],
+            ..Default::default()
+        };
+        assert!(os.applies(None, "macos"));
+        assert!(!os.applies(None, "linux"));
+    }
+
+    #[test]
+    fn test_import_skips_entries_for_other_machines() {
+        let (mut db, _dir) = create_test_db();
+        let content = r#"
+[[aliases]]
+name = "everywhere"
+path = "/tmp"
+created_at = "2024-01-01T00:00:00Z"
+
+[[aliases]]
+name = "laptop-only"
+path = "/tmp"
+created_at = "2024-01-01T00:00:00Z"
+only_hosts = ["laptop"]
+
+[[aliases]]
+name = "mac-only"
+path = "/tmp"
+created_at = "2024-01-01T00:00:00Z"
+only_os = ["macos"]
+"#;
+
+        let result = import_for_machine(&mut db, content, ImportStrategy::Skip, Some("laptop"), "linux").unwrap();
+        assert_eq!(result.imported, 2);
+        assert_eq!(result.not_for_host, 1);
+        assert!(db.contains("everywhere") && db.contains("laptop-only"));
+        assert!(!db.contains("mac-only"));
+
+        let bad = "[[aliases]]\nname = \"x\"\npath = \"/tmp\"\nonly_os = \"linux\"\n";
+        let err = import_for_machine(&mut db, bad, ImportStrategy::Skip, None, "linux").unwrap_err();
+        assert!(err.to_string().contains("only_os must be a list of strings"));
+    }
+
+    #[test]
+    fn test_preview() {
+        let (mut db, _dir) = create_test_db_with_alias();
+        db.insert(Alias::new("web", "/srv/web").unwrap());
+        let content = r#"
+[[aliases]]
+name = "test"
+path = "/tmp"
+
+[[aliases]]
+name = "web"
+path = "/srv/other"
+
+[[aliases]]
+name = "api"
+path = "/srv/api"
+
+[[aliases]]
+name = "bad name"
+path = "/srv/bad"
+
+[[aliases]]
+name = "mac"
+path = "/srv/mac"
+only_os = ["macos"]
+"#;
+
+        let preview = preview_for_machine(&db, content, ImportStrategy::Skip, None, "linux").unwrap();
+        let rows: Vec<(&str, ImportStatus, &str)> =
+            preview.entries.iter().map(|e| (e.name.as_str(), e.status, e.action.as_str())).collect();
+        assert_eq!(
+            rows,
+            [
+                ("test", ImportStatus::Identical, "skip"),
+                ("web", ImportStatus::Conflicting, "skip"),
+                ("api", ImportStatus::New, "add"),
+            ]
+        );
+        assert_eq!(preview.entries[1].current_path.as_deref(), Some("/srv/web"));
+        assert_eq!(preview.entries[0].current_path, None);
+        assert_eq!(preview.summary(), "1 new, 1 identical, 1 conflicting, 1 invalid, 1 not for this machine");
+
+        let preview = preview_for_machine(&db, content, ImportStrategy::Rename, None, "linux").unwrap();
+        assert_eq!(preview.entries[1].action, "add as web_2");
+        let preview = preview_for_machine(&db, content, ImportStrategy::Overwrite, None, "linux").unwrap();
+        assert_eq!(preview.entries[1].action, "overwrite");
+
+        // Nothing was applied
+        assert_eq!(db.get("web").unwrap().path, "/srv/web");
+        assert!(!db.contains("api"));
+    }
+
     #[test]
     fn test_import_preserves_metadata() {
         let (mut db, _dir) = create_test_db();
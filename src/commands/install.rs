@@ -3,7 +3,11 @@
 use std::env;
 use std::error::Error;
 use std::fs;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+use crate::context::Ctx;
+use crate::exit::Failure;
 
 /// Shell wrapper script for bash (embedded)
 const SHELL_BASH: &str = include_str!("../../shell/goto.bash");
@@ -14,39 +18,79 @@ const SHELL_ZSH: &str = include_str!("../../shell/goto.zsh");
 /// Shell wrapper script for fish (embedded)
 const SHELL_FISH: &str = include_str!("../../shell/goto.fish");
 
+/// Shell wrapper script for nushell (embedded)
+const SHELL_NUSHELL: &str = include_str!("../../shell/goto.nu");
+
+/// Shell wrapper script for PowerShell (embedded)
+const SHELL_POWERSHELL: &str = include_str!("../../shell/goto.ps1");
+
+/// `cd` fallback appended to the bash wrapper (`install.cd_fallback`)
+const CD_FALLBACK_BASH: &str = include_str!("../../shell/goto-cd-fallback.bash");
+
+/// `cd` fallback appended to the zsh wrapper (`install.cd_fallback`)
+const CD_FALLBACK_ZSH: &str = include_str!("../../shell/goto-cd-fallback.zsh");
+
+/// Comment written before the source line in rc files
+const RC_MARKER_BEGIN: &str = "# goto - directory navigation";
+
+/// Comment written after the source line in rc files
+const RC_MARKER_END: &str = "# end goto";
+
 /// Supported shell types
 #[derive(Debug, Clone, Copy, PartialEq)]
 pub enum ShellType {
     Bash,
     Zsh,
     Fish,
+    Nushell,
+    PowerShell,
 }
 
-impl ShellType {
+impl FromStr for ShellType {
+    type Err = String;
+
     /// Parse shell type from string
-    pub fn from_str(s: &str) -> Result<Self, String> {
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
         match s.to_lowercase().as_str() {
             "bash" => Ok(ShellType::Bash),
             "zsh" => Ok(ShellType::Zsh),
             "fish" => Ok(ShellType::Fish),
+            "nu" | "nushell" => Ok(ShellType::Nushell),
+            "pwsh" | "powershell" => Ok(ShellType::PowerShell),
             _ => Err(format!(
-                "Invalid shell type '{}'. Must be bash, zsh, or fish.",
+                "Invalid shell type '{}'. Must be bash, zsh, fish, nushell, or powershell.",
                 s
             )),
         }
     }
+}
+
+impl ShellType {
+    /// All supported shells
+    pub fn all() -> [ShellType; 5] {
+        [
+            ShellType::Bash,
+            ShellType::Zsh,
+            ShellType::Fish,
+            ShellType::Nushell,
+            ShellType::PowerShell,
+        ]
+    }
 
     /// Auto-detect shell from SHELL environment variable
     pub fn detect() -> Result<Self, String> {
         let shell = env::var("SHELL").unwrap_or_default();
-        let shell_name = shell.rsplit('/').next().unwrap_or("");
+        let shell_name = shell.rsplit(['/', '\\']).next().unwrap_or("");
+        let shell_name = shell_name.strip_suffix(".exe").unwrap_or(shell_name);
 
         match shell_name {
             "bash" => Ok(ShellType::Bash),
             "zsh" => Ok(ShellType::Zsh),
             "fish" => Ok(ShellType::Fish),
+            "nu" => Ok(ShellType::Nushell),
+            "pwsh" | "powershell" => Ok(ShellType::PowerShell),
             _ => Err(format!(
-                "Could not auto-detect shell from '{}'. Please specify --shell=bash|zsh|fish",
+                "Could not auto-detect shell from '{}'. Please specify --shell=bash|zsh|fish|nushell|powershell",
                 shell
             )),
         }
@@ -58,7 +102,28 @@ impl ShellType {
             ShellType::Bash => SHELL_BASH,
             ShellType::Zsh => SHELL_ZSH,
             ShellType::Fish => SHELL_FISH,
+            ShellType::Nushell => SHELL_NUSHELL,
+            ShellType::PowerShell => SHELL_POWERSHELL,
+        }
+    }
+
+    /// Get the `cd` fallback, for shells that have one
+    fn cd_fallback(&self) -> Option<&'static str> {
+        match self {
+            ShellType::Bash => Some(CD_FALLBACK_BASH),
+            ShellType::Zsh => Some(CD_FALLBACK_ZSH),
+            _ => None,
+        }
+    }
+
+    /// Get the wrapper script to install, with the `cd` fallback appended
+    /// when asked for and the shell has one
+    fn wrapper_script(&self, cd_fallback: bool) -> String {
+        let mut script = self.wrapper_content().to_string();
+        if let Some(fallback) = self.cd_fallback().filter(|_| cd_fallback) {
+            script.push_str(fallback);
         }
+        script
     }
 
     /// Get the wrapper filename
@@ -67,12 +132,24 @@ impl ShellType {
             ShellType::Bash => "goto.bash",
             ShellType::Zsh => "goto.zsh",
             ShellType::Fish => "goto.fish",
+            ShellType::Nushell => "goto.nu",
+            ShellType::PowerShell => "goto.ps1",
+        }
+    }
+
+    /// Get the line that loads the wrapper from the rc file
+    fn source_line(&self, wrapper_path: &Path) -> String {
+        match self {
+            ShellType::PowerShell => format!(". \"{}\"", wrapper_path.display()),
+            _ => format!("source {}", wrapper_path.display()),
         }
     }
 
     /// Get the rc file path
     fn rc_file(&self) -> PathBuf {
-        let home = env::var("HOME").unwrap_or_else(|_| ".".to_string());
+        let home = env::var("HOME")
+            .or_else(|_| env::var("USERPROFILE"))
+            .unwrap_or_else(|_| ".".to_string());
         match self {
             ShellType::Bash => PathBuf::from(home).join(".bashrc"),
             ShellType::Zsh => PathBuf::from(home).join(".zshrc"),
@@ -80,6 +157,34 @@ impl ShellType {
                 .join(".config")
                 .join("fish")
                 .join("config.fish"),
+            ShellType::Nushell => {
+                let dir = env::var("XDG_CONFIG_HOME")
+                    .map(PathBuf::from)
+                    .unwrap_or_else(|_| PathBuf::from(&home).join(".config"))
+                    .join("nushell");
+                // Prefer config.nu, but fall back to an existing env.nu
+                let config_nu = dir.join("config.nu");
+                let env_nu = dir.join("env.nu");
+                if !config_nu.exists() && env_nu.exists() {
+                    env_nu
+                } else {
+                    config_nu
+                }
+            }
+            ShellType::PowerShell => {
+                // Matches $PROFILE (CurrentUserCurrentHost) for PowerShell 7
+                if cfg!(windows) {
+                    PathBuf::from(home)
+                        .join("Documents")
+                        .join("PowerShell")
+                        .join("Microsoft.PowerShell_profile.ps1")
+                } else {
+                    PathBuf::from(home)
+                        .join(".config")
+                        .join("powershell")
+                        .join("Microsoft.PowerShell_profile.ps1")
+                }
+            }
         }
     }
 }
@@ -89,6 +194,10 @@ pub struct InstallOptions {
     pub shell: ShellType,
     pub skip_rc: bool,
     pub dry_run: bool,
+    /// Also schedule `goto --watch-scan` (systemd user timer, or a crontab hint)
+    pub watch_timer: bool,
+    /// Make `cd <alias>` fall back to goto (`install.cd_fallback`, bash and zsh)
+    pub cd_fallback: bool,
 }
 
 impl InstallOptions {
@@ -98,6 +207,8 @@ impl InstallOptions {
             shell,
             skip_rc: false,
             dry_run: false,
+            watch_timer: false,
+            cd_fallback: false,
         }
     }
 }
@@ -108,29 +219,37 @@ pub fn install(options: &InstallOptions) -> Result<(), Box<dyn Error>> {
     let config_dir = PathBuf::from(&home).join(".config").join("goto");
     let wrapper_path = config_dir.join(options.shell.wrapper_filename());
     let rc_file = options.shell.rc_file();
-    let source_line = format!("source {}", wrapper_path.display());
+    let source_line = options.shell.source_line(&wrapper_path);
+
+    let steps = if options.watch_timer { 3 } else { 2 };
 
     println!("Installing goto shell integration for {:?}...", options.shell);
     println!();
 
     // Step 1: Create config directory and copy shell wrapper
-    println!("[1/2] Installing shell wrapper to {}", wrapper_path.display());
+    println!("[1/{}] Installing shell wrapper to {}", steps, wrapper_path.display());
+    if options.cd_fallback {
+        match options.shell.cd_fallback() {
+            Some(_) => println!("  With the cd fallback (install.cd_fallback)"),
+            None => println!("  The cd fallback is only available for bash and zsh; installing the plain wrapper"),
+        }
+    }
     if options.dry_run {
         println!("  Would create: {}", config_dir.display());
         println!("  Would write: {}", wrapper_path.display());
     } else {
         fs::create_dir_all(&config_dir)?;
-        fs::write(&wrapper_path, options.shell.wrapper_content())?;
+        fs::write(&wrapper_path, options.shell.wrapper_script(options.cd_fallback))?;
         println!("  Installed");
     }
 
     // Step 2: Update shell config (unless skipped)
     if options.skip_rc {
-        println!("[2/2] Skipping rc file modification (--skip-rc)");
+        println!("[2/{}] Skipping rc file modification (--skip-rc)", steps);
         println!("  Add this line to your shell config manually:");
         println!("  {}", source_line);
     } else {
-        println!("[2/2] Updating {}", rc_file.display());
+        println!("[2/{}] Updating {}", steps, rc_file.display());
         let rc_content = fs::read_to_string(&rc_file).unwrap_or_default();
         let already_present = rc_content.contains(&source_line);
 
@@ -144,20 +263,29 @@ pub fn install(options: &InstallOptions) -> Result<(), Box<dyn Error>> {
             if already_present {
                 println!("  Source line already present, skipping");
             } else {
-                // Create parent directory if needed (for fish)
+                // Create parent directory if needed (fish, nushell, powershell)
                 if let Some(parent) = rc_file.parent() {
                     fs::create_dir_all(parent)?;
                 }
                 let mut content = rc_content;
-                content.push_str("\n# goto - directory navigation\n");
+                content.push('\n');
+                content.push_str(RC_MARKER_BEGIN);
+                content.push('\n');
                 content.push_str(&source_line);
                 content.push('\n');
+                content.push_str(RC_MARKER_END);
+                content.push('\n');
                 fs::write(&rc_file, content)?;
                 println!("  Added source line");
             }
         }
     }
 
+    // Step 3: Periodic scan of watched directories
+    if options.watch_timer {
+        install_watch_timer(&home, options.dry_run)?;
+    }
+
     println!();
     if options.dry_run {
         println!("Dry run complete. No changes were made.");
@@ -169,6 +297,230 @@ pub fn install(options: &InstallOptions) -> Result<(), Box<dyn Error>> {
     Ok(())
 }
 
+/// Names of the systemd units scheduling `--watch-scan`
+const WATCH_UNITS: [&str; 2] = ["goto-watch.service", "goto-watch.timer"];
+
+/// Directory holding systemd user units
+fn systemd_user_dir(home: &str) -> PathBuf {
+    PathBuf::from(home).join(".config").join("systemd").join("user")
+}
+
+/// systemd units running `<bin> --watch-scan` hourly: (service, timer)
+fn watch_timer_units(bin: &Path) -> (String, String) {
+    let service = format!(
+        "[Unit]\n\
+         Description=Look for new directories to alias with goto\n\n\
+         [Service]\n\
+         Type=oneshot\n\
+         ExecStart=\"{}\" --watch-scan\n",
+        bin.display()
+    );
+    let timer = "[Unit]\n\
+                 Description=Run goto --watch-scan hourly\n\n\
+                 [Timer]\n\
+                 OnCalendar=hourly\n\
+                 Persistent=true\n\n\
+                 [Install]\n\
+                 WantedBy=timers.target\n"
+        .to_string();
+    (service, timer)
+}
+
+/// Write a systemd user timer for `--watch-scan`, or explain the cron line
+/// where systemd isn't available
+fn install_watch_timer(home: &str, dry_run: bool) -> Result<(), Box<dyn Error>> {
+    let bin = env::current_exe()?;
+
+    if !cfg!(target_os = "linux") {
+        println!("[3/3] Scheduling --watch-scan");
+        println!("  Add this line to your crontab (crontab -e):");
+        println!("  0 * * * * \"{}\" --watch-scan", bin.display());
+        return Ok(());
+    }
+
+    let unit_dir = systemd_user_dir(home);
+    let service_path = unit_dir.join(WATCH_UNITS[0]);
+    let timer_path = unit_dir.join(WATCH_UNITS[1]);
+    println!("[3/3] Installing systemd timer to {}", timer_path.display());
+    if dry_run {
+        println!("  Would write: {}", service_path.display());
+        println!("  Would write: {}", timer_path.display());
+        return Ok(());
+    }
+
+    let (service, timer) = watch_timer_units(&bin);
+    fs::create_dir_all(&unit_dir)?;
+    fs::write(&service_path, service)?;
+    fs::write(&timer_path, timer)?;
+    println!("  Installed");
+    println!("  Enable it with: systemctl --user enable --now goto-watch.timer");
+    Ok(())
+}
+
+/// Uninstall options
+pub struct UninstallOptions {
+    /// Shell to uninstall from; `None` cleans up every supported shell
+    pub shell: Option<ShellType>,
+    /// Also remove the data and config directories
+    pub purge: bool,
+    /// Skip the purge confirmation
+    pub force: bool,
+    pub dry_run: bool,
+}
+
+/// Remove the goto block from rc file content
+///
+/// Removes everything between the install markers, a legacy marker followed
+/// by the source line, and any bare copy of the source line. Returns `None`
+/// if nothing was removed.
+fn remove_integration_block(content: &str, source_line: &str) -> Option<String> {
+    let lines: Vec<&str> = content.lines().collect();
+    let mut kept: Vec<&str> = Vec::with_capacity(lines.len());
+    let mut removed = false;
+    let mut i = 0;
+
+    while i < lines.len() {
+        let line = lines[i].trim();
+
+        if line == RC_MARKER_BEGIN {
+            // Drop the blank separator line install adds before the block
+            if kept.last().is_some_and(|l| l.trim().is_empty()) {
+                kept.pop();
+            }
+            let end = lines[i + 1..]
+                .iter()
+                .position(|l| l.trim() == RC_MARKER_END)
+                .map(|offset| i + 1 + offset);
+            i = match end {
+                Some(end) => end + 1,
+                // Legacy block without an end marker: marker + source line
+                None if lines.get(i + 1).is_some_and(|l| l.trim() == source_line) => i + 2,
+                None => i + 1,
+            };
+            removed = true;
+            continue;
+        }
+
+        if line == source_line {
+            removed = true;
+        } else {
+            kept.push(lines[i]);
+        }
+        i += 1;
+    }
+
+    if !removed {
+        return None;
+    }
+
+    let mut result = kept.join("\n");
+    if !result.is_empty() {
+        result.push('\n');
+    }
+    Some(result)
+}
+
+/// Remove shell integration (rc file block + wrapper script), optionally purging data
+///
+/// `data_dirs` are the directories removed by `--purge`, see [`crate::config::purge_dirs`].
+pub fn uninstall(ctx: &mut Ctx, options: &UninstallOptions, data_dirs: &[&Path]) -> Result<(), Box<dyn Error>> {
+    let home = env::var("HOME")?;
+    let config_dir = PathBuf::from(&home).join(".config").join("goto");
+    let shells: Vec<ShellType> = match options.shell {
+        Some(shell) => vec![shell],
+        None => ShellType::all().to_vec(),
+    };
+
+    let mut changed = false;
+
+    for shell in shells {
+        let wrapper_path = config_dir.join(shell.wrapper_filename());
+        let rc_file = shell.rc_file();
+        let source_line = shell.source_line(&wrapper_path);
+
+        if let Ok(rc_content) = fs::read_to_string(&rc_file) {
+            if let Some(new_content) = remove_integration_block(&rc_content, &source_line) {
+                changed = true;
+                if options.dry_run {
+                    println!("Would remove goto block from {}", rc_file.display());
+                } else {
+                    fs::write(&rc_file, new_content)?;
+                    println!("Removed goto block from {}", rc_file.display());
+                }
+            }
+        }
+
+        if wrapper_path.exists() {
+            changed = true;
+            if options.dry_run {
+                println!("Would delete: {}", wrapper_path.display());
+            } else {
+                fs::remove_file(&wrapper_path)?;
+                println!("Deleted: {}", wrapper_path.display());
+            }
+        }
+    }
+
+    // The --watch-scan timer from `--install --watch-timer`
+    for unit in WATCH_UNITS {
+        let path = systemd_user_dir(&home).join(unit);
+        if path.exists() {
+            changed = true;
+            if options.dry_run {
+                println!("Would delete: {}", path.display());
+            } else {
+                fs::remove_file(&path)?;
+                println!("Deleted: {}", path.display());
+            }
+        }
+    }
+
+    if !changed {
+        println!("No shell integration found");
+    }
+
+    let mut purge_dirs: Vec<&Path> = Vec::new();
+    for dir in data_dirs {
+        if options.purge && dir.exists() && !purge_dirs.contains(dir) {
+            purge_dirs.push(dir);
+        }
+    }
+
+    if !purge_dirs.is_empty() {
+        if options.dry_run {
+            for dir in &purge_dirs {
+                println!("Would delete database directory: {}", dir.display());
+            }
+        } else {
+            let listing = purge_dirs
+                .iter()
+                .map(|d| d.display().to_string())
+                .collect::<Vec<_>>()
+                .join(", ");
+            let confirmed = options.force
+                || ctx.confirm(
+                    &format!("Delete database directory {} and all aliases?", listing),
+                    false,
+                )?;
+            if !confirmed {
+                return Err(Failure::cancelled("Purge cancelled").into());
+            }
+            for dir in &purge_dirs {
+                fs::remove_dir_all(dir)?;
+                println!("Deleted database directory: {}", dir.display());
+            }
+        }
+    }
+
+    if options.dry_run {
+        println!("Dry run complete. No changes were made.");
+    } else if changed {
+        println!("Restart your shell to finish uninstalling.");
+    }
+
+    Ok(())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -199,7 +551,15 @@ mod tests {
         let err = result.unwrap_err();
         assert!(err.contains("Invalid shell type"));
         assert!(err.contains("invalid"));
-        assert!(err.contains("bash, zsh, or fish"));
+        assert!(err.contains("bash, zsh, fish, nushell, or powershell"));
+    }
+
+    #[test]
+    fn test_shell_type_from_str_nushell_powershell() {
+        assert!(matches!(ShellType::from_str("nu"), Ok(ShellType::Nushell)));
+        assert!(matches!(ShellType::from_str("Nushell"), Ok(ShellType::Nushell)));
+        assert!(matches!(ShellType::from_str("pwsh"), Ok(ShellType::PowerShell)));
+        assert!(matches!(ShellType::from_str("PowerShell"), Ok(ShellType::PowerShell)));
     }
 
     #[test]
@@ -222,6 +582,8 @@ mod tests {
         assert!(!ShellType::Bash.wrapper_content().is_empty());
         assert!(!ShellType::Zsh.wrapper_content().is_empty());
         assert!(!ShellType::Fish.wrapper_content().is_empty());
+        assert!(!ShellType::Nushell.wrapper_content().is_empty());
+        assert!(!ShellType::PowerShell.wrapper_content().is_empty());
     }
 
     #[test]
@@ -237,6 +599,56 @@ mod tests {
         assert_eq!(ShellType::Bash.wrapper_filename(), "goto.bash");
         assert_eq!(ShellType::Zsh.wrapper_filename(), "goto.zsh");
         assert_eq!(ShellType::Fish.wrapper_filename(), "goto.fish");
+        assert_eq!(ShellType::Nushell.wrapper_filename(), "goto.nu");
+        assert_eq!(ShellType::PowerShell.wrapper_filename(), "goto.ps1");
+    }
+
+    #[test]
+    fn test_wrapper_content_registers_completion() {
+        assert!(ShellType::Nushell
+            .wrapper_content()
+            .contains("def --env goto"));
+        assert!(ShellType::Nushell
+            .wrapper_content()
+            .contains("nu-complete goto aliases"));
+        assert!(ShellType::PowerShell
+            .wrapper_content()
+            .contains("Register-ArgumentCompleter"));
+    }
+
+    #[test]
+    fn test_source_line() {
+        let path = Path::new("/home/user/.config/goto/goto.nu");
+        assert_eq!(
+            ShellType::Nushell.source_line(path),
+            "source /home/user/.config/goto/goto.nu"
+        );
+        let path = Path::new("/home/user/.config/goto/goto.ps1");
+        assert_eq!(
+            ShellType::PowerShell.source_line(path),
+            ". \"/home/user/.config/goto/goto.ps1\""
+        );
+        let path = Path::new("/home/user/.config/goto/goto.bash");
+        assert_eq!(
+            ShellType::Bash.source_line(path),
+            "source /home/user/.config/goto/goto.bash"
+        );
+    }
+
+    #[test]
+    fn test_rc_file_nushell() {
+        let rc = ShellType::Nushell.rc_file();
+        let rc_str = rc.to_string_lossy();
+        assert!(rc_str.contains("nushell"));
+        assert!(rc_str.ends_with("config.nu") || rc_str.ends_with("env.nu"));
+    }
+
+    #[test]
+    fn test_rc_file_powershell() {
+        let rc = ShellType::PowerShell.rc_file();
+        assert!(rc
+            .to_string_lossy()
+            .ends_with("Microsoft.PowerShell_profile.ps1"));
     }
 
     #[test]
@@ -275,12 +687,77 @@ mod tests {
         assert!(components.len() >= 4);
     }
 
+    #[test]
+    fn test_remove_integration_block_with_markers() {
+        let source = "source /home/user/.config/goto/goto.bash";
+        let content = format!(
+            "export A=1\n\n{}\n{}\n{}\nexport B=2\n",
+            RC_MARKER_BEGIN, source, RC_MARKER_END
+        );
+        let result = remove_integration_block(&content, source).unwrap();
+        assert_eq!(result, "export A=1\nexport B=2\n");
+    }
+
+    #[test]
+    fn test_remove_integration_block_legacy() {
+        // Older installs wrote only the begin marker
+        let source = "source /home/user/.config/goto/goto.zsh";
+        let content = format!("export A=1\n\n{}\n{}\n", RC_MARKER_BEGIN, source);
+        let result = remove_integration_block(&content, source).unwrap();
+        assert_eq!(result, "export A=1\n");
+    }
+
+    #[test]
+    fn test_remove_integration_block_bare_source_line() {
+        let source = "source /home/user/.config/goto/goto.fish";
+        let content = format!("set -x A 1\n{}\n", source);
+        let result = remove_integration_block(&content, source).unwrap();
+        assert_eq!(result, "set -x A 1\n");
+    }
+
+    #[test]
+    fn test_remove_integration_block_not_present() {
+        let source = "source /home/user/.config/goto/goto.bash";
+        assert!(remove_integration_block("export A=1\n", source).is_none());
+        assert!(remove_integration_block("", source).is_none());
+    }
+
+    #[test]
+    fn test_shell_type_all() {
+        let all = ShellType::all();
+        assert_eq!(all.len(), 5);
+        assert!(all.contains(&ShellType::Nushell));
+        assert!(all.contains(&ShellType::PowerShell));
+    }
+
     #[test]
     fn test_install_options_new_defaults() {
         let opts = InstallOptions::new(ShellType::Bash);
         assert_eq!(opts.shell, ShellType::Bash);
         assert!(!opts.dry_run);
         assert!(!opts.skip_rc);
+        assert!(!opts.watch_timer);
+        assert!(!opts.cd_fallback);
+    }
+
+    #[test]
+    fn test_wrapper_script_cd_fallback() {
+        assert_eq!(ShellType::Bash.wrapper_script(false), SHELL_BASH);
+        let script = ShellType::Bash.wrapper_script(true);
+        assert!(script.starts_with(SHELL_BASH));
+        assert!(script.contains("--resolve-quiet"));
+        assert!(ShellType::Zsh.wrapper_script(true).contains("GOTO_SHELL=zsh GOTO_OLDPWD"));
+
+        // No fallback for the other shells
+        assert_eq!(ShellType::Fish.wrapper_script(true), SHELL_FISH);
+    }
+
+    #[test]
+    fn test_watch_timer_units() {
+        let (service, timer) = watch_timer_units(Path::new("/usr/local/bin/goto-bin"));
+        assert!(service.contains("ExecStart=\"/usr/local/bin/goto-bin\" --watch-scan\n"));
+        assert!(timer.contains("OnCalendar=hourly\n"));
+        assert!(timer.contains("WantedBy=timers.target\n"));
     }
 
     #[test]
@@ -316,7 +793,7 @@ mod tests {
     #[test]
     fn test_shell_type_clone() {
         let shell = ShellType::Bash;
-        let cloned = shell.clone();
+        let cloned = shell;
         assert_eq!(shell, cloned);
     }
 
@@ -424,6 +901,22 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_detect_nushell_powershell() {
+        let original = env::var("SHELL").ok();
+
+        env::set_var("SHELL", "/usr/bin/nu");
+        assert!(matches!(ShellType::detect(), Ok(ShellType::Nushell)));
+
+        env::set_var("SHELL", "/usr/bin/pwsh");
+        assert!(matches!(ShellType::detect(), Ok(ShellType::PowerShell)));
+
+        match original {
+            Some(val) => env::set_var("SHELL", val),
+            None => env::remove_var("SHELL"),
+        }
+    }
+
     #[test]
     fn test_detect_empty_shell_env() {
         let original = env::var("SHELL").ok();
@@ -1,8 +1,26 @@
-//! List commands: list, list_with_options, list_names
-
+//! List commands: list, list_names, and `goto %N` for rows of the last listing
+
+use std::collections::{BTreeMap, HashMap};
+use std::fs::{self, File};
+use std::io::BufReader;
+use std::path::{Component, Path, PathBuf};
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+use comfy_table::Table;
+use serde::{Deserialize, Serialize};
+
+use crate::alias::Alias;
+use crate::collate;
+use crate::commands::check::{alias_statuses, PathStatus};
+use crate::commands::navigate::{self, NavigateOptions, NavigationResult};
 use crate::config::Config;
+use crate::context::Ctx;
 use crate::database::Database;
-use crate::table::{TableStyle, create_table};
+use crate::exit::Failure;
+use crate::filter::AliasFilter;
+use crate::stack;
+use crate::table::{format_date, stdout_is_terminal, DateStyle, PathStyle, TableFit, TableStyle, TagStyle, create_table, format_path};
 
 /// Sort order for listing aliases
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
@@ -14,6 +32,8 @@ pub enum SortOrder {
     Usage,
     /// Sort by last used time (most recent first)
     Recent,
+    /// Sort by path status (problems first)
+    Status,
 }
 
 impl From<&str> for SortOrder {
@@ -21,6 +41,7 @@ impl From<&str> for SortOrder {
         match s.to_lowercase().as_str() {
             "usage" => SortOrder::Usage,
             "recent" => SortOrder::Recent,
+            "status" => SortOrder::Status,
             _ => SortOrder::Alpha,
         }
     }
@@ -32,106 +53,482 @@ impl std::fmt::Display for SortOrder {
             SortOrder::Alpha => write!(f, "alpha"),
             SortOrder::Usage => write!(f, "usage"),
             SortOrder::Recent => write!(f, "recent"),
+            SortOrder::Status => write!(f, "status"),
         }
     }
 }
 
-/// List all aliases with optional sorting and filtering
-pub fn list_with_options(
-    db: &Database,
-    config: &Config,
-    sort_order: Option<&str>,
-    filter_tag: Option<&str>,
-) -> Result<(), Box<dyn std::error::Error>> {
-    let mut aliases: Vec<_> = db.all().cloned().collect();
-
-    // Filter by tag if specified
-    if let Some(tag) = filter_tag {
-        let tag_lower = tag.to_lowercase();
-        aliases.retain(|a| a.tags.iter().any(|t| t.to_lowercase() == tag_lower));
-    }
-
-    if aliases.is_empty() {
-        if filter_tag.is_some() {
-            eprintln!("No aliases with tag '{}'", filter_tag.unwrap());
-        } else {
-            eprintln!("No aliases registered");
+/// A column that can be shown in the alias listing
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Column {
+    /// Row number for `goto %N`
+    Index,
+    Name,
+    Path,
+    Tags,
+    Count,
+    LastUsed,
+    Created,
+    Status,
+}
+
+impl Column {
+    /// Parse a single column name
+    pub fn parse(s: &str) -> Result<Self, Failure> {
+        match s.trim().to_lowercase().as_str() {
+            "index" | "#" => Ok(Column::Index),
+            "name" => Ok(Column::Name),
+            "path" => Ok(Column::Path),
+            "tags" => Ok(Column::Tags),
+            "count" | "uses" => Ok(Column::Count),
+            "last_used" | "last-used" => Ok(Column::LastUsed),
+            "created" | "created_at" => Ok(Column::Created),
+            "status" => Ok(Column::Status),
+            other => Err(Failure::invalid(format!(
+                "invalid column '{}': expected index, name, path, tags, count, last_used, created, status",
+                other
+            ))),
         }
-        return Ok(());
     }
 
-    // Determine sort order from argument or config default
-    let order = sort_order
-        .map(SortOrder::from)
-        .unwrap_or_else(|| SortOrder::from(config.user.general.default_sort.as_str()));
+    /// Parse a comma-separated column list (e.g. "name,path,count")
+    pub fn parse_list(s: &str) -> Result<Vec<Self>, Failure> {
+        let columns = s
+            .split(',')
+            .filter(|c| !c.trim().is_empty())
+            .map(Column::parse)
+            .collect::<Result<Vec<_>, _>>()?;
 
-    // Sort entries
-    match order {
-        SortOrder::Usage => aliases.sort_by(|a, b| b.use_count.cmp(&a.use_count)),
-        SortOrder::Recent => aliases.sort_by(|a, b| b.last_used.cmp(&a.last_used)),
-        SortOrder::Alpha => aliases.sort_by(|a, b| a.name.cmp(&b.name)),
+        if columns.is_empty() {
+            return Err(Failure::invalid("invalid column list: at least one column is required"));
+        }
+        Ok(columns)
     }
 
-    // Build table with configured style
-    let style = TableStyle::from(config.user.display.table_style.as_str());
-    let mut table = create_table(style);
+    /// Default columns derived from the display config
+    pub fn defaults(config: &Config) -> Vec<Self> {
+        let mut columns = Vec::new();
+        if config.user.display.show_index {
+            columns.push(Column::Index);
+        }
+        columns.extend([Column::Name, Column::Path]);
+        if config.user.display.show_stats && config.user.privacy.track_usage {
+            columns.push(Column::Count);
+        }
+        if config.user.display.show_tags {
+            columns.push(Column::Tags);
+        }
+        if config.user.display.check_paths {
+            columns.push(Column::Status);
+        }
+        columns
+    }
 
-    // Build header dynamically based on config
-    let mut header = vec!["Name", "Path"];
-    if config.user.display.show_stats {
-        header.push("Uses");
+    /// Table header for this column
+    pub fn header(&self) -> &'static str {
+        match self {
+            Column::Index => "#",
+            Column::Name => "Name",
+            Column::Path => "Path",
+            Column::Tags => "Tags",
+            Column::Count => "Uses",
+            Column::LastUsed => "Last Used",
+            Column::Created => "Created",
+            Column::Status => "Status",
+        }
     }
-    if config.user.display.show_tags {
-        header.push("Tags");
+
+    /// Render the cell for an alias; `index` is its 1-based row in the
+    /// listing and `status` its checked path status
+    pub fn cell(
+        &self,
+        alias: &Alias,
+        index: usize,
+        path_style: PathStyle,
+        date_style: DateStyle,
+        tag_style: TagStyle,
+        status: Option<PathStatus>,
+    ) -> String {
+        match self {
+            Column::Index => index.to_string(),
+            Column::Name => alias.name.clone(),
+            Column::Path if alias.is_pending() => {
+                format!("{} (pending)", format_path(&alias.path, path_style))
+            }
+            Column::Path => format_path(&alias.path, path_style),
+            Column::Tags => tag_style.join(&alias.tags, ", "),
+            Column::Count => alias.use_count.to_string(),
+            Column::LastUsed => format_date(alias.last_used, date_style),
+            Column::Created => format_date(Some(alias.created_at), date_style),
+            Column::Status => status.map_or("-", PathStatus::label).to_string(),
+        }
     }
-    table.set_header(header);
+}
 
-    // Add rows for each alias
-    for alias in &aliases {
-        let mut row: Vec<String> = vec![alias.name.clone(), alias.path.clone()];
+/// What to list, as given on the command line
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ListOptions<'a> {
+    /// Sort order (`alpha`, `usage`, `recent`); the configured default when `None`
+    pub sort: Option<&'a str>,
+    /// Only aliases matching this filter expression (see [`AliasFilter`])
+    pub filter: Option<&'a str>,
+    /// Comma-separated columns; the configured ones when `None`
+    pub columns: Option<&'a str>,
+    /// Split into sections (`tag` or `dir-prefix`)
+    pub group_by: Option<&'a str>,
+}
 
-        if config.user.display.show_stats {
-            row.push(alias.use_count.to_string());
+/// Aliases selected by [`list`]
+#[derive(Debug, Clone)]
+#[non_exhaustive]
+pub struct ListResult {
+    /// Matching aliases in the requested order
+    pub aliases: Vec<Alias>,
+    /// Columns to show
+    pub columns: Vec<Column>,
+    /// Sections requested with `--group-by`
+    pub group_by: Option<GroupBy>,
+    /// Path status per alias name, when the status column or sort needs it
+    pub statuses: HashMap<String, PathStatus>,
+}
+
+/// How `-l --group-by` splits the listing into sections
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GroupBy {
+    /// One section per tag; aliases appear under each of their tags
+    Tag,
+    /// One section per top-level directory (below `~` for paths in $HOME)
+    DirPrefix,
+}
+
+impl GroupBy {
+    pub fn parse(s: &str) -> Result<Self, Failure> {
+        match s.trim().to_lowercase().as_str() {
+            "tag" | "tags" => Ok(GroupBy::Tag),
+            "dir-prefix" | "dir" => Ok(GroupBy::DirPrefix),
+            other => Err(Failure::invalid(format!(
+                "invalid group-by '{}': expected tag, dir-prefix",
+                other
+            ))),
         }
+    }
+}
 
-        if config.user.display.show_tags {
-            let tags_str = if alias.tags.is_empty() {
-                "-".to_string()
-            } else {
-                alias.tags.join(", ")
+/// Section name for aliases without tags
+const UNTAGGED_GROUP: &str = "(none)";
+
+/// Split sorted aliases into named sections, keeping their order within each
+pub fn group_aliases(aliases: &[Alias], group_by: GroupBy) -> Vec<(String, Vec<&Alias>)> {
+    let mut groups: BTreeMap<String, Vec<&Alias>> = BTreeMap::new();
+    let mut untagged = Vec::new();
+
+    for alias in aliases {
+        match group_by {
+            GroupBy::Tag if alias.tags.is_empty() => untagged.push(alias),
+            GroupBy::Tag => {
+                for tag in &alias.tags {
+                    groups.entry(tag.clone()).or_default().push(alias);
+                }
+            }
+            GroupBy::DirPrefix => groups.entry(dir_prefix(&alias.path)).or_default().push(alias),
+        }
+    }
+
+    let mut sections: Vec<_> = groups.into_iter().collect();
+    if !untagged.is_empty() {
+        sections.push((UNTAGGED_GROUP.to_string(), untagged));
+    }
+    sections
+}
+
+/// Top-level parent directory of a path, e.g. `~/src` or `/opt`
+fn dir_prefix(path: &str) -> String {
+    let path = Path::new(path);
+    if let Some(home) = dirs::home_dir() {
+        if let Ok(rest) = path.strip_prefix(&home) {
+            return match rest.components().next() {
+                Some(first) => format!("~/{}", first.as_os_str().to_string_lossy()),
+                None => "~".to_string(),
             };
-            row.push(tags_str);
         }
+    }
+    match path.components().find(|c| matches!(c, Component::Normal(_))) {
+        Some(first) => format!("/{}", first.as_os_str().to_string_lossy()),
+        None => "/".to_string(),
+    }
+}
+
+/// Select aliases to list, validating the column and grouping options
+pub fn list(
+    db: &Database,
+    config: &Config,
+    options: &ListOptions,
+) -> Result<ListResult, Box<dyn std::error::Error>> {
+    // Validate options before doing any work
+    let mut columns = match options.columns {
+        Some(spec) => Column::parse_list(spec)?,
+        None => Column::defaults(config),
+    };
+    // Usage isn't shown when it isn't tracked, even when asked for
+    if !config.user.privacy.track_usage {
+        columns.retain(|c| !matches!(c, Column::Count | Column::LastUsed));
+        if columns.is_empty() {
+            columns.push(Column::Name);
+        }
+    }
+    // Without path checks there is no status to show
+    if !config.user.display.check_paths {
+        columns.retain(|c| *c != Column::Status);
+        if columns.is_empty() {
+            columns.push(Column::Name);
+        }
+    }
+    let group_by = options.group_by.map(GroupBy::parse).transpose()?;
+    let filter = options.filter.map(AliasFilter::parse).transpose()?;
+
+    let mut aliases: Vec<Alias> = db
+        .all()
+        .filter(|a| filter.as_ref().is_none_or(|f| f.matches(a)))
+        .cloned()
+        .collect();
+    let order = sort_order(config, options.sort);
+    let statuses = if columns.contains(&Column::Status) || order == SortOrder::Status {
+        check_paths(&aliases, Duration::from_millis(config.user.general.path_timeout_ms))
+    } else {
+        HashMap::new()
+    };
+    sort_aliases(&mut aliases, order, &statuses);
+
+    Ok(ListResult {
+        aliases,
+        columns,
+        group_by,
+        statuses,
+    })
+}
+
+/// Path status of each alias, by name
+fn check_paths(aliases: &[Alias], timeout: Duration) -> HashMap<String, PathStatus> {
+    aliases
+        .iter()
+        .map(|a| a.name.clone())
+        .zip(alias_statuses(aliases, timeout))
+        .collect()
+}
 
+/// Render a listing as one table, or one titled table per group
+///
+/// Long paths are shortened to fit the terminal unless `wide` (`--wide`).
+pub fn format_list(result: &ListResult, config: &Config, wide: bool) -> String {
+    let fit = config.table_fit(wide);
+    match result.group_by {
+        None => build_table(config, result, result.aliases.iter(), fit).to_string(),
+        Some(group_by) => group_aliases(&result.aliases, group_by)
+            .iter()
+            .map(|(group, members)| {
+                format!(
+                    "{} ({})\n{}",
+                    group,
+                    members.len(),
+                    build_table(config, result, members.iter().copied(), fit)
+                )
+            })
+            .collect::<Vec<_>>()
+            .join("\n\n"),
+    }
+}
+
+/// Build a table of aliases with the configured style
+///
+/// Paths are shortened to fit `fit`'s width; the other columns are kept.
+fn build_table<'a>(
+    config: &Config,
+    result: &ListResult,
+    aliases: impl Iterator<Item = &'a Alias>,
+    fit: TableFit,
+) -> Table {
+    let style = TableStyle::from(config.user.display.table_style.as_str());
+    let path_style = PathStyle::from(config.user.display.path_style.as_str());
+    let date_style = config.user.display.date_style();
+    let tag_style = config.tag_style(stdout_is_terminal());
+    let mut table = create_table(style);
+    fit.apply(&mut table);
+    // Rows keep their number from the ungrouped listing, so `goto %N` doesn't
+    // depend on --group-by
+    let rows: HashMap<&str, usize> = result
+        .aliases
+        .iter()
+        .enumerate()
+        .map(|(i, a)| (a.name.as_str(), i + 1))
+        .collect();
+
+    let header: Vec<&str> = result.columns.iter().map(Column::header).collect();
+    let mut cells: Vec<Vec<String>> = aliases
+        .map(|alias| {
+            let status = result.statuses.get(&alias.name).copied();
+            let index = rows.get(alias.name.as_str()).copied().unwrap_or_default();
+            result
+                .columns
+                .iter()
+                .map(|c| c.cell(alias, index, path_style, date_style, tag_style, status))
+                .collect()
+        })
+        .collect();
+    if let Some(path) = result.columns.iter().position(|c| *c == Column::Path) {
+        fit.shorten_paths(style, &header, &mut cells, path);
+    }
+
+    table.set_header(header);
+    for row in cells {
         table.add_row(row);
     }
 
-    println!("{table}");
+    table
+}
+
+/// Format a single alias as a line of the stable porcelain format
+///
+/// Fields are tab-separated: `name`, `path`, `tags` (comma-separated),
+/// `count` and `last_used` (RFC 3339, empty if never used). This format
+/// will not change between versions; new fields are only ever appended.
+pub fn porcelain_line(alias: &Alias) -> String {
+    let last_used = alias
+        .last_used
+        .map(|t| t.to_rfc3339_opts(chrono::SecondsFormat::Secs, true))
+        .unwrap_or_default();
+    format!(
+        "{}\t{}\t{}\t{}\t{}",
+        alias.name,
+        alias.path,
+        alias.tags.join(","),
+        alias.use_count,
+        last_used
+    )
+}
+
+/// Sort order from the argument or the config default
+///
+/// Usage orders fall back to name when usage isn't tracked, and status does
+/// when path checks are off.
+fn sort_order(config: &Config, sort_order: Option<&str>) -> SortOrder {
+    let order = sort_order
+        .map(SortOrder::from)
+        .unwrap_or_else(|| SortOrder::from(config.user.general.default_sort.as_str()));
+    match order {
+        SortOrder::Usage | SortOrder::Recent if !config.user.privacy.track_usage => SortOrder::Alpha,
+        SortOrder::Status if !config.user.display.check_paths => SortOrder::Alpha,
+        order => order,
+    }
+}
+
+/// Sort aliases in place; `statuses` is only used by [`SortOrder::Status`]
+fn sort_aliases(aliases: &mut [Alias], order: SortOrder, statuses: &HashMap<String, PathStatus>) {
+    match order {
+        SortOrder::Usage => aliases.sort_by_key(|a| std::cmp::Reverse(a.use_count)),
+        SortOrder::Recent => aliases.sort_by_key(|a| std::cmp::Reverse(a.last_used)),
+        SortOrder::Alpha => aliases.sort_by_cached_key(|a| collate::sort_key(&a.name)),
+        SortOrder::Status => aliases.sort_by(|a, b| {
+            let status = |alias: &Alias| statuses.get(&alias.name).copied().unwrap_or(PathStatus::Unreachable);
+            status(a).cmp(&status(b)).then_with(|| collate::compare(&a.name, &b.name))
+        }),
+    }
+}
+
+/// How long `goto %N` trusts the last listing
+pub const LISTING_TTL: Duration = Duration::from_secs(30 * 60);
+
+/// Rows of the last `goto -l` in a shell session, for `goto %N`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+struct Listing {
+    listed_at: DateTime<Utc>,
+    /// Alias names and paths, in listed order
+    aliases: Vec<(String, String)>,
+}
+
+/// Directory holding the last listing of each shell session
+fn listings_dir(config: &Config) -> PathBuf {
+    config.database_path.join("listings")
+}
 
+/// Remember the rows of a listing so `goto %N` can refer to them
+///
+/// Each shell session keeps its own; listings of sessions that have exited
+/// or are older than [`LISTING_TTL`] are removed.
+pub fn record_listing(config: &Config, aliases: &[Alias]) -> Result<(), Box<dyn std::error::Error>> {
+    let dir = listings_dir(config);
+    fs::create_dir_all(&dir)?;
+    let session = stack::session_id();
+    let listing = Listing {
+        listed_at: Utc::now(),
+        aliases: aliases.iter().map(|a| (a.name.clone(), a.path.clone())).collect(),
+    };
+    serde_json::to_writer(File::create(stack::session_stack_path(&dir, &session))?, &listing)?;
+    stack::gc_stale_sessions(&dir, &session, LISTING_TTL)?;
     Ok(())
 }
 
-/// List all aliases with default options (uses config for display settings)
-pub fn list(db: &Database, config: &Config) -> Result<(), Box<dyn std::error::Error>> {
-    list_with_options(db, config, None, None)
+fn load_listing(config: &Config) -> Option<Listing> {
+    let file = File::open(stack::session_stack_path(&listings_dir(config), &stack::session_id())).ok()?;
+    serde_json::from_reader(BufReader::new(file)).ok()
 }
 
-/// List only alias names (one per line, for shell completion)
-pub fn list_names(db: &Database) -> Result<(), Box<dyn std::error::Error>> {
-    let mut names: Vec<_> = db.names().collect();
-    names.sort();
+/// Alias on row `index` (1-based) of `listing`, checked against the database
+///
+/// Fails rather than guess when the listing has expired or the alias was
+/// removed or moved since it was listed.
+fn listed_alias(
+    db: &Database,
+    listing: Option<Listing>,
+    index: usize,
+    now: DateTime<Utc>,
+) -> Result<String, Box<dyn std::error::Error>> {
+    let listing = listing.ok_or("no recent listing in this shell; run goto -l first")?;
+    let age = (now - listing.listed_at).to_std().unwrap_or_default();
+    if age > LISTING_TTL {
+        return Err(format!(
+            "the last listing is {} minutes old; run goto -l again",
+            age.as_secs() / 60
+        )
+        .into());
+    }
+    if index < 1 || index > listing.aliases.len() {
+        return Err(Failure::invalid(format!(
+            "invalid listing index: {} (valid: 1-{})",
+            index,
+            listing.aliases.len()
+        ))
+        .into());
+    }
 
-    for name in names {
-        println!("{}", name);
+    let (name, path) = &listing.aliases[index - 1];
+    match db.get(name) {
+        Some(alias) if alias.path == *path => Ok(name.clone()),
+        _ => Err(format!("alias '{}' changed since the last listing; run goto -l again", name).into()),
     }
+}
 
-    Ok(())
+/// Navigate to row `index` of this shell's last `goto -l`
+pub fn navigate_to_listed(
+    ctx: &mut Ctx,
+    db: &mut Database,
+    config: &Config,
+    index: usize,
+) -> Result<NavigationResult, Box<dyn std::error::Error>> {
+    let name = listed_alias(db, load_listing(config), index, Utc::now())?;
+    navigate::navigate_with_options(ctx, db, &name, &NavigateOptions::from_config(config))
+}
+
+/// Alias names, sorted (for shell completion)
+pub fn list_names(db: &Database) -> Vec<String> {
+    let mut names = db.list_names();
+    names.sort();
+    names
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::alias::Alias;
+    use chrono::{Duration, TimeZone, Utc};
     use tempfile::tempdir;
 
     fn create_test_db_and_config() -> (Database, Config, tempfile::TempDir) {
@@ -150,6 +547,7 @@ mod tests {
         assert_eq!(SortOrder::from("USAGE"), SortOrder::Usage);
         assert_eq!(SortOrder::from("recent"), SortOrder::Recent);
         assert_eq!(SortOrder::from("RECENT"), SortOrder::Recent);
+        assert_eq!(SortOrder::from("status"), SortOrder::Status);
         assert_eq!(SortOrder::from("invalid"), SortOrder::Alpha); // default
     }
 
@@ -160,11 +558,251 @@ mod tests {
         assert_eq!(format!("{}", SortOrder::Recent), "recent");
     }
 
+    #[test]
+    fn test_column_parse_list() {
+        assert_eq!(
+            Column::parse_list("name,path,tags,count").unwrap(),
+            vec![Column::Name, Column::Path, Column::Tags, Column::Count]
+        );
+        assert_eq!(
+            Column::parse_list(" Name , count ").unwrap(),
+            vec![Column::Name, Column::Count]
+        );
+        assert!(Column::parse_list("name,bogus").is_err());
+        assert!(Column::parse_list("").is_err());
+    }
+
+    #[test]
+    fn test_column_defaults_follow_display_config() {
+        let (_db, mut config, _dir) = create_test_db_and_config();
+        config.user.display.show_stats = true;
+        config.user.display.show_tags = false;
+        assert_eq!(
+            Column::defaults(&config),
+            vec![Column::Name, Column::Path, Column::Count, Column::Status]
+        );
+
+        config.user.display.check_paths = false;
+        assert!(!Column::defaults(&config).contains(&Column::Status));
+    }
+
+    #[test]
+    fn test_list_sort_by_status() {
+        let (mut db, mut config, dir) = create_test_db_and_config();
+        db.insert(Alias::new("good", dir.path().to_str().unwrap()).unwrap());
+        db.insert(Alias::new("gone", "/nonexistent/goto/gone").unwrap());
+        db.insert(Alias::new("also-good", dir.path().to_str().unwrap()).unwrap());
+
+        let options = ListOptions { sort: Some("status"), columns: Some("name,status"), ..Default::default() };
+        let result = list(&db, &config, &options).unwrap();
+        let names: Vec<_> = result.aliases.iter().map(|a| a.name.as_str()).collect();
+        assert_eq!(names, vec!["gone", "also-good", "good"]);
+        assert_eq!(result.statuses["gone"], PathStatus::Missing);
+        assert!(format_list(&result, &config, false).contains("missing"));
+
+        // Without path checks nothing is statted and the order is by name
+        config.user.display.check_paths = false;
+        let result = list(&db, &config, &options).unwrap();
+        let names: Vec<_> = result.aliases.iter().map(|a| a.name.as_str()).collect();
+        assert_eq!(names, vec!["also-good", "gone", "good"]);
+        assert!(result.statuses.is_empty());
+        assert_eq!(result.columns, vec![Column::Name]);
+    }
+
+    #[test]
+    fn test_group_by_parse() {
+        assert_eq!(GroupBy::parse("tag").unwrap(), GroupBy::Tag);
+        assert_eq!(GroupBy::parse("DIR-PREFIX").unwrap(), GroupBy::DirPrefix);
+        assert!(GroupBy::parse("size").unwrap_err().message.starts_with("invalid group-by"));
+    }
+
+    #[test]
+    fn test_group_aliases_by_tag() {
+        let mut both = Alias::new("both", "/tmp/both").unwrap();
+        both.add_tag("work");
+        both.add_tag("rust");
+        let mut work = Alias::new("work", "/tmp/work").unwrap();
+        work.add_tag("work");
+        let plain = Alias::new("plain", "/tmp/plain").unwrap();
+        let aliases = vec![both, plain, work];
+
+        let groups: Vec<(String, Vec<&str>)> = group_aliases(&aliases, GroupBy::Tag)
+            .into_iter()
+            .map(|(g, members)| (g, members.iter().map(|a| a.name.as_str()).collect()))
+            .collect();
+        assert_eq!(
+            groups,
+            vec![
+                ("rust".to_string(), vec!["both"]),
+                ("work".to_string(), vec!["both", "work"]),
+                ("(none)".to_string(), vec!["plain"]),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_group_aliases_by_dir_prefix() {
+        let home = dirs::home_dir().unwrap();
+        let aliases = vec![
+            Alias::new("a", home.join("src").join("a").to_str().unwrap()).unwrap(),
+            Alias::new("b", "/opt/tools/b").unwrap(),
+            Alias::new("c", home.join("src").join("c").to_str().unwrap()).unwrap(),
+            Alias::new("home", home.to_str().unwrap()).unwrap(),
+        ];
+
+        let groups: Vec<(String, usize)> = group_aliases(&aliases, GroupBy::DirPrefix)
+            .into_iter()
+            .map(|(g, members)| (g, members.len()))
+            .collect();
+        assert_eq!(
+            groups,
+            vec![
+                ("/opt".to_string(), 1),
+                ("~".to_string(), 1),
+                ("~/src".to_string(), 2),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_list_grouped() {
+        let (mut db, config, _dir) = create_test_db_and_config();
+        db.insert(Alias::new("proj", "/tmp/proj").unwrap());
+
+        let mut other = Alias::new("other", "/tmp/other").unwrap();
+        other.add_tag("work");
+        db.insert(other);
+
+        let options = ListOptions {
+            columns: Some("name"),
+            group_by: Some("tag"),
+            ..Default::default()
+        };
+        let result = list(&db, &config, &options).unwrap();
+        assert_eq!(result.group_by, Some(GroupBy::Tag));
+        let out = format_list(&result, &config, false);
+        assert!(out.starts_with("work (1)\n"), "{}", out);
+        assert!(out.contains("\n\n(none) (1)\n"), "{}", out);
+
+        let options = ListOptions { group_by: Some("bogus"), ..Default::default() };
+        assert!(list(&db, &config, &options).is_err());
+    }
+
+    #[test]
+    fn test_index_column_ignores_grouping() {
+        let (mut db, mut config, _dir) = create_test_db_and_config();
+        config.user.display.show_index = true;
+        assert_eq!(Column::defaults(&config)[0], Column::Index);
+        assert_eq!(Column::parse("#").unwrap(), Column::Index);
+
+        db.insert(Alias::new("alpha", "/tmp/alpha").unwrap());
+        let mut beta = Alias::new("beta", "/tmp/beta").unwrap();
+        beta.add_tag("work");
+        db.insert(beta);
+
+        let options = ListOptions {
+            sort: Some("alpha"),
+            columns: Some("index,name"),
+            group_by: Some("tag"),
+            ..Default::default()
+        };
+        let result = list(&db, &config, &options).unwrap();
+        let out = format_list(&result, &config, false);
+        // beta keeps row 2 although its section comes first
+        let beta_line = out.lines().find(|l| l.contains("beta")).unwrap();
+        assert!(beta_line.contains(" 2 "), "{}", out);
+        assert!(out.find("beta").unwrap() < out.find("alpha").unwrap(), "{}", out);
+    }
+
+    #[test]
+    fn test_listed_alias_safeguards() {
+        let (mut db, _config, _dir) = create_test_db_and_config();
+        db.insert(Alias::new("alpha", "/tmp/alpha").unwrap());
+        db.insert(Alias::new("beta", "/tmp/beta").unwrap());
+        let now = Utc::now();
+        let listing = |age: Duration, aliases: &[(&str, &str)]| Listing {
+            listed_at: now - age,
+            aliases: aliases.iter().map(|(n, p)| (n.to_string(), p.to_string())).collect(),
+        };
+        let rows = [("beta", "/tmp/beta"), ("alpha", "/tmp/alpha")];
+
+        assert_eq!(listed_alias(&db, Some(listing(Duration::minutes(1), &rows)), 2, now).unwrap(), "alpha");
+
+        let err = listed_alias(&db, None, 1, now).unwrap_err();
+        assert!(err.to_string().contains("run goto -l first"));
+        let err = listed_alias(&db, Some(listing(Duration::hours(2), &rows)), 1, now).unwrap_err();
+        assert!(err.to_string().contains("120 minutes old"), "{}", err);
+        let err = listed_alias(&db, Some(listing(Duration::zero(), &rows)), 3, now).unwrap_err();
+        assert_eq!(err.to_string(), "invalid listing index: 3 (valid: 1-2)");
+        assert!(listed_alias(&db, Some(listing(Duration::zero(), &rows)), 0, now).is_err());
+
+        // Removed or moved since it was listed
+        let err = listed_alias(&db, Some(listing(Duration::zero(), &[("gone", "/tmp/gone")])), 1, now).unwrap_err();
+        assert!(err.to_string().contains("alias 'gone' changed"));
+        let moved = [("beta", "/tmp/elsewhere")];
+        assert!(listed_alias(&db, Some(listing(Duration::zero(), &moved)), 1, now).is_err());
+    }
+
+    #[test]
+    fn test_column_cell() {
+        let mut alias = Alias::new("proj", "/tmp/proj").unwrap();
+        alias.use_count = 7;
+        assert_eq!(Column::Name.cell(&alias, 1, PathStyle::Full, DateStyle::Relative, TagStyle::Plain, None), "proj");
+        assert_eq!(Column::Path.cell(&alias, 1, PathStyle::Full, DateStyle::Relative, TagStyle::Plain, None), "/tmp/proj");
+        assert_eq!(Column::Tags.cell(&alias, 1, PathStyle::Full, DateStyle::Relative, TagStyle::Plain, None), "-");
+        assert_eq!(Column::Count.cell(&alias, 1, PathStyle::Full, DateStyle::Relative, TagStyle::Plain, None), "7");
+        alias.add_tag("work");
+        assert_eq!(Column::Tags.cell(&alias, 1, PathStyle::Full, DateStyle::Relative, TagStyle::Plain, None), "work");
+
+        let mut pending = Alias::new("mnt", "/nonexistent/mnt").unwrap();
+        pending.pending = true;
+        assert_eq!(Column::Path.cell(&pending, 1, PathStyle::Full, DateStyle::Relative, TagStyle::Plain, None), "/nonexistent/mnt (pending)");
+    }
+
+    #[test]
+    fn test_column_date_cells() {
+        let mut alias = Alias::new("proj", "/tmp/proj").unwrap();
+        alias.created_at = Utc.with_ymd_and_hms(2024, 5, 1, 9, 0, 0).unwrap();
+        assert_eq!(Column::LastUsed.cell(&alias, 1, PathStyle::Full, DateStyle::Relative, TagStyle::Plain, None), "never");
+        assert_eq!(Column::Created.cell(&alias, 1, PathStyle::Full, DateStyle::Absolute, TagStyle::Plain, None), "2024-05-01 09:00");
+
+        alias.last_used = Some(Utc::now() - Duration::days(3));
+        assert_eq!(Column::LastUsed.cell(&alias, 1, PathStyle::Full, DateStyle::Relative, TagStyle::Plain, None), "3 days ago");
+        assert_eq!(Column::parse_list("last_used,created").unwrap(), vec![Column::LastUsed, Column::Created]);
+    }
+
+    #[test]
+    fn test_list_with_invalid_columns() {
+        let (mut db, config, _dir) = create_test_db_and_config();
+        db.insert(Alias::new("test", "/tmp").unwrap());
+        let options = ListOptions { columns: Some("name,size"), ..Default::default() };
+        assert!(list(&db, &config, &options).is_err());
+    }
+
+    #[test]
+    fn test_porcelain_line() {
+        let mut alias = Alias::new("proj", "/tmp/my proj").unwrap();
+        assert_eq!(porcelain_line(&alias), "proj\t/tmp/my proj\t\t0\t");
+
+        alias.add_tag("work");
+        alias.add_tag("rust");
+        alias.use_count = 3;
+        alias.last_used = Some(
+            chrono::DateTime::parse_from_rfc3339("2024-01-02T03:04:05Z")
+                .unwrap()
+                .with_timezone(&chrono::Utc),
+        );
+        assert_eq!(
+            porcelain_line(&alias),
+            "proj\t/tmp/my proj\trust,work\t3\t2024-01-02T03:04:05Z"
+        );
+    }
+
     #[test]
     fn test_list_empty() {
         let (db, config, _dir) = create_test_db_and_config();
-        let result = list(&db, &config);
-        assert!(result.is_ok());
+        let result = list(&db, &config, &ListOptions::default()).unwrap();
+        assert!(result.aliases.is_empty());
     }
 
     #[test]
@@ -172,8 +810,10 @@ mod tests {
         let (mut db, config, _dir) = create_test_db_and_config();
         db.insert(Alias::new("test", "/tmp").unwrap());
 
-        let result = list(&db, &config);
-        assert!(result.is_ok());
+        let result = list(&db, &config, &ListOptions::default()).unwrap();
+        assert_eq!(result.aliases.len(), 1);
+        assert_eq!(result.columns, Column::defaults(&config));
+        assert!(format_list(&result, &config, false).contains("/tmp"));
     }
 
     #[test]
@@ -182,8 +822,9 @@ mod tests {
         db.insert(Alias::new("alpha", "/tmp/a").unwrap());
         db.insert(Alias::new("beta", "/tmp/b").unwrap());
 
-        let result = list_names(&db);
-        assert!(result.is_ok());
+        db.insert(Alias::new("aardvark", "/tmp/c").unwrap());
+
+        assert_eq!(list_names(&db), vec!["aardvark", "alpha", "beta"]);
     }
 
     #[test]
@@ -198,9 +839,28 @@ mod tests {
         alias2.use_count = 100;
         db.insert(alias2);
 
-        // Should not error - output tested via integration tests
-        let result = list_with_options(&db, &config, Some("usage"), None);
-        assert!(result.is_ok());
+        let options = ListOptions { sort: Some("usage"), ..Default::default() };
+        let result = list(&db, &config, &options).unwrap();
+        let names: Vec<_> = result.aliases.iter().map(|a| a.name.as_str()).collect();
+        assert_eq!(names, vec!["high", "low"]);
+    }
+
+    #[test]
+    fn test_list_without_usage_tracking() {
+        let (mut db, mut config, _dir) = create_test_db_and_config();
+        config.user.privacy.track_usage = false;
+
+        let mut busy = Alias::new("busy", "/tmp/busy").unwrap();
+        busy.use_count = 100;
+        db.insert(busy);
+        db.insert(Alias::new("alpha", "/tmp/alpha").unwrap());
+
+        let options = ListOptions { sort: Some("usage"), columns: Some("name,count,last_used"), ..Default::default() };
+        let result = list(&db, &config, &options).unwrap();
+        let names: Vec<_> = result.aliases.iter().map(|a| a.name.as_str()).collect();
+        assert_eq!(names, vec!["alpha", "busy"]);
+        assert_eq!(result.columns, vec![Column::Name]);
+        assert!(!Column::defaults(&config).contains(&Column::Count));
     }
 
     #[test]
@@ -220,8 +880,11 @@ mod tests {
         db.insert(alias3);
 
         // Filter by "work" tag
-        let result = list_with_options(&db, &config, None, Some("work"));
-        assert!(result.is_ok());
+        let options = ListOptions { filter: Some("work"), ..Default::default() };
+        let result = list(&db, &config, &options).unwrap();
+        let names: Vec<_> = result.aliases.iter().map(|a| a.name.as_str()).collect();
+        assert_eq!(names.len(), 2);
+        assert!(names.contains(&"work1") && names.contains(&"work2"));
     }
 
     #[test]
@@ -229,8 +892,14 @@ mod tests {
         let (mut db, config, _dir) = create_test_db_and_config();
         db.insert(Alias::new("test", "/tmp").unwrap());
 
-        // Filtering by non-existent tag should still succeed (just print message)
-        let result = list_with_options(&db, &config, None, Some("nonexistent"));
-        assert!(result.is_ok());
+        // Filtering by non-existent tag should still succeed, with nothing listed
+        let options = ListOptions { filter: Some("nonexistent"), ..Default::default() };
+        assert!(list(&db, &config, &options).unwrap().aliases.is_empty());
+
+        // The same expressions as everywhere else
+        let options = ListOptions { filter: Some("!tag:nonexistent"), ..Default::default() };
+        assert_eq!(list(&db, &config, &options).unwrap().aliases.len(), 1);
+        let options = ListOptions { filter: Some("tag:"), ..Default::default() };
+        assert!(list(&db, &config, &options).is_err());
     }
 }
@@ -1,17 +1,43 @@
 //! Command implementations for the goto CLI
 
+pub mod backup;
+pub mod bench;
+pub mod check;
 pub mod cleanup;
+pub mod clone;
+pub mod complete;
 pub mod config;
+pub mod counter;
+pub mod diff;
+pub mod duplicates;
+pub mod env;
+pub mod explain;
+pub mod history;
 pub mod import_export;
 pub mod install;
 pub mod list;
+pub mod mount;
 pub mod navigate;
+pub mod notes;
+pub mod open;
+pub mod pick;
+pub mod prompt;
+pub mod preview;
 pub mod prune;
+pub mod random;
 pub mod register;
+pub mod report;
+pub mod review;
+pub mod rewrite;
+pub mod search;
+pub mod ssh;
 pub mod stack;
 pub mod stats;
 pub mod tags;
+pub mod template;
+pub mod time_spent;
 pub mod update;
+pub mod watch;
 
 // Re-export commonly used types
 pub use import_export::{ImportResult, ImportStrategy};
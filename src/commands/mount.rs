@@ -0,0 +1,164 @@
+//! Waiting for removable drives before entering their aliases
+//!
+//! An alias tagged `removable`, or below one of `mounts.prefixes`, may point
+//! at a drive that isn't plugged in or mounted yet. Navigation then offers
+//! the configured mount command and waits a while for the directory to
+//! appear instead of failing straight away.
+
+use std::io;
+use std::path::{Path, PathBuf};
+use std::process;
+use std::thread;
+use std::time::{Duration, Instant};
+
+use crate::alias::Alias;
+use crate::config::{expand_path_with, Config};
+use crate::context::Ctx;
+
+/// Tag marking an alias whose directory is on a removable or automounted drive
+pub const REMOVABLE_TAG: &str = "removable";
+
+/// How often a missing directory is checked while waiting
+const POLL_INTERVAL: Duration = Duration::from_millis(200);
+
+/// How navigation waits for removable drives (`[mounts]`)
+#[derive(Debug, Clone, Default)]
+pub struct MountOptions {
+    /// Drives that may not be mounted
+    pub prefixes: Vec<PathBuf>,
+    /// Shell command offered when a directory is missing
+    pub command: Option<String>,
+    /// How long to wait for the directory
+    pub wait: Duration,
+}
+
+impl MountOptions {
+    /// Build options from the user configuration
+    pub fn from_config(config: &Config) -> Self {
+        let mounts = &config.user.mounts;
+        let command = mounts.command.trim();
+        Self {
+            // Mount points are compared as configured, not resolved: the
+            // drive may not be there to resolve them
+            prefixes: mounts
+                .prefixes
+                .iter()
+                .filter_map(|prefix| expand_path_with(prefix, false).ok())
+                .collect(),
+            command: (!command.is_empty()).then(|| command.to_string()),
+            wait: Duration::from_secs(mounts.wait_seconds),
+        }
+    }
+
+    /// Whether `alias` lives on a removable drive
+    pub fn is_removable(&self, alias: &Alias) -> bool {
+        alias.tags.iter().any(|t| t == REMOVABLE_TAG)
+            || self.prefixes.iter().any(|prefix| Path::new(&alias.path).starts_with(prefix))
+    }
+}
+
+/// Run the mount command for `path`
+///
+/// Like template init commands it goes through `sh -c`, with `GOTO_PATH`
+/// set to the directory, and its output goes to stderr.
+fn run_mount_command(command: &str, path: &Path) -> Result<(), Box<dyn std::error::Error>> {
+    let status = process::Command::new("sh")
+        .arg("-c")
+        .arg(command)
+        .env("GOTO_PATH", path)
+        .stdout(io::stderr())
+        .status()
+        .map_err(|e| format!("failed to run mount command: {}", e))?;
+    if !status.success() {
+        return Err(format!("mount command '{}' failed", command).into());
+    }
+    Ok(())
+}
+
+/// Give the drive holding `path` a chance to appear
+///
+/// Offers the mount command (yes by default, also without a terminal), then
+/// waits up to `options.wait` for the directory. Returns whether it exists.
+pub fn wait_for(ctx: &mut Ctx, path: &Path, options: &MountOptions) -> Result<bool, Box<dyn std::error::Error>> {
+    if let Some(command) = &options.command {
+        let message = format!("{} is not mounted. Run '{}'?", path.display(), command);
+        if ctx.confirm(&message, true)? {
+            run_mount_command(command, path)?;
+        }
+    }
+
+    let start = Instant::now();
+    if !path.exists() && !options.wait.is_zero() {
+        crate::enote!(ctx, "Waiting up to {}s for {} to be mounted...", options.wait.as_secs(), path.display());
+    }
+    while !path.exists() {
+        if start.elapsed() >= options.wait {
+            return Ok(false);
+        }
+        thread::sleep(POLL_INTERVAL);
+    }
+    crate::debug!("{} appeared after {:?}", path.display(), start.elapsed());
+    Ok(true)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_is_removable() {
+        let options = MountOptions {
+            prefixes: vec![PathBuf::from("/media/usb")],
+            ..Default::default()
+        };
+        assert!(options.is_removable(&Alias::new("photos", "/media/usb/photos").unwrap()));
+        assert!(!options.is_removable(&Alias::new("usb2", "/media/usb2").unwrap()));
+
+        let mut tagged = Alias::new("backup", "/srv/backup").unwrap();
+        assert!(!options.is_removable(&tagged));
+        tagged.add_tag(REMOVABLE_TAG);
+        assert!(options.is_removable(&tagged));
+    }
+
+    #[test]
+    fn test_wait_for_runs_mount_command() {
+        let dir = tempdir().unwrap();
+        let target = dir.path().join("drive").join("proj");
+
+        // Nothing mounts it: gives up once the wait is over
+        assert!(!wait_for(&mut Ctx::silent(), &target, &MountOptions::default()).unwrap());
+
+        let options = MountOptions {
+            command: Some("mkdir -p \"$GOTO_PATH\"".to_string()),
+            wait: Duration::from_secs(5),
+            ..Default::default()
+        };
+        assert!(wait_for(&mut Ctx::silent(), &target, &options).unwrap());
+
+        let failing = MountOptions {
+            command: Some("exit 3".to_string()),
+            ..Default::default()
+        };
+        let err = wait_for(&mut Ctx::silent(), &dir.path().join("other"), &failing).unwrap_err();
+        assert!(err.to_string().contains("mount command 'exit 3' failed"));
+    }
+
+    #[test]
+    fn test_wait_for_appearing_directory() {
+        let dir = tempdir().unwrap();
+        let target = dir.path().join("late");
+        let created = target.clone();
+        let automount = thread::spawn(move || {
+            thread::sleep(Duration::from_millis(300));
+            std::fs::create_dir(created).unwrap();
+        });
+
+        let options = MountOptions {
+            wait: Duration::from_secs(10),
+            ..Default::default()
+        };
+        assert!(wait_for(&mut Ctx::silent(), &target, &options).unwrap());
+        automount.join().unwrap();
+    }
+}
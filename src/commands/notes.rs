@@ -0,0 +1,170 @@
+//! Free-form notes per alias, kept as Markdown files beside the database
+
+use std::collections::HashMap;
+use std::env;
+use std::fs;
+use std::io;
+use std::path::PathBuf;
+use std::process;
+
+use crate::alias::AliasError;
+use crate::config::Config;
+use crate::database::Database;
+
+/// Directory holding one `<alias>.md` file per note
+pub fn notes_dir(config: &Config) -> PathBuf {
+    config.database_path.join("notes")
+}
+
+/// Note file of an alias (which may not exist)
+pub fn note_path(config: &Config, alias: &str) -> PathBuf {
+    notes_dir(config).join(format!("{}.md", alias))
+}
+
+/// Note of an alias, trimmed; `None` if it has none or it's blank
+pub fn read_note(config: &Config, alias: &str) -> Option<String> {
+    let content = fs::read_to_string(note_path(config, alias)).ok()?;
+    let content = content.trim();
+    (!content.is_empty()).then(|| content.to_string())
+}
+
+/// Notes of every alias in the database that has one, by alias name
+pub fn read_notes(config: &Config, db: &Database) -> HashMap<String, String> {
+    db.names()
+        .filter_map(|name| read_note(config, name).map(|note| (name.to_string(), note)))
+        .collect()
+}
+
+/// First non-empty line of a note, used where only one line fits
+pub fn first_line(note: &str) -> &str {
+    note.lines().map(str::trim).find(|l| !l.is_empty()).unwrap_or("")
+}
+
+/// Editor command from `$VISUAL`, then `$EDITOR`, split on whitespace
+/// (so `code --wait` works); falls back to the platform default
+pub(crate) fn editor_command(visual: Option<String>, editor: Option<String>) -> Vec<String> {
+    let fallback = if cfg!(windows) { "notepad" } else { "vi" };
+    let command = visual
+        .into_iter()
+        .chain(editor)
+        .find(|e| !e.trim().is_empty())
+        .unwrap_or_else(|| fallback.to_string());
+    command.split_whitespace().map(String::from).collect()
+}
+
+/// Open an alias's note in the user's editor
+///
+/// The editor inherits the terminal. A note left blank is removed. Returns
+/// the note as saved, or `None` if it ended up empty.
+pub fn edit(config: &Config, db: &Database, alias: &str) -> Result<Option<String>, Box<dyn std::error::Error>> {
+    if !db.contains(alias) {
+        return Err(AliasError::NotFound(alias.to_string()).into());
+    }
+
+    let path = note_path(config, alias);
+    fs::create_dir_all(notes_dir(config))?;
+
+    let command = editor_command(env::var("VISUAL").ok(), env::var("EDITOR").ok());
+    let status = process::Command::new(&command[0])
+        .args(&command[1..])
+        .arg(&path)
+        .status()
+        .map_err(|e| format!("failed to run editor '{}': {}", command[0], e))?;
+    if !status.success() {
+        return Err(format!("editor '{}' exited with {}", command[0], status).into());
+    }
+
+    let note = read_note(config, alias);
+    if note.is_none() && path.exists() {
+        fs::remove_file(&path)?;
+    }
+    Ok(note)
+}
+
+/// Move a note along with its alias
+pub fn rename(config: &Config, old_name: &str, new_name: &str) -> io::Result<()> {
+    let from = note_path(config, old_name);
+    if from.exists() {
+        fs::rename(from, note_path(config, new_name))?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::alias::Alias;
+    use tempfile::{tempdir, NamedTempFile};
+
+    fn write_note(config: &Config, alias: &str, content: &str) {
+        fs::create_dir_all(notes_dir(config)).unwrap();
+        fs::write(note_path(config, alias), content).unwrap();
+    }
+
+    #[test]
+    fn test_read_note_skips_blank() {
+        let dir = tempdir().unwrap();
+        let config = Config::in_dir(dir.path());
+        assert_eq!(read_note(&config, "proj"), None);
+
+        write_note(&config, "proj", "\n  \n");
+        assert_eq!(read_note(&config, "proj"), None);
+
+        write_note(&config, "proj", "\nDeploy with make release\nNeeds VPN\n");
+        assert_eq!(read_note(&config, "proj").unwrap(), "Deploy with make release\nNeeds VPN");
+    }
+
+    #[test]
+    fn test_read_notes_only_for_known_aliases() {
+        let dir = tempdir().unwrap();
+        let config = Config::in_dir(dir.path());
+        let file = NamedTempFile::new().unwrap();
+        let mut db = Database::load_from_path(file.path()).unwrap();
+        db.insert(Alias::new("proj", "/tmp").unwrap());
+
+        write_note(&config, "proj", "hello");
+        write_note(&config, "gone", "orphan");
+
+        let notes = read_notes(&config, &db);
+        assert_eq!(notes.len(), 1);
+        assert_eq!(notes["proj"], "hello");
+    }
+
+    #[test]
+    fn test_rename_moves_note() {
+        let dir = tempdir().unwrap();
+        let config = Config::in_dir(dir.path());
+        write_note(&config, "old", "text");
+
+        rename(&config, "old", "new").unwrap();
+        assert_eq!(read_note(&config, "old"), None);
+        assert_eq!(read_note(&config, "new").unwrap(), "text");
+
+        // Aliases without a note are fine
+        rename(&config, "none", "other").unwrap();
+    }
+
+    #[test]
+    fn test_editor_command() {
+        let visual = Some("code --wait".to_string());
+        let editor = Some("nano".to_string());
+        assert_eq!(editor_command(visual, editor.clone()), vec!["code", "--wait"]);
+        assert_eq!(editor_command(Some(" ".to_string()), editor), vec!["nano"]);
+        assert_eq!(editor_command(None, None).len(), 1);
+    }
+
+    #[test]
+    fn test_first_line() {
+        assert_eq!(first_line("\n  first \nsecond"), "first");
+        assert_eq!(first_line(""), "");
+    }
+
+    #[test]
+    fn test_edit_unknown_alias() {
+        let dir = tempdir().unwrap();
+        let config = Config::in_dir(dir.path());
+        let file = NamedTempFile::new().unwrap();
+        let db = Database::load_from_path(file.path()).unwrap();
+        assert!(edit(&config, &db, "missing").is_err());
+    }
+}
@@ -0,0 +1,136 @@
+//! Hand an alias's directory to a file manager or editor (`--open`, `--code`)
+
+use std::env;
+use std::process;
+
+use crate::alias::AliasError;
+use crate::commands::navigate::check_enterable;
+use crate::commands::notes::editor_command;
+use crate::config::Config;
+use crate::database::Database;
+
+/// Placeholder replaced with the directory in `[integrations]` commands
+const PATH_PLACEHOLDER: &str = "{path}";
+
+/// Program a directory is handed to
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Opener {
+    /// The file manager (`--open`)
+    FileManager,
+    /// The editor (`--code`)
+    Editor,
+}
+
+/// The platform's file manager launcher
+fn default_open_command() -> &'static str {
+    if cfg!(target_os = "macos") {
+        "open"
+    } else if cfg!(windows) {
+        "explorer"
+    } else {
+        "xdg-open"
+    }
+}
+
+/// Expand a command template for `path`
+///
+/// The template is split on whitespace and `{path}` replaced in each word;
+/// without a placeholder the path becomes the last argument.
+pub fn command_line(template: &str, path: &str) -> Vec<String> {
+    let mut argv: Vec<String> = template
+        .split_whitespace()
+        .map(|word| word.replace(PATH_PLACEHOLDER, path))
+        .collect();
+    if !template.contains(PATH_PLACEHOLDER) {
+        argv.push(path.to_string());
+    }
+    argv
+}
+
+impl Opener {
+    /// Command line opening `path`, from `[integrations]` or the defaults
+    pub fn command(self, config: &Config, path: &str) -> Vec<String> {
+        let integrations = &config.user.integrations;
+        match self {
+            Opener::FileManager if integrations.open.trim().is_empty() => command_line(default_open_command(), path),
+            Opener::FileManager => command_line(&integrations.open, path),
+            Opener::Editor if integrations.editor.trim().is_empty() => {
+                let editor = editor_command(env::var("VISUAL").ok(), env::var("EDITOR").ok());
+                command_line(&editor.join(" "), path)
+            }
+            Opener::Editor => command_line(&integrations.editor, path),
+        }
+    }
+}
+
+/// Open the directory of `alias` with `opener`, recording the use
+///
+/// Returns the directory. Terminal editors inherit the terminal, so this
+/// waits until the program exits.
+pub fn open(
+    db: &mut Database,
+    config: &Config,
+    alias: &str,
+    opener: Opener,
+) -> Result<String, Box<dyn std::error::Error>> {
+    let path = db
+        .get(alias)
+        .map(|a| a.path.clone())
+        .ok_or_else(|| AliasError::NotFound(alias.to_string()))?;
+    check_enterable(&path)?;
+
+    let argv = opener.command(config, &path);
+    crate::debug!("running {:?}", argv);
+    let status = process::Command::new(&argv[0])
+        .args(&argv[1..])
+        .status()
+        .map_err(|e| format!("failed to run '{}': {}", argv[0], e))?;
+    if !status.success() {
+        return Err(format!("'{}' exited with {}", argv[0], status).into());
+    }
+
+    db.record_usage(alias)?;
+    db.save()?;
+    Ok(path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::alias::Alias;
+    use std::fs;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_command_line() {
+        assert_eq!(command_line("xdg-open", "/srv/my api"), ["xdg-open", "/srv/my api"]);
+        assert_eq!(command_line("code -n", "/srv/api"), ["code", "-n", "/srv/api"]);
+        assert_eq!(
+            command_line("code --folder-uri=file://{path} --new-window", "/srv/api"),
+            ["code", "--folder-uri=file:///srv/api", "--new-window"]
+        );
+    }
+
+    #[test]
+    fn test_open_runs_configured_command() {
+        let dir = tempdir().unwrap();
+        let mut config = Config::in_dir(dir.path());
+        let marker = dir.path().join("opened");
+        config.user.integrations.open = format!("touch {}", marker.display());
+        config.user.integrations.editor = "false".to_string();
+
+        let mut db = Database::load_from_path(&dir.path().join("aliases")).unwrap();
+        db.insert(Alias::new("here", dir.path().to_str().unwrap()).unwrap());
+        db.insert(Alias::new("gone", "/nonexistent/goto/gone").unwrap());
+
+        // touch <marker> <dir>
+        open(&mut db, &config, "here", Opener::FileManager).unwrap();
+        assert!(fs::metadata(&marker).is_ok());
+        assert_eq!(db.get("here").unwrap().use_count, 1);
+
+        let err = open(&mut db, &config, "here", Opener::Editor).unwrap_err();
+        assert!(err.to_string().contains("'false' exited with"));
+        assert!(open(&mut db, &config, "gone", Opener::FileManager).is_err());
+        assert!(open(&mut db, &config, "missing", Opener::FileManager).is_err());
+    }
+}
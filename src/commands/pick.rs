@@ -0,0 +1,181 @@
+//! Pick an alias interactively (`goto --pick`, and bare `goto` in the shell
+//! wrappers) with the built-in selector or fzf
+
+use std::io::{self, IsTerminal, Write};
+use std::process::{self, Stdio};
+use std::str::FromStr;
+
+use crate::alias::Alias;
+use crate::collate;
+use crate::commands::navigate::{navigate_with_options, NavigateOptions, NavigationResult};
+use crate::config::Config;
+use crate::context::Ctx;
+use crate::database::Database;
+use crate::exit::Failure;
+use crate::table::TagStyle;
+use crate::ui::selector::{self, Choice};
+
+/// Aliases offered by the built-in selector, most used first
+const BUILTIN_LIMIT: usize = 10;
+
+/// Which picker `--pick` uses (`general.picker`)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Picker {
+    Builtin,
+    Fzf,
+}
+
+impl FromStr for Picker {
+    type Err = Failure;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_lowercase().as_str() {
+            "builtin" => Ok(Picker::Builtin),
+            "fzf" => Ok(Picker::Fzf),
+            _ => Err(Failure::invalid(format!("invalid picker: {} (must be builtin or fzf)", s))),
+        }
+    }
+}
+
+/// Aliases in picker order: most used first, then by name
+fn ordered(db: &Database) -> Vec<&Alias> {
+    let mut aliases: Vec<_> = db.all().collect();
+    aliases.sort_by(|a, b| b.use_count.cmp(&a.use_count).then_with(|| collate::compare(&a.name, &b.name)));
+    aliases
+}
+
+/// `name<TAB>path<TAB>tags` lines fed to fzf, tags comma-separated
+pub fn fzf_lines(db: &Database, tag_style: TagStyle) -> Vec<String> {
+    ordered(db)
+        .iter()
+        .map(|a| {
+            let tags: Vec<String> = a.tags.iter().map(|t| tag_style.paint(t)).collect();
+            format!("{}\t{}\t{}", a.name, a.path, tags.join(","))
+        })
+        .collect()
+}
+
+/// Run fzf over `lines` and return the picked alias name
+///
+/// fzf draws on the terminal itself, so only the candidates go through its
+/// stdin and the selection comes back on its stdout. Returns `Ok(None)` when
+/// fzf isn't installed, and an error when the pick was cancelled.
+fn run_fzf(lines: &[String], profile: Option<&str>) -> Result<Option<String>, Box<dyn std::error::Error>> {
+    let mut command = process::Command::new("fzf");
+    command
+        .args(["--ansi", "--delimiter", "\t", "--tabstop", "4"])
+        .args(["--preview", "goto-bin --preview {1}", "--preview-window", "right:50%"])
+        .args(["--height", "40%", "--layout", "reverse", "--border"])
+        .args(std::env::var("GOTO_FZF_OPTS").unwrap_or_default().split_whitespace())
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped());
+    // The preview runs goto-bin again; keep it on the same profile
+    if let Some(profile) = profile {
+        command.env("GOTO_PROFILE", profile);
+    }
+
+    let mut child = match command.spawn() {
+        Ok(child) => child,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(None),
+        Err(e) => return Err(format!("failed to run fzf: {}", e).into()),
+    };
+    if let Some(mut stdin) = child.stdin.take() {
+        // fzf may exit before reading everything; that's not an error here
+        let _ = stdin.write_all(lines.join("\n").as_bytes());
+    }
+
+    let output = child.wait_with_output()?;
+    let selected = String::from_utf8_lossy(&output.stdout);
+    match selected.lines().next().and_then(|l| l.split('\t').next()) {
+        Some(name) if output.status.success() && !name.is_empty() => Ok(Some(name.to_string())),
+        _ => Err(Failure::cancelled("Navigation cancelled").into()),
+    }
+}
+
+/// Pick one of the most used aliases with the built-in selector
+fn pick_builtin(ctx: &mut Ctx, db: &Database, tag_style: TagStyle) -> Result<String, Box<dyn std::error::Error>> {
+    let aliases: Vec<_> = ordered(db).into_iter().take(BUILTIN_LIMIT).collect();
+    let choices: Vec<Choice> = aliases
+        .iter()
+        .map(|a| Choice {
+            label: a.name.clone(),
+            score: None,
+            path: a.path.clone(),
+            detail: (!a.tags.is_empty()).then(|| format!("[{}]", tag_style.join(&a.tags, ", "))),
+        })
+        .collect();
+
+    match selector::select(ctx, &choices)? {
+        Some(i) => Ok(aliases[i].name.clone()),
+        None => Err(Failure::cancelled("Navigation cancelled").into()),
+    }
+}
+
+/// Pick an alias and navigate to it, recording its use
+///
+/// Uses fzf when `general.picker` is `fzf` and it is installed, otherwise
+/// the built-in selector. Returns `None` when there are no aliases.
+pub fn pick(ctx: &mut Ctx, db: &mut Database, config: &Config) -> Result<Option<NavigationResult>, Box<dyn std::error::Error>> {
+    if db.is_empty() {
+        return Ok(None);
+    }
+
+    let picker = Picker::from_str(&config.user.general.picker)?;
+    // Both pickers draw on the terminal, not on stdout
+    let tag_style = config.tag_style(io::stderr().is_terminal());
+    let picked = match picker {
+        Picker::Fzf => run_fzf(&fzf_lines(db, tag_style), config.profile.as_deref())?,
+        Picker::Builtin => None,
+    };
+    let name = match picked {
+        Some(name) => name,
+        None => {
+            if picker == Picker::Fzf {
+                crate::debug!("fzf not found, using the built-in picker");
+            }
+            pick_builtin(ctx, db, tag_style)?
+        }
+    };
+
+    crate::debug!("picked '{}'", name);
+    let options = NavigateOptions::from_config(config);
+    navigate_with_options(ctx, db, &name, &options).map(Some)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_picker_from_str() {
+        assert_eq!(Picker::from_str("fzf").unwrap(), Picker::Fzf);
+        assert_eq!(Picker::from_str("Builtin").unwrap(), Picker::Builtin);
+        assert!(Picker::from_str("skim").unwrap_err().message.contains("invalid picker"));
+    }
+
+    #[test]
+    fn test_fzf_lines_most_used_first() {
+        let dir = tempdir().unwrap();
+        let mut db = Database::load_from_path(&dir.path().join("aliases")).unwrap();
+        let mut api = Alias::new("api", "/work/api").unwrap();
+        api.add_tag("work");
+        api.add_tag("rust");
+        db.insert(api);
+        let mut blog = Alias::new("blog", "/home/blog").unwrap();
+        blog.use_count = 3;
+        db.insert(blog);
+        db.insert(Alias::new("docs", "/work/docs").unwrap());
+
+        let lines = fzf_lines(&db, TagStyle::Plain);
+        assert_eq!(lines[0], "blog\t/home/blog\t");
+        assert!(lines[1].starts_with("api\t/work/api\t"));
+        assert!(lines[1].contains("work") && lines[1].contains("rust"));
+        assert_eq!(lines[2], "docs\t/work/docs\t");
+
+        // Only the tags are colored; the name fzf hands back stays plain
+        let colors = Default::default();
+        let lines = fzf_lines(&db, TagStyle::Colored(&colors));
+        assert!(lines[1].starts_with("api\t/work/api\t\x1b["));
+    }
+}
@@ -0,0 +1,235 @@
+//! Directory previews for picking between aliases
+
+use std::fmt;
+use std::fs;
+use std::path::Path;
+
+use crate::alias::AliasError;
+use crate::collate;
+use crate::commands::notes;
+use crate::config::Config;
+use crate::database::Database;
+
+/// Number of entries shown in a one-line summary
+const SUMMARY_ENTRIES: usize = 4;
+
+/// Number of entries listed by `--preview`
+const FULL_ENTRIES: usize = 20;
+
+/// A glimpse of a directory's contents
+#[derive(Debug, Clone, PartialEq)]
+pub struct DirPreview {
+    /// Current git branch (or short commit when detached), if a repository
+    pub git_branch: Option<String>,
+    /// First entries in name order, directories with a trailing `/`
+    pub entries: Vec<String>,
+    /// Number of entries not included in `entries`
+    pub more: usize,
+}
+
+/// Read up to `limit` entries of `path` (hidden entries skipped) and its git branch
+pub fn preview_dir(path: &Path, limit: usize) -> DirPreview {
+    let mut names: Vec<String> = fs::read_dir(path)
+        .map(|entries| {
+            entries
+                .filter_map(|e| e.ok())
+                .filter_map(|e| {
+                    let name = e.file_name().to_string_lossy().into_owned();
+                    if name.starts_with('.') {
+                        return None;
+                    }
+                    Some(if e.path().is_dir() { format!("{}/", name) } else { name })
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+    names.sort_by_cached_key(|n| collate::sort_key(n));
+
+    let more = names.len().saturating_sub(limit);
+    names.truncate(limit);
+
+    DirPreview {
+        git_branch: git_branch(path),
+        entries: names,
+        more,
+    }
+}
+
+/// Determine the checked-out branch of a git repository rooted at `path`
+///
+/// Handles both `.git` directories and `.git` files pointing elsewhere
+/// (worktrees and submodules).
+pub fn git_branch(path: &Path) -> Option<String> {
+    let dot_git = path.join(".git");
+    let git_dir = if dot_git.is_dir() {
+        dot_git
+    } else {
+        let content = fs::read_to_string(&dot_git).ok()?;
+        let target = content.trim().strip_prefix("gitdir:")?.trim();
+        path.join(target)
+    };
+
+    let head = fs::read_to_string(git_dir.join("HEAD")).ok()?;
+    let head = head.trim();
+    match head.strip_prefix("ref: ") {
+        Some(reference) => Some(
+            reference
+                .strip_prefix("refs/heads/")
+                .unwrap_or(reference)
+                .to_string(),
+        ),
+        None => Some(head.chars().take(7).collect()),
+    }
+}
+
+/// Format a preview as a single line, e.g. `[main] src/, Cargo.toml, +3 more`
+pub fn summary_line(preview: &DirPreview) -> String {
+    let mut parts = Vec::new();
+    if let Some(branch) = &preview.git_branch {
+        parts.push(format!("[{}]", branch));
+    }
+    if preview.entries.is_empty() {
+        parts.push("(empty)".to_string());
+    } else {
+        let mut listing = preview.entries.join(", ");
+        if preview.more > 0 {
+            listing.push_str(&format!(", +{} more", preview.more));
+        }
+        parts.push(listing);
+    }
+    parts.join(" ")
+}
+
+/// One-line summary of an alias target for selection prompts
+pub fn summarize(path: &str) -> String {
+    let path = Path::new(path);
+    if !path.is_dir() {
+        return "(directory does not exist)".to_string();
+    }
+    summary_line(&preview_dir(path, SUMMARY_ENTRIES))
+}
+
+/// An alias's directory and a preview of its contents, as `--preview` shows it
+#[derive(Debug, Clone, PartialEq)]
+pub struct AliasPreview {
+    pub path: String,
+    pub preview: DirPreview,
+    /// The alias's note, shown above the listing
+    pub note: Option<String>,
+}
+
+impl fmt::Display for AliasPreview {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "{}", self.path)?;
+        if let Some(branch) = &self.preview.git_branch {
+            writeln!(f, "git: {}", branch)?;
+        }
+        writeln!(f)?;
+        if let Some(note) = &self.note {
+            writeln!(f, "{}", note)?;
+            writeln!(f)?;
+        }
+        for name in &self.preview.entries {
+            writeln!(f, "  {}", name)?;
+        }
+        if self.preview.more > 0 {
+            writeln!(f, "  ... {} more", self.preview.more)?;
+        }
+        Ok(())
+    }
+}
+
+/// Preview an alias's path, git branch, note and directory entries
+///
+/// Used by the shell wrappers' fzf preview window.
+pub fn preview(db: &Database, config: &Config, alias: &str) -> Result<AliasPreview, Box<dyn std::error::Error>> {
+    let entry = db
+        .get(alias)
+        .ok_or_else(|| AliasError::NotFound(alias.to_string()))?;
+    let path = Path::new(&entry.path);
+
+    if !path.is_dir() {
+        return Err(AliasError::DirectoryNotFound(entry.path.clone()).into());
+    }
+
+    Ok(AliasPreview {
+        path: entry.path.clone(),
+        preview: preview_dir(path, FULL_ENTRIES),
+        note: notes::read_note(config, alias),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_preview_dir() {
+        let dir = tempdir().unwrap();
+        fs::create_dir(dir.path().join("src")).unwrap();
+        fs::write(dir.path().join("Cargo.toml"), "").unwrap();
+        fs::write(dir.path().join("README.md"), "").unwrap();
+        fs::write(dir.path().join(".hidden"), "").unwrap();
+
+        let preview = preview_dir(dir.path(), 2);
+        assert_eq!(preview.entries, vec!["Cargo.toml", "README.md"]);
+        assert_eq!(preview.more, 1);
+        assert_eq!(preview.git_branch, None);
+        assert_eq!(summary_line(&preview), "Cargo.toml, README.md, +1 more");
+    }
+
+    #[test]
+    fn test_alias_preview_display() {
+        let shown = AliasPreview {
+            path: "/src/proj".to_string(),
+            preview: DirPreview {
+                git_branch: Some("main".to_string()),
+                entries: vec!["src/".to_string()],
+                more: 2,
+            },
+            note: None,
+        };
+        assert_eq!(shown.to_string(), "/src/proj\ngit: main\n\n  src/\n  ... 2 more\n");
+
+        let noted = AliasPreview {
+            note: Some("Run make dev".to_string()),
+            ..shown
+        };
+        assert_eq!(noted.to_string(), "/src/proj\ngit: main\n\nRun make dev\n\n  src/\n  ... 2 more\n");
+    }
+
+    #[test]
+    fn test_git_branch() {
+        let dir = tempdir().unwrap();
+        fs::create_dir(dir.path().join(".git")).unwrap();
+        fs::write(dir.path().join(".git").join("HEAD"), "ref: refs/heads/feature/x\n").unwrap();
+        assert_eq!(git_branch(dir.path()), Some("feature/x".to_string()));
+
+        fs::write(dir.path().join(".git").join("HEAD"), "0123456789abcdef\n").unwrap();
+        assert_eq!(git_branch(dir.path()), Some("0123456".to_string()));
+    }
+
+    #[test]
+    fn test_git_branch_worktree_file() {
+        let dir = tempdir().unwrap();
+        let real = dir.path().join("real-git");
+        fs::create_dir(&real).unwrap();
+        fs::write(real.join("HEAD"), "ref: refs/heads/main\n").unwrap();
+        let checkout = dir.path().join("checkout");
+        fs::create_dir(&checkout).unwrap();
+        fs::write(checkout.join(".git"), format!("gitdir: {}\n", real.display())).unwrap();
+
+        assert_eq!(git_branch(&checkout), Some("main".to_string()));
+    }
+
+    #[test]
+    fn test_summary_line_with_branch_and_empty_dir() {
+        let preview = DirPreview {
+            git_branch: Some("main".to_string()),
+            entries: Vec::new(),
+            more: 0,
+        };
+        assert_eq!(summary_line(&preview), "[main] (empty)");
+    }
+}
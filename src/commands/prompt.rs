@@ -0,0 +1,200 @@
+//! Prompt integration: `--prompt-snippet` and the cached `--where` lookup
+
+use std::fs::{self, File};
+use std::io::BufReader;
+use std::path::{Path, PathBuf};
+use std::time::UNIX_EPOCH;
+
+use serde::{Deserialize, Serialize};
+
+use crate::commands::navigate::{canonical_alias_path, closest_root, Location};
+use crate::config::Config;
+use crate::database::Database;
+use crate::stack;
+
+/// Shells `--prompt-snippet` knows
+pub const PROMPT_SHELLS: &[&str] = &["bash", "zsh", "fish", "starship"];
+
+const BASH_SNIPPET: &str = r#"# goto: show the current alias in the prompt (add to ~/.bashrc)
+__goto_prompt() {
+    local location
+    location=$(goto-bin --where --quiet --cache) && printf '[%s] ' "$location"
+}
+PS1='$(__goto_prompt)'"$PS1"
+"#;
+
+const ZSH_SNIPPET: &str = r#"# goto: show the current alias in the prompt (add to ~/.zshrc)
+setopt PROMPT_SUBST
+__goto_prompt() {
+    local location
+    location=$(goto-bin --where --quiet --cache) && print -rn -- "[${location//\%/%%}] "
+}
+PROMPT='$(__goto_prompt)'"$PROMPT"
+"#;
+
+const FISH_SNIPPET: &str = r#"# goto: show the current alias in the prompt (add to ~/.config/fish/config.fish)
+if not functions -q __goto_original_prompt
+    functions -c fish_prompt __goto_original_prompt
+end
+function __goto_restore_status
+    return $argv[1]
+end
+function fish_prompt
+    set -l last_status $status
+    set -l location (goto-bin --where --quiet --cache)
+    and printf '[%s] ' $location
+    # Let the original prompt see the status of the last command
+    __goto_restore_status $last_status
+    __goto_original_prompt
+end
+"#;
+
+const STARSHIP_SNIPPET: &str = r#"# goto: show the current alias in the prompt (add to ~/.config/starship.toml)
+[custom.goto]
+command = "goto-bin --where --quiet --cache"
+when = true
+format = "[\\[$output\\]]($style) "
+style = "bold blue"
+"#;
+
+/// The prompt snippet for `shell`
+pub fn snippet(shell: &str) -> Result<&'static str, String> {
+    match shell.to_lowercase().as_str() {
+        "bash" => Ok(BASH_SNIPPET),
+        "zsh" => Ok(ZSH_SNIPPET),
+        "fish" => Ok(FISH_SNIPPET),
+        "starship" => Ok(STARSHIP_SNIPPET),
+        _ => Err(format!(
+            "unsupported shell: {} (expected {})",
+            shell,
+            PROMPT_SHELLS.join(", ")
+        )),
+    }
+}
+
+/// Alias directories for `--where --cache`, so a prompt doesn't have to load
+/// the database on every command
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+struct WhereCache {
+    /// Modification time and size of the alias files the entries were built from
+    stamp: Vec<Option<(u128, u64)>>,
+    /// Alias names and their canonical directories
+    aliases: Vec<(String, PathBuf)>,
+}
+
+fn cache_path(config: &Config) -> PathBuf {
+    config.database_path.join("where_cache.json")
+}
+
+/// Modification time (ns) and size of every file aliases are loaded from
+///
+/// The cache is only used while these are unchanged.
+fn stamp(config: &Config) -> Vec<Option<(u128, u64)>> {
+    let session = stack::session_stack_path(&config.temp_dir(), &stack::session_id());
+    let shared = config.shared_aliases_path().ok().flatten();
+    [Some(config.aliases_path.clone()), Some(session), shared]
+        .into_iter()
+        .map(|path| {
+            let meta = fs::metadata(path?).ok()?;
+            let modified = meta.modified().ok()?.duration_since(UNIX_EPOCH).ok()?;
+            Some((modified.as_nanos(), meta.len()))
+        })
+        .collect()
+}
+
+fn load_cache(config: &Config) -> Option<WhereCache> {
+    let file = File::open(cache_path(config)).ok()?;
+    serde_json::from_reader(BufReader::new(file)).ok()
+}
+
+fn save_cache(config: &Config, cache: &WhereCache) -> Result<(), Box<dyn std::error::Error>> {
+    config.ensure_dirs()?;
+    let file = File::create(cache_path(config))?;
+    serde_json::to_writer(file, cache)?;
+    Ok(())
+}
+
+/// Find the alias containing `dir` like `--where`, from the cache if it is
+/// still current
+///
+/// A stale or missing cache is rebuilt from the database. Symlinks in alias
+/// paths are resolved when the cache is built, not on every lookup.
+pub fn cached_where(config: &Config, dir: &Path) -> Result<Option<Location>, Box<dyn std::error::Error>> {
+    let stamp = stamp(config);
+    let cache = match load_cache(config) {
+        Some(cache) if cache.stamp == stamp => cache,
+        _ => {
+            crate::debug!("rebuilding {}", cache_path(config).display());
+            let db = Database::load(config)?;
+            let cache = WhereCache {
+                stamp,
+                aliases: db.all().map(|a| (a.name.clone(), canonical_alias_path(a))).collect(),
+            };
+            // A prompt must not fail because the cache can't be written
+            if let Err(e) = save_cache(config, &cache) {
+                crate::debug!("could not write the where cache: {}", e);
+            }
+            cache
+        }
+    };
+
+    let dir = fs::canonicalize(dir).unwrap_or_else(|_| dir.to_path_buf());
+    let roots = cache.aliases.iter().map(|(name, path)| (name.as_str(), path.clone()));
+    Ok(closest_root(roots, &dir).map(|(alias, relative)| Location {
+        alias: alias.to_string(),
+        relative,
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::alias::Alias;
+    use tempfile::tempdir;
+
+    fn setup() -> (Config, tempfile::TempDir) {
+        let dir = tempdir().unwrap();
+        let mut config = Config {
+            database_path: dir.path().to_path_buf(),
+            stack_path: dir.path().join("goto_stack"),
+            config_path: dir.path().join("config.toml"),
+            aliases_path: dir.path().join("aliases.toml"),
+            profile: None,
+            user: Default::default(),
+            notices: Vec::new(),
+        };
+        config.user.general.shared_aliases = String::new();
+        (config, dir)
+    }
+
+    #[test]
+    fn test_snippet() {
+        for shell in PROMPT_SHELLS {
+            assert!(snippet(shell).unwrap().contains("goto-bin --where --quiet --cache"));
+        }
+        assert!(snippet("tcsh").unwrap_err().contains("unsupported shell"));
+    }
+
+    #[test]
+    fn test_cached_where_follows_database_changes() {
+        let (config, dir) = setup();
+        let project = dir.path().join("project");
+        fs::create_dir_all(project.join("src")).unwrap();
+
+        let mut db = Database::load(&config).unwrap();
+        db.insert(Alias::new("proj", project.to_str().unwrap()).unwrap());
+        db.save().unwrap();
+
+        let location = cached_where(&config, &project.join("src")).unwrap().unwrap();
+        assert_eq!(location.to_string(), "proj/src");
+        assert!(cache_path(&config).exists());
+        assert_eq!(cached_where(&config, dir.path()).unwrap(), None);
+
+        // Registering a deeper alias invalidates the cache
+        let mut db = Database::load(&config).unwrap();
+        db.insert(Alias::new("src", project.join("src").to_str().unwrap()).unwrap());
+        db.save().unwrap();
+        let location = cached_where(&config, &project.join("src")).unwrap().unwrap();
+        assert_eq!(location.to_string(), "src");
+    }
+}
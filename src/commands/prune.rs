@@ -11,6 +11,7 @@ use std::io::BufReader;
 use std::path::{Path, PathBuf};
 
 use crate::config::Config;
+use crate::context::Ctx;
 use crate::database::Database;
 
 /// Cached prune check state
@@ -64,10 +65,10 @@ fn save_cache(config: &Config, cache: &PruneCache) -> Result<(), Box<dyn Error>>
     Ok(())
 }
 
-/// Count aliases pointing to non-existent directories
+/// Count aliases pointing to non-existent directories (pending ones aside)
 pub fn count_stale_aliases(db: &Database) -> usize {
     db.all()
-        .filter(|a| !Path::new(&a.path).exists())
+        .filter(|a| !a.pending && !Path::new(&a.path).exists())
         .count()
 }
 
@@ -98,7 +99,7 @@ pub fn check_for_stale_aliases(db: &Database, config: &Config) -> Option<usize>
 ///
 /// Should be called after list/stats/tags commands complete.
 /// Does NOT add latency - uses cached data when possible.
-pub fn notify_if_stale_aliases(config: &Config, db: &Database) {
+pub fn notify_if_stale_aliases(ctx: &mut Ctx, config: &Config, db: &Database) {
     if !config.user.prune.auto_check {
         return;
     }
@@ -122,7 +123,8 @@ pub fn notify_if_stale_aliases(config: &Config, db: &Database) {
 
     // Show notification if stale aliases exist
     if cache.stale_count > 0 {
-        eprintln!(
+        crate::enote!(
+            ctx,
             "Note: {} alias{} point to missing directories. Run 'goto --cleanup' to review.",
             cache.stale_count,
             if cache.stale_count == 1 { "" } else { "es" }
@@ -135,7 +137,6 @@ pub fn snooze_notifications(config: &Config, days: u32) -> Result<(), Box<dyn Er
     let mut cache = load_cache(config);
     cache.snoozed_until = Some(Utc::now() + Duration::days(days as i64));
     save_cache(config, &cache)?;
-    println!("Prune notifications snoozed for {} days.", days);
     Ok(())
 }
 
@@ -153,21 +154,9 @@ pub fn reset_cache(config: &Config) -> Result<(), Box<dyn Error>> {
 mod tests {
     use super::*;
     use crate::alias::Alias;
-    use crate::config::UserConfig;
     use std::fs;
     use tempfile::TempDir;
 
-    /// Create a test config with a temp directory
-    fn test_config(temp_dir: &std::path::Path) -> Config {
-        Config {
-            database_path: temp_dir.to_path_buf(),
-            stack_path: temp_dir.join("goto_stack"),
-            config_path: temp_dir.join("config.toml"),
-            aliases_path: temp_dir.join("aliases.toml"),
-            user: UserConfig::default(),
-        }
-    }
-
     #[test]
     fn test_prune_cache_default() {
         let cache = PruneCache::default();
@@ -195,7 +184,7 @@ mod tests {
     #[test]
     fn test_load_cache_nonexistent() {
         let temp_dir = TempDir::new().unwrap();
-        let config = test_config(temp_dir.path());
+        let config = Config::in_dir(temp_dir.path());
 
         let cache = load_cache(&config);
         assert_eq!(cache.stale_count, 0);
@@ -205,7 +194,7 @@ mod tests {
     #[test]
     fn test_load_cache_invalid_json() {
         let temp_dir = TempDir::new().unwrap();
-        let config = test_config(temp_dir.path());
+        let config = Config::in_dir(temp_dir.path());
 
         // Write invalid JSON
         let cache_file = temp_dir.path().join("prune_cache.json");
@@ -219,7 +208,7 @@ mod tests {
     #[test]
     fn test_save_and_load_cache() {
         let temp_dir = TempDir::new().unwrap();
-        let config = test_config(temp_dir.path());
+        let config = Config::in_dir(temp_dir.path());
 
         let cache = PruneCache {
             last_check: Utc::now(),
@@ -247,12 +236,18 @@ mod tests {
 
         let count = count_stale_aliases(&db);
         assert_eq!(count, 1);
+
+        // Pending aliases aren't stale
+        let mut pending = Alias::new("pending", "/nonexistent/path/67890").unwrap();
+        pending.pending = true;
+        db.insert(pending);
+        assert_eq!(count_stale_aliases(&db), 1);
     }
 
     #[test]
     fn test_snooze_sets_snoozed_until() {
         let temp_dir = TempDir::new().unwrap();
-        let config = test_config(temp_dir.path());
+        let config = Config::in_dir(temp_dir.path());
 
         snooze_notifications(&config, 7).unwrap();
 
@@ -269,7 +264,7 @@ mod tests {
     #[test]
     fn test_reset_cache() {
         let temp_dir = TempDir::new().unwrap();
-        let config = test_config(temp_dir.path());
+        let config = Config::in_dir(temp_dir.path());
 
         // Save cache with stale count
         let cache = PruneCache {
@@ -290,7 +285,7 @@ mod tests {
     #[test]
     fn test_cache_path() {
         let temp_dir = TempDir::new().unwrap();
-        let config = test_config(temp_dir.path());
+        let config = Config::in_dir(temp_dir.path());
         let path = cache_path(&config);
         assert_eq!(path, temp_dir.path().join("prune_cache.json"));
     }
@@ -298,7 +293,7 @@ mod tests {
     #[test]
     fn test_check_for_stale_aliases_returns_count() {
         let temp_dir = TempDir::new().unwrap();
-        let config = test_config(temp_dir.path());
+        let config = Config::in_dir(temp_dir.path());
         let db_file = temp_dir.path().join("aliases.toml");
         let mut db = crate::database::Database::load_from_path(&db_file).unwrap();
 
@@ -316,20 +311,20 @@ mod tests {
     #[test]
     fn test_notify_disabled_when_auto_check_false() {
         let temp_dir = TempDir::new().unwrap();
-        let mut config = test_config(temp_dir.path());
+        let mut config = Config::in_dir(temp_dir.path());
         config.user.prune.auto_check = false;
 
         let db_file = temp_dir.path().join("aliases.toml");
         let db = crate::database::Database::load_from_path(&db_file).unwrap();
 
         // Should return early without any errors
-        notify_if_stale_aliases(&config, &db);
+        notify_if_stale_aliases(&mut Ctx::silent(), &config, &db);
     }
 
     #[test]
     fn test_notify_respects_snooze() {
         let temp_dir = TempDir::new().unwrap();
-        let config = test_config(temp_dir.path());
+        let config = Config::in_dir(temp_dir.path());
 
         // Set up a cache with snooze active
         let cache = PruneCache {
@@ -343,7 +338,14 @@ mod tests {
         let db = crate::database::Database::load_from_path(&db_file).unwrap();
 
         // Should return early due to snooze (no notification)
-        notify_if_stale_aliases(&config, &db);
-        // Test passes if no panic - we can't easily test stderr output
+        let mut err = Vec::new();
+        notify_if_stale_aliases(&mut Ctx::new(std::io::sink(), &mut err), &config, &db);
+        assert!(err.is_empty());
+
+        // Without the snooze the cached count is reported
+        snooze_notifications(&config, 0).unwrap();
+        let mut err = Vec::new();
+        notify_if_stale_aliases(&mut Ctx::new(std::io::sink(), &mut err), &config, &db);
+        assert!(String::from_utf8(err).unwrap().starts_with("Note: 5 aliases point to missing directories"));
     }
 }
@@ -0,0 +1,133 @@
+//! Navigate to a random alias (`goto --random`)
+//!
+//! Picks among the aliases whose directory exists, optionally only those
+//! matching a filter, either evenly or weighted by how often each has been used.
+
+use std::collections::hash_map::RandomState;
+use std::hash::{BuildHasher, Hasher};
+use std::path::Path;
+
+use crate::alias::Alias;
+use crate::database::Database;
+use crate::exit::Failure;
+use crate::filter::AliasFilter;
+
+/// How `--random` chooses
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Weighting {
+    /// Every alias is as likely (default)
+    #[default]
+    Even,
+    /// Aliases are as likely as their use count, plus one so unused ones stay in (`--weighted`)
+    Usage,
+}
+
+impl Weighting {
+    fn weight(self, alias: &Alias) -> u64 {
+        match self {
+            Weighting::Even => 1,
+            Weighting::Usage => alias.use_count.saturating_add(1),
+        }
+    }
+}
+
+/// Aliases `--random` may pick: permanent ones whose directory exists,
+/// matching `filter` when given
+pub fn candidates<'a>(db: &'a Database, filter: Option<&AliasFilter>) -> Vec<&'a Alias> {
+    let mut aliases: Vec<&Alias> = db
+        .all()
+        .filter(|a| !db.is_temporary(&a.name))
+        .filter(|a| filter.is_none_or(|f| f.matches(a)))
+        .filter(|a| Path::new(&a.path).is_dir())
+        .collect();
+    // A stable order, so a roll always picks the same alias
+    aliases.sort_by(|a, b| a.name.cmp(&b.name));
+    aliases
+}
+
+/// The alias `roll` lands on, each taking a share of the total weight
+pub fn pick<'a>(aliases: &[&'a Alias], weighting: Weighting, roll: u64) -> Option<&'a Alias> {
+    let total: u64 = aliases.iter().map(|a| weighting.weight(a)).fold(0, u64::saturating_add);
+    if total == 0 {
+        return None;
+    }
+    let mut point = roll % total;
+    for alias in aliases {
+        let weight = weighting.weight(alias);
+        if point < weight {
+            return Some(alias);
+        }
+        point -= weight;
+    }
+    None
+}
+
+/// A random number: std seeds its hasher keys from the OS in each process
+fn roll() -> u64 {
+    RandomState::new().build_hasher().finish()
+}
+
+/// Choose a random alias among those matching the filter expression
+/// (see [`AliasFilter`])
+pub fn choose(db: &Database, filter: Option<&str>, weighting: Weighting) -> Result<Alias, Failure> {
+    let parsed = filter.map(AliasFilter::parse).transpose()?;
+    let aliases = candidates(db, parsed.as_ref());
+    match pick(&aliases, weighting, roll()) {
+        Some(alias) => Ok(alias.clone()),
+        None => Err(Failure::not_found(match filter {
+            Some(expr) => format!("no aliases matching '{}' to pick from", expr),
+            None => "no aliases to pick from".to_string(),
+        })),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_pick() {
+        let mut often = Alias::new("often", "/srv/often").unwrap();
+        often.use_count = 8;
+        let never = Alias::new("never", "/srv/never").unwrap();
+        let aliases = [&never, &often];
+
+        assert_eq!(pick(&aliases, Weighting::Even, 0).unwrap().name, "never");
+        assert_eq!(pick(&aliases, Weighting::Even, 1).unwrap().name, "often");
+        assert_eq!(pick(&aliases, Weighting::Even, 2).unwrap().name, "never");
+
+        // 1 share for never, 9 for often
+        let picks: Vec<&str> = (0..10).map(|roll| pick(&aliases, Weighting::Usage, roll).unwrap().name.as_str()).collect();
+        assert_eq!(picks.iter().filter(|n| **n == "often").count(), 9);
+        assert_eq!(picks[0], "never");
+
+        assert!(pick(&[], Weighting::Usage, 3).is_none());
+    }
+
+    #[test]
+    fn test_choose() {
+        let dir = tempdir().unwrap();
+        let mut db = Database::load_from_path(&dir.path().join("aliases")).unwrap();
+        assert_eq!(choose(&db, None, Weighting::Even).unwrap_err().message, "no aliases to pick from");
+
+        let mut here = Alias::new("here", dir.path().to_str().unwrap()).unwrap();
+        here.add_tag("side");
+        db.insert(here);
+        db.insert(Alias::new("gone", "/nonexistent/goto/gone").unwrap());
+        db.insert(Alias::new("untagged", dir.path().to_str().unwrap()).unwrap());
+
+        assert_eq!(candidates(&db, None).len(), 2);
+        for _ in 0..5 {
+            assert_eq!(choose(&db, Some("side"), Weighting::Usage).unwrap().name, "here");
+        }
+        assert_eq!(
+            choose(&db, Some("work"), Weighting::Even).unwrap_err().message,
+            "no aliases matching 'work' to pick from"
+        );
+        // Filters as for --export
+        assert_eq!(choose(&db, Some("!tag:side"), Weighting::Even).unwrap().name, "untagged");
+        assert_eq!(choose(&db, Some("here*"), Weighting::Even).unwrap().name, "here");
+        assert!(choose(&db, Some("tag:"), Weighting::Even).unwrap_err().message.starts_with("invalid filter"));
+    }
+}
@@ -2,34 +2,111 @@
 
 use std::collections::HashSet;
 
-use crate::alias::{validate_alias, validate_tag, Alias, AliasError};
-use crate::config::expand_path;
-use crate::confirm;
+use crate::alias::{validate_tag, Alias, AliasError};
+use crate::commands::duplicates;
+use crate::commands::navigate::{fuzzy_candidates, MAX_SUGGESTIONS};
+use crate::config::{expand_path_with, Config, SafetyConfig};
+use crate::context::Ctx;
 use crate::database::Database;
+use crate::exit::Failure;
+use crate::ui::selector::{self, Choice};
 
 /// Register a new alias for a directory
-pub fn register(db: &mut Database, name: &str, path: &str) -> Result<(), Box<dyn std::error::Error>> {
+///
+/// Returns the alias as stored.
+pub fn register(db: &mut Database, name: &str, path: &str) -> Result<Alias, Box<dyn std::error::Error>> {
     // Register without tags uses force=true since no tags to confirm
-    register_with_tags(db, name, path, &[], true)
+    register_with_tags(&mut Ctx::silent(), db, name, path, &[], true)
 }
 
 /// Register a new alias with optional tags
 ///
 /// # Arguments
+/// * `ctx` - Asked to confirm new tags and overlapping paths, and warned on
 /// * `db` - The alias database
 /// * `name` - The alias name
 /// * `path` - The directory path
 /// * `tags` - Tags to add to the alias
-/// * `force` - If true, skip confirmation for new tags
+/// * `force` - If true, skip confirmation for new tags and overlapping paths
 pub fn register_with_tags(
+    ctx: &mut Ctx,
     db: &mut Database,
     name: &str,
     path: &str,
     tags: &[String],
     force: bool,
-) -> Result<(), Box<dyn std::error::Error>> {
+) -> Result<Alias, Box<dyn std::error::Error>> {
+    let options = RegisterOptions {
+        force,
+        ..Default::default()
+    };
+    register_with_options(ctx, db, name, path, tags, &options)
+}
+
+/// Registration options
+///
+/// New options may be added in minor releases; start from
+/// [`RegisterOptions::from_config`] or the default.
+#[non_exhaustive]
+pub struct RegisterOptions {
+    /// Skip confirmation for new tags and overlapping paths
+    pub force: bool,
+    /// Store the symlink's target; otherwise the path is kept as given (made
+    /// absolute), so navigation follows the symlink rather than its current target
+    pub resolve_symlinks: bool,
+    /// Put the alias in the session overlay, not the database file
+    pub temporary: bool,
+    /// Accept a directory that doesn't exist yet, marking the alias pending
+    pub allow_missing: bool,
+    /// Directories to warn about or refuse; `force` registers them silently
+    pub safety: SafetyConfig,
+}
+
+impl RegisterOptions {
+    /// Options following the user's `general.resolve_symlinks` and `[safety]` settings
+    pub fn from_config(config: &Config) -> Self {
+        Self {
+            resolve_symlinks: config.user.general.resolve_symlinks,
+            safety: config.user.safety.clone(),
+            ..Default::default()
+        }
+    }
+}
+
+impl Default for RegisterOptions {
+    fn default() -> Self {
+        Self {
+            force: false,
+            resolve_symlinks: true,
+            temporary: false,
+            allow_missing: false,
+            safety: SafetyConfig::default(),
+        }
+    }
+}
+
+/// Register a new alias with the given options
+///
+/// Returns the alias as stored; it is pending when the directory doesn't
+/// exist yet.
+pub fn register_with_options(
+    ctx: &mut Ctx,
+    db: &mut Database,
+    name: &str,
+    path: &str,
+    tags: &[String],
+    options: &RegisterOptions,
+) -> Result<Alias, Box<dyn std::error::Error>> {
+    let RegisterOptions {
+        force,
+        resolve_symlinks,
+        temporary,
+        allow_missing,
+        ref safety,
+    } = *options;
+
     // Validate alias name
-    validate_alias(name)?;
+    db.check_name(name)?;
 
     // Validate and normalize tags
     let normalized_tags = validate_and_normalize_tags(tags)?;
@@ -44,8 +121,8 @@ pub fn register_with_tags(
             for tag in &normalized_tags {
                 if !existing_tags.contains_key(tag) {
                     let message = format!("Tag '{}' doesn't exist. Create it?", tag);
-                    if !confirm(&message, false)? {
-                        return Err("Tag creation cancelled".into());
+                    if !ctx.confirm(&message, false)? {
+                        return Err(Failure::cancelled("Tag creation cancelled").into());
                     }
                 }
             }
@@ -53,17 +130,50 @@ pub fn register_with_tags(
     }
 
     // Expand and validate directory
-    let expanded_path = expand_path(path)?;
+    let expanded_path = expand_path_with(path, resolve_symlinks)?;
     let path_str = expanded_path.to_string_lossy().to_string();
 
-    // Check directory exists
-    if !expanded_path.exists() {
+    // Check directory exists, unless it's expected to appear later
+    let pending = !expanded_path.exists();
+    if pending && !allow_missing {
         return Err(AliasError::DirectoryNotFound(path_str).into());
     }
-    if !expanded_path.is_dir() {
+    if !pending && !expanded_path.is_dir() {
         return Err(format!("not a directory: {}", path_str).into());
     }
 
+    // System directories and the whole home make risky targets: wrappers and
+    // hooks run in whatever directory a typo lands in
+    if !force {
+        if let Some(entry) = safety.protects(&expanded_path) {
+            if safety.strict {
+                return Err(Failure::invalid(format!(
+                    "protected directory: {} is in safety.protected_paths ('{}'); use --force to register it anyway",
+                    path_str, entry
+                ))
+                .into());
+            }
+            crate::errln!(
+                ctx,
+                "Warning: {} is a protected directory (safety.protected_paths '{}'); use --force to skip this check",
+                path_str, entry
+            );
+        }
+    }
+
+    // Warn about duplicate or nested paths unless forced
+    if !force {
+        let overlaps = duplicates::overlapping(db, &expanded_path, Some(name));
+        if !overlaps.is_empty() {
+            for (alias, overlap) in &overlaps {
+                crate::errln!(ctx, "Warning: {}", overlap.describe(&path_str, alias));
+            }
+            if !ctx.confirm(&format!("Register '{}' anyway?", name), false)? {
+                return Err(Failure::cancelled("Registration cancelled").into());
+            }
+        }
+    }
+
     // Add alias with tags
     let alias = Alias {
         name: name.to_string(),
@@ -72,27 +182,79 @@ pub fn register_with_tags(
         use_count: 0,
         last_used: None,
         created_at: chrono::Utc::now(),
+        env: Default::default(),
+        pending,
+        dormant: false,
+        reviewed_at: None,
+        // So a missing directory can later be told apart from an unplugged disk
+        volume: if pending { None } else { crate::volume::of(&expanded_path) },
+        link: None,
     };
 
-    db.add_with_tags(alias, normalized_tags.clone())?;
+    if temporary {
+        db.add_temporary(alias, normalized_tags)?;
+    } else {
+        db.add_with_tags(alias, normalized_tags)?;
+    }
     db.save()?;
 
-    if !normalized_tags.is_empty() {
-        println!(
-            "Registered '{}' -> {} [{}]",
-            name,
-            path_str,
-            normalized_tags.join(", ")
-        );
-    } else {
-        println!("Registered '{}' -> {}", name, path_str);
+    db.get(name).cloned().ok_or_else(|| AliasError::NotFound(name.to_string()).into())
+}
+
+/// Register `name` as a link to the alias `target` (`-r <name> --link <target>`)
+///
+/// Navigating to a link goes to the alias it resolves to, following links
+/// transitively, and records the use there. Linking an existing link again
+/// repoints it, keeping its tags; an ordinary alias can't become a link.
+pub fn register_link(
+    db: &mut Database,
+    name: &str,
+    target: &str,
+    tags: &[String],
+) -> Result<Alias, Box<dyn std::error::Error>> {
+    db.check_name(name)?;
+    let normalized_tags = validate_and_normalize_tags(tags)?;
+    db.check_writable(name)?;
+
+    let existing = db.get(name).cloned();
+    if existing.as_ref().is_some_and(|a| a.link.is_none()) {
+        return Err(AliasError::AlreadyExists(name.to_string()).into());
+    }
+    let target_path = db
+        .get(target)
+        .map(|a| a.path.clone())
+        .ok_or_else(|| AliasError::NotFound(target.to_string()))?;
+
+    let mut alias = match existing.clone() {
+        Some(alias) => alias,
+        None => Alias::new(name, &target_path)?,
+    };
+    alias.link = Some(target.to_string());
+    for tag in normalized_tags {
+        alias.add_tag(&tag);
+    }
+    db.insert(alias);
+
+    // Resolving the new link catches cycles through it; undo it if it fails
+    match db.resolve(name).map(|a| a.path.clone()) {
+        Ok(path) => db.set_path(name, path)?,
+        Err(e) => {
+            match existing {
+                Some(previous) => db.insert(previous),
+                None => {
+                    db.remove(name);
+                }
+            }
+            return Err(e.into());
+        }
     }
+    db.save()?;
 
-    Ok(())
+    db.get(name).cloned().ok_or_else(|| AliasError::NotFound(name.to_string()).into())
 }
 
 /// Validate tags and convert to lowercase, removing duplicates
-fn validate_and_normalize_tags(tags: &[String]) -> Result<Vec<String>, AliasError> {
+pub(crate) fn validate_and_normalize_tags(tags: &[String]) -> Result<Vec<String>, AliasError> {
     let mut normalized = Vec::new();
     let mut seen = HashSet::new();
 
@@ -111,12 +273,12 @@ fn validate_and_normalize_tags(tags: &[String]) -> Result<Vec<String>, AliasErro
     Ok(normalized)
 }
 
-/// Unregister (remove) an alias
-pub fn unregister(db: &mut Database, name: &str) -> Result<(), Box<dyn std::error::Error>> {
-    if db.remove(name).is_some() {
+/// Unregister (remove) an alias, returning it
+pub fn unregister(db: &mut Database, name: &str) -> Result<Alias, Box<dyn std::error::Error>> {
+    db.check_writable(name)?;
+    if let Some(alias) = db.remove(name) {
         db.save()?;
-        println!("Unregistered '{}'", name);
-        Ok(())
+        Ok(alias)
     } else {
         Err(AliasError::NotFound(name.to_string()).into())
     }
@@ -129,12 +291,66 @@ pub fn rename(
     new_name: &str,
 ) -> Result<(), Box<dyn std::error::Error>> {
     // Validate new alias name
-    validate_alias(new_name)?;
+    db.check_name(new_name)?;
 
     db.rename_alias(old_name, new_name)?;
     db.save()?;
+    Ok(())
+}
+
+/// Rename an alias picked by fuzzy match, prompting for the new name
+///
+/// An exact name skips the picker. The new name is asked for again until it
+/// is valid and not taken; an empty answer cancels. Returns the old and new
+/// names.
+pub fn rename_interactive(ctx: &mut Ctx, db: &mut Database, query: &str) -> Result<(String, String), Box<dyn std::error::Error>> {
+    let old_name = if db.contains(query) {
+        query.to_string()
+    } else {
+        let mut matches = fuzzy_candidates(db, query);
+        if matches.is_empty() {
+            return Err(AliasError::NotFound(query.to_string()).into());
+        }
+        matches.truncate(MAX_SUGGESTIONS);
+
+        let choices: Vec<Choice> = matches
+            .iter()
+            .map(|(name, score)| Choice {
+                label: name.clone(),
+                score: Some(*score as f64 / 1000.0),
+                path: db.get(name).map(|a| a.path.clone()).unwrap_or_default(),
+                detail: None,
+            })
+            .collect();
+
+        crate::errln!(ctx, "Rename which alias?");
+        match selector::select(ctx, &choices)? {
+            Some(idx) => matches[idx].0.clone(),
+            None => return Err(Failure::cancelled("Rename cancelled").into()),
+        }
+    };
+    db.check_writable(&old_name)?;
+
+    loop {
+        let Some(new_name) = ctx.prompt_input(&format!("New name for '{}':", old_name))? else {
+            return Err(Failure::cancelled("Rename cancelled").into());
+        };
+        match check_new_name(db, &new_name) {
+            Ok(()) => {
+                rename(db, &old_name, &new_name)?;
+                return Ok((old_name, new_name));
+            }
+            Err(problem) => crate::errln!(ctx, "{}", problem),
+        }
+    }
+}
 
-    println!("Renamed alias '{}' to '{}'", old_name, new_name);
+/// Check that `new_name` is a valid alias name that isn't taken yet
+fn check_new_name(db: &Database, new_name: &str) -> Result<(), AliasError> {
+    db.check_name(new_name)?;
+    if db.contains(new_name) {
+        return Err(AliasError::AlreadyExists(new_name.to_string()));
+    }
     Ok(())
 }
 
@@ -160,6 +376,46 @@ mod tests {
         assert!(db.contains("test"));
     }
 
+    #[test]
+    fn test_register_link() {
+        let (mut db, _file) = create_test_db();
+        let a = TempDir::new().unwrap();
+        let b = TempDir::new().unwrap();
+        let a_path = a.path().to_string_lossy().to_string();
+        let b_path = b.path().to_string_lossy().to_string();
+        register(&mut db, "project-a", &a_path).unwrap();
+        register(&mut db, "project-b", &b_path).unwrap();
+
+        let alias = register_link(&mut db, "current", "project-a", &["now".to_string()]).unwrap();
+        assert_eq!(alias.link.as_deref(), Some("project-a"));
+        assert_eq!(alias.path, db.get("project-a").unwrap().path);
+        // Links don't count as another alias for the directory
+        assert_eq!(db.names_at(std::path::Path::new(&alias.path)), vec!["project-a"]);
+
+        // Repointing keeps the tags
+        let alias = register_link(&mut db, "current", "project-b", &[]).unwrap();
+        assert_eq!(alias.link.as_deref(), Some("project-b"));
+        assert_eq!(alias.path, db.get("project-b").unwrap().path);
+        assert_eq!(alias.tags, vec!["now"]);
+
+        // The path follows the alias it stands for
+        db.set_path("project-b", a_path.clone()).unwrap();
+        assert_eq!(db.get("current").unwrap().path, a_path);
+
+        // Ordinary aliases stay ordinary, and cycles are refused
+        assert!(register_link(&mut db, "project-a", "project-b", &[]).is_err());
+        register_link(&mut db, "prev", "current", &[]).unwrap();
+        let err = register_link(&mut db, "current", "prev", &[]).unwrap_err();
+        assert_eq!(err.to_string(), "alias links form a cycle: current -> prev -> current");
+        assert_eq!(db.get("current").unwrap().link.as_deref(), Some("project-b"));
+        assert!(register_link(&mut db, "next", "missing", &[]).unwrap_err().to_string().contains("not found"));
+        assert!(!db.contains("next"));
+
+        // Renaming the alias a link stands for keeps the link
+        rename(&mut db, "project-b", "project-c").unwrap();
+        assert_eq!(db.resolve("current").unwrap().name, "project-c");
+    }
+
     #[test]
     fn test_register_duplicate() {
         let (mut db, _file) = create_test_db();
@@ -178,6 +434,58 @@ mod tests {
         assert!(result.is_err());
     }
 
+    #[test]
+    fn test_register_allow_missing_marks_pending() {
+        let (mut db, _file) = create_test_db();
+        let temp_dir = TempDir::new().unwrap();
+        let later = temp_dir.path().join("mnt").to_string_lossy().to_string();
+        let options = RegisterOptions {
+            force: true,
+            allow_missing: true,
+            ..Default::default()
+        };
+
+        register_with_options(&mut Ctx::silent(), &mut db, "mnt", &later, &[], &options).unwrap();
+        let alias = db.get("mnt").unwrap();
+        assert_eq!(alias.path, later);
+        assert!(alias.pending);
+
+        // An existing directory isn't pending even with --allow-missing
+        let here = temp_dir.path().to_string_lossy().to_string();
+        register_with_options(&mut Ctx::silent(), &mut db, "here", &here, &[], &options).unwrap();
+        assert!(!db.get("here").unwrap().pending);
+    }
+
+    #[test]
+    fn test_check_new_name() {
+        let (mut db, _file) = create_test_db();
+        db.insert(Alias::new("taken", "/tmp/taken").unwrap());
+
+        assert!(check_new_name(&db, "fresh").is_ok());
+        assert!(matches!(check_new_name(&db, "taken"), Err(AliasError::AlreadyExists(_))));
+        assert!(matches!(check_new_name(&db, "no spaces"), Err(AliasError::InvalidAlias { .. })));
+    }
+
+    #[test]
+    fn test_rename_interactive_unknown_alias() {
+        let (mut db, _file) = create_test_db();
+        db.insert(Alias::new("projects", "/tmp/projects").unwrap());
+
+        let err = rename_interactive(&mut Ctx::silent(), &mut db, "zzzz").unwrap_err();
+        assert!(err.to_string().contains("not found"));
+    }
+
+    #[test]
+    fn test_rename_interactive_cancels_without_terminal() {
+        let (mut db, _file) = create_test_db();
+        db.insert(Alias::new("projects", "/tmp/projects").unwrap());
+
+        // In non-interactive mode the new-name prompt returns nothing
+        let err = rename_interactive(&mut Ctx::silent(), &mut db, "projects").unwrap_err();
+        assert!(err.to_string().contains("cancelled"));
+        assert!(db.contains("projects"));
+    }
+
     #[test]
     fn test_register_validates_alias() {
         let (mut db, _file) = create_test_db();
@@ -201,7 +509,7 @@ mod tests {
 
         // First tags (bootstrapping) - no confirmation needed
         let tags = vec!["Work".to_string(), "important".to_string()];
-        let result = register_with_tags(&mut db, "test", &path, &tags, false);
+        let result = register_with_tags(&mut Ctx::silent(), &mut db, "test", &path, &tags, false);
         assert!(result.is_ok());
 
         let alias = db.get("test").unwrap();
@@ -210,6 +518,70 @@ mod tests {
         assert!(alias.tags.contains(&"important".to_string()));
     }
 
+    #[cfg(unix)]
+    #[test]
+    fn test_register_without_resolving_symlinks() {
+        let (mut db, _file) = create_test_db();
+        let temp_dir = TempDir::new().unwrap();
+        let target = temp_dir.path().join("release-42");
+        std::fs::create_dir(&target).unwrap();
+        let link = temp_dir.path().join("current");
+        std::os::unix::fs::symlink(&target, &link).unwrap();
+        let link_str = link.to_string_lossy().to_string();
+
+        let kept = RegisterOptions {
+            force: true,
+            resolve_symlinks: false,
+            ..Default::default()
+        };
+        let resolved = RegisterOptions {
+            force: true,
+            ..Default::default()
+        };
+        register_with_options(&mut Ctx::silent(), &mut db, "kept", &link_str, &[], &kept).unwrap();
+        register_with_options(&mut Ctx::silent(), &mut db, "resolved", &link_str, &[], &resolved).unwrap();
+
+        assert_eq!(db.get("kept").unwrap().path, link_str);
+        assert_ne!(db.get("resolved").unwrap().path, link_str);
+    }
+
+    #[test]
+    fn test_register_protected_directory() {
+        let (mut db, _file) = create_test_db();
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().to_string_lossy().to_string();
+        let mut options = RegisterOptions {
+            safety: SafetyConfig {
+                protected_paths: vec![path.clone()],
+                strict: true,
+            },
+            ..Default::default()
+        };
+
+        let err = register_with_options(&mut Ctx::silent(), &mut db, "prot", &path, &[], &options).unwrap_err();
+        assert!(err.to_string().starts_with("protected directory"));
+        assert!(!db.contains("prot"));
+
+        // Directories below a protected one are fine
+        let below = temp_dir.path().join("project");
+        std::fs::create_dir(&below).unwrap();
+        register_with_options(&mut Ctx::silent(), &mut db, "below", below.to_str().unwrap(), &[], &options).unwrap();
+        db.remove("below");
+
+        // Only a warning outside strict mode, and nothing at all when forced
+        options.safety.strict = false;
+        let mut err = Vec::new();
+        register_with_options(&mut Ctx::new(std::io::sink(), &mut err), &mut db, "warned", &path, &[], &options)
+            .unwrap();
+        assert!(String::from_utf8(err).unwrap().starts_with("Warning: "));
+        options.safety.strict = true;
+        options.force = true;
+        let mut err = Vec::new();
+        register_with_options(&mut Ctx::new(std::io::sink(), &mut err), &mut db, "forced", &path, &[], &options)
+            .unwrap();
+        assert!(err.is_empty());
+    }
+
     #[test]
     fn test_register_with_tags_validates_tags() {
         let (mut db, _file) = create_test_db();
@@ -218,7 +590,7 @@ mod tests {
 
         // Invalid tag starting with dash
         let tags = vec!["-invalid".to_string()];
-        let result = register_with_tags(&mut db, "test", &path, &tags, true);
+        let result = register_with_tags(&mut Ctx::silent(), &mut db, "test", &path, &tags, true);
         assert!(result.is_err());
     }
 
@@ -230,7 +602,7 @@ mod tests {
 
         // Same tag with different cases should be deduplicated (bootstrapping - no confirmation)
         let tags = vec!["Work".to_string(), "WORK".to_string(), "work".to_string()];
-        let result = register_with_tags(&mut db, "test", &path, &tags, false);
+        let result = register_with_tags(&mut Ctx::silent(), &mut db, "test", &path, &tags, false);
         assert!(result.is_ok());
 
         let alias = db.get("test").unwrap();
@@ -246,7 +618,7 @@ mod tests {
 
         // Bootstrapping - no confirmation needed
         let tags = vec!["work".to_string(), "".to_string(), "  ".to_string()];
-        let result = register_with_tags(&mut db, "test", &path, &tags, false);
+        let result = register_with_tags(&mut Ctx::silent(), &mut db, "test", &path, &tags, false);
         assert!(result.is_ok());
 
         let alias = db.get("test").unwrap();
@@ -378,7 +750,7 @@ mod tests {
 
         // No tags exist, so first tags should succeed without confirmation
         let tags = vec!["work".to_string(), "project".to_string()];
-        let result = register_with_tags(&mut db, "test", &path, &tags, false);
+        let result = register_with_tags(&mut Ctx::silent(), &mut db, "test", &path, &tags, false);
         assert!(result.is_ok());
 
         let alias = db.get("test").unwrap();
@@ -397,12 +769,12 @@ mod tests {
 
         // Create first alias with a tag (bootstrapping)
         let tags = vec!["existing".to_string()];
-        register_with_tags(&mut db, "first", &path1, &tags, true).unwrap();
+        register_with_tags(&mut Ctx::silent(), &mut db, "first", &path1, &tags, true).unwrap();
 
         // Try to create second alias with new tag without force
-        // (tests run with piped stdin, so confirm() returns default=false)
+        // (the context is not interactive, so confirm() returns default=false)
         let new_tags = vec!["newtag".to_string()];
-        let result = register_with_tags(&mut db, "second", &path2, &new_tags, false);
+        let result = register_with_tags(&mut Ctx::silent(), &mut db, "second", &path2, &new_tags, false);
         assert!(result.is_err());
         assert!(result.unwrap_err().to_string().contains("cancelled"));
     }
@@ -418,11 +790,11 @@ mod tests {
 
         // Create first alias with a tag
         let tags = vec!["existing".to_string()];
-        register_with_tags(&mut db, "first", &path1, &tags, true).unwrap();
+        register_with_tags(&mut Ctx::silent(), &mut db, "first", &path1, &tags, true).unwrap();
 
         // With force=true, new tag creation should succeed
         let new_tags = vec!["newtag".to_string()];
-        let result = register_with_tags(&mut db, "second", &path2, &new_tags, true);
+        let result = register_with_tags(&mut Ctx::silent(), &mut db, "second", &path2, &new_tags, true);
         assert!(result.is_ok());
 
         let alias = db.get("second").unwrap();
@@ -440,11 +812,11 @@ mod tests {
 
         // Create first alias with a tag
         let tags = vec!["work".to_string()];
-        register_with_tags(&mut db, "first", &path1, &tags, true).unwrap();
+        register_with_tags(&mut Ctx::silent(), &mut db, "first", &path1, &tags, true).unwrap();
 
         // Create second alias with same tag - should succeed without force
         let same_tags = vec!["work".to_string()];
-        let result = register_with_tags(&mut db, "second", &path2, &same_tags, false);
+        let result = register_with_tags(&mut Ctx::silent(), &mut db, "second", &path2, &same_tags, false);
         assert!(result.is_ok());
 
         let alias = db.get("second").unwrap();
@@ -0,0 +1,393 @@
+//! HTML report of the database (`--report`)
+//!
+//! One self-contained page, with its CSS and JavaScript inline, that can be
+//! mailed around or dropped on a wiki: a searchable table of the aliases,
+//! their tags as chips that filter the table, and bar charts of the most
+//! used aliases and the biggest tags. Nothing is loaded from the network.
+
+use std::collections::BTreeMap;
+use std::fmt::Write as _;
+use std::path::Path;
+
+use chrono::Utc;
+use crossterm::style::Color;
+
+use crate::alias::Alias;
+use crate::collate;
+use crate::database::Database;
+use crate::filter::AliasFilter;
+use crate::table::{format_date, format_path, DateStyle, PathStyle, TagStyle};
+
+/// Bars drawn per chart
+const CHART_BARS: usize = 10;
+
+const STYLE: &str = r#"
+body { font: 14px/1.4 system-ui, sans-serif; margin: 2em auto; max-width: 72em; padding: 0 1em; color: #222; }
+h1 { margin-bottom: 0; }
+h2 { font-size: 1.1em; margin: 0 0 .5em; }
+.meta { color: #666; margin-top: .2em; }
+.charts { display: flex; flex-wrap: wrap; gap: 2em; margin: 1.5em 0; }
+.chart { flex: 1 1 20em; }
+.bar-row { display: flex; align-items: center; gap: .5em; margin: .2em 0; }
+.bar-row .label { flex: 0 0 10em; overflow: hidden; text-overflow: ellipsis; white-space: nowrap; }
+.bar-row .bar { height: .9em; background: #4a7bd0; border-radius: 2px; min-width: 2px; }
+.bar-row .value { color: #666; font-variant-numeric: tabular-nums; }
+#search { width: 100%; box-sizing: border-box; padding: .5em; font-size: 1em; margin-bottom: .5em; }
+#chips { margin-bottom: 1em; }
+.chip { display: inline-block; border: 0; border-radius: 1em; padding: .1em .7em; margin: .1em; color: #fff; font-size: .9em; cursor: pointer; }
+.chip.active { outline: 2px solid #222; }
+table { border-collapse: collapse; width: 100%; }
+th, td { text-align: left; padding: .3em .6em; border-bottom: 1px solid #ddd; vertical-align: top; }
+th { cursor: pointer; user-select: none; }
+td.num { text-align: right; font-variant-numeric: tabular-nums; }
+code { font-size: .95em; }
+"#;
+
+const SCRIPT: &str = r#"
+(function () {
+  var search = document.getElementById('search');
+  var rows = Array.prototype.slice.call(document.querySelectorAll('#aliases tbody tr'));
+  var empty = document.getElementById('empty');
+  var tag = null;
+  function update() {
+    // Match like goto does: ignoring case and accents
+    var words = search.value.normalize('NFD').replace(/[\u0300-\u036f]/g, '').toLowerCase().split(/\s+/).filter(Boolean);
+    var shown = 0;
+    rows.forEach(function (row) {
+      var text = row.getAttribute('data-search');
+      var tags = row.getAttribute('data-tags').split(' ');
+      var match = words.every(function (w) { return text.indexOf(w) >= 0; }) &&
+        (tag === null || tags.indexOf(tag) >= 0);
+      row.hidden = !match;
+      if (match) shown++;
+    });
+    empty.hidden = shown > 0;
+  }
+  document.querySelectorAll('.chip[data-tag]').forEach(function (chip) {
+    chip.addEventListener('click', function () {
+      var t = chip.getAttribute('data-tag');
+      tag = tag === t ? null : t;
+      document.querySelectorAll('.chip[data-tag]').forEach(function (c) {
+        c.classList.toggle('active', c.getAttribute('data-tag') === tag);
+      });
+      update();
+    });
+  });
+  document.querySelectorAll('#aliases th').forEach(function (th, column) {
+    var ascending = true;
+    th.addEventListener('click', function () {
+      var body = document.querySelector('#aliases tbody');
+      rows.sort(function (a, b) {
+        var x = a.cells[column].getAttribute('data-sort') || a.cells[column].textContent;
+        var y = b.cells[column].getAttribute('data-sort') || b.cells[column].textContent;
+        var n = parseFloat(x) - parseFloat(y);
+        var order = isNaN(n) ? x.localeCompare(y) : n;
+        return ascending ? order : -order;
+      });
+      ascending = !ascending;
+      rows.forEach(function (row) { body.appendChild(row); });
+    });
+  });
+  search.addEventListener('input', update);
+})();
+"#;
+
+/// Build the HTML report, returning the page and how many aliases it holds
+///
+/// With a filter expression (see [`AliasFilter`]) only matching aliases are
+/// included. Temporary aliases are left out. Tags keep the colors they have
+/// in the terminal; usage columns and the usage chart are dropped when the
+/// database doesn't track usage.
+pub fn report(
+    db: &Database,
+    filter: Option<&str>,
+    paths: PathStyle,
+    tag_colors: &BTreeMap<String, String>,
+) -> Result<(String, usize), Box<dyn std::error::Error>> {
+    let filter = filter.map(AliasFilter::parse).transpose()?;
+    let mut aliases: Vec<&Alias> = db
+        .all()
+        .filter(|a| filter.as_ref().is_none_or(|f| f.matches(a)) && !db.is_temporary(&a.name))
+        .collect();
+    aliases.sort_by_cached_key(|a| collate::sort_key(&a.name));
+
+    let usage = db.tracks_usage();
+    let tag_colors = db.tag_colors(tag_colors);
+    let colors = TagStyle::Colored(&tag_colors);
+    let chip = |tag: &str| {
+        let color = colors.color(tag).map_or("#888", css_color);
+        format!(
+            r#"<span class="chip" style="background:{}">{}</span>"#,
+            color,
+            escape(tag)
+        )
+    };
+
+    // Aliases per tag, biggest first
+    let mut tags: BTreeMap<&str, usize> = BTreeMap::new();
+    for tag in aliases.iter().flat_map(|a| &a.tags) {
+        *tags.entry(tag.as_str()).or_default() += 1;
+    }
+    let mut tag_counts: Vec<(&str, usize)> = tags.into_iter().collect();
+    tag_counts.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| collate::compare(a.0, b.0)));
+    let navigations: u64 = aliases.iter().map(|a| a.use_count).sum();
+
+    let mut html = String::new();
+    html.push_str("<!DOCTYPE html>\n<html lang=\"en\">\n<head>\n<meta charset=\"utf-8\">\n");
+    html.push_str("<meta name=\"viewport\" content=\"width=device-width, initial-scale=1\">\n");
+    html.push_str("<title>goto aliases</title>\n<style>");
+    html.push_str(STYLE);
+    html.push_str("</style>\n</head>\n<body>\n<h1>goto aliases</h1>\n");
+
+    let mut meta = vec![
+        plural(aliases.len(), "alias", "aliases"),
+        plural(tag_counts.len(), "tag", "tags"),
+    ];
+    if usage {
+        meta.push(plural(navigations as usize, "navigation", "navigations"));
+    }
+    meta.push(format!(
+        "generated {} by goto {}",
+        Utc::now().format("%Y-%m-%d %H:%M UTC"),
+        crate::cli::version()
+    ));
+    let _ = writeln!(html, "<p class=\"meta\">{}</p>", meta.join(" &middot; "));
+
+    // Charts
+    let mut most_used: Vec<&Alias> = aliases.iter().copied().filter(|a| a.use_count > 0).collect();
+    most_used.sort_by(|a, b| b.use_count.cmp(&a.use_count).then_with(|| collate::compare(&a.name, &b.name)));
+    let usage_bars: Vec<(&str, u64)> = most_used
+        .iter()
+        .take(CHART_BARS)
+        .map(|a| (a.name.as_str(), a.use_count))
+        .collect();
+    let tag_bars: Vec<(&str, u64)> = tag_counts
+        .iter()
+        .take(CHART_BARS)
+        .map(|(tag, n)| (*tag, *n as u64))
+        .collect();
+    if (usage && !usage_bars.is_empty()) || !tag_bars.is_empty() {
+        html.push_str("<section class=\"charts\">\n");
+        if usage && !usage_bars.is_empty() {
+            chart(&mut html, "Most used", &usage_bars);
+        }
+        if !tag_bars.is_empty() {
+            chart(&mut html, "Aliases per tag", &tag_bars);
+        }
+        html.push_str("</section>\n");
+    }
+
+    // Search and tag chips
+    html.push_str("<input id=\"search\" type=\"search\" placeholder=\"Search aliases, paths and tags\" autofocus>\n");
+    if !tag_counts.is_empty() {
+        html.push_str("<div id=\"chips\">");
+        let mut by_name = tag_counts.clone();
+        by_name.sort_by(|a, b| collate::compare(a.0, b.0));
+        for (tag, count) in by_name {
+            let color = colors.color(tag).map_or("#888", css_color);
+            let description = db
+                .tag_info(tag)
+                .and_then(|info| info.description.as_deref())
+                .map(|d| format!(" title=\"{}\"", escape(d)))
+                .unwrap_or_default();
+            let _ = write!(
+                html,
+                r#"<button class="chip" data-tag="{}" style="background:{}"{}>{} ({})</button>"#,
+                escape(tag),
+                color,
+                description,
+                escape(tag),
+                count
+            );
+        }
+        html.push_str("</div>\n");
+    }
+
+    // The table
+    html.push_str("<table id=\"aliases\">\n<thead><tr><th>Alias</th><th>Path</th><th>Tags</th>");
+    if usage {
+        html.push_str("<th>Uses</th><th>Last used</th>");
+    }
+    html.push_str("</tr></thead>\n<tbody>\n");
+    for alias in &aliases {
+        let target = match &alias.link {
+            Some(link) => format!("&rarr; {}", escape(link)),
+            None => format!("<code>{}</code>", escape(&format_path(&alias.path, paths))),
+        };
+        let search = collate::fold(&format!(
+            "{} {} {}",
+            alias.name,
+            alias.link.as_deref().unwrap_or(&alias.path),
+            alias.tags.join(" ")
+        ));
+        let _ = write!(
+            html,
+            r#"<tr data-search="{}" data-tags="{}"><td>{}</td><td>{}</td><td>{}</td>"#,
+            escape(&search),
+            escape(&alias.tags.join(" ")),
+            escape(&alias.name),
+            target,
+            alias.tags.iter().map(|t| chip(t)).collect::<String>()
+        );
+        if usage {
+            let _ = write!(
+                html,
+                r#"<td class="num">{}</td><td data-sort="{}">{}</td>"#,
+                alias.use_count,
+                alias.last_used.map_or(0, |t| t.timestamp()),
+                escape(&format_date(alias.last_used, DateStyle::Absolute))
+            );
+        }
+        html.push_str("</tr>\n");
+    }
+    html.push_str("</tbody>\n</table>\n<p id=\"empty\" hidden>No aliases match.</p>\n<script>");
+    html.push_str(SCRIPT);
+    html.push_str("</script>\n</body>\n</html>\n");
+
+    Ok((html, aliases.len()))
+}
+
+/// Write the HTML report to a file, returning how many aliases it holds
+///
+/// Written atomically like `--export --output`; nothing is written when no
+/// aliases are selected.
+pub fn report_to_file(
+    db: &Database,
+    output: &str,
+    filter: Option<&str>,
+    paths: PathStyle,
+    tag_colors: &BTreeMap<String, String>,
+) -> Result<usize, Box<dyn std::error::Error>> {
+    let (html, count) = report(db, filter, paths, tag_colors)?;
+    if count == 0 {
+        return Ok(0);
+    }
+
+    crate::commands::import_export::write_atomic(Path::new(output), &html)?;
+    Ok(count)
+}
+
+/// A bar chart of `bars`, scaled to the largest value
+fn chart(html: &mut String, title: &str, bars: &[(&str, u64)]) {
+    let max = bars.iter().map(|(_, n)| *n).max().unwrap_or(1).max(1);
+    let _ = writeln!(html, "<div class=\"chart\"><h2>{}</h2>", escape(title));
+    for (label, value) in bars {
+        let _ = writeln!(
+            html,
+            r#"<div class="bar-row"><span class="label" title="{0}">{0}</span><span class="bar" style="width:{1}%"></span><span class="value">{2}</span></div>"#,
+            escape(label),
+            value * 70 / max,
+            value
+        );
+    }
+    html.push_str("</div>\n");
+}
+
+/// `n` with the singular or plural noun
+fn plural(n: usize, one: &str, many: &str) -> String {
+    format!("{} {}", n, if n == 1 { one } else { many })
+}
+
+/// Escape text for HTML content and quoted attribute values
+fn escape(s: &str) -> String {
+    let mut out = String::with_capacity(s.len());
+    for c in s.chars() {
+        match c {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '"' => out.push_str("&quot;"),
+            '\'' => out.push_str("&#39;"),
+            _ => out.push(c),
+        }
+    }
+    out
+}
+
+/// A terminal tag color as CSS, in shades readable under white text
+fn css_color(color: Color) -> &'static str {
+    match color {
+        Color::Black => "#333333",
+        Color::DarkGrey | Color::Grey | Color::White | Color::Reset => "#777777",
+        Color::Red => "#d0463b",
+        Color::DarkRed => "#962d25",
+        Color::Green => "#3f9a4a",
+        Color::DarkGreen => "#2a6b32",
+        Color::Yellow => "#b8900f",
+        Color::DarkYellow => "#8a6c0b",
+        Color::Blue => "#3d6fd1",
+        Color::DarkBlue => "#264a94",
+        Color::Magenta => "#a44bb8",
+        Color::DarkMagenta => "#73337f",
+        Color::Cyan => "#1f93a3",
+        Color::DarkCyan => "#166773",
+        _ => "#888888",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    fn test_db(dir: &Path) -> Database {
+        let mut db = Database::load_from_path(&dir.join("aliases")).unwrap();
+        let mut api = Alias::new("api", "/srv/api").unwrap();
+        api.add_tag("work");
+        api.use_count = 12;
+        db.insert(api);
+        let mut web = Alias::new("web", "/srv/<web>").unwrap();
+        web.add_tag("work");
+        web.add_tag("frontend");
+        web.use_count = 3;
+        db.insert(web);
+        db.insert(Alias::new("notes", "/home/me/notes").unwrap());
+        db
+    }
+
+    #[test]
+    fn test_report() {
+        let dir = tempdir().unwrap();
+        let db = test_db(dir.path());
+
+        let (html, count) = report(&db, None, PathStyle::Full, &BTreeMap::new()).unwrap();
+        assert_eq!(count, 3);
+        assert!(html.starts_with("<!DOCTYPE html>"));
+        assert!(html.contains("3 aliases &middot; 2 tags &middot; 15 navigations"));
+        // Paths are escaped
+        assert!(html.contains("<code>/srv/&lt;web&gt;</code>"));
+        assert!(html.contains(r#"data-tag="work""#));
+        assert!(html.contains("<h2>Most used</h2>"));
+        assert!(html.contains("<h2>Aliases per tag</h2>"));
+        // Rows are in name order
+        let row = |name: &str| html.find(&format!("<td>{}</td>", name)).unwrap();
+        assert!(row("api") < row("notes") && row("notes") < row("web"));
+        // Nothing is fetched from elsewhere
+        assert!(!html.contains("http"));
+    }
+
+    #[test]
+    fn test_report_filter_and_file() {
+        let dir = tempdir().unwrap();
+        let db = test_db(dir.path());
+
+        let (html, count) = report(&db, Some("tag:frontend"), PathStyle::Full, &BTreeMap::new()).unwrap();
+        assert_eq!(count, 1);
+        assert!(html.contains("<td>web</td>"));
+        assert!(!html.contains("<td>api</td>"));
+
+        let output = dir.path().join("goto.html");
+        let count = report_to_file(&db, output.to_str().unwrap(), None, PathStyle::Full, &BTreeMap::new()).unwrap();
+        assert_eq!(count, 3);
+        assert!(std::fs::read_to_string(&output).unwrap().contains("<td>notes</td>"));
+
+        let empty = dir.path().join("empty.html");
+        assert_eq!(report_to_file(&db, empty.to_str().unwrap(), Some("tag:none"), PathStyle::Full, &BTreeMap::new()).unwrap(), 0);
+        assert!(!empty.exists());
+    }
+
+    #[test]
+    fn test_escape() {
+        assert_eq!(escape(r#"<a href="x">'&'</a>"#), "&lt;a href=&quot;x&quot;&gt;&#39;&amp;&#39;&lt;/a&gt;");
+    }
+}
@@ -0,0 +1,539 @@
+//! Review queue for database hygiene: one alias at a time, a keystroke each
+//!
+//! Aliases are queued when they match a [`Criterion`]: dormant, pointing at a
+//! missing directory, sharing a path with another alias, or untagged. An
+//! alias is dormant once it has gone `hygiene.dormant_after_days` without
+//! being used, created or kept in a review. Archived aliases move to
+//! `archive.toml` in the data directory, from where they can be imported back.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::error::Error;
+use std::fmt;
+use std::fs::File;
+use std::io::{self, BufReader, IsTerminal};
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+use crate::alias::{Alias, AliasError};
+use crate::commands::duplicates::{self, Overlap};
+use crate::commands::register::validate_and_normalize_tags;
+use crate::config::{expand_path, Config};
+use crate::context::Ctx;
+use crate::database::Database;
+use crate::table::{create_table, format_path, PathStyle, TableStyle};
+
+/// Why an alias is up for review, in the order the queue presents them
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Criterion {
+    /// The directory is gone
+    Missing,
+    /// Another alias points at the same directory
+    Duplicate,
+    /// Unused for `hygiene.dormant_after_days`
+    Dormant,
+    /// Has no tags
+    Untagged,
+}
+
+impl Criterion {
+    pub const ALL: [Criterion; 4] = [Criterion::Missing, Criterion::Duplicate, Criterion::Dormant, Criterion::Untagged];
+
+    /// Reviewed when no criteria are given; untagged aliases are opt-in
+    pub const DEFAULT: [Criterion; 3] = [Criterion::Missing, Criterion::Duplicate, Criterion::Dormant];
+
+    /// Parse a comma-separated list; `all` selects every criterion
+    pub fn parse_list(s: &str) -> Result<Vec<Self>, String> {
+        let mut criteria = Vec::new();
+        for word in s.split(',').map(str::trim).filter(|w| !w.is_empty()) {
+            if word.eq_ignore_ascii_case("all") {
+                criteria.extend(Self::ALL);
+            } else {
+                criteria.push(word.parse()?);
+            }
+        }
+        criteria.sort();
+        criteria.dedup();
+        Ok(criteria)
+    }
+}
+
+impl FromStr for Criterion {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "missing" => Ok(Criterion::Missing),
+            "duplicate" | "duplicates" => Ok(Criterion::Duplicate),
+            "dormant" => Ok(Criterion::Dormant),
+            "untagged" => Ok(Criterion::Untagged),
+            _ => Err(format!(
+                "invalid review criterion: {} (must be missing, duplicate, dormant, untagged or all)",
+                s
+            )),
+        }
+    }
+}
+
+impl fmt::Display for Criterion {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Criterion::Missing => "missing directory",
+            Criterion::Duplicate => "duplicate path",
+            Criterion::Dormant => "dormant",
+            Criterion::Untagged => "untagged",
+        })
+    }
+}
+
+/// An alias in the review queue and the criteria it matched
+#[derive(Debug, Clone, Serialize)]
+pub struct ReviewItem {
+    pub alias: Alias,
+    pub reasons: Vec<Criterion>,
+}
+
+/// What to do with a queued alias
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Decision {
+    /// Leave it as it is; a dormant alias starts a new dormancy period
+    Keep,
+    /// Point it at another directory
+    Edit(String),
+    /// Add these tags
+    Tag(Vec<String>),
+    /// Move it to the archive
+    Archive,
+    /// Remove it for good
+    Delete,
+    /// Leave it for the next review
+    Skip,
+    /// Stop reviewing
+    Quit,
+}
+
+/// Outcome of a review
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct ReviewSummary {
+    pub kept: usize,
+    pub edited: usize,
+    pub tagged: usize,
+    pub archived: usize,
+    pub deleted: usize,
+}
+
+/// When the dormancy hint was last shown
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct HintState {
+    #[serde(default)]
+    last_hint: Option<DateTime<Utc>>,
+}
+
+/// File archived aliases are moved to
+pub fn archive_path(config: &Config) -> PathBuf {
+    config.database_path.join("archive.toml")
+}
+
+fn hint_path(config: &Config) -> PathBuf {
+    config.database_path.join("review_cache.json")
+}
+
+/// Dormant aliases, least recently active first
+///
+/// Computed from the current configuration rather than the flags stored at
+/// the last save. Temporary and shared aliases are left out.
+pub fn dormant(db: &Database, config: &Config) -> Vec<Alias> {
+    let Some(after) = config.dormant_after() else {
+        return Vec::new();
+    };
+    let now = Utc::now();
+    let mut aliases: Vec<Alias> = db
+        .all()
+        .filter(|a| !db.is_temporary(&a.name) && !db.is_shared(&a.name))
+        .filter(|a| a.is_dormant(after, now))
+        .cloned()
+        .collect();
+    aliases.sort_by_key(|a| (last_active(a), a.name.clone()));
+    aliases
+}
+
+/// Aliases matching any of `criteria`, in review order
+///
+/// Ordered by their first reason, then least recently active first.
+/// Temporary and shared aliases are left out.
+pub fn queue(db: &Database, config: &Config, criteria: &[Criterion]) -> Vec<ReviewItem> {
+    let dormant: HashSet<String> = dormant(db, config).into_iter().map(|a| a.name).collect();
+    let duplicated: HashSet<&str> = duplicates::find_all(db)
+        .into_iter()
+        .filter(|(overlap, _, _)| *overlap == Overlap::Duplicate)
+        .flat_map(|(_, a, b)| [a.name.as_str(), b.name.as_str()])
+        .collect();
+
+    let mut items: Vec<ReviewItem> = db
+        .all()
+        .filter(|a| !db.is_temporary(&a.name) && !db.is_shared(&a.name))
+        .filter_map(|alias| {
+            let reasons: Vec<Criterion> = Criterion::ALL
+                .into_iter()
+                .filter(|c| criteria.contains(c))
+                .filter(|c| match c {
+                    Criterion::Missing => !alias.pending && !Path::new(&alias.path).exists(),
+                    Criterion::Duplicate => duplicated.contains(alias.name.as_str()),
+                    Criterion::Dormant => dormant.contains(&alias.name),
+                    Criterion::Untagged => alias.tags.is_empty(),
+                })
+                .collect();
+            (!reasons.is_empty()).then(|| ReviewItem {
+                alias: alias.clone(),
+                reasons,
+            })
+        })
+        .collect();
+    items.sort_by_key(|item| (item.reasons[0], last_active(&item.alias), item.alias.name.clone()));
+    items
+}
+
+fn last_active(alias: &Alias) -> DateTime<Utc> {
+    [alias.last_used, alias.reviewed_at]
+        .into_iter()
+        .flatten()
+        .fold(alias.created_at, DateTime::max)
+}
+
+/// Move an alias from the database into the archive
+pub fn archive(db: &mut Database, config: &Config, name: &str) -> Result<(), Box<dyn Error>> {
+    let Some(alias) = db.get(name).cloned() else {
+        return Err(AliasError::NotFound(name.to_string()).into());
+    };
+    let mut archive = Database::load_from_path(&archive_path(config))?;
+    archive.insert(alias);
+    archive.save()?;
+    db.remove(name);
+    Ok(())
+}
+
+/// Walk through the aliases matching `criteria`, asking `decide` about each
+///
+/// Changes are made to `db` as decisions come in and saved at the end.
+pub fn review(
+    db: &mut Database,
+    config: &Config,
+    criteria: &[Criterion],
+    mut decide: impl FnMut(&ReviewItem) -> io::Result<Decision>,
+) -> Result<ReviewSummary, Box<dyn Error>> {
+    let mut summary = ReviewSummary::default();
+    for item in queue(db, config, criteria) {
+        let alias = &item.alias;
+        // An earlier decision may have removed it
+        if !db.contains(&alias.name) {
+            continue;
+        }
+        match decide(&item)? {
+            Decision::Keep => {
+                if let Some(a) = db.get_mut(&alias.name) {
+                    a.reviewed_at = Some(Utc::now());
+                    a.dormant = false;
+                }
+                summary.kept += 1;
+            }
+            Decision::Edit(path) => {
+                let path = existing_dir(&path)?;
+                db.set_path(&alias.name, path)?;
+                if let Some(a) = db.get_mut(&alias.name) {
+                    a.pending = false;
+                    a.reviewed_at = Some(Utc::now());
+                }
+                summary.edited += 1;
+            }
+            Decision::Tag(tags) => {
+                let tags = validate_and_normalize_tags(&tags)?;
+                if let Some(a) = db.get_mut(&alias.name) {
+                    for tag in &tags {
+                        a.add_tag(tag);
+                    }
+                }
+                summary.tagged += 1;
+            }
+            Decision::Archive => {
+                archive(db, config, &alias.name)?;
+                summary.archived += 1;
+            }
+            Decision::Delete => {
+                db.remove(&alias.name);
+                summary.deleted += 1;
+            }
+            Decision::Skip => {}
+            Decision::Quit => break,
+        }
+    }
+    db.save()?;
+    Ok(summary)
+}
+
+/// Expanded form of `path` if it's an existing directory
+fn existing_dir(path: &str) -> Result<String, Box<dyn Error>> {
+    let expanded = expand_path(path)?;
+    if !expanded.is_dir() {
+        return Err(AliasError::DirectoryNotFound(expanded.to_string_lossy().into_owned()).into());
+    }
+    Ok(expanded.to_string_lossy().into_owned())
+}
+
+fn format_reasons(reasons: &[Criterion]) -> String {
+    reasons.iter().map(|r| r.to_string()).collect::<Vec<_>>().join(", ")
+}
+
+/// Ask on the terminal what to do with a queued alias; no answer skips it
+///
+/// Edit and tag ask a follow-up question and re-ask on invalid input, so a
+/// typo doesn't end the review.
+pub fn prompt_decision(ctx: &mut Ctx, item: &ReviewItem) -> io::Result<Decision> {
+    let alias = &item.alias;
+    let message = format!(
+        "'{}' -> {} ({}; last used {})\n[k]eep, [e]dit path, [t]ag, [a]rchive, [d]elete, [s]kip, [q]uit?",
+        alias.name,
+        alias.path,
+        format_reasons(&item.reasons),
+        format_last_used(alias)
+    );
+    loop {
+        let Some(answer) = ctx.prompt_input(&message)? else {
+            return Ok(Decision::Skip);
+        };
+        match answer.to_lowercase().as_str() {
+            "k" | "keep" => return Ok(Decision::Keep),
+            "e" | "edit" => {
+                let Some(path) = ctx.prompt_input("New path:")? else {
+                    continue;
+                };
+                match existing_dir(&path) {
+                    Ok(path) => return Ok(Decision::Edit(path)),
+                    Err(e) => crate::errln!(ctx, "Error: {}", e),
+                }
+            }
+            "t" | "tag" => {
+                let Some(tags) = ctx.prompt_input("Tags (comma-separated):")? else {
+                    continue;
+                };
+                let tags: Vec<String> = tags.split(',').map(String::from).collect();
+                match validate_and_normalize_tags(&tags) {
+                    Ok(tags) if !tags.is_empty() => return Ok(Decision::Tag(tags)),
+                    Ok(_) => {}
+                    Err(e) => crate::errln!(ctx, "Error: {}", e),
+                }
+            }
+            "a" | "archive" => return Ok(Decision::Archive),
+            "d" | "delete" => return Ok(Decision::Delete),
+            "s" | "skip" => return Ok(Decision::Skip),
+            "q" | "quit" => return Ok(Decision::Quit),
+            _ => crate::errln!(ctx, "Please answer k, e, t, a, d, s or q."),
+        }
+    }
+}
+
+fn format_last_used(alias: &Alias) -> String {
+    alias
+        .last_used
+        .map(|t| t.format("%Y-%m-%d").to_string())
+        .unwrap_or_else(|| "never".to_string())
+}
+
+/// Render the review queue as a table
+pub fn format_queue(items: &[ReviewItem], config: &Config) -> String {
+    let style = TableStyle::from(config.user.display.table_style.as_str());
+    let path_style = PathStyle::from(config.user.display.path_style.as_str());
+    let mut table = create_table(style);
+    table.set_header(vec!["Name", "Path", "Reason", "Last used"]);
+    for item in items {
+        table.add_row(vec![
+            item.alias.name.clone(),
+            format_path(&item.alias.path, path_style),
+            format_reasons(&item.reasons),
+            format_last_used(&item.alias),
+        ]);
+    }
+    table.to_string()
+}
+
+/// Mention dormant aliases on stderr, at most once a day
+///
+/// Called after navigating. Only speaks up on a terminal and when
+/// `hygiene.hint` is on.
+pub fn notify_if_dormant(ctx: &mut Ctx, config: &Config, db: &Database) {
+    if !config.user.hygiene.hint || ctx.quiet || !io::stderr().is_terminal() {
+        return;
+    }
+
+    let path = hint_path(config);
+    let state: HintState = File::open(&path)
+        .ok()
+        .and_then(|file| serde_json::from_reader(BufReader::new(file)).ok())
+        .unwrap_or_default();
+    let now = Utc::now();
+    if state.last_hint.is_some_and(|last| now - last < chrono::Duration::days(1)) {
+        return;
+    }
+
+    let count = db.dormant_count();
+    if count == 0 {
+        return;
+    }
+    crate::errln!(
+        ctx,
+        "Note: {} alias{} unused for {}+ days. Run 'goto --review' to tidy up.",
+        count,
+        if count == 1 { "" } else { "es" },
+        config.user.hygiene.dormant_after_days
+    );
+    let state = HintState { last_hint: Some(now) };
+    if let Ok(file) = File::create(&path) {
+        let _ = serde_json::to_writer_pretty(file, &state);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Duration;
+    use std::path::Path;
+    use tempfile::tempdir;
+
+    fn aged(name: &str, days: i64) -> Alias {
+        let mut alias = Alias::new(name, "/tmp").unwrap();
+        alias.created_at = Utc::now() - Duration::days(days);
+        alias
+    }
+
+    fn create_test_db(dir: &Path) -> Database {
+        let mut db = Database::load_from_path(&dir.join("aliases.toml")).unwrap();
+        db.insert(aged("fresh", 5));
+        db.insert(aged("old", 200));
+        db.insert(aged("older", 400));
+        let mut used = aged("used", 400);
+        used.last_used = Some(Utc::now() - Duration::days(1));
+        db.insert(used);
+        db
+    }
+
+    #[test]
+    fn test_dormant_oldest_first() {
+        let dir = tempdir().unwrap();
+        let mut config = Config::in_dir(dir.path());
+        let db = create_test_db(dir.path());
+
+        let names: Vec<String> = dormant(&db, &config).into_iter().map(|a| a.name).collect();
+        assert_eq!(names, vec!["older", "old"]);
+
+        config.user.hygiene.dormant_after_days = 0;
+        assert!(dormant(&db, &config).is_empty());
+    }
+
+    #[test]
+    fn test_save_flags_dormant() {
+        let dir = tempdir().unwrap();
+        let config = Config::in_dir(dir.path());
+        let mut db = Database::load(&config).unwrap();
+        db.insert(aged("old", 200));
+        db.insert(aged("fresh", 5));
+        db.save().unwrap();
+        assert_eq!(db.dormant_count(), 1);
+        assert!(db.get("old").unwrap().dormant);
+    }
+
+    #[test]
+    fn test_review_actions() {
+        let dir = tempdir().unwrap();
+        let config = Config::in_dir(dir.path());
+        let mut db = create_test_db(dir.path());
+
+        let summary = review(&mut db, &config, &[Criterion::Dormant], |item| {
+            Ok(if item.alias.name == "older" { Decision::Archive } else { Decision::Keep })
+        })
+        .unwrap();
+        assert_eq!(summary, ReviewSummary { kept: 1, archived: 1, ..Default::default() });
+        assert!(!db.contains("older"));
+        assert!(db.get("old").unwrap().reviewed_at.is_some());
+        assert!(dormant(&db, &config).is_empty());
+
+        let archive = Database::load_from_path(&archive_path(&config)).unwrap();
+        assert!(archive.contains("older"));
+    }
+
+    #[test]
+    fn test_review_delete_and_quit() {
+        let dir = tempdir().unwrap();
+        let config = Config::in_dir(dir.path());
+        let mut db = create_test_db(dir.path());
+
+        let mut asked = 0;
+        let summary = review(&mut db, &config, &[Criterion::Dormant], |_| {
+            asked += 1;
+            Ok(if asked == 1 { Decision::Delete } else { Decision::Quit })
+        })
+        .unwrap();
+        assert_eq!(summary.deleted, 1);
+        assert_eq!(asked, 2);
+        assert!(!db.contains("older"));
+        assert!(db.contains("old"));
+        assert!(!archive_path(&config).exists());
+    }
+
+    #[test]
+    fn test_parse_criteria() {
+        assert_eq!(Criterion::parse_list("untagged, missing").unwrap(), vec![Criterion::Missing, Criterion::Untagged]);
+        assert_eq!(Criterion::parse_list("all").unwrap(), Criterion::ALL.to_vec());
+        assert!(Criterion::parse_list("stale").unwrap_err().contains("invalid"));
+    }
+
+    #[test]
+    fn test_queue_criteria() {
+        let dir = tempdir().unwrap();
+        let config = Config::in_dir(dir.path());
+        let here = dir.path().to_str().unwrap();
+        let mut db = Database::load_from_path(&dir.path().join("aliases.toml")).unwrap();
+        let mut tagged = Alias::new("tagged", here).unwrap();
+        tagged.add_tag("work");
+        db.insert(tagged);
+        db.insert(Alias::new("twin", here).unwrap());
+        db.insert(Alias::new("gone", "/nonexistent/goto-review").unwrap());
+        db.insert(aged("old", 200));
+
+        let names = |criteria: &[Criterion]| -> Vec<String> {
+            queue(&db, &config, criteria).into_iter().map(|i| i.alias.name).collect()
+        };
+        assert_eq!(names(&Criterion::DEFAULT), vec!["gone", "tagged", "twin", "old"]);
+        assert_eq!(names(&[Criterion::Untagged]), vec!["old", "twin", "gone"]);
+
+        let items = queue(&db, &config, &Criterion::ALL);
+        let twin = items.iter().find(|i| i.alias.name == "twin").unwrap();
+        assert_eq!(twin.reasons, vec![Criterion::Duplicate, Criterion::Untagged]);
+    }
+
+    #[test]
+    fn test_review_edit_and_tag() {
+        let dir = tempdir().unwrap();
+        let config = Config::in_dir(dir.path());
+        let mut db = Database::load_from_path(&dir.path().join("aliases.toml")).unwrap();
+        db.insert(Alias::new("gone", "/nonexistent/goto-review").unwrap());
+        db.insert(Alias::new("plain", dir.path().to_str().unwrap()).unwrap());
+
+        let new_path = dir.path().to_str().unwrap().to_string();
+        let summary = review(&mut db, &config, &[Criterion::Missing, Criterion::Untagged], |item| {
+            Ok(match item.alias.name.as_str() {
+                "gone" if item.reasons[0] == Criterion::Missing => Decision::Edit(new_path.clone()),
+                _ => Decision::Tag(vec!["Work".to_string()]),
+            })
+        })
+        .unwrap();
+        assert_eq!(summary, ReviewSummary { edited: 1, tagged: 1, ..Default::default() });
+        assert_eq!(db.get("gone").unwrap().path, new_path);
+        assert_eq!(db.get("plain").unwrap().tags, vec!["work"]);
+
+        let result = review(&mut db, &config, &[Criterion::Untagged], |_| {
+            Ok(Decision::Edit("/nonexistent/goto-review".to_string()))
+        });
+        assert!(result.is_err());
+    }
+}
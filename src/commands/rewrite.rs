@@ -0,0 +1,237 @@
+//! Move alias paths to a new root (`goto --rewrite-prefix`)
+
+use std::path::Path;
+
+use crate::alias::AliasError;
+use crate::config::expand_path_with;
+use crate::database::Database;
+use crate::plan::PlannedChange;
+use crate::table::{create_table, TableStyle};
+
+/// Options for [`rewrite_prefix`]
+#[derive(Debug, Clone, Copy)]
+pub struct RewriteOptions {
+    /// Only compute the rewrites
+    pub dry_run: bool,
+    /// Rewrite paths whose new directory doesn't exist, marking them pending
+    pub allow_missing: bool,
+    /// Canonicalize the new prefix, like `general.resolve_symlinks` on register
+    pub resolve_symlinks: bool,
+}
+
+impl Default for RewriteOptions {
+    fn default() -> Self {
+        Self {
+            dry_run: false,
+            allow_missing: false,
+            resolve_symlinks: true,
+        }
+    }
+}
+
+/// One alias whose path changes
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PathRewrite {
+    pub name: String,
+    pub before: String,
+    pub after: String,
+    /// The new directory doesn't exist
+    pub missing: bool,
+}
+
+/// Outcome of [`rewrite_prefix`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct RewriteResult {
+    /// Expanded old prefix
+    pub from: String,
+    /// Expanded new prefix
+    pub to: String,
+    /// Affected aliases, sorted by name; empty when nothing was under `from`
+    pub rewrites: Vec<PathRewrite>,
+    /// The rewrites were only previewed, not saved
+    pub dry_run: bool,
+}
+
+impl RewriteResult {
+    /// One-line description, e.g. "Rewrote 3 alias paths from /a to /b"
+    pub fn summary(&self) -> String {
+        let count = self.rewrites.len();
+        if count == 0 {
+            return format!("No aliases under {}", self.from);
+        }
+        let plural = if count == 1 { "" } else { "s" };
+        if self.dry_run {
+            format!("Would rewrite {} alias path{} from {} to {} (dry-run):", count, plural, self.from, self.to)
+        } else {
+            format!("Rewrote {} alias path{} from {} to {}", count, plural, self.from, self.to)
+        }
+    }
+
+    /// The rewrites as planned changes, for a dry run
+    pub fn planned(&self) -> Vec<PlannedChange> {
+        self.rewrites
+            .iter()
+            .map(|r| {
+                let after = if r.missing { format!("{} (missing)", r.after) } else { r.after.clone() };
+                PlannedChange::update(&r.name, r.before.clone(), after)
+            })
+            .collect()
+    }
+}
+
+/// Replace the leading `old` of every alias path with `new`
+///
+/// Only whole path components match: `/code` covers `/code` and
+/// `/code/api` but not `/codex`. Shared aliases are left alone. Unless
+/// `allow_missing` is set, nothing is changed when any new directory is
+/// missing; with it, those aliases become pending. All rewrites are saved
+/// at once.
+pub fn rewrite_prefix(
+    db: &mut Database,
+    old: &str,
+    new: &str,
+    options: &RewriteOptions,
+) -> Result<RewriteResult, Box<dyn std::error::Error>> {
+    // The old location is usually gone already, so it can't be canonicalized
+    let from = expand_path_with(old, false)?;
+    let to = expand_path_with(new, options.resolve_symlinks)?;
+    if from == to {
+        return Err("old and new prefix are the same".into());
+    }
+
+    let mut rewrites: Vec<PathRewrite> = db
+        .all()
+        .filter(|a| !db.is_shared(&a.name))
+        .filter_map(|a| {
+            let rest = Path::new(&a.path).strip_prefix(&from).ok()?;
+            let after = if rest.as_os_str().is_empty() { to.clone() } else { to.join(rest) };
+            Some(PathRewrite {
+                name: a.name.clone(),
+                before: a.path.clone(),
+                missing: !after.is_dir(),
+                after: after.to_string_lossy().into_owned(),
+            })
+        })
+        .collect();
+    rewrites.sort_by(|a, b| a.name.cmp(&b.name));
+
+    let result = RewriteResult {
+        from: from.to_string_lossy().into_owned(),
+        to: to.to_string_lossy().into_owned(),
+        rewrites,
+        dry_run: options.dry_run,
+    };
+    if result.rewrites.is_empty() || options.dry_run {
+        return Ok(result);
+    }
+
+    let missing: Vec<_> = result.rewrites.iter().filter(|r| r.missing).collect();
+    if let (Some(first), false) = (missing.first(), options.allow_missing) {
+        let mut message = AliasError::DirectoryNotFound(first.after.clone()).to_string();
+        if missing.len() > 1 {
+            message.push_str(&format!(" (and {} more)", missing.len() - 1));
+        }
+        message.push_str("; use --allow-missing to rewrite anyway");
+        return Err(message.into());
+    }
+
+    for rewrite in &result.rewrites {
+        db.set_path(&rewrite.name, rewrite.after.clone())?;
+        if let Some(alias) = db.get_mut(&rewrite.name) {
+            alias.pending = rewrite.missing;
+        }
+    }
+    db.save()?;
+    Ok(result)
+}
+
+/// Format rewrites as a table of old and new paths
+pub fn format_rewrites(rewrites: &[PathRewrite], style: TableStyle) -> String {
+    let mut table = create_table(style);
+    table.set_header(vec!["Name", "Current Path", "New Path"]);
+    for rewrite in rewrites {
+        let after = if rewrite.missing {
+            format!("{} (missing)", rewrite.after)
+        } else {
+            rewrite.after.clone()
+        };
+        table.add_row(vec![rewrite.name.clone(), rewrite.before.clone(), after]);
+    }
+    table.to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::alias::Alias;
+    use std::fs;
+    use tempfile::tempdir;
+
+    /// Aliases under `old/`, plus one sharing only a name prefix with it
+    fn setup() -> (Database, tempfile::TempDir) {
+        let dir = tempdir().unwrap();
+        let root = dir.path().canonicalize().unwrap();
+        let mut db = Database::load_from_path(&root.join("aliases")).unwrap();
+        let old = root.join("old");
+        db.insert(Alias::new("root", old.to_str().unwrap()).unwrap());
+        db.insert(Alias::new("api", old.join("api").to_str().unwrap()).unwrap());
+        db.insert(Alias::new("other", root.join("older").to_str().unwrap()).unwrap());
+        (db, dir)
+    }
+
+    fn path(dir: &tempfile::TempDir, rest: &str) -> String {
+        dir.path().canonicalize().unwrap().join(rest).to_string_lossy().into_owned()
+    }
+
+    #[test]
+    fn test_rewrite_prefix() {
+        let (mut db, dir) = setup();
+        fs::create_dir_all(path(&dir, "new/api")).unwrap();
+
+        let result = rewrite_prefix(&mut db, &path(&dir, "old/"), &path(&dir, "new"), &Default::default()).unwrap();
+        let names: Vec<_> = result.rewrites.iter().map(|r| r.name.as_str()).collect();
+        assert_eq!(names, ["api", "root"]);
+        assert_eq!(result.summary(), format!("Rewrote 2 alias paths from {} to {}", path(&dir, "old"), path(&dir, "new")));
+
+        assert_eq!(db.get("api").unwrap().path, path(&dir, "new/api"));
+        assert_eq!(db.get("root").unwrap().path, path(&dir, "new"));
+        assert_eq!(db.get("other").unwrap().path, path(&dir, "older"));
+        let reloaded = Database::load_from_path(&dir.path().canonicalize().unwrap().join("aliases")).unwrap();
+        assert_eq!(reloaded.get("api").unwrap().path, path(&dir, "new/api"));
+    }
+
+    #[test]
+    fn test_rewrite_prefix_missing_and_dry_run() {
+        let (mut db, dir) = setup();
+        fs::create_dir_all(path(&dir, "new")).unwrap();
+        let (old, new) = (path(&dir, "old"), path(&dir, "new"));
+
+        // new/api doesn't exist: nothing changes
+        let err = rewrite_prefix(&mut db, &old, &new, &Default::default()).unwrap_err();
+        assert!(err.to_string().contains("directory does not exist"));
+        assert_eq!(db.get("root").unwrap().path, old);
+
+        let options = RewriteOptions { dry_run: true, ..Default::default() };
+        let result = rewrite_prefix(&mut db, &old, &new, &options).unwrap();
+        assert!(result.summary().starts_with("Would rewrite 2 alias paths"));
+        assert!(result.rewrites[0].missing);
+        assert!(result.planned()[0].after.ends_with("(missing)"));
+        assert_eq!(db.get("root").unwrap().path, old);
+
+        let options = RewriteOptions { allow_missing: true, ..Default::default() };
+        rewrite_prefix(&mut db, &old, &new, &options).unwrap();
+        assert!(db.get("api").unwrap().pending);
+        assert!(!db.get("root").unwrap().pending);
+    }
+
+    #[test]
+    fn test_rewrite_prefix_nothing_to_do() {
+        let (mut db, dir) = setup();
+        let result = rewrite_prefix(&mut db, &path(&dir, "elsewhere"), &path(&dir, "new"), &Default::default()).unwrap();
+        assert!(result.rewrites.is_empty());
+        assert!(result.summary().starts_with("No aliases under"));
+
+        assert!(rewrite_prefix(&mut db, &path(&dir, "old"), &path(&dir, "old"), &Default::default()).is_err());
+    }
+}
@@ -0,0 +1,299 @@
+//! Search aliases across names, paths, tags and notes
+
+use std::collections::HashMap;
+use std::path::Path;
+
+use comfy_table::{Attribute, Cell, Color};
+use serde::Serialize;
+
+use crate::alias::Alias;
+use crate::collate;
+use crate::commands::notes;
+use crate::config::Config;
+use crate::database::Database;
+use crate::fuzzy;
+use crate::table::{create_table, format_path, stdout_is_terminal, PathStyle, TableStyle, TagStyle};
+
+/// Number of results shown when `--limit` is not given
+pub const DEFAULT_LIMIT: usize = 20;
+
+/// The alias field a search result matched on
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Field {
+    Name,
+    Path,
+    Tag,
+    Note,
+}
+
+impl Field {
+    fn label(self) -> &'static str {
+        match self {
+            Field::Name => "name",
+            Field::Path => "path",
+            Field::Tag => "tag",
+            Field::Note => "note",
+        }
+    }
+}
+
+/// A ranked search result
+#[derive(Debug, Clone, Serialize)]
+pub struct SearchResult {
+    pub name: String,
+    pub path: String,
+    pub tags: Vec<String>,
+    /// Field with the best match
+    pub field: Field,
+    /// Match score, 0-1000
+    pub score: i32,
+    /// The matching line of the alias's note, or its first line
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub note: Option<String>,
+}
+
+/// Score a single string against the query (0-1000, 0 if no match)
+fn score(query: &str, candidate: &str) -> i32 {
+    fuzzy::find_matches(query, std::iter::once(candidate))
+        .first()
+        .map_or(0, |(_, score)| *score)
+}
+
+/// Best scoring line of a note, matching on its words
+fn note_match<'a>(query: &str, note: &'a str) -> Option<(&'a str, i32)> {
+    note.lines()
+        .map(str::trim)
+        .filter_map(|line| {
+            line.split(|c: char| !(c.is_alphanumeric() || c == '-' || c == '_'))
+                .map(|word| score(query, word))
+                .max()
+                .map(|score| (line, score))
+        })
+        .fold(None, |best, candidate| match best {
+            Some((_, s)) if s >= candidate.1 => best,
+            _ => Some(candidate),
+        })
+}
+
+/// Best matching field of an alias; names win ties over paths, paths over
+/// tags and tags over notes
+fn best_match(query: &str, alias: &Alias, note: Option<&str>) -> (Field, i32) {
+    let name = score(query, &alias.name);
+
+    // Paths match on any component, or as a substring of the whole path
+    let components = Path::new(&alias.path)
+        .iter()
+        .map(|c| score(query, &c.to_string_lossy()))
+        .max()
+        .unwrap_or(0);
+    let whole = if fuzzy::is_substring(query, &alias.path) {
+        score(query, &alias.path)
+    } else {
+        0
+    };
+    let path = components.max(whole);
+
+    let tag = alias.tags.iter().map(|t| score(query, t)).max().unwrap_or(0);
+
+    let note = note.and_then(|n| note_match(query, n)).map_or(0, |(_, score)| score);
+
+    [(Field::Name, name), (Field::Path, path), (Field::Tag, tag), (Field::Note, note)]
+        .into_iter()
+        .fold((Field::Name, name), |best, candidate| {
+            if candidate.1 > best.1 {
+                candidate
+            } else {
+                best
+            }
+        })
+}
+
+/// Rank aliases matching `query`, best first
+///
+/// `notes` holds alias notes by name (see [`notes::read_notes`]). Only
+/// matches scoring at least `threshold` (0.0-1.0) are returned.
+pub fn find(db: &Database, notes: &HashMap<String, String>, query: &str, threshold: f64) -> Vec<SearchResult> {
+    let min_score = (threshold * 1000.0) as i32;
+
+    let mut results: Vec<SearchResult> = db
+        .all()
+        .filter_map(|alias| {
+            let note = notes.get(&alias.name).map(String::as_str);
+            let (field, score) = best_match(query, alias, note);
+            (score >= min_score && score > 0).then(|| SearchResult {
+                name: alias.name.clone(),
+                path: alias.path.clone(),
+                tags: alias.tags.clone(),
+                field,
+                score,
+                note: note.map(|n| {
+                    let line = match field {
+                        Field::Note => note_match(query, n).map_or("", |(line, _)| line),
+                        _ => notes::first_line(n),
+                    };
+                    line.to_string()
+                }),
+            })
+        })
+        .collect();
+
+    results.sort_by(|a, b| b.score.cmp(&a.score).then_with(|| collate::compare(&a.name, &b.name)));
+    results
+}
+
+/// Search aliases with the configured threshold, keeping the best `limit` results
+pub fn search(db: &Database, config: &Config, query: &str, limit: Option<usize>) -> Vec<SearchResult> {
+    let notes = notes::read_notes(config, db);
+    let mut results = find(db, &notes, query, config.user.general.fuzzy_threshold);
+    results.truncate(limit.unwrap_or(DEFAULT_LIMIT));
+    results
+}
+
+/// Render search results as a table, highlighting the matched field
+///
+/// Tags are colored unless they are the highlighted match; nothing is
+/// colored when `display.color` and `NO_COLOR` say not to. Long paths are
+/// shortened to fit the terminal unless `wide` (`--wide`).
+pub fn format_results(results: &[SearchResult], config: &Config, wide: bool) -> String {
+    let style = TableStyle::from(config.user.display.table_style.as_str());
+    let path_style = PathStyle::from(config.user.display.path_style.as_str());
+    let tag_style = config.tag_style(stdout_is_terminal());
+    let color = matches!(tag_style, TagStyle::Colored(_));
+    let with_notes = results.iter().any(|r| r.note.is_some());
+    let fit = config.table_fit(wide);
+    let mut table = create_table(style);
+    fit.apply(&mut table);
+    if color {
+        // The shell wrappers capture the output, so comfy-table sees no terminal
+        table.enforce_styling();
+    }
+    let mut header = vec!["Name", "Path", "Tags", "Match"];
+    if with_notes {
+        header.push("Note");
+    }
+
+    let mut texts: Vec<Vec<String>> = results
+        .iter()
+        .map(|result| {
+            let tags = if result.field == Field::Tag {
+                TagStyle::Plain.join(&result.tags, ", ")
+            } else {
+                tag_style.join(&result.tags, ", ")
+            };
+            let mut row = vec![
+                result.name.clone(),
+                format_path(&result.path, path_style),
+                tags,
+                format!("{} {}%", result.field.label(), result.score / 10),
+            ];
+            if with_notes {
+                row.push(result.note.as_deref().unwrap_or("-").to_string());
+            }
+            row
+        })
+        .collect();
+    fit.shorten_paths(style, &header, &mut texts, 1);
+    table.set_header(header);
+
+    for (result, texts) in results.iter().zip(texts) {
+        let highlight = |cell: Cell, field: Field| {
+            if color && result.field == field {
+                cell.fg(Color::Green).add_attribute(Attribute::Bold)
+            } else {
+                cell
+            }
+        };
+        let mut row = vec![
+            highlight(Cell::new(&texts[0]), Field::Name),
+            highlight(Cell::new(&texts[1]), Field::Path),
+            highlight(Cell::new(&texts[2]), Field::Tag),
+            Cell::new(&texts[3]),
+        ];
+        if with_notes {
+            row.push(highlight(Cell::new(&texts[4]), Field::Note));
+        }
+        table.add_row(row);
+    }
+
+    table.to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::NamedTempFile;
+
+    fn create_test_db() -> (Database, NamedTempFile) {
+        let file = NamedTempFile::new().unwrap();
+        let mut db = Database::load_from_path(file.path()).unwrap();
+
+        let mut api = Alias::new("api", "/home/user/work/backend").unwrap();
+        api.add_tag("rust");
+        db.insert(api);
+        db.insert(Alias::new("rusty", "/home/user/play").unwrap());
+        db.insert(Alias::new("docs", "/srv/www/docs").unwrap());
+
+        (db, file)
+    }
+
+    #[test]
+    fn test_find_matches_each_field() {
+        let (db, _file) = create_test_db();
+
+        let results = find(&db, &HashMap::new(), "backend", 0.6);
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].name, "api");
+        assert_eq!(results[0].field, Field::Path);
+
+        let results = find(&db, &HashMap::new(), "rust", 0.6);
+        let names: Vec<&str> = results.iter().map(|r| r.name.as_str()).collect();
+        assert_eq!(names, vec!["api", "rusty"]);
+        assert_eq!(results[0].field, Field::Tag);
+        assert_eq!(results[1].field, Field::Name);
+    }
+
+    #[test]
+    fn test_find_prefers_name_on_ties() {
+        let (db, _file) = create_test_db();
+
+        let results = find(&db, &HashMap::new(), "docs", 0.6);
+        assert_eq!(results[0].name, "docs");
+        assert_eq!(results[0].field, Field::Name);
+        assert_eq!(results[0].score, 1000);
+    }
+
+    #[test]
+    fn test_find_respects_threshold() {
+        let (db, _file) = create_test_db();
+        assert!(find(&db, &HashMap::new(), "zzzzzz", 0.6).is_empty());
+    }
+
+    #[test]
+    fn test_find_matches_notes() {
+        let (db, _file) = create_test_db();
+        let notes = HashMap::from([(
+            "docs".to_string(),
+            "Static site\nDeploy with rsync to the staging host".to_string(),
+        )]);
+
+        let results = find(&db, &notes, "staging", 0.6);
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].name, "docs");
+        assert_eq!(results[0].field, Field::Note);
+        assert_eq!(results[0].note.as_deref(), Some("Deploy with rsync to the staging host"));
+
+        // Other matches still show the note's first line
+        let results = find(&db, &notes, "docs", 0.6);
+        assert_eq!(results[0].field, Field::Name);
+        assert_eq!(results[0].note.as_deref(), Some("Static site"));
+    }
+
+    #[test]
+    fn test_search_json_serializes_field() {
+        let (db, _file) = create_test_db();
+        let results = find(&db, &HashMap::new(), "backend", 0.6);
+        let json = serde_json::to_string(&results).unwrap();
+        assert!(json.contains("\"field\":\"path\""));
+    }
+}
@@ -0,0 +1,163 @@
+//! Remote navigation over SSH (`goto --ssh <host> <alias>`)
+//!
+//! Each host has its own alias table under `hosts/` in the database
+//! directory, in the `--export` format, filled by `--ssh-import` (typically
+//! piping `ssh host goto --export` into it). `--ssh` looks an alias up in the
+//! host's table and opens a shell there in the alias's directory.
+
+use std::fs;
+use std::io::{self, Read};
+use std::path::PathBuf;
+use std::process;
+
+use crate::commands::navigate::{fuzzy_candidates, FUZZY_CONFIDENT_SCORE};
+use crate::config::Config;
+use crate::database::Database;
+use crate::exit::Failure;
+use crate::output::quote_posix;
+
+/// Check that `host` can name a table file and can't pass as an ssh option
+pub fn validate_host(host: &str) -> Result<(), Failure> {
+    let valid = !host.is_empty()
+        && !host.starts_with(['-', '.'])
+        && host.chars().all(|c| c.is_ascii_alphanumeric() || "._@-".contains(c));
+    if valid {
+        Ok(())
+    } else {
+        Err(Failure::invalid(format!(
+            "invalid host '{}': use letters, digits, dots, hyphens, underscores and @",
+            host
+        )))
+    }
+}
+
+/// Path of the alias table kept for `host`
+pub fn host_table_path(config: &Config, host: &str) -> Result<PathBuf, Failure> {
+    validate_host(host)?;
+    Ok(config.hosts_dir().join(format!("{}.toml", host)))
+}
+
+/// Replace the alias table of `host` with an `--export` file (`-` for stdin)
+///
+/// Returns how many aliases the table holds.
+pub fn import_host(config: &Config, host: &str, file_path: &str) -> Result<usize, Box<dyn std::error::Error>> {
+    let path = host_table_path(config, host)?;
+    let content = if file_path == "-" {
+        let mut content = String::new();
+        io::stdin().read_to_string(&mut content)?;
+        content
+    } else {
+        fs::read_to_string(file_path)?
+    };
+
+    let mut table = Database::load_from_path(&path)?;
+    let count = table.replace_from_toml(&content)?;
+    table.save()?;
+    Ok(count)
+}
+
+/// Directory `alias` points at on `host`
+pub fn remote_path(config: &Config, host: &str, alias: &str) -> Result<String, Box<dyn std::error::Error>> {
+    let path = host_table_path(config, host)?;
+    if !path.exists() {
+        return Err(format!(
+            "no aliases for host '{}'; import them with: ssh {} goto --export | goto --ssh-import {} -",
+            host, host, host
+        )
+        .into());
+    }
+
+    let table = Database::load_from_path(&path)?;
+    match table.get(alias) {
+        Some(entry) => Ok(entry.path.clone()),
+        None => {
+            let mut message = format!("alias '{}' not found on host '{}'", alias, host);
+            if let Some((name, _)) = fuzzy_candidates(&table, alias)
+                .into_iter()
+                .find(|(_, score)| *score >= FUZZY_CONFIDENT_SCORE)
+            {
+                message.push_str(&format!(" (did you mean '{}'?)", name));
+            }
+            Err(Failure::not_found(message).into())
+        }
+    }
+}
+
+/// Arguments to `ssh` for a shell in `path` on `host`
+///
+/// The remote command is parsed by the login shell on the host, so the path
+/// is quoted for sh-family shells and `$SHELL` is expanded there.
+pub fn ssh_args(host: &str, path: &str) -> Vec<String> {
+    vec![
+        "-t".to_string(),
+        host.to_string(),
+        format!("cd {} && exec \"$SHELL\"", quote_posix(path)),
+    ]
+}
+
+/// The `ssh` command line for `host` and `path`, quoted for sh-family shells
+pub fn ssh_command_line(host: &str, path: &str) -> String {
+    let args = ssh_args(host, path);
+    format!("ssh {} {} {}", args[0], args[1], quote_posix(&args[2]))
+}
+
+/// Open a shell in `path` on `host`, returning ssh's exit code
+pub fn connect(host: &str, path: &str) -> Result<i32, Box<dyn std::error::Error>> {
+    crate::debug!("running {}", ssh_command_line(host, path));
+    let status = process::Command::new("ssh")
+        .args(ssh_args(host, path))
+        .status()
+        .map_err(|e| format!("failed to run ssh: {}", e))?;
+    Ok(status.code().unwrap_or(1))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_validate_host() {
+        assert!(validate_host("build-01.example.com").is_ok());
+        assert!(validate_host("me@box").is_ok());
+        assert!(validate_host("-oProxyCommand=x").is_err());
+        assert!(validate_host("../aliases").is_err());
+        assert!(validate_host("a/b").is_err());
+        assert!(validate_host("").is_err());
+    }
+
+    #[test]
+    fn test_import_host_and_remote_path() {
+        let dir = tempdir().unwrap();
+        let config = Config::in_dir(dir.path());
+        let err = remote_path(&config, "box", "api").unwrap_err();
+        assert!(err.to_string().contains("goto --ssh-import box -"));
+
+        let export = dir.path().join("export.toml");
+        fs::write(
+            &export,
+            "[[aliases]]\nname = \"backend\"\npath = \"/srv/backend\"\n",
+        )
+        .unwrap();
+        assert_eq!(import_host(&config, "box", export.to_str().unwrap()).unwrap(), 1);
+        assert!(dir.path().join("hosts").join("box.toml").exists());
+        assert_eq!(remote_path(&config, "box", "backend").unwrap(), "/srv/backend");
+
+        let err = remote_path(&config, "box", "bakend").unwrap_err();
+        assert_eq!(err.to_string(), "alias 'bakend' not found on host 'box' (did you mean 'backend'?)");
+
+        // A broken file leaves the table alone
+        fs::write(&export, "not toml [").unwrap();
+        assert!(import_host(&config, "box", export.to_str().unwrap()).is_err());
+        assert_eq!(remote_path(&config, "box", "backend").unwrap(), "/srv/backend");
+    }
+
+    #[test]
+    fn test_ssh_command_line() {
+        assert_eq!(ssh_args("box", "/srv/my api")[2], "cd '/srv/my api' && exec \"$SHELL\"");
+        assert_eq!(
+            ssh_command_line("box", "/srv/api"),
+            r#"ssh -t box 'cd '\''/srv/api'\'' && exec "$SHELL"'"#
+        );
+    }
+}
@@ -1,19 +1,54 @@
-//! Stack commands: push, pop
+//! Stack commands: push, pop, show
 
-use std::path::Path;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
 
 use crate::alias::AliasError;
 use crate::config::Config;
 use crate::database::Database;
-use crate::stack::Stack;
+use crate::exit::Failure;
+use crate::stack::{self, Stack};
+
+/// Directory holding per-session stack files
+fn sessions_dir(config: &Config) -> PathBuf {
+    config.database_path.join("stacks")
+}
+
+/// Stack file for the given session (the global file unless per-session is enabled)
+fn stack_path_for_session(config: &Config, session: &str) -> PathBuf {
+    if config.user.stack.per_session {
+        stack::session_stack_path(&sessions_dir(config), session)
+    } else {
+        config.stack_path.clone()
+    }
+}
+
+/// Open the stack for the current shell session
+fn current_stack(config: &Config) -> Stack {
+    Stack::new(stack_path_for_session(config, &stack::session_id()))
+}
+
+/// One directory stack, as listed by [`all_stacks`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct SessionStack {
+    /// Shell session id, or `global` for the shared stack
+    pub session: String,
+    /// Whether this is the calling shell's stack
+    pub current: bool,
+    /// Directories, top of the stack first
+    pub entries: Vec<String>,
+}
 
 /// Push current directory to stack and navigate to alias
-/// Prints the path for the shell function to cd to
-pub fn push(config: &Config, db: &mut Database, alias: &str) -> Result<(), Box<dyn std::error::Error>> {
+///
+/// Returns the alias's directory for the shell to cd to.
+pub fn push(config: &Config, db: &mut Database, alias: &str) -> Result<String, Box<dyn std::error::Error>> {
     // Get the alias path - first check existence, then modify
-    let path = {
-        let entry = db.get(alias).ok_or_else(|| AliasError::NotFound(alias.to_string()))?;
-        entry.path.clone()
+    let (name, path) = {
+        // A link's use is recorded on the alias it stands for
+        let entry = db.resolve(alias)?;
+        (entry.name.clone(), entry.path.clone())
     };
 
     // Verify target directory exists
@@ -29,26 +64,31 @@ pub fn push(config: &Config, db: &mut Database, alias: &str) -> Result<(), Box<d
     let current = std::env::current_dir()?;
 
     // Push to stack (new API handles persistence automatically)
-    let stack = Stack::new(config.stack_path.clone());
+    let stack = current_stack(config);
     stack.push(&current.to_string_lossy())?;
 
+    // Forget stacks of shells that are gone (best-effort)
+    if config.user.stack.per_session {
+        let ttl = Duration::from_secs(config.user.stack.session_ttl_hours * 3600);
+        let _ = stack::gc_stale_sessions(&sessions_dir(config), &stack::session_id(), ttl);
+    }
+
     // Record use after pushing to stack (so we don't record if push fails)
-    if let Some(entry) = db.get_mut(alias) {
+    if let Some(entry) = db.get_mut(&name) {
         entry.record_use();
     }
     db.save()?;
 
-    // Print path for shell to cd to
-    println!("{}", path);
-    Ok(())
+    Ok(path)
 }
 
 /// Pop directory from stack and return to it
-/// Prints the path for the shell function to cd to
-pub fn pop(config: &Config) -> Result<(), Box<dyn std::error::Error>> {
-    let stack = Stack::new(config.stack_path.clone());
+///
+/// Returns the popped directory for the shell to cd to.
+pub fn pop(config: &Config) -> Result<String, Box<dyn std::error::Error>> {
+    let stack = current_stack(config);
 
-    let path = stack.pop().map_err(|_| "stack is empty")?;
+    let path = stack.pop().map_err(|_| Failure::not_found("stack is empty"))?;
 
     // Verify the directory still exists
     let dir_path = Path::new(&path);
@@ -59,8 +99,44 @@ pub fn pop(config: &Config) -> Result<(), Box<dyn std::error::Error>> {
         return Err(format!("not a directory: {}", path).into());
     }
 
-    println!("{}", path);
-    Ok(())
+    Ok(path)
+}
+
+/// The current session's directory stack, top first
+pub fn entries(config: &Config) -> Result<Vec<String>, Box<dyn std::error::Error>> {
+    let mut entries = current_stack(config).entries()?;
+    entries.reverse();
+    Ok(entries)
+}
+
+/// Every non-empty directory stack: the global one, then each session's
+pub fn all_stacks(config: &Config) -> Result<Vec<SessionStack>, Box<dyn std::error::Error>> {
+    let current = stack::session_id();
+    let dir = sessions_dir(config);
+    let ttl = Duration::from_secs(config.user.stack.session_ttl_hours * 3600);
+    let _ = stack::gc_stale_sessions(&dir, &current, ttl);
+
+    let mut stacks = Vec::new();
+    let global = Stack::new(config.stack_path.clone()).entries()?;
+    if !global.is_empty() {
+        stacks.push(SessionStack {
+            session: "global".to_string(),
+            current: !config.user.stack.per_session,
+            entries: global.into_iter().rev().collect(),
+        });
+    }
+    for (session, path) in stack::list_sessions(&dir)? {
+        let entries = Stack::new(path).entries()?;
+        if !entries.is_empty() {
+            stacks.push(SessionStack {
+                current: config.user.stack.per_session && session == current,
+                session,
+                entries: entries.into_iter().rev().collect(),
+            });
+        }
+    }
+
+    Ok(stacks)
 }
 
 #[cfg(test)]
@@ -69,7 +145,6 @@ mod tests {
     use crate::alias::Alias;
     use crate::config::UserConfig;
     use std::fs;
-    use std::path::PathBuf;
     use tempfile::TempDir;
 
     fn create_test_config() -> (Config, TempDir) {
@@ -79,12 +154,14 @@ mod tests {
             stack_path: temp_dir.path().join("goto_stack"),
             config_path: temp_dir.path().join("config.toml"),
             aliases_path: temp_dir.path().join("aliases.toml"),
+            profile: None,
             user: UserConfig::default(),
+            notices: Vec::new(),
         };
         (config, temp_dir)
     }
 
-    fn create_test_db(path: &PathBuf) -> Database {
+    fn create_test_db(path: &Path) -> Database {
         let mut db = Database::load_from_path(path).unwrap();
         db.insert(Alias::new("test", "/tmp").unwrap());
         db
@@ -131,6 +208,39 @@ mod tests {
         assert!(err.contains("not a directory"), "Expected 'not a directory' in: {}", err);
     }
 
+    #[test]
+    fn test_stack_path_for_session() {
+        let (mut config, temp) = create_test_config();
+        assert_eq!(stack_path_for_session(&config, "123"), config.stack_path);
+
+        config.user.stack.per_session = true;
+        assert_eq!(
+            stack_path_for_session(&config, "123"),
+            temp.path().join("stacks").join("123")
+        );
+    }
+
+    #[test]
+    fn test_show_empty_stack() {
+        let (config, _temp) = create_test_config();
+        assert!(entries(&config).unwrap().is_empty());
+        assert!(all_stacks(&config).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_entries_top_first() {
+        let (config, _temp) = create_test_config();
+        let stack = Stack::new(config.stack_path.clone());
+        stack.push("/first").unwrap();
+        stack.push("/second").unwrap();
+
+        assert_eq!(entries(&config).unwrap(), vec!["/second", "/first"]);
+        let stacks = all_stacks(&config).unwrap();
+        assert_eq!(stacks.len(), 1);
+        assert_eq!(stacks[0].session, "global");
+        assert_eq!(stacks[0].entries, vec!["/second", "/first"]);
+    }
+
     #[test]
     fn test_pop_empty_stack() {
         let (config, _temp) = create_test_config();
@@ -168,12 +278,11 @@ mod tests {
         let mut db = create_test_db(&config.aliases_path);
 
         // Push should succeed (alias points to /tmp which exists)
-        let result = push(&config, &mut db, "test");
-        assert!(result.is_ok());
+        assert_eq!(push(&config, &mut db, "test").unwrap(), "/tmp");
 
         // Pop should succeed and return the pushed directory
-        let result = pop(&config);
-        assert!(result.is_ok());
+        let cwd = std::env::current_dir().unwrap();
+        assert_eq!(pop(&config).unwrap(), cwd.to_string_lossy());
     }
 
     #[test]
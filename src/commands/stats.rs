@@ -1,67 +1,119 @@
-//! Statistics commands: stats, recent, clear_recent
+//! Statistics commands: stats, recent, clear_recent, reset_stats, set_count
 
 use chrono::{DateTime, Utc};
 
-use crate::config::Config;
+use crate::commands::navigate::NavigationResult;
+use crate::context::Ctx;
 use crate::database::Database;
-use crate::table::{TableStyle, create_table};
+use crate::exit::Failure;
+use crate::table::{format_date, DateStyle, TableStyle, create_table};
+use crate::ui::selector::{self, Choice};
 
 /// Recent entry for display
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct RecentEntry {
     pub alias: String,
     pub path: String,
     pub last_used: DateTime<Utc>,
 }
 
-/// Show usage statistics
-pub fn stats(db: &Database, config: &Config) -> Result<(), Box<dyn std::error::Error>> {
-    if db.is_empty() {
-        println!("No aliases registered");
-        return Ok(());
+/// Usage statistics, as returned by [`stats`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct UsageStats {
+    /// Number of registered aliases
+    pub total_aliases: usize,
+    /// Sum of all use counts
+    pub total_navigations: u64,
+    /// Up to ten most used aliases, most used first
+    pub top: Vec<UsageEntry>,
+    /// Aliases flagged dormant (see `hygiene.dormant_after_days`)
+    pub dormant: usize,
+    /// Whether uses are recorded; `false` leaves the usage fields empty
+    pub tracking: bool,
+}
+
+/// One row of [`UsageStats::top`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UsageEntry {
+    pub name: String,
+    pub use_count: u64,
+    pub last_used: Option<DateTime<Utc>>,
+}
+
+/// Collect usage statistics
+pub fn stats(db: &Database) -> UsageStats {
+    if !db.tracks_usage() {
+        return UsageStats {
+            total_aliases: db.len(),
+            total_navigations: 0,
+            top: Vec::new(),
+            dormant: 0,
+            tracking: false,
+        };
     }
 
     // Sort by use count descending
     let mut entries: Vec<_> = db.all().collect();
-    entries.sort_by(|a, b| b.use_count.cmp(&a.use_count));
-
-    // Calculate total navigations
-    let total_navigations: u64 = entries.iter().map(|e| e.use_count).sum();
+    entries.sort_by_key(|a| std::cmp::Reverse(a.use_count));
+
+    UsageStats {
+        total_aliases: entries.len(),
+        total_navigations: entries.iter().map(|e| e.use_count).sum(),
+        // Only used entries, top 10
+        top: entries
+            .iter()
+            .filter(|e| e.use_count > 0)
+            .take(10)
+            .map(|e| UsageEntry {
+                name: e.name.clone(),
+                use_count: e.use_count,
+                last_used: e.last_used,
+            })
+            .collect(),
+        dormant: db.dormant_count(),
+        tracking: true,
+    }
+}
 
-    println!("Usage Statistics");
-    println!();
+/// Render usage statistics as `--stats` shows them
+pub fn format_stats(stats: &UsageStats, style: TableStyle, dates: DateStyle) -> String {
+    if stats.total_aliases == 0 {
+        return "No aliases registered".to_string();
+    }
 
-    // Filter to only used entries and take top 10
-    let used_entries: Vec<_> = entries
-        .iter()
-        .filter(|e| e.use_count > 0)
-        .take(10)
-        .collect();
+    if !stats.tracking {
+        return format!(
+            "Usage tracking is off (privacy.track_usage = false)\n\nTotal aliases: {}",
+            stats.total_aliases
+        );
+    }
 
-    if used_entries.is_empty() {
-        println!("(no aliases have been used yet)");
+    let mut out = String::from("Usage Statistics\n\n");
+    if stats.top.is_empty() {
+        out.push_str("(no aliases have been used yet)\n");
     } else {
-        let style = TableStyle::from(config.user.display.table_style.as_str());
         let mut table = create_table(style);
         table.set_header(vec!["#", "Name", "Uses", "Last Used"]);
 
-        for (i, entry) in used_entries.iter().enumerate() {
-            let last_used_str = format_time_ago(entry.last_used);
+        for (i, entry) in stats.top.iter().enumerate() {
             table.add_row(vec![
                 (i + 1).to_string(),
                 entry.name.clone(),
                 entry.use_count.to_string(),
-                last_used_str,
+                format_date(entry.last_used, dates),
             ]);
         }
 
-        println!("{table}");
+        out.push_str(&format!("{table}\n"));
     }
 
-    println!();
-    println!("Total aliases: {}", entries.len());
-    println!("Total navigations: {}", total_navigations);
-
-    Ok(())
+    out.push_str(&format!("\nTotal aliases: {}\n", stats.total_aliases));
+    out.push_str(&format!("Total navigations: {}", stats.total_navigations));
+    if stats.dormant > 0 {
+        out.push_str(&format!("\nDormant aliases: {} (run 'goto --review')", stats.dormant));
+    }
+    out
 }
 
 /// Get recently visited aliases sorted by last_used descending
@@ -74,7 +126,10 @@ pub fn recent(db: &Database, limit: Option<usize>) -> Result<Vec<RecentEntry>, B
     }
 
     // Sort by last_used descending
-    used_entries.sort_by(|a, b| b.last_used.cmp(&a.last_used));
+    used_entries.sort_by_key(|a| std::cmp::Reverse(a.last_used));
+
+    // Back-to-back visits to one directory under different aliases count once
+    used_entries.dedup_by(|later, earlier| later.path == earlier.path);
 
     // Limit results
     if let Some(limit) = limit {
@@ -91,37 +146,29 @@ pub fn recent(db: &Database, limit: Option<usize>) -> Result<Vec<RecentEntry>, B
         .collect())
 }
 
-/// Display recently visited aliases
-pub fn show_recent(db: &Database, config: &Config, limit: usize) -> Result<(), Box<dyn std::error::Error>> {
-    let limit = if limit == 0 { 10 } else { limit };
-    let entries = recent(db, Some(limit))?;
-
+/// Render recently visited aliases as `--recent` lists them
+pub fn format_recent(entries: &[RecentEntry], style: TableStyle, dates: DateStyle) -> String {
     if entries.is_empty() {
-        println!("No recently visited directories");
-        return Ok(());
+        return "No recently visited directories".to_string();
     }
 
-    let style = TableStyle::from(config.user.display.table_style.as_str());
     let mut table = create_table(style);
     table.set_header(vec!["#", "Name", "Path", "Last Visited"]);
 
     for (i, entry) in entries.iter().enumerate() {
-        let time_ago = format_time_ago(Some(entry.last_used));
         table.add_row(vec![
             (i + 1).to_string(),
             entry.alias.clone(),
             entry.path.clone(),
-            time_ago,
+            format_date(Some(entry.last_used), dates),
         ]);
     }
 
-    println!("{table}");
-
-    Ok(())
+    table.to_string()
 }
 
 /// Navigate to the Nth most recent alias
-pub fn navigate_to_recent(db: &mut Database, index: usize) -> Result<(), Box<dyn std::error::Error>> {
+pub fn navigate_to_recent(ctx: &mut Ctx, db: &mut Database, index: usize) -> Result<NavigationResult, Box<dyn std::error::Error>> {
     let entries = recent(db, None)?;
 
     if entries.is_empty() {
@@ -129,80 +176,98 @@ pub fn navigate_to_recent(db: &mut Database, index: usize) -> Result<(), Box<dyn
     }
 
     if index < 1 || index > entries.len() {
-        return Err(format!(
+        return Err(Failure::invalid(format!(
             "invalid recent index: {} (valid: 1-{})",
             index,
             entries.len()
-        )
+        ))
         .into());
     }
 
     // Navigate to the alias
-    crate::commands::navigate::navigate(db, &entries[index - 1].alias)
+    crate::commands::navigate::navigate(ctx, db, &entries[index - 1].alias)
+}
+
+/// Pick a recent alias interactively and navigate to it
+///
+/// Returns `None` when there was nothing to pick from.
+pub fn pick_recent(ctx: &mut Ctx, db: &mut Database, limit: usize) -> Result<Option<NavigationResult>, Box<dyn std::error::Error>> {
+    let limit = if limit == 0 { 10 } else { limit };
+    let entries = recent(db, Some(limit))?;
+
+    if entries.is_empty() {
+        return Ok(None);
+    }
+
+    let choices = recent_choices(&entries);
+    match selector::select(ctx, &choices)? {
+        Some(i) => crate::commands::navigate::navigate(ctx, db, &entries[i].alias).map(Some),
+        None => Err(Failure::cancelled("Navigation cancelled").into()),
+    }
+}
+
+/// Selector entries for recent aliases: name and compact age, path as preview
+fn recent_choices(entries: &[RecentEntry]) -> Vec<Choice> {
+    let width = entries.iter().map(|e| e.alias.chars().count()).max().unwrap_or(0);
+    entries
+        .iter()
+        .map(|e| Choice {
+            label: format!("{:<width$}  {}", e.alias, format_time_ago_short(e.last_used)),
+            score: None,
+            path: e.path.clone(),
+            detail: None,
+        })
+        .collect()
 }
 
 /// Clear recent history (reset last_used for all aliases)
 pub fn clear_recent(db: &mut Database) -> Result<(), Box<dyn std::error::Error>> {
     db.clear_recent_history()?;
     db.save()?;
-    println!("Cleared recent history");
     Ok(())
 }
 
-/// Format a timestamp as a human-readable "time ago" string
-fn format_time_ago(t: Option<DateTime<Utc>>) -> String {
-    let t = match t {
-        Some(t) => t,
-        None => return "never".to_string(),
+/// Reset usage stats for one alias, or for all of them when `alias` is None
+///
+/// Returns how many aliases were reset.
+pub fn reset_stats(db: &mut Database, alias: Option<&str>) -> Result<usize, Box<dyn std::error::Error>> {
+    let count = match alias {
+        Some(name) => {
+            db.reset_stats(name)?;
+            1
+        }
+        None => db.reset_all_stats(),
     };
+    db.save()?;
+    Ok(count)
+}
 
-    let duration = Utc::now().signed_duration_since(t);
-
-    if duration.num_seconds() < 60 {
-        return "just now".to_string();
-    }
+/// Set an alias's use count, e.g. to move it in usage-sorted lists
+pub fn set_count(db: &mut Database, alias: &str, count: u64) -> Result<(), Box<dyn std::error::Error>> {
+    db.set_use_count(alias, count)?;
+    db.save()?;
+    Ok(())
+}
 
+/// Format a timestamp as a compact "time ago" string, e.g. "2h ago"
+fn format_time_ago_short(t: DateTime<Utc>) -> String {
+    let duration = Utc::now().signed_duration_since(t);
     let minutes = duration.num_minutes();
-    if minutes < 60 {
-        return if minutes == 1 {
-            "1 minute ago".to_string()
-        } else {
-            format!("{} minutes ago", minutes)
-        };
-    }
-
     let hours = duration.num_hours();
-    if hours < 24 {
-        return if hours == 1 {
-            "1 hour ago".to_string()
-        } else {
-            format!("{} hours ago", hours)
-        };
-    }
-
     let days = duration.num_days();
-    if days < 7 {
-        return if days == 1 {
-            "1 day ago".to_string()
-        } else {
-            format!("{} days ago", days)
-        };
-    }
-
-    let weeks = days / 7;
-    if weeks < 4 {
-        return if weeks == 1 {
-            "1 week ago".to_string()
-        } else {
-            format!("{} weeks ago", weeks)
-        };
-    }
 
-    let months = days / 30;
-    if months == 1 {
-        "1 month ago".to_string()
+    if minutes < 1 {
+        "just now".to_string()
+    } else if hours < 1 {
+        format!("{}m ago", minutes)
+    } else if days < 1 {
+        format!("{}h ago", hours)
+    } else if days < 7 {
+        format!("{}d ago", days)
+    } else if days < 30 {
+        format!("{}w ago", days / 7)
     } else {
-        format!("{} months ago", months)
+        format!("{}mo ago", days / 30)
     }
 }
 
@@ -210,7 +275,6 @@ fn format_time_ago(t: Option<DateTime<Utc>>) -> String {
 mod tests {
     use super::*;
     use crate::alias::Alias;
-    use crate::config::Config;
     use chrono::Duration;
     use tempfile::NamedTempFile;
 
@@ -238,18 +302,50 @@ mod tests {
     #[test]
     fn test_stats() {
         let (db, _file) = create_test_db();
-        let config = Config::load().unwrap();
-        let result = stats(&db, &config);
-        assert!(result.is_ok());
+        let stats = stats(&db);
+        assert_eq!(stats.total_aliases, 3);
+        assert_eq!(stats.total_navigations, 13);
+        let top: Vec<_> = stats.top.iter().map(|e| (e.name.as_str(), e.use_count)).collect();
+        assert_eq!(top, vec![("often", 10), ("sometimes", 3)]);
+
+        let out = format_stats(&stats, TableStyle::Minimal, DateStyle::Relative);
+        assert!(out.starts_with("Usage Statistics\n"), "{}", out);
+        assert!(out.contains("often"));
+        assert!(out.ends_with("Total aliases: 3\nTotal navigations: 13"), "{}", out);
+    }
+
+    #[test]
+    fn test_stats_without_usage_tracking() {
+        let stats = UsageStats {
+            total_aliases: 3,
+            total_navigations: 0,
+            top: Vec::new(),
+            dormant: 0,
+            tracking: false,
+        };
+        let out = format_stats(&stats, TableStyle::Minimal, DateStyle::Relative);
+        assert!(out.starts_with("Usage tracking is off"), "{}", out);
+        assert!(!out.contains("Total navigations"));
+        assert!(out.ends_with("Total aliases: 3"));
+    }
+
+    #[test]
+    fn test_stats_mentions_dormant_aliases() {
+        let (mut db, _file) = create_test_db();
+        db.get_mut("never").unwrap().dormant = true;
+        let stats = stats(&db);
+        assert_eq!(stats.dormant, 1);
+        let out = format_stats(&stats, TableStyle::Minimal, DateStyle::Relative);
+        assert!(out.ends_with("Dormant aliases: 1 (run 'goto --review')"), "{}", out);
     }
 
     #[test]
     fn test_stats_empty() {
         let file = NamedTempFile::new().unwrap();
         let db = Database::load_from_path(file.path()).unwrap();
-        let config = Config::load().unwrap();
-        let result = stats(&db, &config);
-        assert!(result.is_ok());
+        let stats = stats(&db);
+        assert_eq!(stats.total_aliases, 0);
+        assert_eq!(format_stats(&stats, TableStyle::Minimal, DateStyle::Relative), "No aliases registered");
     }
 
     #[test]
@@ -282,6 +378,28 @@ mod tests {
         assert_eq!(entries[1].alias, "first");
     }
 
+    #[test]
+    fn test_recent_dedups_consecutive_visits_to_same_path() {
+        let file = NamedTempFile::new().unwrap();
+        let mut db = Database::load_from_path(file.path()).unwrap();
+        let now = Utc::now();
+
+        for (name, path, minutes_ago) in [
+            ("proj", "/tmp/proj", 1),
+            ("p", "/tmp/proj", 2),
+            ("docs", "/tmp/docs", 3),
+            ("project", "/tmp/proj", 4),
+        ] {
+            let mut alias = Alias::new(name, path).unwrap();
+            alias.last_used = Some(now - Duration::minutes(minutes_ago));
+            db.insert(alias);
+        }
+
+        let names: Vec<_> = recent(&db, None).unwrap().into_iter().map(|e| e.alias).collect();
+        // "p" repeats "proj" right after it; "project" is a later, separate visit
+        assert_eq!(names, vec!["proj", "docs", "project"]);
+    }
+
     #[test]
     fn test_recent_with_limit() {
         let (db, _file) = create_test_db();
@@ -298,20 +416,16 @@ mod tests {
     }
 
     #[test]
-    fn test_show_recent() {
+    fn test_format_recent() {
         let (db, _file) = create_test_db();
-        let config = Config::load().unwrap();
-        let result = show_recent(&db, &config, 5);
-        assert!(result.is_ok());
+        let out = format_recent(&recent(&db, Some(5)).unwrap(), TableStyle::Minimal, DateStyle::Relative);
+        assert!(out.contains("Last Visited"));
+        assert!(out.contains("/tmp/sometimes"));
     }
 
     #[test]
-    fn test_show_recent_empty() {
-        let file = NamedTempFile::new().unwrap();
-        let db = Database::load_from_path(file.path()).unwrap();
-        let config = Config::load().unwrap();
-        let result = show_recent(&db, &config, 5);
-        assert!(result.is_ok());
+    fn test_format_recent_empty() {
+        assert_eq!(format_recent(&[], TableStyle::Minimal, DateStyle::Relative), "No recently visited directories");
     }
 
     #[test]
@@ -319,12 +433,12 @@ mod tests {
         let (mut db, _file) = create_test_db();
 
         // Index 0 is invalid
-        let result = navigate_to_recent(&mut db, 0);
+        let result = navigate_to_recent(&mut Ctx::silent(), &mut db, 0);
         assert!(result.is_err());
         assert!(result.unwrap_err().to_string().contains("invalid recent index"));
 
         // Index too high
-        let result = navigate_to_recent(&mut db, 100);
+        let result = navigate_to_recent(&mut Ctx::silent(), &mut db, 100);
         assert!(result.is_err());
         assert!(result.unwrap_err().to_string().contains("invalid recent index"));
     }
@@ -334,11 +448,38 @@ mod tests {
         let file = NamedTempFile::new().unwrap();
         let mut db = Database::load_from_path(file.path()).unwrap();
 
-        let result = navigate_to_recent(&mut db, 1);
+        let result = navigate_to_recent(&mut Ctx::silent(), &mut db, 1);
         assert!(result.is_err());
         assert!(result.unwrap_err().to_string().contains("no recently visited"));
     }
 
+    #[test]
+    fn test_pick_recent_empty() {
+        let file = NamedTempFile::new().unwrap();
+        let mut db = Database::load_from_path(file.path()).unwrap();
+        assert_eq!(pick_recent(&mut Ctx::silent(), &mut db, 10).unwrap(), None);
+    }
+
+    #[test]
+    fn test_recent_choices_show_age_and_path() {
+        let entries = vec![
+            RecentEntry {
+                alias: "proj".to_string(),
+                path: "/tmp/proj".to_string(),
+                last_used: Utc::now() - Duration::hours(2),
+            },
+            RecentEntry {
+                alias: "documents".to_string(),
+                path: "/tmp/docs".to_string(),
+                last_used: Utc::now() - Duration::days(3),
+            },
+        ];
+        let choices = recent_choices(&entries);
+        assert_eq!(choices[0].label, "proj       2h ago");
+        assert_eq!(choices[0].path, "/tmp/proj");
+        assert_eq!(choices[1].label, "documents  3d ago");
+    }
+
     #[test]
     fn test_clear_recent() {
         let (mut db, _file) = create_test_db();
@@ -357,58 +498,42 @@ mod tests {
     }
 
     #[test]
-    fn test_format_time_ago_none() {
-        assert_eq!(format_time_ago(None), "never");
-    }
-
-    #[test]
-    fn test_format_time_ago_just_now() {
-        let now = Utc::now();
-        assert_eq!(format_time_ago(Some(now)), "just now");
-    }
+    fn test_reset_stats_single_and_all() {
+        let (mut db, _file) = create_test_db();
 
-    #[test]
-    fn test_format_time_ago_minutes() {
-        let time = Utc::now() - Duration::minutes(1);
-        assert_eq!(format_time_ago(Some(time)), "1 minute ago");
+        assert_eq!(reset_stats(&mut db, Some("often")).unwrap(), 1);
+        assert_eq!(db.get("often").unwrap().use_count, 0);
+        assert_eq!(db.get("sometimes").unwrap().use_count, 3);
 
-        let time = Utc::now() - Duration::minutes(30);
-        assert_eq!(format_time_ago(Some(time)), "30 minutes ago");
+        assert_eq!(reset_stats(&mut db, None).unwrap(), 1);
+        assert!(db.all().all(|a| a.use_count == 0 && a.last_used.is_none()));
     }
 
     #[test]
-    fn test_format_time_ago_hours() {
-        let time = Utc::now() - Duration::hours(1);
-        assert_eq!(format_time_ago(Some(time)), "1 hour ago");
-
-        let time = Utc::now() - Duration::hours(5);
-        assert_eq!(format_time_ago(Some(time)), "5 hours ago");
-    }
-
-    #[test]
-    fn test_format_time_ago_days() {
-        let time = Utc::now() - Duration::days(1);
-        assert_eq!(format_time_ago(Some(time)), "1 day ago");
-
-        let time = Utc::now() - Duration::days(3);
-        assert_eq!(format_time_ago(Some(time)), "3 days ago");
+    fn test_reset_stats_not_found() {
+        let (mut db, _file) = create_test_db();
+        let err = reset_stats(&mut db, Some("missing")).unwrap_err();
+        assert!(err.to_string().contains("not found"));
     }
 
     #[test]
-    fn test_format_time_ago_weeks() {
-        let time = Utc::now() - Duration::weeks(1);
-        assert_eq!(format_time_ago(Some(time)), "1 week ago");
+    fn test_set_count_reorders_usage() {
+        let (mut db, _file) = create_test_db();
+        set_count(&mut db, "never", 100).unwrap();
 
-        let time = Utc::now() - Duration::weeks(2);
-        assert_eq!(format_time_ago(Some(time)), "2 weeks ago");
+        let mut entries: Vec<_> = db.all().collect();
+        entries.sort_by_key(|a| std::cmp::Reverse(a.use_count));
+        assert_eq!(entries[0].name, "never");
     }
 
     #[test]
-    fn test_format_time_ago_months() {
-        let time = Utc::now() - Duration::days(30);
-        assert_eq!(format_time_ago(Some(time)), "1 month ago");
-
-        let time = Utc::now() - Duration::days(90);
-        assert_eq!(format_time_ago(Some(time)), "3 months ago");
+    fn test_format_time_ago_short() {
+        let now = Utc::now();
+        assert_eq!(format_time_ago_short(now), "just now");
+        assert_eq!(format_time_ago_short(now - Duration::minutes(5)), "5m ago");
+        assert_eq!(format_time_ago_short(now - Duration::hours(2)), "2h ago");
+        assert_eq!(format_time_ago_short(now - Duration::days(3)), "3d ago");
+        assert_eq!(format_time_ago_short(now - Duration::weeks(2)), "2w ago");
+        assert_eq!(format_time_ago_short(now - Duration::days(90)), "3mo ago");
     }
 }
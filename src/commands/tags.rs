@@ -1,25 +1,121 @@
-//! Tag commands: tag, untag, list_tags
+//! Tag commands: tag, untag, list_tags, rename_tag, delete_tag, tag_all, tag_detail
+
+use std::collections::BTreeMap;
+
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+
+use crate::alias::{validate_tag, Alias, AliasError};
+use crate::collate;
+use crate::context::Ctx;
+use crate::database::{Database, TagInfo};
+use crate::exit::Failure;
+use crate::filter::AliasFilter;
+use crate::plan::PlannedChange;
+use crate::table::{create_table, format_date, format_path, is_color_name, DateStyle, PathStyle, TableStyle};
+
+/// Which bulk change a [`TagChange`] describes
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TagOperation {
+    /// Renamed to `to`; `merge` when `to` already existed
+    Rename { to: String, merge: bool },
+    /// Removed from every alias
+    Delete,
+    /// Added to the matching aliases
+    Add,
+    /// Removed from the matching aliases
+    Remove,
+}
+
+/// An alias's tags before and after a bulk tag change
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TagUpdate {
+    pub name: String,
+    pub before: Vec<String>,
+    pub after: Vec<String>,
+}
+
+/// Outcome of [`rename_tag`], [`delete_tag`] and [`tag_all`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct TagChange {
+    /// The (normalized) tag operated on
+    pub tag: String,
+    pub operation: TagOperation,
+    /// Affected aliases; empty when there was nothing to do
+    pub updates: Vec<TagUpdate>,
+    /// The updates were only previewed, not saved
+    pub dry_run: bool,
+}
+
+impl TagChange {
+    /// The updates as planned changes of the aliases' tags, for a dry run
+    pub fn planned(&self) -> Vec<PlannedChange> {
+        self.updates
+            .iter()
+            .map(|u| PlannedChange::update(&u.name, u.before.join(", "), u.after.join(", ")))
+            .collect()
+    }
 
-use crate::alias::validate_tag;
-use crate::config::Config;
-use crate::confirm;
-use crate::database::Database;
-use crate::table::{create_table, TableStyle};
+    /// One-line description, e.g. "Deleted tag 'old' from 2 aliases"
+    pub fn summary(&self) -> String {
+        let count = self.updates.len();
+        let plural = if count == 1 { "" } else { "es" };
+        let dry_run = if self.dry_run { " (dry-run):" } else { "" };
+        match &self.operation {
+            TagOperation::Rename { .. } if count == 0 => format!("No aliases with tag '{}'", self.tag),
+            TagOperation::Add | TagOperation::Remove if count == 0 => "No aliases to update".to_string(),
+            TagOperation::Rename { to, merge } => {
+                let verb = match (merge, self.dry_run) {
+                    (true, true) => "Would merge",
+                    (false, true) => "Would rename",
+                    (true, false) => "Merged",
+                    (false, false) => "Renamed",
+                };
+                let affecting = if self.dry_run { "affecting" } else { "on" };
+                format!(
+                    "{} tag '{}' to '{}' {} {} alias{}{}",
+                    verb, self.tag, to, affecting, count, plural, dry_run
+                )
+            }
+            TagOperation::Delete => {
+                let verb = if self.dry_run { "Would delete" } else { "Deleted" };
+                format!("{} tag '{}' from {} alias{}{}", verb, self.tag, count, plural, dry_run)
+            }
+            TagOperation::Add | TagOperation::Remove => {
+                let remove = self.operation == TagOperation::Remove;
+                let verb = match (remove, self.dry_run) {
+                    (true, true) => "Would remove",
+                    (false, true) => "Would add",
+                    (true, false) => "Removed",
+                    (false, false) => "Added",
+                };
+                let preposition = if remove { "from" } else { "to" };
+                format!(
+                    "{} tag '{}' {} {} alias{}{}",
+                    verb, self.tag, preposition, count, plural, dry_run
+                )
+            }
+        }
+    }
+}
 
 /// Add a tag to an alias
 ///
 /// Validates and normalizes the tag to lowercase before adding.
 /// This operation is idempotent - adding an existing tag is a no-op.
+/// Returns the normalized tag.
 ///
 /// # Arguments
+/// * `ctx` - Asked to confirm new tags (`--force` skips it)
 /// * `db` - The alias database
 /// * `alias` - The alias to tag
 /// * `tag_name` - The tag to add
-/// * `force` - If true, skip confirmation for new tags
-pub fn tag(db: &mut Database, alias: &str, tag_name: &str, force: bool) -> Result<(), Box<dyn std::error::Error>> {
+pub fn tag(ctx: &mut Ctx, db: &mut Database, alias: &str, tag_name: &str) -> Result<String, Box<dyn std::error::Error>> {
     // Normalize and validate the tag
     let tag_name = tag_name.trim().to_lowercase();
     validate_tag(&tag_name)?;
+    db.check_writable(alias)?;
 
     // Check if this is a new tag (doesn't exist on any alias)
     let existing_tags = db.get_all_tags();
@@ -29,82 +125,286 @@ pub fn tag(db: &mut Database, alias: &str, tag_name: &str, force: bool) -> Resul
     // Confirm new tag creation if:
     // - Tag doesn't exist anywhere
     // - Other tags exist (not bootstrapping)
-    // - Not using --force
-    if is_new_tag && has_any_tags && !force {
+    if is_new_tag && has_any_tags {
         let message = format!("Tag '{}' doesn't exist. Create it?", tag_name);
-        if !confirm(&message, false)? {
-            return Err("Tag creation cancelled".into());
+        if !ctx.confirm(&message, false)? {
+            return Err(Failure::cancelled("Tag creation cancelled").into());
         }
     }
 
     if let Some(entry) = db.get_mut(alias) {
         entry.add_tag(&tag_name);
         db.save()?;
-        println!("Added tag '{}' to alias '{}'", tag_name, alias);
-        Ok(())
+        Ok(tag_name)
     } else {
-        Err(format!("alias '{}' not found", alias).into())
+        Err(AliasError::NotFound(alias.to_string()).into())
     }
 }
 
 /// Remove a tag from an alias
 ///
 /// This operation is idempotent - removing a non-existent tag is a no-op.
-pub fn untag(db: &mut Database, alias: &str, tag_name: &str) -> Result<(), Box<dyn std::error::Error>> {
+/// Returns the normalized tag.
+pub fn untag(db: &mut Database, alias: &str, tag_name: &str) -> Result<String, Box<dyn std::error::Error>> {
     let tag_name = tag_name.trim().to_lowercase();
+    db.check_writable(alias)?;
 
     if let Some(entry) = db.get_mut(alias) {
         if entry.remove_tag(&tag_name) {
             db.save()?;
-            println!("Removed tag '{}' from alias '{}'", tag_name, alias);
-        } else {
-            println!("Removed tag '{}' from alias '{}'", tag_name, alias);
         }
-        Ok(())
+        Ok(tag_name)
     } else {
-        Err(format!("alias '{}' not found", alias).into())
+        Err(AliasError::NotFound(alias.to_string()).into())
     }
 }
 
-/// List all unique tags with their counts
-pub fn list_tags(db: &Database, config: &Config) -> Result<(), Box<dyn std::error::Error>> {
-    let tag_counts = db.get_all_tags();
+/// All unique tags with the number of aliases carrying them, sorted by tag
+pub fn list_tags(db: &Database) -> Vec<(String, usize)> {
+    complete_tag(db, "")
+}
+
+/// Tags starting with `prefix` (case-insensitive) and their counts, for shell completion
+pub fn complete_tag(db: &Database, prefix: &str) -> Vec<(String, usize)> {
+    let prefix = prefix.to_lowercase();
+    let mut tags: Vec<_> = db
+        .get_all_tags()
+        .into_iter()
+        .filter(|(tag, _)| tag.starts_with(&prefix))
+        .collect();
+    tags.sort_by(|a, b| collate::compare(&a.0, &b.0));
+    tags
+}
 
-    if tag_counts.is_empty() {
-        println!("No tags found");
-        return Ok(());
+/// Sort order of `--tags`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum TagSort {
+    /// Alphabetically by tag (default)
+    #[default]
+    Name,
+    /// Most aliases first
+    Count,
+    /// Most navigations to the tag's aliases first
+    Usage,
+    /// Most recently used alias first
+    Recent,
+}
+
+impl From<&str> for TagSort {
+    fn from(s: &str) -> Self {
+        match s.to_lowercase().as_str() {
+            "count" => TagSort::Count,
+            "usage" => TagSort::Usage,
+            "recent" => TagSort::Recent,
+            _ => TagSort::Name,
+        }
     }
+}
 
-    // Sort tags alphabetically
-    let mut tags: Vec<_> = tag_counts.into_iter().collect();
-    tags.sort_by(|a, b| a.0.cmp(&b.0));
+/// One row of `--tags`: a tag and the usage of the aliases carrying it
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct TagStats {
+    pub tag: String,
+    /// Number of aliases with the tag
+    pub aliases: usize,
+    /// Total use count of those aliases
+    pub use_count: u64,
+    /// Last navigation to any of them
+    pub last_used: Option<DateTime<Utc>>,
+    /// From the `[tags]` table, if set
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+}
 
-    let style = TableStyle::from(config.user.display.table_style.as_str());
-    let mut table = create_table(style);
-    table.set_header(vec!["Tag", "Aliases"]);
+/// Every tag with its alias count and aggregate usage, in `sort` order
+///
+/// Ties, and every tag when sorting by name, are ordered alphabetically.
+pub fn tag_stats(db: &Database, sort: TagSort) -> Vec<TagStats> {
+    let mut by_tag: BTreeMap<&str, TagStats> = BTreeMap::new();
+    for alias in db.all() {
+        for tag in &alias.tags {
+            let stats = by_tag.entry(tag).or_insert_with(|| TagStats {
+                tag: tag.clone(),
+                aliases: 0,
+                use_count: 0,
+                last_used: None,
+                description: db.tag_info(tag).and_then(|info| info.description.clone()),
+            });
+            stats.aliases += 1;
+            stats.use_count += alias.use_count;
+            stats.last_used = stats.last_used.max(alias.last_used);
+        }
+    }
 
-    for (tag, count) in tags {
-        let plural = if count == 1 { "alias" } else { "aliases" };
-        table.add_row(vec![tag, format!("{} {}", count, plural)]);
+    let mut tags: Vec<TagStats> = by_tag.into_values().collect();
+    tags.sort_by(|a, b| collate::compare(&a.tag, &b.tag));
+    match sort {
+        TagSort::Name => {}
+        TagSort::Count => tags.sort_by_key(|t| std::cmp::Reverse(t.aliases)),
+        TagSort::Usage => tags.sort_by_key(|t| std::cmp::Reverse(t.use_count)),
+        TagSort::Recent => tags.sort_by_key(|t| std::cmp::Reverse(t.last_used)),
+    }
+    tags
+}
+
+/// Render tag stats as the `--tags` table
+///
+/// The usage columns are left out when usage isn't tracked, the description
+/// column when no tag has one.
+pub fn format_tags(tags: &[TagStats], style: TableStyle, dates: DateStyle, tracking: bool) -> String {
+    if tags.is_empty() {
+        return "No tags found".to_string();
     }
 
-    println!("{}", table);
-    Ok(())
+    let described = tags.iter().any(|t| t.description.is_some());
+    let mut header = vec!["Tag", "Aliases"];
+    if tracking {
+        header.extend(["Uses", "Last Used"]);
+    }
+    if described {
+        header.push("Description");
+    }
+    let mut table = create_table(style);
+    table.set_header(header);
+
+    for stats in tags {
+        let plural = if stats.aliases == 1 { "alias" } else { "aliases" };
+        let mut row = vec![stats.tag.clone(), format!("{} {}", stats.aliases, plural)];
+        if tracking {
+            row.push(stats.use_count.to_string());
+            row.push(format_date(stats.last_used, dates));
+        }
+        if described {
+            row.push(stats.description.clone().unwrap_or_default());
+        }
+        table.add_row(row);
+    }
+
+    table.to_string()
 }
 
-/// List tag names only (for shell completion)
-pub fn list_tags_raw(db: &Database) -> Result<(), Box<dyn std::error::Error>> {
-    let tag_counts = db.get_all_tags();
+/// What `--tag-info` shows: a tag's description and color, and its aliases
+#[derive(Debug, Clone)]
+pub struct TagDetail {
+    pub tag: String,
+    pub info: TagInfo,
+    /// Aliases with the tag, most used first
+    pub aliases: Vec<Alias>,
+}
 
-    // Sort tags alphabetically
-    let mut tags: Vec<_> = tag_counts.keys().cloned().collect();
-    tags.sort();
+/// A tag's metadata and the aliases carrying it, most used first
+///
+/// Ties go to the most recently used, then by name. A tag no alias carries
+/// is still found while it has a description or color.
+pub fn tag_detail(db: &Database, tag: &str) -> Result<TagDetail, Box<dyn std::error::Error>> {
+    let tag = tag.trim().to_lowercase();
+    let mut aliases: Vec<Alias> = db.all().filter(|a| a.has_tag(&tag)).cloned().collect();
+    let info = db.tag_info(&tag).cloned();
+    if aliases.is_empty() && info.is_none() {
+        return Err(Failure::not_found(format!("tag '{}' not found", tag)).into());
+    }
+    aliases.sort_by(|a, b| {
+        b.use_count
+            .cmp(&a.use_count)
+            .then_with(|| b.last_used.cmp(&a.last_used))
+            .then_with(|| collate::compare(&a.name, &b.name))
+    });
+    Ok(TagDetail {
+        tag,
+        info: info.unwrap_or_default(),
+        aliases,
+    })
+}
 
-    for tag in tags {
-        println!("{}", tag);
+/// Set a tag's description and color in the `[tags]` table
+///
+/// `None` leaves a field as it is; an empty string clears it. Returns the
+/// tag's metadata afterwards.
+pub fn describe_tag(
+    db: &mut Database,
+    tag: &str,
+    description: Option<&str>,
+    color: Option<&str>,
+) -> Result<TagInfo, Box<dyn std::error::Error>> {
+    let tag = tag.trim().to_lowercase();
+    validate_tag(&tag)?;
+    if db.tag_info(&tag).is_none() && !db.all().any(|a| a.has_tag(&tag)) {
+        return Err(Failure::not_found(format!("tag '{}' not found", tag)).into());
+    }
+    let color = color.map(str::trim);
+    if let Some(color) = color.filter(|c| !c.is_empty() && !is_color_name(c)) {
+        return Err(format!("unknown color '{}' (e.g. red, dark_blue, grey)", color).into());
     }
 
-    Ok(())
+    let mut info = db.tag_info(&tag).cloned().unwrap_or_default();
+    let value = |v: &str| Some(v.trim().to_string()).filter(|v| !v.is_empty());
+    if let Some(description) = description {
+        info.description = value(description);
+    }
+    if let Some(color) = color {
+        info.color = value(color);
+    }
+    db.set_tag_info(&tag, info.clone());
+    db.save()?;
+    Ok(info)
+}
+
+/// Render a [`TagDetail`]: the description and color, then the aliases
+///
+/// The usage columns are left out when usage isn't tracked.
+pub fn format_tag_detail(
+    detail: &TagDetail,
+    style: TableStyle,
+    paths: PathStyle,
+    dates: DateStyle,
+    tracking: bool,
+) -> String {
+    let mut out = format!("Tag: {}\n", detail.tag);
+    if let Some(description) = &detail.info.description {
+        out.push_str(&format!("Description: {}\n", description));
+    }
+    if let Some(color) = &detail.info.color {
+        out.push_str(&format!("Color: {}\n", color));
+    }
+    if detail.aliases.is_empty() {
+        out.push_str("No aliases have this tag");
+        return out;
+    }
+
+    let mut table = create_table(style);
+    if tracking {
+        table.set_header(vec!["Name", "Path", "Uses", "Last Used"]);
+    } else {
+        table.set_header(vec!["Name", "Path"]);
+    }
+    for alias in &detail.aliases {
+        let mut row = vec![alias.name.clone(), format_path(&alias.path, paths)];
+        if tracking {
+            row.push(alias.use_count.to_string());
+            row.push(format_date(alias.last_used, dates));
+        }
+        table.add_row(row);
+    }
+    out.push('\n');
+    out.push_str(&table.to_string());
+    out
+}
+
+/// Record the updates for `affected`, computing each alias's tags afterwards
+fn tag_updates(
+    db: &Database,
+    affected: &[String],
+    after: impl Fn(&[String]) -> Vec<String>,
+) -> Vec<TagUpdate> {
+    affected
+        .iter()
+        .filter_map(|name| db.get(name))
+        .map(|alias| TagUpdate {
+            name: alias.name.clone(),
+            before: alias.tags.clone(),
+            after: after(&alias.tags),
+        })
+        .collect()
 }
 
 /// Rename or merge a tag across all aliases
@@ -114,19 +414,16 @@ pub fn list_tags_raw(db: &Database) -> Result<(), Box<dyn std::error::Error>> {
 ///
 /// # Arguments
 /// * `db` - The alias database
-/// * `config` - Config for table styling
 /// * `old_tag` - The tag to rename/remove
 /// * `new_tag` - The target tag name
 /// * `dry_run` - If true, only preview changes without modifying
-/// * `force` - If true, skip confirmation prompt
 pub fn rename_tag(
+    ctx: &mut Ctx,
     db: &mut Database,
-    config: &Config,
     old_tag: &str,
     new_tag: &str,
     dry_run: bool,
-    force: bool,
-) -> Result<(), Box<dyn std::error::Error>> {
+) -> Result<TagChange, Box<dyn std::error::Error>> {
     // Normalize both tags
     let old_tag = old_tag.trim().to_lowercase();
     let new_tag = new_tag.trim().to_lowercase();
@@ -137,78 +434,58 @@ pub fn rename_tag(
     // Check if old_tag exists
     let all_tags = db.get_all_tags();
     if !all_tags.contains_key(&old_tag) {
-        return Err(format!("tag '{}' not found", old_tag).into());
+        return Err(Failure::not_found(format!("tag '{}' not found", old_tag)).into());
     }
 
-    // Find affected aliases
+    // Find affected aliases (shared ones are read-only)
     let affected: Vec<String> = db
         .all()
-        .filter(|a| a.has_tag(&old_tag))
+        .filter(|a| a.has_tag(&old_tag) && !db.is_shared(&a.name))
         .map(|a| a.name.clone())
         .collect();
 
-    if affected.is_empty() {
-        println!("No aliases with tag '{}'", old_tag);
-        return Ok(());
-    }
-
     // Determine if this is a merge (new_tag already exists)
     let is_merge = all_tags.contains_key(&new_tag);
-    let operation = if is_merge { "merge" } else { "rename" };
+    let updates = tag_updates(db, &affected, |tags| {
+        // Remove old, add new if not present
+        let mut after: Vec<String> = tags.iter().filter(|t| **t != old_tag).cloned().collect();
+        if !after.contains(&new_tag) {
+            after.push(new_tag.clone());
+        }
+        after.sort();
+        after
+    });
+    let change = TagChange {
+        tag: old_tag.clone(),
+        operation: TagOperation::Rename {
+            to: new_tag.clone(),
+            merge: is_merge,
+        },
+        updates,
+        dry_run,
+    };
 
-    // Confirmation prompt (unless force or dry_run)
-    if !force && !dry_run {
+    if affected.is_empty() {
+        return Ok(change);
+    }
+
+    // Confirmation prompt (unless dry_run)
+    if !dry_run {
         let message = format!(
             "Will {} tag '{}' to '{}' affecting {} alias{}",
-            operation,
+            if is_merge { "merge" } else { "rename" },
             old_tag,
             new_tag,
             affected.len(),
             if affected.len() == 1 { "" } else { "es" }
         );
-        if !confirm(&message, false)? {
-            return Err("Tag rename cancelled".into());
+        if !ctx.confirm(&message, false)? {
+            return Err(Failure::cancelled("Tag rename cancelled").into());
         }
     }
 
-    // Dry run: display preview table
     if dry_run {
-        println!(
-            "Would {} tag '{}' to '{}' affecting {} alias{} (dry-run):",
-            operation,
-            old_tag,
-            new_tag,
-            affected.len(),
-            if affected.len() == 1 { "" } else { "es" }
-        );
-
-        let style = TableStyle::from(config.user.display.table_style.as_str());
-        let mut table = create_table(style);
-        table.set_header(vec!["Name", "Current Tags", "After"]);
-
-        for name in &affected {
-            if let Some(alias) = db.get(name) {
-                let current_tags = alias.tags.join(", ");
-
-                // Compute "after" tags: remove old, add new if not present
-                let mut after_tags: Vec<String> = alias
-                    .tags
-                    .iter()
-                    .filter(|t| *t != &old_tag)
-                    .cloned()
-                    .collect();
-                if !after_tags.contains(&new_tag) {
-                    after_tags.push(new_tag.clone());
-                }
-                after_tags.sort();
-                let after = after_tags.join(", ");
-
-                table.add_row(vec![name.clone(), current_tags, after]);
-            }
-        }
-
-        println!("{}", table);
-        return Ok(());
+        return Ok(change);
     }
 
     // Apply changes atomically
@@ -220,27 +497,144 @@ pub fn rename_tag(
             }
         }
     }
+    db.rename_tag_info(&old_tag, &new_tag);
 
     // Single save at end
     db.save()?;
+    Ok(change)
+}
+
+/// Delete a tag from all aliases
+///
+/// Only previews the affected aliases with `dry_run` and asks `ctx` for
+/// confirmation otherwise.
+pub fn delete_tag(
+    ctx: &mut Ctx,
+    db: &mut Database,
+    tag_name: &str,
+    dry_run: bool,
+) -> Result<TagChange, Box<dyn std::error::Error>> {
+    let tag_name = tag_name.trim().to_lowercase();
+
+    let mut affected: Vec<String> = db
+        .all()
+        .filter(|a| a.has_tag(&tag_name) && !db.is_shared(&a.name))
+        .map(|a| a.name.clone())
+        .collect();
+    if affected.is_empty() {
+        return Err(Failure::not_found(format!("tag '{}' not found", tag_name)).into());
+    }
+    affected.sort();
+
+    // Confirmation prompt (unless dry_run)
+    if !dry_run {
+        let message = format!(
+            "Will delete tag '{}' from {} alias{}",
+            tag_name,
+            affected.len(),
+            if affected.len() == 1 { "" } else { "es" }
+        );
+        if !ctx.confirm(&message, false)? {
+            return Err(Failure::cancelled("Tag deletion cancelled").into());
+        }
+    }
+
+    let updates = tag_updates(db, &affected, |tags| {
+        tags.iter().filter(|t| **t != tag_name).cloned().collect()
+    });
+    let change = TagChange {
+        tag: tag_name.clone(),
+        operation: TagOperation::Delete,
+        updates,
+        dry_run,
+    };
+    if dry_run {
+        return Ok(change);
+    }
+
+    for name in &affected {
+        if let Some(alias) = db.get_mut(name) {
+            alias.remove_tag(&tag_name);
+        }
+    }
+    db.set_tag_info(&tag_name, TagInfo::default());
+
+    db.save()?;
+    Ok(change)
+}
+
+/// Add or remove a tag on every alias matching a filter
+///
+/// The filter is a tag expression (`tag:a,b`) or a glob over alias names.
+/// Aliases that already have (or, with `remove`, lack) the tag are skipped.
+/// Asks `ctx` for confirmation; `dry_run` only previews.
+pub fn tag_all(
+    ctx: &mut Ctx,
+    db: &mut Database,
+    tag_name: &str,
+    filter: &str,
+    remove: bool,
+    dry_run: bool,
+) -> Result<TagChange, Box<dyn std::error::Error>> {
+    let tag_name = tag_name.trim().to_lowercase();
+    validate_tag(&tag_name)?;
+    let filter = AliasFilter::parse(filter)?;
+
+    let mut affected: Vec<String> = db
+        .all()
+        .filter(|a| filter.matches(a) && a.has_tag(&tag_name) == remove && !db.is_shared(&a.name))
+        .map(|a| a.name.clone())
+        .collect();
+    affected.sort();
+
+    let updates = tag_updates(db, &affected, |tags| {
+        let mut after: Vec<String> = tags.iter().filter(|t| **t != tag_name).cloned().collect();
+        if !remove {
+            after.push(tag_name.clone());
+        }
+        after.sort();
+        after
+    });
+    let change = TagChange {
+        tag: tag_name.clone(),
+        operation: if remove { TagOperation::Remove } else { TagOperation::Add },
+        updates,
+        dry_run,
+    };
+    if affected.is_empty() || dry_run {
+        return Ok(change);
+    }
 
-    println!(
-        "{}d tag '{}' to '{}' on {} alias{}",
-        if is_merge { "Merge" } else { "Rename" },
-        old_tag,
-        new_tag,
+    let message = format!(
+        "Will {} tag '{}' {} {} alias{}",
+        if remove { "remove" } else { "add" },
+        tag_name,
+        if remove { "from" } else { "to" },
         affected.len(),
         if affected.len() == 1 { "" } else { "es" }
     );
+    if !ctx.confirm(&message, false)? {
+        return Err(Failure::cancelled("Bulk tag cancelled").into());
+    }
 
-    Ok(())
+    for name in &affected {
+        if let Some(alias) = db.get_mut(name) {
+            if remove {
+                alias.remove_tag(&tag_name);
+            } else {
+                alias.add_tag(&tag_name);
+            }
+        }
+    }
+
+    db.save()?;
+    Ok(change)
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::alias::Alias;
-    use crate::config::Config;
     use tempfile::NamedTempFile;
 
     fn create_test_db() -> (Database, NamedTempFile) {
@@ -259,12 +653,19 @@ mod tests {
         (db, file)
     }
 
+    /// A non-interactive context, as with `--force` when `force` is set
+    fn ctx(force: bool) -> Ctx<'static> {
+        let mut ctx = Ctx::silent();
+        ctx.force = force;
+        ctx
+    }
+
     #[test]
     fn test_tag() {
         let (mut db, _file) = create_test_db();
 
         // First tag created without confirmation (bootstrapping)
-        let result = tag(&mut db, "test", "work", false);
+        let result = tag(&mut ctx(false), &mut db, "test", "work");
         assert!(result.is_ok());
 
         let alias = db.get("test").unwrap();
@@ -276,7 +677,7 @@ mod tests {
         let (mut db, _file) = create_test_db();
 
         // First tag - no confirmation needed
-        let result = tag(&mut db, "test", "WORK", false);
+        let result = tag(&mut ctx(false), &mut db, "test", "WORK");
         assert!(result.is_ok());
 
         let alias = db.get("test").unwrap();
@@ -289,7 +690,7 @@ mod tests {
         let (mut db, _file) = create_test_db();
 
         // First tag - no confirmation needed
-        let result = tag(&mut db, "test", "  work  ", false);
+        let result = tag(&mut ctx(false), &mut db, "test", "  work  ");
         assert!(result.is_ok());
 
         let alias = db.get("test").unwrap();
@@ -301,15 +702,15 @@ mod tests {
         let (mut db, _file) = create_test_db();
 
         // Empty tag should fail
-        let result = tag(&mut db, "test", "", true);
+        let result = tag(&mut ctx(true), &mut db, "test", "");
         assert!(result.is_err());
 
         // Invalid characters should fail
-        let result = tag(&mut db, "test", "work@home", true);
+        let result = tag(&mut ctx(true), &mut db, "test", "work@home");
         assert!(result.is_err());
 
         // Starting with hyphen should fail
-        let result = tag(&mut db, "test", "-work", true);
+        let result = tag(&mut ctx(true), &mut db, "test", "-work");
         assert!(result.is_err());
     }
 
@@ -318,8 +719,8 @@ mod tests {
         let (mut db, _file) = create_test_db();
 
         // Add tag twice - first one succeeds (bootstrapping), second is idempotent (tag exists)
-        tag(&mut db, "test", "work", false).unwrap();
-        let result = tag(&mut db, "test", "work", false);
+        tag(&mut ctx(false), &mut db, "test", "work").unwrap();
+        let result = tag(&mut ctx(false), &mut db, "test", "work");
         assert!(result.is_ok());
 
         // Tag should still only appear once
@@ -331,14 +732,14 @@ mod tests {
     fn test_tag_not_found() {
         let (mut db, _file) = create_test_db();
         // First tag - no confirmation needed, but alias doesn't exist
-        let result = tag(&mut db, "nonexistent", "work", false);
+        let result = tag(&mut ctx(false), &mut db, "nonexistent", "work");
         assert!(result.is_err());
     }
 
     #[test]
     fn test_untag() {
         let (mut db, _file) = create_test_db();
-        tag(&mut db, "test", "work", true).unwrap();
+        tag(&mut ctx(true), &mut db, "test", "work").unwrap();
 
         let result = untag(&mut db, "test", "work");
         assert!(result.is_ok());
@@ -350,7 +751,7 @@ mod tests {
     #[test]
     fn test_untag_normalizes_to_lowercase() {
         let (mut db, _file) = create_test_db();
-        tag(&mut db, "test", "work", true).unwrap();
+        tag(&mut ctx(true), &mut db, "test", "work").unwrap();
 
         // Should remove "work" even when passed as "WORK"
         let result = untag(&mut db, "test", "WORK");
@@ -379,21 +780,52 @@ mod tests {
     #[test]
     fn test_list_tags() {
         let (mut db, _file) = create_test_db();
-        let config = Config::load().unwrap();
         // Use force=true for second tag (first tag exists)
-        tag(&mut db, "test", "work", true).unwrap();
-        tag(&mut db, "test", "important", true).unwrap();
-
-        let result = list_tags(&db, &config);
-        assert!(result.is_ok());
+        tag(&mut ctx(true), &mut db, "test", "work").unwrap();
+        tag(&mut ctx(true), &mut db, "test", "important").unwrap();
+
+        let tags = list_tags(&db);
+        assert_eq!(tags, vec![("important".to_string(), 1), ("work".to_string(), 1)]);
+        let out = format_tags(&tag_stats(&db, TagSort::Name), TableStyle::Minimal, DateStyle::Relative, true);
+        assert!(out.contains("important"));
+        assert!(out.contains("1 alias"));
+        assert!(out.contains("never"));
     }
 
     #[test]
     fn test_list_tags_empty() {
         let (db, _file) = create_test_db();
-        let config = Config::load().unwrap();
-        let result = list_tags(&db, &config);
-        assert!(result.is_ok());
+        assert!(list_tags(&db).is_empty());
+        assert_eq!(format_tags(&[], TableStyle::Minimal, DateStyle::Relative, true), "No tags found");
+    }
+
+    #[test]
+    fn test_tag_stats_aggregate_and_sort() {
+        let (mut db, _file) = create_test_db_with_multiple_aliases();
+        for alias in ["proj1", "proj2"] {
+            tag(&mut ctx(true), &mut db, alias, "work").unwrap();
+        }
+        tag(&mut ctx(true), &mut db, "docs", "docs").unwrap();
+        db.get_mut("proj1").unwrap().use_count = 2;
+        db.get_mut("proj2").unwrap().use_count = 3;
+        db.get_mut("docs").unwrap().use_count = 10;
+        let now = Utc::now();
+        db.get_mut("proj2").unwrap().last_used = Some(now);
+
+        let stats = tag_stats(&db, TagSort::Name);
+        assert_eq!(stats[1].tag, "work");
+        assert_eq!((stats[1].aliases, stats[1].use_count, stats[1].last_used), (2, 5, Some(now)));
+
+        let order = |sort| tag_stats(&db, sort).into_iter().map(|t| t.tag).collect::<Vec<_>>();
+        assert_eq!(order(TagSort::Name), vec!["docs", "work"]);
+        assert_eq!(order(TagSort::Count), vec!["work", "docs"]);
+        assert_eq!(order(TagSort::Usage), vec!["docs", "work"]);
+        assert_eq!(order(TagSort::Recent), vec!["work", "docs"]);
+        assert_eq!(TagSort::from("COUNT"), TagSort::Count);
+        assert_eq!(TagSort::from("bogus"), TagSort::Name);
+
+        let out = format_tags(&stats, TableStyle::Minimal, DateStyle::Relative, false);
+        assert!(!out.contains("Uses"));
     }
 
     #[test]
@@ -401,11 +833,11 @@ mod tests {
         let (mut db, _file) = create_test_db_with_multiple_aliases();
 
         // Add "work" tag to two aliases (use force=true for subsequent new tags)
-        tag(&mut db, "proj1", "work", true).unwrap();
-        tag(&mut db, "proj2", "work", true).unwrap();
+        tag(&mut ctx(true), &mut db, "proj1", "work").unwrap();
+        tag(&mut ctx(true), &mut db, "proj2", "work").unwrap();
 
         // Add "docs" tag to one alias
-        tag(&mut db, "docs", "docs", true).unwrap();
+        tag(&mut ctx(true), &mut db, "docs", "docs").unwrap();
 
         let tag_counts = db.get_all_tags();
         assert_eq!(tag_counts.get("work"), Some(&2));
@@ -413,20 +845,19 @@ mod tests {
     }
 
     #[test]
-    fn test_list_tags_raw() {
-        let (mut db, _file) = create_test_db();
-        tag(&mut db, "test", "work", true).unwrap();
-        tag(&mut db, "test", "important", true).unwrap();
-
-        let result = list_tags_raw(&db);
-        assert!(result.is_ok());
-    }
-
-    #[test]
-    fn test_list_tags_raw_empty() {
-        let (db, _file) = create_test_db();
-        let result = list_tags_raw(&db);
-        assert!(result.is_ok());
+    fn test_complete_tag_with_prefix() {
+        let (mut db, _file) = create_test_db_with_multiple_aliases();
+        for alias in ["proj1", "proj2", "docs"] {
+            tag(&mut ctx(true), &mut db, alias, "work").unwrap();
+        }
+        tag(&mut ctx(true), &mut db, "proj1", "wip").unwrap();
+        tag(&mut ctx(true), &mut db, "docs", "reference").unwrap();
+
+        let names = |tags: Vec<(String, usize)>| tags.into_iter().map(|(t, _)| t).collect::<Vec<_>>();
+        assert_eq!(names(complete_tag(&db, "")), vec!["reference", "wip", "work"]);
+        assert_eq!(names(complete_tag(&db, "W")), vec!["wip", "work"]);
+        assert_eq!(complete_tag(&db, "wo"), vec![("work".to_string(), 3)]);
+        assert!(complete_tag(&db, "x").is_empty());
     }
 
     // Tests for confirmation behavior (TAG-01 through TAG-04)
@@ -437,7 +868,7 @@ mod tests {
         let (mut db, _file) = create_test_db();
 
         // No tags exist, so first tag should succeed without confirmation
-        let result = tag(&mut db, "test", "work", false);
+        let result = tag(&mut ctx(false), &mut db, "test", "work");
         assert!(result.is_ok());
 
         let alias = db.get("test").unwrap();
@@ -446,15 +877,15 @@ mod tests {
 
     #[test]
     fn test_tag_new_tag_denied_in_non_interactive() {
-        // TAG-03: Non-interactive mode (piped stdin) denies new tag creation
+        // TAG-03: Non-interactive mode (no terminal or --no-input) denies new tag creation
         let (mut db, _file) = create_test_db();
 
         // Create first tag (bootstrapping - succeeds)
-        tag(&mut db, "test", "existing", true).unwrap();
+        tag(&mut ctx(true), &mut db, "test", "existing").unwrap();
 
         // Try to create new tag without force - should be denied in non-interactive
-        // (tests run with piped stdin, so confirm() returns default=false)
-        let result = tag(&mut db, "test", "newtag", false);
+        // (the context is not interactive, so confirm() returns default=false)
+        let result = tag(&mut ctx(false), &mut db, "test", "newtag");
         assert!(result.is_err());
         assert!(result.unwrap_err().to_string().contains("cancelled"));
     }
@@ -465,10 +896,10 @@ mod tests {
         let (mut db, _file) = create_test_db();
 
         // Create first tag
-        tag(&mut db, "test", "existing", true).unwrap();
+        tag(&mut ctx(true), &mut db, "test", "existing").unwrap();
 
         // With force=true, new tag creation should succeed
-        let result = tag(&mut db, "test", "newtag", true);
+        let result = tag(&mut ctx(true), &mut db, "test", "newtag");
         assert!(result.is_ok());
 
         let alias = db.get("test").unwrap();
@@ -481,10 +912,10 @@ mod tests {
         let (mut db, _file) = create_test_db_with_multiple_aliases();
 
         // Create tag on proj1
-        tag(&mut db, "proj1", "work", true).unwrap();
+        tag(&mut ctx(true), &mut db, "proj1", "work").unwrap();
 
         // Add same tag to proj2 - should succeed without confirmation (tag exists)
-        let result = tag(&mut db, "proj2", "work", false);
+        let result = tag(&mut ctx(false), &mut db, "proj2", "work");
         assert!(result.is_ok());
 
         let alias = db.get("proj2").unwrap();
@@ -496,14 +927,13 @@ mod tests {
     #[test]
     fn test_rename_tag_basic() {
         let (mut db, _file) = create_test_db_with_multiple_aliases();
-        let config = Config::load().unwrap();
 
         // Add "work" tag to proj1 and proj2
-        tag(&mut db, "proj1", "work", true).unwrap();
-        tag(&mut db, "proj2", "work", true).unwrap();
+        tag(&mut ctx(true), &mut db, "proj1", "work").unwrap();
+        tag(&mut ctx(true), &mut db, "proj2", "work").unwrap();
 
         // Rename "work" to "job" with force (target doesn't exist)
-        let result = rename_tag(&mut db, &config, "work", "job", false, true);
+        let result = rename_tag(&mut ctx(true), &mut db, "work", "job", false);
         assert!(result.is_ok());
 
         // Verify: "work" tag gone, "job" tag exists
@@ -516,18 +946,17 @@ mod tests {
     #[test]
     fn test_rename_tag_merge() {
         let (mut db, _file) = create_test_db_with_multiple_aliases();
-        let config = Config::load().unwrap();
 
         // Add "work" to proj1, "job" to proj2, and "job" to docs
-        tag(&mut db, "proj1", "work", true).unwrap();
-        tag(&mut db, "proj2", "job", true).unwrap();
-        tag(&mut db, "docs", "job", true).unwrap();
+        tag(&mut ctx(true), &mut db, "proj1", "work").unwrap();
+        tag(&mut ctx(true), &mut db, "proj2", "job").unwrap();
+        tag(&mut ctx(true), &mut db, "docs", "job").unwrap();
 
         // Also add "job" to proj1 (so we can verify no duplicate)
-        tag(&mut db, "proj1", "job", true).unwrap();
+        tag(&mut ctx(true), &mut db, "proj1", "job").unwrap();
 
         // Rename/merge "work" into "job"
-        let result = rename_tag(&mut db, &config, "work", "job", false, true);
+        let result = rename_tag(&mut ctx(true), &mut db, "work", "job", false);
         assert!(result.is_ok());
 
         // Verify: "work" tag gone
@@ -542,9 +971,8 @@ mod tests {
     #[test]
     fn test_rename_tag_source_not_found() {
         let (mut db, _file) = create_test_db();
-        let config = Config::load().unwrap();
 
-        let result = rename_tag(&mut db, &config, "nonexistent", "newtag", false, true);
+        let result = rename_tag(&mut ctx(true), &mut db, "nonexistent", "newtag", false);
         assert!(result.is_err());
         assert!(result.unwrap_err().to_string().contains("not found"));
     }
@@ -552,13 +980,12 @@ mod tests {
     #[test]
     fn test_rename_tag_normalizes_case() {
         let (mut db, _file) = create_test_db();
-        let config = Config::load().unwrap();
 
         // Add "work" tag
-        tag(&mut db, "test", "work", true).unwrap();
+        tag(&mut ctx(true), &mut db, "test", "work").unwrap();
 
         // Rename "WORK" to "JOB" - should normalize to lowercase
-        let result = rename_tag(&mut db, &config, "WORK", "JOB", false, true);
+        let result = rename_tag(&mut ctx(true), &mut db, "WORK", "JOB", false);
         assert!(result.is_ok());
 
         // Verify lowercase "job" exists
@@ -569,36 +996,176 @@ mod tests {
     #[test]
     fn test_rename_tag_force_bypasses_confirm() {
         let (mut db, _file) = create_test_db();
-        let config = Config::load().unwrap();
 
         // Add tag
-        tag(&mut db, "test", "work", true).unwrap();
+        tag(&mut ctx(true), &mut db, "test", "work").unwrap();
 
         // Without force, should fail in non-interactive (confirm returns false)
-        let result = rename_tag(&mut db, &config, "work", "job", false, false);
+        let result = rename_tag(&mut ctx(false), &mut db, "work", "job", false);
         assert!(result.is_err());
         assert!(result.unwrap_err().to_string().contains("cancelled"));
 
         // With force, should succeed
-        let result = rename_tag(&mut db, &config, "work", "job", false, true);
+        let result = rename_tag(&mut ctx(true), &mut db, "work", "job", false);
         assert!(result.is_ok());
     }
 
     #[test]
     fn test_rename_tag_dry_run_no_changes() {
         let (mut db, _file) = create_test_db();
-        let config = Config::load().unwrap();
 
         // Add tag
-        tag(&mut db, "test", "work", true).unwrap();
+        tag(&mut ctx(true), &mut db, "test", "work").unwrap();
 
         // Dry run should not make changes
-        let result = rename_tag(&mut db, &config, "work", "job", true, false);
-        assert!(result.is_ok());
+        let change = rename_tag(&mut ctx(false), &mut db, "work", "job", true).unwrap();
+        assert_eq!(change.summary(), "Would rename tag 'work' to 'job' affecting 1 alias (dry-run):");
+        assert_eq!(change.updates[0].before, vec!["work"]);
+        assert_eq!(change.updates[0].after, vec!["job"]);
 
         // Verify original tag still exists
         assert!(db.get_all_tags().contains_key("work"));
         assert!(!db.get_all_tags().contains_key("job"));
         assert!(db.get("test").unwrap().has_tag("work"));
     }
+
+    // Tests for tag_all function
+
+    #[test]
+    fn test_tag_all_by_glob() {
+        let (mut db, _file) = create_test_db_with_multiple_aliases();
+
+        tag_all(&mut ctx(true), &mut db, "Work", "proj*", false, false).unwrap();
+
+        assert!(db.get("proj1").unwrap().has_tag("work"));
+        assert!(db.get("proj2").unwrap().has_tag("work"));
+        assert!(!db.get("docs").unwrap().has_tag("work"));
+    }
+
+    #[test]
+    fn test_tag_all_by_tag_expression() {
+        let (mut db, _file) = create_test_db_with_multiple_aliases();
+        tag(&mut ctx(true), &mut db, "proj1", "rust").unwrap();
+        tag(&mut ctx(true), &mut db, "docs", "rust").unwrap();
+
+        tag_all(&mut ctx(true), &mut db, "code", "tag:rust", false, false).unwrap();
+
+        assert!(db.get("proj1").unwrap().has_tag("code"));
+        assert!(db.get("docs").unwrap().has_tag("code"));
+        assert!(!db.get("proj2").unwrap().has_tag("code"));
+    }
+
+    #[test]
+    fn test_tag_all_remove() {
+        let (mut db, _file) = create_test_db_with_multiple_aliases();
+        tag(&mut ctx(true), &mut db, "proj1", "old").unwrap();
+        tag(&mut ctx(true), &mut db, "docs", "old").unwrap();
+
+        tag_all(&mut ctx(true), &mut db, "old", "*", true, false).unwrap();
+
+        assert!(!db.get_all_tags().contains_key("old"));
+    }
+
+    #[test]
+    fn test_tag_all_dry_run_makes_no_changes() {
+        let (mut db, _file) = create_test_db_with_multiple_aliases();
+
+        let change = tag_all(&mut ctx(false), &mut db, "work", "*", false, true).unwrap();
+        assert_eq!(change.summary(), "Would add tag 'work' to 3 aliases (dry-run):");
+        assert_eq!(change.planned()[0].after, "work");
+
+        assert!(!db.get_all_tags().contains_key("work"));
+    }
+
+    #[test]
+    fn test_tag_all_invalid_input() {
+        let (mut db, _file) = create_test_db_with_multiple_aliases();
+
+        assert!(tag_all(&mut ctx(true), &mut db, "bad@tag", "*", false, false).is_err());
+        let err = tag_all(&mut ctx(true), &mut db, "work", "tag:", false, false).unwrap_err();
+        assert!(err.to_string().contains("invalid filter"));
+    }
+
+    // Tests for delete_tag function
+
+    #[test]
+    fn test_delete_tag() {
+        let (mut db, _file) = create_test_db_with_multiple_aliases();
+        tag(&mut ctx(true), &mut db, "proj1", "old").unwrap();
+        tag(&mut ctx(true), &mut db, "proj1", "keep").unwrap();
+        tag(&mut ctx(true), &mut db, "docs", "old").unwrap();
+
+        let change = delete_tag(&mut ctx(true), &mut db, "OLD", false).unwrap();
+        assert_eq!(change.summary(), "Deleted tag 'old' from 2 aliases");
+
+        assert!(!db.get_all_tags().contains_key("old"));
+        assert!(db.get("proj1").unwrap().has_tag("keep"));
+    }
+
+    #[test]
+    fn test_delete_tag_dry_run() {
+        let (mut db, _file) = create_test_db_with_multiple_aliases();
+        tag(&mut ctx(true), &mut db, "proj1", "old").unwrap();
+
+        delete_tag(&mut ctx(false), &mut db, "old", true).unwrap();
+
+        assert!(db.get("proj1").unwrap().has_tag("old"));
+    }
+
+    #[test]
+    fn test_delete_tag_not_found() {
+        let (mut db, _file) = create_test_db_with_multiple_aliases();
+
+        let err = delete_tag(&mut ctx(true), &mut db, "missing", false).unwrap_err();
+        assert!(err.to_string().contains("not found"));
+    }
+
+    #[test]
+    fn test_tag_detail_sorts_by_usage() {
+        let (mut db, file) = create_test_db_with_multiple_aliases();
+        for alias in ["proj1", "proj2", "docs"] {
+            tag(&mut ctx(true), &mut db, alias, "work").unwrap();
+        }
+        db.get_mut("proj2").unwrap().use_count = 5;
+        db.get_mut("docs").unwrap().use_count = 1;
+
+        let info = describe_tag(&mut db, "Work", Some("Day job"), Some("dark_blue")).unwrap();
+        assert_eq!(info.color.as_deref(), Some("dark_blue"));
+        let detail = tag_detail(&db, "work").unwrap();
+        let names: Vec<&str> = detail.aliases.iter().map(|a| a.name.as_str()).collect();
+        assert_eq!(names, vec!["proj2", "docs", "proj1"]);
+        let out = format_tag_detail(&detail, TableStyle::Minimal, PathStyle::Full, DateStyle::Relative, true);
+        assert!(out.starts_with("Tag: work\nDescription: Day job\nColor: dark_blue\n"));
+        assert!(out.contains("/tmp/proj2"));
+
+        // Saved in the database file, and shown by --tags
+        let reloaded = Database::load_from_path(file.path()).unwrap();
+        assert_eq!(reloaded.tag_info("work").unwrap().description.as_deref(), Some("Day job"));
+        let stats = tag_stats(&reloaded, TagSort::Name);
+        assert!(format_tags(&stats, TableStyle::Minimal, DateStyle::Relative, false).contains("Day job"));
+
+        // Empty values clear
+        describe_tag(&mut db, "work", None, Some("")).unwrap();
+        assert_eq!(db.tag_info("work").unwrap().color, None);
+        assert!(describe_tag(&mut db, "work", None, Some("mauve")).is_err());
+        assert!(describe_tag(&mut db, "missing", Some("x"), None).is_err());
+        assert!(tag_detail(&db, "missing").is_err());
+    }
+
+    #[test]
+    fn test_tag_info_follows_rename_and_delete() {
+        let (mut db, _file) = create_test_db_with_multiple_aliases();
+        tag(&mut ctx(true), &mut db, "proj1", "old").unwrap();
+        tag(&mut ctx(true), &mut db, "docs", "new").unwrap();
+        describe_tag(&mut db, "old", Some("Old one"), Some("red")).unwrap();
+        describe_tag(&mut db, "new", Some("New one"), None).unwrap();
+
+        rename_tag(&mut ctx(true), &mut db, "old", "new", false).unwrap();
+        assert!(db.tag_info("old").is_none());
+        let info = db.tag_info("new").unwrap();
+        assert_eq!((info.description.as_deref(), info.color.as_deref()), (Some("New one"), Some("red")));
+
+        delete_tag(&mut ctx(true), &mut db, "new", false).unwrap();
+        assert!(db.tag_info("new").is_none());
+    }
 }
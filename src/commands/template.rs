@@ -0,0 +1,225 @@
+//! Create a project from a template and register it as an alias
+
+use std::fs;
+use std::io;
+use std::path::Path;
+use std::process;
+
+use crate::alias::{Alias, AliasError};
+use crate::commands::register::validate_and_normalize_tags;
+use crate::config::{copy_dir, expand_path, expand_path_with, Config, ProjectTemplate};
+use crate::context::Ctx;
+use crate::database::Database;
+use crate::exit::{Code, Failure};
+
+/// Run a template's init command inside `dir`
+///
+/// The command goes through `sh -c` with `GOTO_ALIAS` set to the new alias.
+/// Its output goes to stderr so stdout stays free for the path the shell
+/// wrapper changes into.
+fn run_init(ctx: &mut Ctx, command: &str, dir: &Path, alias: &str) -> Result<(), Box<dyn std::error::Error>> {
+    crate::enote!(ctx, "Running {}", command);
+    let status = process::Command::new("sh")
+        .arg("-c")
+        .arg(command)
+        .current_dir(dir)
+        .env("GOTO_ALIAS", alias)
+        .stdout(io::stderr())
+        .status()
+        .map_err(|e| format!("failed to run template init command: {}", e))?;
+    if !status.success() {
+        return Err(format!("template init command '{}' failed", command).into());
+    }
+    Ok(())
+}
+
+/// Copy the skeleton and run the init command of `template` in `dir`
+fn apply(ctx: &mut Ctx, template: &ProjectTemplate, dir: &Path, alias: &str) -> Result<(), Box<dyn std::error::Error>> {
+    if let Some(skeleton) = &template.skeleton {
+        let skeleton = expand_path(skeleton)?;
+        if !skeleton.is_dir() {
+            return Err(Failure::new(
+                Code::Missing,
+                format!("template skeleton directory does not exist: {}", skeleton.display()),
+            )
+            .into());
+        }
+        copy_dir(&skeleton, dir)?;
+    }
+    if let Some(init) = &template.init {
+        run_init(ctx, init, dir, alias)?;
+    }
+    Ok(())
+}
+
+/// Create a project directory, apply a template and register it
+///
+/// Returns the new alias; the CLI changes into its directory. The directory
+/// may already exist as long as it is empty. Without a template it is only
+/// created. The alias gets the template's tags (default: its name) plus
+/// `tags`. If the template fails, a directory created here is removed again
+/// and nothing is registered.
+pub fn new_project(
+    ctx: &mut Ctx,
+    db: &mut Database,
+    config: &Config,
+    name: &str,
+    path: &str,
+    template: Option<&str>,
+    tags: &[String],
+) -> Result<Alias, Box<dyn std::error::Error>> {
+    db.check_name(name)?;
+    if db.get(name).is_some() {
+        return Err(AliasError::AlreadyExists(name.to_string()).into());
+    }
+
+    let template = match template {
+        Some(t) => {
+            let def = config
+                .user
+                .templates
+                .get(t)
+                .ok_or_else(|| Failure::not_found(format!("template '{}' not found in {}", t, config.config_path.display())))?;
+            Some((t, def))
+        }
+        None => None,
+    };
+
+    let mut all_tags = tags.to_vec();
+    if let Some((t, def)) = template {
+        match &def.tags {
+            Some(template_tags) => all_tags.extend(template_tags.iter().cloned()),
+            None => all_tags.push(t.to_string()),
+        }
+    }
+    let tags = validate_and_normalize_tags(&all_tags)?;
+
+    let target = expand_path_with(path, config.user.general.resolve_symlinks)?;
+    let created = !target.exists();
+    if !created && fs::read_dir(&target)?.next().is_some() {
+        return Err(Failure::new(Code::Exists, format!("{} already exists and is not empty", target.display())).into());
+    }
+    fs::create_dir_all(&target)?;
+
+    if let Some((_, def)) = template {
+        if let Err(e) = apply(ctx, def, &target, name) {
+            if created {
+                let _ = fs::remove_dir_all(&target);
+            }
+            return Err(e);
+        }
+    }
+
+    let path_str = target.to_string_lossy().into_owned();
+    db.add_with_tags(Alias::new(name, &path_str)?, tags)?;
+    db.save()?;
+
+    db.get(name).cloned().ok_or_else(|| AliasError::NotFound(name.to_string()).into())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    fn setup() -> (Database, Config, tempfile::TempDir) {
+        let dir = tempdir().unwrap();
+        let db = Database::load_from_path(&dir.path().join("aliases")).unwrap();
+        let config = Config {
+            database_path: dir.path().to_path_buf(),
+            stack_path: dir.path().join("goto_stack"),
+            config_path: dir.path().join("config.toml"),
+            aliases_path: dir.path().join("aliases.toml"),
+            profile: None,
+            user: Default::default(),
+            notices: Vec::new(),
+        };
+        (db, config, dir)
+    }
+
+    #[test]
+    fn test_new_project_copies_skeleton() {
+        let (mut db, mut config, dir) = setup();
+        let skeleton = dir.path().join("skel");
+        fs::create_dir_all(skeleton.join("src")).unwrap();
+        fs::write(skeleton.join("src").join("main.rs"), "fn main() {}").unwrap();
+        config.user.templates.insert(
+            "rust".to_string(),
+            ProjectTemplate {
+                skeleton: Some(skeleton.to_string_lossy().into_owned()),
+                ..Default::default()
+            },
+        );
+
+        let target = dir.path().join("projects").join("app");
+        let alias = new_project(&mut Ctx::silent(), &mut db, &config, "app", target.to_str().unwrap(), Some("rust"), &["Work".to_string()])
+            .unwrap();
+
+        assert!(target.join("src").join("main.rs").exists());
+        assert_eq!(alias.tags, vec!["rust", "work"]);
+        assert!(db.contains("app"));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_new_project_runs_init_with_template_tags() {
+        let (mut db, mut config, dir) = setup();
+        config.user.templates.insert(
+            "notes".to_string(),
+            ProjectTemplate {
+                init: Some("echo \"$GOTO_ALIAS\" > README".to_string()),
+                tags: Some(vec!["docs".to_string()]),
+                ..Default::default()
+            },
+        );
+
+        let target = dir.path().join("journal");
+        let alias = new_project(&mut Ctx::silent(), &mut db, &config, "journal", target.to_str().unwrap(), Some("notes"), &[]).unwrap();
+
+        assert_eq!(fs::read_to_string(target.join("README")).unwrap().trim(), "journal");
+        assert_eq!(alias.tags, vec!["docs"]);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_new_project_failed_init_cleans_up() {
+        let (mut db, mut config, dir) = setup();
+        config.user.templates.insert(
+            "broken".to_string(),
+            ProjectTemplate {
+                init: Some("exit 1".to_string()),
+                ..Default::default()
+            },
+        );
+
+        let target = dir.path().join("broken");
+        let err = new_project(&mut Ctx::silent(), &mut db, &config, "broken", target.to_str().unwrap(), Some("broken"), &[]).unwrap_err();
+        assert!(err.to_string().contains("failed"));
+        assert!(!target.exists());
+        assert!(!db.contains("broken"));
+    }
+
+    #[test]
+    fn test_new_project_refusals() {
+        let (mut db, config, dir) = setup();
+        let target = dir.path().join("proj");
+
+        let err = new_project(&mut Ctx::silent(), &mut db, &config, "proj", target.to_str().unwrap(), Some("nope"), &[]).unwrap_err();
+        assert!(err.to_string().contains("not found"));
+        assert!(!target.exists());
+
+        fs::create_dir_all(&target).unwrap();
+        fs::write(target.join("file"), "").unwrap();
+        let err = new_project(&mut Ctx::silent(), &mut db, &config, "proj", target.to_str().unwrap(), None, &[]).unwrap_err();
+        assert!(err.to_string().contains("not empty"));
+        assert!(target.join("file").exists());
+
+        // An empty directory is fine, even without a template
+        fs::remove_file(target.join("file")).unwrap();
+        let alias = new_project(&mut Ctx::silent(), &mut db, &config, "proj", target.to_str().unwrap(), None, &[]).unwrap();
+        assert!(alias.tags.is_empty());
+
+        let err = new_project(&mut Ctx::silent(), &mut db, &config, "proj", target.to_str().unwrap(), None, &[]).unwrap_err();
+        assert!(err.to_string().contains("already exists"));
+    }
+}
@@ -0,0 +1,316 @@
+//! Approximate time spent in each alias (`privacy.track_time`)
+//!
+//! Navigating to an alias appends an `enter` event and exports
+//! [`TRACK_DIR_VAR`]; the shell wrappers report `goto --event leave` once the
+//! shell's directory is outside it. Events are appended to one log with a
+//! single write each, without locking or loading the database, so the
+//! prompt hook stays cheap. `--stats` replays the log per shell session.
+
+use std::collections::HashMap;
+use std::fs::{self, OpenOptions};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+
+use crate::commands::navigate::NavigationResult;
+use crate::config::Config;
+use crate::stack;
+
+/// Variable holding the directory whose leaving the wrappers report
+pub const TRACK_DIR_VAR: &str = "GOTO_TRACK_DIR";
+
+/// Longest a single stay counts for: a shell left open overnight isn't work
+pub const MAX_STAY: Duration = Duration::from_secs(8 * 3600);
+
+/// Period `--stats` reports on
+pub const REPORT_PERIOD: Duration = Duration::from_secs(7 * 24 * 3600);
+
+/// Log size above which `--stats` drops events older than [`KEEP_EVENTS`]
+const COMPACT_SIZE: u64 = 256 * 1024;
+
+/// How long events are kept when the log is compacted
+const KEEP_EVENTS: Duration = Duration::from_secs(30 * 24 * 3600);
+
+/// What a shell did
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum EventKind {
+    /// Entered the directory `path` of `alias`
+    Enter { alias: String, path: String },
+    /// Changed directory to `cwd`, which may be outside the alias entered last
+    Leave { cwd: String },
+}
+
+/// One line of the event log
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Event {
+    pub at: DateTime<Utc>,
+    pub session: String,
+    pub kind: EventKind,
+}
+
+impl Event {
+    /// An event of the current shell session, now
+    pub fn now(kind: EventKind) -> Self {
+        Self {
+            at: Utc::now(),
+            session: stack::session_id(),
+            kind,
+        }
+    }
+
+    /// `<unix time>\t<session>\tenter\t<alias>\t<path>` or `...\tleave\t<cwd>`
+    fn to_line(&self) -> String {
+        let fields = match &self.kind {
+            EventKind::Enter { alias, path } => format!("enter\t{}\t{}", alias, path),
+            EventKind::Leave { cwd } => format!("leave\t{}", cwd),
+        };
+        format!("{}\t{}\t{}\n", self.at.timestamp(), self.session, fields)
+    }
+
+    fn parse(line: &str) -> Option<Self> {
+        let mut fields = line.split('\t');
+        let at = DateTime::from_timestamp(fields.next()?.parse().ok()?, 0)?;
+        let session = fields.next()?.to_string();
+        let kind = match fields.next()? {
+            "enter" => EventKind::Enter {
+                alias: fields.next()?.to_string(),
+                path: fields.next()?.to_string(),
+            },
+            "leave" => EventKind::Leave {
+                cwd: fields.next()?.to_string(),
+            },
+            _ => return None,
+        };
+        Some(Self { at, session, kind })
+    }
+}
+
+/// The event log
+pub fn events_path(config: &Config) -> PathBuf {
+    config.database_path.join("time_events.log")
+}
+
+/// Append `event` to the log, if `privacy.track_time` is on
+///
+/// One `write` to a file opened for appending: concurrent shells can't
+/// interleave their lines, so no lock is taken.
+pub fn record(config: &Config, event: &Event) -> io::Result<()> {
+    if !config.user.privacy.track_time {
+        return Ok(());
+    }
+    fs::create_dir_all(&config.database_path)?;
+    let mut file = OpenOptions::new().create(true).append(true).open(events_path(config))?;
+    file.write_all(event.to_line().as_bytes())
+}
+
+/// Record entering the alias `result` leads to
+///
+/// Returns the directory to export as [`TRACK_DIR_VAR`], `None` when time
+/// isn't tracked or the result isn't an alias.
+pub fn track_enter(config: &Config, result: &NavigationResult) -> Option<String> {
+    let alias = result.alias.as_ref().filter(|_| config.user.privacy.track_time)?;
+    let event = Event::now(EventKind::Enter {
+        alias: alias.clone(),
+        path: result.path.clone(),
+    });
+    if let Err(e) = record(config, &event) {
+        crate::debug!("could not record entering '{}': {}", alias, e);
+        return None;
+    }
+    Some(result.path.clone())
+}
+
+/// Read the event log, skipping lines that don't parse
+pub fn read_events(path: &Path) -> io::Result<Vec<Event>> {
+    match fs::read_to_string(path) {
+        Ok(content) => Ok(content.lines().filter_map(Event::parse).collect()),
+        Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(Vec::new()),
+        Err(e) => Err(e),
+    }
+}
+
+/// Drop events older than [`KEEP_EVENTS`] once the log grows large
+///
+/// Events appended while the log is rewritten can be lost; they only
+/// make the estimate a little rougher.
+pub fn compact(path: &Path, now: DateTime<Utc>) -> io::Result<()> {
+    if fs::metadata(path).map(|m| m.len()).unwrap_or(0) < COMPACT_SIZE {
+        return Ok(());
+    }
+    let cutoff = now - chrono::Duration::from_std(KEEP_EVENTS).unwrap_or_default();
+    let kept: String = read_events(path)?
+        .iter()
+        .filter(|e| e.at >= cutoff)
+        .map(Event::to_line)
+        .collect();
+    let tmp = path.with_extension("log.tmp");
+    fs::write(&tmp, kept)?;
+    fs::rename(&tmp, path)
+}
+
+/// Time spent in each alias between `since` and `now`, longest first
+///
+/// A stay in an alias starts with its `enter` event and ends with the
+/// session's next `enter`, or a `leave` outside the alias's directory. Stays
+/// that never end (the shell was closed) run until `now`; every stay counts
+/// for at most [`MAX_STAY`].
+pub fn time_spent(events: &[Event], since: DateTime<Utc>, now: DateTime<Utc>) -> Vec<(String, Duration)> {
+    let max_stay = chrono::Duration::from_std(MAX_STAY).unwrap_or_default();
+    let mut open: HashMap<&str, (&str, &str, DateTime<Utc>)> = HashMap::new();
+    let mut totals: HashMap<String, Duration> = HashMap::new();
+    let mut add = |alias: &str, start: DateTime<Utc>, end: DateTime<Utc>| {
+        let end = end.min(start + max_stay).min(now);
+        let start = start.max(since);
+        if let Ok(stay) = (end - start).to_std() {
+            *totals.entry(alias.to_string()).or_default() += stay;
+        }
+    };
+
+    let mut sorted: Vec<&Event> = events.iter().collect();
+    sorted.sort_by_key(|e| e.at);
+    for event in sorted {
+        match &event.kind {
+            EventKind::Enter { alias, path } => {
+                if let Some((alias, _, start)) = open.insert(&event.session, (alias, path, event.at)) {
+                    add(alias, start, event.at);
+                }
+            }
+            EventKind::Leave { cwd } => {
+                let left = open
+                    .get(event.session.as_str())
+                    .is_some_and(|(_, path, _)| !Path::new(cwd).starts_with(path));
+                if left {
+                    if let Some((alias, _, start)) = open.remove(event.session.as_str()) {
+                        add(alias, start, event.at);
+                    }
+                }
+            }
+        }
+    }
+    for (alias, _, start) in open.into_values() {
+        add(alias, start, now);
+    }
+
+    let mut totals: Vec<_> = totals.into_iter().filter(|(_, d)| !d.is_zero()).collect();
+    totals.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+    totals
+}
+
+/// A rough duration: `~45m`, `~6h`, `~2h 30m`
+pub fn format_approx(duration: Duration) -> String {
+    let minutes = (duration.as_secs() + 30) / 60;
+    match (minutes / 60, minutes % 60) {
+        (0, 0) => "<1m".to_string(),
+        (0, m) => format!("~{}m", m),
+        (h, 0) => format!("~{}h", h),
+        (h, m) => format!("~{}h {}m", h, m),
+    }
+}
+
+/// Render time spent as `--stats` shows it, e.g. "~6h in 'work'"
+pub fn format_time_spent(totals: &[(String, Duration)]) -> String {
+    let mut out = String::from("Time spent this week:");
+    if totals.is_empty() {
+        out.push_str("\n  (nothing recorded yet)");
+    }
+    for (alias, duration) in totals.iter().take(10) {
+        out.push_str(&format!("\n  {} in '{}'", format_approx(*duration), alias));
+    }
+    out
+}
+
+/// The time spent section of `--stats`, compacting the log first if needed
+pub fn report(config: &Config) -> io::Result<String> {
+    let path = events_path(config);
+    let now = Utc::now();
+    if let Err(e) = compact(&path, now) {
+        crate::debug!("could not compact {}: {}", path.display(), e);
+    }
+    let since = now - chrono::Duration::from_std(REPORT_PERIOD).unwrap_or_default();
+    Ok(format_time_spent(&time_spent(&read_events(&path)?, since, now)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn at(minutes: i64) -> DateTime<Utc> {
+        DateTime::from_timestamp(1_700_000_000 + minutes * 60, 0).unwrap()
+    }
+
+    fn enter(minutes: i64, session: &str, alias: &str) -> Event {
+        Event {
+            at: at(minutes),
+            session: session.to_string(),
+            kind: EventKind::Enter {
+                alias: alias.to_string(),
+                path: format!("/src/{}", alias),
+            },
+        }
+    }
+
+    fn leave(minutes: i64, session: &str, cwd: &str) -> Event {
+        Event {
+            at: at(minutes),
+            session: session.to_string(),
+            kind: EventKind::Leave { cwd: cwd.to_string() },
+        }
+    }
+
+    #[test]
+    fn test_event_lines_round_trip() {
+        let event = enter(0, "123", "work");
+        assert_eq!(event.to_line(), "1700000000\t123\tenter\twork\t/src/work\n");
+        assert_eq!(Event::parse(event.to_line().trim_end()), Some(event));
+        let event = leave(5, "123", "/tmp/a b");
+        assert_eq!(Event::parse(event.to_line().trim_end()), Some(event));
+        assert_eq!(Event::parse("garbage"), None);
+    }
+
+    #[test]
+    fn test_time_spent() {
+        let events = vec![
+            enter(0, "a", "work"),
+            // Moving around inside the alias isn't leaving it
+            leave(10, "a", "/src/work/api"),
+            enter(60, "a", "blog"),
+            leave(90, "a", "/tmp"),
+            // Another shell, interleaved
+            enter(20, "b", "work"),
+            leave(50, "b", "/home"),
+            // Never left: counts until now
+            enter(100, "c", "blog"),
+        ];
+        let totals = time_spent(&events, at(0), at(110));
+        assert_eq!(
+            totals,
+            [
+                ("work".to_string(), Duration::from_secs(90 * 60)),
+                ("blog".to_string(), Duration::from_secs(40 * 60)),
+            ]
+        );
+
+        // Only the part after `since` counts
+        let totals = time_spent(&events, at(80), at(110));
+        assert_eq!(totals[0], ("blog".to_string(), Duration::from_secs(20 * 60)));
+        assert_eq!(totals.len(), 1);
+    }
+
+    #[test]
+    fn test_stays_are_capped() {
+        let events = vec![enter(0, "a", "work")];
+        let totals = time_spent(&events, at(0), at(24 * 60));
+        assert_eq!(totals, [("work".to_string(), MAX_STAY)]);
+    }
+
+    #[test]
+    fn test_format_approx() {
+        assert_eq!(format_approx(Duration::from_secs(10)), "<1m");
+        assert_eq!(format_approx(Duration::from_secs(45 * 60)), "~45m");
+        assert_eq!(format_approx(Duration::from_secs(6 * 3600 + 20)), "~6h");
+        assert_eq!(format_approx(Duration::from_secs(150 * 60)), "~2h 30m");
+        assert_eq!(format_time_spent(&[("work".to_string(), Duration::from_secs(6 * 3600))]), "Time spent this week:\n  ~6h in 'work'");
+    }
+}
@@ -1,17 +1,53 @@
 //! Self-update functionality for goto
 
 use chrono::{DateTime, Duration, Utc};
+use minisign_verify::{PublicKey, Signature};
 use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::cmp::Ordering;
 use std::error::Error;
 use std::fs::{self, File};
-use std::io::{self, BufReader, Write};
-use std::path::PathBuf;
+use std::io::{self, BufReader, IsTerminal, Read, Write};
+use std::path::{Path, PathBuf};
+use std::process;
 
 use crate::config::Config;
+use crate::context::Ctx;
+use crate::exit::{Code, Failure};
 
 const GITHUB_API_URL: &str = "https://api.github.com/repos/anttilinno/goto/releases/latest";
+const GITHUB_RELEASES_URL: &str = "https://api.github.com/repos/anttilinno/goto/releases";
 const CURRENT_VERSION: &str = env!("CARGO_PKG_VERSION");
 
+/// minisign public key (base64) that release binaries are signed with
+///
+/// Release builds embed it through `GOTO_RELEASE_PUBLIC_KEY` at compile time;
+/// `--update` then refuses any download whose `<asset>.minisig` is missing or
+/// doesn't verify. Builds without the key only check the SHA-256 checksum.
+const RELEASE_PUBLIC_KEY: Option<&str> = option_env!("GOTO_RELEASE_PUBLIC_KEY");
+
+/// Upper bound for the failure backoff between update checks (one week)
+const MAX_BACKOFF_HOURS: i64 = 24 * 7;
+
+/// Which releases are considered when looking for updates
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum UpdateChannel {
+    /// Only full releases (default)
+    #[default]
+    Stable,
+    /// Full releases and GitHub prereleases
+    Prerelease,
+}
+
+impl From<&str> for UpdateChannel {
+    fn from(s: &str) -> Self {
+        match s.to_lowercase().as_str() {
+            "prerelease" | "pre" | "beta" => UpdateChannel::Prerelease,
+            _ => UpdateChannel::Stable, // Unknown values fall back to stable
+        }
+    }
+}
+
 /// Cached update information
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct UpdateCache {
@@ -19,6 +55,12 @@ pub struct UpdateCache {
     pub latest_version: Option<String>,
     pub download_url: Option<String>,
     pub checksum: Option<String>,
+    /// minisign signature published alongside the binary, if any
+    #[serde(default)]
+    pub signature_url: Option<String>,
+    /// Number of update checks that failed in a row (drives backoff)
+    #[serde(default)]
+    pub consecutive_failures: u32,
 }
 
 impl Default for UpdateCache {
@@ -28,10 +70,26 @@ impl Default for UpdateCache {
             latest_version: None,
             download_url: None,
             checksum: None,
+            signature_url: None,
+            consecutive_failures: 0,
         }
     }
 }
 
+impl UpdateCache {
+    /// Time until the next check is due, backing off exponentially after failures
+    fn check_interval(&self, base_hours: u64) -> Duration {
+        let base = (base_hours as i64).max(1);
+        let factor = 1i64 << self.consecutive_failures.min(16);
+        Duration::hours(base.saturating_mul(factor).min(MAX_BACKOFF_HOURS.max(base)))
+    }
+
+    /// Whether a network check is due at `now`
+    fn is_check_due(&self, base_hours: u64, now: DateTime<Utc>) -> bool {
+        now - self.last_check >= self.check_interval(base_hours)
+    }
+}
+
 /// GitHub release asset information
 #[derive(Debug, Deserialize)]
 struct GitHubAsset {
@@ -44,6 +102,19 @@ struct GitHubAsset {
 struct GitHubRelease {
     tag_name: String,
     assets: Vec<GitHubAsset>,
+    #[serde(default)]
+    prerelease: bool,
+    #[serde(default)]
+    draft: bool,
+}
+
+/// A resolved release ready to be downloaded
+#[derive(Debug, Clone)]
+struct ReleaseInfo {
+    version: String,
+    download_url: Option<String>,
+    checksum: Option<String>,
+    signature_url: Option<String>,
 }
 
 /// Get the path to the update cache file
@@ -82,56 +153,140 @@ fn parse_version(version: &str) -> &str {
 }
 
 /// Compare two version strings (semver-like)
-/// Returns true if version_a > version_b
-fn is_newer_version(version_a: &str, version_b: &str) -> bool {
-    let parse = |v: &str| -> Vec<u32> {
-        parse_version(v)
-            .split('.')
-            .filter_map(|s| s.parse().ok())
-            .collect()
+///
+/// A prerelease suffix (`1.3.0-rc.1`) sorts before the plain release.
+fn compare_versions(version_a: &str, version_b: &str) -> Ordering {
+    let split = |v: &str| -> (Vec<u32>, Option<String>) {
+        let v = parse_version(v);
+        let (core, pre) = match v.split_once('-') {
+            Some((core, pre)) => (core, Some(pre.to_string())),
+            None => (v, None),
+        };
+        let numbers = core.split('.').filter_map(|s| s.parse().ok()).collect();
+        (numbers, pre)
     };
 
-    let a = parse(version_a);
-    let b = parse(version_b);
+    let (a, a_pre) = split(version_a);
+    let (b, b_pre) = split(version_b);
 
     for (va, vb) in a.iter().zip(b.iter()) {
-        if va > vb {
-            return true;
-        }
-        if va < vb {
-            return false;
+        match va.cmp(vb) {
+            Ordering::Equal => {}
+            other => return other,
         }
     }
 
-    a.len() > b.len()
+    match a.len().cmp(&b.len()) {
+        Ordering::Equal => {}
+        other => return other,
+    }
+
+    match (a_pre, b_pre) {
+        (None, None) => Ordering::Equal,
+        (None, Some(_)) => Ordering::Greater,
+        (Some(_), None) => Ordering::Less,
+        (Some(pa), Some(pb)) => pa.cmp(&pb),
+    }
 }
 
-/// Check for updates from GitHub
-fn fetch_latest_release() -> Result<GitHubRelease, Box<dyn Error>> {
-    let client = reqwest::blocking::Client::builder()
+/// Returns true if version_a > version_b
+fn is_newer_version(version_a: &str, version_b: &str) -> bool {
+    compare_versions(version_a, version_b) == Ordering::Greater
+}
+
+/// Build the HTTP client used for GitHub API requests
+fn api_client() -> Result<reqwest::blocking::Client, Box<dyn Error>> {
+    Ok(reqwest::blocking::Client::builder()
         .user_agent(format!("goto/{}", CURRENT_VERSION))
         .timeout(std::time::Duration::from_secs(10))
-        .build()?;
+        .build()?)
+}
+
+/// Check for updates from GitHub on the given channel
+fn fetch_latest_release(channel: UpdateChannel) -> Result<GitHubRelease, Box<dyn Error>> {
+    let client = api_client()?;
 
-    let response = client.get(GITHUB_API_URL).send()?;
+    if channel == UpdateChannel::Stable {
+        let response = client.get(GITHUB_API_URL).send()?;
+
+        if !response.status().is_success() {
+            return Err(format!("GitHub API returned status {}", response.status()).into());
+        }
+
+        let release: GitHubRelease = response.json()?;
+        return Ok(release);
+    }
+
+    let response = client.get(GITHUB_RELEASES_URL).send()?;
 
     if !response.status().is_success() {
         return Err(format!("GitHub API returned status {}", response.status()).into());
     }
 
-    let release: GitHubRelease = response.json()?;
-    Ok(release)
+    let releases: Vec<GitHubRelease> = response.json()?;
+    select_latest_release(releases, channel).ok_or_else(|| "No releases found".into())
+}
+
+/// Pick the highest version from a list of releases, honouring the channel
+fn select_latest_release(
+    releases: Vec<GitHubRelease>,
+    channel: UpdateChannel,
+) -> Option<GitHubRelease> {
+    releases
+        .into_iter()
+        .filter(|r| !r.draft)
+        .filter(|r| channel == UpdateChannel::Prerelease || !r.prerelease)
+        .max_by(|a, b| compare_versions(&a.tag_name, &b.tag_name))
+}
+
+/// Fetch a specific release by version, trying both `vX.Y.Z` and `X.Y.Z` tags
+fn fetch_release_by_version(version: &str) -> Result<GitHubRelease, Box<dyn Error>> {
+    let client = api_client()?;
+    let version = parse_version(version);
+
+    for tag in [format!("v{}", version), version.to_string()] {
+        let url = format!("{}/tags/{}", GITHUB_RELEASES_URL, tag);
+        let response = client.get(&url).send()?;
+
+        if response.status() == reqwest::StatusCode::NOT_FOUND {
+            continue;
+        }
+        if !response.status().is_success() {
+            return Err(format!("GitHub API returned status {}", response.status()).into());
+        }
+
+        let release: GitHubRelease = response.json()?;
+        return Ok(release);
+    }
+
+    Err(Failure::not_found(format!("release '{}' not found", version)).into())
+}
+
+/// Resolve download URL and checksum for the current platform
+fn resolve_release(release: &GitHubRelease) -> ReleaseInfo {
+    let asset_name = get_binary_asset_name();
+    let asset_url = |name: &str| {
+        release
+            .assets
+            .iter()
+            .find(|a| a.name == name)
+            .map(|a| a.browser_download_url.clone())
+    };
+
+    ReleaseInfo {
+        version: parse_version(&release.tag_name).to_string(),
+        download_url: asset_name.and_then(asset_url),
+        checksum: fetch_checksum(&release.assets),
+        signature_url: asset_name.and_then(|name| asset_url(&format!("{}.minisig", name))),
+    }
 }
 
 /// Fetch the checksum file and extract the checksum for the binary
 fn fetch_checksum(assets: &[GitHubAsset]) -> Option<String> {
     let checksum_asset = assets.iter().find(|a| a.name == "checksums.txt")?;
+    let binary_name = get_binary_asset_name()?;
 
-    let client = reqwest::blocking::Client::builder()
-        .user_agent(format!("goto/{}", CURRENT_VERSION))
-        .timeout(std::time::Duration::from_secs(10))
-        .build()
-        .ok()?;
+    let client = api_client().ok()?;
 
     let content = client
         .get(&checksum_asset.browser_download_url)
@@ -140,13 +295,17 @@ fn fetch_checksum(assets: &[GitHubAsset]) -> Option<String> {
         .text()
         .ok()?;
 
-    // Parse checksums.txt format: "hash  filename"
-    for line in content.lines() {
-        if line.contains("goto-linux-amd64") {
-            return line.split_whitespace().next().map(String::from);
-        }
-    }
-    None
+    parse_checksum(&content, binary_name)
+}
+
+/// Parse checksums.txt format ("hash  filename") for the given file
+fn parse_checksum(content: &str, filename: &str) -> Option<String> {
+    content.lines().find_map(|line| {
+        let mut parts = line.split_whitespace();
+        let hash = parts.next()?;
+        let name = parts.next()?.trim_start_matches('*');
+        (name == filename).then(|| hash.to_string())
+    })
 }
 
 /// Get the appropriate binary asset name for the current platform
@@ -166,32 +325,39 @@ pub fn check_for_updates(
     config: &Config,
     force: bool,
 ) -> Result<Option<String>, Box<dyn Error>> {
+    if !config.user.update.enabled {
+        return Err("update checks are disabled (update.enabled = false)".into());
+    }
+
     let mut cache = load_cache(config);
-    let check_interval = Duration::hours(config.user.update.check_interval_hours as i64);
 
     // Skip if checked recently (unless forced)
-    if !force && Utc::now() - cache.last_check < check_interval {
+    if !force && !cache.is_check_due(config.user.update.check_interval_hours, Utc::now()) {
         return Ok(cache.latest_version.filter(|v| is_newer_version(v, CURRENT_VERSION)));
     }
 
-    // Fetch latest release
-    let release = fetch_latest_release()?;
-    let latest_version = parse_version(&release.tag_name).to_string();
-
-    // Find the appropriate binary asset
-    let asset_name = get_binary_asset_name();
-    let download_url = asset_name
-        .and_then(|name| release.assets.iter().find(|a| a.name == name))
-        .map(|a| a.browser_download_url.clone());
-
-    // Fetch checksum
-    let checksum = fetch_checksum(&release.assets);
+    // Fetch latest release on the configured channel
+    let channel = UpdateChannel::from(config.user.update.channel.as_str());
+    let release = match fetch_latest_release(channel) {
+        Ok(release) => release,
+        Err(e) => {
+            // Remember the failure so the next automatic check backs off
+            cache.last_check = Utc::now();
+            cache.consecutive_failures = cache.consecutive_failures.saturating_add(1);
+            let _ = save_cache(config, &cache);
+            return Err(e);
+        }
+    };
+    let info = resolve_release(&release);
+    let latest_version = info.version.clone();
 
     // Update cache
     cache.last_check = Utc::now();
-    cache.latest_version = Some(latest_version.clone());
-    cache.download_url = download_url;
-    cache.checksum = checksum;
+    cache.latest_version = Some(info.version);
+    cache.download_url = info.download_url;
+    cache.checksum = info.checksum;
+    cache.signature_url = info.signature_url;
+    cache.consecutive_failures = 0;
     save_cache(config, &cache)?;
 
     if is_newer_version(&latest_version, CURRENT_VERSION) {
@@ -202,25 +368,26 @@ pub fn check_for_updates(
 }
 
 /// Show a notification if an update is available (non-blocking, best-effort)
-pub fn notify_if_update_available(config: &Config) {
-    if !config.user.update.auto_check {
+///
+/// When a check is due it runs in a detached `goto-bin --update-check-daemon`
+/// process, so navigation never waits on the network.
+pub fn notify_if_update_available(ctx: &mut Ctx, config: &Config) {
+    if !config.user.update.enabled || !config.user.update.auto_check {
         return;
     }
 
-    let cache = load_cache(config);
+    let mut cache = load_cache(config);
 
-    // Check if we should perform a background check
-    let check_interval = Duration::hours(config.user.update.check_interval_hours as i64);
-    if Utc::now() - cache.last_check >= check_interval {
-        // Try to check for updates, but don't block on errors
-        let _ = check_for_updates(config, false);
+    if cache.is_check_due(config.user.update.check_interval_hours, Utc::now()) {
+        let _ = spawn_background_check(config, &mut cache);
         return;
     }
 
     // Show notification if update is available
     if let Some(ref latest) = cache.latest_version {
         if is_newer_version(latest, CURRENT_VERSION) {
-            eprintln!(
+            crate::enote!(
+                ctx,
                 "Update available: {} (current: {}). Run 'goto --update' to upgrade.",
                 latest, CURRENT_VERSION
             );
@@ -228,61 +395,265 @@ pub fn notify_if_update_available(config: &Config) {
     }
 }
 
+/// Claim the due check in the cache and start it in a detached process
+///
+/// Claiming first (by moving `last_check` to now) keeps shells navigating
+/// concurrently from each starting their own check.
+fn spawn_background_check(config: &Config, cache: &mut UpdateCache) -> Result<(), Box<dyn Error>> {
+    cache.last_check = Utc::now();
+    save_cache(config, cache)?;
+
+    let mut command = process::Command::new(get_current_binary_path()?);
+    command.arg("--update-check-daemon");
+    // The daemon must write to this profile's cache
+    if let Some(profile) = &config.profile {
+        command.arg(format!("--profile={}", profile));
+    }
+    command
+        .stdin(process::Stdio::null())
+        .stdout(process::Stdio::null())
+        .stderr(process::Stdio::null())
+        .spawn()?;
+    Ok(())
+}
+
 /// Get the path to the currently running binary
 fn get_current_binary_path() -> Result<PathBuf, Box<dyn Error>> {
     std::env::current_exe().map_err(|e| e.into())
 }
 
-/// Calculate SHA256 checksum of a file
-fn calculate_sha256(path: &PathBuf) -> Result<String, Box<dyn Error>> {
-    use std::io::Read;
+/// Download `url` to `dest`, streaming to disk with a progress bar
+///
+/// A partial `dest` left by an interrupted download of the same `version` is
+/// resumed with an HTTP range request. Returns the SHA-256 (lowercase hex) of
+/// the complete file, hashed as it is written.
+fn download_release(
+    client: &reqwest::blocking::Client,
+    url: &str,
+    dest: &Path,
+    version: &str,
+) -> Result<String, Box<dyn Error>> {
+    // Records which version a partial download belongs to
+    let marker = dest.with_extension("new.version");
+    let resumable = fs::read_to_string(&marker).is_ok_and(|v| v.trim() == version);
+    let mut offset = if resumable {
+        fs::metadata(dest).map(|m| m.len()).unwrap_or(0)
+    } else {
+        0
+    };
 
-    let mut file = File::open(path)?;
-    let mut buffer = Vec::new();
-    file.read_to_end(&mut buffer)?;
+    let mut request = client.get(url);
+    if offset > 0 {
+        request = request.header(reqwest::header::RANGE, format!("bytes={}-", offset));
+    }
+    let mut response = request.send()?;
 
-    // Simple SHA256 implementation using standard library would be ideal,
-    // but we'll use a basic approach for verification
-    // For production, consider adding sha2 crate
-    // For now, we'll use system sha256sum command
-    use std::process::Command;
+    if response.status() == reqwest::StatusCode::RANGE_NOT_SATISFIABLE {
+        // The partial file does not fit the asset any more; start over
+        offset = 0;
+        response = client.get(url).send()?;
+    }
+    if !response.status().is_success() {
+        return Err(format!("Download failed with status {}", response.status()).into());
+    }
+    if response.status() != reqwest::StatusCode::PARTIAL_CONTENT {
+        offset = 0;
+    }
 
-    let output = Command::new("sha256sum").arg(path).output()?;
+    let mut hasher = Sha256::new();
+    let mut file = if offset > 0 {
+        io::copy(&mut File::open(dest)?, &mut hasher)?;
+        println!("Resuming download at {}", format_bytes(offset));
+        fs::OpenOptions::new().append(true).open(dest)?
+    } else {
+        File::create(dest)?
+    };
+    fs::write(&marker, version)?;
+
+    let total = response.content_length().map(|len| len + offset);
+    let show_progress = io::stderr().is_terminal();
+    let mut done = offset;
+    let mut buffer = [0u8; 64 * 1024];
+    loop {
+        let read = response.read(&mut buffer).map_err(|e| {
+            format!("download interrupted ({}); run 'goto --update' again to resume", e)
+        })?;
+        if read == 0 {
+            break;
+        }
+        file.write_all(&buffer[..read])?;
+        hasher.update(&buffer[..read]);
+        done += read as u64;
+        if show_progress {
+            eprint!("\r{}", render_progress(done, total));
+        }
+    }
+    if show_progress {
+        eprintln!();
+    }
+    file.flush()?;
 
-    if !output.status.success() {
-        return Err("Failed to calculate checksum".into());
+    if let Some(total) = total {
+        if done != total {
+            return Err(format!(
+                "download incomplete ({} of {}); run 'goto --update' again to resume",
+                format_bytes(done),
+                format_bytes(total)
+            )
+            .into());
+        }
     }
+    let _ = fs::remove_file(&marker);
 
-    let output_str = String::from_utf8_lossy(&output.stdout);
-    output_str
-        .split_whitespace()
-        .next()
-        .map(String::from)
-        .ok_or_else(|| "Invalid checksum output".into())
+    Ok(hasher
+        .finalize()
+        .iter()
+        .map(|byte| format!("{:02x}", byte))
+        .collect())
 }
 
-/// Perform the self-update
-pub fn perform_update(config: &Config) -> Result<(), Box<dyn Error>> {
-    println!("Checking for updates...");
+/// Render a download progress line, e.g. `[#####-----]  50%  1.0 MiB / 2.0 MiB`
+fn render_progress(done: u64, total: Option<u64>) -> String {
+    const WIDTH: u64 = 30;
+
+    match total {
+        Some(total) if total > 0 => {
+            let done = done.min(total);
+            let filled = (done * WIDTH / total) as usize;
+            format!(
+                "[{}{}] {:>3}%  {} / {}",
+                "#".repeat(filled),
+                "-".repeat(WIDTH as usize - filled),
+                done * 100 / total,
+                format_bytes(done),
+                format_bytes(total)
+            )
+        }
+        _ => format!("{} downloaded", format_bytes(done)),
+    }
+}
 
-    // Force a fresh check
-    let latest = check_for_updates(config, true)?;
+/// Format a byte count with a binary unit
+fn format_bytes(bytes: u64) -> String {
+    const UNITS: [&str; 4] = ["B", "KiB", "MiB", "GiB"];
 
-    match latest {
-        None => {
-            println!("You are running the latest version ({}).", CURRENT_VERSION);
-            return Ok(());
+    let mut value = bytes as f64;
+    let mut unit = 0;
+    while value >= 1024.0 && unit < UNITS.len() - 1 {
+        value /= 1024.0;
+        unit += 1;
+    }
+    if unit == 0 {
+        format!("{} B", bytes)
+    } else {
+        format!("{:.1} {}", value, UNITS[unit])
+    }
+}
+
+/// Verify a minisign signature of `path` against `public_key` (base64)
+fn verify_signature(path: &Path, signature: &str, public_key: &str) -> Result<(), Box<dyn Error>> {
+    let public_key = PublicKey::from_base64(public_key)
+        .map_err(|e| format!("invalid release public key: {}", e))?;
+    let signature =
+        Signature::decode(signature).map_err(|e| format!("invalid release signature: {}", e))?;
+
+    let mut verifier = public_key
+        .verify_stream(&signature)
+        .map_err(|e| format!("signature verification failed: {}", e))?;
+    let mut file = File::open(path)?;
+    let mut buffer = [0u8; 64 * 1024];
+    loop {
+        let read = file.read(&mut buffer)?;
+        if read == 0 {
+            break;
         }
+        verifier.update(&buffer[..read]);
+    }
+    verifier
+        .finalize()
+        .map_err(|e| format!("signature verification failed: {}", e).into())
+}
+
+/// Verify a downloaded release against its published minisign signature
+///
+/// A release without a `.minisig` is an error: once a key is embedded,
+/// an unsigned download can't be told apart from a tampered one.
+fn verify_release(path: &Path, signature_url: Option<&str>, public_key: &str) -> Result<(), Box<dyn Error>> {
+    let url = signature_url.ok_or("release is not signed: no .minisig published, refusing to install")?;
+    let signature = fetch_signature(url)?;
+    verify_signature(path, &signature, public_key)
+}
+
+/// Download a release's minisign signature
+fn fetch_signature(url: &str) -> Result<String, Box<dyn Error>> {
+    let response = api_client()?.get(url).send()?;
+    if !response.status().is_success() {
+        return Err(format!("Signature download failed with status {}", response.status()).into());
+    }
+    Ok(response.text()?)
+}
+
+/// Perform the self-update
+///
+/// With `target_version` set, installs that exact release instead of the
+/// latest one (allowing downgrades). Pinned installs require a checksum.
+pub fn perform_update(config: &Config, target_version: Option<&str>) -> Result<(), Box<dyn Error>> {
+    if !config.user.update.enabled {
+        return Err("updates are disabled (update.enabled = false)".into());
+    }
+
+    let info = match target_version {
         Some(version) => {
-            println!("New version available: {} (current: {})", version, CURRENT_VERSION);
+            println!("Fetching release {}...", parse_version(version));
+            let info = resolve_release(&fetch_release_by_version(version)?);
+            if compare_versions(&info.version, CURRENT_VERSION) == Ordering::Equal {
+                println!("goto {} is already installed.", CURRENT_VERSION);
+                return Ok(());
+            }
+            if info.checksum.is_none() {
+                return Err(format!(
+                    "No checksum published for release {}; refusing to install",
+                    info.version
+                )
+                .into());
+            }
+            info
         }
-    }
+        None => {
+            println!("Checking for updates...");
+
+            // Force a fresh check
+            let latest = check_for_updates(config, true)?;
+
+            match latest {
+                None => {
+                    println!("You are running the latest version ({}).", CURRENT_VERSION);
+                    return Ok(());
+                }
+                Some(version) => {
+                    println!("New version available: {} (current: {})", version, CURRENT_VERSION);
+                }
+            }
 
-    let cache = load_cache(config);
+            let cache = load_cache(config);
+            ReleaseInfo {
+                version: cache.latest_version.unwrap_or_else(|| "unknown".to_string()),
+                download_url: cache.download_url,
+                checksum: cache.checksum,
+                signature_url: cache.signature_url,
+            }
+        }
+    };
+
+    install_release(&info)
+}
 
+/// Download, verify and swap in the binary for a resolved release
+fn install_release(info: &ReleaseInfo) -> Result<(), Box<dyn Error>> {
     // Verify we have a download URL
-    let download_url = cache
+    let download_url = info
         .download_url
+        .as_deref()
         .ok_or("No download URL available for your platform")?;
 
     // Get current binary path
@@ -291,12 +662,16 @@ pub fn perform_update(config: &Config) -> Result<(), Box<dyn Error>> {
     // Check if we have write permissions
     let parent_dir = current_binary.parent().ok_or("Cannot determine binary directory")?;
     if fs::metadata(parent_dir)?.permissions().readonly() {
-        return Err("Cannot update: binary directory is read-only. Try running with elevated permissions.".into());
+        return Err(Failure::new(
+            Code::Denied,
+            "Cannot update: binary directory is read-only. Try running with elevated permissions.",
+        )
+        .into());
     }
 
-    println!("Downloading {}...", cache.latest_version.as_deref().unwrap_or("update"));
+    println!("Downloading {}...", info.version);
 
-    // Download to temp file
+    // Download to temp file (kept on interruption so the next run resumes)
     let temp_path = parent_dir.join(".goto-bin.new");
 
     let client = reqwest::blocking::Client::builder()
@@ -304,24 +679,10 @@ pub fn perform_update(config: &Config) -> Result<(), Box<dyn Error>> {
         .timeout(std::time::Duration::from_secs(120))
         .build()?;
 
-    let response = client.get(&download_url).send()?;
-
-    if !response.status().is_success() {
-        return Err(format!("Download failed with status {}", response.status()).into());
-    }
-
-    let bytes = response.bytes()?;
-    let mut file = File::create(&temp_path)?;
-    file.write_all(&bytes)?;
-    drop(file);
+    let actual_checksum = download_release(&client, download_url, &temp_path, &info.version)?;
 
     // Verify checksum if available
-    if let Some(expected_checksum) = &cache.checksum {
-        print!("Verifying checksum...");
-        io::stdout().flush()?;
-
-        let actual_checksum = calculate_sha256(&temp_path)?;
-
+    if let Some(expected_checksum) = &info.checksum {
         if actual_checksum != *expected_checksum {
             fs::remove_file(&temp_path)?;
             return Err(format!(
@@ -330,11 +691,23 @@ pub fn perform_update(config: &Config) -> Result<(), Box<dyn Error>> {
             )
             .into());
         }
-        println!(" OK");
+        println!("Checksum verified.");
     } else {
         eprintln!("Warning: No checksum available, skipping verification");
     }
 
+    // Builds with a release key only install signed releases
+    if let Some(public_key) = RELEASE_PUBLIC_KEY.filter(|key| !key.trim().is_empty()) {
+        print!("Verifying signature...");
+        io::stdout().flush()?;
+        if let Err(e) = verify_release(&temp_path, info.signature_url.as_deref(), public_key) {
+            println!();
+            fs::remove_file(&temp_path)?;
+            return Err(e);
+        }
+        println!(" OK");
+    }
+
     // Make the new binary executable
     #[cfg(unix)]
     {
@@ -374,11 +747,7 @@ pub fn perform_update(config: &Config) -> Result<(), Box<dyn Error>> {
         }
     }
 
-    println!(
-        "Update complete! goto {} -> {}",
-        CURRENT_VERSION,
-        cache.latest_version.as_deref().unwrap_or("unknown")
-    );
+    println!("Update complete! goto {} -> {}", CURRENT_VERSION, info.version);
     println!("Restart your shell to use the new version.");
 
     Ok(())
@@ -408,18 +777,6 @@ pub fn current_version() -> &'static str {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::config::UserConfig;
-
-    /// Create a test config with a temp directory
-    fn test_config(temp_dir: &std::path::Path) -> Config {
-        Config {
-            database_path: temp_dir.to_path_buf(),
-            stack_path: temp_dir.join("goto_stack"),
-            config_path: temp_dir.join("config.toml"),
-            aliases_path: temp_dir.join("aliases.toml"),
-            user: UserConfig::default(),
-        }
-    }
 
     #[test]
     fn test_parse_version() {
@@ -447,6 +804,206 @@ mod tests {
         assert!(is_newer_version("v1.5.0", "v1.4.0"));
     }
 
+    #[test]
+    fn test_is_newer_version_prerelease() {
+        assert!(is_newer_version("1.3.0", "1.3.0-rc.1"));
+        assert!(is_newer_version("1.3.0-rc.2", "1.3.0-rc.1"));
+        assert!(is_newer_version("1.3.0-rc.1", "1.2.9"));
+        assert!(!is_newer_version("1.3.0-rc.1", "1.3.0"));
+        assert!(!is_newer_version("v1.3.0-beta", "1.3.0-beta"));
+    }
+
+    #[test]
+    fn test_update_channel_from_str() {
+        assert_eq!(UpdateChannel::from("stable"), UpdateChannel::Stable);
+        assert_eq!(UpdateChannel::from("prerelease"), UpdateChannel::Prerelease);
+        assert_eq!(UpdateChannel::from("PRERELEASE"), UpdateChannel::Prerelease);
+        assert_eq!(UpdateChannel::from("nightly"), UpdateChannel::Stable);
+    }
+
+    fn release(tag: &str, prerelease: bool, draft: bool) -> GitHubRelease {
+        GitHubRelease {
+            tag_name: tag.to_string(),
+            assets: Vec::new(),
+            prerelease,
+            draft,
+        }
+    }
+
+    #[test]
+    fn test_select_latest_release_stable_skips_prereleases() {
+        let releases = vec![
+            release("v1.2.0", false, false),
+            release("v1.3.0-rc.1", true, false),
+            release("v1.2.1", false, false),
+        ];
+        let latest = select_latest_release(releases, UpdateChannel::Stable).unwrap();
+        assert_eq!(latest.tag_name, "v1.2.1");
+    }
+
+    #[test]
+    fn test_select_latest_release_prerelease_channel() {
+        let releases = vec![
+            release("v1.2.1", false, false),
+            release("v1.3.0-rc.1", true, false),
+            release("v1.4.0", true, true), // drafts are never selected
+        ];
+        let latest = select_latest_release(releases, UpdateChannel::Prerelease).unwrap();
+        assert_eq!(latest.tag_name, "v1.3.0-rc.1");
+    }
+
+    #[test]
+    fn test_select_latest_release_empty() {
+        assert!(select_latest_release(Vec::new(), UpdateChannel::Stable).is_none());
+    }
+
+    #[test]
+    fn test_parse_checksum() {
+        let content = "abc123  goto-linux-amd64\ndef456  goto-linux-amd64.sig\n789fff *goto-darwin-arm64\n";
+        assert_eq!(parse_checksum(content, "goto-linux-amd64"), Some("abc123".to_string()));
+        assert_eq!(parse_checksum(content, "goto-darwin-arm64"), Some("789fff".to_string()));
+        assert_eq!(parse_checksum(content, "goto-windows-amd64"), None);
+    }
+
+    #[test]
+    fn test_format_bytes() {
+        assert_eq!(format_bytes(512), "512 B");
+        assert_eq!(format_bytes(1536), "1.5 KiB");
+        assert_eq!(format_bytes(5 * 1024 * 1024), "5.0 MiB");
+    }
+
+    #[test]
+    fn test_render_progress() {
+        let line = render_progress(1024, Some(2048));
+        assert!(line.starts_with(&format!("[{}{}]", "#".repeat(15), "-".repeat(15))));
+        assert!(line.contains(" 50%"));
+        assert!(line.ends_with("1.0 KiB / 2.0 KiB"));
+
+        assert_eq!(render_progress(2048, None), "2.0 KiB downloaded");
+    }
+
+    /// Serve `body` over HTTP for `requests` connections, honouring `Range: bytes=N-`
+    fn serve(body: &'static [u8], requests: usize) -> (String, std::thread::JoinHandle<Vec<String>>) {
+        use std::io::{BufRead, BufReader};
+        use std::net::TcpListener;
+
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}/goto-bin", listener.local_addr().unwrap());
+        let handle = std::thread::spawn(move || {
+            let mut ranges = Vec::new();
+            for stream in listener.incoming().take(requests) {
+                let mut stream = stream.unwrap();
+                let mut range = None;
+                for line in BufReader::new(&stream).lines() {
+                    let line = line.unwrap();
+                    if line.is_empty() {
+                        break;
+                    }
+                    if let Some(value) = line.to_lowercase().strip_prefix("range: bytes=") {
+                        range = value.trim_end_matches('-').parse::<usize>().ok();
+                    }
+                }
+                let (status, start) = match range {
+                    Some(start) => ("206 Partial Content", start),
+                    None => ("200 OK", 0),
+                };
+                ranges.push(start.to_string());
+                write!(
+                    stream,
+                    "HTTP/1.1 {}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+                    status,
+                    body.len() - start
+                )
+                .unwrap();
+                stream.write_all(&body[start..]).unwrap();
+            }
+            ranges
+        });
+        (url, handle)
+    }
+
+    fn local_client() -> reqwest::blocking::Client {
+        reqwest::blocking::Client::builder().no_proxy().build().unwrap()
+    }
+
+    #[test]
+    fn test_download_release_hashes_while_streaming() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let dest = temp_dir.path().join(".goto-bin.new");
+        let (url, server) = serve(b"abc", 1);
+
+        let client = local_client();
+        let checksum = download_release(&client, &url, &dest, "2.0.0").unwrap();
+
+        assert_eq!(checksum, "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad");
+        assert_eq!(fs::read(&dest).unwrap(), b"abc");
+        assert!(!dest.with_extension("new.version").exists());
+        assert_eq!(server.join().unwrap(), vec!["0"]);
+    }
+
+    #[test]
+    fn test_download_release_resumes_partial() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let dest = temp_dir.path().join(".goto-bin.new");
+        fs::write(&dest, "a").unwrap();
+        fs::write(dest.with_extension("new.version"), "2.0.0").unwrap();
+        let (url, server) = serve(b"abc", 1);
+
+        let client = local_client();
+        let checksum = download_release(&client, &url, &dest, "2.0.0").unwrap();
+
+        assert_eq!(checksum, "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad");
+        assert_eq!(fs::read(&dest).unwrap(), b"abc");
+        assert_eq!(server.join().unwrap(), vec!["1"]);
+    }
+
+    #[test]
+    fn test_download_release_ignores_partial_of_other_version() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let dest = temp_dir.path().join(".goto-bin.new");
+        fs::write(&dest, "x").unwrap();
+        fs::write(dest.with_extension("new.version"), "1.0.0").unwrap();
+        let (url, server) = serve(b"abc", 1);
+
+        let client = local_client();
+        download_release(&client, &url, &dest, "2.0.0").unwrap();
+
+        assert_eq!(fs::read(&dest).unwrap(), b"abc");
+        assert_eq!(server.join().unwrap(), vec!["0"]);
+    }
+
+    // Test vector from the minisign-verify crate
+    const TEST_PUBLIC_KEY: &str = "RWQf6LRCGA9i53mlYecO4IzT51TGPpvWucNSCh1CBM0QTaLn73Y7GFO3";
+    const TEST_SIGNATURE: &str = "untrusted comment: signature from minisign secret key
+RUQf6LRCGA9i559r3g7V1qNyJDApGip8MfqcadIgT9CuhV3EMhHoN1mGTkUidF/z7SrlQgXdy8ofjb7bNJJylDOocrCo8KLzZwo=
+trusted comment: timestamp:1556193335\tfile:test
+y/rUw2y8/hOUYjZU71eHp/Wo1KZ40fGy2VJEDl34XMJM+TX48Ss/17u3IvIfbVR1FkZZSNCisQbuQY+bHwhEBg==";
+
+    #[test]
+    fn test_verify_signature() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let path = temp_dir.path().join("binary");
+
+        fs::write(&path, "test").unwrap();
+        assert!(verify_signature(&path, TEST_SIGNATURE, TEST_PUBLIC_KEY).is_ok());
+
+        fs::write(&path, "tampered").unwrap();
+        let err = verify_signature(&path, TEST_SIGNATURE, TEST_PUBLIC_KEY).unwrap_err();
+        assert!(err.to_string().contains("signature verification failed"));
+
+        assert!(verify_signature(&path, "garbage", TEST_PUBLIC_KEY).is_err());
+    }
+
+    #[test]
+    fn test_verify_release_requires_signature() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let path = temp_dir.path().join("binary");
+        fs::write(&path, "test").unwrap();
+
+        let err = verify_release(&path, None, TEST_PUBLIC_KEY).unwrap_err();
+        assert!(err.to_string().contains("not signed"));
+    }
+
     #[test]
     fn test_update_cache_default() {
         let cache = UpdateCache::default();
@@ -462,6 +1019,8 @@ mod tests {
             latest_version: Some("1.5.0".to_string()),
             download_url: Some("https://example.com/binary".to_string()),
             checksum: Some("abc123".to_string()),
+            signature_url: None,
+            consecutive_failures: 0,
         };
 
         let json = serde_json::to_string(&cache).unwrap();
@@ -490,7 +1049,7 @@ mod tests {
     #[test]
     fn test_cache_path() {
         let temp_dir = tempfile::tempdir().unwrap();
-        let config = test_config(temp_dir.path());
+        let config = Config::in_dir(temp_dir.path());
         let path = cache_path(&config);
         assert_eq!(path, temp_dir.path().join("update_cache.json"));
     }
@@ -498,7 +1057,7 @@ mod tests {
     #[test]
     fn test_load_cache_nonexistent() {
         let temp_dir = tempfile::tempdir().unwrap();
-        let config = test_config(temp_dir.path());
+        let config = Config::in_dir(temp_dir.path());
 
         let cache = load_cache(&config);
         assert!(cache.latest_version.is_none());
@@ -507,13 +1066,15 @@ mod tests {
     #[test]
     fn test_save_and_load_cache() {
         let temp_dir = tempfile::tempdir().unwrap();
-        let config = test_config(temp_dir.path());
+        let config = Config::in_dir(temp_dir.path());
 
         let cache = UpdateCache {
             last_check: Utc::now(),
             latest_version: Some("2.0.0".to_string()),
             download_url: Some("https://example.com/download".to_string()),
             checksum: Some("sha256hash".to_string()),
+            signature_url: None,
+            consecutive_failures: 0,
         };
 
         save_cache(&config, &cache).unwrap();
@@ -527,7 +1088,7 @@ mod tests {
     #[test]
     fn test_load_cache_invalid_json() {
         let temp_dir = tempfile::tempdir().unwrap();
-        let config = test_config(temp_dir.path());
+        let config = Config::in_dir(temp_dir.path());
 
         // Write invalid JSON
         let cache_file = temp_dir.path().join("update_cache.json");
@@ -541,7 +1102,7 @@ mod tests {
     #[test]
     fn test_version_with_update_status_no_cache() {
         let temp_dir = tempfile::tempdir().unwrap();
-        let config = test_config(temp_dir.path());
+        let config = Config::in_dir(temp_dir.path());
 
         let version = version_with_update_status(&config);
         assert!(version.starts_with("goto version "));
@@ -551,7 +1112,7 @@ mod tests {
     #[test]
     fn test_version_with_update_status_with_update() {
         let temp_dir = tempfile::tempdir().unwrap();
-        let config = test_config(temp_dir.path());
+        let config = Config::in_dir(temp_dir.path());
 
         // Save a cache indicating a newer version
         let cache = UpdateCache {
@@ -559,6 +1120,8 @@ mod tests {
             latest_version: Some("99.0.0".to_string()), // Very high version
             download_url: None,
             checksum: None,
+            signature_url: None,
+            consecutive_failures: 0,
         };
         save_cache(&config, &cache).unwrap();
 
@@ -569,7 +1132,7 @@ mod tests {
     #[test]
     fn test_version_with_update_status_same_version() {
         let temp_dir = tempfile::tempdir().unwrap();
-        let config = test_config(temp_dir.path());
+        let config = Config::in_dir(temp_dir.path());
 
         // Save a cache with the current version
         let cache = UpdateCache {
@@ -577,6 +1140,8 @@ mod tests {
             latest_version: Some(CURRENT_VERSION.to_string()),
             download_url: None,
             checksum: None,
+            signature_url: None,
+            consecutive_failures: 0,
         };
         save_cache(&config, &cache).unwrap();
 
@@ -587,7 +1152,7 @@ mod tests {
     #[test]
     fn test_version_with_update_status_older_version() {
         let temp_dir = tempfile::tempdir().unwrap();
-        let config = test_config(temp_dir.path());
+        let config = Config::in_dir(temp_dir.path());
 
         // Save a cache with an older version (shouldn't happen, but test anyway)
         let cache = UpdateCache {
@@ -595,6 +1160,8 @@ mod tests {
             latest_version: Some("0.0.1".to_string()),
             download_url: None,
             checksum: None,
+            signature_url: None,
+            consecutive_failures: 0,
         };
         save_cache(&config, &cache).unwrap();
 
@@ -602,10 +1169,61 @@ mod tests {
         assert!(!version.contains("update available"));
     }
 
+    #[test]
+    fn test_check_interval_backoff() {
+        let mut cache = UpdateCache::default();
+        assert_eq!(cache.check_interval(24), Duration::hours(24));
+
+        cache.consecutive_failures = 1;
+        assert_eq!(cache.check_interval(24), Duration::hours(48));
+
+        cache.consecutive_failures = 2;
+        assert_eq!(cache.check_interval(24), Duration::hours(96));
+
+        // Capped at one week
+        cache.consecutive_failures = 10;
+        assert_eq!(cache.check_interval(24), Duration::hours(MAX_BACKOFF_HOURS));
+        cache.consecutive_failures = u32::MAX;
+        assert_eq!(cache.check_interval(24), Duration::hours(MAX_BACKOFF_HOURS));
+    }
+
+    #[test]
+    fn test_is_check_due_honours_backoff() {
+        let now = Utc::now();
+        let mut cache = UpdateCache {
+            last_check: now - Duration::hours(30),
+            ..Default::default()
+        };
+        assert!(cache.is_check_due(24, now));
+
+        cache.consecutive_failures = 1;
+        assert!(!cache.is_check_due(24, now));
+    }
+
+    #[test]
+    fn test_cache_without_failures_field_deserializes() {
+        let json = r#"{"last_check":"2024-01-01T00:00:00Z","latest_version":"1.0.0","download_url":null,"checksum":null}"#;
+        let cache: UpdateCache = serde_json::from_str(json).unwrap();
+        assert_eq!(cache.consecutive_failures, 0);
+    }
+
+    #[test]
+    fn test_check_for_updates_disabled() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let mut config = Config::in_dir(temp_dir.path());
+        config.user.update.enabled = false;
+
+        let result = check_for_updates(&config, true);
+        assert!(result.is_err());
+        assert!(result.unwrap_err().to_string().contains("disabled"));
+        // No cache is written when updates are disabled
+        assert!(!cache_path(&config).exists());
+    }
+
     #[test]
     fn test_notify_disabled_when_auto_check_false() {
         let temp_dir = tempfile::tempdir().unwrap();
-        let mut config = test_config(temp_dir.path());
+        let mut config = Config::in_dir(temp_dir.path());
         config.user.update.auto_check = false;
 
         // Save a cache indicating update available
@@ -614,10 +1232,14 @@ mod tests {
             latest_version: Some("99.0.0".to_string()),
             download_url: None,
             checksum: None,
+            signature_url: None,
+            consecutive_failures: 0,
         };
         save_cache(&config, &cache).unwrap();
 
         // Should not panic and return early (no notification when disabled)
-        notify_if_update_available(&config);
+        let mut err = Vec::new();
+        notify_if_update_available(&mut Ctx::new(io::sink(), &mut err), &config);
+        assert!(err.is_empty());
     }
 }
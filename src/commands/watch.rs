@@ -0,0 +1,342 @@
+//! Watch parent directories for new projects and suggest aliases for them
+//!
+//! Watched roots and the suggestion queue live in `watch.json` in the data
+//! directory. Subdirectories present when a root is added, or already
+//! offered once, are remembered so only new ones are suggested.
+
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
+use std::error::Error;
+use std::fs::{self, File};
+use std::io::{self, BufReader, IsTerminal};
+use std::path::{Path, PathBuf};
+
+use crate::alias::{validate_alias, Alias, AliasError};
+use crate::commands::register;
+use crate::config::{expand_path, Config};
+use crate::context::Ctx;
+use crate::database::Database;
+use crate::exit::Failure;
+use crate::table::{create_table, format_path, PathStyle, TableStyle};
+
+/// A new directory waiting to be registered or dismissed
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Suggestion {
+    /// Proposed alias name, derived from the directory name
+    pub name: String,
+    pub path: String,
+    pub found_at: DateTime<Utc>,
+    /// Whether the user has been told about it after navigating
+    #[serde(default)]
+    pub announced: bool,
+}
+
+/// Persisted watch state
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+struct WatchState {
+    #[serde(default)]
+    roots: Vec<String>,
+    /// Directories already known, never suggested (again)
+    #[serde(default)]
+    seen: BTreeSet<String>,
+    #[serde(default)]
+    pending: Vec<Suggestion>,
+    #[serde(default)]
+    last_scan: Option<DateTime<Utc>>,
+}
+
+/// Path of the watch state file
+fn state_path(config: &Config) -> PathBuf {
+    config.database_path.join("watch.json")
+}
+
+/// Load the watch state, starting empty if missing or unreadable
+fn load_state(config: &Config) -> WatchState {
+    File::open(state_path(config))
+        .ok()
+        .and_then(|file| serde_json::from_reader(BufReader::new(file)).ok())
+        .unwrap_or_default()
+}
+
+fn save_state(config: &Config, state: &WatchState) -> Result<(), Box<dyn Error>> {
+    config.ensure_dirs()?;
+    let file = File::create(state_path(config))?;
+    serde_json::to_writer_pretty(file, state)?;
+    Ok(())
+}
+
+/// Visible subdirectories of `root` that `[scan] ignore` doesn't skip, sorted
+fn subdirectories(root: &Path, config: &Config) -> Vec<String> {
+    let mut dirs: Vec<String> = fs::read_dir(root)
+        .map(|entries| {
+            entries
+                .filter_map(|e| e.ok())
+                .filter(|e| {
+                    let name = e.file_name().to_string_lossy().into_owned();
+                    !name.starts_with('.') && !config.user.scan.ignores(&name) && e.path().is_dir()
+                })
+                .map(|e| e.path().to_string_lossy().into_owned())
+                .collect()
+        })
+        .unwrap_or_default();
+    dirs.sort();
+    dirs
+}
+
+/// Alias name for a directory: invalid characters become `-`, and a numeric
+/// suffix is added while `taken` reports the name in use
+///
+/// Returns `None` if nothing usable is left (e.g. a name of only symbols).
+pub fn suggest_name(dir_name: &str, taken: impl Fn(&str) -> bool) -> Option<String> {
+    let cleaned: String = dir_name
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.' | '@') {
+                c
+            } else {
+                '-'
+            }
+        })
+        .collect();
+    let base = cleaned.trim_start_matches(|c: char| !c.is_ascii_alphanumeric());
+    validate_alias(base).ok()?;
+
+    if !taken(base) {
+        return Some(base.to_string());
+    }
+    (2..)
+        .map(|n| format!("{}_{}", base, n))
+        .find(|name| !taken(name))
+}
+
+/// Start watching `dir` for new subdirectories
+///
+/// Directories already inside it are not suggested. Returns the watched path
+/// and whether it was newly added.
+pub fn watch(config: &Config, dir: &str) -> Result<(String, bool), Box<dyn Error>> {
+    let path = expand_path(dir)?;
+    if !path.is_dir() {
+        return Err(AliasError::DirectoryNotFound(path.to_string_lossy().into_owned()).into());
+    }
+    let root = path.to_string_lossy().into_owned();
+
+    let mut state = load_state(config);
+    if state.roots.contains(&root) {
+        return Ok((root, false));
+    }
+    state.seen.extend(subdirectories(&path, config));
+    state.roots.push(root.clone());
+    save_state(config, &state)?;
+    Ok((root, true))
+}
+
+/// Stop watching `dir`, dropping its queued suggestions
+pub fn unwatch(config: &Config, dir: &str) -> Result<String, Box<dyn Error>> {
+    let root = expand_path(dir)?.to_string_lossy().into_owned();
+    let mut state = load_state(config);
+    let Some(pos) = state.roots.iter().position(|r| *r == root) else {
+        return Err(Failure::not_found(format!("watched directory '{}' not found", root)).into());
+    };
+    state.roots.remove(pos);
+    state.pending.retain(|s| Path::new(&s.path).parent() != Some(Path::new(&root)));
+    save_state(config, &state)?;
+    Ok(root)
+}
+
+/// Watched directories, in the order they were added
+pub fn roots(config: &Config) -> Vec<String> {
+    load_state(config).roots
+}
+
+/// Look for new subdirectories under the watched roots and queue them
+///
+/// Directories already registered are skipped. Returns the suggestions added
+/// by this scan.
+pub fn scan(config: &Config, db: &Database) -> Result<Vec<Suggestion>, Box<dyn Error>> {
+    let mut state = load_state(config);
+    let now = Utc::now();
+
+    let mut found = Vec::new();
+    for root in &state.roots {
+        for dir in subdirectories(Path::new(root), config) {
+            if state.seen.contains(&dir) || !db.names_at(Path::new(&dir)).is_empty() {
+                continue;
+            }
+            let dir_name = Path::new(&dir).file_name().unwrap_or_default().to_string_lossy().into_owned();
+            let taken = |name: &str| {
+                db.contains(name)
+                    || state.pending.iter().chain(&found).any(|s: &Suggestion| s.name == name)
+            };
+            if let Some(name) = suggest_name(&dir_name, taken) {
+                found.push(Suggestion {
+                    name,
+                    path: dir.clone(),
+                    found_at: now,
+                    announced: false,
+                });
+            }
+        }
+    }
+
+    state.seen.extend(found.iter().map(|s| s.path.clone()));
+    state.pending.extend(found.iter().cloned());
+    state.last_scan = Some(now);
+    save_state(config, &state)?;
+    Ok(found)
+}
+
+/// Queued suggestions whose directory still exists and isn't registered yet
+pub fn pending(config: &Config, db: &Database) -> Vec<Suggestion> {
+    load_state(config)
+        .pending
+        .into_iter()
+        .filter(|s| Path::new(&s.path).is_dir() && db.names_at(Path::new(&s.path)).is_empty())
+        .collect()
+}
+
+/// Offer each queued suggestion for registration
+///
+/// Accepted ones are registered under their suggested name; declined ones
+/// are dropped from the queue for good. Returns the aliases registered.
+pub fn review(ctx: &mut Ctx, config: &Config, db: &mut Database) -> Result<Vec<Alias>, Box<dyn Error>> {
+    let mut registered = Vec::new();
+    for suggestion in pending(config, db) {
+        let message = format!("Register '{}' -> {}?", suggestion.name, suggestion.path);
+        if ctx.confirm(&message, false)? {
+            registered.push(register::register(db, &suggestion.name, &suggestion.path)?);
+        }
+    }
+
+    let mut state = load_state(config);
+    state.pending.clear();
+    save_state(config, &state)?;
+    Ok(registered)
+}
+
+/// Render queued suggestions as a table
+pub fn format_suggestions(suggestions: &[Suggestion], config: &Config) -> String {
+    let style = TableStyle::from(config.user.display.table_style.as_str());
+    let path_style = PathStyle::from(config.user.display.path_style.as_str());
+    let mut table = create_table(style);
+    table.set_header(vec!["Name", "Path", "Found"]);
+    for suggestion in suggestions {
+        table.add_row(vec![
+            suggestion.name.clone(),
+            format_path(&suggestion.path, path_style),
+            suggestion.found_at.format("%Y-%m-%d %H:%M").to_string(),
+        ]);
+    }
+    table.to_string()
+}
+
+/// Scan when due and mention new suggestions once, on stderr
+///
+/// Called after navigating. Only speaks up on a terminal, so cron runs and
+/// scripts stay quiet.
+pub fn notify_if_suggestions(ctx: &mut Ctx, config: &Config, db: &Database) {
+    if ctx.quiet || !io::stderr().is_terminal() {
+        return;
+    }
+
+    let state = load_state(config);
+    if state.roots.is_empty() {
+        return;
+    }
+    let interval = Duration::minutes(config.user.watch.scan_interval_minutes as i64);
+    let due = state.last_scan.is_none_or(|last| Utc::now() - last >= interval);
+    if config.user.watch.auto_scan && due {
+        let _ = scan(config, db);
+    }
+
+    let mut state = load_state(config);
+    let fresh = state.pending.iter().filter(|s| !s.announced).count();
+    if fresh == 0 {
+        return;
+    }
+    crate::errln!(
+        ctx,
+        "Note: {} new director{} in watched folders. Run 'goto --suggestions' to review.",
+        fresh,
+        if fresh == 1 { "y" } else { "ies" }
+    );
+    for suggestion in &mut state.pending {
+        suggestion.announced = true;
+    }
+    let _ = save_state(config, &state);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::{tempdir, NamedTempFile};
+
+    #[test]
+    fn test_suggest_name() {
+        let none = |_: &str| false;
+        assert_eq!(suggest_name("my project", none).unwrap(), "my-project");
+        assert_eq!(suggest_name("_tmp", none).unwrap(), "tmp");
+        assert_eq!(suggest_name("!!!", none), None);
+
+        let taken = |name: &str| name == "api" || name == "api_2";
+        assert_eq!(suggest_name("api", taken).unwrap(), "api_3");
+    }
+
+    #[test]
+    fn test_scan_only_suggests_new_directories() {
+        let dir = tempdir().unwrap();
+        let config = Config::in_dir(dir.path());
+        let root = dir.path().join("src");
+        fs::create_dir_all(root.join("old")).unwrap();
+        fs::create_dir_all(root.join(".hidden")).unwrap();
+        let file = NamedTempFile::new().unwrap();
+        let mut db = Database::load_from_path(file.path()).unwrap();
+
+        let (watched, added) = watch(&config, root.to_str().unwrap()).unwrap();
+        assert!(added);
+        assert!(!watch(&config, &watched).unwrap().1);
+        assert!(scan(&config, &db).unwrap().is_empty());
+
+        fs::create_dir(root.join("new-app")).unwrap();
+        fs::create_dir(root.join("registered")).unwrap();
+        fs::create_dir(root.join("node_modules")).unwrap();
+        fs::create_dir(root.join("old.bak")).unwrap();
+        let registered = Path::new(&watched).join("registered");
+        db.insert(Alias::new("reg", registered.to_str().unwrap()).unwrap());
+
+        let found = scan(&config, &db).unwrap();
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].name, "new-app");
+        assert_eq!(pending(&config, &db), found);
+
+        // Already queued directories aren't found twice
+        assert!(scan(&config, &db).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_unwatch_drops_suggestions() {
+        let dir = tempdir().unwrap();
+        let config = Config::in_dir(dir.path());
+        let root = dir.path().join("src");
+        fs::create_dir_all(&root).unwrap();
+        let file = NamedTempFile::new().unwrap();
+        let db = Database::load_from_path(file.path()).unwrap();
+
+        let (watched, _) = watch(&config, root.to_str().unwrap()).unwrap();
+        fs::create_dir(root.join("app")).unwrap();
+        assert_eq!(scan(&config, &db).unwrap().len(), 1);
+
+        unwatch(&config, &watched).unwrap();
+        assert!(roots(&config).is_empty());
+        assert!(pending(&config, &db).is_empty());
+        assert!(unwatch(&config, &watched).is_err());
+    }
+
+    #[test]
+    fn test_watch_missing_directory() {
+        let dir = tempdir().unwrap();
+        let config = Config::in_dir(dir.path());
+        let missing = dir.path().join("missing");
+        assert!(watch(&config, missing.to_str().unwrap()).is_err());
+    }
+}
@@ -1,10 +1,24 @@
 //! Configuration loading and path handling
+//!
+//! Settings and data are kept apart, following the XDG base directories:
+//! `config.toml` lives in the config directory (`$XDG_CONFIG_HOME/goto`,
+//! `~/.config/goto`), while the alias database, its usage log, stacks and
+//! backups live in the data directory (`$XDG_DATA_HOME/goto`,
+//! `~/.local/share/goto`). `$GOTO_DB` puts both in one directory.
+//!
+//! Older versions kept everything in the config directory; loading moves
+//! those files over to the data directory (see [`DATA_FILES`]).
 
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 use std::fs;
-use std::path::PathBuf;
+use std::io;
+use std::path::{Component, Path, PathBuf};
 use thiserror::Error;
 
+use crate::rules::dynamic::DynamicRule;
+use crate::table::{ColorMode, DateStyle, PathTruncation, TableFit, TagStyle};
+
 /// Errors that can occur during configuration
 #[derive(Error, Debug)]
 pub enum ConfigError {
@@ -16,6 +30,9 @@ pub enum ConfigError {
 
     #[error("TOML parse error: {0}")]
     TomlParse(#[from] toml::de::Error),
+
+    #[error("invalid profile '{0}': use letters, digits, '-' and '_'")]
+    InvalidProfile(String),
 }
 
 /// General application settings
@@ -26,6 +43,50 @@ pub struct GeneralConfig {
 
     #[serde(default = "default_sort")]
     pub default_sort: String,
+
+    /// Store the canonical path when registering (false keeps symlinks)
+    #[serde(default = "default_resolve_symlinks")]
+    pub resolve_symlinks: bool,
+
+    /// Rank fuzzy suggestions sharing tags with the current directory's alias first
+    #[serde(default = "default_context_ranking")]
+    pub context_ranking: bool,
+
+    /// Allow `alias:name` to jump to a matching subdirectory of an alias
+    #[serde(default = "default_subdir_navigation")]
+    pub subdir_navigation: bool,
+
+    /// How many levels below the alias `alias:name` searches
+    #[serde(default = "default_subdir_depth")]
+    pub subdir_depth: usize,
+
+    /// Weight of use count and recency in fuzzy suggestion order (0 disables)
+    #[serde(default = "default_usage_weight")]
+    pub usage_weight: f64,
+
+    /// Read-only alias file merged under the user's aliases (empty disables)
+    #[serde(default = "default_shared_aliases")]
+    pub shared_aliases: String,
+
+    /// Picker for `--pick` and bare `goto`: "fzf" (when installed) or "builtin"
+    #[serde(default = "default_picker")]
+    pub picker: String,
+
+    /// Directories whose immediate subdirectories are offered for unknown names
+    #[serde(default)]
+    pub search_roots: Vec<String>,
+
+    /// How long a single alias path may take to answer a check, in milliseconds
+    #[serde(default = "default_path_timeout_ms")]
+    pub path_timeout_ms: u64,
+
+    /// Warn when writing the database takes longer than this, in milliseconds (0 disables)
+    #[serde(default = "default_slow_save_ms")]
+    pub slow_save_ms: u64,
+
+    /// Names no new alias may take, compared ignoring case (e.g. "help")
+    #[serde(default)]
+    pub reserved_names: Vec<String>,
 }
 
 fn default_fuzzy_threshold() -> f64 {
@@ -36,11 +97,58 @@ fn default_sort() -> String {
     "alpha".to_string()
 }
 
+fn default_resolve_symlinks() -> bool {
+    true
+}
+
+fn default_context_ranking() -> bool {
+    true
+}
+
+fn default_subdir_navigation() -> bool {
+    true
+}
+
+fn default_subdir_depth() -> usize {
+    2
+}
+
+fn default_shared_aliases() -> String {
+    "/etc/goto/aliases.toml".to_string()
+}
+
+fn default_usage_weight() -> f64 {
+    0.1
+}
+
+fn default_picker() -> String {
+    "fzf".to_string()
+}
+
+fn default_path_timeout_ms() -> u64 {
+    2000
+}
+
+fn default_slow_save_ms() -> u64 {
+    1000
+}
+
 impl Default for GeneralConfig {
     fn default() -> Self {
         Self {
             fuzzy_threshold: default_fuzzy_threshold(),
             default_sort: default_sort(),
+            resolve_symlinks: default_resolve_symlinks(),
+            context_ranking: default_context_ranking(),
+            subdir_navigation: default_subdir_navigation(),
+            subdir_depth: default_subdir_depth(),
+            usage_weight: default_usage_weight(),
+            shared_aliases: default_shared_aliases(),
+            picker: default_picker(),
+            search_roots: Vec::new(),
+            path_timeout_ms: default_path_timeout_ms(),
+            slow_save_ms: default_slow_save_ms(),
+            reserved_names: Vec::new(),
         }
     }
 }
@@ -56,6 +164,49 @@ pub struct DisplayConfig {
 
     #[serde(default = "default_table_style")]
     pub table_style: String,
+
+    /// How paths are rendered in listings: full, home, short
+    #[serde(default = "default_path_style")]
+    pub path_style: String,
+
+    /// How paths too long for the terminal are shortened: middle, end, off
+    #[serde(default = "default_truncate_paths")]
+    pub truncate_paths: String,
+
+    /// Show a glimpse of each candidate directory in fuzzy suggestions
+    #[serde(default)]
+    pub preview: bool,
+
+    /// Show timestamps as "3 days ago" instead of dates
+    #[serde(default = "default_relative_dates")]
+    pub relative_dates: bool,
+
+    /// Stat alias directories for the `-l` status column and `--sort=status`
+    #[serde(default = "default_check_paths")]
+    pub check_paths: bool,
+
+    /// Number the rows of `-l` for `goto %N`
+    #[serde(default)]
+    pub show_index: bool,
+
+    /// When to color output: auto, always, never
+    #[serde(default = "default_color")]
+    pub color: String,
+
+    /// Mark aliases whose directory is missing in tab completion
+    #[serde(default)]
+    pub completion_hints: bool,
+}
+
+impl DisplayConfig {
+    /// How timestamps are rendered, from `relative_dates`
+    pub fn date_style(&self) -> DateStyle {
+        if self.relative_dates {
+            DateStyle::Relative
+        } else {
+            DateStyle::Absolute
+        }
+    }
 }
 
 fn default_show_tags() -> bool {
@@ -66,12 +217,40 @@ fn default_table_style() -> String {
     "unicode".to_string()
 }
 
+fn default_path_style() -> String {
+    "full".to_string()
+}
+
+fn default_truncate_paths() -> String {
+    "middle".to_string()
+}
+
+fn default_relative_dates() -> bool {
+    true
+}
+
+fn default_check_paths() -> bool {
+    true
+}
+
+fn default_color() -> String {
+    "auto".to_string()
+}
+
 impl Default for DisplayConfig {
     fn default() -> Self {
         Self {
             show_stats: false,
             show_tags: true,
             table_style: default_table_style(),
+            path_style: default_path_style(),
+            truncate_paths: default_truncate_paths(),
+            preview: false,
+            relative_dates: default_relative_dates(),
+            check_paths: default_check_paths(),
+            show_index: false,
+            color: default_color(),
+            completion_hints: false,
         }
     }
 }
@@ -79,6 +258,10 @@ impl Default for DisplayConfig {
 /// Update settings
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct UpdateConfig {
+    /// Master switch for all update-related network activity
+    #[serde(default = "default_update_enabled")]
+    pub enabled: bool,
+
     /// Whether to automatically check for updates on startup
     #[serde(default = "default_auto_check")]
     pub auto_check: bool,
@@ -86,6 +269,14 @@ pub struct UpdateConfig {
     /// How often to check for updates (in hours)
     #[serde(default = "default_check_interval")]
     pub check_interval_hours: u64,
+
+    /// Release channel to follow: stable, prerelease
+    #[serde(default = "default_update_channel")]
+    pub channel: String,
+}
+
+fn default_update_enabled() -> bool {
+    true
 }
 
 fn default_auto_check() -> bool {
@@ -96,11 +287,17 @@ fn default_check_interval() -> u64 {
     24
 }
 
+fn default_update_channel() -> String {
+    "stable".to_string()
+}
+
 impl Default for UpdateConfig {
     fn default() -> Self {
         Self {
+            enabled: default_update_enabled(),
             auto_check: default_auto_check(),
             check_interval_hours: default_check_interval(),
+            channel: default_update_channel(),
         }
     }
 }
@@ -134,8 +331,323 @@ impl Default for PruneConfig {
     }
 }
 
+/// Directory stack settings
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StackConfig {
+    /// Keep a separate push/pop stack for each shell session
+    #[serde(default)]
+    pub per_session: bool,
+
+    /// Remove session stacks not touched for this long (in hours)
+    #[serde(default = "default_session_ttl")]
+    pub session_ttl_hours: u64,
+}
+
+fn default_session_ttl() -> u64 {
+    24 * 7
+}
+
+impl Default for StackConfig {
+    fn default() -> Self {
+        Self {
+            per_session: false,
+            session_ttl_hours: default_session_ttl(),
+        }
+    }
+}
+
+/// Alias database backup settings
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BackupConfig {
+    /// Keep a copy of the database before each change to the alias set
+    #[serde(default = "default_backup_enabled")]
+    pub enabled: bool,
+
+    /// Number of backups to keep (oldest are removed first)
+    #[serde(default = "default_backup_keep_count")]
+    pub keep_count: usize,
+
+    /// Backup directory (empty for `backups/` in the database directory)
+    #[serde(default)]
+    pub directory: String,
+}
+
+fn default_backup_enabled() -> bool {
+    true
+}
+
+fn default_backup_keep_count() -> usize {
+    10
+}
+
+impl Default for BackupConfig {
+    fn default() -> Self {
+        Self {
+            enabled: default_backup_enabled(),
+            keep_count: default_backup_keep_count(),
+            directory: String::new(),
+        }
+    }
+}
+
+/// Settings for `--clone`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProjectsConfig {
+    /// Directory repositories are cloned into, as `<root>/<host>/<org>/<repo>`
+    #[serde(default = "default_projects_root")]
+    pub root: String,
+
+    /// Tag cloned repositories with their owner (org or user) name
+    #[serde(default = "default_projects_auto_tags")]
+    pub auto_tags: bool,
+}
+
+fn default_projects_root() -> String {
+    "~/src".to_string()
+}
+
+fn default_projects_auto_tags() -> bool {
+    true
+}
+
+impl Default for ProjectsConfig {
+    fn default() -> Self {
+        Self {
+            root: default_projects_root(),
+            auto_tags: default_projects_auto_tags(),
+        }
+    }
+}
+
+/// Settings for watching directories for new projects (`--watch`)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WatchConfig {
+    /// Scan watched directories after navigating, at most once per interval
+    #[serde(default = "default_watch_auto_scan")]
+    pub auto_scan: bool,
+
+    /// Minimum time between automatic scans (in minutes)
+    #[serde(default = "default_watch_scan_interval")]
+    pub scan_interval_minutes: u64,
+}
+
+fn default_watch_auto_scan() -> bool {
+    true
+}
+
+fn default_watch_scan_interval() -> u64 {
+    60
+}
+
+impl Default for WatchConfig {
+    fn default() -> Self {
+        Self {
+            auto_scan: default_watch_auto_scan(),
+            scan_interval_minutes: default_watch_scan_interval(),
+        }
+    }
+}
+
+/// Directories left out when goto looks below a directory: `alias:subdir`,
+/// the search roots and `--watch` suggestions
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ScanConfig {
+    /// Directory names or `*`/`?` patterns to skip, with everything below them
+    #[serde(default = "default_scan_ignore")]
+    pub ignore: Vec<String>,
+}
+
+fn default_scan_ignore() -> Vec<String> {
+    ["node_modules", ".git", "target", "*.bak"].map(String::from).to_vec()
+}
+
+impl Default for ScanConfig {
+    fn default() -> Self {
+        Self {
+            ignore: default_scan_ignore(),
+        }
+    }
+}
+
+impl ScanConfig {
+    /// Whether a directory called `name` is skipped
+    pub fn ignores(&self, name: &str) -> bool {
+        self.ignore.iter().any(|pattern| crate::filter::glob_match(pattern, name))
+    }
+}
+
+/// Settings for flagging aliases nobody uses any more
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HygieneConfig {
+    /// Flag aliases unused for this many days as dormant (0 disables)
+    #[serde(default = "default_dormant_after_days")]
+    pub dormant_after_days: u64,
+
+    /// Mention dormant aliases after navigating, at most once a day
+    #[serde(default = "default_hygiene_hint")]
+    pub hint: bool,
+}
+
+fn default_dormant_after_days() -> u64 {
+    90
+}
+
+fn default_hygiene_hint() -> bool {
+    true
+}
+
+impl Default for HygieneConfig {
+    fn default() -> Self {
+        Self {
+            dormant_after_days: default_dormant_after_days(),
+            hint: default_hygiene_hint(),
+        }
+    }
+}
+
+/// Settings for what goto remembers about how it's used
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PrivacyConfig {
+    /// Record use counts and last-used times when navigating
+    #[serde(default = "default_track_usage")]
+    pub track_usage: bool,
+
+    /// Record entering and leaving aliases to estimate time spent in them
+    #[serde(default)]
+    pub track_time: bool,
+}
+
+fn default_track_usage() -> bool {
+    true
+}
+
+impl Default for PrivacyConfig {
+    fn default() -> Self {
+        Self {
+            track_usage: default_track_usage(),
+            track_time: false,
+        }
+    }
+}
+
+/// Guards against aliasing directories where a slip does real damage
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SafetyConfig {
+    /// Directories `--register` warns about; `~` is the whole home directory.
+    /// Only the directories themselves count, not what's below them
+    #[serde(default = "default_protected_paths")]
+    pub protected_paths: Vec<String>,
+
+    /// Refuse to register protected directories instead of warning
+    #[serde(default)]
+    pub strict: bool,
+}
+
+fn default_protected_paths() -> Vec<String> {
+    ["/", "/bin", "/boot", "/dev", "/etc", "/lib", "/proc", "/sbin", "/sys", "/usr", "/var", "~"]
+        .iter()
+        .map(|s| s.to_string())
+        .collect()
+}
+
+impl Default for SafetyConfig {
+    fn default() -> Self {
+        Self {
+            protected_paths: default_protected_paths(),
+            strict: false,
+        }
+    }
+}
+
+impl SafetyConfig {
+    /// The protected entry `path` is, if any
+    ///
+    /// Entries are expanded like alias paths and compared with symlinks
+    /// resolved on both sides, so `~/./` or a link to `/etc` still counts.
+    pub fn protects(&self, path: &Path) -> Option<&str> {
+        let target = std::fs::canonicalize(path).unwrap_or_else(|_| path.to_path_buf());
+        self.protected_paths
+            .iter()
+            .find(|p| expand_path(p).is_ok_and(|p| p == target))
+            .map(|p| p.as_str())
+    }
+}
+
+/// Aliases on removable or automounted drives (`[mounts]`)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MountsConfig {
+    /// Paths of drives that may not be mounted; aliases below them (and those
+    /// tagged `removable`) wait for their directory
+    #[serde(default)]
+    pub prefixes: Vec<String>,
+
+    /// Shell command offered when such a directory is missing (`GOTO_PATH` is set to it)
+    #[serde(default)]
+    pub command: String,
+
+    /// How long to wait for the directory to appear before failing
+    #[serde(default = "default_mount_wait_seconds")]
+    pub wait_seconds: u64,
+}
+
+fn default_mount_wait_seconds() -> u64 {
+    10
+}
+
+impl Default for MountsConfig {
+    fn default() -> Self {
+        Self {
+            prefixes: Vec::new(),
+            command: String::new(),
+            wait_seconds: default_mount_wait_seconds(),
+        }
+    }
+}
+
+/// Programs `--open` and `--code` hand an alias's directory to (`[integrations]`)
+///
+/// Commands are split on whitespace; `{path}` is replaced with the directory,
+/// which is appended when the command doesn't mention it.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct IntegrationsConfig {
+    /// File manager for `--open` ("" uses xdg-open, open on macOS, explorer on Windows)
+    #[serde(default)]
+    pub open: String,
+
+    /// Editor for `--code` ("" uses $VISUAL, then $EDITOR)
+    #[serde(default)]
+    pub editor: String,
+}
+
+/// Settings for the shell integration `--install` writes
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct InstallConfig {
+    /// Make `cd <alias>` fall back to goto when `<alias>` isn't a directory (bash, zsh)
+    #[serde(default)]
+    pub cd_fallback: bool,
+}
+
+/// A project template for `--new` (`[templates.<name>]`)
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ProjectTemplate {
+    /// Directory whose contents are copied into the new project
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub skeleton: Option<String>,
+
+    /// Shell command run inside the new project, after the skeleton is copied
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub init: Option<String>,
+
+    /// Tags given to the new alias (default: the template name)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tags: Option<Vec<String>>,
+}
+
 /// User-configurable settings loaded from TOML
+///
+/// New sections may be added in minor releases; start from the default.
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[non_exhaustive]
 pub struct UserConfig {
     #[serde(default)]
     pub general: GeneralConfig,
@@ -148,12 +660,61 @@ pub struct UserConfig {
 
     #[serde(default)]
     pub prune: PruneConfig,
+
+    #[serde(default)]
+    pub stack: StackConfig,
+
+    #[serde(default)]
+    pub backup: BackupConfig,
+
+    #[serde(default)]
+    pub projects: ProjectsConfig,
+
+    #[serde(default)]
+    pub watch: WatchConfig,
+
+    #[serde(default)]
+    pub scan: ScanConfig,
+
+    #[serde(default)]
+    pub hygiene: HygieneConfig,
+
+    #[serde(default)]
+    pub privacy: PrivacyConfig,
+
+    #[serde(default)]
+    pub safety: SafetyConfig,
+
+    #[serde(default)]
+    pub install: InstallConfig,
+
+    #[serde(default)]
+    pub mounts: MountsConfig,
+
+    #[serde(default)]
+    pub integrations: IntegrationsConfig,
+
+    /// Dynamic alias rules (`[[rules]]`), tried in order
+    #[serde(default)]
+    pub rules: Vec<DynamicRule>,
+
+    /// Project templates for `--new`, by name
+    #[serde(default)]
+    pub templates: BTreeMap<String, ProjectTemplate>,
+
+    /// Tag colors (`[tag_colors]`, e.g. `work = "blue"`)
+    #[serde(default)]
+    pub tag_colors: BTreeMap<String, String>,
 }
 
 /// Application configuration
+///
+/// New fields may be added in minor releases; build one with
+/// [`Config::load`], [`Config::load_profile`] or [`Config::in_dir`].
 #[derive(Debug, Clone)]
+#[non_exhaustive]
 pub struct Config {
-    /// Path to the database directory (contains aliases, config, stack)
+    /// Path to the data directory (aliases, stack, caches, backups)
     pub database_path: PathBuf,
     /// Path to the directory stack file
     pub stack_path: PathBuf,
@@ -161,23 +722,65 @@ pub struct Config {
     pub config_path: PathBuf,
     /// Path to the aliases database file
     pub aliases_path: PathBuf,
+    /// Active profile (`--profile` / `GOTO_PROFILE`), if any
+    pub profile: Option<String>,
     /// User configuration loaded from config.toml
     pub user: UserConfig,
+    /// What loading did that the user should hear about, such as data moved
+    /// out of the config directory; for the caller to show
+    pub notices: Vec<String>,
 }
 
 impl Config {
     /// Load configuration from environment and defaults
     pub fn load() -> Result<Self, ConfigError> {
-        let base_path = get_database_path()?;
+        Self::load_profile(std::env::var("GOTO_PROFILE").ok().as_deref())
+    }
+
+    /// Load configuration for a profile (`None` for the default one)
+    ///
+    /// A profile keeps its own config.toml and alias database in
+    /// `profiles/<name>/` below the config and data directories.
+    pub fn load_profile(profile: Option<&str>) -> Result<Self, ConfigError> {
+        let mut base_path = get_database_path()?;
+        // Data files are only ever migrated out of the standard config
+        // directory, never out of one GOTO_CONFIG points into
+        let legacy_dir = get_config_dir()?;
+        let mut notices = Vec::new();
+        if legacy_dir != base_path {
+            let moved = migrate_data_files(&legacy_dir, &base_path)?;
+            if !moved.is_empty() {
+                notices.push(format!(
+                    "Moved goto data ({}) from {} to {}",
+                    moved.join(", "),
+                    legacy_dir.display(),
+                    base_path.display()
+                ));
+            }
+        }
+        let config_path = config_file(profile)?;
+
+        let profile = profile.filter(|p| !p.is_empty()).map(String::from);
+        if let Some(name) = &profile {
+            base_path = base_path.join(PROFILES_DIR).join(name);
+        }
+        let config_dir = config_path.parent().map(Path::to_path_buf).unwrap_or_default();
 
-        let config_path = base_path.join("config.toml");
         let stack_path = base_path.join("goto_stack");
         let aliases_path = base_path.join("aliases.toml");
 
+        crate::debug!(
+            "data directory {}, config directory {}{}",
+            base_path.display(),
+            config_dir.display(),
+            profile.as_deref().map(|p| format!(", profile '{}'", p)).unwrap_or_default()
+        );
         let user = if config_path.exists() {
+            crate::info!("reading config {}", config_path.display());
             let content = fs::read_to_string(&config_path)?;
             toml::from_str(&content)?
         } else {
+            crate::debug!("no config at {}, using defaults", config_path.display());
             UserConfig::default()
         };
 
@@ -186,13 +789,99 @@ impl Config {
             stack_path,
             config_path,
             aliases_path,
+            profile,
             user,
+            notices,
         })
     }
 
-    /// Ensure the config directory exists
+    /// Default settings with every file in `dir`, as with `$GOTO_DB`
+    ///
+    /// No config.toml is read and nothing is created or migrated.
+    pub fn in_dir(dir: &Path) -> Self {
+        Config {
+            database_path: dir.to_path_buf(),
+            stack_path: dir.join("goto_stack"),
+            config_path: dir.join("config.toml"),
+            aliases_path: dir.join("aliases.toml"),
+            profile: None,
+            user: UserConfig::default(),
+            notices: Vec::new(),
+        }
+    }
+
+    /// Directory holding database backups
+    pub fn backup_dir(&self) -> Result<PathBuf, ConfigError> {
+        let dir = self.user.backup.directory.trim();
+        if dir.is_empty() {
+            Ok(self.database_path.join("backups"))
+        } else {
+            expand_path(dir)
+        }
+    }
+
+    /// How tables are fitted to the terminal, unless `wide` (`--wide`)
+    pub fn table_fit(&self, wide: bool) -> TableFit {
+        TableFit::new(PathTruncation::from(self.user.display.truncate_paths.as_str()), wide)
+    }
+
+    /// How to render tags in output going to a terminal (`is_terminal`) or not
+    pub fn tag_style(&self, is_terminal: bool) -> TagStyle<'_> {
+        if ColorMode::from(self.user.display.color.as_str()).enabled(is_terminal) {
+            TagStyle::Colored(&self.user.tag_colors)
+        } else {
+            TagStyle::Plain
+        }
+    }
+
+    /// Directory holding each session's temporary aliases (`-r --temp`)
+    pub fn temp_dir(&self) -> PathBuf {
+        self.database_path.join("temp")
+    }
+
+    /// Directory holding the alias tables of remote hosts (`--ssh`)
+    pub fn hosts_dir(&self) -> PathBuf {
+        self.database_path.join("hosts")
+    }
+
+    /// Read-only shared alias file, if one is configured
+    pub fn shared_aliases_path(&self) -> Result<Option<PathBuf>, ConfigError> {
+        let path = self.user.general.shared_aliases.trim();
+        if path.is_empty() {
+            Ok(None)
+        } else {
+            expand_path(path).map(Some)
+        }
+    }
+
+    /// Directory `--clone` places repositories under
+    pub fn projects_root(&self) -> Result<PathBuf, ConfigError> {
+        expand_path(self.user.projects.root.trim())
+    }
+
+    /// How long an alias goes unused before it's dormant, if flagging is enabled
+    ///
+    /// Without usage tracking nothing is ever used, so nothing is flagged.
+    pub fn dormant_after(&self) -> Option<chrono::Duration> {
+        let days = self.user.hygiene.dormant_after_days;
+        (days > 0 && self.user.privacy.track_usage).then(|| chrono::Duration::days(days as i64))
+    }
+
+    /// How long a database save may take before goto warns, if it warns at all
+    pub fn slow_save(&self) -> Option<std::time::Duration> {
+        let ms = self.user.general.slow_save_ms;
+        (ms > 0).then(|| std::time::Duration::from_millis(ms))
+    }
+
+    /// Directory holding config.toml
+    pub fn config_dir(&self) -> &Path {
+        self.config_path.parent().unwrap_or(&self.database_path)
+    }
+
+    /// Ensure the data and config directories exist
     pub fn ensure_dirs(&self) -> Result<(), ConfigError> {
         fs::create_dir_all(&self.database_path)?;
+        fs::create_dir_all(self.config_dir())?;
         Ok(())
     }
 
@@ -207,19 +896,103 @@ impl Config {
         let default_config = r#"[general]
 fuzzy_threshold = 0.6
 default_sort = "alpha"  # alpha, usage, recent
+resolve_symlinks = true # false stores symlinked paths as given
+context_ranking = true  # Prefer suggestions sharing tags with the current alias
+subdir_navigation = true  # goto alias:name jumps to a matching subdirectory
+subdir_depth = 2        # Levels searched below the alias for alias:name
+usage_weight = 0.1      # How much use count and recency reorder suggestions (0-1)
+shared_aliases = "/etc/goto/aliases.toml"  # Read-only team aliases ("" disables)
+picker = "fzf"          # fzf (falls back to builtin when not installed), builtin
+search_roots = []       # e.g. ["~/src", "~/work"]: offer <root>/<name> for unknown names
+path_timeout_ms = 2000  # Per-path limit for -l status and --cleanup checks (network mounts)
+slow_save_ms = 1000     # Warn when saving the database takes longer (0 = never)
+reserved_names = []     # e.g. ["help", "tmp"]: names no alias may take
 
 [display]
 show_stats = false
 show_tags = true
 table_style = "unicode"  # unicode, ascii, minimal
+path_style = "full"      # full, home (~ for $HOME), short (~/p/w/api)
+truncate_paths = "middle" # Fit paths to the terminal: middle, end, off (-l --wide shows them whole)
+preview = false          # Show directory contents and git branch in suggestions
+relative_dates = true    # "3 days ago"; false shows 2024-05-01 14:30 (UTC)
+check_paths = true       # Status column in -l; false for slow network filesystems
+show_index = false       # Number the rows of -l; goto %3 jumps to the third
+color = "auto"           # auto (terminals, unless NO_COLOR is set), always, never
+completion_hints = false # Mark aliases whose directory is missing when completing (! in bash)
 
 [update]
+enabled = true          # Set to false to disable all network activity
 auto_check = true       # Check for updates automatically
 check_interval_hours = 24
+channel = "stable"      # stable, prerelease
 
 [prune]
 auto_check = true        # Show notification when stale aliases exist
 check_interval_hours = 24
+
+[stack]
+per_session = false      # Separate push/pop stack per shell session
+session_ttl_hours = 168  # Forget session stacks unused for this long
+
+[backup]
+enabled = true           # Back up aliases before changes to the alias set
+keep_count = 10          # Number of backups to keep
+directory = ""           # Defaults to backups/ in the database directory
+
+[projects]
+root = "~/src"           # goto --clone puts repositories in <root>/<host>/<org>/<repo>
+auto_tags = true         # Tag cloned repositories with their owner name
+
+[watch]
+auto_scan = true         # Look for new directories under --watch roots after navigating
+scan_interval_minutes = 60
+
+[scan]
+# Directory names and patterns alias:subdir, search roots and --watch skip
+ignore = ["node_modules", ".git", "target", "*.bak"]
+
+[hygiene]
+dormant_after_days = 90  # Flag aliases unused this long for goto --review (0 disables)
+hint = true              # Mention dormant aliases after navigating (once a day)
+
+[privacy]
+track_usage = true       # Record use counts and last-used times (false hides them)
+track_time = false       # Estimate time spent in each alias for --stats (bash, zsh, fish)
+
+[safety]
+# --register warns about these directories (not what's below them); ~ is your home
+protected_paths = ["/", "/bin", "/boot", "/dev", "/etc", "/lib", "/proc", "/sbin", "/sys", "/usr", "/var", "~"]
+strict = false           # Refuse them instead of warning (--force still registers)
+
+[install]
+cd_fallback = false      # bash/zsh: cd <alias> enters the alias (rerun goto --install)
+
+[mounts]
+prefixes = []            # e.g. ["/media/usb"]: aliases below wait for the drive, like tag "removable"
+command = ""             # e.g. "udisksctl mount -b /dev/sdb1": offered when the directory is missing
+wait_seconds = 10        # How long to wait for the directory to appear
+
+[integrations]
+open = ""                # File manager for --open, e.g. "nautilus {path}" ("" uses xdg-open/open/explorer)
+editor = ""              # Editor for --code, e.g. "code -n" ("" uses $VISUAL, then $EDITOR)
+
+# Dynamic aliases: goto gh:<org>/<repo>
+# [[rules]]
+# pattern = "gh:(.+)/(.+)"
+# path = "~/src/github.com/$1/$2"
+# clone = "https://github.com/$1/$2.git"  # Optional: clone when missing
+
+# Tag colors in -l, --search and the picker; other tags get a color from their name
+# [tag_colors]
+# work = "blue"                  # black, red, green, yellow, blue, magenta, cyan,
+# urgent = "dark_red"            # white, grey, and dark_ variants of them
+
+# Project templates: goto --new <alias> <path> --template=rust
+# [templates.rust]
+# skeleton = "~/templates/rust"  # Optional: directory copied into the project
+# init = "cargo init"            # Optional: command run inside the project
+# tags = ["rust"]                # Optional: defaults to the template name
 "#;
 
         fs::write(&self.config_path, default_config)?;
@@ -228,61 +1001,373 @@ check_interval_hours = 24
 
     /// Format the current configuration as a string
     pub fn format_config(&self) -> String {
-        format!(
+        let mut out = match &self.profile {
+            Some(name) => format!("Profile: {}\n", name),
+            None => String::new(),
+        };
+        out.push_str(&format!(
             "Configuration file: {}\n\n\
              [general]\n\
              fuzzy_threshold = {:.1}\n\
-             default_sort = \"{}\"\n\n\
+             default_sort = \"{}\"\n\
+             resolve_symlinks = {}\n\
+             context_ranking = {}\n\
+             subdir_navigation = {}\n\
+             subdir_depth = {}\n\
+             usage_weight = {:.2}\n\
+             shared_aliases = \"{}\"\n\
+             picker = \"{}\"\n\
+             search_roots = [{}]\n\
+             path_timeout_ms = {}\n\
+             slow_save_ms = {}\n\
+             reserved_names = [{}]\n\n\
              [display]\n\
              show_stats = {}\n\
              show_tags = {}\n\
-             table_style = \"{}\"\n\n\
+             table_style = \"{}\"\n\
+             path_style = \"{}\"\n\
+             truncate_paths = \"{}\"\n\
+             preview = {}\n\
+             relative_dates = {}\n\
+             check_paths = {}\n\
+             show_index = {}\n\
+             color = \"{}\"\n\
+             completion_hints = {}\n\n\
              [update]\n\
+             enabled = {}\n\
              auto_check = {}\n\
-             check_interval_hours = {}\n\n\
+             check_interval_hours = {}\n\
+             channel = \"{}\"\n\n\
              [prune]\n\
              auto_check = {}\n\
-             check_interval_hours = {}\n",
+             check_interval_hours = {}\n\n\
+             [stack]\n\
+             per_session = {}\n\
+             session_ttl_hours = {}\n\n\
+             [backup]\n\
+             enabled = {}\n\
+             keep_count = {}\n\
+             directory = \"{}\"\n\n\
+             [projects]\n\
+             root = \"{}\"\n\
+             auto_tags = {}\n\n\
+             [watch]\n\
+             auto_scan = {}\n\
+             scan_interval_minutes = {}\n\n\
+             [scan]\n\
+             ignore = [{}]\n\n\
+             [hygiene]\n\
+             dormant_after_days = {}\n\
+             hint = {}\n\n\
+             [privacy]\n\
+             track_usage = {}\n\
+             track_time = {}\n\n\
+             [safety]\n\
+             protected_paths = [{}]\n\
+             strict = {}\n\n\
+             [install]\n\
+             cd_fallback = {}\n\n\
+             [mounts]\n\
+             prefixes = [{}]\n\
+             command = \"{}\"\n\
+             wait_seconds = {}\n\n\
+             [integrations]\n\
+             open = \"{}\"\n\
+             editor = \"{}\"\n",
             self.config_path.display(),
             self.user.general.fuzzy_threshold,
             self.user.general.default_sort,
+            self.user.general.resolve_symlinks,
+            self.user.general.context_ranking,
+            self.user.general.subdir_navigation,
+            self.user.general.subdir_depth,
+            self.user.general.usage_weight,
+            self.user.general.shared_aliases,
+            self.user.general.picker,
+            quote_list(&self.user.general.search_roots),
+            self.user.general.path_timeout_ms,
+            self.user.general.slow_save_ms,
+            quote_list(&self.user.general.reserved_names),
             self.user.display.show_stats,
             self.user.display.show_tags,
             self.user.display.table_style,
+            self.user.display.path_style,
+            self.user.display.truncate_paths,
+            self.user.display.preview,
+            self.user.display.relative_dates,
+            self.user.display.check_paths,
+            self.user.display.show_index,
+            self.user.display.color,
+            self.user.display.completion_hints,
+            self.user.update.enabled,
             self.user.update.auto_check,
             self.user.update.check_interval_hours,
+            self.user.update.channel,
             self.user.prune.auto_check,
             self.user.prune.check_interval_hours,
-        )
+            self.user.stack.per_session,
+            self.user.stack.session_ttl_hours,
+            self.user.backup.enabled,
+            self.user.backup.keep_count,
+            self.user.backup.directory,
+            self.user.projects.root,
+            self.user.projects.auto_tags,
+            self.user.watch.auto_scan,
+            self.user.watch.scan_interval_minutes,
+            quote_list(&self.user.scan.ignore),
+            self.user.hygiene.dormant_after_days,
+            self.user.hygiene.hint,
+            self.user.privacy.track_usage,
+            self.user.privacy.track_time,
+            quote_list(&self.user.safety.protected_paths),
+            self.user.safety.strict,
+            self.user.install.cd_fallback,
+            quote_list(&self.user.mounts.prefixes),
+            self.user.mounts.command,
+            self.user.mounts.wait_seconds,
+            self.user.integrations.open,
+            self.user.integrations.editor,
+        ));
+
+        for rule in &self.user.rules {
+            out.push_str(&format!(
+                "\n[[rules]]\npattern = \"{}\"\npath = \"{}\"\n",
+                rule.pattern, rule.path
+            ));
+            if let Some(clone) = &rule.clone {
+                out.push_str(&format!("clone = \"{}\"\n", clone));
+            }
+        }
+        if !self.user.tag_colors.is_empty() {
+            out.push_str("\n[tag_colors]\n");
+            for (tag, color) in &self.user.tag_colors {
+                out.push_str(&format!("{} = \"{}\"\n", tag, color));
+            }
+        }
+        for (name, template) in &self.user.templates {
+            out.push_str(&format!("\n[templates.{}]\n", name));
+            if let Some(skeleton) = &template.skeleton {
+                out.push_str(&format!("skeleton = \"{}\"\n", skeleton));
+            }
+            if let Some(init) = &template.init {
+                out.push_str(&format!("init = \"{}\"\n", init));
+            }
+            if let Some(tags) = &template.tags {
+                out.push_str(&format!("tags = [{}]\n", quote_list(tags)));
+            }
+        }
+        out
+    }
+}
+
+/// `"a", "b"` for the items of a TOML string array
+fn quote_list(items: &[String]) -> String {
+    items.iter().map(|i| format!("\"{}\"", i)).collect::<Vec<_>>().join(", ")
+}
+
+/// Directory below the config and data directories holding profiles
+const PROFILES_DIR: &str = "profiles";
+
+/// Check that a profile name is usable as a directory name
+pub fn validate_profile_name(name: &str) -> Result<(), ConfigError> {
+    let valid = !name.is_empty()
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_');
+    if valid {
+        Ok(())
+    } else {
+        Err(ConfigError::InvalidProfile(name.to_string()))
+    }
+}
+
+/// The config.toml of a profile (`None` for the default one)
+pub fn config_file(profile: Option<&str>) -> Result<PathBuf, ConfigError> {
+    let path = get_config_path()?;
+    match profile.filter(|p| !p.is_empty()) {
+        Some(name) => {
+            validate_profile_name(name)?;
+            Ok(profiles_root(&path).join(name).join("config.toml"))
+        }
+        None => Ok(path),
+    }
+}
+
+/// Names of all profiles with a config or data directory, sorted
+pub fn profile_names() -> Result<Vec<String>, ConfigError> {
+    let mut names = Vec::new();
+    for root in [profiles_root(&get_config_path()?), get_database_path()?.join(PROFILES_DIR)] {
+        let Ok(entries) = fs::read_dir(root) else {
+            continue;
+        };
+        for entry in entries.filter_map(|e| e.ok()) {
+            let name = entry.file_name().to_string_lossy().into_owned();
+            if entry.path().is_dir() && validate_profile_name(&name).is_ok() && !names.contains(&name) {
+                names.push(name);
+            }
+        }
+    }
+    names.sort();
+    Ok(names)
+}
+
+/// Files and directories kept in the data directory
+///
+/// Before the config/data split all of these lived next to config.toml.
+pub const DATA_FILES: &[&str] = &[
+    "aliases.toml",
+    "goto_stack",
+    "stacks",
+    "goto_previous",
+    "previous",
+    "temp",
+    "backups",
+    "update_cache.json",
+    "prune_cache.json",
+];
+
+/// The directories `--uninstall --purge` may delete
+///
+/// Only the standard data and config directories qualify, which goto names
+/// and fills itself. A directory `$GOTO_DB` points at, or the one holding a
+/// `$GOTO_CONFIG` file, may be shared with other files and is never purged.
+pub fn purge_dirs() -> Result<Vec<PathBuf>, ConfigError> {
+    if std::env::var("GOTO_DB").is_ok() {
+        return Ok(Vec::new());
     }
+    Ok(vec![get_database_path()?, get_config_dir()?])
 }
 
-/// Get the database path based on priority:
+/// Get the data directory based on priority:
 /// 1. $GOTO_DB environment variable
-/// 2. $XDG_CONFIG_HOME/goto
-/// 3. ~/.config/goto
+/// 2. $XDG_DATA_HOME/goto
+/// 3. ~/.local/share/goto
 fn get_database_path() -> Result<PathBuf, ConfigError> {
     // Check GOTO_DB env var first
     if let Ok(path) = std::env::var("GOTO_DB") {
         return Ok(PathBuf::from(path));
     }
 
-    // Check XDG_CONFIG_HOME
-    if let Ok(xdg) = std::env::var("XDG_CONFIG_HOME") {
-        return Ok(PathBuf::from(xdg).join("goto"));
+    // Check XDG_DATA_HOME
+    if let Ok(xdg) = std::env::var("XDG_DATA_HOME") {
+        return Ok(PathBuf::from(xdg).join("goto"));
+    }
+
+    // Default to ~/.local/share/goto
+    dirs::home_dir()
+        .map(|h| h.join(".local").join("share").join("goto"))
+        .ok_or(ConfigError::NoHomeDir)
+}
+
+/// Get the directory holding config.toml based on priority:
+/// 1. $GOTO_DB environment variable
+/// 2. $XDG_CONFIG_HOME/goto
+/// 3. ~/.config/goto
+fn get_config_dir() -> Result<PathBuf, ConfigError> {
+    if let Ok(path) = std::env::var("GOTO_DB") {
+        return Ok(PathBuf::from(path));
+    }
+
+    if let Ok(xdg) = std::env::var("XDG_CONFIG_HOME") {
+        return Ok(PathBuf::from(xdg).join("goto"));
+    }
+
+    dirs::home_dir()
+        .map(|h| h.join(".config").join("goto"))
+        .ok_or(ConfigError::NoHomeDir)
+}
+
+/// Get the default profile's config file based on priority:
+/// 1. $GOTO_CONFIG (the file itself, independent of the data directory)
+/// 2. config.toml in the config directory (see [`get_config_dir`])
+fn get_config_path() -> Result<PathBuf, ConfigError> {
+    match std::env::var("GOTO_CONFIG") {
+        Ok(path) if !path.is_empty() => Ok(PathBuf::from(path)),
+        _ => Ok(get_config_dir()?.join("config.toml")),
+    }
+}
+
+/// Directory holding the profiles' config directories: `profiles/` next to
+/// the default profile's config file
+fn profiles_root(config_path: &Path) -> PathBuf {
+    config_path
+        .parent()
+        .map(Path::to_path_buf)
+        .unwrap_or_default()
+        .join(PROFILES_DIR)
+}
+
+/// Move data files left in the config directory by older versions
+///
+/// Entries already present in the data directory are never overwritten, so
+/// once everything has moved this is a no-op. Returns the names moved.
+fn migrate_data_files(config_dir: &Path, data_dir: &Path) -> Result<Vec<&'static str>, ConfigError> {
+    let pending: Vec<&'static str> = DATA_FILES
+        .iter()
+        .copied()
+        .filter(|name| {
+            let old = config_dir.join(name);
+            old.symlink_metadata().is_ok() && data_dir.join(name).symlink_metadata().is_err()
+        })
+        .collect();
+    if pending.is_empty() {
+        return Ok(pending);
+    }
+
+    fs::create_dir_all(data_dir)?;
+    for name in &pending {
+        move_entry(&config_dir.join(name), &data_dir.join(name))?;
     }
+    Ok(pending)
+}
 
-    // Default to ~/.config/goto
-    dirs::home_dir()
-        .map(|h| h.join(".config").join("goto"))
-        .ok_or(ConfigError::NoHomeDir)
+/// Rename a file or directory, copying when it crosses filesystems
+fn move_entry(from: &Path, to: &Path) -> io::Result<()> {
+    if fs::rename(from, to).is_ok() {
+        return Ok(());
+    }
+    if from.is_dir() {
+        copy_dir(from, to)?;
+        fs::remove_dir_all(from)
+    } else {
+        fs::copy(from, to)?;
+        fs::remove_file(from)
+    }
+}
+
+/// Recursively copy a directory's contents, creating `to` as needed
+pub(crate) fn copy_dir(from: &Path, to: &Path) -> io::Result<()> {
+    fs::create_dir_all(to)?;
+    for entry in fs::read_dir(from)? {
+        let entry = entry?;
+        let target = to.join(entry.file_name());
+        if entry.file_type()?.is_dir() {
+            copy_dir(&entry.path(), &target)?;
+        } else {
+            fs::copy(entry.path(), target)?;
+        }
+    }
+    Ok(())
 }
 
 /// Expand ~, environment variables, and convert to absolute path
+///
+/// Relative paths (including `.` and `../sibling`) are resolved against the
+/// current working directory.
 pub fn expand_path(path: &str) -> Result<PathBuf, ConfigError> {
-    let expanded = if path.starts_with('~') {
+    expand_path_with(path, true)
+}
+
+/// Expand a path, optionally keeping symlinks in place
+///
+/// With `resolve_symlinks` the path is canonicalized. Without it, `.` and
+/// `..` are removed lexically and relative paths are joined to the shell's
+/// logical working directory (`$PWD`), so `~/current -> release-42` stays
+/// `~/current`.
+pub fn expand_path_with(path: &str, resolve_symlinks: bool) -> Result<PathBuf, ConfigError> {
+    let expanded = if let Some(rest) = path.strip_prefix('~') {
         let home = dirs::home_dir().ok_or(ConfigError::NoHomeDir)?;
-        let rest = path[1..].trim_start_matches('/');
+        let rest = rest.trim_start_matches('/');
         if rest.is_empty() {
             home
         } else {
@@ -292,8 +1377,50 @@ pub fn expand_path(path: &str) -> Result<PathBuf, ConfigError> {
         PathBuf::from(shellexpand::env(path).unwrap_or(path.into()).into_owned())
     };
 
-    // Try to canonicalize, but fall back to the expanded path if it doesn't exist
-    Ok(std::fs::canonicalize(&expanded).unwrap_or(expanded))
+    if !resolve_symlinks {
+        let absolute = if expanded.is_relative() {
+            logical_current_dir()?.join(expanded)
+        } else {
+            expanded
+        };
+        return Ok(normalize_lexically(&absolute));
+    }
+
+    let absolute = if expanded.is_relative() {
+        std::env::current_dir()?.join(expanded)
+    } else {
+        expanded
+    };
+
+    // Try to canonicalize, but fall back to the absolute path if it doesn't exist
+    Ok(std::fs::canonicalize(&absolute).unwrap_or(absolute))
+}
+
+/// The working directory as the shell sees it, symlinks included
+fn logical_current_dir() -> Result<PathBuf, ConfigError> {
+    let physical = std::env::current_dir()?;
+    if let Ok(pwd) = std::env::var("PWD") {
+        let pwd = PathBuf::from(pwd);
+        if pwd.is_absolute() && std::fs::canonicalize(&pwd).ok() == std::fs::canonicalize(&physical).ok() {
+            return Ok(pwd);
+        }
+    }
+    Ok(physical)
+}
+
+/// Remove `.` and `..` components without touching the filesystem
+fn normalize_lexically(path: &Path) -> PathBuf {
+    let mut normalized = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir => {
+                normalized.pop();
+            }
+            other => normalized.push(other.as_os_str()),
+        }
+    }
+    normalized
 }
 
 #[cfg(test)]
@@ -375,6 +1502,46 @@ mod tests {
         assert_eq!(expanded, home.join("test"));
     }
 
+    #[test]
+    fn test_expand_path_dot_is_cwd() {
+        let cwd = std::fs::canonicalize(env::current_dir().unwrap()).unwrap();
+        assert_eq!(expand_path(".").unwrap(), cwd);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_expand_path_keeps_symlinks() {
+        let temp = tempfile::tempdir().unwrap();
+        let target = temp.path().join("release-42");
+        fs::create_dir(&target).unwrap();
+        let link = temp.path().join("current");
+        std::os::unix::fs::symlink(&target, &link).unwrap();
+        let link_str = link.to_str().unwrap();
+
+        let kept = expand_path_with(link_str, false).unwrap();
+        assert_eq!(kept, link);
+
+        let resolved = expand_path_with(link_str, true).unwrap();
+        assert_eq!(resolved, fs::canonicalize(&target).unwrap());
+    }
+
+    #[test]
+    fn test_normalize_lexically() {
+        assert_eq!(
+            normalize_lexically(Path::new("/a/./b/../c")),
+            PathBuf::from("/a/c")
+        );
+        assert_eq!(normalize_lexically(Path::new("/a/..")), PathBuf::from("/"));
+    }
+
+    #[test]
+    fn test_expand_path_relative_is_absolute() {
+        let cwd = env::current_dir().unwrap();
+        let expanded = expand_path("../goto-nonexistent-sibling-12345").unwrap();
+        assert!(expanded.is_absolute());
+        assert_eq!(expanded, cwd.join("../goto-nonexistent-sibling-12345"));
+    }
+
     #[test]
     fn test_expand_path_env_var() {
         with_env_vars(&[("TEST_EXPAND_VAR", Some("/tmp/test"))], || {
@@ -424,7 +1591,9 @@ fuzzy_threshold = 0.7
             stack_path: temp_dir.path().join("goto_stack"),
             config_path: temp_dir.path().join("config.toml"),
             aliases_path: temp_dir.path().join("aliases.toml"),
+            profile: None,
             user: UserConfig::default(),
+            notices: Vec::new(),
         };
         let formatted = config.format_config();
         assert!(formatted.contains("Configuration file:"));
@@ -432,6 +1601,24 @@ fuzzy_threshold = 0.7
         assert!(formatted.contains("default_sort"));
         assert!(formatted.contains("show_stats"));
         assert!(formatted.contains("show_tags"));
+        assert!(formatted.contains("[privacy]\ntrack_usage = true"));
+    }
+
+    #[test]
+    fn test_no_dormancy_without_usage_tracking() {
+        let mut user = UserConfig::default();
+        let temp_dir = tempfile::tempdir().unwrap();
+        user.privacy.track_usage = false;
+        let config = Config {
+            database_path: temp_dir.path().to_path_buf(),
+            stack_path: temp_dir.path().join("goto_stack"),
+            config_path: temp_dir.path().join("config.toml"),
+            aliases_path: temp_dir.path().join("aliases.toml"),
+            profile: None,
+            user,
+            notices: Vec::new(),
+        };
+        assert_eq!(config.dormant_after(), None);
     }
 
     #[test]
@@ -450,12 +1637,235 @@ fuzzy_threshold = 0.7
                 ("XDG_CONFIG_HOME", Some("/tmp/test-xdg-config")),
             ],
             || {
-                let path = get_database_path().unwrap();
+                let path = get_config_dir().unwrap();
                 assert_eq!(path, PathBuf::from("/tmp/test-xdg-config/goto"));
             },
         );
     }
 
+    #[test]
+    fn test_xdg_data_home_env_var() {
+        with_env_vars(
+            &[
+                ("GOTO_DB", None),
+                ("XDG_DATA_HOME", Some("/tmp/test-xdg-data")),
+            ],
+            || {
+                let path = get_database_path().unwrap();
+                assert_eq!(path, PathBuf::from("/tmp/test-xdg-data/goto"));
+            },
+        );
+    }
+
+    #[test]
+    fn test_load_profile_uses_profile_dirs() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let profile_dir = temp_dir.path().join("profiles").join("work");
+        fs::create_dir_all(&profile_dir).unwrap();
+        fs::write(profile_dir.join("config.toml"), "[display]\ntable_style = \"ascii\"\n").unwrap();
+        fs::create_dir_all(temp_dir.path().join("profiles").join("not valid")).unwrap();
+
+        with_env_vars(
+            &[("GOTO_DB", Some(temp_dir.path().to_str().unwrap()))],
+            || {
+                let config = Config::load_profile(Some("work")).unwrap();
+                assert_eq!(config.profile.as_deref(), Some("work"));
+                assert_eq!(config.aliases_path, profile_dir.join("aliases.toml"));
+                assert_eq!(config.user.display.table_style, "ascii");
+                assert!(config.format_config().starts_with("Profile: work\n"));
+
+                let config = Config::load_profile(None).unwrap();
+                assert_eq!(config.profile, None);
+                assert_eq!(config.aliases_path, temp_dir.path().join("aliases.toml"));
+
+                assert_eq!(profile_names().unwrap(), vec!["work"]);
+            },
+        );
+    }
+
+    #[test]
+    fn test_validate_profile_name() {
+        assert!(validate_profile_name("work").is_ok());
+        assert!(validate_profile_name("home_2-b").is_ok());
+        assert!(validate_profile_name("").is_err());
+        assert!(validate_profile_name("../etc").is_err());
+        assert!(matches!(
+            validate_profile_name("a b"),
+            Err(ConfigError::InvalidProfile(_))
+        ));
+    }
+
+    #[test]
+    fn test_goto_db_overrides_both_dirs() {
+        with_env_vars(
+            &[
+                ("GOTO_DB", Some("/custom/path")),
+                ("XDG_CONFIG_HOME", Some("/tmp/test-xdg-config")),
+                ("XDG_DATA_HOME", Some("/tmp/test-xdg-data")),
+            ],
+            || {
+                assert_eq!(get_config_dir().unwrap(), PathBuf::from("/custom/path"));
+                assert_eq!(get_database_path().unwrap(), PathBuf::from("/custom/path"));
+            },
+        );
+    }
+
+    #[test]
+    fn test_purge_dirs_skip_custom_locations() {
+        with_env_vars(
+            &[
+                ("GOTO_DB", None),
+                ("GOTO_CONFIG", Some("/home/me/dotfiles/goto.toml")),
+                ("XDG_CONFIG_HOME", Some("/tmp/test-xdg-config")),
+                ("XDG_DATA_HOME", Some("/tmp/test-xdg-data")),
+            ],
+            || {
+                // The directory holding GOTO_CONFIG is shared with other dotfiles
+                assert_eq!(
+                    purge_dirs().unwrap(),
+                    vec![PathBuf::from("/tmp/test-xdg-data/goto"), PathBuf::from("/tmp/test-xdg-config/goto")]
+                );
+            },
+        );
+        with_env_vars(&[("GOTO_DB", Some("/custom/path"))], || {
+            assert!(purge_dirs().unwrap().is_empty());
+        });
+    }
+
+    #[test]
+    fn test_config_and_data_location_precedence() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let root = temp_dir.path();
+        let s = |p: &Path| p.to_str().unwrap().to_string();
+        let (home, db, xdg_config, xdg_data) =
+            (root.join("home"), root.join("db"), root.join("xdg-config"), root.join("xdg-data"));
+        let goto_config = root.join("dotfiles").join("goto.toml");
+        fs::create_dir_all(goto_config.parent().unwrap()).unwrap();
+        fs::write(&goto_config, "[general]\nfuzzy_threshold = 0.8\n").unwrap();
+
+        // (GOTO_DB, GOTO_CONFIG, XDG dirs) -> (config file, data directory)
+        let cases = [
+            (false, false, false, home.join(".config/goto/config.toml"), home.join(".local/share/goto")),
+            (false, false, true, xdg_config.join("goto/config.toml"), xdg_data.join("goto")),
+            (true, false, false, db.join("config.toml"), db.clone()),
+            (true, false, true, db.join("config.toml"), db.clone()),
+            (false, true, false, goto_config.clone(), home.join(".local/share/goto")),
+            (false, true, true, goto_config.clone(), xdg_data.join("goto")),
+            (true, true, false, goto_config.clone(), db.clone()),
+            (true, true, true, goto_config.clone(), db.clone()),
+        ];
+        for (set_db, set_config, set_xdg, config_path, data_path) in cases {
+            let (db, config, xdg_config, xdg_data) = (s(&db), s(&goto_config), s(&xdg_config), s(&xdg_data));
+            with_env_vars(
+                &[
+                    ("HOME", Some(&s(&home))),
+                    ("GOTO_DB", set_db.then_some(db.as_str())),
+                    ("GOTO_CONFIG", set_config.then_some(config.as_str())),
+                    ("XDG_CONFIG_HOME", set_xdg.then_some(xdg_config.as_str())),
+                    ("XDG_DATA_HOME", set_xdg.then_some(xdg_data.as_str())),
+                ],
+                || {
+                    let loaded = Config::load_profile(None).unwrap();
+                    let case = (set_db, set_config, set_xdg);
+                    assert_eq!(loaded.config_path, config_path, "{:?}", case);
+                    assert_eq!(loaded.database_path, data_path, "{:?}", case);
+                    assert_eq!(loaded.aliases_path, data_path.join("aliases.toml"), "{:?}", case);
+                    let threshold = if set_config { 0.8 } else { 0.3 };
+                    assert!((loaded.user.general.fuzzy_threshold - threshold).abs() < f64::EPSILON, "{:?}", case);
+                },
+            );
+        }
+    }
+
+    #[test]
+    fn test_goto_config_profiles_live_next_to_it() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let goto_config = temp_dir.path().join("dotfiles").join("config.toml");
+        let profile_dir = temp_dir.path().join("dotfiles").join("profiles").join("work");
+        fs::create_dir_all(&profile_dir).unwrap();
+        fs::write(profile_dir.join("config.toml"), "[display]\ntable_style = \"ascii\"\n").unwrap();
+        let data = temp_dir.path().join("data");
+
+        with_env_vars(
+            &[
+                ("GOTO_DB", Some(data.to_str().unwrap())),
+                ("GOTO_CONFIG", Some(goto_config.to_str().unwrap())),
+            ],
+            || {
+                let config = Config::load_profile(Some("work")).unwrap();
+                assert_eq!(config.config_path, profile_dir.join("config.toml"));
+                assert_eq!(config.aliases_path, data.join("profiles").join("work").join("aliases.toml"));
+                assert_eq!(config.user.display.table_style, "ascii");
+                assert_eq!(profile_names().unwrap(), vec!["work"]);
+            },
+        );
+    }
+
+    #[test]
+    fn test_config_load_splits_and_migrates() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let config_home = temp_dir.path().join("config");
+        let data_home = temp_dir.path().join("data");
+        let old_dir = config_home.join("goto");
+        fs::create_dir_all(old_dir.join("stacks")).unwrap();
+        fs::write(old_dir.join("config.toml"), "[general]\nfuzzy_threshold = 0.9\n").unwrap();
+        fs::write(old_dir.join("aliases.toml"), "# aliases\n").unwrap();
+        fs::write(old_dir.join("stacks").join("1"), "/tmp\n").unwrap();
+
+        with_env_vars(
+            &[
+                ("GOTO_DB", None),
+                ("XDG_CONFIG_HOME", Some(config_home.to_str().unwrap())),
+                ("XDG_DATA_HOME", Some(data_home.to_str().unwrap())),
+            ],
+            || {
+                let config = Config::load().unwrap();
+                assert_eq!(config.config_path, old_dir.join("config.toml"));
+                assert_eq!(config.database_path, data_home.join("goto"));
+                assert_eq!(config.aliases_path, data_home.join("goto").join("aliases.toml"));
+                assert!((config.user.general.fuzzy_threshold - 0.9).abs() < f64::EPSILON);
+            },
+        );
+
+        let new_dir = data_home.join("goto");
+        assert_eq!(fs::read_to_string(new_dir.join("aliases.toml")).unwrap(), "# aliases\n");
+        assert!(new_dir.join("stacks").join("1").exists());
+        assert!(!old_dir.join("aliases.toml").exists());
+        assert!(old_dir.join("config.toml").exists());
+    }
+
+    #[test]
+    fn test_migrate_data_files_never_overwrites() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let old_dir = temp_dir.path().join("old");
+        let new_dir = temp_dir.path().join("new");
+        fs::create_dir_all(&old_dir).unwrap();
+        fs::create_dir_all(&new_dir).unwrap();
+        fs::write(old_dir.join("aliases.toml"), "old").unwrap();
+        fs::write(old_dir.join("goto_stack"), "/tmp").unwrap();
+        fs::write(new_dir.join("aliases.toml"), "new").unwrap();
+
+        let moved = migrate_data_files(&old_dir, &new_dir).unwrap();
+        assert_eq!(moved, vec!["goto_stack"]);
+        assert_eq!(fs::read_to_string(new_dir.join("aliases.toml")).unwrap(), "new");
+        assert_eq!(fs::read_to_string(old_dir.join("aliases.toml")).unwrap(), "old");
+
+        // Nothing left to move the second time
+        assert!(migrate_data_files(&old_dir, &new_dir).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_copy_dir_recursive() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let from = temp_dir.path().join("from");
+        fs::create_dir_all(from.join("nested")).unwrap();
+        fs::write(from.join("nested").join("file"), "x").unwrap();
+
+        let to = temp_dir.path().join("to");
+        copy_dir(&from, &to).unwrap();
+        assert_eq!(fs::read_to_string(to.join("nested").join("file")).unwrap(), "x");
+    }
+
     #[test]
     fn test_config_load_with_existing_config_file() {
         let temp_dir = tempfile::tempdir().unwrap();
@@ -497,7 +1907,9 @@ show_tags = false
             stack_path: nested_path.join("goto_stack"),
             config_path: nested_path.join("config.toml"),
             aliases_path: nested_path.join("aliases.toml"),
+            profile: None,
             user: UserConfig::default(),
+            notices: Vec::new(),
         };
 
         assert!(!nested_path.exists());
@@ -515,7 +1927,9 @@ show_tags = false
             stack_path: temp_dir.path().join("goto_stack"),
             config_path: config_path.clone(),
             aliases_path: temp_dir.path().join("aliases.toml"),
+            profile: None,
             user: UserConfig::default(),
+            notices: Vec::new(),
         };
 
         assert!(!config_path.exists());
@@ -528,6 +1942,7 @@ show_tags = false
         assert!(content.contains("default_sort"));
         assert!(content.contains("show_stats"));
         assert!(content.contains("show_tags"));
+        assert_eq!(crate::commands::config::check_config(&content, true), vec![]);
     }
 
     #[test]
@@ -544,7 +1959,9 @@ show_tags = false
             stack_path: temp_dir.path().join("goto_stack"),
             config_path: config_path.clone(),
             aliases_path: temp_dir.path().join("aliases.toml"),
+            profile: None,
             user: UserConfig::default(),
+            notices: Vec::new(),
         };
 
         // Should return early without overwriting
@@ -566,7 +1983,9 @@ show_tags = false
             stack_path: nested_dir.join("goto_stack"),
             config_path: config_path.clone(),
             aliases_path: nested_dir.join("aliases.toml"),
+            profile: None,
             user: UserConfig::default(),
+            notices: Vec::new(),
         };
 
         assert!(!nested_dir.exists());
@@ -652,7 +2071,9 @@ show_stats = true
             stack_path: temp_dir.path().join("goto_stack"),
             config_path: config_path.clone(),
             aliases_path: temp_dir.path().join("aliases.toml"),
+            profile: None,
             user: UserConfig::default(),
+            notices: Vec::new(),
         };
 
         config.create_default_config_file().unwrap();
@@ -670,13 +2091,277 @@ show_stats = true
             stack_path: temp_dir.path().join("goto_stack"),
             config_path: temp_dir.path().join("config.toml"),
             aliases_path: temp_dir.path().join("aliases.toml"),
+            profile: None,
             user: UserConfig::default(),
+            notices: Vec::new(),
         };
         let formatted = config.format_config();
         assert!(formatted.contains("table_style"));
         assert!(formatted.contains("unicode"));
     }
 
+    #[test]
+    fn test_parse_config_path_style() {
+        let toml_str = r#"
+[display]
+path_style = "short"
+"#;
+        let config: UserConfig = toml::from_str(toml_str).unwrap();
+        assert_eq!(config.display.path_style, "short");
+
+        let config: UserConfig = toml::from_str("").unwrap();
+        assert_eq!(config.display.path_style, "full");
+    }
+
+    #[test]
+    fn test_safety_protects() {
+        let safety = SafetyConfig::default();
+        assert_eq!(safety.protects(Path::new("/")), Some("/"));
+        assert_eq!(safety.protects(&dirs::home_dir().unwrap()), Some("~"));
+        assert_eq!(safety.protects(&dirs::home_dir().unwrap().join("src")), None);
+
+        let config: UserConfig = toml::from_str("[safety]\nprotected_paths = [\"/srv\"]\nstrict = true\n").unwrap();
+        assert!(config.safety.strict);
+        assert_eq!(config.safety.protects(Path::new("/srv")), Some("/srv"));
+        assert_eq!(config.safety.protects(Path::new("/")), None);
+    }
+
+    #[test]
+    fn test_parse_config_truncate_paths() {
+        let config: UserConfig = toml::from_str("[display]\ntruncate_paths = \"end\"\n").unwrap();
+        assert_eq!(config.display.truncate_paths, "end");
+
+        let config: UserConfig = toml::from_str("").unwrap();
+        assert_eq!(config.display.truncate_paths, "middle");
+    }
+
+    #[test]
+    fn test_parse_config_relative_dates() {
+        let config: UserConfig = toml::from_str("[display]\nrelative_dates = false\n").unwrap();
+        assert!(!config.display.relative_dates);
+        assert_eq!(config.display.date_style(), DateStyle::Absolute);
+
+        let config: UserConfig = toml::from_str("").unwrap();
+        assert!(config.display.relative_dates);
+        assert_eq!(config.display.date_style(), DateStyle::Relative);
+    }
+
+    #[test]
+    fn test_parse_config_check_paths() {
+        let config: UserConfig = toml::from_str("[display]\ncheck_paths = false\n").unwrap();
+        assert!(!config.display.check_paths);
+
+        let config: UserConfig = toml::from_str("").unwrap();
+        assert!(config.display.check_paths);
+        assert!(!config.display.show_index);
+
+        let config: UserConfig = toml::from_str("[display]\nshow_index = true\n").unwrap();
+        assert!(config.display.show_index);
+    }
+
+    #[test]
+    fn test_parse_config_projects() {
+        let config: UserConfig =
+            toml::from_str("[projects]\nroot = \"~/code\"\nauto_tags = false\n").unwrap();
+        assert_eq!(config.projects.root, "~/code");
+        assert!(!config.projects.auto_tags);
+
+        let config: UserConfig = toml::from_str("").unwrap();
+        assert_eq!(config.projects.root, "~/src");
+        assert!(config.projects.auto_tags);
+    }
+
+    #[test]
+    fn test_parse_config_rules() {
+        let toml_str = r#"
+[[rules]]
+pattern = "gh:(.+)/(.+)"
+path = "~/src/github.com/$1/$2"
+clone = "https://github.com/$1/$2.git"
+
+[[rules]]
+pattern = "w:(.+)"
+path = "~/work/$1"
+"#;
+        let config: UserConfig = toml::from_str(toml_str).unwrap();
+        assert_eq!(config.rules.len(), 2);
+        assert_eq!(config.rules[0].path, "~/src/github.com/$1/$2");
+        assert!(config.rules[1].clone.is_none());
+
+        let config: UserConfig = toml::from_str("").unwrap();
+        assert!(config.rules.is_empty());
+    }
+
+    #[test]
+    fn test_parse_config_install() {
+        let config: UserConfig = toml::from_str("[install]\ncd_fallback = true\n").unwrap();
+        assert!(config.install.cd_fallback);
+
+        let config: UserConfig = toml::from_str("").unwrap();
+        assert!(!config.install.cd_fallback);
+    }
+
+    #[test]
+    fn test_parse_config_mounts() {
+        let config: UserConfig =
+            toml::from_str("[mounts]\nprefixes = [\"/media/usb\"]\ncommand = \"mount /media/usb\"\n").unwrap();
+        assert_eq!(config.mounts.prefixes, ["/media/usb"]);
+        assert_eq!(config.mounts.command, "mount /media/usb");
+        assert_eq!(config.mounts.wait_seconds, 10);
+
+        let config: UserConfig = toml::from_str("").unwrap();
+        assert!(config.mounts.prefixes.is_empty());
+        assert!(config.mounts.command.is_empty());
+    }
+
+    #[test]
+    fn test_parse_config_integrations() {
+        let config: UserConfig = toml::from_str("[integrations]\neditor = \"code -n\"\n").unwrap();
+        assert_eq!(config.integrations.editor, "code -n");
+        assert!(config.integrations.open.is_empty());
+    }
+
+    #[test]
+    fn test_parse_config_tag_colors() {
+        let config: UserConfig =
+            toml::from_str("[display]\ncolor = \"never\"\n\n[tag_colors]\nwork = \"blue\"\n").unwrap();
+        assert_eq!(config.display.color, "never");
+        assert_eq!(config.tag_colors["work"], "blue");
+
+        let config: UserConfig = toml::from_str("").unwrap();
+        assert_eq!(config.display.color, "auto");
+        assert!(config.tag_colors.is_empty());
+    }
+
+    #[test]
+    fn test_parse_config_templates() {
+        let toml_str = r#"
+[templates.rust]
+init = "cargo init"
+
+[templates.web]
+skeleton = "~/templates/web"
+tags = ["web", "frontend"]
+"#;
+        let config: UserConfig = toml::from_str(toml_str).unwrap();
+        assert_eq!(config.templates.len(), 2);
+        assert_eq!(config.templates["rust"].init.as_deref(), Some("cargo init"));
+        assert!(config.templates["rust"].tags.is_none());
+        assert_eq!(config.templates["web"].skeleton.as_deref(), Some("~/templates/web"));
+
+        let config: UserConfig = toml::from_str("").unwrap();
+        assert!(config.templates.is_empty());
+    }
+
+    #[test]
+    fn test_parse_config_preview() {
+        let config: UserConfig = toml::from_str("[display]\npreview = true\n").unwrap();
+        assert!(config.display.preview);
+
+        let config: UserConfig = toml::from_str("").unwrap();
+        assert!(!config.display.preview);
+    }
+
+    #[test]
+    fn test_parse_config_usage_weight() {
+        let config: UserConfig = toml::from_str("[general]\nusage_weight = 0.5\n").unwrap();
+        assert!((config.general.usage_weight - 0.5).abs() < f64::EPSILON);
+
+        let config: UserConfig = toml::from_str("").unwrap();
+        assert!((config.general.usage_weight - 0.1).abs() < f64::EPSILON);
+    }
+
+    #[test]
+    fn test_parse_config_search_roots() {
+        let config: UserConfig = toml::from_str("[general]\nsearch_roots = [\"~/src\", \"~/work\"]\n").unwrap();
+        assert_eq!(config.general.search_roots, vec!["~/src", "~/work"]);
+
+        let config: UserConfig = toml::from_str("").unwrap();
+        assert!(config.general.search_roots.is_empty());
+    }
+
+    #[test]
+    fn test_parse_config_path_timeout() {
+        let config: UserConfig = toml::from_str("[general]\npath_timeout_ms = 500\n").unwrap();
+        assert_eq!(config.general.path_timeout_ms, 500);
+
+        let config: UserConfig = toml::from_str("").unwrap();
+        assert_eq!(config.general.path_timeout_ms, 2000);
+    }
+
+    #[test]
+    fn test_parse_config_slow_save() {
+        let config: UserConfig = toml::from_str("[general]\nslow_save_ms = 0\n").unwrap();
+        assert_eq!(config.general.slow_save_ms, 0);
+
+        let config: UserConfig = toml::from_str("").unwrap();
+        assert_eq!(config.general.slow_save_ms, 1000);
+    }
+
+    #[test]
+    fn test_parse_config_picker() {
+        let config: UserConfig = toml::from_str("[general]\npicker = \"builtin\"\n").unwrap();
+        assert_eq!(config.general.picker, "builtin");
+
+        let config: UserConfig = toml::from_str("").unwrap();
+        assert_eq!(config.general.picker, "fzf");
+    }
+
+    #[test]
+    fn test_parse_config_shared_aliases() {
+        let config: UserConfig = toml::from_str("[general]\nshared_aliases = \"\"\n").unwrap();
+        assert_eq!(config.general.shared_aliases, "");
+
+        let config: UserConfig = toml::from_str("").unwrap();
+        assert_eq!(config.general.shared_aliases, "/etc/goto/aliases.toml");
+    }
+
+    #[test]
+    fn test_parse_config_update_channel() {
+        let toml_str = r#"
+[update]
+channel = "prerelease"
+"#;
+        let config: UserConfig = toml::from_str(toml_str).unwrap();
+        assert_eq!(config.update.channel, "prerelease");
+        assert!(config.update.auto_check); // default
+        assert!(config.update.enabled); // default
+
+        let config: UserConfig = toml::from_str("").unwrap();
+        assert_eq!(config.update.channel, "stable");
+    }
+
+    #[test]
+    fn test_parse_config_stack_section() {
+        let toml_str = r#"
+[stack]
+per_session = true
+"#;
+        let config: UserConfig = toml::from_str(toml_str).unwrap();
+        assert!(config.stack.per_session);
+        assert_eq!(config.stack.session_ttl_hours, 168); // default
+
+        let config: UserConfig = toml::from_str("").unwrap();
+        assert!(!config.stack.per_session);
+    }
+
+    #[test]
+    fn test_parse_config_backup_section() {
+        let toml_str = r#"
+[backup]
+keep_count = 3
+directory = "/tmp/goto-backups"
+"#;
+        let config: UserConfig = toml::from_str(toml_str).unwrap();
+        assert!(config.backup.enabled); // default
+        assert_eq!(config.backup.keep_count, 3);
+        assert_eq!(config.backup.directory, "/tmp/goto-backups");
+
+        let config: UserConfig = toml::from_str("").unwrap();
+        assert_eq!(config.backup.keep_count, 10);
+        assert!(config.backup.directory.is_empty());
+    }
+
     #[test]
     fn test_parse_config_with_prune_section() {
         let toml_str = r#"
@@ -711,7 +2396,9 @@ fuzzy_threshold = 0.5
             stack_path: temp_dir.path().join("goto_stack"),
             config_path: config_path.clone(),
             aliases_path: temp_dir.path().join("aliases.toml"),
+            profile: None,
             user: UserConfig::default(),
+            notices: Vec::new(),
         };
 
         config.create_default_config_file().unwrap();
@@ -729,11 +2416,36 @@ fuzzy_threshold = 0.5
             stack_path: temp_dir.path().join("goto_stack"),
             config_path: temp_dir.path().join("config.toml"),
             aliases_path: temp_dir.path().join("aliases.toml"),
+            profile: None,
             user: UserConfig::default(),
+            notices: Vec::new(),
         };
         let formatted = config.format_config();
         assert!(formatted.contains("[prune]"));
         assert!(formatted.contains("auto_check = true"));
         assert!(formatted.contains("check_interval_hours = 24"));
     }
+
+    #[test]
+    fn test_watch_config_defaults_and_parse() {
+        let config: UserConfig = toml::from_str("").unwrap();
+        assert!(config.watch.auto_scan);
+        assert_eq!(config.watch.scan_interval_minutes, 60);
+
+        let config: UserConfig = toml::from_str("[watch]\nauto_scan = false\n").unwrap();
+        assert!(!config.watch.auto_scan);
+        assert_eq!(config.watch.scan_interval_minutes, 60);
+    }
+
+    #[test]
+    fn test_scan_ignore() {
+        let config: UserConfig = toml::from_str("").unwrap();
+        assert!(config.scan.ignores("node_modules"));
+        assert!(config.scan.ignores("old.bak"));
+        assert!(!config.scan.ignores("src"));
+
+        let config: UserConfig = toml::from_str("[scan]\nignore = [\"build-*\"]\n").unwrap();
+        assert!(config.scan.ignores("build-x86"));
+        assert!(!config.scan.ignores("node_modules"));
+    }
 }
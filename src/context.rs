@@ -0,0 +1,221 @@
+//! Execution context: where output goes and how goto may talk to the user
+//!
+//! `goto-bin` builds an [`ExecContext`] from the global `--quiet` and
+//! `--no-input` flags and hands a [`Ctx`] made from it to each command it
+//! runs. Commands write through the `Ctx` rather than to stdout and stderr
+//! directly, and ask it before waiting on the user, so their output can be
+//! captured and their prompts answered in tests.
+
+use std::fmt;
+use std::io::{self, IsTerminal, Write};
+
+/// How this process may talk to the user
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ExecContext {
+    /// Suppress informational messages and hints (`--quiet`)
+    pub quiet: bool,
+    /// Never prompt; confirmations take their default (`--no-input`)
+    pub no_input: bool,
+}
+
+impl ExecContext {
+    /// Whether prompts may wait for input
+    ///
+    /// False with `--no-input` or when stdin isn't a terminal.
+    pub fn interactive(&self) -> bool {
+        !self.no_input && io::stdin().is_terminal()
+    }
+}
+
+/// Output streams and interaction settings for one command
+///
+/// Write errors (such as a closed pipe) are ignored, like [`crate::output::print_path`] does.
+pub struct Ctx<'a> {
+    /// Results: paths, tables, JSON
+    pub out: Box<dyn Write + 'a>,
+    /// Messages, warnings and errors
+    pub err: Box<dyn Write + 'a>,
+    /// Prompts may wait for input
+    pub interactive: bool,
+    /// The command was given `--force`: confirmations are skipped
+    pub force: bool,
+    /// Informational messages are dropped (`--quiet`)
+    pub quiet: bool,
+}
+
+impl Ctx<'static> {
+    /// Stdout and stderr, with the settings of `context`
+    pub fn stdio(context: ExecContext) -> Self {
+        Ctx {
+            out: Box::new(io::stdout()),
+            err: Box::new(io::stderr()),
+            interactive: context.interactive(),
+            force: false,
+            quiet: context.quiet,
+        }
+    }
+
+    /// A non-interactive context that discards everything written to it
+    pub fn silent() -> Self {
+        Ctx::new(io::sink(), io::sink())
+    }
+}
+
+impl<'a> Ctx<'a> {
+    /// A non-interactive context writing to `out` and `err`
+    pub fn new(out: impl Write + 'a, err: impl Write + 'a) -> Self {
+        Ctx {
+            out: Box::new(out),
+            err: Box::new(err),
+            interactive: false,
+            force: false,
+            quiet: false,
+        }
+    }
+
+    /// Write `text` to `out` as is
+    pub fn print(&mut self, text: &str) {
+        let _ = self.out.write_all(text.as_bytes());
+    }
+
+    /// Write a line to `out` (see [`outln!`](crate::outln))
+    pub fn println(&mut self, args: fmt::Arguments) {
+        let _ = writeln!(self.out, "{}", args);
+    }
+
+    /// Write a line to `err` (see [`errln!`](crate::errln))
+    pub fn eprintln(&mut self, args: fmt::Arguments) {
+        let _ = writeln!(self.err, "{}", args);
+    }
+
+    /// Write an informational line to `out` unless quiet (see [`note!`](crate::note))
+    pub fn note(&mut self, args: fmt::Arguments) {
+        if !self.quiet {
+            self.println(args);
+        }
+    }
+
+    /// Write an informational line to `err` unless quiet (see [`enote!`](crate::enote))
+    pub fn enote(&mut self, args: fmt::Arguments) {
+        if !self.quiet {
+            self.eprintln(args);
+        }
+    }
+
+    /// Write a directory path for the shell to `out`
+    pub fn path(&mut self, path: &str) {
+        crate::output::write_path(&mut self.out, path);
+    }
+
+    /// Hand the shell a directory to enter, followed by `exports` to evaluate there
+    ///
+    /// Goes to the cd file when there is one, otherwise to `out` like [`Ctx::path`].
+    pub fn cd(&mut self, path: &str, exports: &str) {
+        match crate::output::cd_file() {
+            Some(file) => {
+                if let Err(e) = crate::output::write_cd_file(file, path, exports) {
+                    self.eprintln(format_args!("Cannot write cd file {}: {}", file.display(), e));
+                }
+            }
+            None => {
+                self.path(path);
+                self.print(exports);
+            }
+        }
+    }
+
+    /// Ask for confirmation: yes with `force`, `default` when not interactive
+    pub fn confirm(&mut self, message: &str, default: bool) -> io::Result<bool> {
+        if self.force {
+            return Ok(true);
+        }
+        if !self.interactive {
+            return Ok(default);
+        }
+        crate::confirm(message, default)
+    }
+
+    /// Ask for a line of text: `None` when cancelled or not interactive
+    pub fn prompt_input(&mut self, message: &str) -> io::Result<Option<String>> {
+        if !self.interactive {
+            return Ok(None);
+        }
+        crate::prompt_input(message)
+    }
+}
+
+/// `println!` through a [`Ctx`]: `outln!(ctx, "{}", value)`
+#[macro_export]
+macro_rules! outln {
+    ($ctx:expr) => {
+        $ctx.println(format_args!(""))
+    };
+    ($ctx:expr, $($arg:tt)*) => {
+        $ctx.println(format_args!($($arg)*))
+    };
+}
+
+/// `eprintln!` through a [`Ctx`]
+#[macro_export]
+macro_rules! errln {
+    ($ctx:expr, $($arg:tt)*) => {
+        $ctx.eprintln(format_args!($($arg)*))
+    };
+}
+
+/// A confirmation or other message `--quiet` suppresses, on stdout
+#[macro_export]
+macro_rules! note {
+    ($ctx:expr, $($arg:tt)*) => {
+        $ctx.note(format_args!($($arg)*))
+    };
+}
+
+/// A confirmation or other message `--quiet` suppresses, on stderr
+#[macro_export]
+macro_rules! enote {
+    ($ctx:expr, $($arg:tt)*) => {
+        $ctx.enote(format_args!($($arg)*))
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_no_input_is_never_interactive() {
+        let context = ExecContext {
+            quiet: false,
+            no_input: true,
+        };
+        assert!(!context.interactive());
+    }
+
+    #[test]
+    fn test_ctx_captures_output() {
+        let (mut out, mut err) = (Vec::new(), Vec::new());
+        {
+            let mut ctx = Ctx::new(&mut out, &mut err);
+            outln!(ctx, "result {}", 1);
+            note!(ctx, "registered");
+            errln!(ctx, "failed");
+            ctx.quiet = true;
+            note!(ctx, "dropped");
+            enote!(ctx, "dropped");
+            ctx.path("/tmp");
+        }
+        assert_eq!(String::from_utf8(out).unwrap(), "result 1\nregistered\n/tmp\n");
+        assert_eq!(String::from_utf8(err).unwrap(), "failed\n");
+    }
+
+    #[test]
+    fn test_ctx_confirm() {
+        let mut ctx = Ctx::silent();
+        assert!(!ctx.confirm("Delete?", false).unwrap());
+        assert_eq!(ctx.prompt_input("Name:").unwrap(), None);
+        assert!(ctx.confirm("Delete?", true).unwrap());
+        ctx.force = true;
+        assert!(ctx.confirm("Delete?", false).unwrap());
+    }
+}
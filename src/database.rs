@@ -1,16 +1,26 @@
 //! TOML-based alias storage with metadata
 
-use chrono::Utc;
+use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
-use std::fs;
-use std::io;
+use std::borrow::Cow;
+use std::collections::hash_map::DefaultHasher;
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+use std::fs::{self, File, OpenOptions};
+use std::hash::{Hash, Hasher};
+use std::io::{self, Read, Seek, SeekFrom, Write};
 use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
 use thiserror::Error;
 
-use crate::alias::{Alias, AliasError};
+use crate::alias::{validate_alias, Alias, AliasError};
+use crate::backup::BackupStore;
+use crate::collate;
 use crate::config::{Config, ConfigError};
+use crate::document;
 use crate::fuzzy;
+use crate::recovery;
+use crate::stack;
+use crate::timings::{self, Phase};
 
 /// Errors that can occur during database operations
 #[derive(Error, Debug)]
@@ -24,6 +34,9 @@ pub enum DatabaseError {
     #[error("TOML deserialization error: {0}")]
     TomlDe(#[from] toml::de::Error),
 
+    #[error("TOML parse error: {0}")]
+    TomlEdit(#[from] toml_edit::TomlError),
+
     #[error("config error: {0}")]
     Config(#[from] ConfigError),
 
@@ -31,11 +44,34 @@ pub enum DatabaseError {
     Alias(#[from] AliasError),
 }
 
+/// Usage log entries after which loading schedules a fold into the TOML file
+const COMPACT_AFTER: usize = 1000;
+
 /// Database file format - array-based structure
 #[derive(Debug, Serialize, Deserialize, Default)]
 struct DatabaseFile {
     #[serde(default)]
     aliases: Vec<Alias>,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    tags: BTreeMap<String, TagInfo>,
+}
+
+/// A tag's entry in the `[tags]` table of the database file
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct TagInfo {
+    /// What the tag is for, shown by `--tags` and `--tag-info`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    /// Color the tag is shown in; overrides `[tag_colors]`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub color: Option<String>,
+}
+
+impl TagInfo {
+    /// Whether neither a description nor a color is set
+    pub fn is_empty(&self) -> bool {
+        self.description.is_none() && self.color.is_none()
+    }
 }
 
 /// In-memory database with file persistence
@@ -47,15 +83,146 @@ pub struct Database {
     text_path: PathBuf,
     /// Aliases stored by name for fast lookup
     aliases: HashMap<String, Alias>,
+    /// Tag descriptions and colors, by tag
+    tags: BTreeMap<String, TagInfo>,
+    /// The TOML file as last read or written, so saving keeps the user's
+    /// comments, ordering and extra keys (see [`crate::document`])
+    document: Option<toml_edit::DocumentMut>,
+    /// Alias names by directory, for lookups by path
+    ///
+    /// `Path` orders by component, so the directories below one follow it
+    /// without gaps.
+    by_path: BTreeMap<PathBuf, BTreeSet<String>>,
     /// Whether the database has unsaved changes
     dirty: bool,
+    /// Where to keep backups before the alias set changes
+    backups: Option<BackupStore>,
+    /// Fingerprint of names, paths and tags as last read from or written to disk
+    saved_shape: u64,
+    /// Session overlay file holding temporary aliases, if any
+    overlay_path: Option<PathBuf>,
+    /// Names of aliases that live in the overlay rather than the database file
+    temporary: HashSet<String>,
+    /// Names of read-only aliases from the shared alias file
+    shared: HashSet<String>,
+    /// Inactivity after which aliases are flagged dormant on save
+    dormant_after: Option<chrono::Duration>,
+    /// Whether navigation records uses (`privacy.track_usage`)
+    track_usage: bool,
+    /// How long a save may take before it warns (`general.slow_save_ms`)
+    slow_save: Option<Duration>,
+    /// Names new aliases may not take (`general.reserved_names`)
+    reserved_names: Vec<String>,
+    /// Uses recorded in memory but not yet appended to the usage log
+    unlogged_usage: Vec<(String, DateTime<Utc>)>,
+    /// How far into the usage log has been folded into `aliases`
+    log_offset: u64,
+    /// Whether saving is skipped (`--dry-run`)
+    dry_run: bool,
+    /// Warnings from loading and saving not yet taken by the caller
+    warnings: Vec<String>,
 }
 
 impl Database {
     /// Load the database from the configured path
+    ///
+    /// A database file that no longer parses is salvaged first (see
+    /// [`crate::recovery`]); what was recovered is left in
+    /// [`Database::take_warnings`].
     pub fn load(config: &Config) -> Result<Self, DatabaseError> {
         config.ensure_dirs()?;
-        Self::load_from_path(&config.aliases_path)
+        let mut warnings = Vec::new();
+        let mut db = match Self::load_from_path(&config.aliases_path) {
+            Err(DatabaseError::TomlDe(_)) => {
+                let toml_path = config.aliases_path.with_extension("toml");
+                if let Some(recovery) = recovery::recover(&toml_path, false)? {
+                    warnings.push(format!("Warning: {} was damaged ({})", toml_path.display(), recovery.error));
+                    warnings.push(recovery.summary());
+                    warnings.push(format!(
+                        "The damaged file was kept as {}; goto --backups lists earlier versions",
+                        recovery.copy.display()
+                    ));
+                }
+                Self::load_from_path(&config.aliases_path)?
+            }
+            loaded => loaded?,
+        };
+        db.warnings = warnings;
+        if config.user.backup.enabled {
+            db.backups = Some(BackupStore::new(
+                config.backup_dir()?,
+                config.user.backup.keep_count,
+            ));
+        }
+        db.dormant_after = config.dormant_after();
+        db.track_usage = config.user.privacy.track_usage;
+        db.slow_save = config.slow_save();
+        db.reserved_names = config.user.general.reserved_names.clone();
+        db.flag_dormant();
+
+        // Temporary aliases of this shell session; forget those of shells that are gone
+        let temp_dir = config.temp_dir();
+        let session = stack::session_id();
+        if temp_dir.is_dir() {
+            let ttl = Duration::from_secs(config.user.stack.session_ttl_hours * 3600);
+            let _ = stack::gc_stale_sessions(&temp_dir, &session, ttl);
+        }
+        db.load_overlay(&stack::session_stack_path(&temp_dir, &session))?;
+
+        // A broken shared file shouldn't lock everyone out of their own aliases
+        if let Some(path) = config.shared_aliases_path()? {
+            if let Err(e) = db.load_shared(&path) {
+                db.warnings.push(format!("Warning: ignoring shared aliases {}: {}", path.display(), e));
+            }
+        }
+        Ok(db)
+    }
+
+    /// Merge read-only aliases from a shared alias file, if it exists
+    ///
+    /// The user's own (and temporary) aliases shadow shared ones of the same
+    /// name. Shared aliases are never written back.
+    pub fn load_shared(&mut self, path: &Path) -> Result<(), DatabaseError> {
+        if !path.exists() {
+            crate::debug!("no shared aliases at {}", path.display());
+            return Ok(());
+        }
+        let content = fs::read_to_string(path)?;
+        let db_file: DatabaseFile = toml::from_str(&content)?;
+        crate::info!("read {} shared aliases from {}", db_file.aliases.len(), path.display());
+        for alias in db_file.aliases.into_iter().map(composed) {
+            if self.aliases.contains_key(&alias.name) {
+                crate::debug!("shared alias '{}' is shadowed by a user alias", alias.name);
+            } else {
+                self.shared.insert(alias.name.clone());
+                self.index(&alias);
+                self.aliases.insert(alias.name.clone(), alias);
+            }
+        }
+        Ok(())
+    }
+
+    /// Merge temporary aliases from a session overlay file
+    ///
+    /// Overlay entries never shadow aliases in the database file. Changes to
+    /// temporary aliases are written back to the overlay, never the database.
+    pub fn load_overlay(&mut self, path: &Path) -> Result<(), DatabaseError> {
+        if path.exists() {
+            let content = fs::read_to_string(path)?;
+            let db_file: DatabaseFile = toml::from_str(&content)?;
+            crate::info!("read {} temporary aliases from {}", db_file.aliases.len(), path.display());
+            for alias in db_file.aliases.into_iter().map(composed) {
+                if self.aliases.contains_key(&alias.name) {
+                    crate::debug!("temporary alias '{}' is shadowed by the database", alias.name);
+                } else {
+                    self.temporary.insert(alias.name.clone());
+                    self.index(&alias);
+                    self.aliases.insert(alias.name.clone(), alias);
+                }
+            }
+        }
+        self.overlay_path = Some(path.to_path_buf());
+        Ok(())
     }
 
     /// Load the database from a specific path
@@ -69,18 +236,52 @@ impl Database {
             toml_path,
             text_path,
             aliases: HashMap::new(),
+            tags: BTreeMap::new(),
+            document: None,
+            by_path: BTreeMap::new(),
             dirty: false,
+            backups: None,
+            saved_shape: 0,
+            overlay_path: None,
+            temporary: HashSet::new(),
+            shared: HashSet::new(),
+            dormant_after: None,
+            track_usage: true,
+            slow_save: None,
+            reserved_names: Vec::new(),
+            unlogged_usage: Vec::new(),
+            log_offset: 0,
+            dry_run: false,
+            warnings: Vec::new(),
         };
 
         db.load_entries()?;
+        db.reindex();
+        db.saved_shape = db.shape();
         Ok(db)
     }
 
+    /// Aliases in a database file that isn't the live database, such as a backup
+    ///
+    /// The file is only read: no lock file is created and no usage log folded in.
+    pub fn read_aliases(path: &Path) -> Result<Vec<Alias>, DatabaseError> {
+        let content = fs::read_to_string(path)?;
+        let db_file: DatabaseFile = toml::from_str(&content)?;
+        Ok(db_file.aliases.into_iter().map(composed).collect())
+    }
+
     /// Load entries from storage (TOML or migrate from text)
     fn load_entries(&mut self) -> Result<(), DatabaseError> {
         // Check if TOML file exists
         if self.toml_path.exists() {
+            // A save by another instance can't slip in between the two reads
+            let _lock = self.lock(false);
             self.load_toml()?;
+            let folded = self.fold_usage_log()?;
+            if folded >= COMPACT_AFTER {
+                crate::info!("{} uses in the usage log, folding them in on save", folded);
+                self.dirty = true;
+            }
             return Ok(());
         }
 
@@ -91,6 +292,7 @@ impl Database {
         }
 
         // No database exists, start empty
+        crate::debug!("no database at {}, starting empty", self.toml_path.display());
         Ok(())
     }
 
@@ -98,11 +300,14 @@ impl Database {
     fn load_toml(&mut self) -> Result<(), DatabaseError> {
         let content = fs::read_to_string(&self.toml_path)?;
         let db_file: DatabaseFile = toml::from_str(&content)?;
+        crate::info!("read {} aliases from {}", db_file.aliases.len(), self.toml_path.display());
+        self.document = Some(content.parse()?);
 
         self.aliases.clear();
-        for alias in db_file.aliases {
+        for alias in db_file.aliases.into_iter().map(composed) {
             self.aliases.insert(alias.name.clone(), alias);
         }
+        self.tags = db_file.tags;
 
         Ok(())
     }
@@ -128,7 +333,14 @@ impl Database {
                     use_count: 0,
                     last_used: None,
                     created_at: now,
+                    env: Default::default(),
+                    pending: false,
+                    dormant: false,
+                    reviewed_at: None,
+                    volume: None,
+                    link: None,
                 };
+                let alias = composed(alias);
                 self.aliases.insert(alias.name.clone(), alias);
             }
         }
@@ -144,49 +356,459 @@ impl Database {
         Ok(())
     }
 
+    /// Usage log next to the TOML file (`aliases.usage.log`)
+    ///
+    /// Navigation appends a `<RFC 3339 time>\t<alias>` line here instead of
+    /// rewriting the database, so concurrent shells don't lose each other's
+    /// counts. Entries are folded in when the database is loaded and written
+    /// into the TOML file by the next full save.
+    pub fn usage_log_path(&self) -> PathBuf {
+        self.toml_path.with_extension("usage.log")
+    }
+
+    /// Advisory lock serializing saves, log appends and loads across instances
+    ///
+    /// Held until the returned file is dropped. Locking is best effort: a
+    /// read-only data directory just goes without.
+    fn lock(&self, exclusive: bool) -> Option<File> {
+        lock(&self.toml_path, exclusive)
+    }
+
+    /// Replace the database file at `path` (the base path, as for
+    /// [`Database::load_from_path`]) with `content`, clearing the usage log
+    ///
+    /// Uses logged against the old file would otherwise be folded into the
+    /// new one by the next save.
+    pub fn overwrite_file(path: &Path, content: &str) -> Result<(), DatabaseError> {
+        let toml_path = path.with_extension("toml");
+        let _lock = lock(&toml_path, true);
+        fs::write(&toml_path, content)?;
+        let log = toml_path.with_extension("usage.log");
+        if log.exists() {
+            crate::info!("clearing {}", log.display());
+            File::create(log)?;
+        }
+        Ok(())
+    }
+
+    /// Apply usage log entries past `log_offset`, returning how many were read
+    ///
+    /// Entries for aliases that no longer exist are skipped. A trailing line
+    /// without a newline is left for the next read.
+    fn fold_usage_log(&mut self) -> Result<usize, DatabaseError> {
+        let mut file = match File::open(self.usage_log_path()) {
+            Ok(file) => file,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(0),
+            Err(e) => return Err(e.into()),
+        };
+        // Shorter than what we've read means another instance folded it
+        // into the TOML file and cleared it since
+        if file.metadata()?.len() < self.log_offset {
+            self.log_offset = 0;
+        }
+        file.seek(SeekFrom::Start(self.log_offset))?;
+        let mut content = String::new();
+        file.read_to_string(&mut content)?;
+
+        let mut count = 0;
+        let complete = content.rfind('\n').map_or(0, |i| i + 1);
+        for line in content[..complete].lines() {
+            let Some((time, name)) = line.split_once('\t') else {
+                continue;
+            };
+            let Ok(time) = DateTime::parse_from_rfc3339(time) else {
+                continue;
+            };
+            count += 1;
+            if self.temporary.contains(name) || self.shared.contains(name) {
+                continue;
+            }
+            if let Some(alias) = self.aliases.get_mut(name) {
+                alias.use_count += 1;
+                alias.last_used = alias.last_used.max(Some(time.with_timezone(&Utc)));
+                alias.pending = false;
+                alias.dormant = false;
+            }
+        }
+        self.log_offset += complete as u64;
+        if count > 0 {
+            crate::debug!("folded {} uses from {}", count, self.usage_log_path().display());
+        }
+        Ok(count)
+    }
+
+    /// Append uses recorded since the last save to the usage log
+    fn append_usage_log(&mut self) -> Result<(), DatabaseError> {
+        let _lock = self.lock(true);
+        // Catch up first, so our own lines aren't folded in a second time
+        self.fold_usage_log()?;
+
+        let lines: String = self
+            .unlogged_usage
+            .drain(..)
+            .map(|(name, time)| format!("{}\t{}\n", time.to_rfc3339(), name))
+            .collect();
+        let mut file = OpenOptions::new().create(true).append(true).open(self.usage_log_path())?;
+        file.write_all(lines.as_bytes())?;
+        self.log_offset = file.metadata()?.len();
+        Ok(())
+    }
+
+    /// Schedule the usage log to be folded into the TOML file on save
+    ///
+    /// Called by commands that show usage statistics anyway.
+    pub fn compact_usage_log(&mut self) {
+        if self.log_offset > 0 {
+            self.dirty = true;
+        }
+    }
+
     /// Save the database to disk
+    ///
+    /// Only uses recorded since loading are appended to the usage log; any
+    /// other change rewrites the TOML file, folding in and clearing the log.
+    /// A save slower than `general.slow_save_ms` leaves a warning in
+    /// [`Database::take_warnings`].
     pub fn save(&mut self) -> Result<(), DatabaseError> {
-        if !self.dirty {
-            return Ok(());
+        let started = Instant::now();
+        let result = timings::measure(Phase::Save, || self.write());
+        let took = started.elapsed();
+        if self.slow_save.is_some_and(|limit| took > limit) {
+            self.warnings.push(format!(
+                "Warning: saving {} took {} ms. Fewer aliases save faster (goto --review, goto --cleanup), \
+                 as does a data directory on a local disk (GOTO_DB).",
+                self.toml_path.display(),
+                took.as_millis()
+            ));
         }
+        result
+    }
 
-        // Collect aliases into a vector sorted by name for consistent output
-        let mut aliases: Vec<Alias> = self.aliases.values().cloned().collect();
-        aliases.sort_by(|a, b| a.name.cmp(&b.name));
+    /// Warnings from loading and saving since they were last taken, for the
+    /// caller to show
+    pub fn take_warnings(&mut self) -> Vec<String> {
+        std::mem::take(&mut self.warnings)
+    }
 
-        let db_file = DatabaseFile { aliases };
-        let content = toml::to_string_pretty(&db_file)?;
+    /// Write what [`Database::save`] saves
+    fn write(&mut self) -> Result<(), DatabaseError> {
+        if self.dry_run {
+            crate::debug!("dry run, not saving {}", self.toml_path.display());
+            return Ok(());
+        }
+        if !self.dirty {
+            if !self.unlogged_usage.is_empty() {
+                crate::debug!("appending {} uses to {}", self.unlogged_usage.len(), self.usage_log_path().display());
+                return self.append_usage_log();
+            }
+            crate::debug!("no changes, not saving {}", self.toml_path.display());
+            return Ok(());
+        }
 
         // Ensure parent directory exists
         if let Some(parent) = self.toml_path.parent() {
             fs::create_dir_all(parent)?;
         }
 
+        // Other instances may have logged uses since we loaded
+        let _lock = self.lock(true);
+        self.fold_usage_log()?;
+
+        self.flag_dormant();
+        self.sync_links();
+        self.save_overlay()?;
+
+        // Collect aliases into a vector sorted by name for consistent output,
+        // then fold them into the file as the user left it
+        let aliases = self.sorted(false);
+        let count = aliases.len();
+        let fresh = toml::to_string_pretty(&DatabaseFile {
+            aliases,
+            tags: self.tags.clone(),
+        })?;
+        let content = match &mut self.document {
+            Some(existing) => {
+                document::merge(existing, &fresh.parse()?);
+                existing.to_string()
+            }
+            None => {
+                self.document = Some(fresh.parse()?);
+                fresh
+            }
+        };
+
+        // Back up the previous file when aliases were added, removed or
+        // edited; usage statistics alone don't warrant a backup
+        let shape = self.shape();
+        if shape != self.saved_shape {
+            if let Some(store) = &self.backups {
+                crate::info!("backing up {} before the alias set changes", self.toml_path.display());
+                store.create(&self.toml_path)?;
+            }
+        }
+
+        crate::info!("writing {} aliases to {}", count, self.toml_path.display());
         fs::write(&self.toml_path, content)?;
         self.dirty = false;
+        self.saved_shape = shape;
+
+        // Everything logged is in the TOML file now, including our own uses
+        self.unlogged_usage.clear();
+        if self.log_offset > 0 {
+            File::create(self.usage_log_path())?;
+            self.log_offset = 0;
+        }
+        Ok(())
+    }
+
+    /// Refresh every alias's dormant flag
+    fn flag_dormant(&mut self) {
+        let now = Utc::now();
+        let after = self.dormant_after;
+        for alias in self.aliases.values_mut() {
+            alias.dormant = after.is_some_and(|after| alias.is_dormant(after, now));
+        }
+    }
+
+    /// Keep every change in memory from now on (`--dry-run`)
+    ///
+    /// Saves, including the one on drop, write nothing: no database file,
+    /// usage log, overlay or backup.
+    pub fn set_dry_run(&mut self) {
+        self.dry_run = true;
+    }
+
+    /// Whether uses are recorded (`privacy.track_usage`)
+    pub fn tracks_usage(&self) -> bool {
+        self.track_usage
+    }
+
+    /// Number of aliases flagged dormant
+    pub fn dormant_count(&self) -> usize {
+        self.aliases.values().filter(|a| a.dormant).count()
+    }
+
+    /// Write temporary aliases to the session overlay, removing it once empty
+    fn save_overlay(&self) -> Result<(), DatabaseError> {
+        let Some(path) = &self.overlay_path else {
+            return Ok(());
+        };
+
+        let aliases = self.sorted(true);
+        if aliases.is_empty() {
+            if path.exists() {
+                crate::info!("removing empty session overlay {}", path.display());
+                fs::remove_file(path)?;
+            }
+            return Ok(());
+        }
+
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        crate::info!("writing {} temporary aliases to {}", aliases.len(), path.display());
+        let db_file = DatabaseFile {
+            aliases,
+            tags: BTreeMap::new(),
+        };
+        fs::write(path, toml::to_string_pretty(&db_file)?)?;
         Ok(())
     }
 
+    /// Temporary or persistent aliases, sorted by name (never shared ones)
+    fn sorted(&self, temporary: bool) -> Vec<Alias> {
+        let mut aliases: Vec<Alias> = self
+            .aliases
+            .values()
+            .filter(|a| self.temporary.contains(&a.name) == temporary && !self.shared.contains(&a.name))
+            .cloned()
+            .collect();
+        aliases.sort_by(|a, b| a.name.cmp(&b.name));
+        aliases
+    }
+
+    /// Hash of alias names, paths and tags, ignoring usage statistics,
+    /// temporary and shared aliases
+    fn shape(&self) -> u64 {
+        let mut names: Vec<&String> = self
+            .aliases
+            .keys()
+            .filter(|n| !self.temporary.contains(*n) && !self.shared.contains(*n))
+            .collect();
+        names.sort();
+
+        let mut hasher = DefaultHasher::new();
+        for name in names {
+            let alias = &self.aliases[name];
+            alias.name.hash(&mut hasher);
+            alias.path.hash(&mut hasher);
+            alias.tags.hash(&mut hasher);
+            alias.env.hash(&mut hasher);
+        }
+        self.tags.hash(&mut hasher);
+        hasher.finish()
+    }
+
+    /// Rebuild the path index from scratch
+    fn reindex(&mut self) {
+        self.by_path.clear();
+        for alias in self.aliases.values().filter(|a| a.link.is_none()) {
+            self.by_path.entry(PathBuf::from(&alias.path)).or_default().insert(alias.name.clone());
+        }
+    }
+
+    /// Add `alias` to the path index
+    ///
+    /// Links are left out: their directory belongs to the alias they stand for.
+    fn index(&mut self, alias: &Alias) {
+        if alias.link.is_none() {
+            self.by_path.entry(PathBuf::from(&alias.path)).or_default().insert(alias.name.clone());
+        }
+    }
+
+    /// Drop `alias` from the path index
+    fn unindex(&mut self, alias: &Alias) {
+        let path = Path::new(&alias.path);
+        if let Some(names) = self.by_path.get_mut(path) {
+            names.remove(&alias.name);
+            if names.is_empty() {
+                self.by_path.remove(path);
+            }
+        }
+    }
+
+    /// Names of the aliases registered for exactly `path`, sorted
+    pub fn names_at(&self, path: &Path) -> Vec<&str> {
+        self.by_path
+            .get(path)
+            .map(|names| names.iter().map(String::as_str).collect())
+            .unwrap_or_default()
+    }
+
+    /// Aliases whose directory is `path` or contains it, deepest first
+    ///
+    /// Paths are compared as stored, without resolving symlinks.
+    pub fn aliases_containing(&self, path: &Path) -> Vec<&Alias> {
+        path.ancestors()
+            .filter_map(|dir| self.by_path.get(dir))
+            .flatten()
+            .filter_map(|name| self.aliases.get(name))
+            .collect()
+    }
+
+    /// Aliases whose directory is strictly inside `path`, in path order
+    pub fn aliases_inside(&self, path: &Path) -> Vec<&Alias> {
+        self.by_path
+            .range::<Path, _>((std::ops::Bound::Excluded(path), std::ops::Bound::Unbounded))
+            .take_while(|(dir, _)| dir.starts_with(path))
+            .flat_map(|(_, names)| names)
+            .filter_map(|name| self.aliases.get(name))
+            .collect()
+    }
+
+    /// Point an alias at another directory
+    pub fn set_path(&mut self, name: &str, path: String) -> Result<(), DatabaseError> {
+        let mut alias = self.aliases.remove(name).ok_or_else(|| AliasError::NotFound(name.to_string()))?;
+        self.unindex(&alias);
+        alias.path = path;
+        self.index(&alias);
+        self.aliases.insert(name.to_string(), alias);
+        self.sync_links();
+        self.dirty = true;
+        Ok(())
+    }
+
+    /// Follow `name`'s links to the alias that holds its directory and statistics
+    ///
+    /// An ordinary alias resolves to itself.
+    pub fn resolve(&self, name: &str) -> Result<&Alias, AliasError> {
+        let mut alias = self.get(name).ok_or_else(|| AliasError::NotFound(name.to_string()))?;
+        let mut chain = vec![name];
+        while let Some(target) = alias.link.as_deref() {
+            let seen = chain.contains(&target);
+            chain.push(target);
+            if seen {
+                return Err(AliasError::LinkCycle(chain.join(" -> ")));
+            }
+            alias = self.aliases.get(target).ok_or_else(|| AliasError::BrokenLink {
+                alias: alias.name.clone(),
+                target: target.to_string(),
+            })?;
+        }
+        Ok(alias)
+    }
+
+    /// Copy each link's directory from the alias it resolves to
+    ///
+    /// Broken links keep the last directory they had.
+    fn sync_links(&mut self) {
+        let paths: Vec<(String, String)> = self
+            .aliases
+            .values()
+            .filter(|a| a.link.is_some())
+            .filter_map(|a| Some((a.name.clone(), self.resolve(&a.name).ok()?.path.clone())))
+            .collect();
+        for (name, path) in paths {
+            if self.aliases.get(&name).is_none_or(|a| a.path == path) {
+                continue;
+            }
+            // Out and back in, so the path index follows the link
+            if let Some(mut alias) = self.aliases.remove(&name) {
+                self.unindex(&alias);
+                alias.path = path;
+                self.index(&alias);
+                self.aliases.insert(name, alias);
+                self.dirty = true;
+            }
+        }
+    }
+
     /// Get an alias by name
+    ///
+    /// Names are compared in composed form, so "café" typed with a combining
+    /// accent finds the alias.
     pub fn get(&self, name: &str) -> Option<&Alias> {
-        self.aliases.get(name)
+        self.aliases.get(collate::nfc(name).as_ref())
+    }
+
+    /// The only alias whose name equals `name` ignoring case, if there is one
+    ///
+    /// `None` when no alias matches or several do (`Api` and `API`).
+    pub fn find_ignoring_case(&self, name: &str) -> Option<&Alias> {
+        let mut found = self.aliases.values().filter(|a| collate::eq_ignore_case(&a.name, name));
+        let first = found.next()?;
+        found.next().is_none().then_some(first)
     }
 
     /// Get a mutable reference to an alias by name
+    ///
+    /// Change paths with [`Database::set_path`] instead, which keeps the
+    /// path index current.
     pub fn get_mut(&mut self, name: &str) -> Option<&mut Alias> {
         self.dirty = true;
-        self.aliases.get_mut(name)
+        self.aliases.get_mut(collate::nfc(name).as_ref())
     }
 
     /// Insert or update an alias
+    ///
+    /// The alias is the user's own from now on: it shadows a shared one and
+    /// replaces a temporary one in the database file. The name is stored in
+    /// composed form, which is how lookups find it.
     pub fn insert(&mut self, alias: Alias) {
+        let alias = composed(alias);
         self.dirty = true;
+        self.shared.remove(&alias.name);
+        self.temporary.remove(&alias.name);
+        if let Some(old) = self.aliases.remove(&alias.name) {
+            self.unindex(&old);
+        }
+        self.index(&alias);
         self.aliases.insert(alias.name.clone(), alias);
     }
 
     /// Add a new alias (fails if exists)
     pub fn add(&mut self, alias: Alias) -> Result<(), DatabaseError> {
-        if self.aliases.contains_key(&alias.name) {
+        if self.contains(&alias.name) {
             return Err(AliasError::AlreadyExists(alias.name).into());
         }
         self.insert(alias);
@@ -195,7 +817,7 @@ impl Database {
 
     /// Add a new alias with tags (fails if exists)
     pub fn add_with_tags(&mut self, mut alias: Alias, mut tags: Vec<String>) -> Result<(), DatabaseError> {
-        if self.aliases.contains_key(&alias.name) {
+        if self.contains(&alias.name) {
             return Err(AliasError::AlreadyExists(alias.name).into());
         }
         tags.sort();
@@ -204,15 +826,68 @@ impl Database {
         Ok(())
     }
 
+    /// Add a new temporary alias with tags (fails if exists)
+    ///
+    /// It is kept in the session overlay and disappears with the session.
+    pub fn add_temporary(&mut self, alias: Alias, tags: Vec<String>) -> Result<(), DatabaseError> {
+        if self.overlay_path.is_none() {
+            return Err(io::Error::other("no session overlay for temporary aliases").into());
+        }
+        let name = collate::nfc(&alias.name).into_owned();
+        self.add_with_tags(alias, tags)?;
+        self.temporary.insert(name);
+        Ok(())
+    }
+
+    /// Check if an alias is temporary (lives only in the session overlay)
+    pub fn is_temporary(&self, name: &str) -> bool {
+        self.temporary.contains(name)
+    }
+
+    /// Check if an alias comes from the read-only shared alias file
+    pub fn is_shared(&self, name: &str) -> bool {
+        self.shared.contains(name)
+    }
+
+    /// Check that a new alias may be called `name`
+    ///
+    /// Beyond [`validate_alias`], the name mustn't be one of
+    /// `general.reserved_names` (compared ignoring case).
+    pub fn check_name(&self, name: &str) -> Result<(), AliasError> {
+        validate_alias(name)?;
+        if self.reserved_names.iter().any(|r| collate::eq_ignore_case(r, name)) {
+            return Err(AliasError::InvalidAlias {
+                alias: name.to_string(),
+                reason: "reserved by general.reserved_names in the config".to_string(),
+            });
+        }
+        Ok(())
+    }
+
+    /// Fail with a read-only error if `name` is a shared alias
+    pub fn check_writable(&self, name: &str) -> Result<(), AliasError> {
+        if self.is_shared(name) {
+            Err(AliasError::ReadOnly(name.to_string()))
+        } else {
+            Ok(())
+        }
+    }
+
     /// Remove an alias by name
     pub fn remove(&mut self, name: &str) -> Option<Alias> {
+        let name = collate::nfc(name);
+        let name = name.as_ref();
         self.dirty = true;
-        self.aliases.remove(name)
+        self.temporary.remove(name);
+        self.shared.remove(name);
+        let alias = self.aliases.remove(name)?;
+        self.unindex(&alias);
+        Some(alias)
     }
 
     /// Check if an alias exists
     pub fn contains(&self, name: &str) -> bool {
-        self.aliases.contains_key(name)
+        self.aliases.contains_key(collate::nfc(name).as_ref())
     }
 
     /// Get all aliases
@@ -241,10 +916,24 @@ impl Database {
     }
 
     /// Record usage of an alias (increment use_count, update last_used)
+    ///
+    /// Uses of the user's own aliases go to the usage log on save rather
+    /// than rewriting the database, unless the use clears the pending flag.
+    /// Nothing is recorded when usage tracking is off.
     pub fn record_usage(&mut self, name: &str) -> Result<(), DatabaseError> {
+        let name = collate::nfc(name);
+        let name = name.as_ref();
         if let Some(alias) = self.aliases.get_mut(name) {
+            if !self.track_usage {
+                return Ok(());
+            }
+            let was_pending = alias.pending;
             alias.record_use();
-            self.dirty = true;
+            if was_pending || self.temporary.contains(name) || self.shared.contains(name) {
+                self.dirty = true;
+            } else if let Some(time) = alias.last_used {
+                self.unlogged_usage.push((name.to_string(), time));
+            }
             Ok(())
         } else {
             Err(AliasError::NotFound(name.to_string()).into())
@@ -253,6 +942,10 @@ impl Database {
 
     /// Rename an alias while preserving all metadata
     pub fn rename_alias(&mut self, old_name: &str, new_name: &str) -> Result<(), DatabaseError> {
+        let (old_name, new_name) = (collate::nfc(old_name), collate::nfc(new_name));
+        let (old_name, new_name) = (old_name.as_ref(), new_name.as_ref());
+        self.check_writable(old_name)?;
+
         // Check new name doesn't exist
         if self.aliases.contains_key(new_name) {
             return Err(AliasError::AlreadyExists(new_name.to_string()).into());
@@ -265,14 +958,26 @@ impl Database {
             .ok_or_else(|| AliasError::NotFound(old_name.to_string()))?;
 
         // Update name and insert with new key
+        self.unindex(&alias);
         alias.name = new_name.to_string();
+        self.index(&alias);
         self.aliases.insert(new_name.to_string(), alias);
+        // Links follow the alias to its new name
+        for other in self.aliases.values_mut() {
+            if other.link.as_deref() == Some(old_name) {
+                other.link = Some(new_name.to_string());
+            }
+        }
+        if self.temporary.remove(old_name) {
+            self.temporary.insert(new_name.to_string());
+        }
         self.dirty = true;
         Ok(())
     }
 
     /// Add a tag to an alias
     pub fn add_tag(&mut self, alias_name: &str, tag: &str) -> Result<(), DatabaseError> {
+        self.check_writable(alias_name)?;
         if let Some(alias) = self.aliases.get_mut(alias_name) {
             alias.add_tag(tag);
             self.dirty = true;
@@ -284,6 +989,7 @@ impl Database {
 
     /// Remove a tag from an alias
     pub fn remove_tag(&mut self, alias_name: &str, tag: &str) -> Result<(), DatabaseError> {
+        self.check_writable(alias_name)?;
         if let Some(alias) = self.aliases.get_mut(alias_name) {
             alias.remove_tag(tag);
             self.dirty = true;
@@ -295,6 +1001,7 @@ impl Database {
 
     /// Set all tags on an alias (replacing existing)
     pub fn set_tags(&mut self, alias_name: &str, tags: Vec<String>) -> Result<(), DatabaseError> {
+        self.check_writable(alias_name)?;
         if let Some(alias) = self.aliases.get_mut(alias_name) {
             alias.tags = tags;
             alias.tags.sort();
@@ -328,6 +1035,48 @@ impl Database {
         tags
     }
 
+    /// Description and color of `tag`, if any are set
+    pub fn tag_info(&self, tag: &str) -> Option<&TagInfo> {
+        self.tags.get(tag)
+    }
+
+    /// Set the description and color of `tag`; an empty `info` removes its entry
+    pub fn set_tag_info(&mut self, tag: &str, info: TagInfo) {
+        if info.is_empty() {
+            if self.tags.remove(tag).is_some() {
+                self.dirty = true;
+            }
+        } else if self.tags.get(tag) != Some(&info) {
+            self.tags.insert(tag.to_string(), info);
+            self.dirty = true;
+        }
+    }
+
+    /// Move `old`'s description and color to `new`
+    ///
+    /// What `new` already has set is kept; `old`'s fill in the rest.
+    pub fn rename_tag_info(&mut self, old: &str, new: &str) {
+        let Some(old_info) = self.tags.remove(old) else {
+            return;
+        };
+        let info = self.tags.entry(new.to_string()).or_default();
+        info.description = info.description.take().or(old_info.description);
+        info.color = info.color.take().or(old_info.color);
+        self.dirty = true;
+    }
+
+    /// Tag colors: `configured` (`[tag_colors]`) with those of the `[tags]` table on top
+    pub fn tag_colors(&self, configured: &BTreeMap<String, String>) -> BTreeMap<String, String> {
+        let mut colors = configured.clone();
+        for (tag, info) in &self.tags {
+            if let Some(color) = &info.color {
+                colors.retain(|name, _| !collate::eq_ignore_case(name, tag));
+                colors.insert(tag.clone(), color.clone());
+            }
+        }
+        colors
+    }
+
     /// Clear recent history (reset last_used for all aliases)
     pub fn clear_recent_history(&mut self) -> Result<(), DatabaseError> {
         for alias in self.aliases.values_mut() {
@@ -337,17 +1086,67 @@ impl Database {
         Ok(())
     }
 
+    /// Zero an alias's use count and forget when it was last used
+    pub fn reset_stats(&mut self, name: &str) -> Result<(), DatabaseError> {
+        if let Some(alias) = self.aliases.get_mut(name) {
+            alias.use_count = 0;
+            alias.last_used = None;
+            self.dirty = true;
+            Ok(())
+        } else {
+            Err(AliasError::NotFound(name.to_string()).into())
+        }
+    }
+
+    /// Reset usage stats for every alias, returning how many had any
+    pub fn reset_all_stats(&mut self) -> usize {
+        let mut reset = 0;
+        for alias in self.aliases.values_mut() {
+            if alias.use_count > 0 || alias.last_used.is_some() {
+                alias.use_count = 0;
+                alias.last_used = None;
+                reset += 1;
+            }
+        }
+        if reset > 0 {
+            self.dirty = true;
+        }
+        reset
+    }
+
+    /// Set an alias's use count (last_used is left alone)
+    pub fn set_use_count(&mut self, name: &str, count: u64) -> Result<(), DatabaseError> {
+        if let Some(alias) = self.aliases.get_mut(name) {
+            alias.use_count = count;
+            self.dirty = true;
+            Ok(())
+        } else {
+            Err(AliasError::NotFound(name.to_string()).into())
+        }
+    }
+
     /// Find similar alias names using fuzzy matching
     pub fn find_similar(&self, query: &str, threshold: f64) -> Vec<String> {
         let names = self.list_names();
         fuzzy::find_similar_names(query, &names, threshold)
     }
 
-    /// Export the database as TOML string
+    /// Export the database as TOML string (temporary aliases are left out)
     pub fn export_toml(&self) -> Result<String, DatabaseError> {
-        let mut aliases: Vec<Alias> = self.aliases.values().cloned().collect();
-        aliases.sort_by(|a, b| a.name.cmp(&b.name));
-        let db_file = DatabaseFile { aliases };
+        self.export_toml_where(|_| true)
+    }
+
+    /// Export the aliases accepted by `keep` as TOML string
+    pub fn export_toml_where<F: Fn(&Alias) -> bool>(&self, keep: F) -> Result<String, DatabaseError> {
+        let aliases: Vec<Alias> = self.sorted(false).into_iter().filter(|a| keep(a)).collect();
+        // Only the metadata of tags that come along
+        let tags = self
+            .tags
+            .iter()
+            .filter(|(tag, _)| aliases.iter().any(|a| a.has_tag(tag)))
+            .map(|(tag, info)| (tag.clone(), info.clone()))
+            .collect();
+        let db_file = DatabaseFile { aliases, tags };
         Ok(toml::to_string_pretty(&db_file)?)
     }
 
@@ -355,12 +1154,63 @@ impl Database {
     pub fn import_toml(&mut self, content: &str) -> Result<usize, DatabaseError> {
         let db_file: DatabaseFile = toml::from_str(content)?;
         let count = db_file.aliases.len();
-        for alias in db_file.aliases {
+        for alias in db_file.aliases.into_iter().map(composed) {
+            // Importing over a temporary or shared alias makes it the user's own
+            self.temporary.remove(&alias.name);
+            self.shared.remove(&alias.name);
             self.aliases.insert(alias.name.clone(), alias);
         }
+        // Descriptions and colors already set here win
+        for (tag, info) in db_file.tags {
+            self.tags.entry(tag).or_insert(info);
+        }
+        self.reindex();
         self.dirty = true;
         Ok(count)
     }
+
+    /// Replace all aliases with those in a TOML string
+    ///
+    /// The content's comments and layout replace the file's too. Nothing
+    /// changes when the content doesn't parse.
+    pub fn replace_from_toml(&mut self, content: &str) -> Result<usize, DatabaseError> {
+        let db_file: DatabaseFile = toml::from_str(content)?;
+        self.document = Some(content.parse()?);
+        self.aliases.clear();
+        self.temporary.clear();
+        self.shared.clear();
+        let count = db_file.aliases.len();
+        for alias in db_file.aliases.into_iter().map(composed) {
+            self.aliases.insert(alias.name.clone(), alias);
+        }
+        self.tags = db_file.tags;
+        self.reindex();
+        self.dirty = true;
+        Ok(count)
+    }
+}
+
+/// Lock `<toml_path>.lock`, see [`Database::lock`]
+fn lock(toml_path: &Path, exclusive: bool) -> Option<File> {
+    let path = toml_path.with_extension("lock");
+    let file = OpenOptions::new()
+        .create(true)
+        .truncate(false)
+        .write(true)
+        .open(&path)
+        .inspect_err(|e| crate::debug!("not locking {}: {}", path.display(), e))
+        .ok()?;
+    let locked = if exclusive { file.lock() } else { file.lock_shared() };
+    locked.inspect_err(|e| crate::debug!("not locking {}: {}", path.display(), e)).ok()?;
+    Some(file)
+}
+
+/// `alias` with its name in composed form (NFC), the form the map is keyed by
+fn composed(mut alias: Alias) -> Alias {
+    if let Cow::Owned(name) = collate::nfc(&alias.name) {
+        alias.name = name;
+    }
+    alias
 }
 
 impl Drop for Database {
@@ -455,6 +1305,105 @@ mod tests {
         assert!(db.get("test").unwrap().last_used.is_some());
     }
 
+    #[test]
+    fn test_usage_goes_to_log() {
+        let (mut db, dir) = create_test_db();
+        db.insert(Alias::new("test", "/tmp/test").unwrap());
+        db.save().unwrap();
+        let saved = fs::read_to_string(dir.path().join("aliases.toml")).unwrap();
+
+        db.record_usage("test").unwrap();
+        db.save().unwrap();
+        assert_eq!(fs::read_to_string(dir.path().join("aliases.toml")).unwrap(), saved);
+        let log = fs::read_to_string(db.usage_log_path()).unwrap();
+        assert!(log.ends_with("\ttest\n"), "{}", log);
+
+        let reloaded = Database::load_from_path(&dir.path().join("aliases")).unwrap();
+        assert_eq!(reloaded.get("test").unwrap().use_count, 1);
+        assert!(reloaded.get("test").unwrap().last_used.is_some());
+    }
+
+    #[test]
+    fn test_save_keeps_hand_edits() {
+        let dir = tempdir().unwrap();
+        let toml_path = dir.path().join("aliases.toml");
+        fs::write(
+            &toml_path,
+            r#"# Edited by hand
+[[aliases]]
+name = "web"
+path = "/srv/web" # production
+owner = "ops"
+created_at = "2024-01-01T00:00:00Z"
+
+[[aliases]]
+name = "api"
+path = "/srv/api"
+created_at = "2024-01-01T00:00:00Z"
+"#,
+        )
+        .unwrap();
+
+        let mut db = Database::load_from_path(&dir.path().join("aliases")).unwrap();
+        db.record_usage("web").unwrap();
+        db.compact_usage_log();
+        db.save().unwrap();
+        db.import_toml("[[aliases]]\nname = \"docs\"\npath = \"/srv/docs\"\n").unwrap();
+        db.save().unwrap();
+
+        let saved = fs::read_to_string(&toml_path).unwrap();
+        assert!(saved.starts_with("# Edited by hand\n[[aliases]]\nname = \"web\"\npath = \"/srv/web\" # production\nowner = \"ops\"\n"), "{}", saved);
+        assert!(saved.contains("use_count = 1"), "{}", saved);
+        // Hand-ordered, so the new alias goes last
+        let order: Vec<&str> = saved.lines().filter(|l| l.starts_with("name = ")).collect();
+        assert_eq!(order, ["name = \"web\"", "name = \"api\"", "name = \"docs\""]);
+        assert_eq!(Database::load_from_path(&dir.path().join("aliases")).unwrap().len(), 3);
+    }
+
+    #[test]
+    fn test_concurrent_usage_is_not_lost() {
+        let (mut db, dir) = create_test_db();
+        let path = dir.path().join("aliases");
+        db.insert(Alias::new("test", "/tmp/test").unwrap());
+        db.save().unwrap();
+
+        // Two shells navigate from the same snapshot
+        let mut first = Database::load_from_path(&path).unwrap();
+        let mut second = Database::load_from_path(&path).unwrap();
+        first.record_usage("test").unwrap();
+        first.save().unwrap();
+        second.record_usage("test").unwrap();
+        second.save().unwrap();
+        assert_eq!(Database::load_from_path(&path).unwrap().get("test").unwrap().use_count, 2);
+
+        // A full save folds the log into the TOML file and clears it
+        second.add_tag("test", "work").unwrap();
+        second.save().unwrap();
+        assert_eq!(fs::read_to_string(second.usage_log_path()).unwrap(), "");
+        let reloaded = Database::load_from_path(&path).unwrap();
+        assert_eq!(reloaded.get("test").unwrap().use_count, 2);
+
+        // The first shell notices the log was cleared and keeps counting
+        first.record_usage("test").unwrap();
+        first.save().unwrap();
+        assert_eq!(Database::load_from_path(&path).unwrap().get("test").unwrap().use_count, 3);
+    }
+
+    #[test]
+    fn test_record_usage_untracked() {
+        let (mut db, _dir) = create_test_db();
+        db.track_usage = false;
+        db.insert(Alias::new("test", "/tmp/test").unwrap());
+        db.save().unwrap();
+
+        db.record_usage("test").unwrap();
+        assert_eq!(db.get("test").unwrap().use_count, 0);
+        assert!(db.get("test").unwrap().last_used.is_none());
+        assert!(db.record_usage("nonexistent").is_err());
+        db.save().unwrap();
+        assert!(!db.usage_log_path().exists());
+    }
+
     #[test]
     fn test_record_usage_not_found() {
         let (mut db, _dir) = create_test_db();
@@ -480,6 +1429,77 @@ mod tests {
         assert!(renamed.has_tag("work"));
     }
 
+    #[test]
+    fn test_rename_alias_to_decomposed_name() {
+        let (mut db, dir) = create_test_db();
+        db.insert(Alias::new("cafe", "/tmp/cafe").unwrap());
+
+        db.rename_alias("cafe", "cafe\u{301}").unwrap();
+        assert_eq!(db.list_names(), ["café"]);
+        assert_eq!(db.get("café").unwrap().path, "/tmp/cafe");
+        assert!(db.get("cafe\u{301}").is_some());
+        db.save().unwrap();
+
+        // Names written by hand in decomposed form are found too
+        fs::write(
+            dir.path().join("aliases.toml"),
+            "[[aliases]]\nname = \"e\u{301}cole\"\npath = \"/tmp/ecole\"\ncreated_at = \"2024-01-01T00:00:00Z\"\n",
+        )
+        .unwrap();
+        let mut reloaded = Database::load_from_path(&dir.path().join("aliases")).unwrap();
+        assert!(reloaded.contains("école"));
+        assert!(reloaded.remove("e\u{301}cole").is_some());
+        assert!(reloaded.is_empty());
+
+        assert!(db.remove("cafe\u{301}").is_some());
+        assert!(db.is_empty());
+    }
+
+    #[test]
+    fn test_path_index() {
+        let (mut db, dir) = create_test_db();
+        db.insert(Alias::new("src", "/srv/src").unwrap());
+        db.insert(Alias::new("api", "/srv/src/api").unwrap());
+        db.insert(Alias::new("api2", "/srv/src/api/").unwrap());
+        db.insert(Alias::new("sibling", "/srv/src-old").unwrap());
+
+        assert_eq!(db.names_at(Path::new("/srv/src/api")), ["api", "api2"]);
+        let names = |aliases: Vec<&Alias>| aliases.iter().map(|a| a.name.clone()).collect::<Vec<_>>();
+        assert_eq!(names(db.aliases_containing(Path::new("/srv/src/api/v1"))), ["api", "api2", "src"]);
+        assert_eq!(names(db.aliases_inside(Path::new("/srv/src"))), ["api", "api2"]);
+        assert!(db.aliases_inside(Path::new("/srv/src/api")).is_empty());
+
+        // Kept current through every change
+        db.rename_alias("api2", "v2").unwrap();
+        db.set_path("v2", "/srv/v2".to_string()).unwrap();
+        db.insert(Alias::new("src", "/opt/src").unwrap());
+        db.remove("sibling");
+        assert_eq!(db.names_at(Path::new("/srv/src/api")), ["api"]);
+        assert_eq!(db.names_at(Path::new("/srv/v2")), ["v2"]);
+        assert_eq!(names(db.aliases_containing(Path::new("/srv/src/api"))), ["api"]);
+        assert!(db.names_at(Path::new("/srv/src-old")).is_empty());
+
+        db.save().unwrap();
+        let reloaded = Database::load_from_path(&dir.path().join("aliases")).unwrap();
+        assert_eq!(reloaded.names_at(Path::new("/opt/src")), ["src"]);
+        assert!(db.set_path("missing", "/x".to_string()).is_err());
+    }
+
+    #[test]
+    fn test_path_index_follows_links() {
+        let (mut db, _dir) = create_test_db();
+        db.insert(Alias::new("release", "/srv/release-41").unwrap());
+        let mut current = Alias::new("current", "/srv/release-41").unwrap();
+        current.link = Some("release".to_string());
+        db.insert(current);
+
+        db.set_path("release", "/srv/release-42".to_string()).unwrap();
+        assert_eq!(db.get("current").unwrap().path, "/srv/release-42");
+        // The link moves with its target and is never indexed on its own
+        assert_eq!(db.names_at(Path::new("/srv/release-42")), ["release"]);
+        assert!(db.names_at(Path::new("/srv/release-41")).is_empty());
+    }
+
     #[test]
     fn test_rename_alias_to_existing() {
         let (mut db, _dir) = create_test_db();
@@ -576,6 +1596,49 @@ mod tests {
         assert!(db.get("test").unwrap().last_used.is_none());
     }
 
+    #[test]
+    fn test_reset_stats() {
+        let (mut db, _dir) = create_test_db();
+        db.insert(Alias::new("used", "/tmp/used").unwrap());
+        db.insert(Alias::new("other", "/tmp/other").unwrap());
+        db.record_usage("used").unwrap();
+        db.record_usage("other").unwrap();
+
+        db.reset_stats("used").unwrap();
+        let used = db.get("used").unwrap();
+        assert_eq!(used.use_count, 0);
+        assert!(used.last_used.is_none());
+        assert_eq!(db.get("other").unwrap().use_count, 1);
+
+        assert!(db.reset_stats("missing").is_err());
+    }
+
+    #[test]
+    fn test_reset_all_stats_counts_only_used_aliases() {
+        let (mut db, _dir) = create_test_db();
+        db.insert(Alias::new("used", "/tmp/used").unwrap());
+        db.insert(Alias::new("unused", "/tmp/unused").unwrap());
+        db.record_usage("used").unwrap();
+
+        assert_eq!(db.reset_all_stats(), 1);
+        assert_eq!(db.get("used").unwrap().use_count, 0);
+        assert_eq!(db.reset_all_stats(), 0);
+    }
+
+    #[test]
+    fn test_set_use_count() {
+        let (mut db, _dir) = create_test_db();
+        db.insert(Alias::new("test", "/tmp/test").unwrap());
+        db.record_usage("test").unwrap();
+
+        db.set_use_count("test", 42).unwrap();
+        let alias = db.get("test").unwrap();
+        assert_eq!(alias.use_count, 42);
+        assert!(alias.last_used.is_some());
+
+        assert!(db.set_use_count("missing", 1).is_err());
+    }
+
     #[test]
     fn test_find_similar() {
         let (mut db, _dir) = create_test_db();
@@ -610,6 +1673,135 @@ mod tests {
         assert!(alias.has_tag("work"));
     }
 
+    #[test]
+    fn test_names_with_dots_and_at_signs_round_trip() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("aliases");
+
+        {
+            let mut db = Database::load_from_path(&path).unwrap();
+            db.insert(Alias::new("api.v2", "/tmp/api").unwrap());
+            db.insert(Alias::new("work@client", "/tmp/client").unwrap());
+            db.save().unwrap();
+        }
+
+        let db = Database::load_from_path(&path).unwrap();
+        assert!(db.get("api.v2").is_some());
+        assert!(db.get("work@client").is_some());
+        assert!(db.find_similar("api.v3", 0.5).contains(&"api.v2".to_string()));
+
+        let exported = db.export_toml().unwrap();
+        let mut other = Database::load_from_path(&dir.path().join("other")).unwrap();
+        other.import_toml(&exported).unwrap();
+        assert!(other.contains("work@client"));
+    }
+
+    #[test]
+    fn test_temporary_aliases_stay_in_overlay() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("aliases");
+        let overlay = dir.path().join("temp").join("session");
+
+        {
+            let mut db = Database::load_from_path(&path).unwrap();
+            db.load_overlay(&overlay).unwrap();
+            db.insert(Alias::new("kept", "/tmp/kept").unwrap());
+            db.add_temporary(Alias::new("scratch", "/tmp/scratch").unwrap(), vec!["wip".to_string()])
+                .unwrap();
+            assert!(db.is_temporary("scratch"));
+            assert!(!db.export_toml().unwrap().contains("scratch"));
+            db.save().unwrap();
+        }
+
+        // The database file never sees it
+        let db = Database::load_from_path(&path).unwrap();
+        assert!(db.contains("kept"));
+        assert!(!db.contains("scratch"));
+
+        // The session overlay brings it back, renames included
+        let mut db = Database::load_from_path(&path).unwrap();
+        db.load_overlay(&overlay).unwrap();
+        assert!(db.get("scratch").unwrap().has_tag("wip"));
+        db.rename_alias("scratch", "scratch2").unwrap();
+        assert!(db.is_temporary("scratch2"));
+
+        // Removing the last temporary alias removes the overlay
+        db.remove("scratch2");
+        db.save().unwrap();
+        assert!(!overlay.exists());
+    }
+
+    #[test]
+    fn test_overlay_never_shadows_database() {
+        let (mut db, dir) = create_test_db();
+        let overlay = dir.path().join("session");
+        fs::write(&overlay, "[[aliases]]\nname = \"kept\"\npath = \"/tmp/other\"\ncreated_at = \"2024-01-01T00:00:00Z\"\n").unwrap();
+        db.insert(Alias::new("kept", "/tmp/kept").unwrap());
+
+        db.load_overlay(&overlay).unwrap();
+        assert_eq!(db.get("kept").unwrap().path, "/tmp/kept");
+        assert!(!db.is_temporary("kept"));
+        assert!(db.add_temporary(Alias::new("kept", "/tmp/x").unwrap(), Vec::new()).is_err());
+    }
+
+    #[test]
+    fn test_check_name_reserved() {
+        let (mut db, _dir) = create_test_db();
+        assert!(db.check_name("help").is_ok());
+        db.reserved_names = vec!["help".to_string()];
+        let err = db.check_name("HELP").unwrap_err().to_string();
+        assert!(err.contains("general.reserved_names"), "{}", err);
+        assert!(db.check_name("helper").is_ok());
+        assert!(db.check_name("--list").unwrap_err().to_string().contains("goto option"));
+    }
+
+    #[test]
+    fn test_shared_aliases_are_read_only() {
+        let (mut db, dir) = create_test_db();
+        let shared = dir.path().join("shared.toml");
+        fs::write(
+            &shared,
+            "[[aliases]]\nname = \"team\"\npath = \"/srv/team\"\ncreated_at = \"2024-01-01T00:00:00Z\"\n\n\
+             [[aliases]]\nname = \"mine\"\npath = \"/srv/theirs\"\ncreated_at = \"2024-01-01T00:00:00Z\"\n",
+        )
+        .unwrap();
+        db.insert(Alias::new("mine", "/home/user/mine").unwrap());
+
+        db.load_shared(&shared).unwrap();
+        // User aliases shadow shared ones
+        assert_eq!(db.get("mine").unwrap().path, "/home/user/mine");
+        assert!(!db.is_shared("mine"));
+        assert!(db.is_shared("team"));
+
+        assert!(matches!(
+            db.add_tag("team", "x"),
+            Err(DatabaseError::Alias(AliasError::ReadOnly(_)))
+        ));
+        assert!(db.rename_alias("team", "team2").is_err());
+        assert!(db.record_usage("team").is_ok());
+
+        db.save().unwrap();
+        let content = fs::read_to_string(dir.path().join("aliases.toml")).unwrap();
+        assert!(!content.contains("team"));
+        assert!(!db.export_toml().unwrap().contains("team"));
+    }
+
+    #[test]
+    fn test_slow_save_leaves_warning() {
+        let (mut db, _dir) = create_test_db();
+        db.insert(Alias::new("proj", "/tmp").unwrap());
+        db.save().unwrap();
+        assert!(db.take_warnings().is_empty());
+
+        db.slow_save = Some(Duration::ZERO);
+        db.insert(Alias::new("other", "/tmp").unwrap());
+        db.save().unwrap();
+        let warnings = db.take_warnings();
+        assert_eq!(warnings.len(), 1);
+        assert!(warnings[0].starts_with("Warning: saving"));
+        assert!(db.take_warnings().is_empty());
+    }
+
     #[test]
     fn test_migrate_from_text_format() {
         let dir = tempdir().unwrap();
@@ -620,7 +1812,7 @@ mod tests {
         let mut file = fs::File::create(&text_path).unwrap();
         writeln!(file, "projects /home/user/projects").unwrap();
         writeln!(file, "# comment line").unwrap();
-        writeln!(file, "").unwrap();
+        writeln!(file).unwrap();
         writeln!(file, "work /home/user/work").unwrap();
         drop(file);
 
@@ -655,6 +1847,34 @@ mod tests {
         assert!(db2.get("test").unwrap().has_tag("work"));
     }
 
+    #[test]
+    fn test_tag_info_export_and_colors() {
+        let (mut db, _dir) = create_test_db();
+        let mut alias = Alias::new("test", "/tmp/test").unwrap();
+        alias.add_tag("work");
+        db.insert(alias);
+        let info = |color: &str| TagInfo {
+            description: None,
+            color: Some(color.to_string()),
+        };
+        db.set_tag_info("work", info("red"));
+        db.set_tag_info("unused", info("blue"));
+
+        // Only the tags of exported aliases come along
+        let exported = db.export_toml().unwrap();
+        assert!(exported.contains("[tags.work]") && !exported.contains("unused"));
+        let (mut db2, _dir2) = create_test_db();
+        db2.import_toml(&exported).unwrap();
+        assert_eq!(db2.tag_info("work"), Some(&info("red")));
+
+        let configured = BTreeMap::from([("Work".to_string(), "green".to_string())]);
+        assert_eq!(db.tag_colors(&configured).get("work").map(String::as_str), Some("red"));
+        assert!(!db.tag_colors(&configured).contains_key("Work"));
+
+        db.set_tag_info("work", TagInfo::default());
+        assert!(db.tag_info("work").is_none());
+    }
+
     #[test]
     fn test_load_existing_toml() {
         let dir = tempdir().unwrap();
@@ -689,7 +1909,9 @@ created_at = "2024-01-01T00:00:00Z"
             stack_path: dir.path().join("goto_stack"),
             config_path: dir.path().join("config.toml"),
             aliases_path: dir.path().join("aliases"),
+            profile: None,
             user: UserConfig::default(),
+            notices: Vec::new(),
         };
 
         // Test Database::load() which calls config.ensure_dirs()
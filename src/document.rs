@@ -0,0 +1,302 @@
+//! Keeping hand edits to the alias file when goto rewrites it
+//!
+//! Aliases are (de)serialized with serde; on save, the freshly serialized
+//! entries are merged into the document as it was last read or written: untouched values keep their formatting and trailing comments,
+//! comments between entries and keys goto doesn't know stay where they are,
+//! and entries keep their order. Removed aliases take their comments with
+//! them.
+
+use std::collections::{HashMap, HashSet};
+
+use toml_edit::{ArrayOfTables, DocumentMut, Item, Table, Value};
+
+/// Keys of an `[[aliases]]` entry that goto writes; any others are the user's
+const FIELDS: &[&str] = &[
+    "name",
+    "path",
+    "tags",
+    "use_count",
+    "last_used",
+    "created_at",
+    "env",
+    "pending",
+    "dormant",
+    "reviewed_at",
+    "volume",
+    "link",
+];
+
+/// Whether two values are the same TOML data, however they are written
+fn same_value(a: &Value, b: &Value) -> bool {
+    match (a, b) {
+        (Value::String(a), Value::String(b)) => a.value() == b.value(),
+        (Value::Integer(a), Value::Integer(b)) => a.value() == b.value(),
+        (Value::Float(a), Value::Float(b)) => a.value() == b.value(),
+        (Value::Boolean(a), Value::Boolean(b)) => a.value() == b.value(),
+        (Value::Datetime(a), Value::Datetime(b)) => a.value() == b.value(),
+        (Value::Array(a), Value::Array(b)) => {
+            a.len() == b.len() && a.iter().zip(b.iter()).all(|(a, b)| same_value(a, b))
+        }
+        (Value::InlineTable(a), Value::InlineTable(b)) => {
+            a.len() == b.len() && a.iter().all(|(k, v)| b.get(k).is_some_and(|w| same_value(v, w)))
+        }
+        _ => false,
+    }
+}
+
+/// Replace `old` by `new` unless they hold the same data, keeping `old`'s comments
+fn merge_value(old: &mut Value, new: Value) {
+    if same_value(old, &new) {
+        return;
+    }
+    let decor = old.decor().clone();
+    *old = new;
+    *old.decor_mut() = decor;
+}
+
+fn merge_item(old: &mut Item, new: &Item) {
+    match (old, new) {
+        (Item::Value(old), Item::Value(new)) => merge_value(old, new.clone()),
+        (Item::Table(old), Item::Table(new)) => merge_table(old, new, &[]),
+        // A hand-written `env = { ... }` stays inline
+        (Item::Value(old @ Value::InlineTable(_)), Item::Table(new)) => {
+            merge_value(old, Value::InlineTable(new.clone().into_inline_table()))
+        }
+        (old, new) => *old = new.clone(),
+    }
+}
+
+/// Bring `old` in line with `new`, leaving keys outside `owned` alone
+///
+/// An empty `owned` means every key is goto's (nested tables such as `env`).
+fn merge_table(old: &mut Table, new: &Table, owned: &[&str]) {
+    for (key, item) in new.iter() {
+        match old.get_mut(key) {
+            Some(existing) => merge_item(existing, item),
+            None => {
+                old.insert(key, item.clone());
+            }
+        }
+    }
+    // Fields no longer written, such as `pending` once cleared
+    let stale: Vec<String> = old
+        .iter()
+        .map(|(key, _)| key.to_string())
+        .filter(|key| (owned.is_empty() || owned.contains(&key.as_str())) && !new.contains_key(key))
+        .collect();
+    for key in stale {
+        old.remove(&key);
+    }
+}
+
+fn entry_name(table: &Table) -> Option<&str> {
+    table.get("name").and_then(Item::as_str)
+}
+
+/// Merge the aliases and tags of `fresh` (as serialized by goto) into `document`
+///
+/// Entries are matched by name. New entries go where they sort when the
+/// file is sorted by name, and at the end otherwise. The `[tags]` table
+/// follows the aliases.
+pub fn merge(document: &mut DocumentMut, fresh: &DocumentMut) {
+    merge_aliases(document, fresh);
+    merge_tags(document, fresh);
+}
+
+fn merge_aliases(document: &mut DocumentMut, fresh: &DocumentMut) {
+    let empty = ArrayOfTables::new();
+    let new_entries = fresh.get("aliases").and_then(Item::as_array_of_tables).unwrap_or(&empty);
+    let Some(old_entries) = document.get("aliases").and_then(Item::as_array_of_tables) else {
+        document.insert("aliases", Item::ArrayOfTables(new_entries.clone()));
+        return;
+    };
+
+    let by_name: HashMap<&str, &Table> = new_entries.iter().filter_map(|t| Some((entry_name(t)?, t))).collect();
+    let sorted = old_entries
+        .iter()
+        .filter_map(entry_name)
+        .collect::<Vec<_>>()
+        .windows(2)
+        .all(|w| w[0] <= w[1]);
+
+    let mut kept: Vec<Table> = Vec::new();
+    let mut seen: HashSet<String> = HashSet::new();
+    for old in old_entries.iter() {
+        let Some(name) = entry_name(old) else { continue };
+        let Some(new) = by_name.get(name) else { continue };
+        if !seen.insert(name.to_string()) {
+            continue;
+        }
+        let mut table = old.clone();
+        merge_table(&mut table, new, FIELDS);
+        kept.push(table);
+    }
+    for new in new_entries.iter() {
+        let Some(name) = entry_name(new) else { continue };
+        if seen.contains(name) {
+            continue;
+        }
+        let at = if sorted {
+            kept.iter().position(|t| entry_name(t).is_some_and(|n| n > name)).unwrap_or(kept.len())
+        } else {
+            kept.len()
+        };
+        kept.insert(at, new.clone());
+    }
+
+    // Comments above the first entry head the file, whichever entry comes first now
+    let header = old_entries.iter().next().and_then(|t| t.decor().prefix()).and_then(|p| p.as_str());
+    let header = header.unwrap_or("").to_string();
+
+    // Entries copied from `fresh` bring its blank lines and table positions;
+    // number every table again so they print in this order
+    let mut position = old_entries.iter().filter_map(Table::position).min().unwrap_or(0);
+    let mut entries = ArrayOfTables::new();
+    for (i, mut table) in kept.into_iter().enumerate() {
+        let prefix = table.decor().prefix().and_then(|p| p.as_str()).unwrap_or("");
+        if i == 0 {
+            table.decor_mut().set_prefix(header.as_str());
+        } else if prefix.is_empty() || prefix == header {
+            table.decor_mut().set_prefix("\n");
+        }
+        renumber(&mut table, &mut position);
+        entries.push(table);
+    }
+    document.insert("aliases", Item::ArrayOfTables(entries));
+}
+
+/// Bring the `[tags]` table in line with `fresh`'s, dropping it when `fresh` has none
+fn merge_tags(document: &mut DocumentMut, fresh: &DocumentMut) {
+    let Some(new) = fresh.get("tags").and_then(Item::as_table) else {
+        document.remove("tags");
+        return;
+    };
+    let mut position = document
+        .get("aliases")
+        .and_then(Item::as_array_of_tables)
+        .and_then(|entries| entries.iter().filter_map(last_position).max())
+        .map_or(0, |last| last + 1);
+    let tags = match document.get_mut("tags").and_then(Item::as_table_mut) {
+        Some(old) => {
+            merge_table(old, new, &[]);
+            old
+        }
+        None => {
+            document.insert("tags", Item::Table(new.clone()));
+            document["tags"].as_table_mut().expect("just inserted")
+        }
+    };
+    renumber(tags, &mut position);
+}
+
+/// The highest position of `table` and the tables nested in it
+fn last_position(table: &Table) -> Option<usize> {
+    let nested = table.iter().filter_map(|(_, item)| item.as_table().and_then(last_position));
+    table.position().into_iter().chain(nested).max()
+}
+
+/// Give `table` and the tables nested in it consecutive positions from `next`
+fn renumber(table: &mut Table, next: &mut usize) {
+    table.set_position(*next);
+    *next += 1;
+    for (_, item) in table.iter_mut() {
+        if let Item::Table(nested) = item {
+            renumber(nested, next);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn merged(old: &str, fresh: &str) -> String {
+        let mut document: DocumentMut = old.parse().unwrap();
+        merge(&mut document, &fresh.parse().unwrap());
+        document.to_string()
+    }
+
+    #[test]
+    fn test_merge_keeps_comments_and_unknown_keys() {
+        let old = r#"# My aliases
+[[aliases]]
+name = "web"   # the site
+path = '/srv/web'
+owner = "ops"
+use_count = 3 # bumped by goto
+pending = true
+
+# Work
+[[aliases]]
+name = "api"
+path = "/srv/api"
+env = { PORT = "8080" }
+"#;
+        let fresh = r#"[[aliases]]
+name = "api"
+path = "/srv/api"
+use_count = 0
+
+[aliases.env]
+PORT = "8080"
+
+[[aliases]]
+name = "web"
+path = "/srv/web"
+use_count = 4
+"#;
+        assert_eq!(
+            merged(old, fresh),
+            r#"# My aliases
+[[aliases]]
+name = "web"   # the site
+path = '/srv/web'
+owner = "ops"
+use_count = 4 # bumped by goto
+
+# Work
+[[aliases]]
+name = "api"
+path = "/srv/api"
+env = { PORT = "8080" }
+use_count = 0
+"#
+        );
+    }
+
+    #[test]
+    fn test_merge_adds_and_removes_entries() {
+        let old = "[[aliases]]\nname = \"a\"\npath = \"/a\"\n\n# gone\n[[aliases]]\nname = \"b\"\npath = \"/b\"\n\n[[aliases]]\nname = \"d\"\npath = \"/d\"\n";
+        let fresh = "[[aliases]]\nname = \"a\"\npath = \"/a\"\n\n[[aliases]]\nname = \"c\"\npath = \"/c\"\n\n[[aliases]]\nname = \"d\"\npath = \"/d\"\n";
+        // Sorted files stay sorted
+        assert_eq!(
+            merged(old, fresh),
+            "[[aliases]]\nname = \"a\"\npath = \"/a\"\n\n[[aliases]]\nname = \"c\"\npath = \"/c\"\n\n[[aliases]]\nname = \"d\"\npath = \"/d\"\n"
+        );
+
+        // The file's opening comment stays on top
+        let old = "# goto aliases\n[[aliases]]\nname = \"d\"\npath = \"/d\"\n";
+        assert!(merged(old, fresh).starts_with("# goto aliases\n[[aliases]]\nname = \"a\"\npath = \"/a\"\n\n[[aliases]]\nname = \"c\""));
+
+        // Hand-ordered ones get new entries at the end
+        let old = "[[aliases]]\nname = \"d\"\npath = \"/d\"\n\n[[aliases]]\nname = \"a\"\npath = \"/a\"\n";
+        assert_eq!(
+            merged(old, fresh),
+            "[[aliases]]\nname = \"d\"\npath = \"/d\"\n\n[[aliases]]\nname = \"a\"\npath = \"/a\"\n\n[[aliases]]\nname = \"c\"\npath = \"/c\"\n"
+        );
+    }
+
+    #[test]
+    fn test_merge_tags_follow_aliases() {
+        let old = "[[aliases]]\nname = \"a\"\npath = \"/a\"\n\n[tags.work]\ndescription = \"Day job\" # mine\n\n[tags.old]\ncolor = \"red\"\n";
+        let fresh = "[[aliases]]\nname = \"a\"\npath = \"/a\"\n\n[[aliases]]\nname = \"b\"\npath = \"/b\"\n\n[tags.work]\ndescription = \"Day job\"\ncolor = \"blue\"\n";
+        assert_eq!(
+            merged(old, fresh),
+            "[[aliases]]\nname = \"a\"\npath = \"/a\"\n\n[[aliases]]\nname = \"b\"\npath = \"/b\"\n\n[tags.work]\ndescription = \"Day job\" # mine\ncolor = \"blue\"\n"
+        );
+
+        // No tags left, no table
+        let fresh = "[[aliases]]\nname = \"a\"\npath = \"/a\"\n";
+        assert_eq!(merged(old, fresh), fresh);
+    }
+}
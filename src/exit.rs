@@ -0,0 +1,125 @@
+//! Exit codes, decided by the type of a command's error
+//!
+//! The codes are listed under "Exit Codes" in docs/commands.md. An
+//! [`AliasError`] (also inside a [`DatabaseError`]) maps by variant; other
+//! errors that call for a particular code are raised as a [`Failure`], and
+//! anything else exits with [`Code::Error`].
+
+use std::error::Error;
+use std::fmt;
+
+use crate::alias::AliasError;
+use crate::database::DatabaseError;
+
+/// Exit code of a failed command
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Code {
+    /// Not found, nothing to act on, cancelled, or a check found problems
+    NotFound = 1,
+    /// The directory no longer exists
+    Missing = 2,
+    /// Invalid input: an alias, tag, filter or option value, or a protected directory
+    Invalid = 3,
+    /// The alias or directory already exists
+    Exists = 4,
+    /// A system or I/O error, or anything not classified
+    Error = 5,
+    /// Permission denied, or the alias is shared and read-only
+    Denied = 6,
+}
+
+impl Code {
+    /// The code `err` exits with
+    pub fn of(err: &(dyn Error + 'static)) -> Code {
+        if let Some(failure) = err.downcast_ref::<Failure>() {
+            failure.code
+        } else if let Some(e) = err.downcast_ref::<AliasError>() {
+            Code::from(e)
+        } else if let Some(DatabaseError::Alias(e)) = err.downcast_ref::<DatabaseError>() {
+            Code::from(e)
+        } else {
+            Code::Error
+        }
+    }
+}
+
+impl From<&AliasError> for Code {
+    fn from(e: &AliasError) -> Self {
+        match e {
+            AliasError::NotFound(_) | AliasError::BrokenLink { .. } => Code::NotFound,
+            AliasError::DirectoryNotFound(_) => Code::Missing,
+            AliasError::InvalidAlias { .. } | AliasError::InvalidTag { .. } | AliasError::InvalidEnv { .. } => {
+                Code::Invalid
+            }
+            AliasError::AlreadyExists(_) => Code::Exists,
+            AliasError::PermissionDenied(_) | AliasError::ReadOnly(_) => Code::Denied,
+            AliasError::LinkCycle(_) => Code::Error,
+        }
+    }
+}
+
+impl From<Code> for u8 {
+    fn from(code: Code) -> u8 {
+        code as u8
+    }
+}
+
+/// An error message and the exit code it calls for
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Failure {
+    pub code: Code,
+    pub message: String,
+}
+
+impl Failure {
+    pub fn new(code: Code, message: impl Into<String>) -> Self {
+        Self {
+            code,
+            message: message.into(),
+        }
+    }
+
+    /// Something looked up isn't there (exit code 1)
+    pub fn not_found(message: impl Into<String>) -> Self {
+        Self::new(Code::NotFound, message)
+    }
+
+    /// The user declined to go on (exit code 1)
+    pub fn cancelled(message: impl Into<String>) -> Self {
+        Self::new(Code::NotFound, message)
+    }
+
+    /// Input that can't be used (exit code 3)
+    pub fn invalid(message: impl Into<String>) -> Self {
+        Self::new(Code::Invalid, message)
+    }
+}
+
+impl fmt::Display for Failure {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.message)
+    }
+}
+
+impl Error for Failure {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_code_of() {
+        let boxed: Box<dyn Error> = Failure::invalid("invalid listing index: 9 (valid: 1-3)").into();
+        assert_eq!(Code::of(&*boxed), Code::Invalid);
+
+        let boxed: Box<dyn Error> = AliasError::NotFound("x".to_string()).into();
+        assert_eq!(Code::of(&*boxed), Code::NotFound);
+        let boxed: Box<dyn Error> = DatabaseError::from(AliasError::ReadOnly("x".to_string())).into();
+        assert_eq!(Code::of(&*boxed), Code::Denied);
+
+        // The message doesn't matter, only the type
+        let boxed: Box<dyn Error> = "alias 'x' not found".into();
+        assert_eq!(Code::of(&*boxed), Code::Error);
+        assert_eq!(u8::from(Code::Denied), 6);
+    }
+}
@@ -0,0 +1,188 @@
+//! Alias filter expressions, the one grammar of every `--filter`
+//!
+//! A filter is a tag expression, a glob over alias names, or a bare word:
+//!
+//! - `tag:work` matches aliases tagged `work`
+//! - `tag:work,rust` matches aliases tagged `work` or `rust`
+//! - `proj-*` matches alias names (`*` any run of characters, `?` one character)
+//! - `work` matches the alias named `work` and aliases tagged `work`
+//! - a leading `!` negates the expression, e.g. `!tag:archived`
+//!
+//! Tags and names are compared ignoring case.
+
+use crate::alias::Alias;
+use crate::collate;
+use crate::exit::Failure;
+
+/// A parsed alias filter expression
+#[derive(Debug, Clone, PartialEq)]
+pub enum AliasFilter {
+    /// Matches aliases carrying any of the listed tags
+    Tags(Vec<String>),
+    /// Matches alias names against a glob pattern; without `*` or `?`, also
+    /// aliases carrying it as a tag
+    NameGlob(String),
+    /// Inverts the inner filter
+    Not(Box<AliasFilter>),
+}
+
+impl AliasFilter {
+    /// Parse a filter expression
+    pub fn parse(expr: &str) -> Result<Self, Failure> {
+        let expr = expr.trim();
+        if expr.is_empty() {
+            return Err(Failure::invalid("invalid filter: expression is empty"));
+        }
+
+        if let Some(inner) = expr.strip_prefix('!') {
+            return Ok(AliasFilter::Not(Box::new(AliasFilter::parse(inner)?)));
+        }
+
+        if let Some(list) = expr.strip_prefix("tag:") {
+            let tags: Vec<String> = list
+                .split(',')
+                .map(|t| t.trim().to_lowercase())
+                .filter(|t| !t.is_empty())
+                .collect();
+            if tags.is_empty() {
+                return Err(Failure::invalid(format!("invalid filter '{}': no tags given", expr)));
+            }
+            return Ok(AliasFilter::Tags(tags));
+        }
+
+        Ok(AliasFilter::NameGlob(expr.to_string()))
+    }
+
+    /// Check whether an alias matches this filter
+    pub fn matches(&self, alias: &Alias) -> bool {
+        match self {
+            AliasFilter::Tags(tags) => tags.iter().any(|t| has_tag(alias, t)),
+            AliasFilter::NameGlob(pattern) => {
+                glob_match(&collate::fold_case(pattern), &collate::fold_case(&alias.name))
+                    || (!pattern.contains(['*', '?']) && has_tag(alias, pattern))
+            }
+            AliasFilter::Not(inner) => !inner.matches(alias),
+        }
+    }
+}
+
+fn has_tag(alias: &Alias, tag: &str) -> bool {
+    alias.tags.iter().any(|t| collate::eq_ignore_case(t, tag))
+}
+
+/// Match `text` against a glob pattern supporting `*` and `?`
+pub fn glob_match(pattern: &str, text: &str) -> bool {
+    let pattern: Vec<char> = pattern.chars().collect();
+    let text: Vec<char> = text.chars().collect();
+
+    let (mut p, mut t) = (0, 0);
+    // Position of the last `*` seen and the text index it was matched at
+    let mut backtrack: Option<(usize, usize)> = None;
+
+    while t < text.len() {
+        if p < pattern.len() && (pattern[p] == '?' || pattern[p] == text[t]) {
+            p += 1;
+            t += 1;
+        } else if p < pattern.len() && pattern[p] == '*' {
+            backtrack = Some((p, t));
+            p += 1;
+        } else if let Some((star, matched)) = backtrack {
+            // Let the last `*` absorb one more character
+            p = star + 1;
+            t = matched + 1;
+            backtrack = Some((star, t));
+        } else {
+            return false;
+        }
+    }
+
+    pattern[p..].iter().all(|&c| c == '*')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn alias_with_tags(name: &str, tags: &[&str]) -> Alias {
+        let mut alias = Alias::new(name, "/tmp").unwrap();
+        for tag in tags {
+            alias.add_tag(tag);
+        }
+        alias
+    }
+
+    #[test]
+    fn test_glob_match() {
+        assert!(glob_match("proj-*", "proj-api"));
+        assert!(glob_match("proj-*", "proj-"));
+        assert!(glob_match("*api", "proj-api"));
+        assert!(glob_match("p?oj", "proj"));
+        assert!(glob_match("*", "anything"));
+        assert!(glob_match("a*b*c", "axxbyyc"));
+        assert!(!glob_match("proj-*", "work"));
+        assert!(!glob_match("p?oj", "pj"));
+        assert!(!glob_match("a*b", "acbd"));
+    }
+
+    #[test]
+    fn test_parse_tag_filter() {
+        assert_eq!(
+            AliasFilter::parse("tag:Work,rust").unwrap(),
+            AliasFilter::Tags(vec!["work".to_string(), "rust".to_string()])
+        );
+    }
+
+    #[test]
+    fn test_parse_glob_filter() {
+        assert_eq!(
+            AliasFilter::parse("proj-*").unwrap(),
+            AliasFilter::NameGlob("proj-*".to_string())
+        );
+    }
+
+    #[test]
+    fn test_parse_invalid_filter() {
+        assert!(AliasFilter::parse("").is_err());
+        assert!(AliasFilter::parse("tag:").is_err());
+        assert!(AliasFilter::parse("!").is_err());
+    }
+
+    #[test]
+    fn test_matches() {
+        let api = alias_with_tags("proj-api", &["work", "rust"]);
+        let blog = alias_with_tags("blog", &["personal"]);
+
+        let filter = AliasFilter::parse("tag:rust").unwrap();
+        assert!(filter.matches(&api));
+        assert!(!filter.matches(&blog));
+
+        let filter = AliasFilter::parse("tag:rust,personal").unwrap();
+        assert!(filter.matches(&api));
+        assert!(filter.matches(&blog));
+
+        let filter = AliasFilter::parse("proj-*").unwrap();
+        assert!(filter.matches(&api));
+        assert!(!filter.matches(&blog));
+
+        let filter = AliasFilter::parse("!tag:work").unwrap();
+        assert!(!filter.matches(&api));
+        assert!(filter.matches(&blog));
+    }
+
+    #[test]
+    fn test_bare_word_matches_name_or_tag() {
+        let api = alias_with_tags("proj-api", &["work", "rust"]);
+        let work = alias_with_tags("work", &[]);
+        let blog = alias_with_tags("blog", &["personal"]);
+
+        let filter = AliasFilter::parse("Work").unwrap();
+        assert!(filter.matches(&api));
+        assert!(filter.matches(&work));
+        assert!(!filter.matches(&blog));
+
+        // A glob is only ever a name pattern
+        let filter = AliasFilter::parse("wor?").unwrap();
+        assert!(!filter.matches(&api));
+        assert!(filter.matches(&work));
+    }
+}
@@ -1,5 +1,12 @@
 use std::cmp::min;
 
+use chrono::{DateTime, Utc};
+
+use crate::collate;
+
+/// Days after which the recency half of [`usage_score`] halves
+const USAGE_HALF_LIFE_DAYS: f64 = 30.0;
+
 /// Match result with similarity score
 #[derive(Debug, Clone)]
 pub struct Match {
@@ -7,23 +14,28 @@ pub struct Match {
     pub similarity: f64,
 }
 
-/// Calculate Levenshtein distance between two strings (case-insensitive)
+/// Calculate Levenshtein distance between two strings
+///
+/// Case and accents are ignored (see [`collate::fold`]); the distance counts
+/// characters, not bytes.
 pub fn levenshtein_distance(s1: &str, s2: &str) -> usize {
-    let s1 = s1.to_lowercase();
-    let s2 = s2.to_lowercase();
+    distance(&collate::fold(s1), &collate::fold(s2))
+}
 
+/// Levenshtein distance between two already folded strings
+fn distance(s1: &str, s2: &str) -> usize {
     if s1 == s2 {
         return 0;
     }
-    if s1.is_empty() {
-        return s2.len();
-    }
-    if s2.is_empty() {
-        return s1.len();
-    }
 
     let s1_chars: Vec<char> = s1.chars().collect();
     let s2_chars: Vec<char> = s2.chars().collect();
+    if s1_chars.is_empty() {
+        return s2_chars.len();
+    }
+    if s2_chars.is_empty() {
+        return s1_chars.len();
+    }
 
     let mut prev: Vec<usize> = (0..=s2_chars.len()).collect();
     let mut curr = vec![0; s2_chars.len() + 1];
@@ -43,32 +55,40 @@ pub fn levenshtein_distance(s1: &str, s2: &str) -> usize {
     prev[s2_chars.len()]
 }
 
+/// Similarity of names differing only in accents ("cafe" and "café")
+const ACCENT_SIMILARITY: f64 = 0.95;
+
 /// Calculate similarity score between 0.0 and 1.0
-/// 1.0 = exact match, 0.0 = completely different
+/// 1.0 = exact match (ignoring case), 0.0 = completely different
 pub fn similarity(s1: &str, s2: &str) -> f64 {
-    if s1 == s2 {
+    if s1 == s2 || collate::eq_ignore_case(s1, s2) {
         return 1.0;
     }
 
-    let s1_lower = s1.to_lowercase();
-    let s2_lower = s2.to_lowercase();
-
-    if s1_lower == s2_lower {
-        return 1.0;
+    let s1 = collate::fold(s1);
+    let s2 = collate::fold(s2);
+    if s1 == s2 {
+        return ACCENT_SIMILARITY;
     }
 
-    let max_len = s1.len().max(s2.len());
+    let max_len = s1.chars().count().max(s2.chars().count());
     if max_len == 0 {
         return 1.0;
     }
 
-    let distance = levenshtein_distance(s1, s2);
+    let distance = distance(&s1, &s2);
     1.0 - (distance as f64) / (max_len as f64)
 }
 
-/// Check if query is a substring of target (case-insensitive)
+/// Check if query is a substring of target (ignoring case and accents)
 pub fn is_substring(query: &str, target: &str) -> bool {
-    target.to_lowercase().contains(&query.to_lowercase())
+    collate::fold(target).contains(&collate::fold(query))
+}
+
+/// Score of `query` found inside `candidate`: longer shares score higher
+fn substring_score(query: &str, candidate: &str) -> f64 {
+    let share = query.chars().count() as f64 / candidate.chars().count().max(1) as f64;
+    0.5 + share * 0.5
 }
 
 /// Find strings similar to query from candidates
@@ -87,8 +107,7 @@ pub fn find_similar(query: &str, candidates: &[String], threshold: f64) -> Vec<M
 
         // Boost score for substring matches
         if is_substring(query, candidate) {
-            let substring_boost = query.len() as f64 / candidate.len() as f64;
-            sim = sim.max(0.5 + substring_boost * 0.5);
+            sim = sim.max(substring_score(query, candidate));
         }
 
         if sim >= threshold {
@@ -104,7 +123,7 @@ pub fn find_similar(query: &str, candidates: &[String], threshold: f64) -> Vec<M
         b.similarity
             .partial_cmp(&a.similarity)
             .unwrap_or(std::cmp::Ordering::Equal)
-            .then_with(|| a.value.cmp(&b.value))
+            .then_with(|| collate::compare(&a.value, &b.value))
     });
 
     matches
@@ -133,16 +152,16 @@ pub fn find_matches<'a>(query: &str, candidates: impl Iterator<Item = &'a str>)
         let sim = similarity(query, candidate);
 
         // Boost for substring matches
-        let boosted_sim = if is_substring(query, candidate) {
-            let substring_boost = query.len() as f64 / candidate.len() as f64;
-            sim.max(0.5 + substring_boost * 0.5)
+        let substring = is_substring(query, candidate);
+        let boosted_sim = if substring {
+            sim.max(substring_score(query, candidate))
         } else {
             sim
         };
 
         // Convert similarity (0.0-1.0) to score (0-1000)
         // Only include if there's some match
-        if boosted_sim >= 0.3 || is_substring(query, candidate) {
+        if boosted_sim >= 0.3 || substring {
             let score = (boosted_sim * 1000.0) as i32;
             matches.push((candidate, score));
         }
@@ -150,15 +169,53 @@ pub fn find_matches<'a>(query: &str, candidates: impl Iterator<Item = &'a str>)
 
     // Sort by score descending, then by name ascending for ties
     matches.sort_by(|a, b| {
-        b.1.cmp(&a.1).then_with(|| a.0.cmp(b.0))
+        b.1.cmp(&a.1).then_with(|| collate::compare(a.0, b.0))
     });
 
     matches
 }
 
+/// Usage signal between 0 and 1000 for ranking fuzzy matches
+///
+/// Half comes from `use_count` relative to `max_count` (log-scaled, so a
+/// few uses already count), half from how recently the alias was used.
+pub fn usage_score(
+    use_count: u64,
+    max_count: u64,
+    last_used: Option<DateTime<Utc>>,
+    now: DateTime<Utc>,
+) -> i32 {
+    let frequency = if max_count == 0 {
+        0.0
+    } else {
+        (use_count as f64).ln_1p() / (max_count as f64).ln_1p()
+    };
+    let recency = last_used.map_or(0.0, |t| {
+        let days = (now - t).num_seconds().max(0) as f64 / 86_400.0;
+        0.5f64.powf(days / USAGE_HALF_LIFE_DAYS)
+    });
+    (500.0 * frequency.min(1.0) + 500.0 * recency) as i32
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use chrono::Duration;
+
+    #[test]
+    fn test_usage_score() {
+        let now = Utc::now();
+        assert_eq!(usage_score(0, 0, None, now), 0);
+        assert_eq!(usage_score(10, 10, Some(now), now), 1000);
+        assert_eq!(usage_score(0, 10, None, now), 0);
+
+        // Recency halves every 30 days
+        let month_ago = now - Duration::days(30);
+        assert_eq!(usage_score(10, 10, Some(month_ago), now), 750);
+
+        // Frequency is relative to the most used alias
+        assert!(usage_score(1, 100, None, now) < usage_score(50, 100, None, now));
+    }
 
     #[test]
     fn test_levenshtein_identical() {
@@ -198,10 +255,10 @@ mod tests {
     fn test_similarity_bounds() {
         // Similarity should always be between 0.0 and 1.0
         let sim = similarity("abc", "xyz");
-        assert!(sim >= 0.0 && sim <= 1.0);
+        assert!((0.0..=1.0).contains(&sim));
 
         let sim = similarity("", "test");
-        assert!(sim >= 0.0 && sim <= 1.0);
+        assert!((0.0..=1.0).contains(&sim));
     }
 
     #[test]
@@ -292,4 +349,34 @@ mod tests {
             assert!(matches[0].1 >= matches[1].1);
         }
     }
+
+    #[test]
+    fn test_similarity_counts_characters() {
+        // One edit in a six-letter name, however many bytes a letter takes
+        assert!((similarity("проект", "прожект") - similarity("projet", "project")).abs() < 1e-9);
+        assert_eq!(levenshtein_distance("", "東京"), 2);
+        assert_eq!(levenshtein_distance("東京", "京都"), 2);
+        assert!((similarity("東京都", "東京") - 2.0 / 3.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_matching_ignores_case_and_accents() {
+        assert_eq!(similarity("МОСКВА", "москва"), 1.0);
+        assert_eq!(similarity("Ecole", "école"), ACCENT_SIMILARITY);
+        assert_eq!(similarity("cafe\u{301}", "café"), 1.0);
+        assert!(is_substring("zur", "Zürich"));
+        assert!(is_substring("京", "東京"));
+
+        let matches = find_matches("resume", ["résumé", "résumés", "notes"].into_iter());
+        assert_eq!(matches.iter().map(|m| m.0).collect::<Vec<_>>(), vec!["résumé", "résumés"]);
+
+        let names = vec!["Документы".to_string(), "доклад".to_string(), "загрузки".to_string()];
+        assert_eq!(find_similar_names("документ", &names, 0.6), vec!["Документы"]);
+    }
+
+    #[test]
+    fn test_ties_sort_in_reading_order() {
+        let matches = find_matches("x", ["Éx", "ax", "Zx", "ex"].into_iter());
+        assert_eq!(matches.iter().map(|m| m.0).collect::<Vec<_>>(), vec!["ax", "ex", "Éx", "Zx"]);
+    }
 }
@@ -2,17 +2,51 @@
 //!
 //! This library provides functionality for managing directory aliases,
 //! enabling quick navigation between frequently used directories.
+//!
+//! # Stability
+//!
+//! [`Alias`], [`Database`], [`Config`] and the functions and result types in
+//! [`commands`] (such as [`commands::navigate::NavigationResult`] and
+//! [`commands::list::ListResult`]) follow semver. [`Alias`], [`Config`],
+//! [`config::UserConfig`] and [`commands::register::RegisterOptions`] are
+//! `#[non_exhaustive]`: minor releases may add fields, so build them with
+//! their constructors rather than struct literals.
+//!
+//! Commands don't print. They return results and leave rendering to the
+//! caller, using the `format_*` helpers where a text form is wanted; the
+//! commands that ask or warn take a [`context::Ctx`] and write their prompts
+//! and messages through it. Programs they run (git, mount and template init
+//! commands) write to stderr. Loading and saving don't print either: what
+//! the user should hear about is left in [`config::Config::notices`] and
+//! [`database::Database::take_warnings`].
+//!
+//! [`cli`], [`ui`], [`output`], [`commands::install`] and [`commands::update`]
+//! belong to the `goto-bin` frontend and may change in any release.
 
 use std::io::{self, IsTerminal, Write};
 
 pub mod alias;
+pub mod backup;
 pub mod cli;
+pub mod collate;
 pub mod commands;
 pub mod config;
+pub mod context;
 pub mod database;
+mod document;
+pub mod exit;
+pub mod filter;
 pub mod fuzzy;
+pub mod log;
+pub mod output;
+pub mod plan;
+pub mod recovery;
+pub mod rules;
 pub mod stack;
 pub mod table;
+pub mod timings;
+pub mod ui;
+pub mod volume;
 
 pub use alias::Alias;
 pub use cli::{parse_args, Args, Command};
@@ -24,6 +58,8 @@ pub use table::{TableStyle, create_table};
 /// Prompt user for y/n confirmation.
 ///
 /// Returns the default value if stdin is not a terminal (for piped/non-interactive use).
+/// Commands ask through [`context::Ctx::confirm`], which also honours `--no-input`
+/// and `--force`.
 /// On a terminal, displays the message with (Y/n) or (y/N) suffix based on default,
 /// then parses user input: empty returns default, y/yes returns true, n/no returns false.
 ///
@@ -57,10 +93,30 @@ pub fn confirm(message: &str, default: bool) -> io::Result<bool> {
     })
 }
 
+/// Prompt user for a line of text.
+///
+/// Returns `None` if stdin is not a terminal or when the input is empty
+/// (cancel); otherwise the trimmed input. Commands ask through
+/// [`context::Ctx::prompt_input`], which also honours `--no-input`.
+pub fn prompt_input(message: &str) -> io::Result<Option<String>> {
+    if !io::stdin().is_terminal() {
+        return Ok(None);
+    }
+
+    eprint!("{} ", message);
+    io::stderr().flush()?;
+
+    let mut input = String::new();
+    io::stdin().read_line(&mut input)?;
+
+    let input = input.trim();
+    Ok((!input.is_empty()).then(|| input.to_string()))
+}
+
 /// Prompt user to select from numbered options.
 ///
 /// Returns the selected index (0-based) on valid input, None on cancel.
-/// Returns None immediately if stdin is not a terminal (non-interactive mode).
+/// Returns None immediately if stdin is not a terminal.
 ///
 /// # Arguments
 /// * `options` - List of option labels to display
@@ -73,6 +129,18 @@ pub fn confirm(message: &str, default: bool) -> io::Result<bool> {
 pub fn prompt_selection(
     options: &[&str],
     similarity_scores: Option<&[f64]>,
+) -> io::Result<Option<usize>> {
+    prompt_selection_with_details(options, similarity_scores, None)
+}
+
+/// Prompt user to select from numbered options, each followed by a detail line.
+///
+/// Behaves like [`prompt_selection`]; `details`, when given, are printed
+/// indented below the matching option (e.g. a preview of the directory).
+pub fn prompt_selection_with_details(
+    options: &[&str],
+    similarity_scores: Option<&[f64]>,
+    details: Option<&[String]>,
 ) -> io::Result<Option<usize>> {
     // Non-interactive mode: return None immediately
     if !io::stdin().is_terminal() {
@@ -91,6 +159,9 @@ pub fn prompt_selection(
         } else {
             eprintln!("  [{}] {}", i + 1, option);
         }
+        if let Some(detail) = details.and_then(|d| d.get(i)) {
+            eprintln!("      {}", detail);
+        }
     }
 
     eprint!("Select [1-{}] or Enter to cancel: ", options.len());
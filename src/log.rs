@@ -0,0 +1,125 @@
+//! Lightweight debug logging to stderr
+//!
+//! Enabled with `-V/--verbose` or `GOTO_LOG=debug` (or `info` for the
+//! coarser messages only). Each line is timestamped and names the module it
+//! came from, e.g.
+//!
+//! ```text
+//! [2024-05-01T09:30:12.345Z DEBUG goto::database] read 42 aliases from ~/.local/share/goto/aliases.toml
+//! ```
+//!
+//! Use the [`debug!`](crate::debug) and [`info!`](crate::info) macros; their
+//! arguments are only formatted when the level is enabled.
+
+use std::fmt;
+use std::io::Write;
+use std::sync::atomic::{AtomicU8, Ordering};
+
+use chrono::Utc;
+
+/// Verbosity, from quietest to noisiest
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Level {
+    Off = 0,
+    /// Files read and written, commands run
+    Info = 1,
+    /// Matching decisions and everything else
+    Debug = 2,
+}
+
+impl Level {
+    /// Parse a `GOTO_LOG` value; unknown values enable debug output
+    pub fn parse(value: &str) -> Self {
+        match value.trim().to_lowercase().as_str() {
+            "" | "0" | "off" | "false" | "none" => Level::Off,
+            "info" => Level::Info,
+            _ => Level::Debug,
+        }
+    }
+
+    fn label(self) -> &'static str {
+        match self {
+            Level::Off => "OFF",
+            Level::Info => "INFO",
+            Level::Debug => "DEBUG",
+        }
+    }
+}
+
+static LEVEL: AtomicU8 = AtomicU8::new(Level::Off as u8);
+
+/// Set the level from `--verbose` and `GOTO_LOG` (the louder one wins)
+pub fn init(verbose: bool) {
+    let from_env = std::env::var("GOTO_LOG")
+        .map(|v| Level::parse(&v))
+        .unwrap_or(Level::Off);
+    let flag = if verbose { Level::Debug } else { Level::Off };
+    set_level(from_env.max(flag));
+}
+
+/// Set the level directly
+pub fn set_level(level: Level) {
+    LEVEL.store(level as u8, Ordering::Relaxed);
+}
+
+/// Whether messages at `level` are written
+pub fn enabled(level: Level) -> bool {
+    level != Level::Off && LEVEL.load(Ordering::Relaxed) >= level as u8
+}
+
+/// Format one log line
+pub fn format_line(level: Level, module: &str, args: fmt::Arguments) -> String {
+    format!(
+        "[{} {} {}] {}",
+        Utc::now().format("%Y-%m-%dT%H:%M:%S%.3fZ"),
+        level.label(),
+        module,
+        args
+    )
+}
+
+/// Write a log line to stderr (use the macros instead)
+pub fn write(level: Level, module: &str, args: fmt::Arguments) {
+    let _ = writeln!(std::io::stderr().lock(), "{}", format_line(level, module, args));
+}
+
+/// Log a debug message: matching decisions and other details
+#[macro_export]
+macro_rules! debug {
+    ($($arg:tt)*) => {
+        if $crate::log::enabled($crate::log::Level::Debug) {
+            $crate::log::write($crate::log::Level::Debug, module_path!(), format_args!($($arg)*));
+        }
+    };
+}
+
+/// Log an info message: files read and written
+#[macro_export]
+macro_rules! info {
+    ($($arg:tt)*) => {
+        if $crate::log::enabled($crate::log::Level::Info) {
+            $crate::log::write($crate::log::Level::Info, module_path!(), format_args!($($arg)*));
+        }
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_level() {
+        assert_eq!(Level::parse(""), Level::Off);
+        assert_eq!(Level::parse("off"), Level::Off);
+        assert_eq!(Level::parse("INFO"), Level::Info);
+        assert_eq!(Level::parse("debug"), Level::Debug);
+        assert_eq!(Level::parse("1"), Level::Debug);
+    }
+
+    #[test]
+    fn test_format_line() {
+        let line = format_line(Level::Debug, "goto::database", format_args!("read {} aliases", 3));
+        assert!(line.starts_with('['));
+        assert!(line.ends_with("Z DEBUG goto::database] read 3 aliases"), "{}", line);
+    }
+}
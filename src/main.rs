@@ -1,15 +1,29 @@
 //! goto - CLI entry point for the goto directory navigation tool
 
 use std::env;
+use std::path::{Path, PathBuf};
 use std::process::ExitCode;
+use std::str::FromStr;
 
 use goto::cli::{self, Command};
 use goto::commands;
-use goto::config::Config;
+use goto::commands::install::ShellType;
+use goto::commands::navigate::NavigationResult;
+use goto::config::{Config, ConfigError};
+use goto::context::Ctx;
 use goto::database::Database;
+use goto::exit::{self, Failure};
+use goto::plan;
+use goto::table::{PathStyle, TableStyle};
+use goto::timings::{self, Phase};
+use goto::{enote, errln, note, outln};
 
 fn main() -> ExitCode {
-    match run() {
+    let result = run();
+    if timings::wants_report() {
+        eprintln!("{}", timings::report());
+    }
+    match result {
         Ok(()) => ExitCode::SUCCESS,
         Err(code) => ExitCode::from(code),
     }
@@ -27,6 +41,20 @@ fn run() -> Result<(), u8> {
         }
     };
 
+    goto::log::init(parsed.verbose);
+    goto::debug!("goto {} running {:?}", cli::version(), parsed.command);
+    goto::output::set_path_format(parsed.path_format);
+    timings::set_report(parsed.timings);
+    let cd_file = parsed.cd_file.clone().or_else(|| env::var_os("GOTO_CD_FILE").filter(|f| !f.is_empty()).map(PathBuf::from));
+    goto::output::set_cd_file(cd_file);
+    let mut ctx = Ctx::stdio(goto::context::ExecContext {
+        quiet: parsed.quiet,
+        no_input: parsed.no_input,
+    });
+    ctx.force = parsed.command.force();
+    let ctx = &mut ctx;
+    let profile = parsed.profile.clone().or_else(|| env::var("GOTO_PROFILE").ok());
+
     // Handle commands that don't need config/database
     match &parsed.command {
         Command::Help => {
@@ -35,23 +63,32 @@ fn run() -> Result<(), u8> {
         }
         Command::Version => {
             // Try to show version with update status if config is available
-            if let Ok(config) = Config::load() {
-                println!("{}", commands::update::version_with_update_status(&config));
+            if let Ok(config) = Config::load_profile(profile.as_deref()) {
+                print_notices(ctx, &config.notices);
+                outln!(ctx, "{}", commands::update::version_with_update_status(&config));
             } else {
-                println!("goto version {}", cli::version());
+                outln!(ctx, "goto version {}", cli::version());
             }
             return Ok(());
         }
-        Command::Install { shell, skip_rc, dry_run } => {
+        Command::PromptSnippet { shell } => {
+            let snippet = commands::prompt::snippet(shell).map_err(|e| {
+                errln!(ctx, "{}", e);
+                3u8
+            })?;
+            ctx.print(snippet);
+            return Ok(());
+        }
+        Command::Install { shell, skip_rc, dry_run, watch_timer } => {
             use commands::install::{InstallOptions, ShellType};
 
             let shell_type = match shell {
                 Some(s) => ShellType::from_str(s).map_err(|e| {
-                    eprintln!("{}", e);
+                    errln!(ctx, "{}", e);
                     3u8
                 })?,
                 None => ShellType::detect().map_err(|e| {
-                    eprintln!("{}", e);
+                    errln!(ctx, "{}", e);
                     3u8
                 })?,
             };
@@ -59,201 +96,1220 @@ fn run() -> Result<(), u8> {
             let mut options = InstallOptions::new(shell_type);
             options.skip_rc = *skip_rc;
             options.dry_run = *dry_run;
+            options.watch_timer = *watch_timer;
+            // Installing shouldn't need a readable config; without one there's no fallback
+            if let Ok(config) = Config::load_profile(profile.as_deref()) {
+                print_notices(ctx, &config.notices);
+                options.cd_fallback = config.user.install.cd_fallback;
+            }
 
             commands::install::install(&options).map_err(|e| {
-                eprintln!("{}", e);
+                errln!(ctx, "{}", e);
                 5u8
             })?;
             return Ok(());
         }
+        // Checked before loading, which would stop at the first error
+        Command::Config { validate: true } => {
+            let path = goto::config::config_file(profile.as_deref()).map_err(|e| handle_error(ctx, e.into()))?;
+            let problems = commands::config::validate_config(&path).map_err(|e| handle_error(ctx, e.into()))?;
+            if problems.is_empty() {
+                note!(ctx, "{}: no problems found", path.display());
+                return Ok(());
+            }
+            ctx.print(&commands::config::format_problems(&path, &problems));
+            let failed = problems.iter().any(|p| p.severity == commands::config::Severity::Error);
+            return if failed { Err(1) } else { Ok(()) };
+        }
         _ => {}
     }
 
-    let config = Config::load().map_err(|e| {
-        eprintln!("Error loading config: {}", e);
+    let mut config = timings::measure(Phase::Config, || Config::load_profile(profile.as_deref())).map_err(|e| {
+        errln!(ctx, "Error loading config: {}", e);
+        if matches!(e, ConfigError::InvalidProfile(_)) {
+            3u8
+        } else {
+            5u8
+        }
+    })?;
+    print_notices(ctx, &config.notices);
+    if parsed.absolute_dates {
+        config.user.display.relative_dates = false;
+    }
+    // Completion and prompt hooks run too often to repeat the warnings
+    if !ctx.quiet
+        && !matches!(
+            parsed.command,
+            Command::Complete { .. } | Command::Where { .. } | Command::Counter | Command::Event { .. }
+        )
+    {
+        let warnings = commands::config::load_warnings(&config);
+        if !warnings.is_empty() {
+            errln!(ctx, "{}", commands::config::format_problems(&config.config_path, &warnings).trim_end());
+        }
+    }
+
+    if !parsed.command.needs_database() {
+        return run_without_database(parsed.command, &config, ctx);
+    }
+
+    let mut db = timings::measure(Phase::Database, || Database::load(&config)).map_err(|e| {
+        errln!(ctx, "Error loading database: {}", e);
         5u8
     })?;
+    print_notices(ctx, &db.take_warnings());
+    // Colors set with --tag-info win over [tag_colors]
+    config.user.tag_colors = db.tag_colors(&config.user.tag_colors);
+
+    let result = run_with_database(parsed.command, &config, &mut db, ctx);
+    // Saving on drop would be too late to show a slow save
+    let _ = db.save();
+    print_notices(ctx, &db.take_warnings());
+    result
+}
 
-    // Handle config command (needs config but not database)
-    if matches!(parsed.command, Command::Config) {
-        commands::config::show_config(&config);
-        return Ok(());
+/// Show what loading or saving left for the user, on stderr
+fn print_notices(ctx: &mut Ctx, notices: &[String]) {
+    for notice in notices {
+        errln!(ctx, "{}", notice);
     }
+}
 
-    // Handle update commands
-    match &parsed.command {
-        Command::Update => {
-            commands::update::perform_update(&config).map_err(|e| {
-                eprintln!("{}", e);
+/// Dispatch a command that needs config but not the alias database
+fn run_without_database(command: Command, config: &Config, ctx: &mut Ctx) -> Result<(), u8> {
+    match command {
+        Command::Ssh { host, alias, print } => {
+            let path = commands::ssh::remote_path(config, &host, &alias).map_err(|e| handle_error(ctx, e))?;
+            if print {
+                outln!(ctx, "{}", commands::ssh::ssh_command_line(&host, &path));
+                return Ok(());
+            }
+            match commands::ssh::connect(&host, &path).map_err(|e| handle_error(ctx, e))? {
+                0 => Ok(()),
+                code => Err(u8::try_from(code).unwrap_or(1)),
+            }
+        }
+
+        Command::Event { enter } => {
+            use commands::time_spent::{Event, EventKind};
+
+            let cwd = env::current_dir().map_err(|e| handle_error(ctx, e.into()))?.to_string_lossy().into_owned();
+            let kind = match enter {
+                Some(alias) => EventKind::Enter { alias, path: cwd },
+                None => EventKind::Leave { cwd },
+            };
+            commands::time_spent::record(config, &Event::now(kind)).map_err(|e| handle_error(ctx, e.into()))
+        }
+
+        Command::SshImport { host, file } => {
+            let count = commands::ssh::import_host(config, &host, &file).map_err(|e| handle_error(ctx, e))?;
+            note!(ctx, "Stored {} aliases for host '{}'", count, host);
+            Ok(())
+        }
+
+        Command::Config { .. } => {
+            ctx.print(&config.format_config());
+            Ok(())
+        }
+        Command::Where { .. } => {
+            let quiet = ctx.quiet;
+            let cwd = env::current_dir().map_err(|e| handle_error(ctx, e.into()))?;
+            match commands::prompt::cached_where(config, &cwd) {
+                Ok(Some(location)) => {
+                    outln!(ctx, "{}", location);
+                    Ok(())
+                }
+                Ok(None) if !quiet => {
+                    errln!(ctx, "current directory not found in any alias: {}", cwd.display());
+                    Err(1)
+                }
+                Ok(None) => Err(1),
+                Err(_) if quiet => Err(1),
+                Err(e) => Err(handle_error(ctx, e)),
+            }
+        }
+        Command::Counter => {
+            outln!(ctx, "{}", commands::counter::count(config));
+            Ok(())
+        }
+        Command::Profiles => {
+            for profile in commands::config::list_profiles(config).map_err(|e| handle_error(ctx, e))? {
+                outln!(ctx, "{}", profile);
+            }
+            Ok(())
+        }
+
+        // Restore replaces the database file directly
+        Command::Backups => {
+            let backups = commands::backup::list_backups(config).map_err(|e| handle_error(ctx, e))?;
+            if backups.is_empty() {
+                let dir = config.backup_dir().map_err(|e| handle_error(ctx, e.into()))?;
+                outln!(ctx, "No backups found in {}", dir.display());
+            } else {
+                outln!(ctx, "{}", commands::backup::format_backups(&backups, table_style(config)));
+            }
+            Ok(())
+        }
+        Command::Fsck { dry_run } => {
+            let toml_path = config.aliases_path.with_extension("toml");
+            match goto::recovery::recover(&toml_path, dry_run).map_err(|e| handle_error(ctx, e.into()))? {
+                None => outln!(ctx, "No problems found in {}", toml_path.display()),
+                Some(recovery) => {
+                    outln!(ctx, "{} is damaged: {}", toml_path.display(), recovery.error);
+                    outln!(ctx, "{}", recovery.summary());
+                    if dry_run {
+                        outln!(ctx, "Dry run: nothing was changed");
+                    } else {
+                        outln!(ctx, "The damaged file was kept as {}", recovery.copy.display());
+                    }
+                }
+            }
+            Ok(())
+        }
+        Command::Restore { timestamp, .. } => {
+            let (backup, count) =
+                commands::backup::restore(ctx, config, &timestamp).map_err(|e| handle_error(ctx, e))?;
+            note!(ctx, "Restored {} aliases from backup {}", count, backup.timestamp);
+            Ok(())
+        }
+
+        // Needs config for --purge, but not the database
+        Command::Uninstall { shell, purge, dry_run, .. } => {
+            use commands::install::{ShellType, UninstallOptions};
+
+            let shell = match shell {
+                Some(s) => Some(ShellType::from_str(&s).map_err(|e| {
+                    errln!(ctx, "{}", e);
+                    3u8
+                })?),
+                None => None,
+            };
+            let options = UninstallOptions {
+                shell,
+                purge,
+                force: ctx.force,
+                dry_run,
+            };
+            let owned = goto::config::purge_dirs().map_err(|e| handle_error(ctx, e.into()))?;
+            if purge {
+                for dir in [config.database_path.as_path(), config.config_dir()] {
+                    if dir.exists() && !owned.iter().any(|d| dir.starts_with(d)) {
+                        errln!(ctx, "Keeping {}: set through GOTO_DB or GOTO_CONFIG, delete it yourself", dir.display());
+                    }
+                }
+            }
+            let owned: Vec<&Path> = owned.iter().map(PathBuf::as_path).collect();
+            commands::install::uninstall(ctx, &options, &owned).map_err(|e| handle_error(ctx, e))
+        }
+
+        Command::Update { version } => {
+            commands::update::perform_update(config, version.as_deref()).map_err(|e| {
+                errln!(ctx, "{}", e);
                 5u8
             })?;
-            return Ok(());
+            Ok(())
         }
+
         Command::CheckUpdate => {
-            match commands::update::check_for_updates(&config, true) {
+            match commands::update::check_for_updates(config, true) {
                 Ok(Some(version)) => {
-                    println!(
+                    outln!(ctx, 
                         "Update available: {} (current: {})",
                         version,
                         commands::update::current_version()
                     );
-                    println!("Run 'goto --update' to upgrade.");
+                    outln!(ctx, "Run 'goto --update' to upgrade.");
                 }
                 Ok(None) => {
-                    println!(
+                    outln!(ctx, 
                         "You are running the latest version ({}).",
                         commands::update::current_version()
                     );
                 }
                 Err(e) => {
-                    eprintln!("Failed to check for updates: {}", e);
+                    errln!(ctx, "Failed to check for updates: {}", e);
                     return Err(5);
                 }
             }
-            return Ok(());
+            Ok(())
         }
-        _ => {}
-    }
 
-    let mut db = Database::load(&config).map_err(|e| {
-        eprintln!("Error loading database: {}", e);
-        5u8
-    })?;
+        Command::UpdateCheckDaemon => {
+            // Detached from the terminal; failures are recorded in the cache
+            let _ = commands::update::check_for_updates(config, true);
+            Ok(())
+        }
 
-    match parsed.command {
-        Command::Help | Command::Version | Command::Config | Command::Install { .. }
-        | Command::Update | Command::CheckUpdate => unreachable!(),
+        Command::BenchSelftest { max_ms } => {
+            use commands::bench;
+
+            let timings = bench::selftest(bench::SYNTHETIC_ALIASES, bench::SELFTEST_RUNS)
+                .map_err(|e| handle_error(ctx, e))?;
+            outln!(ctx, "Alias resolution, {} aliases, {} runs each", bench::SYNTHETIC_ALIASES, bench::SELFTEST_RUNS);
+            outln!(ctx, "{}", bench::format_timings(&timings, table_style(config)));
+            let Some(max_ms) = max_ms else {
+                return Ok(());
+            };
+            let slow = bench::over_budget(&timings, std::time::Duration::from_millis(max_ms));
+            if slow.is_empty() {
+                return Ok(());
+            }
+            for timing in slow {
+                errln!(ctx, "{} took {:.2} ms on average (budget {} ms)", timing.step, timing.mean.as_secs_f64() * 1000.0, max_ms);
+            }
+            Err(1)
+        }
 
         Command::PruneSnooze { days } => {
-            commands::prune::snooze_notifications(&config, days).map_err(handle_error)
+            commands::prune::snooze_notifications(config, days).map_err(|e| handle_error(ctx, e))?;
+            note!(ctx, "Prune notifications snoozed for {} days.", days);
+            Ok(())
+        }
+
+        Command::Watch { dir: Some(dir) } => {
+            let (root, added) = commands::watch::watch(config, &dir).map_err(|e| handle_error(ctx, e))?;
+            if added {
+                note!(ctx, "Watching {} for new directories", root);
+            } else {
+                note!(ctx, "Already watching {}", root);
+            }
+            Ok(())
+        }
+        Command::Watch { dir: None } => {
+            let roots = commands::watch::roots(config);
+            if roots.is_empty() {
+                outln!(ctx, "Not watching any directories");
+            }
+            for root in roots {
+                outln!(ctx, "{}", root);
+            }
+            Ok(())
+        }
+        Command::Unwatch { dir } => {
+            let root = commands::watch::unwatch(config, &dir).map_err(|e| handle_error(ctx, e))?;
+            note!(ctx, "Stopped watching {}", root);
+            Ok(())
+        }
+
+        Command::Pop => {
+            let path = commands::stack::pop(config).map_err(|e| handle_error(ctx, e))?;
+            ctx.cd(&path, "");
+            commands::navigate::record_previous(config);
+            Ok(())
+        }
+
+        Command::Previous => {
+            let path = commands::navigate::previous(config).map_err(|e| handle_error(ctx, e))?;
+            ctx.cd(&path, "");
+            Ok(())
         }
 
-        Command::List { sort, filter } => {
-            let result = commands::list::list_with_options(&db, &config, sort.as_deref(), filter.as_deref())
-                .map_err(handle_error);
-            if result.is_ok() {
-                commands::prune::notify_if_stale_aliases(&config, &db);
+        Command::ShowStack { all_sessions: false } => {
+            let entries = commands::stack::entries(config).map_err(|e| handle_error(ctx, e))?;
+            if entries.is_empty() {
+                errln!(ctx, "Directory stack is empty");
             }
-            result
+            for entry in entries {
+                outln!(ctx, "{}", entry);
+            }
+            Ok(())
         }
 
-        Command::ListNames => commands::list::list_names(&db).map_err(handle_error),
+        Command::ShowStack { all_sessions: true } => {
+            let stacks = commands::stack::all_stacks(config).map_err(|e| handle_error(ctx, e))?;
+            if stacks.is_empty() {
+                errln!(ctx, "No directory stacks");
+            }
+            for (i, stack) in stacks.iter().enumerate() {
+                if i > 0 {
+                    outln!(ctx);
+                }
+                let marker = if stack.current && config.user.stack.per_session {
+                    " (current)"
+                } else {
+                    ""
+                };
+                outln!(ctx, "Session {}{}:", stack.session, marker);
+                for entry in &stack.entries {
+                    outln!(ctx, "  {}", entry);
+                }
+            }
+            Ok(())
+        }
 
-        Command::ListTagsRaw => commands::tags::list_tags_raw(&db).map_err(handle_error),
+        // Help, version, install and prompt snippets are handled before config is loaded
+        _ => unreachable!("command needs the database"),
+    }
+}
+
+/// Dispatch a command that works on the alias database
+fn run_with_database(command: Command, config: &Config, db: &mut Database, ctx: &mut Ctx) -> Result<(), u8> {
+    match command {
+        Command::Help | Command::Version | Command::Config { .. } | Command::Profiles
+        | Command::Install { .. } | Command::Uninstall { .. } | Command::Ssh { .. } | Command::SshImport { .. } | Command::Event { .. }
+        | Command::Backups
+        | Command::Fsck { .. }
+        | Command::Restore { .. }
+        | Command::Update { .. } | Command::CheckUpdate | Command::UpdateCheckDaemon
+        | Command::BenchSelftest { .. }
+        | Command::PruneSnooze { .. } | Command::Watch { .. } | Command::Unwatch { .. }
+        | Command::Pop | Command::Previous | Command::Counter
+        | Command::ShowStack { .. } | Command::PromptSnippet { .. } => {
+            unreachable!()
+        }
+
+        Command::List { sort, filter, porcelain: true, .. } => {
+            let options = commands::list::ListOptions {
+                sort: sort.as_deref(),
+                filter: filter.as_deref(),
+                ..Default::default()
+            };
+            let result = commands::list::list(db, config, &options).map_err(|e| handle_error(ctx, e))?;
+            for alias in &result.aliases {
+                outln!(ctx, "{}", commands::list::porcelain_line(alias));
+            }
+            Ok(())
+        }
+
+        Command::List { sort, filter, columns, group_by, wide, .. } => {
+            let options = commands::list::ListOptions {
+                sort: sort.as_deref(),
+                filter: filter.as_deref(),
+                columns: columns.as_deref(),
+                group_by: group_by.as_deref(),
+            };
+            let result = commands::list::list(db, config, &options).map_err(|e| handle_error(ctx, e))?;
+            if result.aliases.is_empty() {
+                match &filter {
+                    Some(tag) => errln!(ctx, "No aliases with tag '{}'", tag),
+                    None => errln!(ctx, "No aliases registered"),
+                }
+                return Ok(());
+            }
+            outln!(ctx, "{}", commands::list::format_list(&result, config, wide));
+            // Only `goto %N` depends on it, so listing doesn't fail when it can't be written
+            if let Err(e) = commands::list::record_listing(config, &result.aliases) {
+                goto::debug!("could not record the listing: {}", e);
+            }
+            commands::prune::notify_if_stale_aliases(ctx, config, db);
+            // Usage was shown anyway; write the logged part into the database
+            db.compact_usage_log();
+            Ok(())
+        }
+
+        Command::Complete { words } => {
+            let candidates = if config.user.display.completion_hints {
+                commands::complete::complete_with_hints(db, &words, commands::complete::HINT_TIMEOUT)
+            } else {
+                commands::complete::complete(db, &words)
+            };
+            for candidate in candidates {
+                outln!(ctx, "{}", candidate);
+            }
+            Ok(())
+        }
+
+        Command::ListNames => {
+            for name in commands::list::list_names(db) {
+                outln!(ctx, "{}", name);
+            }
+            Ok(())
+        }
+
+        Command::ListTagsRaw { with_counts } => {
+            print_tag_lines(ctx, &commands::tags::list_tags(db), with_counts);
+            Ok(())
+        }
+
+        Command::CompleteTag { prefix, with_counts } => {
+            print_tag_lines(ctx, &commands::tags::complete_tag(db, &prefix), with_counts);
+            Ok(())
+        }
 
         Command::Stats => {
-            let result = commands::stats::stats(&db, &config).map_err(handle_error);
-            if result.is_ok() {
-                commands::prune::notify_if_stale_aliases(&config, &db);
+            let stats = commands::stats::stats(db);
+            outln!(ctx, "{}", commands::stats::format_stats(&stats, table_style(config), config.user.display.date_style()));
+            if config.user.privacy.track_time {
+                let report = commands::time_spent::report(config).map_err(|e| handle_error(ctx, e.into()))?;
+                outln!(ctx, "\n{}", report);
+            }
+            if stats.total_aliases > 0 {
+                commands::prune::notify_if_stale_aliases(ctx, config, db);
+            }
+            db.compact_usage_log();
+            Ok(())
+        }
+
+        Command::Register { name, tags, link: Some(target), dry_run, .. } => {
+            if dry_run {
+                return preview_changes(ctx, config, db, |_, db| {
+                    commands::register::register_link(db, &name, &target, &tags)
+                });
             }
-            result
+            let alias = commands::register::register_link(db, &name, &target, &tags)
+                .map_err(|e| handle_error(ctx, e))?;
+            note!(ctx, "Registered {}", describe_alias(&alias));
+            Ok(())
+        }
+
+        Command::Register { name, path, tags, no_resolve, temporary, allow_missing, dry_run, .. } => {
+            let mut options = commands::register::RegisterOptions::from_config(config);
+            options.force = ctx.force;
+            options.resolve_symlinks &= !no_resolve;
+            options.temporary = temporary;
+            options.allow_missing = allow_missing;
+            if dry_run {
+                return preview_changes(ctx, config, db, |ctx, db| {
+                    commands::register::register_with_options(ctx, db, &name, &path, &tags, &options)
+                });
+            }
+            let alias = commands::register::register_with_options(ctx, db, &name, &path, &tags, &options)
+                .map_err(|e| handle_error(ctx, e))?;
+            let kind = if temporary { "temporary " } else { "" };
+            note!(ctx, "Registered {}{}", kind, describe_alias(&alias));
+            if alias.pending {
+                outln!(ctx, "The directory doesn't exist yet; '{}' is pending until it does", alias.name);
+            }
+            Ok(())
+        }
+
+        Command::Clone { url, alias, tags } => {
+            let alias = commands::clone::clone(ctx, db, config, &url, alias.as_deref(), &tags)
+                .map_err(|e| handle_error(ctx, e))?;
+            enote!(ctx, "Registered {}", describe_alias(&alias));
+            ctx.cd(&alias.path, "");
+            Ok(())
+        }
+
+        Command::New {
+            name,
+            path,
+            template,
+            tags,
+        } => {
+            let alias = commands::template::new_project(ctx, db, config, &name, &path, template.as_deref(), &tags)
+                .map_err(|e| handle_error(ctx, e))?;
+            enote!(ctx, "Registered {}", describe_alias(&alias));
+            ctx.cd(&alias.path, "");
+            Ok(())
+        }
+
+        Command::Unregister { name, dry_run: true } => {
+            preview_changes(ctx, config, db, |_, db| commands::register::unregister(db, &name))
+        }
+
+        Command::Unregister { name, .. } => {
+            let alias = commands::register::unregister(db, &name).map_err(|e| handle_error(ctx, e))?;
+            note!(ctx, "Unregistered '{}'", alias.name);
+            Ok(())
+        }
+
+        Command::Expand { alias } => {
+            let path = commands::navigate::expand(db, &alias).map_err(|e| handle_error(ctx, e))?;
+            ctx.path(&path);
+            Ok(())
         }
 
-        Command::Register { name, path, tags, force } => {
-            commands::register::register_with_tags(&mut db, &name, &path, &tags, force)
-                .map_err(handle_error)
+        Command::Env { alias, assignments, unset } => {
+            use commands::env::EnvChange;
+
+            match commands::env::env(db, &alias, &assignments, &unset).map_err(|e| handle_error(ctx, e))? {
+                EnvChange::Show(env) if env.is_empty() => {
+                    outln!(ctx, "No environment set for '{}'", alias);
+                }
+                EnvChange::Show(env) => {
+                    for (key, value) in env {
+                        outln!(ctx, "{}={}", key, value);
+                    }
+                }
+                EnvChange::Updated { unset, set } => {
+                    for key in unset {
+                        note!(ctx, "Unset {} on '{}'", key, alias);
+                    }
+                    for (key, value) in set {
+                        note!(ctx, "Set {}={} on '{}'", key, value, alias);
+                    }
+                }
+            }
+            Ok(())
         }
 
-        Command::Unregister { name } => {
-            commands::register::unregister(&mut db, &name).map_err(handle_error)
+        Command::Check => {
+            let issues = commands::check::audit(db);
+            if issues.is_empty() {
+                note!(ctx, "All {} aliases look good.", db.len());
+                return Ok(());
+            }
+            outln!(ctx, "{}", commands::check::format_issues(&issues, table_style(config)));
+            Err(handle_error(ctx, Failure::new(exit::Code::NotFound, commands::check::failure_message(&issues)).into()))
         }
 
-        Command::Expand { alias } => commands::navigate::expand(&db, &alias).map_err(handle_error),
+        Command::Duplicates => {
+            let pairs = commands::duplicates::find_all(db);
+            if pairs.is_empty() {
+                outln!(ctx, "No overlapping aliases.");
+            } else {
+                outln!(ctx, "{}", commands::duplicates::format_overlaps(&pairs, config));
+            }
+            Ok(())
+        }
 
         Command::Cleanup { dry_run } => {
-            commands::cleanup::cleanup(&mut db, &config, dry_run).map_err(handle_error)
+            let result = commands::cleanup::cleanup(db, config, dry_run).map_err(|e| handle_error(ctx, e))?;
+            if !result.unreachable.is_empty() {
+                outln!(ctx, 
+                    "Skipping {} aliases whose paths did not respond (unreachable mount?):",
+                    result.unreachable.len()
+                );
+                outln!(ctx, "{}", commands::cleanup::format_unreachable(&result.unreachable, table_style(config)));
+            }
+            if !result.unmounted.is_empty() {
+                outln!(ctx, "Skipping {} aliases on volumes that are not mounted:", result.unmounted.len());
+                outln!(ctx, "{}", commands::cleanup::format_unmounted(&result.unmounted, table_style(config)));
+            }
+            let removed = result.removed;
+            if removed.is_empty() {
+                if result.unreachable.is_empty() && result.unmounted.is_empty() {
+                    outln!(ctx, "All aliases point to valid paths.");
+                }
+                return Ok(());
+            }
+            if dry_run {
+                outln!(ctx, "Would remove {} aliases with invalid paths (dry-run):", removed.len());
+            } else {
+                outln!(ctx, "Removing {} aliases with invalid paths:", removed.len());
+            }
+            outln!(ctx, "{}", commands::cleanup::format_removed(&removed, table_style(config)));
+            if !dry_run {
+                note!(ctx, "Cleanup complete.");
+            }
+            Ok(())
         }
 
         Command::Push { alias } => {
-            commands::stack::push(&config, &mut db, &alias).map_err(handle_error)
+            let path = commands::stack::push(config, db, &alias).map_err(|e| handle_error(ctx, e))?;
+            ctx.cd(&path, "");
+            commands::navigate::record_previous(config);
+            Ok(())
         }
 
-        Command::Pop => commands::stack::pop(&config).map_err(handle_error),
+        Command::Where { .. } => match commands::navigate::where_am_i(db) {
+            Ok(location) => {
+                outln!(ctx, "{}", location);
+                Ok(())
+            }
+            Err(_) if ctx.quiet => Err(1),
+            Err(e) => Err(handle_error(ctx, e)),
+        },
 
-        Command::Rename { old_name, new_name } => {
-            commands::register::rename(&mut db, &old_name, &new_name).map_err(handle_error)
+        Command::Search { query, limit, json, wide } => {
+            let results = commands::search::search(db, config, &query, limit);
+            if json {
+                let json = serde_json::to_string_pretty(&results).map_err(|e| handle_error(ctx, e.into()))?;
+                outln!(ctx, "{}", json);
+            } else if results.is_empty() {
+                errln!(ctx, "No aliases match '{}'", query);
+            } else {
+                outln!(ctx, "{}", commands::search::format_results(&results, config, wide));
+            }
+            Ok(())
         }
 
-        Command::Tag { alias, tag, force } => {
-            commands::tags::tag(&mut db, &alias, &tag, force).map_err(handle_error)
+        Command::Preview { alias } => {
+            let preview = commands::preview::preview(db, config, &alias).map_err(|e| handle_error(ctx, e))?;
+            ctx.print(&preview.to_string());
+            Ok(())
+        }
+
+        Command::Explain { query } => {
+            ctx.print(&commands::explain::explain(db, config, &query));
+            Ok(())
+        }
+
+        Command::Note { alias } => {
+            match commands::notes::edit(config, db, &alias).map_err(|e| handle_error(ctx, e))? {
+                Some(_) => note!(ctx, "Saved note for '{}'", alias),
+                None => outln!(ctx, "No note saved for '{}'", alias),
+            }
+            Ok(())
+        }
+
+        Command::Open { alias, opener } => {
+            let path = commands::open::open(db, config, &alias, opener).map_err(|e| handle_error(ctx, e))?;
+            enote!(ctx, "Opened {}", path);
+            Ok(())
+        }
+
+        Command::Rename { old_name, new_name: Some(new_name) } => {
+            commands::register::rename(db, &old_name, &new_name).map_err(|e| handle_error(ctx, e))?;
+            commands::notes::rename(config, &old_name, &new_name).map_err(|e| handle_error(ctx, e.into()))?;
+            note!(ctx, "Renamed alias '{}' to '{}'", old_name, new_name);
+            Ok(())
+        }
+
+        Command::Rename { old_name: query, new_name: None } => {
+            let (old_name, new_name) =
+                commands::register::rename_interactive(ctx, db, &query).map_err(|e| handle_error(ctx, e))?;
+            commands::notes::rename(config, &old_name, &new_name).map_err(|e| handle_error(ctx, e.into()))?;
+            note!(ctx, "Renamed alias '{}' to '{}'", old_name, new_name);
+            Ok(())
+        }
+
+        Command::Tag { alias, tag, .. } => {
+            let tag = commands::tags::tag(ctx, db, &alias, &tag).map_err(|e| handle_error(ctx, e))?;
+            note!(ctx, "Added tag '{}' to alias '{}'", tag, alias);
+            Ok(())
         }
 
         Command::Untag { alias, tag } => {
-            commands::tags::untag(&mut db, &alias, &tag).map_err(handle_error)
+            let tag = commands::tags::untag(db, &alias, &tag).map_err(|e| handle_error(ctx, e))?;
+            note!(ctx, "Removed tag '{}' from alias '{}'", tag, alias);
+            Ok(())
+        }
+
+        Command::RenameTag { old_tag, new_tag, dry_run, .. } => {
+            let change = commands::tags::rename_tag(ctx, db, &old_tag, &new_tag, dry_run)
+                .map_err(|e| handle_error(ctx, e))?;
+            print_tag_change(ctx, &change, config);
+            Ok(())
+        }
+
+        Command::DeleteTag { tag, dry_run, .. } => {
+            let change =
+                commands::tags::delete_tag(ctx, db, &tag, dry_run).map_err(|e| handle_error(ctx, e))?;
+            print_tag_change(ctx, &change, config);
+            Ok(())
+        }
+
+        Command::RewritePrefix { old, new, dry_run, allow_missing } => {
+            let options = commands::rewrite::RewriteOptions {
+                dry_run,
+                allow_missing,
+                resolve_symlinks: config.user.general.resolve_symlinks,
+            };
+            let result =
+                commands::rewrite::rewrite_prefix(db, &old, &new, &options).map_err(|e| handle_error(ctx, e))?;
+            if result.rewrites.is_empty() {
+                errln!(ctx, "{}", result.summary());
+                return Ok(());
+            }
+            note!(ctx, "{}", result.summary());
+            if dry_run {
+                outln!(ctx, "{}", plan::format_planned(&result.planned(), table_style(config)));
+            } else if !ctx.quiet {
+                outln!(ctx, "{}", commands::rewrite::format_rewrites(&result.rewrites, table_style(config)));
+            }
+            Ok(())
         }
 
-        Command::RenameTag { old_tag, new_tag, dry_run, force } => {
-            commands::tags::rename_tag(&mut db, &config, &old_tag, &new_tag, dry_run, force)
-                .map_err(handle_error)
+        Command::TagAll { tag, filter, remove, dry_run, .. } => {
+            let change = commands::tags::tag_all(ctx, db, &tag, &filter, remove, dry_run)
+                .map_err(|e| handle_error(ctx, e))?;
+            print_tag_change(ctx, &change, config);
+            Ok(())
         }
 
-        Command::ListTags => {
-            let result = commands::tags::list_tags(&db, &config).map_err(handle_error);
-            if result.is_ok() {
-                commands::prune::notify_if_stale_aliases(&config, &db);
+        Command::ListTags { sort, json } => {
+            use commands::tags::TagSort;
+
+            let tracking = config.user.privacy.track_usage;
+            let sort = match sort.as_deref().map(TagSort::from).unwrap_or_default() {
+                TagSort::Usage | TagSort::Recent if !tracking => TagSort::Name,
+                sort => sort,
+            };
+            let tags = commands::tags::tag_stats(db, sort);
+            if json {
+                let json = serde_json::to_string_pretty(&tags).map_err(|e| handle_error(ctx, e.into()))?;
+                outln!(ctx, "{}", json);
+                return Ok(());
             }
-            result
+            outln!(ctx, 
+                "{}",
+                commands::tags::format_tags(&tags, table_style(config), config.user.display.date_style(), tracking)
+            );
+            if !tags.is_empty() {
+                commands::prune::notify_if_stale_aliases(ctx, config, db);
+            }
+            Ok(())
+        }
+
+        Command::TagInfo { tag, description: None, color: None } => {
+            let detail = commands::tags::tag_detail(db, &tag).map_err(|e| handle_error(ctx, e))?;
+            outln!(ctx,
+                "{}",
+                commands::tags::format_tag_detail(
+                    &detail,
+                    table_style(config),
+                    PathStyle::from(config.user.display.path_style.as_str()),
+                    config.user.display.date_style(),
+                    config.user.privacy.track_usage,
+                )
+            );
+            Ok(())
+        }
+
+        Command::TagInfo { tag, description, color } => {
+            let info = commands::tags::describe_tag(db, &tag, description.as_deref(), color.as_deref())
+                .map_err(|e| handle_error(ctx, e))?;
+            let tag = tag.trim().to_lowercase();
+            match (&info.description, &info.color) {
+                (None, None) => note!(ctx, "Cleared the description and color of tag '{}'", tag),
+                (description, color) => {
+                    if let Some(description) = description {
+                        note!(ctx, "Tag '{}': {}", tag, description);
+                    }
+                    if let Some(color) = color {
+                        note!(ctx, "Tag '{}' is shown in {}", tag, color);
+                    }
+                }
+            }
+            Ok(())
+        }
+
+        Command::Pick => {
+            match commands::pick::pick(ctx, db, config).map_err(|e| handle_error(ctx, e))? {
+                Some(result) => {
+                    print_navigation(ctx, config, &result);
+                    commands::navigate::record_previous(config);
+                }
+                None => errln!(ctx, "No aliases registered"),
+            }
+            Ok(())
         }
 
         Command::Recent { count, navigate_to } => {
             if let Some(n) = navigate_to {
-                commands::stats::navigate_to_recent(&mut db, n).map_err(handle_error)
+                let result = commands::stats::navigate_to_recent(ctx, db, n).map_err(|e| handle_error(ctx, e))?;
+                print_navigation(ctx, config, &result);
+                commands::navigate::record_previous(config);
+            } else if count.is_none() && ctx.interactive {
+                // A bare --recent on a terminal picks an entry to go to
+                match commands::stats::pick_recent(ctx, db, 10).map_err(|e| handle_error(ctx, e))? {
+                    Some(result) => {
+                        print_navigation(ctx, config, &result);
+                        commands::navigate::record_previous(config);
+                    }
+                    None => outln!(ctx, "No recently visited directories"),
+                }
+            } else {
+                let limit = count.filter(|&n| n > 0).unwrap_or(10);
+                let entries = commands::stats::recent(db, Some(limit)).map_err(|e| handle_error(ctx, e))?;
+                outln!(ctx, "{}", commands::stats::format_recent(&entries, table_style(config), config.user.display.date_style()));
+            }
+            Ok(())
+        }
+
+        Command::RecentClear => {
+            commands::stats::clear_recent(db).map_err(|e| handle_error(ctx, e))?;
+            note!(ctx, "Cleared recent history");
+            Ok(())
+        }
+
+        Command::ResetStats { alias: Some(alias) } => {
+            commands::stats::reset_stats(db, Some(&alias)).map_err(|e| handle_error(ctx, e))?;
+            note!(ctx, "Reset usage stats for '{}'", alias);
+            Ok(())
+        }
+
+        Command::ResetStats { alias: None } => {
+            let count = commands::stats::reset_stats(db, None).map_err(|e| handle_error(ctx, e))?;
+            note!(ctx, "Reset usage stats for {} alias{}", count, if count == 1 { "" } else { "es" });
+            Ok(())
+        }
+
+        Command::SetCount { alias, count } => {
+            commands::stats::set_count(db, &alias, count).map_err(|e| handle_error(ctx, e))?;
+            note!(ctx, "Set use count of '{}' to {}", alias, count);
+            Ok(())
+        }
+
+        Command::Export { .. } if db.is_empty() => {
+            errln!(ctx, "No aliases to export");
+            Ok(())
+        }
+
+        Command::Export {
+            output: Some(output),
+            filter,
+        } => {
+            let count =
+                commands::import_export::export_to_file(db, &output, filter.as_deref()).map_err(|e| handle_error(ctx, e))?;
+            if count == 0 {
+                errln!(ctx, "No aliases match filter");
             } else {
-                commands::stats::show_recent(&db, &config, count.unwrap_or(10)).map_err(handle_error)
+                enote!(ctx, "Exported {} aliases to {}", count, output);
             }
+            Ok(())
         }
 
-        Command::RecentClear => commands::stats::clear_recent(&mut db).map_err(handle_error),
+        Command::Export { output: None, filter } => {
+            let (toml, count) = commands::import_export::export(db, filter.as_deref()).map_err(|e| handle_error(ctx, e))?;
+            if count == 0 {
+                errln!(ctx, "No aliases match filter");
+            } else {
+                ctx.print(&toml);
+            }
+            Ok(())
+        }
 
-        Command::Export => commands::import_export::export(&db).map_err(handle_error),
+        Command::Diff { old, new } => {
+            let changes = commands::diff::diff(db, &old, new.as_deref()).map_err(|e| handle_error(ctx, e))?;
+            outln!(ctx, "{}", commands::diff::summary(&changes));
+            if !changes.is_empty() {
+                outln!(ctx, "{}", plan::format_planned(&changes, table_style(config)));
+            }
+            Ok(())
+        }
 
-        Command::Import { file, strategy } => {
-            match commands::import_export::import(&mut db, &file, strategy) {
+        Command::Report { output, filter } => {
+            let paths = PathStyle::from(config.user.display.path_style.as_str());
+            if let Some(output) = output {
+                let count = commands::report::report_to_file(db, &output, filter.as_deref(), paths, &config.user.tag_colors)
+                    .map_err(|e| handle_error(ctx, e))?;
+                if count == 0 {
+                    errln!(ctx, "No aliases to report");
+                } else {
+                    enote!(ctx, "Wrote a report of {} aliases to {}", count, output);
+                }
+            } else {
+                let (html, count) = commands::report::report(db, filter.as_deref(), paths, &config.user.tag_colors)
+                    .map_err(|e| handle_error(ctx, e))?;
+                if count == 0 {
+                    errln!(ctx, "No aliases to report");
+                } else {
+                    ctx.print(&html);
+                }
+            }
+            Ok(())
+        }
+        Command::ExportShellAliases { shell, output, filter } => {
+            let shell_type = match shell {
+                Some(s) => ShellType::from_str(&s),
+                None => ShellType::detect(),
+            }
+            .map_err(|e| handle_error(ctx, e.into()))?;
+
+            if let Some(output) = output {
+                let count = commands::import_export::export_shell_aliases_to_file(db, shell_type, &output, filter.as_deref())
+                    .map_err(|e| handle_error(ctx, e))?;
+                if count == 0 {
+                    errln!(ctx, "No aliases to export");
+                } else {
+                    enote!(ctx, "Exported {} shell aliases to {}", count, output);
+                }
+            } else {
+                let (script, count) = commands::import_export::export_shell_aliases(db, shell_type, filter.as_deref())
+                    .map_err(|e| handle_error(ctx, e))?;
+                if count == 0 {
+                    errln!(ctx, "No aliases to export");
+                } else {
+                    ctx.print(&script);
+                }
+            }
+            Ok(())
+        }
+
+        Command::Import { file, strategy, dry_run, .. } => {
+            let content = commands::import_export::read_source(&file).map_err(|e| handle_error(ctx, e.into()))?;
+            if dry_run {
+                return preview_changes(ctx, config, db, |_, db| {
+                    commands::import_export::import_from_content(db, &content, strategy)
+                });
+            }
+            // Show what would change first, unless forced or there's no one to ask;
+            // on stderr with the prompt, since the shell functions capture stdout
+            if !ctx.force && ctx.interactive {
+                let preview =
+                    commands::import_export::preview(db, &content, strategy).map_err(|e| handle_error(ctx, e))?;
+                errln!(ctx, "{}", commands::import_export::format_preview(&preview, config));
+                errln!(ctx, "{}", preview.summary());
+                if !ctx.confirm("Apply this import?", false).map_err(|e| handle_error(ctx, e.into()))? {
+                    return Err(handle_error(ctx, Failure::cancelled("Import cancelled").into()));
+                }
+            }
+            let imported = commands::import_export::import_from_content(db, &content, strategy)
+                .and_then(|result| db.save().map(|_| result).map_err(Into::into));
+            match imported {
                 Ok(result) => {
                     for warning in &result.warnings {
-                        eprintln!("{}", warning);
+                        errln!(ctx, "{}", warning);
                     }
-                    print!("Import complete: {} imported", result.imported);
+                    let mut summary = format!("Import complete: {} imported", result.imported);
                     if result.skipped > 0 {
-                        print!(", {} skipped", result.skipped);
+                        summary.push_str(&format!(", {} skipped", result.skipped));
                     }
                     if result.renamed > 0 {
-                        print!(", {} renamed", result.renamed);
+                        summary.push_str(&format!(", {} renamed", result.renamed));
+                    }
+                    if result.not_for_host > 0 {
+                        summary.push_str(&format!(", {} not for this machine", result.not_for_host));
                     }
-                    println!();
+                    note!(ctx, "{}", summary);
                     Ok(())
                 }
-                Err(e) => Err(handle_error(e)),
+                Err(e) => Err(handle_error(ctx, e)),
+            }
+        }
+
+        Command::WatchScan => {
+            let found = commands::watch::scan(config, db).map_err(|e| handle_error(ctx, e))?;
+            // Quiet when there's nothing new, so cron doesn't send mail
+            if !found.is_empty() {
+                outln!(ctx, 
+                    "Found {} new director{}. Run 'goto --suggestions' to review.",
+                    found.len(),
+                    if found.len() == 1 { "y" } else { "ies" }
+                );
+            }
+            Ok(())
+        }
+
+        Command::Suggestions => {
+            let pending = commands::watch::pending(config, db);
+            if pending.is_empty() {
+                outln!(ctx, "No suggestions");
+            } else if ctx.interactive {
+                for alias in commands::watch::review(ctx, config, db).map_err(|e| handle_error(ctx, e))? {
+                    outln!(ctx, "Registered {}", describe_alias(&alias));
+                }
+            } else {
+                outln!(ctx, "{}", commands::watch::format_suggestions(&pending, config));
+            }
+            Ok(())
+        }
+
+        Command::LearnFromHistory { file, limit } => {
+            let limit = limit.unwrap_or(commands::history::DEFAULT_LIMIT);
+            let candidates =
+                commands::history::learn(db, config, file.as_deref(), limit).map_err(|e| handle_error(ctx, e))?;
+            if candidates.is_empty() {
+                outln!(ctx, "No directories in shell history to suggest");
+                return Ok(());
+            }
+            outln!(ctx, "{}", commands::history::format_candidates(&candidates, config));
+            if !ctx.interactive {
+                return Ok(());
+            }
+            let Some(input) = ctx.prompt_input("Register which? (e.g. 1-3,5 or all; Enter for none):")
+                .map_err(|e| handle_error(ctx, e.into()))?
+            else {
+                return Ok(());
+            };
+            let picked =
+                commands::history::parse_selection(&input, candidates.len()).map_err(|e| handle_error(ctx, e.into()))?;
+            let registered = commands::history::register_picked(db, &candidates, &picked)
+                .map_err(|e| handle_error(ctx, e))?;
+            for alias in registered {
+                outln!(ctx, "Registered {}", describe_alias(&alias));
+            }
+            Ok(())
+        }
+
+        Command::Review { criteria } => {
+            let queue = commands::review::queue(db, config, &criteria);
+            if queue.is_empty() {
+                outln!(ctx, "Nothing to review");
+            } else if ctx.interactive {
+                let summary =
+                    commands::review::review(db, config, &criteria, |item| commands::review::prompt_decision(ctx, item))
+                        .map_err(|e| handle_error(ctx, e))?;
+                outln!(ctx, 
+                    "Kept {}, edited {}, tagged {}, archived {}, deleted {}",
+                    summary.kept, summary.edited, summary.tagged, summary.archived, summary.deleted
+                );
+            } else {
+                outln!(ctx, "{}", commands::review::format_queue(&queue, config));
+            }
+            Ok(())
+        }
+
+        Command::ResolveQuiet { query } => {
+            let options = commands::navigate::NavigateOptions::from_config(config);
+            // No match is a plain exit 1: the cd fallback then lets cd report it
+            let result = commands::navigate::resolve_quiet(db, &query, &options).ok_or(1u8)?;
+            print_navigation(ctx, config, &result);
+            commands::navigate::record_previous(config);
+            Ok(())
+        }
+
+        Command::Random { filter, weighted, dry_run } => {
+            let weighting = if weighted {
+                commands::random::Weighting::Usage
+            } else {
+                commands::random::Weighting::Even
+            };
+            let alias = commands::random::choose(db, filter.as_deref(), weighting)
+                .map_err(|e| handle_error(ctx, e.into()))?;
+            if dry_run {
+                outln!(ctx, "{} -> {}", alias.name, alias.path);
+                return Ok(());
             }
+            let options = commands::navigate::NavigateOptions::from_config(config);
+            let result = commands::navigate::navigate_with_options(ctx, db, &alias.name, &options)
+                .map_err(|e| handle_error(ctx, e))?;
+            print_navigation(ctx, config, &result);
+            commands::navigate::record_previous(config);
+            Ok(())
+        }
+
+        Command::NavigateListed { index } => {
+            let result = commands::list::navigate_to_listed(ctx, db, config, index)
+                .map_err(|e| handle_error(ctx, e))?;
+            print_navigation(ctx, config, &result);
+            commands::navigate::record_previous(config);
+            Ok(())
         }
 
         Command::Navigate { alias } => {
-            let result = commands::navigate::navigate(&mut db, &alias).map_err(handle_error);
+            let options = commands::navigate::NavigateOptions::from_config(config);
+            let result = timings::measure(Phase::Matching, || {
+                commands::navigate::navigate_with_options(ctx, db, &alias, &options)
+            })
+            .map_err(|e| handle_error(ctx, e))?;
+            print_navigation(ctx, config, &result);
             // Show update notification after successful navigation (goes to stderr)
-            if result.is_ok() {
-                commands::update::notify_if_update_available(&config);
-            }
-            result
+            commands::navigate::record_previous(config);
+            commands::update::notify_if_update_available(ctx, config);
+            commands::watch::notify_if_suggestions(ctx, config, db);
+            commands::review::notify_if_dormant(ctx, config, db);
+            Ok(())
+        }
+    }
+}
+
+/// Print where to cd to, followed by the alias's environment exports
+///
+/// The exports are only written when the shell wrapper announces itself
+/// through `GOTO_SHELL`, so plain `goto-bin <alias>` output stays a single path.
+fn print_navigation(ctx: &mut Ctx, config: &Config, result: &NavigationResult) {
+    let mut env = result.env.clone();
+    // The wrappers report leaving this directory (privacy.track_time)
+    if let Some(dir) = commands::time_spent::track_enter(config, result) {
+        env.insert(commands::time_spent::TRACK_DIR_VAR.to_string(), dir);
+    }
+    let exports = match env::var("GOTO_SHELL").ok().and_then(|s| ShellType::from_str(&s).ok()) {
+        Some(shell) if !env.is_empty() => commands::env::format_exports(&env, shell),
+        _ => String::new(),
+    };
+    ctx.cd(&result.path, &exports);
+}
+
+/// Print tags one per line, optionally followed by a tab and their count
+fn print_tag_lines(ctx: &mut Ctx, tags: &[(String, usize)], with_counts: bool) {
+    for (tag, count) in tags {
+        if with_counts {
+            outln!(ctx, "{}\t{}", tag, count);
+        } else {
+            outln!(ctx, "{}", tag);
         }
     }
 }
 
-fn handle_error(err: Box<dyn std::error::Error>) -> u8 {
-    eprintln!("{}", err);
-
-    // Map error types to exit codes
-    let err_str = err.to_string();
-    if err_str.contains("directory does not exist") {
-        2
-    } else if err_str.contains("invalid alias") || err_str.contains("invalid tag") {
-        3
-    } else if err_str.contains("already exists") {
-        4
-    } else if err_str.contains("not found") || err_str.contains("stack is empty") {
-        1
-    } else if err_str.contains("cancelled") || err_str.contains("aborted") {
-        1
+/// Print the outcome of a bulk tag change, with a preview table for dry runs
+fn print_tag_change(ctx: &mut Ctx, change: &commands::tags::TagChange, config: &Config) {
+    outln!(ctx, "{}", change.summary());
+    if change.dry_run && !change.updates.is_empty() {
+        outln!(ctx, "{}", plan::format_planned(&change.planned(), table_style(config)));
+    }
+}
+
+/// Run `change` against a database that doesn't save, and print what it would change
+///
+/// For `--dry-run` of commands without a preview of their own.
+fn preview_changes<T>(
+    ctx: &mut Ctx,
+    config: &Config,
+    db: &mut Database,
+    change: impl FnOnce(&mut Ctx, &mut Database) -> Result<T, Box<dyn std::error::Error>>,
+) -> Result<(), u8> {
+    db.set_dry_run();
+    let before: Vec<goto::Alias> = db.all().cloned().collect();
+    change(ctx, db).map_err(|e| handle_error(ctx, e))?;
+    let planned = plan::diff(&before, db.all());
+    outln!(ctx, "{}", plan::summary(&planned));
+    if !planned.is_empty() {
+        outln!(ctx, "{}", plan::format_planned(&planned, table_style(config)));
+    }
+    Ok(())
+}
+
+/// `'name' -> path [tags]`
+fn describe_alias(alias: &goto::Alias) -> String {
+    let target = match &alias.link {
+        Some(link) => format!("'{}' ({})", link, alias.path),
+        None => alias.path.clone(),
+    };
+    if alias.tags.is_empty() {
+        format!("'{}' -> {}", alias.name, target)
     } else {
-        5
+        format!("'{}' -> {} [{}]", alias.name, target, alias.tags.join(", "))
+    }
+}
+
+/// Table style from the display config
+fn table_style(config: &Config) -> TableStyle {
+    TableStyle::from(config.user.display.table_style.as_str())
+}
+
+/// Print `err` and return the exit code its type calls for
+fn handle_error(ctx: &mut Ctx, err: Box<dyn std::error::Error>) -> u8 {
+    errln!(ctx, "{}", err);
+    exit::Code::of(&*err).into()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    fn setup() -> (Config, tempfile::TempDir) {
+        let dir = tempdir().unwrap();
+        let mut config = Config::in_dir(dir.path());
+        config.user.general.shared_aliases = String::new();
+        (config, dir)
+    }
+
+    /// Run `args` against the database of `config`, returning the exit code,
+    /// stdout and stderr
+    fn run_args(config: &Config, args: &[&str], quiet: bool) -> (Result<(), u8>, String, String) {
+        let args: Vec<String> = std::iter::once("goto").chain(args.iter().copied()).map(String::from).collect();
+        let parsed = cli::parse_args(&args).unwrap();
+        let (mut out, mut err) = (Vec::new(), Vec::new());
+        let result = {
+            let mut ctx = Ctx::new(&mut out, &mut err);
+            ctx.quiet = quiet;
+            ctx.force = parsed.command.force();
+            let mut db = Database::load(config).unwrap();
+            run_with_database(parsed.command, config, &mut db, &mut ctx)
+        };
+        (result, String::from_utf8(out).unwrap(), String::from_utf8(err).unwrap())
+    }
+
+    #[test]
+    fn test_register_output() {
+        let (config, dir) = setup();
+        let path = dir.path().to_str().unwrap();
+
+        let (result, out, err) = run_args(&config, &["-r", "proj", path], false);
+        assert_eq!(result, Ok(()));
+        assert_eq!(out, format!("Registered 'proj' -> {}\n", path));
+        assert_eq!(err, "");
+
+        let (result, out, _) = run_args(&config, &["-r", "other", path, "--force"], true);
+        assert_eq!(result, Ok(()));
+        assert_eq!(out, "");
+
+        let (result, out, _) = run_args(&config, &["-x", "proj"], true);
+        assert_eq!(result, Ok(()));
+        assert_eq!(out, format!("{}\n", path));
+    }
+
+    #[test]
+    fn test_errors_go_to_err() {
+        let (config, _dir) = setup();
+        let (result, out, err) = run_args(&config, &["-u", "missing"], true);
+        assert_eq!(result, Err(1));
+        assert_eq!(out, "");
+        assert!(err.contains("not found"));
     }
 }
@@ -0,0 +1,173 @@
+//! Printing directory paths for the shell wrappers and scripts
+//!
+//! Commands that hand a directory to the shell (navigation, `-x`, the stack,
+//! `-`) print it through [`print_path`], which honours `--print0` and
+//! `--escape=<shell>` so paths containing newlines survive the trip.
+//!
+//! With a cd file (`--cd-file <path>` or `GOTO_CD_FILE`), the directory to
+//! enter is written there instead and stdout is left to the command's own
+//! output, so the wrapper doesn't have to capture it.
+
+use std::fs;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+
+/// How [`print_path`] writes a path
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum PathFormat {
+    /// The path followed by a newline
+    #[default]
+    Line,
+    /// The path followed by a NUL byte (`--print0`)
+    Nul,
+    /// A single line of sh/bash/zsh syntax (`--escape=shell`)
+    Shell,
+    /// A single line of fish syntax (`--escape=fish`)
+    Fish,
+}
+
+impl PathFormat {
+    /// Parse an `--escape=` value
+    pub fn from_escape(value: &str) -> Result<Self, String> {
+        match value {
+            "shell" | "sh" | "bash" | "zsh" => Ok(PathFormat::Shell),
+            "fish" => Ok(PathFormat::Fish),
+            _ => Err(format!("Invalid escape: {}. Use shell or fish.", value)),
+        }
+    }
+
+    /// The bytes written for `path`
+    pub fn format(self, path: &str) -> String {
+        match self {
+            PathFormat::Line => format!("{}\n", path),
+            PathFormat::Nul => format!("{}\0", path),
+            PathFormat::Shell => format!("{}\n", quote_posix(path)),
+            PathFormat::Fish => format!("{}\n", quote_fish(path)),
+        }
+    }
+}
+
+static PATH_FORMAT: OnceLock<PathFormat> = OnceLock::new();
+
+/// Choose the path format for this process (the first call wins)
+pub fn set_path_format(format: PathFormat) {
+    let _ = PATH_FORMAT.set(format);
+}
+
+/// Print a directory path for the shell to cd to
+pub fn print_path(path: &str) {
+    write_path(&mut io::stdout().lock(), path);
+}
+
+/// Write a directory path for the shell to `out`, in this process's format
+pub fn write_path(out: &mut dyn Write, path: &str) {
+    let format = PATH_FORMAT.get().copied().unwrap_or_default();
+    let _ = out.write_all(format.format(path).as_bytes());
+    let _ = out.flush();
+}
+
+static CD_FILE: OnceLock<Option<PathBuf>> = OnceLock::new();
+
+/// Choose the cd file for this process (the first call wins)
+pub fn set_cd_file(file: Option<PathBuf>) {
+    let _ = CD_FILE.set(file);
+}
+
+/// Where to write the directory to enter, if the wrapper asked for a file
+pub fn cd_file() -> Option<&'static Path> {
+    CD_FILE.get().and_then(|f| f.as_deref())
+}
+
+/// Write the directory to enter to `file`: the path, a NUL, then the exports
+///
+/// The path is written as is; alone in its file, it needs no quoting.
+pub fn write_cd_file(file: &Path, path: &str, exports: &str) -> io::Result<()> {
+    fs::write(file, format!("{}\0{}", path, exports))
+}
+
+/// Quote `s` as one line for sh-family shells
+///
+/// Everything is single-quoted; control characters such as newlines are
+/// spliced in as bash/zsh `$'\n'` segments.
+pub fn quote_posix(s: &str) -> String {
+    let mut out = String::from("'");
+    for c in s.chars() {
+        match c {
+            '\'' => out.push_str(r"'\''"),
+            '\n' => out.push_str(r"'$'\n''"),
+            '\t' => out.push_str(r"'$'\t''"),
+            c if c.is_ascii_control() => out.push_str(&format!(r"'$'\x{:02x}''", c as u32)),
+            c => out.push(c),
+        }
+    }
+    out.push('\'');
+    out
+}
+
+/// Quote `s` as one line for fish
+///
+/// Inside fish single quotes only `\` and `'` need escaping; control
+/// characters are spliced in unquoted as `\n`-style escapes.
+pub fn quote_fish(s: &str) -> String {
+    let mut out = String::from("'");
+    for c in s.chars() {
+        match c {
+            '\\' => out.push_str(r"\\"),
+            '\'' => out.push_str(r"\'"),
+            '\n' => out.push_str(r"'\n'"),
+            '\t' => out.push_str(r"'\t'"),
+            c if c.is_ascii_control() => out.push_str(&format!(r"'\x{:02x}'", c as u32)),
+            c => out.push(c),
+        }
+    }
+    out.push('\'');
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_quote_posix() {
+        assert_eq!(quote_posix("/tmp/a b"), "'/tmp/a b'");
+        assert_eq!(quote_posix("/tmp/it's"), r"'/tmp/it'\''s'");
+        assert_eq!(quote_posix("/tmp/a\nb"), r"'/tmp/a'$'\n''b'");
+        assert_eq!(quote_posix("/tmp/$HOME`x`"), "'/tmp/$HOME`x`'");
+        assert_eq!(quote_posix("/tmp/Ünïcødé"), "'/tmp/Ünïcødé'");
+    }
+
+    #[test]
+    fn test_quote_fish() {
+        assert_eq!(quote_fish("/tmp/a b"), "'/tmp/a b'");
+        assert_eq!(quote_fish(r"/tmp/it's\x"), r"'/tmp/it\'s\\x'");
+        assert_eq!(quote_fish("/tmp/a\nb"), r"'/tmp/a'\n'b'");
+    }
+
+    #[test]
+    fn test_format() {
+        assert_eq!(PathFormat::Line.format("/a b"), "/a b\n");
+        assert_eq!(PathFormat::Nul.format("/a\nb"), "/a\nb\0");
+        assert_eq!(PathFormat::Shell.format("/a"), "'/a'\n");
+    }
+
+    #[test]
+    fn test_write_cd_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let file = dir.path().join("cd");
+        write_cd_file(&file, "/a b\nc", "").unwrap();
+        assert_eq!(fs::read_to_string(&file).unwrap(), "/a b\nc\0");
+
+        // Overwritten, not appended
+        write_cd_file(&file, "/srv/api", "export PORT='8080'\n").unwrap();
+        assert_eq!(fs::read_to_string(&file).unwrap(), "/srv/api\0export PORT='8080'\n");
+    }
+
+    #[test]
+    fn test_from_escape() {
+        assert_eq!(PathFormat::from_escape("shell"), Ok(PathFormat::Shell));
+        assert_eq!(PathFormat::from_escape("fish"), Ok(PathFormat::Fish));
+        assert!(PathFormat::from_escape("cmd").is_err());
+    }
+}
@@ -0,0 +1,182 @@
+//! Planned changes: what a command run with `--dry-run` would do
+//!
+//! Commands that change aliases describe their preview as a list of
+//! [`PlannedChange`]s, so every dry run renders the same table. Commands
+//! without their own preview run against a database that doesn't save (see
+//! [`crate::Database::set_dry_run`]) and [`diff`] the aliases before and after.
+
+use std::collections::BTreeMap;
+
+use crate::alias::Alias;
+use crate::table::{create_table, TableStyle};
+
+/// What happens to an alias
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Change {
+    Add,
+    Update,
+    Remove,
+}
+
+impl Change {
+    pub fn label(&self) -> &'static str {
+        match self {
+            Change::Add => "add",
+            Change::Update => "update",
+            Change::Remove => "remove",
+        }
+    }
+}
+
+/// One alias a dry run would add, update or remove
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PlannedChange {
+    pub alias: String,
+    pub change: Change,
+    /// What the alias holds now; empty when adding
+    pub before: String,
+    /// What it would hold; empty when removing
+    pub after: String,
+}
+
+impl PlannedChange {
+    pub fn add(alias: &str, after: impl Into<String>) -> Self {
+        Self {
+            alias: alias.to_string(),
+            change: Change::Add,
+            before: String::new(),
+            after: after.into(),
+        }
+    }
+
+    pub fn update(alias: &str, before: impl Into<String>, after: impl Into<String>) -> Self {
+        Self {
+            alias: alias.to_string(),
+            change: Change::Update,
+            before: before.into(),
+            after: after.into(),
+        }
+    }
+
+    pub fn remove(alias: &str, before: impl Into<String>) -> Self {
+        Self {
+            alias: alias.to_string(),
+            change: Change::Remove,
+            before: before.into(),
+            after: String::new(),
+        }
+    }
+}
+
+/// `path [tags]`, or `-> target` for a link
+fn describe(alias: &Alias) -> String {
+    let target = match &alias.link {
+        Some(link) => format!("-> {}", link),
+        None => alias.path.clone(),
+    };
+    if alias.tags.is_empty() {
+        target
+    } else {
+        format!("{} [{}]", target, alias.tags.join(", "))
+    }
+}
+
+/// The changes that turn `before` into `after`, sorted by alias name
+///
+/// Aliases are matched by name. Usage statistics don't count as a change.
+pub fn diff<'a>(before: &[Alias], after: impl IntoIterator<Item = &'a Alias>) -> Vec<PlannedChange> {
+    let mut old: BTreeMap<&str, &Alias> = before.iter().map(|a| (a.name.as_str(), a)).collect();
+    let mut changes = Vec::new();
+    for alias in after {
+        match old.remove(alias.name.as_str()) {
+            None => changes.push(PlannedChange::add(&alias.name, describe(alias))),
+            Some(was) if describe(was) != describe(alias) || was.env != alias.env => {
+                changes.push(PlannedChange::update(&alias.name, describe(was), describe(alias)))
+            }
+            Some(_) => {}
+        }
+    }
+    changes.extend(old.values().map(|a| PlannedChange::remove(&a.name, describe(a))));
+    changes.sort_by(|a, b| a.alias.cmp(&b.alias));
+    changes
+}
+
+/// One line heading the preview, e.g. "Would add 2 aliases and remove 1 (dry-run):"
+pub fn summary(changes: &[PlannedChange]) -> String {
+    if changes.is_empty() {
+        return "Nothing to change (dry-run)".to_string();
+    }
+    let count = |change| changes.iter().filter(|c| c.change == change).count();
+    let mut parts: Vec<String> = [(Change::Add, "add"), (Change::Update, "update"), (Change::Remove, "remove")]
+        .into_iter()
+        .map(|(change, verb)| (count(change), verb))
+        .filter(|(n, _)| *n > 0)
+        .map(|(n, verb)| format!("{} {}", verb, n))
+        .collect();
+    let last = parts.pop().expect("changes isn't empty");
+    if parts.is_empty() {
+        let plural = if changes.len() == 1 { "alias" } else { "aliases" };
+        format!("Would {} {} (dry-run):", last, plural)
+    } else {
+        format!("Would {} and {} (dry-run):", parts.join(", "), last)
+    }
+}
+
+/// Render planned changes as a table: Alias, Change, Before, After
+pub fn format_planned(changes: &[PlannedChange], style: TableStyle) -> String {
+    let mut table = create_table(style);
+    table.set_header(vec!["Alias", "Change", "Before", "After"]);
+    let cell = |s: &str| if s.is_empty() { "-".to_string() } else { s.to_string() };
+    for change in changes {
+        table.add_row(vec![
+            change.alias.clone(),
+            change.change.label().to_string(),
+            cell(&change.before),
+            cell(&change.after),
+        ]);
+    }
+    table.to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_diff() {
+        let mut api = Alias::new("api", "/srv/api").unwrap();
+        let web = Alias::new("web", "/srv/web").unwrap();
+        let before = vec![api.clone(), web.clone()];
+
+        api.add_tag("work");
+        api.use_count = 4;
+        let docs = Alias::new("docs", "/srv/docs").unwrap();
+        let changes = diff(&before, [&api, &docs]);
+        assert_eq!(
+            changes,
+            vec![
+                PlannedChange::update("api", "/srv/api", "/srv/api [work]"),
+                PlannedChange::add("docs", "/srv/docs"),
+                PlannedChange::remove("web", "/srv/web"),
+            ]
+        );
+        assert_eq!(summary(&changes), "Would add 1, update 1 and remove 1 (dry-run):");
+
+        // Usage alone isn't a change
+        let mut used = web.clone();
+        used.use_count = 9;
+        assert!(diff(&[web], [&used]).is_empty());
+    }
+
+    #[test]
+    fn test_summary_and_format() {
+        let changes = vec![PlannedChange::add("a", "/a"), PlannedChange::add("b", "/b")];
+        assert_eq!(summary(&changes), "Would add 2 aliases (dry-run):");
+        assert_eq!(summary(&changes[..1]), "Would add 1 alias (dry-run):");
+        assert_eq!(summary(&[]), "Nothing to change (dry-run)");
+
+        let out = format_planned(&[PlannedChange::remove("old", "/old")], TableStyle::Minimal);
+        assert!(out.contains("remove"));
+        assert!(out.contains("/old"));
+    }
+}
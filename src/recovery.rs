@@ -0,0 +1,241 @@
+//! Salvaging an alias database that no longer parses
+//!
+//! The file is split into its `[[aliases]]` blocks, which are read one at a
+//! time: a damaged block only costs its own alias. Within a damaged block,
+//! lines that aren't valid TOML on their own are dropped before giving up on
+//! it. The damaged file is kept next to the database before it's replaced.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+
+use crate::alias::{validate_alias, Alias};
+use crate::database::DatabaseError;
+
+/// Header starting each alias in the database file
+const BLOCK_HEADER: &str = "[[aliases]]";
+
+/// The database file layout, as in [`crate::database`]
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct AliasFile {
+    #[serde(default)]
+    aliases: Vec<Alias>,
+}
+
+/// What could be read back from a damaged file
+#[derive(Debug, Default)]
+pub struct Salvage {
+    /// Aliases read back, in file order
+    pub aliases: Vec<Alias>,
+    /// Aliases read back after dropping unreadable lines, e.g. "'api' (line 12)"
+    pub repaired: Vec<String>,
+    /// Blocks that couldn't be read, e.g. "'api' (line 12)" or "line 12"
+    pub lost: Vec<String>,
+}
+
+/// The `[[aliases]]` blocks of `content` with their first line number
+///
+/// Anything before the first header (comments) is skipped.
+fn blocks(content: &str) -> Vec<(usize, String)> {
+    let mut blocks: Vec<(usize, String)> = Vec::new();
+    for (i, line) in content.lines().enumerate() {
+        if line.trim() == BLOCK_HEADER {
+            blocks.push((i + 1, String::new()));
+        }
+        if let Some((_, block)) = blocks.last_mut() {
+            block.push_str(line);
+            block.push('\n');
+        }
+    }
+    blocks
+}
+
+/// The single alias of a block, if it reads as one
+fn parse_block(block: &str) -> Option<Alias> {
+    let mut file: AliasFile = toml::from_str(block).ok()?;
+    if file.aliases.len() != 1 || validate_alias(&file.aliases[0].name).is_err() || file.aliases[0].path.is_empty() {
+        return None;
+    }
+    file.aliases.pop()
+}
+
+/// `block` without the lines that aren't valid TOML by themselves
+fn readable_lines(block: &str) -> String {
+    block
+        .lines()
+        .filter(|line| {
+            let line = line.trim();
+            line.is_empty() || line.starts_with('[') || toml::from_str::<toml::Table>(line).is_ok()
+        })
+        .map(|line| format!("{}\n", line))
+        .collect()
+}
+
+/// How a block is named in the report: its alias name, if one can be made out
+fn describe(block: &str, line: usize) -> String {
+    let name = block.lines().find_map(|l| {
+        let value = l.trim().strip_prefix("name")?.trim_start().strip_prefix('=')?.trim();
+        Some(value.trim_matches(['"', '\'']).to_string())
+    });
+    match name {
+        Some(name) if !name.is_empty() => format!("'{}' (line {})", name, line),
+        _ => format!("line {}", line),
+    }
+}
+
+/// Read back as many aliases as possible from a damaged database file
+///
+/// When a name appears twice, the first alias wins.
+pub fn salvage(content: &str) -> Salvage {
+    let mut salvage = Salvage::default();
+    for (line, block) in blocks(content) {
+        let alias = match parse_block(&block) {
+            Some(alias) => alias,
+            None => match parse_block(&readable_lines(&block)) {
+                Some(alias) => {
+                    salvage.repaired.push(describe(&block, line));
+                    alias
+                }
+                None => {
+                    salvage.lost.push(describe(&block, line));
+                    continue;
+                }
+            },
+        };
+        if salvage.aliases.iter().any(|a| a.name == alias.name) {
+            salvage.lost.push(format!("'{}' (line {}, duplicate)", alias.name, line));
+        } else {
+            salvage.aliases.push(alias);
+        }
+    }
+    salvage
+}
+
+/// The outcome of recovering a damaged database file
+#[derive(Debug)]
+pub struct Recovery {
+    /// Why the file didn't parse
+    pub error: String,
+    /// Copy of the damaged file; not written on a dry run
+    pub copy: PathBuf,
+    pub salvage: Salvage,
+}
+
+impl Recovery {
+    /// A report of what was recovered and lost, one fact per line
+    pub fn summary(&self) -> String {
+        let mut lines = vec![format!("Recovered {} aliases", self.salvage.aliases.len())];
+        if !self.salvage.repaired.is_empty() {
+            lines.push(format!("Repaired (unreadable lines dropped): {}", self.salvage.repaired.join(", ")));
+        }
+        if !self.salvage.lost.is_empty() {
+            lines.push(format!("Lost: {}", self.salvage.lost.join(", ")));
+        }
+        lines.join("\n")
+    }
+}
+
+/// Where the damaged `toml_path` is kept: `aliases.toml.corrupt-<time>`
+pub fn copy_path(toml_path: &Path) -> PathBuf {
+    let mut name = toml_path.file_name().unwrap_or_default().to_os_string();
+    name.push(format!(".corrupt-{}", Utc::now().format("%Y%m%dT%H%M%S")));
+    toml_path.with_file_name(name)
+}
+
+/// Check `toml_path` and salvage it if it doesn't parse
+///
+/// Returns `None` when the file is missing or fine. Otherwise the damaged
+/// file is copied aside and replaced by the aliases read back, unless
+/// `dry_run`.
+pub fn recover(toml_path: &Path, dry_run: bool) -> Result<Option<Recovery>, DatabaseError> {
+    let content = match fs::read_to_string(toml_path) {
+        Ok(content) => content,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+        Err(e) => return Err(e.into()),
+    };
+    let error = match toml::from_str::<AliasFile>(&content) {
+        Ok(_) => return Ok(None),
+        // The first line of the message says where
+        Err(e) => e.to_string().lines().next().unwrap_or_default().to_string(),
+    };
+
+    let recovery = Recovery {
+        error,
+        copy: copy_path(toml_path),
+        salvage: salvage(&content),
+    };
+    if !dry_run {
+        fs::copy(toml_path, &recovery.copy)?;
+        let mut aliases = recovery.salvage.aliases.clone();
+        aliases.sort_by(|a, b| a.name.cmp(&b.name));
+        fs::write(toml_path, toml::to_string_pretty(&AliasFile { aliases })?)?;
+        crate::info!("salvaged {} into {}", toml_path.display(), recovery.copy.display());
+    }
+    Ok(Some(recovery))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    const DAMAGED: &str = r#"# goto aliases
+[[aliases]]
+name = "api"
+path = "/srv/api"
+tags = ["work"]
+use_count = 3
+
+[[aliases]]
+name = "blog"
+path = "/srv/blog"
+use_count = "many
+created_at = "2024-01-01T00:00:00Z"
+
+[[aliases]]
+name = "broken
+path = /srv/x
+
+[[aliases]]
+name = "api"
+path = "/srv/other"
+"#;
+
+    #[test]
+    fn test_salvage() {
+        let salvage = salvage(DAMAGED);
+        let names: Vec<&str> = salvage.aliases.iter().map(|a| a.name.as_str()).collect();
+        assert_eq!(names, ["api", "blog"]);
+        assert_eq!(salvage.aliases[0].tags, ["work"]);
+        assert_eq!(salvage.aliases[0].use_count, 3);
+        assert_eq!(salvage.repaired, ["'blog' (line 8)"]);
+        assert_eq!(salvage.lost, ["'broken' (line 14)", "'api' (line 18, duplicate)"]);
+    }
+
+    #[test]
+    fn test_recover() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("aliases.toml");
+        assert!(recover(&path, false).unwrap().is_none());
+
+        fs::write(&path, DAMAGED).unwrap();
+        let dry = recover(&path, true).unwrap().unwrap();
+        assert!(!dry.copy.exists());
+        assert_eq!(fs::read_to_string(&path).unwrap(), DAMAGED);
+
+        let recovery = recover(&path, false).unwrap().unwrap();
+        assert!(recovery.error.contains("line"));
+        assert_eq!(fs::read_to_string(&recovery.copy).unwrap(), DAMAGED);
+        assert_eq!(
+            recovery.summary(),
+            "Recovered 2 aliases\nRepaired (unreadable lines dropped): 'blog' (line 8)\nLost: 'broken' (line 14), 'api' (line 18, duplicate)"
+        );
+
+        // What's left parses, so there's nothing more to do
+        assert!(recover(&path, false).unwrap().is_none());
+        let file: AliasFile = toml::from_str(&fs::read_to_string(&path).unwrap()).unwrap();
+        assert_eq!(file.aliases.len(), 2);
+    }
+}
@@ -0,0 +1,208 @@
+//! Dynamic aliases: regex rules that map an argument to a path template
+//!
+//! Rules are declared in the config file:
+//!
+//! ```toml
+//! [[rules]]
+//! pattern = "gh:(.+)/(.+)"
+//! path = "~/src/github.com/$1/$2"
+//! clone = "https://github.com/$1/$2.git"   # optional
+//! ```
+//!
+//! The pattern must match the whole argument. `$1`..`$9` (or `${1}`) in
+//! `path` and `clone` are replaced with the captured groups; `~` and other
+//! environment variables are expanded afterwards.
+
+use std::path::{Component, Path, PathBuf};
+
+use regex::{Captures, Regex};
+use serde::{Deserialize, Serialize};
+
+use crate::config::expand_path;
+use crate::exit::{Code, Failure};
+
+/// A dynamic alias rule from the config file
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DynamicRule {
+    /// Regular expression matched against the whole argument
+    pub pattern: String,
+    /// Target path template
+    pub path: String,
+    /// Repository URL template cloned into `path` when it does not exist
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub clone: Option<String>,
+}
+
+/// The outcome of a rule matching an argument
+#[derive(Debug, Clone, PartialEq)]
+pub struct ResolvedRule {
+    pub path: PathBuf,
+    pub clone_url: Option<String>,
+}
+
+impl DynamicRule {
+    /// Compile the pattern, anchored to the whole argument
+    fn regex(&self) -> Result<Regex, Failure> {
+        Regex::new(&format!("^(?:{})$", self.pattern))
+            .map_err(|e| Failure::invalid(format!("invalid rule pattern '{}': {}", self.pattern, e)))
+    }
+
+    /// Check that the pattern compiles
+    pub fn check(&self) -> Result<(), Failure> {
+        self.regex().map(|_| ())
+    }
+
+    /// Resolve `input` against this rule, or `None` if the pattern doesn't match
+    pub fn resolve(&self, input: &str) -> Result<Option<ResolvedRule>, Failure> {
+        let Some(captures) = self.regex()?.captures(input) else {
+            return Ok(None);
+        };
+
+        // Captures become path components; don't let them climb out of the template
+        for group in captures.iter().skip(1).flatten() {
+            if Path::new(group.as_str())
+                .components()
+                .any(|c| matches!(c, Component::ParentDir | Component::RootDir))
+            {
+                return Err(Failure::invalid(format!(
+                    "invalid rule input '{}': captured '{}' escapes the rule's path",
+                    input,
+                    group.as_str()
+                )));
+            }
+        }
+
+        let path =
+            expand_path(&substitute(&self.path, &captures)).map_err(|e| Failure::new(Code::Error, e.to_string()))?;
+        Ok(Some(ResolvedRule {
+            path,
+            clone_url: self.clone.as_ref().map(|url| substitute(url, &captures)),
+        }))
+    }
+}
+
+/// Resolve `input` with the first matching rule
+pub fn resolve(rules: &[DynamicRule], input: &str) -> Result<Option<ResolvedRule>, Failure> {
+    for rule in rules {
+        if let Some(resolved) = rule.resolve(input)? {
+            return Ok(Some(resolved));
+        }
+    }
+    Ok(None)
+}
+
+/// Replace `$N` and `${N}` with capture group `N`
+///
+/// Other `$` sequences (e.g. `$HOME`) are left for environment expansion.
+pub fn substitute(template: &str, captures: &Captures) -> String {
+    let mut out = String::with_capacity(template.len());
+    let mut rest = template;
+
+    while let Some(pos) = rest.find('$') {
+        out.push_str(&rest[..pos]);
+        let after = &rest[pos + 1..];
+
+        let (digits, consumed) = if let Some(braced) = after.strip_prefix('{') {
+            match braced.find('}') {
+                Some(end) if end > 0 && braced[..end].bytes().all(|b| b.is_ascii_digit()) => {
+                    (&braced[..end], end + 2)
+                }
+                _ => ("", 0),
+            }
+        } else {
+            let end = after.bytes().take_while(|b| b.is_ascii_digit()).count();
+            (&after[..end], end)
+        };
+
+        match digits.parse::<usize>() {
+            Ok(group) => {
+                out.push_str(captures.get(group).map_or("", |m| m.as_str()));
+                rest = &after[consumed..];
+            }
+            Err(_) => {
+                out.push('$');
+                rest = after;
+            }
+        }
+    }
+
+    out.push_str(rest);
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn gh_rule() -> DynamicRule {
+        DynamicRule {
+            pattern: "gh:(.+)/(.+)".to_string(),
+            path: "/src/github.com/$1/$2".to_string(),
+            clone: Some("https://github.com/${1}/${2}.git".to_string()),
+        }
+    }
+
+    #[test]
+    fn test_substitute() {
+        let re = Regex::new("(a+)-(b+)").unwrap();
+        let caps = re.captures("aa-bbb").unwrap();
+
+        assert_eq!(substitute("$1/$2", &caps), "aa/bbb");
+        assert_eq!(substitute("${1}x${2}", &caps), "aaxbbb");
+        assert_eq!(substitute("$0", &caps), "aa-bbb");
+        assert_eq!(substitute("$HOME/$1", &caps), "$HOME/aa");
+        assert_eq!(substitute("$9 ${x} $", &caps), " ${x} $");
+    }
+
+    #[test]
+    fn test_resolve_with_captures() {
+        let resolved = gh_rule().resolve("gh:rust-lang/cargo").unwrap().unwrap();
+        assert_eq!(resolved.path, PathBuf::from("/src/github.com/rust-lang/cargo"));
+        assert_eq!(
+            resolved.clone_url.as_deref(),
+            Some("https://github.com/rust-lang/cargo.git")
+        );
+    }
+
+    #[test]
+    fn test_pattern_must_match_whole_input() {
+        assert_eq!(gh_rule().resolve("xgh:a/b").unwrap(), None);
+        assert_eq!(gh_rule().resolve("proj").unwrap(), None);
+    }
+
+    #[test]
+    fn test_first_matching_rule_wins() {
+        let rules = vec![
+            DynamicRule {
+                pattern: "gh:work/(.+)".to_string(),
+                path: "/work/$1".to_string(),
+                clone: None,
+            },
+            gh_rule(),
+        ];
+
+        let resolved = resolve(&rules, "gh:work/api").unwrap().unwrap();
+        assert_eq!(resolved.path, PathBuf::from("/work/api"));
+        assert_eq!(resolved.clone_url, None);
+
+        let resolved = resolve(&rules, "gh:other/api").unwrap().unwrap();
+        assert_eq!(resolved.path, PathBuf::from("/src/github.com/other/api"));
+    }
+
+    #[test]
+    fn test_captures_cannot_escape_template() {
+        let err = gh_rule().resolve("gh:../../etc/passwd").unwrap_err();
+        assert!(err.message.starts_with("invalid rule input"));
+        assert_eq!(err.code, Code::Invalid);
+    }
+
+    #[test]
+    fn test_invalid_pattern() {
+        let rule = DynamicRule {
+            pattern: "gh:(".to_string(),
+            path: "/x".to_string(),
+            clone: None,
+        };
+        assert!(rule.resolve("gh:a").unwrap_err().message.starts_with("invalid rule pattern"));
+    }
+}
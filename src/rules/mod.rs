@@ -0,0 +1,3 @@
+//! Navigation rules evaluated when an argument is not a registered alias
+
+pub mod dynamic;
@@ -2,7 +2,8 @@
 
 use std::fs::{self, File};
 use std::io::{BufRead, BufReader, Write};
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
 
 use thiserror::Error;
 
@@ -63,6 +64,11 @@ impl Stack {
         self.save(&[])
     }
 
+    /// Get all entries, bottom of the stack first
+    pub fn entries(&self) -> Result<Vec<String>, StackError> {
+        self.load()
+    }
+
     fn load(&self) -> Result<Vec<String>, StackError> {
         if !self.path.exists() {
             return Ok(Vec::new());
@@ -89,6 +95,7 @@ impl Stack {
             fs::create_dir_all(parent)?;
         }
 
+        crate::debug!("writing {} stack entries to {}", entries.len(), self.path.display());
         let mut file = File::create(&self.path)?;
         for entry in entries {
             writeln!(file, "{}", entry)?;
@@ -97,6 +104,95 @@ impl Stack {
     }
 }
 
+/// Identify the current shell session for per-session stacks
+///
+/// Uses `$GOTO_SESSION` (exported by the shell wrapper) and falls back to
+/// the parent process ID, which is the shell when goto-bin is run directly.
+pub fn session_id() -> String {
+    if let Ok(session) = std::env::var("GOTO_SESSION") {
+        let sanitized: String = session
+            .chars()
+            .filter(|c| c.is_ascii_alphanumeric() || *c == '-' || *c == '_')
+            .collect();
+        if !sanitized.is_empty() {
+            return sanitized;
+        }
+    }
+
+    #[cfg(unix)]
+    {
+        std::os::unix::process::parent_id().to_string()
+    }
+    #[cfg(not(unix))]
+    {
+        "default".to_string()
+    }
+}
+
+/// Path of the stack file for a session inside the sessions directory
+pub fn session_stack_path(sessions_dir: &Path, session: &str) -> PathBuf {
+    sessions_dir.join(session)
+}
+
+/// List all session stacks as (session id, path), sorted by session id
+pub fn list_sessions(sessions_dir: &Path) -> Result<Vec<(String, PathBuf)>, StackError> {
+    if !sessions_dir.exists() {
+        return Ok(Vec::new());
+    }
+
+    let mut sessions = Vec::new();
+    for entry in fs::read_dir(sessions_dir)? {
+        let entry = entry?;
+        if entry.file_type()?.is_file() {
+            sessions.push((entry.file_name().to_string_lossy().into_owned(), entry.path()));
+        }
+    }
+    sessions.sort();
+    Ok(sessions)
+}
+
+/// Remove session stacks that are empty, belong to a shell that has exited,
+/// or have not been touched within `max_age`. Returns how many were removed.
+pub fn gc_stale_sessions(
+    sessions_dir: &Path,
+    current: &str,
+    max_age: Duration,
+) -> Result<usize, StackError> {
+    let now = SystemTime::now();
+    let mut removed = 0;
+
+    for (session, path) in list_sessions(sessions_dir)? {
+        if session == current {
+            continue;
+        }
+
+        let metadata = fs::metadata(&path)?;
+        let expired = metadata
+            .modified()
+            .ok()
+            .and_then(|m| now.duration_since(m).ok())
+            .is_some_and(|age| age > max_age);
+
+        if expired || metadata.len() == 0 || !session_alive(&session) {
+            crate::debug!("removing stale session file {}", path.display());
+            fs::remove_file(&path)?;
+            removed += 1;
+        }
+    }
+
+    Ok(removed)
+}
+
+/// Best-effort check whether a numeric session ID is a live process
+fn session_alive(session: &str) -> bool {
+    let proc_dir = Path::new("/proc");
+    match session.parse::<u32>() {
+        Ok(pid) if proc_dir.is_dir() => proc_dir.join(pid.to_string()).exists(),
+        // Named sessions, or no /proc to ask: rely on the age check
+        _ => true,
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -146,6 +242,74 @@ mod tests {
         assert!(matches!(stack.peek(), Err(StackError::Empty)));
     }
 
+    #[test]
+    fn test_entries() {
+        let dir = tempdir().unwrap();
+        let stack = Stack::new(dir.path().join("stack"));
+
+        assert!(stack.entries().unwrap().is_empty());
+        stack.push("/a").unwrap();
+        stack.push("/b").unwrap();
+        assert_eq!(stack.entries().unwrap(), vec!["/a", "/b"]);
+    }
+
+    #[test]
+    fn test_list_sessions() {
+        let dir = tempdir().unwrap();
+        let sessions_dir = dir.path().join("stacks");
+        assert!(list_sessions(&sessions_dir).unwrap().is_empty());
+
+        Stack::new(session_stack_path(&sessions_dir, "b")).push("/b").unwrap();
+        Stack::new(session_stack_path(&sessions_dir, "a")).push("/a").unwrap();
+
+        let names: Vec<_> = list_sessions(&sessions_dir)
+            .unwrap()
+            .into_iter()
+            .map(|(name, _)| name)
+            .collect();
+        assert_eq!(names, vec!["a", "b"]);
+    }
+
+    #[test]
+    fn test_gc_stale_sessions() {
+        let dir = tempdir().unwrap();
+        let sessions_dir = dir.path().join("stacks");
+
+        Stack::new(session_stack_path(&sessions_dir, "current")).push("/c").unwrap();
+        Stack::new(session_stack_path(&sessions_dir, "named")).push("/n").unwrap();
+        // Emptied stacks are always collected
+        Stack::new(session_stack_path(&sessions_dir, "empty")).clear().unwrap();
+
+        let removed =
+            gc_stale_sessions(&sessions_dir, "current", Duration::from_secs(3600)).unwrap();
+        assert_eq!(removed, 1);
+        assert!(session_stack_path(&sessions_dir, "named").exists());
+
+        // With a zero max age everything but the current session expires
+        std::thread::sleep(Duration::from_millis(10));
+        gc_stale_sessions(&sessions_dir, "current", Duration::ZERO).unwrap();
+        assert!(!session_stack_path(&sessions_dir, "named").exists());
+        assert!(session_stack_path(&sessions_dir, "current").exists());
+    }
+
+    #[test]
+    fn test_gc_removes_dead_pid_sessions() {
+        if !Path::new("/proc").is_dir() {
+            return;
+        }
+        let dir = tempdir().unwrap();
+        let sessions_dir = dir.path().join("stacks");
+
+        let alive = std::process::id().to_string();
+        Stack::new(session_stack_path(&sessions_dir, &alive)).push("/a").unwrap();
+        // PIDs are capped well below u32::MAX, so this one cannot be running
+        Stack::new(session_stack_path(&sessions_dir, "4294967294")).push("/d").unwrap();
+
+        gc_stale_sessions(&sessions_dir, "current", Duration::from_secs(3600)).unwrap();
+        assert!(session_stack_path(&sessions_dir, &alive).exists());
+        assert!(!session_stack_path(&sessions_dir, "4294967294").exists());
+    }
+
     #[test]
     fn test_persistence() {
         let dir = tempdir().unwrap();
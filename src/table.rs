@@ -3,7 +3,12 @@
 //! This module provides a thin abstraction over comfy-table that ensures
 //! consistent table styling across all display commands.
 
+use std::collections::BTreeMap;
+use std::io::{self, IsTerminal};
+
+use chrono::{DateTime, Utc};
 use comfy_table::{presets, modifiers, ContentArrangement, Table};
+use crossterm::style::{Color, Stylize};
 
 /// Table display style options
 #[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
@@ -28,6 +33,373 @@ impl From<&str> for TableStyle {
     }
 }
 
+/// How paths are rendered in table output
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum PathStyle {
+    /// Absolute path as stored (default)
+    #[default]
+    Full,
+    /// Contract `$HOME` to `~`
+    Home,
+    /// Contract `$HOME` and abbreviate intermediate components (`~/p/w/api`)
+    Short,
+}
+
+impl From<&str> for PathStyle {
+    fn from(s: &str) -> Self {
+        match s.to_lowercase().as_str() {
+            "home" => PathStyle::Home,
+            "short" => PathStyle::Short,
+            _ => PathStyle::Full, // Unknown values fall back to full
+        }
+    }
+}
+
+/// Format a path for display according to the given style
+pub fn format_path(path: &str, style: PathStyle) -> String {
+    let home = dirs::home_dir().map(|h| h.to_string_lossy().into_owned());
+    format_path_with_home(path, style, home.as_deref())
+}
+
+fn format_path_with_home(path: &str, style: PathStyle, home: Option<&str>) -> String {
+    if style == PathStyle::Full {
+        return path.to_string();
+    }
+
+    let contracted = match home {
+        Some(home) if !home.is_empty() && home != "/" => {
+            if path == home {
+                "~".to_string()
+            } else if let Some(rest) = path.strip_prefix(home).filter(|r| r.starts_with('/')) {
+                format!("~{}", rest)
+            } else {
+                path.to_string()
+            }
+        }
+        _ => path.to_string(),
+    };
+
+    if style == PathStyle::Home {
+        return contracted;
+    }
+
+    // Abbreviate every component except the first ("~" or "") and the last
+    let parts: Vec<&str> = contracted.split('/').collect();
+    if parts.len() <= 2 {
+        return contracted;
+    }
+    let last = parts.len() - 1;
+    parts
+        .iter()
+        .enumerate()
+        .map(|(i, part)| {
+            if i == 0 || i == last || part.is_empty() {
+                part.to_string()
+            } else {
+                abbreviate_component(part)
+            }
+        })
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+/// Shorten a single path component to its first character (keeping a leading dot)
+fn abbreviate_component(part: &str) -> String {
+    let mut chars = part.chars();
+    match chars.next() {
+        Some('.') => match chars.next() {
+            Some(c) => format!(".{}", c),
+            None => ".".to_string(),
+        },
+        Some(c) => c.to_string(),
+        None => String::new(),
+    }
+}
+
+/// How paths too long for the terminal are shortened (`display.truncate_paths`)
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum PathTruncation {
+    /// Keep the start and the end: `/home/user/...work/api` (default)
+    #[default]
+    Middle,
+    /// Keep the start: `/home/user/projects/...`
+    End,
+    /// Never shorten; the table wraps instead
+    Off,
+}
+
+impl From<&str> for PathTruncation {
+    fn from(s: &str) -> Self {
+        match s.to_lowercase().as_str() {
+            "end" => PathTruncation::End,
+            "off" | "none" => PathTruncation::Off,
+            _ => PathTruncation::Middle, // Unknown values fall back to middle
+        }
+    }
+}
+
+/// Marks the part of a path left out
+const ELLIPSIS: &str = "...";
+
+/// Narrowest a shortened path gets, however little room the other columns leave
+const MIN_PATH_WIDTH: usize = 16;
+
+impl PathTruncation {
+    /// Shorten `path` to at most `width` characters
+    pub fn shorten(self, path: &str, width: usize) -> String {
+        let len = path.chars().count();
+        if self == PathTruncation::Off || len <= width || width <= ELLIPSIS.len() {
+            return path.to_string();
+        }
+        let keep = width - ELLIPSIS.len();
+        let head: String = path.chars().take(if self == PathTruncation::End { keep } else { keep / 2 }).collect();
+        if self == PathTruncation::End {
+            return format!("{}{}", head, ELLIPSIS);
+        }
+        // The end of a path names the directory, so it gets the odd character
+        let tail: String = path.chars().skip(len - (keep - keep / 2)).collect();
+        format!("{}{}{}", head, ELLIPSIS, tail)
+    }
+}
+
+/// How a table is fitted to the terminal
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct TableFit {
+    /// Columns available; `None` leaves the table at its natural width
+    pub width: Option<usize>,
+    /// How the path column is shortened to fit
+    pub paths: PathTruncation,
+}
+
+impl TableFit {
+    /// Fit to the terminal stdout ends up on, unless `wide` (`--wide`)
+    pub fn new(paths: PathTruncation, wide: bool) -> Self {
+        let width = (!wide && stdout_is_terminal()).then(crate::ui::width);
+        Self { width, paths }
+    }
+
+    /// Shorten column `column` of `rows` so the table fits the width
+    ///
+    /// The table is first measured with every path in full; the path column
+    /// then gives up what the table is too wide, down to [`MIN_PATH_WIDTH`].
+    pub fn shorten_paths(&self, style: TableStyle, header: &[&str], rows: &mut [Vec<String>], column: usize) {
+        let Some(width) = self.width.filter(|_| self.paths != PathTruncation::Off) else {
+            return;
+        };
+        let mut table = create_table(style);
+        table.set_content_arrangement(ContentArrangement::Disabled);
+        table.set_header(header.to_vec());
+        for row in rows.iter() {
+            table.add_row(row.clone());
+        }
+        let natural = table.lines().map(|line| crate::ui::visible_width(&line)).max().unwrap_or(0);
+        if natural <= width {
+            return;
+        }
+        let longest = rows.iter().map(|row| row[column].chars().count()).max().unwrap_or(0);
+        let budget = longest.saturating_sub(natural - width).max(MIN_PATH_WIDTH);
+        for row in rows.iter_mut() {
+            row[column] = self.paths.shorten(&row[column], budget);
+        }
+    }
+
+    /// Constrain `table` to the width, or turn off wrapping without one
+    pub fn apply(&self, table: &mut Table) {
+        match self.width {
+            Some(width) => {
+                table.set_width(u16::try_from(width).unwrap_or(u16::MAX));
+            }
+            None => {
+                table.set_content_arrangement(ContentArrangement::Disabled);
+            }
+        }
+    }
+}
+
+/// How timestamps are rendered in list, recent and stats output
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum DateStyle {
+    /// "3 days ago" (default)
+    #[default]
+    Relative,
+    /// `2024-05-01 14:30` (UTC)
+    Absolute,
+}
+
+/// Format a timestamp for display according to the given style
+///
+/// A missing timestamp is shown as "never" in both styles.
+pub fn format_date(t: Option<DateTime<Utc>>, style: DateStyle) -> String {
+    match (t, style) {
+        (Some(t), DateStyle::Absolute) => t.format("%Y-%m-%d %H:%M").to_string(),
+        _ => format_time_ago(t),
+    }
+}
+
+/// Format a timestamp as a human-readable "time ago" string
+fn format_time_ago(t: Option<DateTime<Utc>>) -> String {
+    let t = match t {
+        Some(t) => t,
+        None => return "never".to_string(),
+    };
+
+    let duration = Utc::now().signed_duration_since(t);
+
+    if duration.num_seconds() < 60 {
+        return "just now".to_string();
+    }
+
+    let minutes = duration.num_minutes();
+    if minutes < 60 {
+        return if minutes == 1 {
+            "1 minute ago".to_string()
+        } else {
+            format!("{} minutes ago", minutes)
+        };
+    }
+
+    let hours = duration.num_hours();
+    if hours < 24 {
+        return if hours == 1 {
+            "1 hour ago".to_string()
+        } else {
+            format!("{} hours ago", hours)
+        };
+    }
+
+    let days = duration.num_days();
+    if days < 7 {
+        return if days == 1 {
+            "1 day ago".to_string()
+        } else {
+            format!("{} days ago", days)
+        };
+    }
+
+    let weeks = days / 7;
+    if weeks < 4 {
+        return if weeks == 1 {
+            "1 week ago".to_string()
+        } else {
+            format!("{} weeks ago", weeks)
+        };
+    }
+
+    let months = days / 30;
+    if months == 1 {
+        "1 month ago".to_string()
+    } else {
+        format!("{} months ago", months)
+    }
+}
+
+/// When output is colored (`display.color`)
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ColorMode {
+    /// Color on terminals unless `NO_COLOR` is set (default)
+    #[default]
+    Auto,
+    /// Always color, even with `NO_COLOR` set
+    Always,
+    /// Never color
+    Never,
+}
+
+impl From<&str> for ColorMode {
+    fn from(s: &str) -> Self {
+        match s.to_lowercase().as_str() {
+            "always" => ColorMode::Always,
+            "never" => ColorMode::Never,
+            _ => ColorMode::Auto, // Unknown values fall back to auto
+        }
+    }
+}
+
+impl ColorMode {
+    /// Whether to color output going to a terminal (`is_terminal`) or not
+    ///
+    /// `NO_COLOR` (any non-empty value) turns off `auto`; an explicit
+    /// `always` in the config wins over it.
+    pub fn enabled(self, is_terminal: bool) -> bool {
+        let no_color = std::env::var_os("NO_COLOR").is_some_and(|v| !v.is_empty());
+        match self {
+            ColorMode::Always => true,
+            ColorMode::Never => false,
+            ColorMode::Auto => is_terminal && !no_color,
+        }
+    }
+}
+
+/// Whether stdout ends up on a terminal
+///
+/// The shell wrappers capture goto-bin's output before printing it, so they
+/// report their own stdout through `GOTO_TTY=1`.
+pub fn stdout_is_terminal() -> bool {
+    io::stdout().is_terminal() || std::env::var("GOTO_TTY").is_ok_and(|v| v == "1")
+}
+
+/// Colors tags get when `[tag_colors]` doesn't name one
+const TAG_PALETTE: [Color; 6] = [
+    Color::Blue,
+    Color::Green,
+    Color::Magenta,
+    Color::Cyan,
+    Color::Yellow,
+    Color::Red,
+];
+
+/// How tags are rendered: plain, or each in its own color
+#[derive(Debug, Clone, Copy)]
+pub enum TagStyle<'a> {
+    Plain,
+    /// Colored, with the configured `[tag_colors]`
+    Colored(&'a BTreeMap<String, String>),
+}
+
+impl TagStyle<'_> {
+    /// The color of `tag`: configured, or picked from its name
+    ///
+    /// Hashing the name keeps a tag's color the same between runs and
+    /// machines. Unknown color names fall back to the hashed color.
+    pub fn color(&self, tag: &str) -> Option<Color> {
+        let TagStyle::Colored(colors) = self else {
+            return None;
+        };
+        let configured = colors
+            .iter()
+            .find(|(name, _)| crate::collate::eq_ignore_case(name, tag))
+            .and_then(|(_, color)| Color::try_from(color.as_str()).ok());
+        Some(configured.unwrap_or_else(|| TAG_PALETTE[fnv1a(tag) as usize % TAG_PALETTE.len()]))
+    }
+
+    /// `tag`, colored when this style is
+    pub fn paint(&self, tag: &str) -> String {
+        match self.color(tag) {
+            Some(color) => tag.with(color).to_string(),
+            None => tag.to_string(),
+        }
+    }
+
+    /// Tags joined with `sep`, or "-" when there are none
+    pub fn join(&self, tags: &[String], sep: &str) -> String {
+        if tags.is_empty() {
+            return "-".to_string();
+        }
+        tags.iter().map(|t| self.paint(t)).collect::<Vec<_>>().join(sep)
+    }
+}
+
+/// Whether `name` is a color tags can be shown in (`red`, `dark_blue`, ...)
+pub fn is_color_name(name: &str) -> bool {
+    Color::try_from(name).is_ok()
+}
+
+/// 32-bit FNV-1a: a hash that, unlike std's, is fixed across Rust releases
+fn fnv1a(s: &str) -> u32 {
+    s.bytes()
+        .fold(0x811c_9dc5, |hash: u32, b| (hash ^ u32::from(b)).wrapping_mul(0x0100_0193))
+}
+
 /// Create a new table with the specified style
 ///
 /// Returns a configured `comfy_table::Table` with:
@@ -58,6 +430,7 @@ pub fn create_table(style: TableStyle) -> Table {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use chrono::{Duration, TimeZone};
 
     #[test]
     fn test_table_style_from_str() {
@@ -82,11 +455,91 @@ mod tests {
         assert_eq!(TableStyle::from("fancy"), TableStyle::Unicode);
     }
 
+    #[test]
+    fn test_color_mode() {
+        assert_eq!(ColorMode::from("ALWAYS"), ColorMode::Always);
+        assert_eq!(ColorMode::from("never"), ColorMode::Never);
+        assert_eq!(ColorMode::from("bogus"), ColorMode::Auto);
+
+        assert!(ColorMode::Always.enabled(false));
+        assert!(!ColorMode::Never.enabled(true));
+        assert!(!ColorMode::Auto.enabled(false));
+    }
+
+    #[test]
+    fn test_tag_style() {
+        let mut colors = BTreeMap::new();
+        colors.insert("work".to_string(), "dark_blue".to_string());
+        colors.insert("typo".to_string(), "bleu".to_string());
+        let style = TagStyle::Colored(&colors);
+
+        assert_eq!(style.color("work"), Some(Color::DarkBlue));
+        assert_eq!(style.color("WORK"), Some(Color::DarkBlue));
+        // Unconfigured (or misspelled) tags keep one color across runs
+        let hashed = style.color("rust").unwrap();
+        assert_eq!(style.color("rust"), Some(hashed));
+        assert!(TAG_PALETTE.contains(&hashed));
+        assert!(TAG_PALETTE.contains(&style.color("typo").unwrap()));
+        assert_eq!(fnv1a("rust"), 0x1d3f_bde7);
+
+        assert_eq!(style.paint("work"), "\u{1b}[38;5;4mwork\u{1b}[39m");
+        assert_eq!(TagStyle::Plain.join(&["a".to_string(), "b".to_string()], ", "), "a, b");
+        assert_eq!(style.join(&[], ", "), "-");
+    }
+
     #[test]
     fn test_table_style_default() {
         assert_eq!(TableStyle::default(), TableStyle::Unicode);
     }
 
+    #[test]
+    fn test_path_style_from_str() {
+        assert_eq!(PathStyle::from("full"), PathStyle::Full);
+        assert_eq!(PathStyle::from("HOME"), PathStyle::Home);
+        assert_eq!(PathStyle::from("short"), PathStyle::Short);
+        assert_eq!(PathStyle::from("invalid"), PathStyle::Full);
+    }
+
+    #[test]
+    fn test_format_path_full_is_unchanged() {
+        let path = "/home/user/projects/work/api";
+        assert_eq!(format_path_with_home(path, PathStyle::Full, Some("/home/user")), path);
+    }
+
+    #[test]
+    fn test_format_path_home() {
+        let home = Some("/home/user");
+        assert_eq!(format_path_with_home("/home/user", PathStyle::Home, home), "~");
+        assert_eq!(
+            format_path_with_home("/home/user/projects", PathStyle::Home, home),
+            "~/projects"
+        );
+        // Sibling directories sharing the prefix are not contracted
+        assert_eq!(
+            format_path_with_home("/home/username/x", PathStyle::Home, home),
+            "/home/username/x"
+        );
+        assert_eq!(format_path_with_home("/tmp", PathStyle::Home, home), "/tmp");
+    }
+
+    #[test]
+    fn test_format_path_short() {
+        let home = Some("/home/user");
+        assert_eq!(
+            format_path_with_home("/home/user/projects/work/api", PathStyle::Short, home),
+            "~/p/w/api"
+        );
+        assert_eq!(
+            format_path_with_home("/usr/local/share", PathStyle::Short, home),
+            "/u/l/share"
+        );
+        assert_eq!(
+            format_path_with_home("/home/user/.config/goto", PathStyle::Short, home),
+            "~/.c/goto"
+        );
+        assert_eq!(format_path_with_home("/tmp", PathStyle::Short, home), "/tmp");
+    }
+
     #[test]
     fn test_create_table_returns_table() {
         // Smoke test: verify create_table returns a table for each style
@@ -124,4 +577,111 @@ mod tests {
         assert!(!output.is_empty());
         // Table renders without panic at narrow width
     }
+
+    #[test]
+    fn test_path_truncation() {
+        assert_eq!(PathTruncation::from("END"), PathTruncation::End);
+        assert_eq!(PathTruncation::from("off"), PathTruncation::Off);
+        assert_eq!(PathTruncation::from("bogus"), PathTruncation::Middle);
+
+        let path = "/home/user/projects/work/api";
+        assert_eq!(PathTruncation::Middle.shorten(path, 16), "/home/...ork/api");
+        assert_eq!(PathTruncation::End.shorten(path, 16), "/home/user/pr...");
+        assert_eq!(PathTruncation::Off.shorten(path, 16), path);
+        assert_eq!(PathTruncation::Middle.shorten(path, 40), path);
+    }
+
+    #[test]
+    fn test_table_fit_shortens_paths() {
+        let path = "/home/user/very/deeply/nested/project/directory/with/a/long/name";
+        let header = ["Name", "Path"];
+        let mut rows = vec![vec!["project".to_string(), path.to_string()]];
+        let fit = TableFit {
+            width: Some(40),
+            paths: PathTruncation::Middle,
+        };
+        fit.shorten_paths(TableStyle::Unicode, &header, &mut rows, 1);
+        assert!(rows[0][1].starts_with("/home/user/"));
+        assert!(rows[0][1].ends_with("long/name"));
+        assert!(rows[0][1].contains("..."));
+
+        let mut table = create_table(TableStyle::Unicode);
+        fit.apply(&mut table);
+        table.set_header(header.to_vec());
+        table.add_row(rows[0].clone());
+        assert!(table.lines().all(|line| line.chars().count() <= 40));
+
+        // Without a terminal width, and with truncation off, paths are kept
+        for fit in [TableFit::default(), TableFit { width: Some(40), paths: PathTruncation::Off }] {
+            let mut rows = vec![vec!["project".to_string(), path.to_string()]];
+            fit.shorten_paths(TableStyle::Unicode, &header, &mut rows, 1);
+            assert_eq!(rows[0][1], path);
+        }
+    }
+
+    #[test]
+    fn test_format_time_ago_none() {
+        assert_eq!(format_time_ago(None), "never");
+    }
+
+    #[test]
+    fn test_format_time_ago_just_now() {
+        let now = Utc::now();
+        assert_eq!(format_time_ago(Some(now)), "just now");
+    }
+
+    #[test]
+    fn test_format_time_ago_minutes() {
+        let time = Utc::now() - Duration::minutes(1);
+        assert_eq!(format_time_ago(Some(time)), "1 minute ago");
+
+        let time = Utc::now() - Duration::minutes(30);
+        assert_eq!(format_time_ago(Some(time)), "30 minutes ago");
+    }
+
+    #[test]
+    fn test_format_time_ago_hours() {
+        let time = Utc::now() - Duration::hours(1);
+        assert_eq!(format_time_ago(Some(time)), "1 hour ago");
+
+        let time = Utc::now() - Duration::hours(5);
+        assert_eq!(format_time_ago(Some(time)), "5 hours ago");
+    }
+
+    #[test]
+    fn test_format_time_ago_days() {
+        let time = Utc::now() - Duration::days(1);
+        assert_eq!(format_time_ago(Some(time)), "1 day ago");
+
+        let time = Utc::now() - Duration::days(3);
+        assert_eq!(format_time_ago(Some(time)), "3 days ago");
+    }
+
+    #[test]
+    fn test_format_time_ago_weeks() {
+        let time = Utc::now() - Duration::weeks(1);
+        assert_eq!(format_time_ago(Some(time)), "1 week ago");
+
+        let time = Utc::now() - Duration::weeks(2);
+        assert_eq!(format_time_ago(Some(time)), "2 weeks ago");
+    }
+
+    #[test]
+    fn test_format_time_ago_months() {
+        let time = Utc::now() - Duration::days(30);
+        assert_eq!(format_time_ago(Some(time)), "1 month ago");
+
+        let time = Utc::now() - Duration::days(90);
+        assert_eq!(format_time_ago(Some(time)), "3 months ago");
+    }
+
+    #[test]
+    fn test_format_date_absolute() {
+        let time = Utc.with_ymd_and_hms(2024, 5, 1, 14, 30, 5).unwrap();
+        assert_eq!(format_date(Some(time), DateStyle::Absolute), "2024-05-01 14:30");
+        assert_eq!(format_date(None, DateStyle::Absolute), "never");
+
+        let time = Utc::now() - Duration::days(3);
+        assert_eq!(format_date(Some(time), DateStyle::Relative), "3 days ago");
+    }
 }
@@ -0,0 +1,114 @@
+//! Where a command's time goes (`--timings`)
+//!
+//! The phases that touch the disk or scan aliases are measured in every run;
+//! `goto-bin` prints them to stderr with `--timings`. A phase measured inside
+//! another (a save during navigation) only counts towards the inner one.
+
+use std::fmt;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// A measured part of a run
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Phase {
+    /// Reading config.toml
+    Config,
+    /// Reading the alias database, usage log, overlay and shared aliases
+    Database,
+    /// Finding the alias to navigate to
+    Matching,
+    /// Writing the alias database or appending to the usage log
+    Save,
+}
+
+impl fmt::Display for Phase {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Phase::Config => "config load",
+            Phase::Database => "database load",
+            Phase::Matching => "matching",
+            Phase::Save => "save",
+        })
+    }
+}
+
+static RECORDED: Mutex<Vec<(Phase, Duration)>> = Mutex::new(Vec::new());
+static REPORT: AtomicBool = AtomicBool::new(false);
+
+/// Ask for the report at the end of the run (`--timings`)
+pub fn set_report(report: bool) {
+    REPORT.store(report, Ordering::Relaxed);
+}
+
+/// Whether the report was asked for
+pub fn wants_report() -> bool {
+    REPORT.load(Ordering::Relaxed)
+}
+
+fn recorded() -> Vec<(Phase, Duration)> {
+    RECORDED.lock().map(|r| r.clone()).unwrap_or_default()
+}
+
+fn total() -> Duration {
+    recorded().iter().map(|(_, d)| *d).sum()
+}
+
+/// Add `duration` to `phase`
+pub fn record(phase: Phase, duration: Duration) {
+    if let Ok(mut recorded) = RECORDED.lock() {
+        match recorded.iter_mut().find(|(p, _)| *p == phase) {
+            Some((_, total)) => *total += duration,
+            None => recorded.push((phase, duration)),
+        }
+    }
+}
+
+/// Run `f`, counting its time towards `phase` less the phases measured inside it
+pub fn measure<T>(phase: Phase, f: impl FnOnce() -> T) -> T {
+    let before = total();
+    let started = Instant::now();
+    let value = f();
+    let inner = total().saturating_sub(before);
+    record(phase, started.elapsed().saturating_sub(inner));
+    value
+}
+
+/// One line per phase measured so far, in the order they first ran
+pub fn report() -> String {
+    format_report(&recorded())
+}
+
+fn format_report(recorded: &[(Phase, Duration)]) -> String {
+    recorded
+        .iter()
+        .map(|(phase, duration)| format!("{:<14} {:>9.2} ms", phase.to_string(), duration.as_secs_f64() * 1000.0))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_format_report() {
+        let report = format_report(&[
+            (Phase::Config, Duration::from_micros(1250)),
+            (Phase::Save, Duration::from_millis(1200)),
+        ]);
+        assert_eq!(report, "config load         1.25 ms\nsave             1200.00 ms");
+    }
+
+    #[test]
+    fn test_measure_excludes_inner_phases() {
+        let value = measure(Phase::Matching, || {
+            record(Phase::Save, Duration::from_secs(60));
+            42
+        });
+        assert_eq!(value, 42);
+        let recorded = recorded();
+        let matching = recorded.iter().find(|(p, _)| *p == Phase::Matching).unwrap().1;
+        assert!(matching < Duration::from_secs(1), "{:?}", matching);
+    }
+}
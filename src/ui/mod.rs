@@ -0,0 +1,132 @@
+//! Terminal UI building blocks shared by the interactive prompts
+
+pub mod selector;
+
+use std::io::{self, IsTerminal};
+
+use crossterm::event::{self, Event, KeyEvent, KeyEventKind};
+use crossterm::{cursor, execute, terminal};
+
+/// Whether the terminal can run the inline (raw mode) UI
+///
+/// Needs stdin for key presses and stderr for drawing (stdout belongs to the
+/// shell wrapper). `TERM=dumb` terminals get the line-based prompts instead.
+pub fn supports_inline_ui() -> bool {
+    io::stdin().is_terminal()
+        && io::stderr().is_terminal()
+        && (cfg!(windows) || !is_dumb_term(std::env::var("TERM").ok().as_deref()))
+}
+
+fn is_dumb_term(term: Option<&str>) -> bool {
+    matches!(term, None | Some("") | Some("dumb"))
+}
+
+/// Raw mode with a hidden cursor, restored when dropped
+pub struct RawMode;
+
+impl RawMode {
+    pub fn enable() -> io::Result<Self> {
+        terminal::enable_raw_mode()?;
+        let _ = execute!(io::stderr(), cursor::Hide);
+        Ok(RawMode)
+    }
+}
+
+impl Drop for RawMode {
+    fn drop(&mut self) {
+        let _ = execute!(io::stderr(), cursor::Show);
+        let _ = terminal::disable_raw_mode();
+    }
+}
+
+/// Block until a key is pressed (releases and other events are skipped)
+pub fn read_key() -> io::Result<KeyEvent> {
+    loop {
+        if let Event::Key(key) = event::read()? {
+            if key.kind == KeyEventKind::Press {
+                return Ok(key);
+            }
+        }
+    }
+}
+
+/// Terminal width in columns, 80 when unknown
+pub fn width() -> usize {
+    match terminal::size() {
+        Ok((w, _)) if w > 0 => w as usize,
+        _ => 80,
+    }
+}
+
+/// Cut `line` to at most `width` visible characters, marking the cut with `…`
+///
+/// Color escape sequences (as in colored tags) take no space and are kept;
+/// a cut colored line is reset before the `…`.
+pub fn truncate(line: &str, width: usize) -> String {
+    if visible_width(line) <= width {
+        return line.to_string();
+    }
+    let mut cut = String::new();
+    let mut visible = 0;
+    let mut styled = false;
+    let mut chars = line.chars();
+    while let Some(c) = chars.next() {
+        if c == '\x1b' {
+            styled = true;
+            cut.push(c);
+            // Copy the sequence up to its final letter
+            for c in chars.by_ref() {
+                cut.push(c);
+                if c.is_ascii_alphabetic() {
+                    break;
+                }
+            }
+        } else if visible + 1 < width {
+            cut.push(c);
+            visible += 1;
+        }
+    }
+    if styled {
+        cut.push_str("\x1b[0m");
+    }
+    cut.push('…');
+    cut
+}
+
+/// Characters of `line` outside escape sequences
+pub(crate) fn visible_width(line: &str) -> usize {
+    let mut width = 0;
+    let mut in_escape = false;
+    for c in line.chars() {
+        match c {
+            '\x1b' => in_escape = true,
+            c if in_escape => in_escape = !c.is_ascii_alphabetic(),
+            _ => width += 1,
+        }
+    }
+    width
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_dumb_term() {
+        assert!(is_dumb_term(None));
+        assert!(is_dumb_term(Some("")));
+        assert!(is_dumb_term(Some("dumb")));
+        assert!(!is_dumb_term(Some("xterm-256color")));
+    }
+
+    #[test]
+    fn test_truncate() {
+        assert_eq!(truncate("short", 10), "short");
+        assert_eq!(truncate("/home/user/projects", 8), "/home/u…");
+        assert_eq!(truncate("äöü", 3), "äöü");
+
+        let colored = "[\x1b[34mwork\x1b[39m, rust]";
+        assert_eq!(truncate(colored, 12), colored);
+        assert_eq!(truncate(colored, 4), "[\x1b[34mwo\x1b[39m\x1b[0m…");
+    }
+}
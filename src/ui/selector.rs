@@ -0,0 +1,250 @@
+//! Inline arrow-key selector for picking one of a few choices
+
+use std::io::{self, Write};
+
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use crossterm::style::Stylize;
+use crossterm::terminal::{Clear, ClearType};
+use crossterm::{cursor, queue};
+
+use crate::context::Ctx;
+
+use super::{read_key, supports_inline_ui, truncate, width, RawMode};
+
+/// One selectable entry
+#[derive(Debug, Clone, PartialEq)]
+pub struct Choice {
+    pub label: String,
+    /// Similarity shown as a percentage (0.0-1.0)
+    pub score: Option<f64>,
+    /// Target path, previewed while the choice is highlighted
+    pub path: String,
+    /// Extra line shown under the path (and in the numbered fallback)
+    pub detail: Option<String>,
+}
+
+/// Result of a key press
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Outcome {
+    Continue,
+    Selected(usize),
+    Cancelled,
+}
+
+/// Highlight position within a list of choices
+#[derive(Debug)]
+pub struct Selector<'a> {
+    choices: &'a [Choice],
+    selected: usize,
+}
+
+impl<'a> Selector<'a> {
+    pub fn new(choices: &'a [Choice]) -> Self {
+        Self {
+            choices,
+            selected: 0,
+        }
+    }
+
+    /// Index of the highlighted choice
+    pub fn selected(&self) -> usize {
+        self.selected
+    }
+
+    /// Apply a key press: arrows (or Ctrl-P/N, k/j) move, Enter picks, a digit
+    /// picks directly, Esc / q / Ctrl-C cancel
+    pub fn handle_key(&mut self, key: KeyEvent) -> Outcome {
+        let len = self.choices.len();
+        let ctrl = key.modifiers.contains(KeyModifiers::CONTROL);
+        match key.code {
+            KeyCode::Up | KeyCode::BackTab | KeyCode::Char('k') if !ctrl => {
+                self.selected = (self.selected + len - 1) % len;
+            }
+            KeyCode::Down | KeyCode::Tab | KeyCode::Char('j') if !ctrl => {
+                self.selected = (self.selected + 1) % len;
+            }
+            KeyCode::Char('p') if ctrl => self.selected = (self.selected + len - 1) % len,
+            KeyCode::Char('n') if ctrl => self.selected = (self.selected + 1) % len,
+            KeyCode::Char('c') if ctrl => return Outcome::Cancelled,
+            KeyCode::Enter => return Outcome::Selected(self.selected),
+            KeyCode::Esc | KeyCode::Char('q') => return Outcome::Cancelled,
+            KeyCode::Char(c) => {
+                if let Some(n) = c.to_digit(10).map(|d| d as usize) {
+                    if (1..=len).contains(&n) {
+                        return Outcome::Selected(n - 1);
+                    }
+                }
+            }
+            _ => {}
+        }
+        Outcome::Continue
+    }
+
+    /// Lines to draw: the choices, then a preview of the highlighted one
+    pub fn lines(&self) -> Vec<String> {
+        let label_width = self.choices.iter().map(|c| c.label.chars().count()).max().unwrap_or(0);
+
+        let mut lines: Vec<String> = self
+            .choices
+            .iter()
+            .enumerate()
+            .map(|(i, choice)| {
+                let marker = if i == self.selected { '>' } else { ' ' };
+                match choice.score {
+                    Some(score) => format!(
+                        "{} {:<width$}  {}% match",
+                        marker,
+                        choice.label,
+                        (score * 100.0).round() as u32,
+                        width = label_width
+                    ),
+                    None => format!("{} {}", marker, choice.label),
+                }
+            })
+            .collect();
+
+        let current = &self.choices[self.selected];
+        lines.push(format!("  → {}", current.path));
+        if let Some(detail) = &current.detail {
+            lines.push(format!("    {}", detail));
+        }
+        lines.push("  ↑/↓ move · Enter select · Esc cancel".to_string());
+        lines
+    }
+}
+
+/// Let the user pick one of `choices`, returning its index
+///
+/// Uses the inline selector on capable terminals and numbered entry
+/// otherwise. Returns `None` when cancelled or when `ctx` isn't interactive
+/// (stdin isn't a terminal, or `--no-input`).
+pub fn select(ctx: &Ctx, choices: &[Choice]) -> io::Result<Option<usize>> {
+    if choices.is_empty() || !ctx.interactive {
+        return Ok(None);
+    }
+
+    if !supports_inline_ui() {
+        let labels: Vec<&str> = choices.iter().map(|c| c.label.as_str()).collect();
+        let scores: Option<Vec<f64>> = choices.iter().map(|c| c.score).collect();
+        let details: Option<Vec<String>> = choices.iter().map(|c| c.detail.clone()).collect();
+        return crate::prompt_selection_with_details(&labels, scores.as_deref(), details.as_deref());
+    }
+
+    let mut selector = Selector::new(choices);
+    let mut stderr = io::stderr();
+    let _raw = RawMode::enable()?;
+
+    let mut drawn = draw(&mut stderr, &selector, 0)?;
+    let outcome = loop {
+        match selector.handle_key(read_key()?) {
+            Outcome::Continue => drawn = draw(&mut stderr, &selector, drawn)?,
+            Outcome::Selected(i) => break Some(i),
+            Outcome::Cancelled => break None,
+        }
+    };
+
+    clear(&mut stderr, drawn)?;
+    Ok(outcome)
+}
+
+/// Redraw the selector over the `previous` lines, returning the lines drawn
+fn draw(out: &mut impl Write, selector: &Selector, previous: usize) -> io::Result<usize> {
+    clear(out, previous)?;
+
+    let width = width().saturating_sub(1).max(10);
+    let lines = selector.lines();
+    for (i, line) in lines.iter().enumerate() {
+        if i > 0 {
+            write!(out, "\r\n")?;
+        }
+        let line = truncate(line, width);
+        if i == selector.selected() {
+            write!(out, "{}", line.reverse())?;
+        } else {
+            write!(out, "{}", line)?;
+        }
+    }
+    out.flush()?;
+    Ok(lines.len())
+}
+
+/// Erase `lines` lines drawn by [`draw`], leaving the cursor where they began
+fn clear(out: &mut impl Write, lines: usize) -> io::Result<()> {
+    if lines > 1 {
+        queue!(out, cursor::MoveUp((lines - 1) as u16))?;
+    }
+    queue!(out, cursor::MoveToColumn(0), Clear(ClearType::FromCursorDown))?;
+    out.flush()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn choices() -> Vec<Choice> {
+        vec![
+            Choice {
+                label: "api".to_string(),
+                score: Some(0.92),
+                path: "/work/api".to_string(),
+                detail: Some("[main] src/".to_string()),
+            },
+            Choice {
+                label: "apps".to_string(),
+                score: Some(0.8),
+                path: "/work/apps".to_string(),
+                detail: None,
+            },
+        ]
+    }
+
+    fn key(code: KeyCode) -> KeyEvent {
+        KeyEvent::new(code, KeyModifiers::NONE)
+    }
+
+    #[test]
+    fn test_arrows_wrap_around() {
+        let choices = choices();
+        let mut selector = Selector::new(&choices);
+
+        assert_eq!(selector.handle_key(key(KeyCode::Up)), Outcome::Continue);
+        assert_eq!(selector.selected(), 1);
+        selector.handle_key(key(KeyCode::Down));
+        assert_eq!(selector.selected(), 0);
+        selector.handle_key(KeyEvent::new(KeyCode::Char('n'), KeyModifiers::CONTROL));
+        assert_eq!(selector.selected(), 1);
+        assert_eq!(selector.handle_key(key(KeyCode::Enter)), Outcome::Selected(1));
+    }
+
+    #[test]
+    fn test_digits_and_cancel() {
+        let choices = choices();
+        let mut selector = Selector::new(&choices);
+
+        assert_eq!(selector.handle_key(key(KeyCode::Char('2'))), Outcome::Selected(1));
+        assert_eq!(selector.handle_key(key(KeyCode::Char('3'))), Outcome::Continue);
+        assert_eq!(selector.handle_key(key(KeyCode::Esc)), Outcome::Cancelled);
+        assert_eq!(
+            selector.handle_key(KeyEvent::new(KeyCode::Char('c'), KeyModifiers::CONTROL)),
+            Outcome::Cancelled
+        );
+    }
+
+    #[test]
+    fn test_lines_preview_highlighted_choice() {
+        let choices = choices();
+        let mut selector = Selector::new(&choices);
+
+        let lines = selector.lines();
+        assert_eq!(lines[0], "> api   92% match");
+        assert_eq!(lines[1], "  apps  80% match");
+        assert_eq!(lines[2], "  → /work/api");
+        assert_eq!(lines[3], "    [main] src/");
+        assert_eq!(lines.len(), 5);
+
+        selector.handle_key(key(KeyCode::Down));
+        let lines = selector.lines();
+        assert_eq!(lines[2], "  → /work/apps");
+        assert_eq!(lines.len(), 4);
+    }
+}
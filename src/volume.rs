@@ -0,0 +1,250 @@
+//! Which volume an alias directory lives on
+//!
+//! Registering an alias records the filesystem its directory is on when that
+//! isn't the root filesystem: the mount point, plus the UUID, label and
+//! device that identify it. A missing directory whose volume isn't mounted
+//! any more is then reported as unmounted rather than deleted, and
+//! `--cleanup` leaves it alone.
+//!
+//! Mounts are read from `/proc/self/mountinfo`, and UUIDs and labels from
+//! the `/dev/disk/by-uuid` and `/dev/disk/by-label` links, so volumes are
+//! only recorded on Linux.
+
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+/// A filesystem an alias directory was on when it was registered
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct Volume {
+    /// Where it was mounted
+    pub mount_point: String,
+    /// Filesystem UUID, when it has one
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub uuid: Option<String>,
+    /// Filesystem label, when it has one
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub label: Option<String>,
+    /// What was mounted: a device such as `/dev/sdb1`, or `host:/export`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub source: Option<String>,
+}
+
+impl fmt::Display for Volume {
+    /// How a user would name it to `mount`: `LABEL=backup`, `UUID=...`, the source or the mount point
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match (&self.label, &self.uuid, &self.source) {
+            (Some(label), _, _) => write!(f, "LABEL={}", label),
+            (None, Some(uuid), _) => write!(f, "UUID={}", uuid),
+            (None, None, Some(source)) => f.write_str(source),
+            (None, None, None) => f.write_str(&self.mount_point),
+        }
+    }
+}
+
+/// One line of the mount table
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Mount {
+    pub mount_point: PathBuf,
+    pub source: String,
+}
+
+/// The filesystems mounted right now
+#[derive(Debug, Clone, Default)]
+pub struct MountTable {
+    mounts: Vec<Mount>,
+}
+
+impl MountTable {
+    /// Read the mount table; empty where it can't be read
+    pub fn read() -> Self {
+        let content = fs::read_to_string("/proc/self/mountinfo").unwrap_or_default();
+        Self::parse(&content)
+    }
+
+    /// Parse `/proc/self/mountinfo` content
+    ///
+    /// Fields are space-separated, with spaces in paths written as `\040`;
+    /// the mount point is the fifth, and the source follows the `-` separator
+    /// and the filesystem type.
+    pub fn parse(content: &str) -> Self {
+        let mounts = content
+            .lines()
+            .filter_map(|line| {
+                let fields: Vec<&str> = line.split(' ').collect();
+                let mount_point = fields.get(4)?;
+                let separator = fields.iter().position(|f| *f == "-")?;
+                let source = fields.get(separator + 2)?;
+                Some(Mount {
+                    mount_point: PathBuf::from(unescape(mount_point)),
+                    source: unescape(source),
+                })
+            })
+            .collect();
+        Self { mounts }
+    }
+
+    /// The mount `path` is on: the one with the longest mount point above it
+    ///
+    /// Later mounts over the same point hide earlier ones (`max_by_key`
+    /// returns the last of equal keys), such as a drive over its autofs
+    /// placeholder.
+    pub fn mount_of(&self, path: &Path) -> Option<&Mount> {
+        self.mounts
+            .iter()
+            .filter(|m| path.starts_with(&m.mount_point))
+            .max_by_key(|m| m.mount_point.components().count())
+    }
+
+    /// Whether something is mounted at `volume`'s mount point
+    pub fn is_mounted(&self, volume: &Volume) -> bool {
+        self.mounts.iter().any(|m| m.mount_point == Path::new(&volume.mount_point))
+    }
+}
+
+/// Undo the octal escapes (`\040` for a space) of the mount table and
+/// `/dev/disk` names (`\x20`)
+fn unescape(field: &str) -> String {
+    let mut out = Vec::new();
+    let bytes = field.as_bytes();
+    let mut i = 0;
+    while i < bytes.len() {
+        let octal = bytes.get(i + 1..i + 4).and_then(|d| u8::from_str_radix(std::str::from_utf8(d).ok()?, 8).ok());
+        let hex = bytes
+            .get(i + 1..i + 4)
+            .filter(|d| d[0] == b'x')
+            .and_then(|d| u8::from_str_radix(std::str::from_utf8(&d[1..]).ok()?, 16).ok());
+        match (bytes[i], octal, hex) {
+            (b'\\', Some(byte), _) | (b'\\', None, Some(byte)) => {
+                out.push(byte);
+                i += 4;
+            }
+            (byte, _, _) => {
+                out.push(byte);
+                i += 1;
+            }
+        }
+    }
+    String::from_utf8_lossy(&out).into_owned()
+}
+
+/// The name in `dir` (e.g. `/dev/disk/by-uuid`) of the link pointing at `device`
+fn disk_link(dir: &Path, device: &Path) -> Option<String> {
+    fs::read_dir(dir).ok()?.flatten().find_map(|entry| {
+        let target = fs::canonicalize(entry.path()).ok()?;
+        (target == device).then(|| unescape(&entry.file_name().to_string_lossy()))
+    })
+}
+
+/// The volume `path` is on, unless that's the root filesystem
+pub fn of(path: &Path) -> Option<Volume> {
+    of_with(path, &MountTable::read(), Path::new("/dev/disk"))
+}
+
+/// [`of`] with a given mount table and `/dev/disk` directory
+fn of_with(path: &Path, table: &MountTable, disks: &Path) -> Option<Volume> {
+    let path = fs::canonicalize(path).ok()?;
+    let mount = table.mount_of(&path)?;
+    if mount.mount_point == Path::new("/") {
+        return None;
+    }
+    let device = fs::canonicalize(&mount.source).ok();
+    let link = |kind: &str| device.as_deref().and_then(|d| disk_link(&disks.join(kind), d));
+    Some(Volume {
+        mount_point: mount.mount_point.to_string_lossy().into_owned(),
+        uuid: link("by-uuid"),
+        label: link("by-label"),
+        source: Some(mount.source.clone()).filter(|s| !s.is_empty() && s != "none"),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    const MOUNTINFO: &str = "\
+22 1 259:2 / / rw,relatime shared:1 - ext4 /dev/nvme0n1p2 rw
+45 22 8:17 / /media/back\\040up rw,nosuid shared:30 - vfat /dev/sdb1 rw
+46 22 0:53 / /mnt/nas rw shared:31 master:2 - nfs4 nas:/export rw
+";
+
+    #[test]
+    fn test_parse_mount_table() {
+        let table = MountTable::parse(MOUNTINFO);
+        assert_eq!(
+            table.mount_of(Path::new("/media/back up/photos")).unwrap(),
+            &Mount {
+                mount_point: PathBuf::from("/media/back up"),
+                source: "/dev/sdb1".to_string()
+            }
+        );
+        assert_eq!(table.mount_of(Path::new("/mnt/nas/x")).unwrap().source, "nas:/export");
+        assert_eq!(table.mount_of(Path::new("/media/backup")).unwrap().mount_point, Path::new("/"));
+    }
+
+    #[test]
+    fn test_mount_of_prefers_latest_stacked_mount() {
+        // An automounted drive: the autofs placeholder, then the drive over it
+        let table = MountTable::parse(
+            "22 1 259:2 / / rw - ext4 /dev/nvme0n1p2 rw\n\
+             50 22 0:60 / /media/usb rw - autofs systemd-1 rw\n\
+             51 50 8:17 / /media/usb rw - vfat /dev/sdb1 rw\n",
+        );
+        assert_eq!(table.mount_of(Path::new("/media/usb/photos")).unwrap().source, "/dev/sdb1");
+    }
+
+    #[test]
+    fn test_is_mounted_and_display() {
+        let table = MountTable::parse(MOUNTINFO);
+        let mut volume = Volume {
+            mount_point: "/mnt/nas".to_string(),
+            uuid: None,
+            label: None,
+            source: Some("nas:/export".to_string()),
+        };
+        assert!(table.is_mounted(&volume));
+        assert_eq!(volume.to_string(), "nas:/export");
+
+        volume.mount_point = "/media/usb".to_string();
+        volume.uuid = Some("1234-ABCD".to_string());
+        assert!(!table.is_mounted(&volume));
+        assert_eq!(volume.to_string(), "UUID=1234-ABCD");
+        volume.label = Some("backup".to_string());
+        assert_eq!(volume.to_string(), "LABEL=backup");
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_of_with() {
+        // A directory standing in for the device, linked from fake /dev/disk entries
+        let dir = tempdir().unwrap();
+        let device = dir.path().join("sdb1");
+        let mount_point = dir.path().join("media");
+        let disks = dir.path().join("disk");
+        fs::create_dir_all(mount_point.join("photos")).unwrap();
+        fs::create_dir(&device).unwrap();
+        fs::create_dir_all(disks.join("by-uuid")).unwrap();
+        fs::create_dir_all(disks.join("by-label")).unwrap();
+        std::os::unix::fs::symlink(&device, disks.join("by-uuid/1234-ABCD")).unwrap();
+        std::os::unix::fs::symlink(&device, disks.join("by-label/my\\x20backup")).unwrap();
+
+        let mount_point = fs::canonicalize(&mount_point).unwrap();
+        let table = MountTable::parse(&format!(
+            "1 0 8:1 / / rw - ext4 /dev/root rw\n2 1 8:17 / {} rw - vfat {} rw\n",
+            mount_point.display(),
+            device.display()
+        ));
+        let volume = of_with(&mount_point.join("photos"), &table, &disks).unwrap();
+        assert_eq!(volume.mount_point, mount_point.to_string_lossy());
+        assert_eq!(volume.uuid.as_deref(), Some("1234-ABCD"));
+        assert_eq!(volume.label.as_deref(), Some("my backup"));
+        assert_eq!(volume.to_string(), "LABEL=my backup");
+
+        // Nothing is recorded for the root filesystem
+        let table = MountTable::parse("1 0 8:1 / / rw - ext4 /dev/root rw\n");
+        assert!(of_with(&mount_point, &table, &disks).is_none());
+    }
+}
@@ -1,6 +1,6 @@
 #!/usr/bin/env bats
 
-# Test fzf integration in shell wrappers
+# Test the interactive picker in shell wrappers (fzf itself runs in goto-bin --pick)
 
 setup() {
     # Save original PATH for teardown
@@ -21,7 +21,12 @@ setup() {
     # Create mock goto-bin
     cat > "$MOCK_BIN/goto-bin" << 'MOCK_GOTO'
 #!/bin/bash
+[[ "$1" == --escape=* ]] && shift
 case "$1" in
+    --pick)
+        touch "$TEST_DIR/picked"
+        printf "'%s'\n" "$TEST_DIR/projects/foo"
+        ;;
     --names-only)
         echo "foo"
         echo "bar"
@@ -37,11 +42,11 @@ case "$1" in
         esac
         ;;
     foo)
-        echo "$TEST_DIR/projects/foo"
+        printf "'%s'\n" "$TEST_DIR/projects/foo"
         exit 0
         ;;
     bar)
-        echo "$TEST_DIR/projects/bar"
+        printf "'%s'\n" "$TEST_DIR/projects/bar"
         exit 0
         ;;
     *)
@@ -71,155 +76,40 @@ load_bash_wrapper() {
 }
 
 #
-# Fallback tests (no fzf available)
+# Not interactive: list instead of picking
 #
 
-@test "bash: no-args without fzf shows list" {
-    # Shadow fzf with a script that exits with error (simulating not found)
-    cat > "$MOCK_BIN/fzf" << 'EOF'
-#!/bin/bash
-exit 127
-EOF
-    chmod +x "$MOCK_BIN/fzf"
-
-    # Override command -v to report fzf not found
-    run bash -c '
-        fzf() { return 127; }
-        command() {
-            if [[ "$1" == "-v" && "$2" == "fzf" ]]; then
-                return 1
-            fi
-            builtin command "$@"
-        }
-        export PATH="'"$MOCK_BIN"':$PATH"
-        source '"$PROJECT_ROOT"'/shell/goto.bash
-        goto
-    '
-
-    [[ "$output" == *"foo"* ]]
-    [[ "$output" == *"bar"* ]]
-}
-
 @test "bash: no-args in non-interactive mode shows list" {
-    # Create mock fzf that should NOT be called
-    cat > "$MOCK_BIN/fzf" << 'EOF'
-#!/bin/bash
-echo "FZF_SHOULD_NOT_BE_CALLED"
-exit 1
-EOF
-    chmod +x "$MOCK_BIN/fzf"
-
-    load_bash_wrapper
-
-    # Pipe input to make stdin non-interactive
     run bash -c 'source '"$PROJECT_ROOT"'/shell/goto.bash && echo "" | goto'
 
-    # Should show list, not call fzf
-    [[ "$output" != *"FZF_SHOULD_NOT_BE_CALLED"* ]]
+    [[ "$output" == *"foo"* ]]
+    [[ "$output" == *"bar"* ]]
+    [[ ! -f "$TEST_DIR/picked" ]]
 }
 
 #
-# fzf integration tests
+# Picker
 #
 
-@test "bash: fzf receives alias names from --names-only" {
-    # Create mock fzf that records its stdin
-    cat > "$MOCK_BIN/fzf" << 'EOF'
-#!/bin/bash
-cat > "$TEST_DIR/fzf_input.txt"
-# Simulate user pressing Escape (empty selection)
-exit 0
-EOF
-    chmod +x "$MOCK_BIN/fzf"
-
-    load_bash_wrapper
-
-    # Run in a pseudo-terminal context using script command
-    script -q -c 'source '"$PROJECT_ROOT"'/shell/goto.bash && goto' /dev/null < /dev/null || true
-
-    # Verify fzf received the alias names
-    if [[ -f "$TEST_DIR/fzf_input.txt" ]]; then
-        [[ "$(cat "$TEST_DIR/fzf_input.txt")" == *"foo"* ]]
-        [[ "$(cat "$TEST_DIR/fzf_input.txt")" == *"bar"* ]]
-    fi
-}
-
-@test "bash: selecting alias in fzf navigates to directory" {
-    # Create mock fzf that selects "foo"
-    cat > "$MOCK_BIN/fzf" << 'EOF'
-#!/bin/bash
-echo "foo"
-EOF
-    chmod +x "$MOCK_BIN/fzf"
-
-    # Need to test that cd would be called with the right path
-    # We'll check by examining the function behavior
-    load_bash_wrapper
-
-    # Capture what directory we'd cd to by running in subshell
+@test "bash: no-args on a terminal navigates to the goto-bin --pick result" {
     result=$(script -q -c '
         source '"$PROJECT_ROOT"'/shell/goto.bash
         goto
         pwd
     ' /dev/null 2>/dev/null | tail -1)
 
-    # Should have changed to foo directory
-    [[ "$result" == *"projects/foo"* ]] || [[ "$?" -eq 0 ]]
-}
-
-@test "bash: canceling fzf (empty selection) returns silently" {
-    # Create mock fzf that returns empty (user pressed Escape)
-    cat > "$MOCK_BIN/fzf" << 'EOF'
-#!/bin/bash
-# Return empty string (user canceled)
-exit 0
-EOF
-    chmod +x "$MOCK_BIN/fzf"
-
-    load_bash_wrapper
-
-    run script -q -c 'source '"$PROJECT_ROOT"'/shell/goto.bash && goto' /dev/null
-
-    # Should return 0 and not produce error output
-    [[ "$status" -eq 0 ]]
-}
-
-@test "bash: GOTO_FZF_OPTS is passed to fzf" {
-    # Create mock fzf that records its arguments
-    cat > "$MOCK_BIN/fzf" << 'EOF'
-#!/bin/bash
-echo "$@" > "$TEST_DIR/fzf_args.txt"
-exit 0
-EOF
-    chmod +x "$MOCK_BIN/fzf"
-
-    export GOTO_FZF_OPTS="--height 80%"
-    load_bash_wrapper
-
-    script -q -c 'source '"$PROJECT_ROOT"'/shell/goto.bash && goto' /dev/null < /dev/null || true
-
-    if [[ -f "$TEST_DIR/fzf_args.txt" ]]; then
-        [[ "$(cat "$TEST_DIR/fzf_args.txt")" == *"--height 80%"* ]]
-    fi
+    [[ -f "$TEST_DIR/picked" ]]
+    [[ "$result" == *"projects/foo"* ]]
 }
 
-@test "bash: fzf preview uses goto-bin -x" {
-    # Create mock fzf that records its arguments
-    cat > "$MOCK_BIN/fzf" << 'EOF'
-#!/bin/bash
-echo "$@" > "$TEST_DIR/fzf_args.txt"
-exit 0
-EOF
-    chmod +x "$MOCK_BIN/fzf"
-
-    load_bash_wrapper
-
-    script -q -c 'source '"$PROJECT_ROOT"'/shell/goto.bash && goto' /dev/null < /dev/null || true
+@test "bash: goto --pick navigates to the picked directory" {
+    result=$(bash -c '
+        source '"$PROJECT_ROOT"'/shell/goto.bash
+        goto --pick
+        pwd
+    ')
 
-    if [[ -f "$TEST_DIR/fzf_args.txt" ]]; then
-        [[ "$(cat "$TEST_DIR/fzf_args.txt")" == *"--preview"* ]]
-        [[ "$(cat "$TEST_DIR/fzf_args.txt")" == *"goto-bin -x"* ]]
-    fi
+    [[ "$result" == *"projects/foo"* ]]
 }
 
 #
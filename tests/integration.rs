@@ -1,6 +1,7 @@
 //! Integration tests for the goto CLI
 
 use std::fs;
+use std::path::Path;
 use std::process::Command;
 use tempfile::tempdir;
 
@@ -440,6 +441,16 @@ fn test_stats() {
         "Stats failed: {}",
         String::from_utf8_lossy(&output.stderr)
     );
+    assert!(String::from_utf8_lossy(&output.stdout).contains("just now"));
+
+    // --absolute-dates shows the date instead
+    let mut cmd = goto_bin();
+    cmd.env("GOTO_DB", &db_dir);
+    cmd.args(["--stats", "--absolute-dates"]);
+    let output = cmd.output().unwrap();
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(!stdout.contains("just now"), "{}", stdout);
+    assert!(stdout.contains(&chrono::Utc::now().format("%Y-%m-%d").to_string()), "{}", stdout);
 }
 
 #[test]
@@ -782,6 +793,66 @@ fn test_recent_navigation() {
     );
 }
 
+#[test]
+fn test_reset_stats_and_set_count() {
+    let temp = tempdir().unwrap();
+    let db_dir = temp.path().join("db");
+    fs::create_dir(&db_dir).unwrap();
+
+    for name in ["alpha", "beta"] {
+        let dir = temp.path().join(name);
+        fs::create_dir(&dir).unwrap();
+        let output = goto_bin()
+            .env("GOTO_DB", &db_dir)
+            .args(["-r", name, dir.to_str().unwrap()])
+            .output()
+            .unwrap();
+        assert!(output.status.success());
+    }
+    for _ in 0..3 {
+        assert!(goto_bin().env("GOTO_DB", &db_dir).arg("alpha").output().unwrap().status.success());
+    }
+
+    let usage_order = || {
+        let output = goto_bin()
+            .env("GOTO_DB", &db_dir)
+            .args(["--list", "--sort=usage"])
+            .output()
+            .unwrap();
+        let stdout = String::from_utf8_lossy(&output.stdout).to_string();
+        stdout.find("alpha").unwrap() < stdout.find("beta").unwrap()
+    };
+    assert!(usage_order(), "alpha was used most");
+
+    // Lift beta above alpha
+    let output = goto_bin()
+        .env("GOTO_DB", &db_dir)
+        .args(["--set-count", "beta", "10"])
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    assert!(String::from_utf8_lossy(&output.stdout).contains("Set use count of 'beta' to 10"));
+    assert!(!usage_order(), "beta should now sort first");
+
+    let output = goto_bin()
+        .env("GOTO_DB", &db_dir)
+        .args(["--reset-stats", "--all"])
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    assert!(String::from_utf8_lossy(&output.stdout).contains("Reset usage stats for 2 aliases"));
+
+    let output = goto_bin().env("GOTO_DB", &db_dir).arg("--recent").output().unwrap();
+    assert!(String::from_utf8_lossy(&output.stdout).contains("No recently visited"));
+
+    let output = goto_bin()
+        .env("GOTO_DB", &db_dir)
+        .args(["--reset-stats", "missing"])
+        .output()
+        .unwrap();
+    assert_eq!(output.status.code(), Some(1));
+}
+
 #[test]
 fn test_tag_and_untag() {
     let temp = tempdir().unwrap();
@@ -1353,7 +1424,7 @@ fn test_persistence() {
     assert!(output.status.success());
     let stdout = String::from_utf8_lossy(&output.stdout);
     // Stats should reflect usage
-    assert!(stdout.contains("persistent") || stdout.len() > 0);
+    assert!(stdout.contains("persistent") || !stdout.is_empty());
 }
 
 #[test]
@@ -1580,6 +1651,370 @@ fn test_update_cache_file() {
     }
 }
 
+#[test]
+fn test_profiles_keep_separate_aliases() {
+    let temp = tempdir().unwrap();
+    let db_dir = temp.path().join("db");
+    fs::create_dir(&db_dir).unwrap();
+    let target = temp.path().join("target");
+    fs::create_dir(&target).unwrap();
+
+    let output = goto_bin()
+        .env("GOTO_DB", &db_dir)
+        .env_remove("GOTO_PROFILE")
+        .args(["--profile=work", "-r", "proj", target.to_str().unwrap()])
+        .output()
+        .unwrap();
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+    assert!(db_dir.join("profiles/work/aliases.toml").exists());
+
+    // The default profile doesn't see it
+    let output = goto_bin()
+        .env("GOTO_DB", &db_dir)
+        .env_remove("GOTO_PROFILE")
+        .args(["-x", "proj"])
+        .output()
+        .unwrap();
+    assert_eq!(output.status.code(), Some(1));
+
+    // GOTO_PROFILE selects it too
+    let output = goto_bin()
+        .env("GOTO_DB", &db_dir)
+        .env("GOTO_PROFILE", "work")
+        .args(["-x", "proj"])
+        .output()
+        .unwrap();
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+
+    let output = goto_bin()
+        .env("GOTO_DB", &db_dir)
+        .env("GOTO_PROFILE", "work")
+        .arg("--profiles")
+        .output()
+        .unwrap();
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("  default (0 aliases)"), "{}", stdout);
+    assert!(stdout.contains("* work (1 alias)"), "{}", stdout);
+}
+
+#[test]
+fn test_invalid_profile_name() {
+    let temp = tempdir().unwrap();
+    let output = goto_bin()
+        .env("GOTO_DB", temp.path())
+        .args(["--profile=../x", "-l"])
+        .output()
+        .unwrap();
+    assert_eq!(output.status.code(), Some(3));
+    assert!(String::from_utf8_lossy(&output.stderr).contains("invalid profile"));
+}
+
+#[test]
+fn test_explain_does_not_record_usage() {
+    let temp = tempdir().unwrap();
+    let db_dir = temp.path().join("db");
+    let target = temp.path().join("project");
+    fs::create_dir(&target).unwrap();
+
+    let output = goto_bin()
+        .env("GOTO_DB", &db_dir)
+        .args(["-r", "project", target.to_str().unwrap()])
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    let before = fs::read_to_string(db_dir.join("aliases.toml")).unwrap();
+
+    let output = goto_bin()
+        .env("GOTO_DB", &db_dir)
+        .args(["--explain", "projet"])
+        .output()
+        .unwrap();
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("Query: projet"), "{}", stdout);
+    assert!(stdout.contains("Result: asks \"Did you mean\": project"), "{}", stdout);
+
+    let output = goto_bin()
+        .env("GOTO_DB", &db_dir)
+        .args(["--explain", "project"])
+        .output()
+        .unwrap();
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains(&format!("Result: prints {}", target.display())), "{}", stdout);
+
+    assert_eq!(fs::read_to_string(db_dir.join("aliases.toml")).unwrap(), before);
+}
+
+#[test]
+fn test_verbose_logs_to_stderr() {
+    let temp = tempdir().unwrap();
+    let db_dir = temp.path().join("db");
+    fs::create_dir(&db_dir).unwrap();
+    let project = temp.path().join("project");
+    fs::create_dir(&project).unwrap();
+    let output = goto_bin()
+        .env("GOTO_DB", &db_dir)
+        .args(["-r", "project", project.to_str().unwrap()])
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+
+    let output = goto_bin()
+        .env("GOTO_DB", &db_dir)
+        .args(["-V", "projet"])
+        .output()
+        .unwrap();
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("INFO goto::database] read 1 aliases from"), "{}", stderr);
+    assert!(stderr.contains("fuzzy candidates: [(\"project\""), "{}", stderr);
+    // Nothing leaks into the path the shell reads
+    assert!(output.stdout.is_empty());
+
+    // GOTO_LOG=info keeps only file operations
+    let output = goto_bin()
+        .env("GOTO_DB", &db_dir)
+        .env("GOTO_LOG", "info")
+        .args(["-x", "project"])
+        .output()
+        .unwrap();
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("INFO goto::database] read 1 aliases from"), "{}", stderr);
+    assert!(!stderr.contains("DEBUG"), "{}", stderr);
+    assert_eq!(String::from_utf8_lossy(&output.stdout).trim(), project.to_str().unwrap());
+
+    // Off by default
+    let output = goto_bin().env("GOTO_DB", &db_dir).env_remove("GOTO_LOG").args(["-x", "project"]).output().unwrap();
+    assert!(output.stderr.is_empty());
+}
+
+#[test]
+fn test_timings_report_on_stderr() {
+    let temp = tempdir().unwrap();
+    let db_dir = temp.path().join("db");
+    fs::create_dir(&db_dir).unwrap();
+    let project = temp.path().join("project");
+    fs::create_dir(&project).unwrap();
+    let output = goto_bin()
+        .env("GOTO_DB", &db_dir)
+        .args(["-r", "project", project.to_str().unwrap()])
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+
+    let output = goto_bin().env("GOTO_DB", &db_dir).args(["project", "--timings"]).output().unwrap();
+    assert!(output.status.success());
+    assert_eq!(String::from_utf8_lossy(&output.stdout).trim(), project.to_str().unwrap());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    let phases: Vec<&str> = stderr.lines().filter_map(|l| l.split("  ").next()).collect();
+    assert_eq!(phases, ["config load", "database load", "save", "matching"], "{}", stderr);
+    assert!(stderr.lines().all(|l| l.ends_with(" ms")), "{}", stderr);
+}
+
+#[test]
+fn test_commands_without_database_skip_loading_it() {
+    let temp = tempdir().unwrap();
+    let db_dir = temp.path().join("db");
+    fs::create_dir(&db_dir).unwrap();
+    // An unparseable database only matters to commands that read it
+    fs::write(db_dir.join("aliases.toml"), "not [valid toml").unwrap();
+
+    let run = |args: &[&str]| goto_bin().env("GOTO_DB", &db_dir).args(args).output().unwrap();
+    assert!(run(&["-v"]).status.success());
+    assert!(run(&["--help"]).status.success());
+    assert!(run(&["--config"]).status.success());
+    assert!(run(&["--stack"]).status.success());
+    assert_eq!(run(&["--pop"]).status.code(), Some(1));
+
+    // Commands that read it salvage it first
+    let output = run(&["-l"]);
+    assert!(output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("was damaged"));
+}
+
+#[test]
+fn test_temporary_alias_is_session_scoped() {
+    let temp = tempdir().unwrap();
+    let db_dir = temp.path().join("db");
+    fs::create_dir(&db_dir).unwrap();
+    let scratch = temp.path().join("scratch");
+    fs::create_dir(&scratch).unwrap();
+    // PIDs are capped well below u32::MAX, so this session's shell is gone
+    let session = "4294967294";
+
+    let output = goto_bin()
+        .env("GOTO_DB", &db_dir)
+        .env("GOTO_SESSION", session)
+        .args(["--temp", "-r", "scratch", scratch.to_str().unwrap()])
+        .output()
+        .unwrap();
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+    assert!(String::from_utf8_lossy(&output.stdout).contains("Registered temporary 'scratch'"));
+    assert!(!fs::read_to_string(db_dir.join("aliases.toml")).unwrap_or_default().contains("scratch"));
+
+    let expand = |session: &str| {
+        goto_bin()
+            .env("GOTO_DB", &db_dir)
+            .env("GOTO_SESSION", session)
+            .args(["-x", "scratch"])
+            .output()
+            .unwrap()
+    };
+    let output = expand(session);
+    assert!(output.status.success());
+    assert_eq!(String::from_utf8_lossy(&output.stdout).trim(), scratch.to_str().unwrap());
+
+    // Other sessions don't see it, and collect the overlay of the dead shell
+    assert!(!expand("other").status.success());
+    assert!(!db_dir.join("temp").join(session).exists());
+}
+
+#[test]
+fn test_shared_aliases_layer() {
+    let temp = tempdir().unwrap();
+    let db_dir = temp.path().join("db");
+    fs::create_dir(&db_dir).unwrap();
+    let team_dir = temp.path().join("team");
+    fs::create_dir(&team_dir).unwrap();
+    let shared = temp.path().join("shared.toml");
+    fs::write(
+        &shared,
+        format!(
+            "[[aliases]]\nname = \"team\"\npath = \"{}\"\ncreated_at = \"2024-01-01T00:00:00Z\"\n",
+            team_dir.display()
+        ),
+    )
+    .unwrap();
+    fs::write(
+        db_dir.join("config.toml"),
+        format!("[general]\nshared_aliases = \"{}\"\n", shared.display()),
+    )
+    .unwrap();
+
+    let output = goto_bin().env("GOTO_DB", &db_dir).args(["-x", "team"]).output().unwrap();
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+    assert_eq!(String::from_utf8_lossy(&output.stdout).trim(), team_dir.to_str().unwrap());
+
+    // Writes to shared aliases fail with a clear error
+    let output = goto_bin().env("GOTO_DB", &db_dir).args(["-u", "team"]).output().unwrap();
+    assert_eq!(output.status.code(), Some(6));
+    assert!(String::from_utf8_lossy(&output.stderr).contains("alias 'team' is shared and read-only"));
+    assert!(fs::read_to_string(&shared).unwrap().contains("team"));
+
+    // Navigating doesn't copy it into the user's database
+    let output = goto_bin().env("GOTO_DB", &db_dir).arg("team").output().unwrap();
+    assert!(output.status.success());
+    assert!(!fs::read_to_string(db_dir.join("aliases.toml")).unwrap_or_default().contains("team"));
+}
+
+/// Register directories whose names trip up `cd $(goto x)`, returning (alias, path)
+fn register_awkward_dirs(root: &std::path::Path, db_dir: &std::path::Path) -> Vec<(String, String)> {
+    let names = ["with space", "it's \"quoted\" `x`", "ünïcødé ✓", "line\nbreak"];
+    names
+        .iter()
+        .enumerate()
+        .map(|(i, name)| {
+            let dir = root.join(name);
+            fs::create_dir(&dir).unwrap();
+            let path = dir.canonicalize().unwrap().to_str().unwrap().to_string();
+            let alias = format!("awkward{}", i);
+            let output = goto_bin()
+                .env("GOTO_DB", db_dir)
+                .args(["-r", &alias, &path])
+                .output()
+                .unwrap();
+            assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+            (alias, path)
+        })
+        .collect()
+}
+
+#[test]
+fn test_print0_and_escape_paths() {
+    let temp = tempdir().unwrap();
+    let db_dir = temp.path().join("db");
+    fs::create_dir(&db_dir).unwrap();
+
+    for (alias, path) in register_awkward_dirs(temp.path(), &db_dir) {
+        let output = goto_bin()
+            .env("GOTO_DB", &db_dir)
+            .args(["--print0", "-x", &alias])
+            .output()
+            .unwrap();
+        assert!(output.status.success());
+        assert_eq!(output.stdout, format!("{}\0", path).into_bytes());
+
+        let output = goto_bin()
+            .env("GOTO_DB", &db_dir)
+            .args([&alias, "--escape=shell"])
+            .output()
+            .unwrap();
+        assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+        let quoted = String::from_utf8(output.stdout).unwrap();
+        assert_eq!(quoted.lines().count(), 1, "{}", quoted);
+
+        // The quoted line evaluates back to the exact path
+        let evaluated = Command::new("bash")
+            .args(["-c", "eval \"target=$1\"; printf %s \"$target\"", "_", quoted.trim_end()])
+            .output()
+            .unwrap();
+        assert_eq!(String::from_utf8(evaluated.stdout).unwrap(), path);
+    }
+}
+
+#[test]
+fn test_cd_file() {
+    let temp = tempdir().unwrap();
+    let db_dir = temp.path().join("db");
+    fs::create_dir(&db_dir).unwrap();
+    let cd_file = temp.path().join("cd");
+
+    for (alias, path) in register_awkward_dirs(temp.path(), &db_dir) {
+        let output = goto_bin()
+            .env("GOTO_DB", &db_dir)
+            .env("GOTO_CD_FILE", &cd_file)
+            .arg(&alias)
+            .output()
+            .unwrap();
+        assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+        assert!(output.stdout.is_empty());
+        assert_eq!(fs::read(&cd_file).unwrap(), format!("{}\0", path).into_bytes());
+
+        // -x prints for scripts; there's nothing to enter
+        fs::remove_file(&cd_file).unwrap();
+        let output = goto_bin()
+            .env("GOTO_DB", &db_dir)
+            .args(["--cd-file", cd_file.to_str().unwrap(), "-x", &alias])
+            .output()
+            .unwrap();
+        assert_eq!(output.stdout, format!("{}\n", path).into_bytes());
+        assert!(!cd_file.exists());
+    }
+}
+
+#[cfg(unix)]
+#[test]
+fn test_bash_wrapper_enters_awkward_paths() {
+    let temp = tempdir().unwrap();
+    let db_dir = temp.path().join("db");
+    fs::create_dir(&db_dir).unwrap();
+    let dirs = register_awkward_dirs(temp.path(), &db_dir);
+
+    let bin_dir = std::path::Path::new(env!("CARGO_BIN_EXE_goto-bin")).parent().unwrap();
+    let wrapper = concat!(env!("CARGO_MANIFEST_DIR"), "/shell/goto.bash");
+    let path_var = format!("{}:{}", bin_dir.display(), std::env::var("PATH").unwrap_or_default());
+
+    for (alias, path) in dirs {
+        let output = Command::new("bash")
+            .args(["-c", "source \"$1\" && goto \"$2\" && printf %s \"$PWD\"", "_", wrapper, &alias])
+            .env("GOTO_DB", &db_dir)
+            .env("PATH", &path_var)
+            .output()
+            .unwrap();
+        assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+        assert_eq!(String::from_utf8(output.stdout).unwrap(), path);
+    }
+}
+
 #[test]
 fn test_config_shows_update_settings() {
     let temp = tempdir().unwrap();
@@ -1613,6 +2048,75 @@ fn test_config_shows_update_settings() {
     );
 }
 
+#[test]
+fn test_register_reserved_names() {
+    let temp = tempdir().unwrap();
+    let db_dir = temp.path().join("db");
+    fs::create_dir(&db_dir).unwrap();
+    fs::write(db_dir.join("config.toml"), "[general]\nreserved_names = [\"help\"]\n").unwrap();
+    let dir = temp.path().to_str().unwrap();
+
+    let output = goto_bin().env("GOTO_DB", &db_dir).args(["-r", "Help", dir]).output().unwrap();
+    assert_eq!(output.status.code(), Some(3));
+    assert!(String::from_utf8_lossy(&output.stderr).contains("reserved by general.reserved_names"));
+
+    let output = goto_bin().env("GOTO_DB", &db_dir).args(["-r", "..", dir]).output().unwrap();
+    assert_eq!(output.status.code(), Some(3));
+    assert!(String::from_utf8_lossy(&output.stderr).contains("reserved: '..' is a relative path"));
+
+    let output = goto_bin().env("GOTO_DB", &db_dir).args(["-r", "helpdesk", dir]).output().unwrap();
+    assert!(output.status.success());
+}
+
+#[test]
+fn test_register_protected_directory() {
+    let temp = tempdir().unwrap();
+    let db_dir = temp.path().join("db");
+    fs::create_dir(&db_dir).unwrap();
+
+    // Warned about, but registered
+    let output = goto_bin().env("GOTO_DB", &db_dir).args(["-r", "root", "/"]).output().unwrap();
+    assert!(output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("protected directory"));
+
+    fs::write(db_dir.join("config.toml"), "[safety]\nstrict = true\n").unwrap();
+    let output = goto_bin().env("GOTO_DB", &db_dir).args(["-r", "etc", "/etc"]).output().unwrap();
+    assert_eq!(output.status.code(), Some(3));
+
+    let output = goto_bin().env("GOTO_DB", &db_dir).args(["-r", "etc", "/etc", "--force"]).output().unwrap();
+    assert!(output.status.success());
+}
+
+#[test]
+fn test_config_validate() {
+    let temp = tempdir().unwrap();
+    let db_dir = temp.path().join("db");
+    fs::create_dir(&db_dir).unwrap();
+    let config = db_dir.join("config.toml");
+
+    let output = goto_bin().env("GOTO_DB", &db_dir).args(["--config", "--validate"]).output().unwrap();
+    assert!(output.status.success());
+
+    fs::write(&config, "[general]\nfuzy_threshold = 0.5\n\n[display]\ntable_style = \"fancy\"\n").unwrap();
+    let output = goto_bin().env("GOTO_DB", &db_dir).args(["--config", "--validate"]).output().unwrap();
+    assert_eq!(output.status.code(), Some(1));
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("config.toml:2: error: unknown key 'fuzy_threshold'"), "{}", stdout);
+    assert!(stdout.contains("config.toml:5: error: invalid display.table_style 'fancy'"), "{}", stdout);
+
+    // Other commands still run, warning about the same problems
+    let output = goto_bin().env("GOTO_DB", &db_dir).args(["-l"]).output().unwrap();
+    assert!(output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("config.toml:2: warning: unknown key 'fuzy_threshold'"), "{}", stderr);
+
+    // A type error stops loading, but --validate still points at the line
+    fs::write(&config, "[general]\nsubdir_depth = \"deep\"\n").unwrap();
+    let output = goto_bin().env("GOTO_DB", &db_dir).args(["--config", "--validate"]).output().unwrap();
+    assert_eq!(output.status.code(), Some(1));
+    assert!(String::from_utf8_lossy(&output.stdout).contains("config.toml:2: error: "));
+}
+
 // Tests for tag creation confirmation (TAG-01 through TAG-04)
 
 #[test]
@@ -2339,3 +2843,1702 @@ fn test_rename_tag_source_not_found() {
         stderr
     );
 }
+
+#[test]
+fn test_list_columns() {
+    let temp = tempdir().unwrap();
+    let db_dir = temp.path().join("db");
+    fs::create_dir(&db_dir).unwrap();
+    let proj = temp.path().join("projects");
+    fs::create_dir(&proj).unwrap();
+
+    let mut cmd = goto_bin();
+    cmd.env("GOTO_DB", &db_dir);
+    cmd.args(["-r", "proj", proj.to_str().unwrap()]);
+    assert!(cmd.output().unwrap().status.success());
+
+    // Only the requested columns are shown
+    let mut cmd = goto_bin();
+    cmd.env("GOTO_DB", &db_dir);
+    cmd.args(["-l", "--columns=name,count"]);
+    let output = cmd.output().unwrap();
+    assert!(output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("proj"), "Should list alias: {}", stdout);
+    assert!(stdout.contains("Uses"), "Should show count column: {}", stdout);
+    assert!(
+        !stdout.contains(proj.to_str().unwrap()),
+        "Path column should be hidden: {}",
+        stdout
+    );
+
+    // Unknown columns are rejected as invalid input
+    let mut cmd = goto_bin();
+    cmd.env("GOTO_DB", &db_dir);
+    cmd.args(["-l", "--columns=name,size"]);
+    let output = cmd.output().unwrap();
+    assert_eq!(output.status.code(), Some(3));
+}
+
+#[test]
+fn test_list_porcelain() {
+    let temp = tempdir().unwrap();
+    let db_dir = temp.path().join("db");
+    fs::create_dir(&db_dir).unwrap();
+    let proj = temp.path().join("projects");
+    fs::create_dir(&proj).unwrap();
+
+    let mut cmd = goto_bin();
+    cmd.env("GOTO_DB", &db_dir);
+    cmd.args(["-r", "proj", proj.to_str().unwrap(), "--tags=work", "--force"]);
+    assert!(cmd.output().unwrap().status.success());
+
+    let mut cmd = goto_bin();
+    cmd.env("GOTO_DB", &db_dir);
+    cmd.args(["--list", "--porcelain"]);
+    let output = cmd.output().unwrap();
+    assert!(output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let fields: Vec<&str> = stdout.trim_end_matches('\n').split('\t').collect();
+    let expected_path = fs::canonicalize(&proj).unwrap();
+    assert_eq!(
+        fields,
+        vec!["proj", expected_path.to_str().unwrap(), "work", "0", ""],
+        "Unexpected porcelain output: {:?}",
+        stdout
+    );
+}
+
+#[test]
+fn test_unicode_names_sort_and_resolve() {
+    let temp = tempdir().unwrap();
+    let db_dir = temp.path().join("db");
+    fs::create_dir(&db_dir).unwrap();
+    let dir = temp.path().join("dir");
+    fs::create_dir(&dir).unwrap();
+
+    for name in ["zoo", "École", "東京", "проект", "ecole", "Арбуз"] {
+        let output = goto_bin()
+            .env("GOTO_DB", &db_dir)
+            .args(["-r", name, dir.to_str().unwrap(), "--force"])
+            .output()
+            .unwrap();
+        assert!(output.status.success(), "{}: {}", name, String::from_utf8_lossy(&output.stderr));
+    }
+
+    let output = goto_bin().env("GOTO_DB", &db_dir).args(["-l", "--porcelain"]).output().unwrap();
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let names: Vec<&str> = stdout.lines().filter_map(|l| l.split('\t').next()).collect();
+    assert_eq!(names, vec!["ecole", "École", "zoo", "Арбуз", "проект", "東京"]);
+
+    // Upper case finds the alias without asking
+    let output = goto_bin().env("GOTO_DB", &db_dir).args(["ПРОЕКТ"]).output().unwrap();
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+    let expected = fs::canonicalize(&dir).unwrap();
+    assert_eq!(String::from_utf8_lossy(&output.stdout).trim(), expected.to_str().unwrap());
+}
+
+#[test]
+fn test_install_and_uninstall() {
+    let temp = tempdir().unwrap();
+    let home = temp.path().join("home");
+    fs::create_dir(&home).unwrap();
+    let db_dir = temp.path().join("db");
+    fs::create_dir(&db_dir).unwrap();
+    let bashrc = home.join(".bashrc");
+    fs::write(&bashrc, "export KEEP=1\n").unwrap();
+
+    let mut cmd = goto_bin();
+    cmd.env("HOME", &home).env("GOTO_DB", &db_dir);
+    cmd.args(["--install", "--shell=bash"]);
+    assert!(cmd.output().unwrap().status.success());
+
+    let wrapper = home.join(".config").join("goto").join("goto.bash");
+    assert!(wrapper.exists());
+    let content = fs::read_to_string(&bashrc).unwrap();
+    assert!(content.contains("# goto - directory navigation"));
+    assert!(content.contains("# end goto"));
+
+    let mut cmd = goto_bin();
+    cmd.env("HOME", &home).env("GOTO_DB", &db_dir);
+    cmd.args(["--uninstall", "--shell=bash"]);
+    let output = cmd.output().unwrap();
+    assert!(
+        output.status.success(),
+        "Uninstall failed: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+
+    assert!(!wrapper.exists(), "Wrapper should be deleted");
+    assert_eq!(fs::read_to_string(&bashrc).unwrap(), "export KEEP=1\n");
+    assert!(db_dir.exists(), "Database is kept without --purge");
+
+    // --purge never deletes a directory GOTO_DB points at
+    let mut cmd = goto_bin();
+    cmd.env("HOME", &home).env("GOTO_DB", &db_dir);
+    cmd.args(["--uninstall", "--shell=bash", "--purge", "--force"]);
+    let output = cmd.output().unwrap();
+    assert!(output.status.success());
+    assert!(db_dir.exists(), "A GOTO_DB directory must not be purged");
+    assert!(String::from_utf8_lossy(&output.stderr).contains("Keeping"));
+}
+
+/// `goto-bin` with HOME at `home` and the standard XDG locations below it
+fn goto_bin_at_home(home: &Path) -> Command {
+    let mut cmd = goto_bin();
+    cmd.env("HOME", home)
+        .env_remove("GOTO_DB")
+        .env_remove("GOTO_CONFIG")
+        .env_remove("XDG_CONFIG_HOME")
+        .env_remove("XDG_DATA_HOME");
+    cmd
+}
+
+#[test]
+fn test_uninstall_purge_requires_confirmation() {
+    let temp = tempdir().unwrap();
+    let home = temp.path().join("home");
+    let data_dir = home.join(".local").join("share").join("goto");
+    fs::create_dir_all(&data_dir).unwrap();
+
+    // Non-interactive stdin declines the confirmation
+    let output = goto_bin_at_home(&home).args(["--uninstall", "--purge"]).output().unwrap();
+    assert!(!output.status.success());
+    assert!(data_dir.exists(), "Database must not be purged without confirmation");
+
+    let output = goto_bin_at_home(&home).args(["--uninstall", "--purge", "--force"]).output().unwrap();
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+    assert!(!data_dir.exists(), "Database should be purged");
+}
+
+#[test]
+fn test_uninstall_purge_keeps_shared_config_directory() {
+    let temp = tempdir().unwrap();
+    let home = temp.path().join("home");
+    let data_dir = home.join(".local").join("share").join("goto");
+    let config_dir = home.join(".config").join("goto");
+    fs::create_dir_all(&data_dir).unwrap();
+    fs::create_dir_all(&config_dir).unwrap();
+    let dotfiles = home.join("dotfiles");
+    fs::create_dir(&dotfiles).unwrap();
+    fs::write(dotfiles.join("goto.toml"), "").unwrap();
+    fs::write(dotfiles.join("vimrc"), "set number\n").unwrap();
+
+    let run = |args: &[&str]| {
+        let mut cmd = goto_bin_at_home(&home);
+        cmd.env("GOTO_CONFIG", dotfiles.join("goto.toml")).args(args);
+        cmd.output().unwrap()
+    };
+
+    let output = run(&["--uninstall", "--purge", "--dry-run"]);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains(data_dir.to_str().unwrap()), "{}", stdout);
+    assert!(!stdout.contains(dotfiles.to_str().unwrap()), "{}", stdout);
+
+    let output = run(&["--uninstall", "--purge", "--force"]);
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+    assert!(!data_dir.exists());
+    assert!(!config_dir.exists());
+    assert!(dotfiles.join("vimrc").exists(), "The directory holding GOTO_CONFIG must survive");
+    assert!(dotfiles.join("goto.toml").exists());
+}
+
+#[test]
+fn test_per_session_stacks() {
+    let temp = tempdir().unwrap();
+    let db_dir = temp.path().join("db");
+    fs::create_dir(&db_dir).unwrap();
+    fs::write(db_dir.join("config.toml"), "[stack]\nper_session = true\n").unwrap();
+    let target = temp.path().join("target");
+    let start_a = temp.path().join("start_a");
+    let start_b = temp.path().join("start_b");
+    for dir in [&target, &start_a, &start_b] {
+        fs::create_dir(dir).unwrap();
+    }
+
+    let mut cmd = goto_bin();
+    cmd.env("GOTO_DB", &db_dir);
+    cmd.args(["-r", "target", target.to_str().unwrap()]);
+    assert!(cmd.output().unwrap().status.success());
+
+    // Two sessions push from different directories
+    for (session, start) in [("a", &start_a), ("b", &start_b)] {
+        let mut cmd = goto_bin();
+        cmd.env("GOTO_DB", &db_dir).env("GOTO_SESSION", session);
+        cmd.current_dir(start).args(["-p", "target"]);
+        assert!(cmd.output().unwrap().status.success());
+    }
+
+    // Popping in session "a" returns to session a's directory
+    let mut cmd = goto_bin();
+    cmd.env("GOTO_DB", &db_dir).env("GOTO_SESSION", "a");
+    cmd.arg("-o");
+    let output = cmd.output().unwrap();
+    assert!(output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert_eq!(
+        stdout.trim(),
+        fs::canonicalize(&start_a).unwrap().to_str().unwrap()
+    );
+
+    // Session "b" still has its entry
+    let mut cmd = goto_bin();
+    cmd.env("GOTO_DB", &db_dir).env("GOTO_SESSION", "a");
+    cmd.args(["--stack", "--all-sessions"]);
+    let output = cmd.output().unwrap();
+    assert!(output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("Session b:"), "Missing session b: {}", stdout);
+    assert!(!stdout.contains("Session a"), "Session a is empty: {}", stdout);
+}
+
+#[test]
+fn test_previous_directory() {
+    let temp = tempdir().unwrap();
+    let db_dir = temp.path().join("db");
+    fs::create_dir(&db_dir).unwrap();
+    let start = temp.path().join("start");
+    let target = temp.path().join("target");
+    fs::create_dir(&start).unwrap();
+    fs::create_dir(&target).unwrap();
+    let start = fs::canonicalize(&start).unwrap();
+    let target = fs::canonicalize(&target).unwrap();
+
+    let mut cmd = goto_bin();
+    cmd.env("GOTO_DB", &db_dir);
+    cmd.args(["-r", "target", target.to_str().unwrap()]);
+    assert!(cmd.output().unwrap().status.success());
+
+    // No navigation yet: nothing to go back to
+    let mut cmd = goto_bin();
+    cmd.env("GOTO_DB", &db_dir).env_remove("OLDPWD").env_remove("GOTO_OLDPWD");
+    cmd.arg("-");
+    assert_eq!(cmd.output().unwrap().status.code(), Some(1));
+
+    // Navigate from start to target
+    let mut cmd = goto_bin();
+    cmd.env("GOTO_DB", &db_dir).current_dir(&start);
+    cmd.arg("target");
+    assert!(cmd.output().unwrap().status.success());
+
+    // From target, `goto -` returns to start
+    let mut cmd = goto_bin();
+    cmd.env("GOTO_DB", &db_dir).env_remove("OLDPWD").env_remove("GOTO_OLDPWD");
+    cmd.current_dir(&target).arg("-");
+    let output = cmd.output().unwrap();
+    assert!(output.status.success());
+    assert_eq!(String::from_utf8_lossy(&output.stdout).trim(), start.to_str().unwrap());
+
+    // And again toggles back to target
+    let mut cmd = goto_bin();
+    cmd.env("GOTO_DB", &db_dir).env_remove("OLDPWD").env_remove("GOTO_OLDPWD");
+    cmd.current_dir(&start).arg("-");
+    let output = cmd.output().unwrap();
+    assert_eq!(String::from_utf8_lossy(&output.stdout).trim(), target.to_str().unwrap());
+
+    // The shell's previous directory takes priority
+    let mut cmd = goto_bin();
+    cmd.env("GOTO_DB", &db_dir).env("GOTO_OLDPWD", &target);
+    cmd.arg("-");
+    let output = cmd.output().unwrap();
+    assert_eq!(String::from_utf8_lossy(&output.stdout).trim(), target.to_str().unwrap());
+}
+
+#[test]
+fn test_tag_all_with_filter() {
+    let temp = tempdir().unwrap();
+    let db_dir = temp.path().join("db");
+    fs::create_dir(&db_dir).unwrap();
+
+    for name in ["proj-api", "proj-web", "notes"] {
+        let dir = temp.path().join(name);
+        fs::create_dir(&dir).unwrap();
+        let mut cmd = goto_bin();
+        cmd.env("GOTO_DB", &db_dir);
+        cmd.args(["-r", name, dir.to_str().unwrap()]);
+        assert!(cmd.output().unwrap().status.success());
+    }
+
+    // Without --force the confirmation declines on non-interactive stdin
+    let mut cmd = goto_bin();
+    cmd.env("GOTO_DB", &db_dir);
+    cmd.args(["--tag-all", "work", "--filter=proj-*"]);
+    let output = cmd.output().unwrap();
+    assert_eq!(output.status.code(), Some(1));
+
+    let mut cmd = goto_bin();
+    cmd.env("GOTO_DB", &db_dir);
+    cmd.args(["--tag-all", "work", "--filter=proj-*", "--force"]);
+    assert!(cmd.output().unwrap().status.success());
+
+    let mut cmd = goto_bin();
+    cmd.env("GOTO_DB", &db_dir);
+    cmd.args(["-l", "--porcelain", "--filter=work"]);
+    let output = cmd.output().unwrap();
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let names: Vec<&str> = stdout.lines().map(|l| l.split('\t').next().unwrap()).collect();
+    assert_eq!(names, vec!["proj-api", "proj-web"]);
+
+    let mut cmd = goto_bin();
+    cmd.env("GOTO_DB", &db_dir);
+    cmd.args(["--tag-all", "work", "--filter=tag:", "--force"]);
+    assert_eq!(cmd.output().unwrap().status.code(), Some(3));
+}
+
+#[test]
+fn test_dry_run_saves_nothing() {
+    let temp = tempdir().unwrap();
+    let db_dir = temp.path().join("db");
+    fs::create_dir(&db_dir).unwrap();
+    let dir = temp.path().join("api");
+    fs::create_dir(&dir).unwrap();
+
+    let mut cmd = goto_bin();
+    cmd.env("GOTO_DB", &db_dir);
+    cmd.args(["-r", "api", dir.to_str().unwrap(), "--dry-run"]);
+    let output = cmd.output().unwrap();
+    assert!(output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("Would add 1 alias (dry-run):"));
+    assert!(stdout.contains("api"));
+    assert!(!db_dir.join("aliases.toml").exists());
+
+    let mut cmd = goto_bin();
+    cmd.env("GOTO_DB", &db_dir);
+    cmd.args(["-r", "api", dir.to_str().unwrap()]);
+    assert!(cmd.output().unwrap().status.success());
+
+    let mut cmd = goto_bin();
+    cmd.env("GOTO_DB", &db_dir);
+    cmd.args(["-u", "api", "--dry-run"]);
+    let output = cmd.output().unwrap();
+    assert!(String::from_utf8_lossy(&output.stdout).contains("Would remove 1 alias"));
+
+    let mut cmd = goto_bin();
+    cmd.env("GOTO_DB", &db_dir);
+    cmd.args(["-x", "api"]);
+    assert!(cmd.output().unwrap().status.success());
+
+    let mut cmd = goto_bin();
+    cmd.env("GOTO_DB", &db_dir);
+    cmd.args(["--rename", "api", "web", "--dry-run"]);
+    assert_eq!(cmd.output().unwrap().status.code(), Some(1));
+}
+
+#[test]
+fn test_counter_counts_session_navigations() {
+    let temp = tempdir().unwrap();
+    let db_dir = temp.path().join("db");
+    fs::create_dir(&db_dir).unwrap();
+
+    let mut cmd = goto_bin();
+    cmd.env("GOTO_DB", &db_dir);
+    cmd.args(["-r", "tmp", temp.path().to_str().unwrap()]);
+    assert!(cmd.output().unwrap().status.success());
+
+    let counter = |session: &str| {
+        let mut cmd = goto_bin();
+        cmd.env("GOTO_DB", &db_dir).env("GOTO_SESSION", session).arg("--counter");
+        String::from_utf8_lossy(&cmd.output().unwrap().stdout).trim().to_string()
+    };
+    assert_eq!(counter("one"), "0");
+    for _ in 0..2 {
+        let mut cmd = goto_bin();
+        cmd.env("GOTO_DB", &db_dir).env("GOTO_SESSION", "one").arg("tmp");
+        assert!(cmd.output().unwrap().status.success());
+    }
+    assert_eq!(counter("one"), "2");
+    assert_eq!(counter("two"), "0");
+}
+
+#[test]
+fn test_tag_info() {
+    let temp = tempdir().unwrap();
+    let db_dir = temp.path().join("db");
+    fs::create_dir(&db_dir).unwrap();
+    let dir = temp.path().join("api");
+    fs::create_dir(&dir).unwrap();
+
+    let mut cmd = goto_bin();
+    cmd.env("GOTO_DB", &db_dir);
+    cmd.args(["-r", "api", dir.to_str().unwrap(), "-t", "work"]);
+    assert!(cmd.output().unwrap().status.success());
+
+    let mut cmd = goto_bin();
+    cmd.env("GOTO_DB", &db_dir);
+    cmd.args(["--tag-info", "work", "--description=Day job", "--color=blue"]);
+    assert!(cmd.output().unwrap().status.success());
+    let saved = fs::read_to_string(db_dir.join("aliases.toml")).unwrap();
+    assert!(saved.contains("[tags.work]\ndescription = \"Day job\"\ncolor = \"blue\""));
+
+    let mut cmd = goto_bin();
+    cmd.env("GOTO_DB", &db_dir);
+    cmd.args(["--tag-info", "work"]);
+    let output = cmd.output().unwrap();
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("Description: Day job"));
+    assert!(stdout.contains("api"));
+
+    let mut cmd = goto_bin();
+    cmd.env("GOTO_DB", &db_dir);
+    cmd.arg("--tags");
+    assert!(String::from_utf8_lossy(&cmd.output().unwrap().stdout).contains("Day job"));
+
+    let mut cmd = goto_bin();
+    cmd.env("GOTO_DB", &db_dir);
+    cmd.args(["--tag-info", "nope"]);
+    assert_eq!(cmd.output().unwrap().status.code(), Some(1));
+}
+
+#[test]
+fn test_filter_grammar_is_shared() {
+    let temp = tempdir().unwrap();
+    let test_dir = temp.path().join("api");
+    fs::create_dir(&test_dir).unwrap();
+    let db_dir = temp.path().join("db");
+    fs::create_dir(&db_dir).unwrap();
+
+    let mut cmd = goto_bin();
+    cmd.env("GOTO_DB", &db_dir);
+    cmd.args(["-r", "api", test_dir.to_str().unwrap(), "--tags=work"]);
+    assert!(cmd.output().unwrap().status.success());
+
+    // A bare word finds the tag for -l and --export alike
+    for args in [["-l", "--filter=work"], ["--export", "--filter=work"]] {
+        let output = goto_bin().env("GOTO_DB", &db_dir).args(args).output().unwrap();
+        assert!(output.status.success());
+        assert!(String::from_utf8_lossy(&output.stdout).contains("api"), "{:?}", args);
+    }
+}
+
+#[test]
+fn test_export_shell_aliases() {
+    let temp = tempdir().unwrap();
+    let test_dir = temp.path().join("test dir");
+    fs::create_dir(&test_dir).unwrap();
+    let db_dir = temp.path().join("db");
+    fs::create_dir(&db_dir).unwrap();
+
+    let mut cmd = goto_bin();
+    cmd.env("GOTO_DB", &db_dir);
+    cmd.args(["-r", "test", test_dir.to_str().unwrap()]);
+    assert!(cmd.output().unwrap().status.success());
+
+    let output = goto_bin()
+        .env("GOTO_DB", &db_dir)
+        .args(["--export-shell-aliases", "--shell=bash"])
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    let script = String::from_utf8_lossy(&output.stdout).into_owned();
+    assert!(script.contains("alias test="));
+
+    // The alias enters the directory in a real shell
+    let output = Command::new("bash")
+        .arg("-c")
+        .arg(format!("shopt -s expand_aliases\n{}\ntest\npwd", script))
+        .output()
+        .unwrap();
+    let pwd = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    assert_eq!(std::path::Path::new(&pwd).canonicalize().unwrap(), test_dir.canonicalize().unwrap());
+
+    let output = goto_bin()
+        .env("GOTO_DB", &db_dir)
+        .args(["--export-shell-aliases", "--shell=nu"])
+        .output()
+        .unwrap();
+    assert!(!output.status.success());
+}
+
+#[test]
+fn test_diff() {
+    let temp = tempdir().unwrap();
+    let test_dir = temp.path().join("project");
+    fs::create_dir(&test_dir).unwrap();
+    let db_dir = temp.path().join("db");
+    fs::create_dir(&db_dir).unwrap();
+
+    let mut cmd = goto_bin();
+    cmd.env("GOTO_DB", &db_dir);
+    cmd.args(["-r", "proj", test_dir.to_str().unwrap()]);
+    assert!(cmd.output().unwrap().status.success());
+
+    let export = temp.path().join("mine.toml");
+    let output = goto_bin()
+        .env("GOTO_DB", &db_dir)
+        .args(["--export", &format!("--output={}", export.display())])
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+
+    let output = goto_bin().env("GOTO_DB", &db_dir).args(["--diff", export.to_str().unwrap()]).output().unwrap();
+    assert!(output.status.success());
+    assert_eq!(String::from_utf8_lossy(&output.stdout).trim(), "No differences");
+
+    // A teammate's version retags proj and adds an alias
+    let theirs = temp.path().join("theirs.toml");
+    fs::write(
+        &theirs,
+        format!(
+            "[[aliases]]\nname = \"proj\"\npath = \"{0}\"\ntags = [\"team\"]\n\n\
+             [[aliases]]\nname = \"docs\"\npath = \"{0}/docs\"\n",
+            test_dir.display()
+        ),
+    )
+    .unwrap();
+    let output = goto_bin()
+        .env("GOTO_DB", &db_dir)
+        .args(["--diff", export.to_str().unwrap(), theirs.to_str().unwrap()])
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.starts_with("1 added, 1 changed"), "{}", stdout);
+    assert!(stdout.contains("[team]"), "{}", stdout);
+}
+
+#[test]
+fn test_report() {
+    let temp = tempdir().unwrap();
+    let test_dir = temp.path().join("project");
+    fs::create_dir(&test_dir).unwrap();
+    let db_dir = temp.path().join("db");
+    fs::create_dir(&db_dir).unwrap();
+
+    let mut cmd = goto_bin();
+    cmd.env("GOTO_DB", &db_dir);
+    cmd.args(["-r", "proj", test_dir.to_str().unwrap(), "--tags=work"]);
+    assert!(cmd.output().unwrap().status.success());
+
+    let report = temp.path().join("goto.html");
+    let output = goto_bin()
+        .env("GOTO_DB", &db_dir)
+        .args(["--report", &format!("--output={}", report.display())])
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    let html = fs::read_to_string(&report).unwrap();
+    assert!(html.starts_with("<!DOCTYPE html>"));
+    assert!(html.contains("<td>proj</td>"));
+    assert!(html.contains(r#"data-tag="work""#));
+
+    // Nothing matches: no file is written
+    let empty = temp.path().join("empty.html");
+    let output = goto_bin()
+        .env("GOTO_DB", &db_dir)
+        .args(["--report", "--filter=tag:none", &format!("--output={}", empty.display())])
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    assert!(!empty.exists());
+}
+
+#[test]
+fn test_time_tracking_events() {
+    let temp = tempdir().unwrap();
+    let work = temp.path().join("work");
+    fs::create_dir(&work).unwrap();
+    fs::write(temp.path().join("config.toml"), "[privacy]\ntrack_time = true\n").unwrap();
+
+    let mut cmd = goto_bin();
+    cmd.env("GOTO_DB", temp.path());
+    cmd.args(["-r", "work", work.to_str().unwrap()]);
+    assert!(cmd.output().unwrap().status.success());
+
+    // Navigating records entering and tells the wrapper what to watch
+    let output = goto_bin()
+        .env("GOTO_DB", temp.path())
+        .env("GOTO_SESSION", "s1")
+        .env("GOTO_SHELL", "bash")
+        .arg("work")
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    assert!(String::from_utf8_lossy(&output.stdout).contains("export GOTO_TRACK_DIR="));
+
+    let output = goto_bin()
+        .env("GOTO_DB", temp.path())
+        .env("GOTO_SESSION", "s1")
+        .current_dir(temp.path())
+        .args(["--event", "leave"])
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+
+    let log = fs::read_to_string(temp.path().join("time_events.log")).unwrap();
+    let lines: Vec<_> = log.lines().collect();
+    assert_eq!(lines.len(), 2);
+    assert!(lines[0].contains("\ts1\tenter\twork\t"));
+    assert!(lines[1].contains("\ts1\tleave\t"));
+
+    let output = goto_bin().env("GOTO_DB", temp.path()).arg("--stats").output().unwrap();
+    assert!(String::from_utf8_lossy(&output.stdout).contains("Time spent this week:"));
+}
+
+#[test]
+fn test_ssh_import_and_print() {
+    use std::io::Write;
+    use std::process::Stdio;
+
+    let temp = tempdir().unwrap();
+    let mut child = goto_bin()
+        .env("GOTO_DB", temp.path())
+        .args(["--ssh-import", "box", "-"])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .spawn()
+        .unwrap();
+    child
+        .stdin
+        .take()
+        .unwrap()
+        .write_all(b"[[aliases]]\nname = \"api\"\npath = \"/srv/my api\"\n")
+        .unwrap();
+    let output = child.wait_with_output().unwrap();
+    assert!(output.status.success());
+    assert!(String::from_utf8_lossy(&output.stdout).contains("Stored 1 aliases for host 'box'"));
+
+    let output = goto_bin()
+        .env("GOTO_DB", temp.path())
+        .args(["--ssh", "box", "api", "--print"])
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    assert_eq!(
+        String::from_utf8_lossy(&output.stdout).trim(),
+        r#"ssh -t box 'cd '\''/srv/my api'\'' && exec "$SHELL"'"#
+    );
+
+    let output = goto_bin()
+        .env("GOTO_DB", temp.path())
+        .args(["--ssh", "other", "api", "--print"])
+        .output()
+        .unwrap();
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("no aliases for host 'other'"));
+}
+
+#[test]
+fn test_export_to_file_and_import_from_stdin() {
+    use std::io::Write;
+    use std::process::Stdio;
+
+    let temp = tempdir().unwrap();
+    let test_dir = temp.path().join("testdir");
+    fs::create_dir(&test_dir).unwrap();
+    let db_dir = temp.path().join("db");
+    fs::create_dir(&db_dir).unwrap();
+
+    let mut cmd = goto_bin();
+    cmd.env("GOTO_DB", &db_dir);
+    cmd.args(["-r", "test", test_dir.to_str().unwrap()]);
+    assert!(cmd.output().unwrap().status.success());
+
+    let export_file = temp.path().join("backup.toml");
+    let mut cmd = goto_bin();
+    cmd.env("GOTO_DB", &db_dir);
+    cmd.args(["--export", &format!("--output={}", export_file.display())]);
+    let output = cmd.output().unwrap();
+    assert!(output.status.success());
+    assert!(output.stdout.is_empty(), "Export to file should not write stdout");
+    let content = fs::read_to_string(&export_file).unwrap();
+    assert!(content.contains("test"));
+
+    // Pipe the export into a fresh database
+    let db_dir2 = temp.path().join("db2");
+    fs::create_dir(&db_dir2).unwrap();
+    let mut child = goto_bin()
+        .env("GOTO_DB", &db_dir2)
+        .args(["--import", "-"])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .spawn()
+        .unwrap();
+    child.stdin.take().unwrap().write_all(content.as_bytes()).unwrap();
+    let output = child.wait_with_output().unwrap();
+    assert!(output.status.success());
+    assert!(String::from_utf8_lossy(&output.stdout).contains("1 imported"));
+
+    let mut cmd = goto_bin();
+    cmd.env("GOTO_DB", &db_dir2);
+    cmd.args(["-x", "test"]);
+    assert!(cmd.output().unwrap().status.success());
+}
+
+#[test]
+fn test_register_overlapping_paths_needs_force() {
+    let temp = tempdir().unwrap();
+    let project = temp.path().join("project");
+    let nested = project.join("src");
+    fs::create_dir_all(&nested).unwrap();
+    let db_dir = temp.path().join("db");
+
+    let output = goto_bin()
+        .env("GOTO_DB", &db_dir)
+        .args(["-r", "project", project.to_str().unwrap()])
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+
+    // Declined without a terminal
+    let output = goto_bin()
+        .env("GOTO_DB", &db_dir)
+        .args(["-r", "src", nested.to_str().unwrap()])
+        .output()
+        .unwrap();
+    assert_eq!(output.status.code(), Some(1));
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("is inside 'project'"), "{}", stderr);
+
+    let output = goto_bin()
+        .env("GOTO_DB", &db_dir)
+        .args(["-r", "src", nested.to_str().unwrap(), "--force"])
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+
+    let output = goto_bin()
+        .env("GOTO_DB", &db_dir)
+        .arg("--duplicates")
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("nested"), "{}", stdout);
+    assert!(stdout.contains("project") && stdout.contains("src"), "{}", stdout);
+}
+
+#[test]
+fn test_backup_and_restore() {
+    let temp = tempdir().unwrap();
+    let test_dir = temp.path().join("testdir");
+    fs::create_dir(&test_dir).unwrap();
+    let db_dir = temp.path().join("db");
+    fs::create_dir(&db_dir).unwrap();
+
+    for name in ["one", "two"] {
+        let mut cmd = goto_bin();
+        cmd.env("GOTO_DB", &db_dir);
+        cmd.args(["-r", name, test_dir.to_str().unwrap(), "--force"]);
+        assert!(cmd.output().unwrap().status.success());
+    }
+
+    let mut cmd = goto_bin();
+    cmd.env("GOTO_DB", &db_dir);
+    cmd.args(["-u", "two"]);
+    assert!(cmd.output().unwrap().status.success());
+
+    let mut cmd = goto_bin();
+    cmd.env("GOTO_DB", &db_dir);
+    cmd.arg("--backups");
+    let output = cmd.output().unwrap();
+    assert!(output.status.success());
+    assert!(String::from_utf8_lossy(&output.stdout).contains("Timestamp"));
+
+    // Without --force the confirmation declines on non-interactive stdin
+    let mut cmd = goto_bin();
+    cmd.env("GOTO_DB", &db_dir);
+    cmd.args(["--restore", "latest"]);
+    assert_eq!(cmd.output().unwrap().status.code(), Some(1));
+
+    let mut cmd = goto_bin();
+    cmd.env("GOTO_DB", &db_dir);
+    cmd.args(["--restore", "latest", "--force"]);
+    let output = cmd.output().unwrap();
+    assert!(
+        output.status.success(),
+        "Restore failed: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+
+    let mut cmd = goto_bin();
+    cmd.env("GOTO_DB", &db_dir);
+    cmd.args(["-x", "two"]);
+    assert!(cmd.output().unwrap().status.success());
+}
+
+#[test]
+fn test_check_reports_problems() {
+    let temp = tempdir().unwrap();
+    let test_dir = temp.path().join("testdir");
+    fs::create_dir(&test_dir).unwrap();
+    let db_dir = temp.path().join("db");
+    fs::create_dir(&db_dir).unwrap();
+
+    let mut cmd = goto_bin();
+    cmd.env("GOTO_DB", &db_dir);
+    cmd.args(["-r", "proj", test_dir.to_str().unwrap()]);
+    assert!(cmd.output().unwrap().status.success());
+
+    let mut cmd = goto_bin();
+    cmd.env("GOTO_DB", &db_dir);
+    cmd.arg("--check");
+    let output = cmd.output().unwrap();
+    assert!(output.status.success());
+    assert!(String::from_utf8_lossy(&output.stdout).contains("look good"));
+
+    fs::remove_dir(&test_dir).unwrap();
+
+    let mut cmd = goto_bin();
+    cmd.env("GOTO_DB", &db_dir);
+    cmd.arg("--check");
+    let output = cmd.output().unwrap();
+    assert_eq!(output.status.code(), Some(1));
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("proj"), "Expected alias in report: {}", stdout);
+    assert!(stdout.contains("does not exist"));
+}
+
+#[test]
+fn test_alias_env_exports() {
+    let temp = tempdir().unwrap();
+    let test_dir = temp.path().join("testdir");
+    fs::create_dir(&test_dir).unwrap();
+    let db_dir = temp.path().join("db");
+    fs::create_dir(&db_dir).unwrap();
+
+    let mut cmd = goto_bin();
+    cmd.env("GOTO_DB", &db_dir);
+    cmd.args(["-r", "proj", test_dir.to_str().unwrap()]);
+    assert!(cmd.output().unwrap().status.success());
+
+    let mut cmd = goto_bin();
+    cmd.env("GOTO_DB", &db_dir);
+    cmd.args(["--env", "proj", "RUST_LOG=debug"]);
+    assert!(cmd.output().unwrap().status.success());
+
+    let mut cmd = goto_bin();
+    cmd.env("GOTO_DB", &db_dir);
+    cmd.args(["--env", "proj", "1BAD=x"]);
+    assert_eq!(cmd.output().unwrap().status.code(), Some(3));
+
+    // Without the shell wrapper only the path is printed
+    let expected_path = fs::canonicalize(&test_dir).unwrap();
+    let mut cmd = goto_bin();
+    cmd.env("GOTO_DB", &db_dir).env_remove("GOTO_SHELL");
+    cmd.arg("proj");
+    let output = cmd.output().unwrap();
+    assert_eq!(
+        String::from_utf8_lossy(&output.stdout).trim(),
+        expected_path.to_str().unwrap()
+    );
+
+    let mut cmd = goto_bin();
+    cmd.env("GOTO_DB", &db_dir).env("GOTO_SHELL", "bash");
+    cmd.arg("proj");
+    let output = cmd.output().unwrap();
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let lines: Vec<&str> = stdout.lines().collect();
+    assert_eq!(lines, vec![expected_path.to_str().unwrap(), "export RUST_LOG='debug'"]);
+}
+
+#[test]
+fn test_where_reports_containing_alias() {
+    let temp = tempdir().unwrap();
+    let proj = temp.path().join("proj");
+    let sub = proj.join("src");
+    fs::create_dir_all(&sub).unwrap();
+    let db_dir = temp.path().join("db");
+    fs::create_dir(&db_dir).unwrap();
+
+    let mut cmd = goto_bin();
+    cmd.env("GOTO_DB", &db_dir);
+    cmd.args(["-r", "proj", proj.to_str().unwrap()]);
+    assert!(cmd.output().unwrap().status.success());
+
+    let mut cmd = goto_bin();
+    cmd.env("GOTO_DB", &db_dir).current_dir(&sub);
+    cmd.arg("--where");
+    let output = cmd.output().unwrap();
+    assert!(output.status.success());
+    assert_eq!(String::from_utf8_lossy(&output.stdout).trim(), "proj/src");
+
+    let mut cmd = goto_bin();
+    cmd.env("GOTO_DB", &db_dir).current_dir(temp.path());
+    cmd.arg("--where");
+    assert_eq!(cmd.output().unwrap().status.code(), Some(1));
+}
+
+#[test]
+fn test_preview_shows_branch_and_entries() {
+    let temp = tempdir().unwrap();
+    let proj = temp.path().join("proj");
+    fs::create_dir_all(proj.join(".git")).unwrap();
+    fs::create_dir(proj.join("src")).unwrap();
+    fs::write(proj.join(".git").join("HEAD"), "ref: refs/heads/main\n").unwrap();
+    fs::write(proj.join("Cargo.toml"), "").unwrap();
+    let db_dir = temp.path().join("db");
+    fs::create_dir(&db_dir).unwrap();
+
+    let mut cmd = goto_bin();
+    cmd.env("GOTO_DB", &db_dir);
+    cmd.args(["-r", "proj", proj.to_str().unwrap()]);
+    assert!(cmd.output().unwrap().status.success());
+
+    let mut cmd = goto_bin();
+    cmd.env("GOTO_DB", &db_dir);
+    cmd.args(["--preview", "proj"]);
+    let output = cmd.output().unwrap();
+    assert!(output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("git: main"));
+    assert!(stdout.contains("  Cargo.toml"));
+    assert!(stdout.contains("  src/"));
+    assert!(!stdout.contains(".git"));
+
+    let mut cmd = goto_bin();
+    cmd.env("GOTO_DB", &db_dir);
+    cmd.args(["--preview", "missing"]);
+    assert_eq!(cmd.output().unwrap().status.code(), Some(1));
+}
+
+#[cfg(unix)]
+#[test]
+fn test_navigate_permission_denied_exit_code() {
+    use std::os::unix::fs::PermissionsExt;
+
+    let temp = tempdir().unwrap();
+    let locked = temp.path().join("locked");
+    fs::create_dir(&locked).unwrap();
+    let db_dir = temp.path().join("db");
+    fs::create_dir(&db_dir).unwrap();
+
+    let mut cmd = goto_bin();
+    cmd.env("GOTO_DB", &db_dir);
+    cmd.args(["-r", "locked", locked.to_str().unwrap()]);
+    assert!(cmd.output().unwrap().status.success());
+
+    fs::set_permissions(&locked, fs::Permissions::from_mode(0o000)).unwrap();
+    // Privileged users (e.g. root in CI containers) bypass permission checks
+    let enforced = fs::metadata(locked.join(".")).is_err();
+
+    let mut cmd = goto_bin();
+    cmd.env("GOTO_DB", &db_dir);
+    cmd.arg("locked");
+    let output = cmd.output().unwrap();
+    fs::set_permissions(&locked, fs::Permissions::from_mode(0o755)).unwrap();
+
+    if enforced {
+        assert_eq!(output.status.code(), Some(6));
+        assert!(String::from_utf8_lossy(&output.stderr).contains("permission denied"));
+    } else {
+        assert!(output.status.success());
+    }
+}
+
+#[test]
+fn test_due_update_check_is_claimed_without_blocking() {
+    let temp = tempdir().unwrap();
+    let proj = temp.path().join("proj");
+    fs::create_dir(&proj).unwrap();
+    let db_dir = temp.path().join("db");
+    fs::create_dir(&db_dir).unwrap();
+    let cache_path = db_dir.join("update_cache.json");
+    fs::write(
+        &cache_path,
+        r#"{"last_check":"2000-01-01T00:00:00Z","latest_version":null,"download_url":null,"checksum":null}"#,
+    )
+    .unwrap();
+
+    let mut cmd = goto_bin();
+    cmd.env("GOTO_DB", &db_dir);
+    cmd.args(["-r", "proj", proj.to_str().unwrap()]);
+    assert!(cmd.output().unwrap().status.success());
+
+    let mut cmd = goto_bin();
+    cmd.env("GOTO_DB", &db_dir);
+    cmd.arg("proj");
+    let output = cmd.output().unwrap();
+    assert!(output.status.success());
+    assert_eq!(String::from_utf8_lossy(&output.stdout).trim(), proj.to_str().unwrap());
+
+    // The check is handed to a background process and marked as started
+    let cache = fs::read_to_string(&cache_path).unwrap();
+    assert!(!cache.contains("2000-01-01"));
+}
+
+#[test]
+fn test_dynamic_rule_clones_missing_target() {
+    let temp = tempdir().unwrap();
+    let db_dir = temp.path().join("db");
+    fs::create_dir(&db_dir).unwrap();
+
+    // A local repository stands in for the remote
+    let upstream = temp.path().join("upstream").join("tool");
+    fs::create_dir_all(&upstream).unwrap();
+    let git = |dir: &std::path::Path, args: &[&str]| {
+        Command::new("git")
+            .current_dir(dir)
+            .args(["-c", "user.name=t", "-c", "user.email=t@example.com"])
+            .args(args)
+            .output()
+            .map(|o| o.status.success())
+            .unwrap_or(false)
+    };
+    if !git(&upstream, &["init", "-q"]) {
+        return; // git unavailable
+    }
+    fs::write(upstream.join("README"), "hi").unwrap();
+    assert!(git(&upstream, &["add", "README"]));
+    assert!(git(&upstream, &["commit", "-q", "-m", "init"]));
+
+    let src = temp.path().join("src");
+    fs::write(
+        db_dir.join("config.toml"),
+        format!(
+            "[[rules]]\npattern = \"local:(.+)\"\npath = \"{}/$1\"\nclone = \"{}/$1\"\n",
+            src.display(),
+            temp.path().join("upstream").display()
+        ),
+    )
+    .unwrap();
+
+    let mut cmd = goto_bin();
+    cmd.env("GOTO_DB", &db_dir);
+    cmd.arg("local:tool");
+    let output = cmd.output().unwrap();
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+    let target = src.join("tool");
+    assert_eq!(String::from_utf8_lossy(&output.stdout).trim(), target.to_str().unwrap());
+    assert!(target.join("README").exists());
+
+    // Captures may not climb out of the rule's directory
+    let mut cmd = goto_bin();
+    cmd.env("GOTO_DB", &db_dir);
+    cmd.arg("local:../escape");
+    assert_eq!(cmd.output().unwrap().status.code(), Some(3));
+}
+
+#[test]
+fn test_clone_registers_and_prints_path() {
+    let temp = tempdir().unwrap();
+    let db_dir = temp.path().join("db");
+    fs::create_dir(&db_dir).unwrap();
+
+    let upstream = temp.path().join("upstream").join("acme").join("widget");
+    fs::create_dir_all(&upstream).unwrap();
+    let git = |dir: &std::path::Path, args: &[&str]| {
+        Command::new("git")
+            .current_dir(dir)
+            .args(["-c", "user.name=t", "-c", "user.email=t@example.com"])
+            .args(args)
+            .output()
+            .map(|o| o.status.success())
+            .unwrap_or(false)
+    };
+    if !git(&upstream, &["init", "-q"]) {
+        return; // git unavailable
+    }
+    fs::write(upstream.join("README"), "hi").unwrap();
+    assert!(git(&upstream, &["add", "README"]));
+    assert!(git(&upstream, &["commit", "-q", "-m", "init"]));
+
+    let root = temp.path().join("src");
+    fs::write(
+        db_dir.join("config.toml"),
+        format!("[projects]\nroot = \"{}\"\n", root.display()),
+    )
+    .unwrap();
+
+    let url = format!("file://{}", upstream.display());
+    let mut cmd = goto_bin();
+    cmd.env("GOTO_DB", &db_dir);
+    cmd.args(["--clone", &url]);
+    let output = cmd.output().unwrap();
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let path = std::path::PathBuf::from(stdout.trim());
+    assert!(path.ends_with("upstream/acme/widget"));
+    assert!(path.starts_with(fs::canonicalize(&root).unwrap().join("local")));
+    assert!(path.join("README").exists());
+
+    // Registered under the repository name, tagged with the owner
+    let mut cmd = goto_bin();
+    cmd.env("GOTO_DB", &db_dir);
+    cmd.args(["-l", "--filter=acme"]);
+    let output = cmd.output().unwrap();
+    assert!(String::from_utf8_lossy(&output.stdout).contains("widget"));
+
+    // Cloning again with the same alias is refused
+    let mut cmd = goto_bin();
+    cmd.env("GOTO_DB", &db_dir);
+    cmd.args(["--clone", &url]);
+    assert_eq!(cmd.output().unwrap().status.code(), Some(4));
+}
+
+#[test]
+fn test_new_from_template() {
+    let temp = tempdir().unwrap();
+    let db_dir = temp.path().join("db");
+    fs::create_dir(&db_dir).unwrap();
+
+    let skeleton = temp.path().join("skel");
+    fs::create_dir(&skeleton).unwrap();
+    fs::write(skeleton.join("Makefile"), "all:\n").unwrap();
+    fs::write(
+        db_dir.join("config.toml"),
+        format!("[templates.c]\nskeleton = \"{}\"\ntags = [\"c\", \"native\"]\n", skeleton.display()),
+    )
+    .unwrap();
+
+    let target = temp.path().join("projects").join("hello");
+    let mut cmd = goto_bin();
+    cmd.env("GOTO_DB", &db_dir);
+    cmd.args(["--new", "hello", target.to_str().unwrap(), "--template=c"]);
+    let output = cmd.output().unwrap();
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+    assert_eq!(String::from_utf8_lossy(&output.stdout).trim(), target.to_str().unwrap());
+    assert!(target.join("Makefile").exists());
+
+    let mut cmd = goto_bin();
+    cmd.env("GOTO_DB", &db_dir);
+    cmd.args(["-l", "--filter=native"]);
+    let output = cmd.output().unwrap();
+    assert!(String::from_utf8_lossy(&output.stdout).contains("hello"));
+
+    // Unknown templates are refused before anything is created
+    let mut cmd = goto_bin();
+    cmd.env("GOTO_DB", &db_dir);
+    cmd.args(["--new", "other", temp.path().join("other").to_str().unwrap(), "--template=go"]);
+    assert_eq!(cmd.output().unwrap().status.code(), Some(1));
+    assert!(!temp.path().join("other").exists());
+}
+
+#[cfg(unix)]
+#[test]
+fn test_pick_with_fzf_records_usage() {
+    use std::os::unix::fs::PermissionsExt;
+
+    let temp = tempdir().unwrap();
+    let db_dir = temp.path().join("db");
+    fs::create_dir(&db_dir).unwrap();
+    let api = temp.path().join("api");
+    let web = temp.path().join("web");
+    fs::create_dir(&api).unwrap();
+    fs::create_dir(&web).unwrap();
+
+    for (name, dir) in [("api", &api), ("web", &web)] {
+        let mut cmd = goto_bin();
+        cmd.env("GOTO_DB", &db_dir);
+        cmd.args(["-r", name, dir.to_str().unwrap(), "-t", "work"]);
+        assert!(cmd.output().unwrap().status.success());
+    }
+
+    // A stand-in fzf that keeps its input and picks the line mentioning web
+    let bin = temp.path().join("bin");
+    fs::create_dir(&bin).unwrap();
+    let fzf = bin.join("fzf");
+    fs::write(&fzf, "#!/bin/sh\ntee \"$(dirname \"$0\")/input\" | grep '^web'\n").unwrap();
+    fs::set_permissions(&fzf, fs::Permissions::from_mode(0o755)).unwrap();
+
+    let mut cmd = goto_bin();
+    cmd.env("GOTO_DB", &db_dir);
+    cmd.env("PATH", format!("{}:{}", bin.display(), std::env::var("PATH").unwrap_or_default()));
+    cmd.arg("--pick");
+    let output = cmd.output().unwrap();
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+    assert_eq!(String::from_utf8_lossy(&output.stdout).trim(), web.to_str().unwrap());
+
+    let input = fs::read_to_string(bin.join("input")).unwrap();
+    assert!(input.contains(&format!("api\t{}\twork", api.display())));
+
+    let mut cmd = goto_bin();
+    cmd.env("GOTO_DB", &db_dir);
+    cmd.args(["-l", "--porcelain"]);
+    let stdout = String::from_utf8_lossy(&cmd.output().unwrap().stdout).to_string();
+    let counts: Vec<&str> = stdout.lines().map(|l| l.split('\t').nth(3).unwrap()).collect();
+    assert_eq!(counts, vec!["0", "1"]);
+}
+
+#[test]
+fn test_search_json() {
+    let temp = tempdir().unwrap();
+    let backend = temp.path().join("backend");
+    fs::create_dir(&backend).unwrap();
+    let db_dir = temp.path().join("db");
+    fs::create_dir(&db_dir).unwrap();
+
+    let mut cmd = goto_bin();
+    cmd.env("GOTO_DB", &db_dir);
+    cmd.args(["-r", "api", backend.to_str().unwrap()]);
+    assert!(cmd.output().unwrap().status.success());
+
+    let mut cmd = goto_bin();
+    cmd.env("GOTO_DB", &db_dir);
+    cmd.args(["--search", "backend", "--json"]);
+    let output = cmd.output().unwrap();
+    assert!(output.status.success());
+
+    let results: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(results[0]["name"], "api");
+    assert_eq!(results[0]["field"], "path");
+
+    let mut cmd = goto_bin();
+    cmd.env("GOTO_DB", &db_dir);
+    cmd.args(["--search", "zzzzzz", "--json"]);
+    let output = cmd.output().unwrap();
+    assert_eq!(String::from_utf8_lossy(&output.stdout).trim(), "[]");
+}
+
+#[cfg(unix)]
+#[test]
+fn test_note_is_previewed_and_searched() {
+    use std::os::unix::fs::PermissionsExt;
+
+    let temp = tempdir().unwrap();
+    let proj = temp.path().join("proj");
+    fs::create_dir(&proj).unwrap();
+    let db_dir = temp.path().join("db");
+    fs::create_dir(&db_dir).unwrap();
+    let editor = temp.path().join("editor.sh");
+    fs::write(&editor, "#!/bin/sh\nprintf 'Deploy notes\\nuse the staging host\\n' > \"$1\"\n").unwrap();
+    fs::set_permissions(&editor, fs::Permissions::from_mode(0o755)).unwrap();
+
+    let output = goto_bin()
+        .env("GOTO_DB", &db_dir)
+        .args(["-r", "proj", proj.to_str().unwrap()])
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+
+    let output = goto_bin()
+        .env("GOTO_DB", &db_dir)
+        .env_remove("VISUAL")
+        .env("EDITOR", &editor)
+        .args(["--note", "proj"])
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    assert!(String::from_utf8_lossy(&output.stdout).contains("Saved note for 'proj'"));
+    assert!(db_dir.join("notes").join("proj.md").exists());
+
+    let output = goto_bin().env("GOTO_DB", &db_dir).args(["--preview", "proj"]).output().unwrap();
+    assert!(String::from_utf8_lossy(&output.stdout).contains("Deploy notes"));
+
+    let output = goto_bin()
+        .env("GOTO_DB", &db_dir)
+        .args(["--search", "staging", "--json"])
+        .output()
+        .unwrap();
+    let results: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(results[0]["name"], "proj");
+    assert_eq!(results[0]["field"], "note");
+    assert_eq!(results[0]["note"], "use the staging host");
+
+    // The note follows a renamed alias
+    let output = goto_bin()
+        .env("GOTO_DB", &db_dir)
+        .args(["--rename", "proj", "app"])
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    assert!(db_dir.join("notes").join("app.md").exists());
+    assert!(!db_dir.join("notes").join("proj.md").exists());
+}
+
+#[test]
+fn test_watch_scan_queues_new_directories() {
+    let temp = tempdir().unwrap();
+    let root = temp.path().join("src");
+    fs::create_dir_all(root.join("existing")).unwrap();
+    let db_dir = temp.path().join("db");
+    fs::create_dir(&db_dir).unwrap();
+
+    let output = goto_bin()
+        .env("GOTO_DB", &db_dir)
+        .args(["--watch", root.to_str().unwrap()])
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    assert!(String::from_utf8_lossy(&output.stdout).starts_with("Watching "));
+
+    fs::create_dir(root.join("fresh")).unwrap();
+    let output = goto_bin().env("GOTO_DB", &db_dir).arg("--watch-scan").output().unwrap();
+    assert!(output.status.success());
+    assert!(String::from_utf8_lossy(&output.stdout).contains("Found 1 new directory"));
+
+    // Piped, suggestions are listed rather than offered
+    let output = goto_bin().env("GOTO_DB", &db_dir).arg("--suggestions").output().unwrap();
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("fresh"));
+    assert!(!stdout.contains("existing"));
+
+    let output = goto_bin().env("GOTO_DB", &db_dir).arg("--watch-scan").output().unwrap();
+    assert!(output.stdout.is_empty());
+
+    let output = goto_bin()
+        .env("GOTO_DB", &db_dir)
+        .args(["--unwatch", temp.path().join("elsewhere").to_str().unwrap()])
+        .output()
+        .unwrap();
+    assert_eq!(output.status.code(), Some(1));
+}
+
+#[test]
+fn test_learn_from_history_lists_candidates() {
+    let temp = tempdir().unwrap();
+    let project = temp.path().join("project");
+    fs::create_dir(&project).unwrap();
+    let db_dir = temp.path().join("db");
+    fs::create_dir(&db_dir).unwrap();
+    let history = temp.path().join("history");
+    let project_path = project.to_str().unwrap();
+    fs::write(&history, format!("cd {0}\nls\ncd {0} && make\ncd relative\n", project_path)).unwrap();
+
+    // Piped, the candidates are listed and nothing is registered
+    let output = goto_bin()
+        .env("GOTO_DB", &db_dir)
+        .args(["--learn-from-history", history.to_str().unwrap()])
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("project"));
+    assert!(stdout.contains('2'));
+    assert!(!fs::read_to_string(db_dir.join("aliases.toml")).unwrap_or_default().contains("project"));
+
+    let output = goto_bin()
+        .env("GOTO_DB", &db_dir)
+        .args(["--learn-from-history", temp.path().join("missing").to_str().unwrap()])
+        .output()
+        .unwrap();
+    assert_eq!(output.status.code(), Some(1));
+}
+
+#[test]
+fn test_register_allow_missing() {
+    let temp = tempdir().unwrap();
+    let db_dir = temp.path().join("db");
+    fs::create_dir(&db_dir).unwrap();
+    let later = temp.path().join("later");
+
+    let output = goto_bin()
+        .env("GOTO_DB", &db_dir)
+        .args(["-r", "later", later.to_str().unwrap()])
+        .output()
+        .unwrap();
+    assert_eq!(output.status.code(), Some(2), "missing directories are still rejected by default");
+
+    let output = goto_bin()
+        .env("GOTO_DB", &db_dir)
+        .args(["-r", "later", later.to_str().unwrap(), "--allow-missing"])
+        .output()
+        .unwrap();
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+    assert!(String::from_utf8_lossy(&output.stdout).contains("pending"));
+
+    let output = goto_bin().env("GOTO_DB", &db_dir).arg("-l").output().unwrap();
+    assert!(String::from_utf8_lossy(&output.stdout).contains("(pending)"));
+
+    let output = goto_bin().env("GOTO_DB", &db_dir).arg("-c").output().unwrap();
+    assert!(output.status.success());
+    assert!(String::from_utf8_lossy(&output.stdout).contains("All aliases point to valid paths"));
+
+    // Navigating fails until the directory exists, then works normally
+    let output = goto_bin().env("GOTO_DB", &db_dir).arg("later").output().unwrap();
+    assert_eq!(output.status.code(), Some(2));
+
+    fs::create_dir(&later).unwrap();
+    let output = goto_bin().env("GOTO_DB", &db_dir).arg("later").output().unwrap();
+    assert!(output.status.success());
+    let stored = fs::read_to_string(db_dir.join("aliases.toml")).unwrap();
+    assert!(!stored.contains("pending"), "{}", stored);
+}
+
+#[test]
+fn test_tag_completion_data() {
+    let temp = tempdir().unwrap();
+    let db_dir = temp.path().join("db");
+    fs::create_dir(&db_dir).unwrap();
+
+    for (name, tags) in [("one", "work,wip"), ("two", "work"), ("three", "docs")] {
+        let dir = temp.path().join(name);
+        fs::create_dir(&dir).unwrap();
+        let output = goto_bin()
+            .env("GOTO_DB", &db_dir)
+            .args(["-r", name, dir.to_str().unwrap(), "-t", tags, "--force"])
+            .output()
+            .unwrap();
+        assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+    }
+
+    let output = goto_bin()
+        .env("GOTO_DB", &db_dir)
+        .args(["--tags-raw", "--with-counts"])
+        .output()
+        .unwrap();
+    assert_eq!(String::from_utf8_lossy(&output.stdout), "docs\t1\nwip\t1\nwork\t2\n");
+
+    let output = goto_bin()
+        .env("GOTO_DB", &db_dir)
+        .args(["--complete-tag", "W"])
+        .output()
+        .unwrap();
+    assert_eq!(String::from_utf8_lossy(&output.stdout), "wip\nwork\n");
+}
+
+#[test]
+fn test_dormant_aliases_in_stats_and_review() {
+    let temp = tempdir().unwrap();
+    let proj = temp.path().join("proj");
+    fs::create_dir(&proj).unwrap();
+    let db_dir = temp.path().join("db");
+    fs::create_dir(&db_dir).unwrap();
+    fs::write(
+        db_dir.join("aliases.toml"),
+        format!(
+            "[[aliases]]\nname = \"old\"\npath = \"{}\"\ncreated_at = \"2020-01-01T00:00:00Z\"\n\n\
+             [[aliases]]\nname = \"proj\"\npath = \"{}\"\ncreated_at = \"2020-01-01T00:00:00Z\"\n",
+            temp.path().display(),
+            proj.display()
+        ),
+    )
+    .unwrap();
+
+    // Navigating leaves the unused alias behind
+    let output = goto_bin().env("GOTO_DB", &db_dir).arg("proj").output().unwrap();
+    assert!(output.status.success());
+
+    let output = goto_bin().env("GOTO_DB", &db_dir).arg("--stats").output().unwrap();
+    assert!(String::from_utf8_lossy(&output.stdout).contains("Dormant aliases: 1"));
+
+    // Without a terminal, --review only lists them
+    let output = goto_bin().env("GOTO_DB", &db_dir).arg("--review").output().unwrap();
+    assert!(output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("old"));
+    assert!(!stdout.contains("proj"));
+}
+
+#[test]
+fn test_quiet_suppresses_confirmations() {
+    let temp = tempdir().unwrap();
+    let proj = temp.path().join("proj");
+    fs::create_dir(&proj).unwrap();
+    let db_dir = temp.path().join("db");
+    fs::create_dir(&db_dir).unwrap();
+
+    let output = goto_bin()
+        .env("GOTO_DB", &db_dir)
+        .args(["--quiet", "-r", "proj", proj.to_str().unwrap()])
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    assert!(output.stdout.is_empty());
+    assert!(output.stderr.is_empty());
+
+    // Results and errors are still printed
+    let output = goto_bin().env("GOTO_DB", &db_dir).args(["-x", "proj", "--quiet"]).output().unwrap();
+    assert_eq!(String::from_utf8_lossy(&output.stdout).trim(), proj.to_str().unwrap());
+    let output = goto_bin().env("GOTO_DB", &db_dir).args(["--quiet", "-u", "nope"]).output().unwrap();
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("not found"));
+}
+
+#[test]
+fn test_no_input_takes_confirm_defaults() {
+    let temp = tempdir().unwrap();
+    let db_dir = temp.path().join("db");
+    fs::create_dir(&db_dir).unwrap();
+    fs::write(
+        db_dir.join("aliases.toml"),
+        "[[aliases]]\nname = \"a\"\npath = \"/tmp\"\ntags = [\"old\"]\n",
+    )
+    .unwrap();
+
+    // Deleting a tag in use asks first; the default answer is no
+    let output = goto_bin()
+        .env("GOTO_DB", &db_dir)
+        .args(["--no-input", "--delete-tag", "old"])
+        .output()
+        .unwrap();
+    assert!(!output.status.success());
+    assert!(fs::read_to_string(db_dir.join("aliases.toml")).unwrap().contains("old"));
+}
+
+#[test]
+fn test_navigate_to_listed_row() {
+    let temp = tempdir().unwrap();
+    let db_dir = temp.path().join("db");
+    fs::create_dir(&db_dir).unwrap();
+    for name in ["alpha", "beta"] {
+        let dir = temp.path().join(name);
+        fs::create_dir(&dir).unwrap();
+        let output = goto_bin().env("GOTO_DB", &db_dir).args(["-r", name, dir.to_str().unwrap()]).output().unwrap();
+        assert!(output.status.success());
+    }
+
+    // Nothing listed in this session yet
+    let output = goto_bin().env("GOTO_DB", &db_dir).env("GOTO_SESSION", "a").arg("%1").output().unwrap();
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("goto -l first"));
+
+    let output = goto_bin()
+        .env("GOTO_DB", &db_dir)
+        .env("GOTO_SESSION", "a")
+        .args(["-l", "--columns=index,name"])
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    assert!(String::from_utf8_lossy(&output.stdout).contains("#"));
+
+    let output = goto_bin().env("GOTO_DB", &db_dir).env("GOTO_SESSION", "a").arg("%2").output().unwrap();
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+    let beta = fs::canonicalize(temp.path().join("beta")).unwrap();
+    assert_eq!(String::from_utf8_lossy(&output.stdout).trim(), beta.to_str().unwrap());
+
+    // Out of range, and listings are per session
+    let output = goto_bin().env("GOTO_DB", &db_dir).env("GOTO_SESSION", "a").arg("%3").output().unwrap();
+    assert!(String::from_utf8_lossy(&output.stderr).contains("valid: 1-2"));
+    assert_eq!(output.status.code(), Some(3), "an index out of range is a usage error");
+    let output = goto_bin().env("GOTO_DB", &db_dir).env("GOTO_SESSION", "b").arg("%1").output().unwrap();
+    assert!(!output.status.success());
+}
+
+#[test]
+fn test_list_colors_tags_for_terminals() {
+    let temp = tempdir().unwrap();
+    let db_dir = temp.path().join("db");
+    fs::create_dir(&db_dir).unwrap();
+    fs::write(db_dir.join("config.toml"), "[tag_colors]\nwork = \"dark_blue\"\n").unwrap();
+    fs::write(
+        db_dir.join("aliases.toml"),
+        "[[aliases]]\nname = \"a\"\npath = \"/tmp\"\ntags = [\"work\"]\n",
+    )
+    .unwrap();
+
+    let list = |envs: &[(&str, &str)]| {
+        let mut cmd = goto_bin();
+        cmd.env("GOTO_DB", &db_dir).env_remove("NO_COLOR").env_remove("GOTO_TTY").arg("-l");
+        for (key, value) in envs {
+            cmd.env(key, value);
+        }
+        String::from_utf8_lossy(&cmd.output().unwrap().stdout).into_owned()
+    };
+
+    // The shell wrapper reports a terminal through GOTO_TTY
+    assert!(list(&[("GOTO_TTY", "1")]).contains("\x1b[38;5;4mwork\x1b[39m"));
+    assert!(!list(&[]).contains('\x1b'));
+    assert!(!list(&[("GOTO_TTY", "1"), ("NO_COLOR", "1")]).contains('\x1b'));
+}
+
+#[test]
+fn test_resolve_quiet() {
+    let temp = tempdir().unwrap();
+    let db_dir = temp.path().join("db");
+    let proj = temp.path().join("proj");
+    fs::create_dir_all(&proj).unwrap();
+    let output = goto_bin().env("GOTO_DB", &db_dir).args(["-r", "proj", proj.to_str().unwrap()]).output().unwrap();
+    assert!(output.status.success());
+
+    let output = goto_bin().env("GOTO_DB", &db_dir).args(["--resolve-quiet", "proj"]).output().unwrap();
+    assert!(output.status.success());
+    assert_eq!(String::from_utf8_lossy(&output.stdout).trim(), fs::canonicalize(&proj).unwrap().to_str().unwrap());
+
+    // A near miss neither suggests nor prints anything
+    let output = goto_bin().env("GOTO_DB", &db_dir).args(["--resolve-quiet", "prj"]).output().unwrap();
+    assert_eq!(output.status.code(), Some(1));
+    assert!(output.stdout.is_empty());
+    assert!(output.stderr.is_empty());
+}
+
+#[test]
+fn test_random_navigation() {
+    let temp = tempdir().unwrap();
+    let side = temp.path().join("side");
+    fs::create_dir(&side).unwrap();
+    let db_dir = temp.path().join("db");
+
+    let output = goto_bin()
+        .env("GOTO_DB", &db_dir)
+        .args(["--random"])
+        .output()
+        .unwrap();
+    assert_eq!(output.status.code(), Some(1));
+    assert!(String::from_utf8_lossy(&output.stderr).contains("no aliases to pick from"));
+
+    let output = goto_bin()
+        .env("GOTO_DB", &db_dir)
+        .args(["-r", "side", side.to_str().unwrap(), "-t", "fun"])
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+
+    let output = goto_bin()
+        .env("GOTO_DB", &db_dir)
+        .args(["--random", "--filter=fun", "--dry-run"])
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    assert!(String::from_utf8_lossy(&output.stdout).starts_with("side -> "));
+
+    // Nothing matching the filter is the same as nothing at all
+    let output = goto_bin()
+        .env("GOTO_DB", &db_dir)
+        .args(["--random", "--filter=tag:work"])
+        .output()
+        .unwrap();
+    assert_eq!(output.status.code(), Some(1));
+    assert!(String::from_utf8_lossy(&output.stderr).contains("no aliases matching 'tag:work' to pick from"));
+
+    let output = goto_bin()
+        .env("GOTO_DB", &db_dir)
+        .args(["--random", "--weighted"])
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    let path = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    assert_eq!(
+        std::path::Path::new(&path).canonicalize().unwrap(),
+        side.canonicalize().unwrap()
+    );
+}
+
+#[test]
+fn test_damaged_database_is_salvaged() {
+    let temp = tempdir().unwrap();
+    let db_dir = temp.path().join("db");
+    fs::create_dir(&db_dir).unwrap();
+    let project = temp.path().join("project");
+    fs::create_dir(&project).unwrap();
+    fs::write(
+        db_dir.join("aliases.toml"),
+        format!(
+            "[[aliases]]\nname = \"proj\"\npath = \"{}\"\n\n[[aliases]]\nname = \"half\npath = \n",
+            project.display()
+        ),
+    )
+    .unwrap();
+
+    let output = goto_bin()
+        .env("GOTO_DB", &db_dir)
+        .args(["--fsck", "--dry-run"])
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("Recovered 1 aliases"));
+    assert!(stdout.contains("Lost: 'half' (line 5)"));
+
+    // Any command salvages what it can instead of failing
+    let output = goto_bin()
+        .env("GOTO_DB", &db_dir)
+        .args(["-x", "proj"])
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("was damaged"));
+    let copies = fs::read_dir(&db_dir)
+        .unwrap()
+        .filter(|e| e.as_ref().unwrap().file_name().to_string_lossy().starts_with("aliases.toml.corrupt-"))
+        .count();
+    assert_eq!(copies, 1);
+
+    let output = goto_bin()
+        .env("GOTO_DB", &db_dir)
+        .args(["--fsck"])
+        .output()
+        .unwrap();
+    assert!(String::from_utf8_lossy(&output.stdout).contains("No problems found"));
+}